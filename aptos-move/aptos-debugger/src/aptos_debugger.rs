@@ -9,6 +9,7 @@ use aptos_types::{
     account_address::AccountAddress,
     block_executor::config::{
         BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
+        DependencyWaitStrategy,
     },
     state_store::TStateView,
     transaction::{
@@ -432,6 +433,10 @@ fn execute_block_no_limit(
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                versioned_data_memory_budget: None,
+                dependency_wait_strategy: DependencyWaitStrategy::default(),
+                auto_select_execution_mode: false,
+                prefetch_predicted_reads: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         },
@@ -43,7 +43,10 @@ use aptos_types::state_store::StateViewId;
 use aptos_types::{
     account_config::{self, new_block_event_key, AccountResource},
     block_executor::{
-        config::{BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig},
+        config::{
+            BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
+            DependencyWaitStrategy,
+        },
         partitioner::PartitionedTransactions,
     },
     block_metadata::BlockMetadata,
@@ -121,6 +124,10 @@ static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
 static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static DISCARD_FAILED_BLOCKS: OnceCell<bool> = OnceCell::new();
+static VERSIONED_DATA_MEMORY_BUDGET: OnceCell<Option<u64>> = OnceCell::new();
+static DEPENDENCY_WAIT_STRATEGY: OnceCell<DependencyWaitStrategy> = OnceCell::new();
+static AUTO_SELECT_EXECUTION_MODE: OnceCell<bool> = OnceCell::new();
+static PREFETCH_PREDICTED_READS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
 
 macro_rules! deprecated_module_bundle {
@@ -343,6 +350,53 @@ impl AptosVM {
         }
     }
 
+    /// Sets runtime config when invoked the first time.
+    pub fn set_versioned_data_memory_budget(memory_budget: Option<u64>) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        VERSIONED_DATA_MEMORY_BUDGET.set(memory_budget).ok();
+    }
+
+    /// Get the memory budget for versioned data in parallel execution if already set,
+    /// otherwise return default (None, i.e. unbounded)
+    pub fn get_versioned_data_memory_budget() -> Option<u64> {
+        VERSIONED_DATA_MEMORY_BUDGET.get().copied().flatten()
+    }
+
+    /// Sets runtime config when invoked the first time.
+    pub fn set_dependency_wait_strategy(strategy: DependencyWaitStrategy) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        DEPENDENCY_WAIT_STRATEGY.set(strategy).ok();
+    }
+
+    /// Get the strategy for waiting on dependencies in parallel execution if already set,
+    /// otherwise return default (blocking on a condition variable)
+    pub fn get_dependency_wait_strategy() -> DependencyWaitStrategy {
+        DEPENDENCY_WAIT_STRATEGY.get().copied().unwrap_or_default()
+    }
+
+    /// Sets runtime config when invoked the first time.
+    pub fn set_auto_select_execution_mode(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        AUTO_SELECT_EXECUTION_MODE.set(enable).ok();
+    }
+
+    /// Get the automatic execution mode selection flag if already set, otherwise return default (false)
+    pub fn get_auto_select_execution_mode() -> bool {
+        AUTO_SELECT_EXECUTION_MODE.get().copied().unwrap_or(false)
+    }
+
+    /// Sets runtime config when invoked the first time.
+    pub fn set_prefetch_predicted_reads(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        PREFETCH_PREDICTED_READS.set(enable).ok();
+    }
+
+    /// Get the flag for prefetching the predicted reads of a block if already set, otherwise
+    /// return default (false)
+    pub fn get_prefetch_predicted_reads() -> bool {
+        PREFETCH_PREDICTED_READS.get().copied().unwrap_or(false)
+    }
+
     /// Sets the # of async proof reading threads.
     pub fn set_num_proof_reading_threads_once(mut num_threads: usize) {
         // TODO(grao): Do more analysis to tune this magic number.
@@ -2568,6 +2622,10 @@ impl VMExecutor for AptosVM {
                     concurrency_level: Self::get_concurrency_level(),
                     allow_fallback: true,
                     discard_failed_blocks: Self::get_discard_failed_blocks(),
+                    versioned_data_memory_budget: Self::get_versioned_data_memory_budget(),
+                    dependency_wait_strategy: Self::get_dependency_wait_strategy(),
+                    auto_select_execution_mode: Self::get_auto_select_execution_mode(),
+                    prefetch_predicted_reads: Self::get_prefetch_predicted_reads(),
                 },
                 onchain: onchain_config,
             },
@@ -6,19 +6,23 @@ pub(crate) mod vm_wrapper;
 
 use crate::{
     block_executor::vm_wrapper::AptosExecutorTask,
-    counters::{BLOCK_EXECUTOR_CONCURRENCY, BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS},
+    counters::{
+        BLOCK_EXECUTOR_CONCURRENCY, BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS,
+        BLOCK_EXECUTOR_EXECUTION_MODE_COUNT,
+    },
 };
 use aptos_aggregator::{
     delayed_change::DelayedChange, delta_change_set::DeltaOp, resolver::TAggregatorV1View,
 };
 use aptos_block_executor::{
-    errors::BlockExecutionError, executor::BlockExecutor,
+    conflict_rate::RECENT_CONFLICT_RATE, errors::BlockExecutionError, executor::BlockExecutor,
     task::TransactionOutput as BlockExecutorTransactionOutput,
     txn_commit_hook::TransactionCommitHook, types::InputOutputKey,
 };
 use aptos_infallible::Mutex;
 use aptos_types::{
-    block_executor::config::BlockExecutorConfig,
+    account_config::AccountResource,
+    block_executor::config::{BlockExecutorConfig, BlockExecutorLocalConfig},
     contract_event::ContractEvent,
     delayed_fields::PanicError,
     executable::ExecutableTestType,
@@ -35,6 +39,7 @@ use aptos_vm_types::{
     abstract_write_op::AbstractResourceWriteOp, environment::Environment, output::VMOutput,
 };
 use move_core_types::{
+    account_address::AccountAddress,
     language_storage::StructTag,
     value::MoveTypeLayout,
     vm_status::{StatusCode, VMStatus},
@@ -43,10 +48,26 @@ use move_vm_types::delayed_values::delayed_field_id::DelayedFieldID;
 use once_cell::sync::{Lazy, OnceCell};
 use rayon::ThreadPool;
 use std::{
-    collections::{BTreeMap, HashSet},
-    sync::Arc,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
+/// When selecting the execution mode, blocks with fewer transactions are executed sequentially,
+/// as the overhead of orchestrating parallel execution outweighs the gains.
+const MIN_TXNS_FOR_PARALLEL_EXECUTION: usize = 8;
+/// When selecting the execution mode, blocks are executed sequentially while the blocks recently
+/// executed in parallel had more speculative aborts per transaction on average.
+const MAX_CONFLICT_RATE_FOR_PARALLEL_EXECUTION: f64 = 1.5;
+/// While the conflict rate is too high, one in this many blocks is still executed in parallel,
+/// so that the conflict rate keeps reflecting the current workload.
+const PARALLEL_EXECUTION_PROBE_INTERVAL: u64 = 10;
+
+/// Number of consecutive blocks executed sequentially due to the conflict rate.
+static NUM_BLOCKS_SEQUENTIAL_DUE_TO_CONFLICTS: AtomicU64 = AtomicU64::new(0);
+
 pub static RAYON_EXEC_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
     Arc::new(
         rayon::ThreadPoolBuilder::new()
@@ -391,7 +412,7 @@ impl BlockAptosVM {
         executor_thread_pool: Arc<ThreadPool>,
         signature_verified_block: &[SignatureVerifiedTransaction],
         state_view: &S,
-        config: BlockExecutorConfig,
+        mut config: BlockExecutorConfig,
         transaction_commit_listener: Option<L>,
     ) -> Result<BlockOutput<TransactionOutput>, VMStatus> {
         let _timer = BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
@@ -402,7 +423,19 @@ impl BlockAptosVM {
             init_speculative_logs(num_txns);
         }
 
+        config.local.concurrency_level = Self::select_concurrency_level(
+            &config.local,
+            num_txns,
+            RECENT_CONFLICT_RATE.get(),
+            &NUM_BLOCKS_SEQUENTIAL_DUE_TO_CONFLICTS,
+        );
+
         BLOCK_EXECUTOR_CONCURRENCY.set(config.local.concurrency_level as i64);
+        let predicted_reads = if config.local.prefetch_predicted_reads {
+            Self::predicted_reads(signature_verified_block)
+        } else {
+            vec![]
+        };
         let executor = BlockExecutor::<
             SignatureVerifiedTransaction,
             AptosExecutorTask,
@@ -413,7 +446,12 @@ impl BlockAptosVM {
 
         let environment =
             Arc::new(Environment::new(state_view).try_enable_delayed_field_optimization());
-        let ret = executor.execute_block(environment, signature_verified_block, state_view);
+        let ret = executor.execute_block_with_predicted_reads(
+            environment,
+            signature_verified_block,
+            state_view,
+            &predicted_reads,
+        );
         match ret {
             Ok(block_output) => {
                 let (transaction_outputs, block_end_info) = block_output.into_inner();
@@ -444,6 +482,55 @@ impl BlockAptosVM {
         }
     }
 
+    /// Returns the concurrency level to execute a block of num_txns transactions with, 1 if the
+    /// block should be executed sequentially as per automatic execution mode selection, given the
+    /// recent conflict rate and the number of consecutive blocks executed sequentially due to it.
+    fn select_concurrency_level(
+        config: &BlockExecutorLocalConfig,
+        num_txns: usize,
+        recent_conflict_rate: Option<f64>,
+        num_blocks_sequential: &AtomicU64,
+    ) -> usize {
+        if !config.auto_select_execution_mode || config.concurrency_level <= 1 {
+            return config.concurrency_level;
+        }
+
+        let (concurrency_level, mode, reason) = if num_txns < MIN_TXNS_FOR_PARALLEL_EXECUTION {
+            (1, "sequential", "small_block")
+        } else if recent_conflict_rate
+            .is_some_and(|rate| rate > MAX_CONFLICT_RATE_FOR_PARALLEL_EXECUTION)
+        {
+            let num_blocks = num_blocks_sequential.fetch_add(1, Ordering::Relaxed);
+            if num_blocks + 1 < PARALLEL_EXECUTION_PROBE_INTERVAL {
+                (1, "sequential", "high_conflict_rate")
+            } else {
+                num_blocks_sequential.store(0, Ordering::Relaxed);
+                (config.concurrency_level, "parallel", "conflict_rate_probe")
+            }
+        } else {
+            num_blocks_sequential.store(0, Ordering::Relaxed);
+            (config.concurrency_level, "parallel", "default")
+        };
+
+        BLOCK_EXECUTOR_EXECUTION_MODE_COUNT
+            .with_label_values(&[mode, reason])
+            .inc();
+        concurrency_level
+    }
+
+    /// Returns the keys the transactions of the block are predicted to read, which are the
+    /// accounts of the senders of the user transactions, read by the prologue.
+    fn predicted_reads(signature_verified_block: &[SignatureVerifiedTransaction]) -> Vec<StateKey> {
+        let senders: BTreeSet<AccountAddress> = signature_verified_block
+            .iter()
+            .filter_map(SignatureVerifiedTransaction::sender)
+            .collect();
+        senders
+            .iter()
+            .filter_map(|sender| StateKey::resource_typed::<AccountResource>(sender).ok())
+            .collect()
+    }
+
     /// Uses shared thread pool to execute blocks.
     pub fn execute_block<
         S: StateView + Sync,
@@ -463,3 +550,131 @@ impl BlockAptosVM {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+    use aptos_types::{
+        block_executor::config::DependencyWaitStrategy,
+        test_helpers::transaction_test_helpers::get_test_signed_txn, transaction::Transaction,
+    };
+
+    fn local_config(
+        concurrency_level: usize,
+        auto_select_execution_mode: bool,
+    ) -> BlockExecutorLocalConfig {
+        BlockExecutorLocalConfig {
+            concurrency_level,
+            allow_fallback: true,
+            discard_failed_blocks: false,
+            versioned_data_memory_budget: None,
+            dependency_wait_strategy: DependencyWaitStrategy::default(),
+            auto_select_execution_mode,
+            prefetch_predicted_reads: false,
+        }
+    }
+
+    fn select(
+        config: &BlockExecutorLocalConfig,
+        num_txns: usize,
+        rate: Option<f64>,
+        num_blocks_sequential: &AtomicU64,
+    ) -> usize {
+        BlockAptosVM::select_concurrency_level(config, num_txns, rate, num_blocks_sequential)
+    }
+
+    #[test]
+    fn select_concurrency_level_disabled() {
+        let config = local_config(8, false);
+        let num_blocks_sequential = AtomicU64::new(0);
+        assert_eq!(select(&config, 1, Some(10.0), &num_blocks_sequential), 8);
+        assert_eq!(num_blocks_sequential.load(Ordering::Relaxed), 0);
+
+        let config = local_config(1, true);
+        assert_eq!(select(&config, 100, None, &num_blocks_sequential), 1);
+    }
+
+    #[test]
+    fn select_concurrency_level_small_block() {
+        let config = local_config(8, true);
+        let num_blocks_sequential = AtomicU64::new(0);
+        assert_eq!(
+            select(
+                &config,
+                MIN_TXNS_FOR_PARALLEL_EXECUTION - 1,
+                None,
+                &num_blocks_sequential
+            ),
+            1
+        );
+        assert_eq!(
+            select(
+                &config,
+                MIN_TXNS_FOR_PARALLEL_EXECUTION,
+                None,
+                &num_blocks_sequential
+            ),
+            8
+        );
+    }
+
+    #[test]
+    fn select_concurrency_level_conflict_rate() {
+        let config = local_config(8, true);
+        let num_blocks_sequential = AtomicU64::new(0);
+        let high_rate = Some(MAX_CONFLICT_RATE_FOR_PARALLEL_EXECUTION + 0.5);
+
+        assert_eq!(
+            select(
+                &config,
+                100,
+                Some(MAX_CONFLICT_RATE_FOR_PARALLEL_EXECUTION),
+                &num_blocks_sequential
+            ),
+            8
+        );
+        // While the conflict rate stays high, every probe interval-th block is executed in parallel.
+        for _ in 0..2 {
+            for _ in 1..PARALLEL_EXECUTION_PROBE_INTERVAL {
+                assert_eq!(select(&config, 100, high_rate, &num_blocks_sequential), 1);
+            }
+            assert_eq!(select(&config, 100, high_rate, &num_blocks_sequential), 8);
+            assert_eq!(num_blocks_sequential.load(Ordering::Relaxed), 0);
+        }
+
+        // A block with a low conflict rate resets the count of sequential blocks.
+        assert_eq!(select(&config, 100, high_rate, &num_blocks_sequential), 1);
+        assert_eq!(select(&config, 100, Some(0.1), &num_blocks_sequential), 8);
+        assert_eq!(num_blocks_sequential.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn predicted_reads_are_the_accounts_of_the_senders() {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let user_txn = |sender: AccountAddress, sequence_number: u64| {
+            SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(get_test_signed_txn(
+                sender,
+                sequence_number,
+                &private_key,
+                private_key.public_key(),
+                None,
+            )))
+        };
+        let (alice, bob) = (AccountAddress::random(), AccountAddress::random());
+        let block = vec![
+            user_txn(alice, 0),
+            SignatureVerifiedTransaction::Valid(Transaction::StateCheckpoint(HashValue::zero())),
+            user_txn(bob, 0),
+            user_txn(alice, 1),
+        ];
+
+        // Each sender once, whatever the number of its transactions.
+        let predicted_reads = BlockAptosVM::predicted_reads(&block);
+        assert_eq!(predicted_reads.len(), 2);
+        for sender in [alice, bob] {
+            assert!(predicted_reads
+                .contains(&StateKey::resource_typed::<AccountResource>(&sender).unwrap()));
+        }
+    }
+}
@@ -32,6 +32,15 @@ pub static BLOCK_EXECUTOR_CONCURRENCY: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static BLOCK_EXECUTOR_EXECUTION_MODE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "block_executor_execution_mode_count",
+        "Count of blocks by the selected execution mode and the reason for it",
+        &["mode", "reason"]
+    )
+    .unwrap()
+});
+
 pub static BLOCK_EXECUTOR_SIGNATURE_VERIFICATION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
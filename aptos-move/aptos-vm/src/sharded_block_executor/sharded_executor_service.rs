@@ -19,7 +19,7 @@ use crate::{
 use aptos_logger::{info, trace};
 use aptos_types::{
     block_executor::{
-        config::{BlockExecutorConfig, BlockExecutorLocalConfig},
+        config::{BlockExecutorConfig, BlockExecutorLocalConfig, DependencyWaitStrategy},
         partitioner::{ShardId, SubBlock, SubBlocksForShard, TransactionWithDependencies},
     },
     state_store::StateView,
@@ -234,6 +234,10 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                                 concurrency_level: concurrency_level_per_shard,
                                 allow_fallback: true,
                                 discard_failed_blocks: false,
+                                versioned_data_memory_budget: None,
+                                dependency_wait_strategy: DependencyWaitStrategy::default(),
+                                auto_select_execution_mode: false,
+                                prefetch_predicted_reads: false,
                             },
                             onchain: onchain_config,
                         },
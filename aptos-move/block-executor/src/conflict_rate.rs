@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::RECENT_CONFLICT_RATE_GAUGE;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Weight of the latest block in the moving average of the conflict rate.
+const LATEST_BLOCK_WEIGHT: f64 = 0.2;
+
+/// Tracks the exponential moving average of the conflict rate of the blocks executed in parallel,
+/// i.e. the number of speculative aborts per transaction, which can be used to decide whether
+/// upcoming blocks are worth executing in parallel.
+pub struct ConflictRateTracker {
+    // f64 bits of the average, NaN if no block has been recorded yet.
+    average_bits: AtomicU64,
+}
+
+impl ConflictRateTracker {
+    fn new() -> Self {
+        Self {
+            average_bits: AtomicU64::new(f64::NAN.to_bits()),
+        }
+    }
+
+    /// Records the number of speculative aborts during the parallel execution of a block.
+    pub fn record(&self, num_txns: u32, num_aborts: u32) {
+        if num_txns == 0 {
+            return;
+        }
+        let block_rate = num_aborts as f64 / num_txns as f64;
+
+        let mut average = 0.0;
+        // The closure never returns None, so the update always succeeds.
+        let _ = self
+            .average_bits
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                let prev = f64::from_bits(bits);
+                average = if prev.is_nan() {
+                    block_rate
+                } else {
+                    LATEST_BLOCK_WEIGHT * block_rate + (1.0 - LATEST_BLOCK_WEIGHT) * prev
+                };
+                Some(average.to_bits())
+            });
+        RECENT_CONFLICT_RATE_GAUGE.set(average);
+    }
+
+    /// Returns the average conflict rate of the recent blocks, None if none was recorded.
+    pub fn get(&self) -> Option<f64> {
+        let average = f64::from_bits(self.average_bits.load(Ordering::Relaxed));
+        (!average.is_nan()).then_some(average)
+    }
+}
+
+/// Conflict rate of the blocks recently executed in parallel by any block executor.
+pub static RECENT_CONFLICT_RATE: Lazy<ConflictRateTracker> = Lazy::new(ConflictRateTracker::new);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn moving_average() {
+        let tracker = ConflictRateTracker::new();
+        assert_eq!(tracker.get(), None);
+
+        tracker.record(0, 0);
+        assert_eq!(tracker.get(), None);
+
+        tracker.record(10, 5);
+        assert_eq!(tracker.get(), Some(0.5));
+
+        tracker.record(10, 0);
+        let average = tracker.get().unwrap();
+        assert!((average - 0.4).abs() < 1e-9);
+    }
+}
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_avg_counter_vec, register_histogram, register_histogram_vec,
-    register_int_counter, register_int_counter_vec, Histogram, HistogramVec, IntCounter,
-    IntCounterVec,
+    exponential_buckets, register_avg_counter_vec, register_gauge, register_histogram,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, Gauge, Histogram,
+    HistogramVec, IntCounter, IntCounterVec,
 };
 use aptos_mvhashmap::BlockStateStats;
 use aptos_types::fee_statement::FeeStatement;
@@ -58,6 +58,24 @@ pub static MODULE_PUBLISHING_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of transactions re-executed at commit time after a VM or delayed field error.
+pub static COMMIT_TIME_REEXECUTION_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_commit_time_reexecution_count",
+        "Number of transactions re-executed at commit time after hitting a VM or delayed field error speculatively"
+    )
+    .unwrap()
+});
+
+/// Moving average of the speculative aborts per transaction of the blocks executed in parallel.
+pub static RECENT_CONFLICT_RATE_GAUGE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "aptos_execution_recent_conflict_rate",
+        "Moving average of speculative aborts per transaction in parallel execution"
+    )
+    .unwrap()
+});
+
 /// Count of speculative transaction re-executions due to a failed validation.
 pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -162,6 +180,54 @@ pub static DEPENDENCY_WAIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of dependency waits, by the strategy and by whether the dependency got resolved
+/// while busy-waiting (spinning or yielding), or the thread had to block.
+pub static DEPENDENCY_WAIT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_dependency_wait_count",
+        "Count of dependency waits in Block STM, by strategy and outcome",
+        &["strategy", "outcome"]
+    )
+    .unwrap()
+});
+
+/// Count of timed out slices of dependency waits with the timeout strategy.
+pub static DEPENDENCY_WAIT_TIMEOUT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_dependency_wait_timeout_count",
+        "Count of timed out slices of dependency waits in Block STM"
+    )
+    .unwrap()
+});
+
+pub static BASE_VALUE_PREFETCH_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_execution_base_value_prefetch_seconds",
+        "The time spent in seconds prefetching the base values of predicted reads",
+        time_buckets(),
+    )
+    .unwrap()
+});
+
+pub static BASE_VALUE_PREFETCH_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_base_value_prefetch_count",
+        "Count of base values prefetched from storage for predicted reads"
+    )
+    .unwrap()
+});
+
+pub static VERSIONED_DATA_EVICTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_versioned_data_eviction_seconds",
+        // metric description
+        "The time spent in seconds evicting committed versions of data in Block STM",
+        time_buckets(),
+    )
+    .unwrap()
+});
+
 pub static BLOCK_GAS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_execution_block_gas",
@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    conflict_rate::RECENT_CONFLICT_RATE,
     counters,
     counters::{
         PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
-        TASK_VALIDATE_SECONDS, VM_INIT_SECONDS, WORK_WITH_TASK_SECONDS,
+        TASK_VALIDATE_SECONDS, VERSIONED_DATA_EVICTION_SECONDS, VM_INIT_SECONDS,
+        WORK_WITH_TASK_SECONDS,
     },
     errors::*,
     executor_utilities::*,
@@ -35,7 +37,7 @@ use aptos_mvhashmap::{
 use aptos_types::{
     block_executor::config::BlockExecutorConfig,
     delayed_fields::PanicError,
-    executable::Executable,
+    executable::{Executable, ModulePath},
     on_chain_config::BlockGasLimitType,
     state_store::{state_value::StateValue, TStateView},
     transaction::{
@@ -51,7 +53,7 @@ use core::panic;
 use fail::fail_point;
 use move_core_types::{value::MoveTypeLayout, vm_status::StatusCode};
 use num_cpus;
-use rayon::ThreadPool;
+use rayon::{prelude::*, ThreadPool};
 use std::{
     cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
@@ -62,6 +64,10 @@ use std::{
     },
 };
 
+/// Minimum number of newly materialized transactions for evicting committed versions of data
+/// again, when over the memory budget.
+const MIN_TXNS_BETWEEN_EVICTIONS: TxnIndex = 64;
+
 pub struct BlockExecutor<T, E, S, L, X> {
     // Number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
@@ -268,11 +274,20 @@ where
                 (ExecutionStatus::Abort(err), Vec::new())
             },
             ExecutionStatus::DelayedFieldsCodeInvariantError(msg) => {
-                return Err(code_invariant_error(format!(
+                // May be caused by an inconsistent speculative state, so instead of failing the
+                // block, the transaction is re-executed once more when committing (when all
+                // preceding transactions are committed), failing the block only if it persists.
+                debug!(
                     "[Execution] At txn {}, failed with DelayedFieldsCodeInvariantError: {:?}",
                     idx_to_execute, msg
-                ))
-                .into());
+                );
+                read_set.capture_delayed_field_read_error(&PanicOr::Or(
+                    MVDelayedFieldsError::DeltaApplicationFailure,
+                ));
+                (
+                    ExecutionStatus::DelayedFieldsCodeInvariantError(msg),
+                    Vec::new(),
+                )
             },
         };
 
@@ -468,9 +483,22 @@ where
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         let mut block_limit_processor = shared_commit_state.acquire();
 
+        if let Some(memory_budget) = self.config.local.versioned_data_memory_budget {
+            Self::evict_committed_versions(memory_budget, scheduler, versioned_cache);
+        }
+
         while let Some((txn_idx, incarnation)) = scheduler.try_commit() {
-            if !Self::validate_commit_ready(txn_idx, versioned_cache, last_input_output)? {
-                // Transaction needs to be re-executed, one final time.
+            let has_unsupported_status = last_input_output.has_unsupported_status(txn_idx);
+            if !Self::validate_commit_ready(txn_idx, versioned_cache, last_input_output)?
+                || has_unsupported_status
+            {
+                // Transaction needs to be re-executed, one final time. Since all preceding
+                // transactions are committed, this is effectively a sequential execution of
+                // the transaction at its index, which also re-checks a VM or delayed field
+                // error hit speculatively before falling back for the whole block.
+                if has_unsupported_status {
+                    counters::COMMIT_TIME_REEXECUTION_COUNT.inc();
+                }
 
                 Self::update_transaction_on_abort(txn_idx, last_input_output, versioned_cache);
                 // We are going to skip reducing validation index here, as we
@@ -495,6 +523,18 @@ where
 
                 scheduler.finish_execution_during_commit(txn_idx)?;
 
+                if let ExecutionStatus::DelayedFieldsCodeInvariantError(msg) = last_input_output
+                    .txn_output(txn_idx)
+                    .expect("Output must be recorded after execution")
+                    .as_ref()
+                {
+                    return Err(code_invariant_error(format!(
+                        "[Execution] At txn {}, failed with DelayedFieldsCodeInvariantError: {:?}",
+                        txn_idx, msg
+                    ))
+                    .into());
+                }
+
                 let validation_result =
                     Self::validate(txn_idx, last_input_output, versioned_cache)?;
                 if !validation_result
@@ -609,6 +649,27 @@ where
         Ok(())
     }
 
+    /// When the values written in the block exceed the memory budget, drops the versions of data
+    /// that can no longer be observed, i.e. those overwritten below the materialized index (as
+    /// transactions below are committed, and no longer read on behalf of their outputs).
+    fn evict_committed_versions(
+        memory_budget: u64,
+        scheduler: &Scheduler,
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
+    ) {
+        let versioned_data = versioned_cache.data();
+        if versioned_data.total_versioned_value_size() <= memory_budget {
+            return;
+        }
+
+        // An eviction goes over all keys, so it is only worth it after enough new commits.
+        let materialized_idx = scheduler.materialized_idx();
+        if materialized_idx >= versioned_data.evicted_idx() + MIN_TXNS_BETWEEN_EVICTIONS {
+            let _timer = VERSIONED_DATA_EVICTION_SECONDS.start_timer();
+            versioned_data.evict_below(materialized_idx);
+        }
+    }
+
     fn materialize_aggregator_v1_delta_writes(
         txn_idx: TxnIndex,
         last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
@@ -780,6 +841,7 @@ where
                     base_view,
                     final_results,
                 )?;
+                scheduler.finish_materialization(txn_idx);
             }
             Ok(())
         };
@@ -862,11 +924,47 @@ where
         }
     }
 
+    /// Sets the base values of the keys predicted to be read by the block in the versioned
+    /// cache, fetching them from storage in parallel, so the transactions do not stall on
+    /// their first reads. Storage errors are ignored here, to be handled by the actual reads.
+    fn prefetch_base_values(
+        &self,
+        predicted_reads: &[T::Key],
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
+        base_view: &S,
+    ) {
+        if predicted_reads.is_empty() {
+            return;
+        }
+
+        let _timer = counters::BASE_VALUE_PREFETCH_SECONDS.start_timer();
+        self.executor_thread_pool.install(|| {
+            predicted_reads
+                .par_iter()
+                // Modules are not stored in the versioned data.
+                .filter(|key| !key.is_module_path())
+                .for_each(|key| {
+                    if versioned_cache
+                        .data()
+                        .prefetch_base_value(key.clone(), |key| {
+                            base_view
+                                .get_state_value(key)
+                                .ok()
+                                .map(TransactionWrite::from_state_value)
+                        })
+                    {
+                        counters::BASE_VALUE_PREFETCH_COUNT.inc();
+                    }
+                });
+        });
+    }
+
     pub(crate) fn execute_transactions_parallel(
         &self,
         env: &E::Environment,
         signature_verified_block: &[T],
         base_view: &S,
+        predicted_reads: &[T::Key],
     ) -> Result<BlockOutput<E::Output>, ()> {
         let _timer = PARALLEL_EXECUTION_SECONDS.start_timer();
         // Using parallel execution with 1 thread currently will not work as it
@@ -886,6 +984,8 @@ where
             return Ok(BlockOutput::new(vec![], self.empty_block_end_info()));
         }
 
+        self.prefetch_base_values(predicted_reads, &versioned_cache, base_view);
+
         let num_txns = signature_verified_block.len();
         let num_workers = self.config.local.concurrency_level.min(num_txns / 2).max(2);
 
@@ -906,7 +1006,8 @@ where
         let num_txns = num_txns as u32;
 
         let last_input_output = TxnLastInputOutput::new(num_txns);
-        let scheduler = Scheduler::new(num_txns);
+        let scheduler = Scheduler::new(num_txns)
+            .with_dependency_wait_strategy(self.config.local.dependency_wait_strategy);
 
         let timer = RAYON_EXECUTION_SECONDS.start_timer();
         self.executor_thread_pool.scope(|s| {
@@ -942,6 +1043,9 @@ where
         drop(timer);
 
         counters::update_state_counters(versioned_cache.stats(), true);
+        if !shared_maybe_error.load(Ordering::SeqCst) {
+            RECENT_CONFLICT_RATE.record(num_txns, scheduler.num_aborts());
+        }
 
         // Explicit async drops.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
@@ -1352,10 +1456,28 @@ where
         env: E::Environment,
         signature_verified_block: &[T],
         base_view: &S,
+    ) -> BlockExecutionResult<BlockOutput<E::Output>, E::Error> {
+        self.execute_block_with_predicted_reads(env, signature_verified_block, base_view, &[])
+    }
+
+    /// Like execute_block, but with the keys predicted to be read by the block (e.g. by a static
+    /// analysis of the transactions, or hints from mempool), whose base values are prefetched
+    /// from storage before executing the block in parallel. The prediction may be incomplete or
+    /// wrong, in which case only the prefetching effort is wasted.
+    pub fn execute_block_with_predicted_reads(
+        &self,
+        env: E::Environment,
+        signature_verified_block: &[T],
+        base_view: &S,
+        predicted_reads: &[T::Key],
     ) -> BlockExecutionResult<BlockOutput<E::Output>, E::Error> {
         if self.config.local.concurrency_level > 1 {
-            let parallel_result =
-                self.execute_transactions_parallel(&env, signature_verified_block, base_view);
+            let parallel_result = self.execute_transactions_parallel(
+                &env,
+                signature_verified_block,
+                base_view,
+                predicted_reads,
+            );
 
             // If parallel gave us result, return it
             if let Ok(output) = parallel_result {
@@ -1371,6 +1493,7 @@ where
             init_speculative_logs(signature_verified_block.len());
 
             info!("parallel execution requiring fallback");
+            self.notify_execution_restarted();
         }
 
         // If we didn't run parallel, or it didn't finish successfully - run sequential
@@ -1396,6 +1519,7 @@ where
                 // All logs from the first pass of sequential execution should be cleared and not reported.
                 // Clear by re-initializing the speculative logs.
                 init_speculative_logs(signature_verified_block.len());
+                self.notify_execution_restarted();
 
                 let sequential_result = self.execute_transactions_sequential(
                     env,
@@ -1432,13 +1556,25 @@ where
                     StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
                 },
             };
-            let ret = signature_verified_block
+            let ret: Vec<_> = signature_verified_block
                 .iter()
                 .map(|_| E::Output::discard_output(error_code))
                 .collect();
+            if let Some(commit_hook) = &self.transaction_commit_hook {
+                commit_hook.on_execution_restarted();
+                for (idx, output) in ret.iter().enumerate() {
+                    commit_hook.on_transaction_committed(idx as TxnIndex, output);
+                }
+            }
             return Ok(BlockOutput::new(ret, self.empty_block_end_info()));
         }
 
         Err(sequential_error)
     }
+
+    fn notify_execution_restarted(&self) {
+        if let Some(commit_hook) = &self.transaction_commit_hook {
+            commit_hook.on_execution_restarted();
+        }
+    }
 }
@@ -82,7 +82,7 @@ fn run_transactions<K, V, E>(
             executor_thread_pool.clone(),
             None,
         )
-        .execute_transactions_parallel(&(), &transactions, &data_view);
+        .execute_transactions_parallel(&(), &transactions, &data_view, &[]);
 
         if module_access.0 && module_access.1 {
             assert_matches!(output, Err(()));
@@ -217,7 +217,7 @@ fn deltas_writes_mixed_with_block_gas_limit(num_txns: usize, maybe_block_gas_lim
             executor_thread_pool.clone(),
             None,
         )
-        .execute_transactions_parallel(&(), &transactions, &data_view);
+        .execute_transactions_parallel(&(), &transactions, &data_view, &[]);
 
         BaselineOutput::generate(&transactions, maybe_block_gas_limit)
             .assert_parallel_output(&output);
@@ -268,7 +268,7 @@ fn deltas_resolver_with_block_gas_limit(num_txns: usize, maybe_block_gas_limit:
             executor_thread_pool.clone(),
             None,
         )
-        .execute_transactions_parallel(&(), &transactions, &data_view);
+        .execute_transactions_parallel(&(), &transactions, &data_view, &[]);
 
         BaselineOutput::generate(&transactions, maybe_block_gas_limit)
             .assert_parallel_output(&output);
@@ -424,7 +424,7 @@ fn publishing_fixed_params_with_block_gas_limit(
         executor_thread_pool,
         None,
     )
-    .execute_transactions_parallel(&(), &transactions, &data_view);
+    .execute_transactions_parallel(&(), &transactions, &data_view, &[]);
     assert_ok!(output);
 
     // Adjust the reads of txn indices[2] to contain module read to key 42.
@@ -469,7 +469,7 @@ fn publishing_fixed_params_with_block_gas_limit(
             executor_thread_pool.clone(),
             None,
         ) // Ensure enough gas limit to commit the module txns (4 is maximum gas per txn)
-        .execute_transactions_parallel(&(), &transactions, &data_view);
+        .execute_transactions_parallel(&(), &transactions, &data_view, &[]);
 
         assert_matches!(output, Err(()));
     }
@@ -548,7 +548,7 @@ fn non_empty_group(
             executor_thread_pool.clone(),
             None,
         )
-        .execute_transactions_parallel(&(), &transactions, &data_view);
+        .execute_transactions_parallel(&(), &transactions, &data_view, &[]);
 
         BaselineOutput::generate(&transactions, None).assert_parallel_output(&output);
     }
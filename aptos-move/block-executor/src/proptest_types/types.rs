@@ -28,6 +28,7 @@ use aptos_types::{
 use aptos_vm_types::resolver::{TExecutorView, TResourceGroupView};
 use bytes::Bytes;
 use claims::{assert_ge, assert_le, assert_ok};
+use fail::fail_point;
 use move_core_types::{identifier::IdentStr, value::MoveTypeLayout};
 use move_vm_types::delayed_values::delayed_field_id::DelayedFieldID;
 use once_cell::sync::OnceCell;
@@ -865,6 +866,12 @@ where
         txn: &Self::Txn,
         txn_idx: TxnIndex,
     ) -> ExecutionStatus<Self::Output, Self::Error> {
+        // Lets tests abort executions of the second transaction, e.g. "1*return()" aborts only
+        // the first one, as if it had observed an inconsistent speculative state.
+        fail_point!("mock-txn-1-abort", txn_idx == 1, |_| {
+            ExecutionStatus::Abort(txn_idx as usize)
+        });
+
         match txn {
             MockTransaction::Write {
                 incarnation_counter,
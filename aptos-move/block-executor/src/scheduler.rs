@@ -6,7 +6,7 @@ use crate::explicit_sync_wrapper::ExplicitSyncWrapper;
 use aptos_aggregator::types::code_invariant_error;
 use aptos_infallible::Mutex;
 use aptos_mvhashmap::types::{Incarnation, TxnIndex};
-use aptos_types::delayed_fields::PanicError;
+use aptos_types::{block_executor::config::DependencyWaitStrategy, delayed_fields::PanicError};
 use concurrent_queue::{ConcurrentQueue, PopError};
 use crossbeam::utils::CachePadded;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
@@ -252,6 +252,11 @@ pub trait TWaitForDependency {
         txn_idx: TxnIndex,
         dep_txn_idx: TxnIndex,
     ) -> Result<DependencyResult, PanicError>;
+
+    /// How to wait for a dependency returned by wait_for_dependency to be resolved.
+    fn dependency_wait_strategy(&self) -> DependencyWaitStrategy {
+        DependencyWaitStrategy::default()
+    }
 }
 
 pub struct Scheduler {
@@ -300,6 +305,17 @@ pub struct Scheduler {
     queueing_commits_lock: CachePadded<ArmedLock>,
 
     commit_queue: ConcurrentQueue<u32>,
+
+    /// An index i maps to whether the output of (committed) transaction i is materialized.
+    txn_materialized: Vec<AtomicBool>,
+    /// All transactions below this index are materialized, see `materialized_idx`.
+    materialized_idx: CachePadded<AtomicU32>,
+
+    /// How the executing transactions wait for their dependencies to be resolved.
+    dependency_wait_strategy: DependencyWaitStrategy,
+
+    /// Number of successful aborts of executed incarnations (i.e. of speculative conflicts).
+    num_aborts: AtomicU32,
 }
 
 /// Public Interfaces for the Scheduler
@@ -328,13 +344,27 @@ impl Scheduler {
             has_halted: CachePadded::new(AtomicBool::new(false)),
             queueing_commits_lock: CachePadded::new(ArmedLock::new()),
             commit_queue: ConcurrentQueue::<u32>::bounded(num_txns as usize),
+            txn_materialized: (0..num_txns).map(|_| AtomicBool::new(false)).collect(),
+            materialized_idx: CachePadded::new(AtomicU32::new(0)),
+            dependency_wait_strategy: DependencyWaitStrategy::default(),
+            num_aborts: AtomicU32::new(0),
         }
     }
 
+    pub fn with_dependency_wait_strategy(mut self, strategy: DependencyWaitStrategy) -> Self {
+        self.dependency_wait_strategy = strategy;
+        self
+    }
+
     pub fn num_txns(&self) -> TxnIndex {
         self.num_txns
     }
 
+    /// Returns the number of incarnations aborted so far, see try_abort.
+    pub fn num_aborts(&self) -> u32 {
+        self.num_aborts.load(Ordering::Relaxed)
+    }
+
     pub fn add_to_commit_queue(&self, txn_idx: u32) {
         self.commit_queue
             .push(txn_idx)
@@ -345,6 +375,23 @@ impl Scheduler {
         self.commit_queue.pop()
     }
 
+    /// Must be called once the output of a committed transaction is materialized, i.e. when
+    /// there will be no more reads on its behalf.
+    pub fn finish_materialization(&self, txn_idx: TxnIndex) {
+        self.txn_materialized[txn_idx as usize].store(true, Ordering::Release);
+    }
+
+    /// Returns an index such that all transactions below it are materialized. Since committed
+    /// transactions are materialized concurrently, this may lag behind the commit index.
+    pub fn materialized_idx(&self) -> TxnIndex {
+        let mut idx = self.materialized_idx.load(Ordering::Acquire);
+        while idx < self.num_txns && self.txn_materialized[idx as usize].load(Ordering::Acquire) {
+            idx += 1;
+        }
+        self.materialized_idx.fetch_max(idx, Ordering::AcqRel);
+        idx
+    }
+
     pub fn queueing_commits_mark_done(&self) {
         self.queueing_commits_lock.unlock()
     }
@@ -432,6 +479,7 @@ impl Scheduler {
 
         if *status == ExecutionStatus::Executed(incarnation) {
             *status = ExecutionStatus::Aborting(incarnation);
+            self.num_aborts.fetch_add(1, Ordering::Relaxed);
             true
         } else {
             false
@@ -703,6 +751,10 @@ impl TWaitForDependency for Scheduler {
 
         Ok(DependencyResult::Dependency(dep_condvar))
     }
+
+    fn dependency_wait_strategy(&self) -> DependencyWaitStrategy {
+        self.dependency_wait_strategy
+    }
 }
 
 /// Private functions of the Scheduler
@@ -5,13 +5,21 @@ use crate::task::TransactionOutput;
 use aptos_mvhashmap::types::TxnIndex;
 
 /// An interface for listening to transaction commit events. The listener is called only once
-/// for each transaction commit.
+/// for each transaction commit, as soon as its output is materialized (i.e. while the rest of
+/// the block may still be executing), so the outputs can be streamed to consumers.
+///
+/// The outputs are final, unless the execution of the block is restarted (e.g. when parallel
+/// execution falls back to sequential), in which case `on_execution_restarted` is called first
+/// and the commits of the block are reported again from the first transaction.
 pub trait TransactionCommitHook: Send + Sync {
     type Output;
 
     fn on_transaction_committed(&self, txn_idx: TxnIndex, output: &Self::Output);
 
     fn on_execution_aborted(&self, txn_idx: TxnIndex);
+
+    /// The commits reported so far for the block are discarded.
+    fn on_execution_restarted(&self) {}
 }
 
 pub struct NoOpTransactionCommitHook<T, E> {
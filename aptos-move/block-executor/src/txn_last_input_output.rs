@@ -206,6 +206,20 @@ impl<T: Transaction, O: TransactionOutput<Txn = T>, E: Debug + Send + Clone>
         )
     }
 
+    /// Returns true if the recorded (speculative) execution of the transaction ended with an
+    /// unrecoverable VM error or a delayed field code invariant error, which, before giving up
+    /// on the block, are re-checked by executing the transaction once more at commit time.
+    pub(crate) fn has_unsupported_status(&self, txn_idx: TxnIndex) -> bool {
+        self.outputs[txn_idx as usize]
+            .load_full()
+            .is_some_and(|status| {
+                matches!(
+                    status.as_ref(),
+                    ExecutionStatus::Abort(_) | ExecutionStatus::DelayedFieldsCodeInvariantError(_)
+                )
+            })
+    }
+
     pub(crate) fn check_fatal_vm_error(
         &self,
         txn_idx: TxnIndex,
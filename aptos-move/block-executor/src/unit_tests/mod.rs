@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    counters::COMMIT_TIME_REEXECUTION_COUNT,
     errors::SequentialBlockExecutionError,
     executor::BlockExecutor,
     proptest_types::{
@@ -15,21 +16,23 @@ use crate::{
     scheduler::{
         DependencyResult, ExecutionTaskType, Scheduler, SchedulerTask, TWaitForDependency,
     },
-    txn_commit_hook::NoOpTransactionCommitHook,
+    txn_commit_hook::{NoOpTransactionCommitHook, TransactionCommitHook},
 };
 use aptos_aggregator::{
     bounded_math::SignedU128,
     delta_change_set::{delta_add, delta_sub, DeltaOp},
     delta_math::DeltaHistory,
 };
+use aptos_infallible::Mutex;
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
-    block_executor::config::BlockExecutorConfig,
+    block_executor::config::{BlockExecutorConfig, DependencyWaitStrategy},
     contract_event::TransactionEvent,
     executable::{ExecutableTestType, ModulePath},
     state_store::state_value::StateValueMetadata,
+    transaction::BlockOutput,
 };
-use claims::assert_matches;
+use claims::{assert_gt, assert_matches, assert_ok};
 use fail::FailScenario;
 use rand::{prelude::*, random};
 use std::{
@@ -98,7 +101,7 @@ fn resource_group_bcs_fallback() {
     );
 
     // Execute the block normally.
-    let output = block_executor.execute_transactions_parallel(&(), &transactions, &data_view);
+    let output = block_executor.execute_transactions_parallel(&(), &transactions, &data_view, &[]);
     match output {
         Ok(block_output) => {
             let txn_outputs = block_output.into_transaction_outputs_forced();
@@ -116,7 +119,8 @@ fn resource_group_bcs_fallback() {
     fail::cfg("fail-point-resource-group-serialization", "return()").unwrap();
     assert!(!fail::list().is_empty());
 
-    let par_output = block_executor.execute_transactions_parallel(&(), &transactions, &data_view);
+    let par_output =
+        block_executor.execute_transactions_parallel(&(), &transactions, &data_view, &[]);
     assert_matches!(par_output, Err(()));
 
     let seq_output =
@@ -196,11 +200,167 @@ fn block_output_err_precedence() {
     assert!(!fail::list().is_empty());
     // Pause the thread that processes the aborting txn1, so txn2 can halt the scheduler first.
     // Confirm that the fatal VM error is still detected and sequential fallback triggered.
-    let output = block_executor.execute_transactions_parallel(&(), &transactions, &data_view);
+    let output = block_executor.execute_transactions_parallel(&(), &transactions, &data_view, &[]);
     assert_matches!(output, Err(()));
     scenario.teardown();
 }
 
+fn independent_transactions(num_txns: u32) -> Vec<MockTransaction<KeyType<u32>, MockEvent>> {
+    (0..num_txns)
+        .map(|i| {
+            MockTransaction::from_behavior(MockIncarnation::new(
+                vec![KeyType::<u32>(i * 2, false)],
+                vec![(
+                    KeyType::<u32>(i * 2 + 1, false),
+                    ValueType::from_value(vec![5], true),
+                )],
+                vec![],
+                vec![],
+                10,
+            ))
+        })
+        .collect()
+}
+
+fn execute_parallel(
+    transactions: &[MockTransaction<KeyType<u32>, MockEvent>],
+) -> Result<BlockOutput<MockOutput<KeyType<u32>, MockEvent>>, ()> {
+    let data_view = DeltaDataView::<KeyType<u32>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    BlockExecutor::<
+        MockTransaction<KeyType<u32>, MockEvent>,
+        MockTask<KeyType<u32>, MockEvent>,
+        DeltaDataView<KeyType<u32>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, usize>,
+        ExecutableTestType,
+    >::new(
+        BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
+        executor_thread_pool,
+        None,
+    )
+    .execute_transactions_parallel(&(), transactions, &data_view, &[])
+}
+
+#[test]
+fn speculative_abort_cleared_at_commit() {
+    // Without conflicts, the aborted execution stays until the transaction is committed.
+    let transactions = independent_transactions(4);
+
+    let scenario = FailScenario::setup();
+    fail::cfg("mock-txn-1-abort", "1*return()").unwrap();
+    let reexecutions = COMMIT_TIME_REEXECUTION_COUNT.get();
+    let output = execute_parallel(&transactions);
+    scenario.teardown();
+
+    // Re-executed at commit, where it succeeds, instead of falling back for the whole block.
+    assert_ok!(&output);
+    assert_gt!(COMMIT_TIME_REEXECUTION_COUNT.get(), reexecutions);
+    BaselineOutput::generate(&transactions, None).assert_parallel_output(&output);
+}
+
+#[test]
+fn persistent_abort_falls_back() {
+    let mut transactions = independent_transactions(4);
+    transactions[1] = MockTransaction::Abort;
+
+    let reexecutions = COMMIT_TIME_REEXECUTION_COUNT.get();
+    let output = execute_parallel(&transactions);
+
+    // Still aborting when re-executed at commit, so the block falls back to sequential execution.
+    assert_gt!(COMMIT_TIME_REEXECUTION_COUNT.get(), reexecutions);
+    assert_matches!(output, Err(()));
+}
+
+#[derive(Debug, PartialEq)]
+enum CommitEvent {
+    Committed(TxnIndex),
+    Aborted(TxnIndex),
+    Restarted,
+}
+
+struct RecordingCommitHook {
+    events: Arc<Mutex<Vec<CommitEvent>>>,
+}
+
+impl TransactionCommitHook for RecordingCommitHook {
+    type Output = MockOutput<KeyType<u32>, MockEvent>;
+
+    fn on_transaction_committed(&self, txn_idx: TxnIndex, _output: &Self::Output) {
+        self.events.lock().push(CommitEvent::Committed(txn_idx));
+    }
+
+    fn on_execution_aborted(&self, txn_idx: TxnIndex) {
+        self.events.lock().push(CommitEvent::Aborted(txn_idx));
+    }
+
+    fn on_execution_restarted(&self) {
+        self.events.lock().push(CommitEvent::Restarted);
+    }
+}
+
+#[test]
+fn commit_hook_streams_each_output_once() {
+    let transactions: Vec<_> = (0..10)
+        .map(|i| {
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<u32>, MockEvent>::new(
+                vec![KeyType::<u32>(i, false)],
+                vec![(
+                    KeyType::<u32>(i + 1, false),
+                    ValueType::from_value(vec![5], true),
+                )],
+                vec![],
+                vec![],
+                10,
+            ))
+        })
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<u32>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let events = Arc::new(Mutex::new(vec![]));
+    let block_executor = BlockExecutor::<
+        MockTransaction<KeyType<u32>, MockEvent>,
+        MockTask<KeyType<u32>, MockEvent>,
+        DeltaDataView<KeyType<u32>>,
+        RecordingCommitHook,
+        ExecutableTestType,
+    >::new(
+        BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
+        executor_thread_pool,
+        Some(RecordingCommitHook {
+            events: events.clone(),
+        }),
+    );
+
+    let output = block_executor.execute_block((), &transactions, &data_view);
+    assert_eq!(output.unwrap().into_transaction_outputs_forced().len(), 10);
+
+    let mut events = std::mem::take(&mut *events.lock());
+    // Materialization is concurrent, so the commits are not necessarily reported in order.
+    events.sort_by_key(|event| match event {
+        CommitEvent::Committed(idx) => *idx,
+        _ => unreachable!("Unexpected event {:?}", event),
+    });
+    assert_eq!(
+        events,
+        (0..10).map(CommitEvent::Committed).collect::<Vec<_>>()
+    );
+}
+
 #[test]
 fn skip_rest_gas_limit() {
     // The contents of the second txn does not matter, as the first should hit the gas limit and
@@ -229,7 +389,7 @@ fn skip_rest_gas_limit() {
     );
 
     // Should hit block limit on the skip transaction.
-    let _ = block_executor.execute_transactions_parallel(&(), &transactions, &data_view);
+    let _ = block_executor.execute_transactions_parallel(&(), &transactions, &data_view, &[]);
 }
 
 // TODO: add unit test for block gas limit!
@@ -237,6 +397,21 @@ fn run_and_assert<K, E>(transactions: Vec<MockTransaction<K, E>>)
 where
     K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
     E: Send + Sync + Debug + Clone + TransactionEvent + 'static,
+{
+    run_and_assert_with_config(
+        transactions,
+        BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
+        &[],
+    )
+}
+
+fn run_and_assert_with_config<K, E>(
+    transactions: Vec<MockTransaction<K, E>>,
+    config: BlockExecutorConfig,
+    predicted_reads: &[K],
+) where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    E: Send + Sync + Debug + Clone + TransactionEvent + 'static,
 {
     let data_view = DeltaDataView::<K> {
         phantom: PhantomData,
@@ -255,12 +430,8 @@ where
         DeltaDataView<K>,
         NoOpTransactionCommitHook<MockOutput<K, E>, usize>,
         ExecutableTestType,
-    >::new(
-        BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
-        executor_thread_pool,
-        None,
-    )
-    .execute_transactions_parallel(&(), &transactions, &data_view);
+    >::new(config, executor_thread_pool, None)
+    .execute_transactions_parallel(&(), &transactions, &data_view, predicted_reads);
 
     let baseline = BaselineOutput::generate(&transactions, None);
     baseline.assert_parallel_output(&output);
@@ -403,6 +574,68 @@ fn cycle_transactions() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn dependency_wait_strategies() {
+    for strategy in [
+        DependencyWaitStrategy::Condvar,
+        DependencyWaitStrategy::Spin(1000),
+        DependencyWaitStrategy::Yield(10),
+        DependencyWaitStrategy::Timeout(10),
+    ] {
+        // A few keys each written by a long chain of transactions, so that they often read
+        // estimates and wait on dependencies.
+        let keys: Vec<_> = (0..4)
+            .map(|_| KeyType(random::<[u8; 32]>(), false))
+            .collect();
+        let transactions: Vec<_> = (0..200)
+            .map(|i| {
+                let key = keys[i % keys.len()];
+                MockTransaction::from_behavior(
+                    MockIncarnation::<KeyType<[u8; 32]>, MockEvent>::new(
+                        vec![key],                        // reads
+                        vec![(key, random_value(false))], // writes
+                        vec![],
+                        vec![],
+                        1, // gas
+                    ),
+                )
+            })
+            .collect();
+
+        let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+        config.local.dependency_wait_strategy = strategy;
+        run_and_assert_with_config(transactions, config, &[]);
+    }
+}
+
+#[test]
+fn predicted_reads_prefetch() {
+    let keys: Vec<_> = (0..20)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let transactions: Vec<_> = (0..100)
+        .map(|i| {
+            let key = keys[i % keys.len()];
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<[u8; 32]>, MockEvent>::new(
+                vec![key],                        // reads
+                vec![(key, random_value(false))], // writes
+                vec![],
+                vec![],
+                1, // gas
+            ))
+        })
+        .collect();
+
+    // Predictions can include keys that are not read, and miss keys that are.
+    let mut predicted_reads = keys[..10].to_vec();
+    predicted_reads.push(KeyType(random::<[u8; 32]>(), false));
+    run_and_assert_with_config(
+        transactions,
+        BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
+        &predicted_reads,
+    );
+}
+
 const NUM_BLOCKS: u64 = 10;
 const TXN_PER_BLOCK: u64 = 100;
 
@@ -855,6 +1088,23 @@ fn scheduler_drain_idx() {
     assert_matches!(s.next_task(), SchedulerTask::Done);
 }
 
+#[test]
+fn scheduler_materialized_idx() {
+    let s = Scheduler::new(4);
+    assert_eq!(s.materialized_idx(), 0);
+
+    // Materialization may finish out of order, the index only covers a prefix.
+    s.finish_materialization(1);
+    s.finish_materialization(2);
+    assert_eq!(s.materialized_idx(), 0);
+
+    s.finish_materialization(0);
+    assert_eq!(s.materialized_idx(), 3);
+
+    s.finish_materialization(3);
+    assert_eq!(s.materialized_idx(), 4);
+}
+
 #[test]
 fn finish_execution_wave() {
     // Wave won't be increased, because validation index is already 2, and finish_execution
@@ -36,6 +36,7 @@ use aptos_mvhashmap::{
     MVHashMap,
 };
 use aptos_types::{
+    block_executor::config::DependencyWaitStrategy,
     delayed_fields::PanicError,
     executable::{Executable, ModulePath},
     state_store::{
@@ -66,10 +67,13 @@ use std::{
     cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Debug,
+    hint,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc,
     },
+    thread,
+    time::Duration,
 };
 
 /// A struct which describes the result of the read from the proxy. The client
@@ -425,9 +429,47 @@ fn wait_for_dependency(
             // than txn_idx are not blocked, so the execution of dep_idx will
             // eventually finish and lead to unblocking txn_idx, contradiction.
             let (lock, cvar) = &*dep_condition;
+            let strategy = wait_for.dependency_wait_strategy();
+            let (strategy_label, busy_wait_iterations) = match strategy {
+                DependencyWaitStrategy::Condvar => ("condvar", 0),
+                DependencyWaitStrategy::Spin(iterations) => ("spin", iterations),
+                DependencyWaitStrategy::Yield(iterations) => ("yield", iterations),
+                DependencyWaitStrategy::Timeout(_) => ("timeout", 0),
+            };
+
+            for _ in 0..busy_wait_iterations {
+                let dep_resolved = lock.lock();
+                if !matches!(*dep_resolved, DependencyStatus::Unresolved) {
+                    counters::DEPENDENCY_WAIT_COUNT
+                        .with_label_values(&[strategy_label, "busy_wait"])
+                        .inc();
+                    return Ok(matches!(*dep_resolved, DependencyStatus::Resolved));
+                }
+                drop(dep_resolved);
+                if matches!(strategy, DependencyWaitStrategy::Spin(_)) {
+                    hint::spin_loop();
+                } else {
+                    thread::yield_now();
+                }
+            }
+
+            counters::DEPENDENCY_WAIT_COUNT
+                .with_label_values(&[strategy_label, "blocked"])
+                .inc();
             let mut dep_resolved = lock.lock();
             while matches!(*dep_resolved, DependencyStatus::Unresolved) {
-                dep_resolved = cvar.wait(dep_resolved).unwrap();
+                dep_resolved = match strategy {
+                    DependencyWaitStrategy::Timeout(timeout_micros) => {
+                        let (dep_resolved, wait_result) = cvar
+                            .wait_timeout(dep_resolved, Duration::from_micros(timeout_micros))
+                            .unwrap();
+                        if wait_result.timed_out() {
+                            counters::DEPENDENCY_WAIT_TIMEOUT_COUNT.inc();
+                        }
+                        dep_resolved
+                    },
+                    _ => cvar.wait(dep_resolved).unwrap(),
+                };
             }
             // dep resolved status is either resolved or execution halted.
             Ok(matches!(*dep_resolved, DependencyStatus::Resolved))
@@ -29,6 +29,7 @@ use aptos_types::{
     },
     block_executor::config::{
         BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
+        DependencyWaitStrategy,
     },
     block_metadata::BlockMetadata,
     chain_id::ChainId,
@@ -529,6 +530,10 @@ impl FakeExecutor {
                 },
                 allow_fallback: self.allow_block_executor_fallback,
                 discard_failed_blocks: false,
+                versioned_data_memory_budget: None,
+                dependency_wait_strategy: DependencyWaitStrategy::default(),
+                auto_select_execution_mode: false,
+                prefetch_predicted_reads: false,
             },
             onchain: onchain_config,
         };
@@ -1102,6 +1107,16 @@ impl FakeExecutor {
         function_name: &str,
         type_params: Vec<TypeTag>,
         args: Vec<Vec<u8>>,
+    ) -> Result<(WriteSet, Vec<ContractEvent>), VMStatus> {
+        self.try_exec_module(&Self::module(module_name), function_name, type_params, args)
+    }
+
+    pub fn try_exec_module(
+        &mut self,
+        module_id: &ModuleId,
+        function_name: &str,
+        type_params: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
     ) -> Result<(WriteSet, Vec<ContractEvent>), VMStatus> {
         let resolver = self.data_store.as_move_resolver();
         let vm = MoveVmExt::new(
@@ -1114,7 +1129,7 @@ impl FakeExecutor {
         let storage = TraversalStorage::new();
         session
             .execute_function_bypass_visibility(
-                &Self::module(module_name),
+                module_id,
                 &Self::name(function_name),
                 type_params,
                 args,
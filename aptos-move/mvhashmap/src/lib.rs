@@ -41,7 +41,7 @@ pub struct MVHashMap<K, T, V: TransactionWrite, X: Executable, I: Clone> {
 }
 
 impl<
-        K: ModulePath + Hash + Clone + Eq + Debug,
+        K: ModulePath + Hash + Clone + Eq + Ord + Debug,
         T: Hash + Clone + Eq + Debug + Serialize,
         V: TransactionWrite,
         X: Executable,
@@ -92,7 +92,7 @@ impl<
 }
 
 impl<
-        K: ModulePath + Hash + Clone + Debug + Eq,
+        K: ModulePath + Hash + Clone + Debug + Eq + Ord,
         T: Hash + Clone + Debug + Eq + Serialize,
         V: TransactionWrite,
         X: Executable,
@@ -255,7 +255,7 @@ pub(crate) mod test {
     use move_core_types::{account_address::AccountAddress, identifier::IdentStr};
     use std::{fmt::Debug, hash::Hash, sync::Arc};
 
-    #[derive(Clone, Eq, Hash, PartialEq, Debug)]
+    #[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Debug)]
     pub(crate) struct KeyType<K: Hash + Clone + Debug + Eq>(
         /// Wrapping the types used for testing to add ModulePath trait implementation.
         pub K,
@@ -374,7 +374,7 @@ pub(crate) mod test {
     }
 
     // Generate a Vec deterministically based on txn_idx and incarnation.
-    fn value_for(txn_idx: TxnIndex, incarnation: Incarnation) -> TestValue {
+    pub(crate) fn value_for(txn_idx: TxnIndex, incarnation: Incarnation) -> TestValue {
         TestValue::new(vec![txn_idx * 5, txn_idx + incarnation, incarnation * 5])
     }
 
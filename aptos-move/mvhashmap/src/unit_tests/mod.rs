@@ -4,8 +4,8 @@
 
 use super::{
     types::{
-        test::{arc_value_for, u128_for, KeyType, TestValue},
-        MVDataError, MVDataOutput,
+        test::{arc_value_for, u128_for, value_for, KeyType, TestValue},
+        MVDataError, MVDataOutput, StorageVersion,
     },
     unsync_map::UnsyncMap,
     *,
@@ -383,3 +383,151 @@ fn commit_without_entry() {
     // Must panic as there is no delta at provided index.
     let _ = vd.materialize_delta(&ap, 9);
 }
+
+#[test]
+fn fetch_range() {
+    use MVDataError::*;
+    use MVDataOutput::*;
+
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap_a = KeyType(b"/foo/a".to_vec());
+    let ap_b = KeyType(b"/foo/b".to_vec());
+    let ap_c = KeyType(b"/foo/c".to_vec());
+    let ap_other = KeyType(b"/zoo/a".to_vec());
+
+    vd.write(ap_c.clone(), 3, 0, arc_value_for(3, 0), None);
+    vd.write(ap_a.clone(), 5, 1, arc_value_for(5, 1), None);
+    vd.write(ap_b.clone(), 8, 0, arc_value_for(8, 0), None);
+    vd.write(ap_other.clone(), 1, 0, arc_value_for(1, 0), None);
+
+    let range = KeyType(b"/foo/".to_vec())..KeyType(b"/foo0".to_vec());
+
+    // Only keys written below the reader's index, in key order.
+    assert_eq!(vd.fetch_range(&range, 3), vec![]);
+    assert_eq!(vd.fetch_range(&range, 6), vec![
+        (
+            ap_a.clone(),
+            Ok(Versioned(
+                Ok((5, 1)),
+                ValueWithLayout::Exchanged(arc_value_for(5, 1), None)
+            ))
+        ),
+        (
+            ap_c.clone(),
+            Ok(Versioned(
+                Ok((3, 0)),
+                ValueWithLayout::Exchanged(arc_value_for(3, 0), None)
+            ))
+        ),
+    ]);
+
+    // Estimates surface as dependencies.
+    vd.mark_estimate(&ap_a, 5);
+    let reads = vd.fetch_range(&range, 10);
+    assert_eq!(reads.len(), 3);
+    assert_eq!(reads[0], (ap_a, Err(Dependency(5))));
+    assert_eq!(reads[1].0, ap_b);
+    assert_eq!(reads[2].0, ap_c);
+}
+
+#[test]
+fn fetch_range_misses_phantoms() {
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap_a = KeyType(b"/foo/a".to_vec());
+    let ap_b = KeyType(b"/foo/b".to_vec());
+    let range = KeyType(b"/foo/".to_vec())..KeyType(b"/foo0".to_vec());
+
+    vd.write(ap_a.clone(), 2, 0, arc_value_for(2, 0), None);
+    let reads = vd.fetch_range(&range, 10);
+    assert_eq!(reads.len(), 1);
+    assert_eq!(reads[0].0, ap_a);
+
+    // A lower transaction writing a new key into the range (e.g. re-executed) changes what
+    // the range read by transaction 10 should have returned, and nothing records that the read
+    // depended on the absence of the key.
+    vd.write(ap_b.clone(), 5, 0, arc_value_for(5, 0), None);
+    let reads_after = vd.fetch_range(&range, 10);
+    assert_eq!(reads_after.len(), 2);
+    assert_eq!(reads_after[1].0, ap_b);
+    assert_ne!(reads, reads_after);
+}
+
+#[test]
+fn evict_below() {
+    use MVDataError::*;
+    use MVDataOutput::*;
+
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap1 = KeyType(b"/foo/a".to_vec());
+    let ap2 = KeyType(b"/foo/b".to_vec());
+    let ap3 = KeyType(b"/foo/c".to_vec());
+
+    vd.write(ap1.clone(), 1, 0, arc_value_for(1, 0), None);
+    vd.write(ap1.clone(), 3, 0, arc_value_for(3, 0), None);
+    vd.write(ap1.clone(), 6, 0, arc_value_for(6, 0), None);
+    // Deltas below the index without a shortcut resolve against the write below them.
+    vd.write(ap2.clone(), 0, 0, arc_value_for(0, 0), None);
+    vd.add_delta(ap2.clone(), 2, delta_add(5, u128::MAX));
+    vd.add_delta(ap2.clone(), 4, delta_add(7, u128::MAX));
+    // Deltas with nothing to resolve against are kept.
+    vd.add_delta(ap3.clone(), 1, delta_add(5, u128::MAX));
+
+    let size_before = vd.total_versioned_value_size();
+    let ap2_value = vd.fetch_data(&ap2, 5).unwrap();
+    vd.evict_below(5);
+    assert_eq!(vd.evicted_idx(), 5);
+
+    // Reads at or above the index are unaffected.
+    assert_eq!(
+        vd.fetch_data(&ap1, 5),
+        Ok(Versioned(
+            Ok((3, 0)),
+            ValueWithLayout::Exchanged(arc_value_for(3, 0), None)
+        ))
+    );
+    assert_eq!(
+        vd.fetch_data(&ap1, 7),
+        Ok(Versioned(
+            Ok((6, 0)),
+            ValueWithLayout::Exchanged(arc_value_for(6, 0), None)
+        ))
+    );
+    assert_eq!(vd.fetch_data(&ap2, 5).unwrap(), ap2_value);
+    assert_eq!(
+        vd.fetch_data(&ap3, 5),
+        Err(Unresolved(delta_add(5, u128::MAX)))
+    );
+
+    // The write at 1 is dropped.
+    assert_eq!(vd.fetch_data(&ap1, 2), Err(Uninitialized));
+    assert!(vd.total_versioned_value_size() < size_before);
+
+    // Evicting below a lower index is a no-op.
+    vd.evict_below(2);
+    assert_eq!(vd.evicted_idx(), 5);
+}
+
+#[test]
+fn prefetch_base_value() {
+    use MVDataError::*;
+    use MVDataOutput::*;
+
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap1 = KeyType(b"/foo/a".to_vec());
+    let ap2 = KeyType(b"/foo/b".to_vec());
+
+    assert!(vd.prefetch_base_value(ap1.clone(), |_| Some(value_for(0, 1))));
+    assert_eq!(
+        vd.fetch_data(&ap1, 3),
+        Ok(Versioned(
+            Err(StorageVersion),
+            ValueWithLayout::RawFromStorage(arc_value_for(0, 1))
+        ))
+    );
+
+    // Already set base values are not fetched again.
+    assert!(!vd.prefetch_base_value(ap1.clone(), |_| unreachable!("Must not fetch")));
+    // Failed fetches leave the key uninitialized.
+    assert!(!vd.prefetch_base_value(ap2.clone(), |_| None));
+    assert_eq!(vd.fetch_data(&ap2, 3), Err(Uninitialized));
+}
@@ -212,7 +212,7 @@ fn run_and_assert<K, V>(
     test_group: bool,
 ) -> Result<(), TestCaseError>
 where
-    K: PartialOrd + Send + Clone + Hash + Eq + Sync + Debug,
+    K: PartialOrd + Ord + Send + Clone + Hash + Eq + Sync + Debug,
     V: Send + Into<Vec<u8>> + Debug + Clone + PartialEq + Sync,
 {
     let transactions: Vec<(K, Operator<V>)> = transaction_gens
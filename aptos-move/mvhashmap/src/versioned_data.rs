@@ -10,14 +10,19 @@ use aptos_aggregator::delta_change_set::DeltaOp;
 use aptos_types::write_set::TransactionWrite;
 use claims::assert_some;
 use crossbeam::utils::CachePadded;
-use dashmap::DashMap;
+use dashmap::{mapref::one::RefMut, DashMap};
 use move_core_types::value::MoveTypeLayout;
+use parking_lot::RwLock;
 use std::{
-    collections::btree_map::{self, BTreeMap},
+    collections::{
+        btree_map::{self, BTreeMap},
+        BTreeSet,
+    },
     fmt::Debug,
     hash::Hash,
+    ops::RangeBounds,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicU32, AtomicU64, Ordering},
         Arc,
     },
 };
@@ -55,7 +60,15 @@ struct VersionedValue<V> {
 /// Maps each key (access path) to an internal versioned value representation.
 pub struct VersionedData<K, V> {
     values: DashMap<K, VersionedValue<V>>,
+    /// The keys of `values` in order, for range reads. A key is added before its first entry,
+    /// and kept for the lifetime of the data-structure.
+    keys: RwLock<BTreeSet<K>>,
     total_base_value_size: AtomicU64,
+    /// Total size of the values written by the transactions (i.e. excluding base values)
+    /// that are currently held, which eviction of committed versions aims to bound.
+    total_versioned_value_size: AtomicU64,
+    /// Entries below this index have been evicted, see `evict_below`.
+    evicted_idx: AtomicU32,
 }
 
 impl<V> Entry<V> {
@@ -77,6 +90,16 @@ impl<V> Entry<V> {
         self.flag
     }
 
+    fn value_size(&self) -> u64
+    where
+        V: TransactionWrite,
+    {
+        match &self.cell {
+            EntryCell::Write(_, value) => value.bytes_len().unwrap_or(0) as u64,
+            EntryCell::Delta(_, _) => 0,
+        }
+    }
+
     fn mark_estimate(&mut self) {
         self.flag = Flag::Estimate;
     }
@@ -211,13 +234,41 @@ impl<V: TransactionWrite> VersionedValue<V> {
             None => Err(Uninitialized),
         }
     }
+
+    // Drops all entries below the latest one below txn_idx that a read can resolve to without
+    // traversing further (a write, or a delta with a shortcut), so reads at indices >= txn_idx
+    // are not affected. Returns the total size of the dropped (non-base) values.
+    fn evict_below(&mut self, txn_idx: TxnIndex) -> u64 {
+        let keep_from = self
+            .versioned_map
+            .range(..ShiftedTxnIndex::new(txn_idx))
+            .rev()
+            .find(|(_, entry)| !matches!(entry.cell, EntryCell::Delta(_, None)))
+            .map(|(idx, _)| idx.clone());
+
+        match keep_from {
+            Some(keep_from) => {
+                let kept = self.versioned_map.split_off(&keep_from);
+                std::mem::replace(&mut self.versioned_map, kept)
+                    .into_iter()
+                    .filter(|(idx, _)| idx.idx().is_ok())
+                    .map(|(_, entry)| entry.value_size())
+                    .sum()
+            },
+            // Nothing to resolve against below txn_idx, keep the deltas.
+            None => 0,
+        }
+    }
 }
 
-impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
+impl<K: Hash + Clone + Debug + Eq + Ord, V: TransactionWrite> VersionedData<K, V> {
     pub(crate) fn new() -> Self {
         Self {
             values: DashMap::new(),
+            keys: RwLock::new(BTreeSet::new()),
             total_base_value_size: AtomicU64::new(0),
+            total_versioned_value_size: AtomicU64::new(0),
+            evicted_idx: AtomicU32::new(0),
         }
     }
 
@@ -229,8 +280,17 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         self.total_base_value_size.load(Ordering::Relaxed)
     }
 
+    pub fn total_versioned_value_size(&self) -> u64 {
+        self.total_versioned_value_size.load(Ordering::Relaxed)
+    }
+
+    /// The index below which entries were last evicted.
+    pub fn evicted_idx(&self) -> TxnIndex {
+        self.evicted_idx.load(Ordering::Relaxed)
+    }
+
     pub fn add_delta(&self, key: K, txn_idx: TxnIndex, delta: DeltaOp) {
-        let mut v = self.values.entry(key).or_default();
+        let mut v = self.versioned_value_mut(key);
         v.versioned_map.insert(
             ShiftedTxnIndex::new(txn_idx),
             CachePadded::new(Entry::new_delta_from(delta)),
@@ -252,10 +312,11 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
     pub fn remove(&self, key: &K, txn_idx: TxnIndex) {
         // TODO: investigate logical deletion.
         let mut v = self.values.get_mut(key).expect("Path must exist");
-        assert_some!(
+        let removed = assert_some!(
             v.versioned_map.remove(&ShiftedTxnIndex::new(txn_idx)),
             "Entry for key / idx must exist to be deleted"
         );
+        self.sub_versioned_value_size(&removed);
     }
 
     pub fn fetch_data(
@@ -283,8 +344,35 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         }
     }
 
+    /// Sets the base value of the key to the value fetched by fetch_base_value, unless a base
+    /// value is already set. Meant for prefetching the base values of the keys predicted to be
+    /// read, before the transactions request them. The value is fetched without holding a lock,
+    /// so a concurrent set_base_value is possible, which is handled as in set_base_value.
+    /// Returns true if the base value was fetched and set.
+    pub fn prefetch_base_value(
+        &self,
+        key: K,
+        fetch_base_value: impl FnOnce(&K) -> Option<V>,
+    ) -> bool {
+        let is_set = self
+            .values
+            .get(&key)
+            .is_some_and(|v| v.versioned_map.contains_key(&ShiftedTxnIndex::zero_idx()));
+        if is_set {
+            return false;
+        }
+
+        match fetch_base_value(&key) {
+            Some(value) => {
+                self.set_base_value(key, ValueWithLayout::RawFromStorage(Arc::new(value)));
+                true
+            },
+            None => false,
+        }
+    }
+
     pub fn set_base_value(&self, key: K, value: ValueWithLayout<V>) {
-        let mut v = self.values.entry(key).or_default();
+        let mut v = self.versioned_value_mut(key);
         // For base value, incarnation is irrelevant, and is always set to 0.
 
         use btree_map::Entry::*;
@@ -342,14 +430,18 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         data: Arc<V>,
         maybe_layout: Option<Arc<MoveTypeLayout>>,
     ) {
-        let mut v = self.values.entry(key).or_default();
-        let prev_entry = v.versioned_map.insert(
-            ShiftedTxnIndex::new(txn_idx),
-            CachePadded::new(Entry::new_write_from(
-                incarnation,
-                ValueWithLayout::Exchanged(data, maybe_layout),
-            )),
-        );
+        let entry =
+            Entry::new_write_from(incarnation, ValueWithLayout::Exchanged(data, maybe_layout));
+        self.total_versioned_value_size
+            .fetch_add(entry.value_size(), Ordering::Relaxed);
+
+        let mut v = self.versioned_value_mut(key);
+        let prev_entry = v
+            .versioned_map
+            .insert(ShiftedTxnIndex::new(txn_idx), CachePadded::new(entry));
+        if let Some(prev_entry) = &prev_entry {
+            self.sub_versioned_value_size(prev_entry);
+        }
 
         // Assert that the previous entry for txn_idx, if present, had lower incarnation.
         assert!(prev_entry.map_or(true, |entry| -> bool {
@@ -372,15 +464,20 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         data: V,
     ) -> bool {
         let arc_data = Arc::new(data);
-
-        let mut v = self.values.entry(key).or_default();
-        let prev_entry = v.versioned_map.insert(
-            ShiftedTxnIndex::new(txn_idx),
-            CachePadded::new(Entry::new_write_from(
-                incarnation,
-                ValueWithLayout::Exchanged(arc_data.clone(), None),
-            )),
+        let entry = Entry::new_write_from(
+            incarnation,
+            ValueWithLayout::Exchanged(arc_data.clone(), None),
         );
+        self.total_versioned_value_size
+            .fetch_add(entry.value_size(), Ordering::Relaxed);
+
+        let mut v = self.versioned_value_mut(key);
+        let prev_entry = v
+            .versioned_map
+            .insert(ShiftedTxnIndex::new(txn_idx), CachePadded::new(entry));
+        if let Some(prev_entry) = &prev_entry {
+            self.sub_versioned_value_size(prev_entry);
+        }
 
         // Changes versioned metadata that was stored.
         prev_entry.map_or(true, |entry| -> bool {
@@ -422,4 +519,74 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
             ),
         }
     }
+
+    /// Bounds the memory held for a large block by dropping, at every key, the entries below
+    /// the latest one below `txn_idx`, which is the only one a read by a transaction at or above
+    /// `txn_idx` can observe. It must be guaranteed by the caller that all transactions below
+    /// `txn_idx` are committed, and that no more reads below `txn_idx` will happen (i.e. the
+    /// outputs of the committed transactions have been materialized).
+    pub fn evict_below(&self, txn_idx: TxnIndex) {
+        if self.evicted_idx.fetch_max(txn_idx, Ordering::Relaxed) >= txn_idx {
+            return;
+        }
+
+        for mut v in self.values.iter_mut() {
+            let evicted_size = v.evict_below(txn_idx);
+            self.total_versioned_value_size
+                .fetch_sub(evicted_size, Ordering::Relaxed);
+        }
+    }
+
+    /// Reads, as `fetch_data` by transaction `txn_idx`, of the keys in `range` that have an
+    /// entry below `txn_idx`, in key order. A prefix read is a range read over the keys
+    /// starting with the prefix.
+    ///
+    /// Keys without such an entry (that would read `Uninitialized`) are omitted, so the caller
+    /// must merge the result with the same range in storage, the entries here taking precedence.
+    /// Reads that hit an estimate are returned as `Dependency` errors for the caller to wait on.
+    ///
+    /// Unlike the reads of single keys, nothing is recorded to validate a range read: a key
+    /// that a lower transaction writes into the range afterwards (a phantom) goes unnoticed. So
+    /// it is not safe for speculative execution, only once all transactions below `txn_idx`
+    /// are committed.
+    pub fn fetch_range<R: RangeBounds<K>>(
+        &self,
+        range: &R,
+        txn_idx: TxnIndex,
+    ) -> Vec<(K, anyhow::Result<MVDataOutput<V>, MVDataError>)> {
+        // Not holding the lock of the index while reading, which would order it before the
+        // locks of the values, while the writers of new keys take it after.
+        let keys: Vec<K> = self
+            .keys
+            .read()
+            .range((range.start_bound(), range.end_bound()))
+            .cloned()
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| {
+                let read = self.values.get(&key)?.read(txn_idx);
+                match read {
+                    Err(MVDataError::Uninitialized) => None,
+                    read => Some((key, read)),
+                }
+            })
+            .collect()
+    }
+
+    /// The versioned value of `key`, adding the key to the index if it is new.
+    fn versioned_value_mut(&self, key: K) -> RefMut<'_, K, VersionedValue<V>> {
+        use dashmap::mapref::entry::Entry::*;
+        match self.values.entry(key) {
+            Occupied(o) => o.into_ref(),
+            Vacant(v) => {
+                self.keys.write().insert(v.key().clone());
+                v.insert(VersionedValue::default())
+            },
+        }
+    }
+
+    fn sub_versioned_value_size(&self, entry: &Entry<V>) {
+        self.total_versioned_value_size
+            .fetch_sub(entry.value_size(), Ordering::Relaxed);
+    }
 }
@@ -4,9 +4,12 @@
 use crate::types::{
     Flag, Incarnation, MVGroupError, ShiftedTxnIndex, TxnIndex, ValueWithLayout, Version,
 };
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use aptos_types::write_set::{TransactionWrite, WriteOpKind};
-use aptos_vm_types::{resolver::ResourceGroupSize, resource_group_adapter::group_size_as_sum};
+use aptos_vm_types::{
+    resolver::ResourceGroupSize,
+    resource_group_adapter::{group_size_as_sum, group_tagged_resource_size},
+};
 use claims::{assert_matches, assert_none, assert_some};
 use crossbeam::utils::CachePadded;
 use dashmap::DashMap;
@@ -62,6 +65,8 @@ pub(crate) struct VersionedGroupValue<T, V> {
 
     /// Group contents corresponding to the latest committed version.
     committed_group: HashMap<T, ValueWithLayout<V>>,
+    /// Size of the committed group contents, updated along with committed_group.
+    committed_size: ResourceGroupSize,
 
     /// Group size has changed between speculative executions. Useful to know for the best
     /// heuristic behavior when reading the group size (e.g. wait on the dependency or not).
@@ -81,6 +86,7 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> Default
             versioned_map: HashMap::new(),
             idx_to_update: BTreeMap::new(),
             committed_group: HashMap::new(),
+            committed_size: ResourceGroupSize::zero_combined(),
             size_changed: false,
         }
     }
@@ -152,7 +158,12 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
                             .get_mut(&tag)
                             .expect("Tag must exist in committed when updating for exchange");
                         assert_matches!(existing, &mut ValueWithLayout::RawFromStorage(_));
+                        let prev_len = existing.bytes_len();
+                        let new_len = v.bytes_len();
                         *existing = v;
+                        // Tag was already serialized when the base value was committed.
+                        update_combined_size(&mut self.committed_size, &tag, prev_len, new_len)
+                            .expect("Tag of a committed value must serialize");
                     },
                     ValueWithLayout::Exchanged(_, _) => {
                         // already exchanged, skipping.
@@ -174,9 +185,17 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
         let zero_idx = ShiftedTxnIndex::zero_idx();
         let at_base_version = shifted_idx == zero_idx;
 
-        // Remove any prior entries.
-        let mut prev_tag_and_sizes: HashMap<T, Option<usize>> =
-            self.remove(shifted_idx.clone()).into_iter().collect();
+        // Prior entries are merged with the new ones: entries at the written tags get replaced
+        // in place, and only those at the tags that are no longer written get removed.
+        let mut prev_tag_and_sizes: HashMap<T, Option<usize>> = self
+            .idx_to_update
+            .remove(&shifted_idx)
+            .map_or_else(HashMap::new, |map| {
+                map.into_inner()
+                    .into_iter()
+                    .map(|(tag, v)| (tag, v.bytes_len()))
+                    .collect()
+            });
 
         // Changes the set of values, or the size of the entries (that might have been
         // used even when marked as an estimate, if self.size_changed was still false).
@@ -201,8 +220,15 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
             })
             .collect();
 
-        if !prev_tag_and_sizes.is_empty() {
+        for tag in prev_tag_and_sizes.into_keys() {
             changes_behavior = true;
+            assert_some!(
+                self.versioned_map
+                    .get_mut(&tag)
+                    .expect("Versioned entry must exist for tag")
+                    .remove(&shifted_idx),
+                "Entry for tag / idx must exist to be removed"
+            );
         }
 
         assert_none!(
@@ -227,6 +253,32 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
         changes_behavior
     }
 
+    fn write_tagged(
+        &mut self,
+        shifted_idx: ShiftedTxnIndex,
+        incarnation: Incarnation,
+        tag: T,
+        value: ValueWithLayout<V>,
+    ) -> bool {
+        let prev_size = self
+            .idx_to_update
+            .entry(shifted_idx.clone())
+            .or_default()
+            .insert(tag.clone(), value.clone())
+            .map(|prev| prev.bytes_len());
+        let changes_behavior = prev_size != Some(value.bytes_len());
+
+        self.versioned_map.entry(tag).or_default().insert(
+            shifted_idx,
+            CachePadded::new(GroupEntry::new(incarnation, value)),
+        );
+
+        if changes_behavior && incarnation > 0 {
+            self.size_changed = true;
+        }
+        changes_behavior
+    }
+
     fn mark_estimate(&mut self, txn_idx: TxnIndex) {
         let shifted_idx = ShiftedTxnIndex::new(txn_idx);
         let idx_updates = self
@@ -287,6 +339,10 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
             .get(&shifted_idx)
             .expect("Group updates must exist at the index to commit");
         for (tag, v) in idx_updates.iter() {
+            let prev_len = self
+                .committed_group
+                .get(tag)
+                .and_then(|prev| prev.bytes_len());
             match (self.committed_group.entry(tag.clone()), v.write_op_kind()) {
                 (Occupied(entry), Deletion) => {
                     entry.remove();
@@ -310,6 +366,11 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
                     );
                 },
             }
+            let new_len = self
+                .committed_group
+                .get(tag)
+                .and_then(|new| new.bytes_len());
+            update_combined_size(&mut self.committed_size, tag, prev_len, new_len)?;
         }
 
         Ok(())
@@ -319,6 +380,10 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
         self.committed_group.clone().into_iter().collect()
     }
 
+    fn get_committed_tagged_value(&self, tag: &T) -> Option<ValueWithLayout<V>> {
+        self.committed_group.get(tag).cloned()
+    }
+
     fn get_latest_tagged_value(
         &self,
         tag: &T,
@@ -397,6 +462,33 @@ impl<T: Hash + Clone + Debug + Eq + Serialize, V: TransactionWrite> VersionedGro
     }
 }
 
+// Updates a combined group size for the value at tag changing from prev_len to new_len bytes
+// (None if there is no value at tag before / after, respectively).
+fn update_combined_size<T: Serialize + Clone + Debug>(
+    size: &mut ResourceGroupSize,
+    tag: &T,
+    prev_len: Option<usize>,
+    new_len: Option<usize>,
+) -> anyhow::Result<()> {
+    if let ResourceGroupSize::Combined {
+        num_tagged_resources,
+        all_tagged_resources_size,
+    } = size
+    {
+        if let Some(prev_len) = prev_len {
+            *num_tagged_resources -= 1;
+            *all_tagged_resources_size -=
+                group_tagged_resource_size(tag, prev_len).map_err(|e| anyhow!("{e:?}"))?;
+        }
+        if let Some(new_len) = new_len {
+            *num_tagged_resources += 1;
+            *all_tagged_resources_size +=
+                group_tagged_resource_size(tag, new_len).map_err(|e| anyhow!("{e:?}"))?;
+        }
+    }
+    Ok(())
+}
+
 impl<
         K: Hash + Clone + Debug + Eq,
         T: Hash + Clone + Debug + Eq + Serialize,
@@ -451,6 +543,28 @@ impl<
         )
     }
 
+    /// Writes a single tagged value of the group at access path 'key' by transaction 'txn_idx',
+    /// merged with the other values the transaction already wrote to the group, which are kept
+    /// as is (as opposed to write, which replaces all of them). Useful to build the writes of an
+    /// incarnation tag by tag, without collecting or rewriting the whole set. Returns true if the
+    /// size of the value at tag changed (or the tag was not written by the transaction before).
+    pub fn write_tagged(
+        &self,
+        key: K,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        tag: T,
+        value: (V, Option<Arc<MoveTypeLayout>>),
+    ) -> bool {
+        let (v, layout) = value;
+        self.group_values.entry(key).or_default().write_tagged(
+            ShiftedTxnIndex::new(txn_idx),
+            incarnation,
+            tag,
+            ValueWithLayout::Exchanged(Arc::new(v), layout),
+        )
+    }
+
     /// Mark all entry from transaction 'txn_idx' at access path 'key' as an estimated write
     /// (for future incarnation). Will panic if the entry is not in the data-structure.
     pub fn mark_estimate(&self, key: &K, txn_idx: TxnIndex) {
@@ -544,6 +658,21 @@ impl<
         let v = self.group_values.get_mut(key).expect("Path must exist");
         Ok(v.get_committed_group())
     }
+
+    /// Returns the latest committed value at a tag of the group, without cloning the group.
+    pub fn get_last_committed_tagged_value(&self, key: &K, tag: &T) -> Option<ValueWithLayout<V>> {
+        self.group_values
+            .get(key)
+            .and_then(|g| g.get_committed_tagged_value(tag))
+    }
+
+    /// Returns the size of the latest committed group contents, which is tracked as
+    /// the group gets committed rather than computed from its members.
+    pub fn get_last_committed_group_size(&self, key: &K) -> ResourceGroupSize {
+        self.group_values
+            .get(key)
+            .map_or(ResourceGroupSize::zero_combined(), |g| g.committed_size)
+    }
 }
 
 #[cfg(test)]
@@ -953,6 +1082,92 @@ mod test {
         assert!(map.group_values.get(&ap_3).unwrap().size_changed);
     }
 
+    #[test]
+    fn group_write_tagged() {
+        let ap = KeyType(b"/foo/g".to_vec());
+        let map = VersionedGroupData::<KeyType<Vec<u8>>, usize, TestValue>::new();
+
+        map.set_raw_base_values(ap.clone(), vec![(0, TestValue::creation_with_len(1))]);
+        map.write(
+            ap.clone(),
+            3,
+            0,
+            // tags 1, 2
+            (1..3).map(|i| (i, (TestValue::creation_with_len(2), None))),
+        );
+
+        // A new tag, and a tag previously written with the same size.
+        assert!(map.write_tagged(ap.clone(), 3, 0, 3, (TestValue::creation_with_len(3), None)));
+        assert!(!map.write_tagged(ap.clone(), 3, 0, 1, (TestValue::creation_with_len(2), None)));
+        assert!(!map.group_values.get(&ap).unwrap().size_changed);
+        // Other tags written by the transaction are kept.
+        for (tag, len) in [(1, 2), (2, 2), (3, 3)] {
+            assert_eq!(
+                map.fetch_tagged_data(&ap, &tag, 4).unwrap(),
+                (
+                    Ok((3, 0)),
+                    ValueWithLayout::Exchanged(Arc::new(TestValue::creation_with_len(len)), None)
+                )
+            );
+        }
+
+        // Size change by a later incarnation.
+        assert!(map.write_tagged(ap.clone(), 3, 1, 2, (TestValue::creation_with_len(4), None)));
+        assert!(map.group_values.get(&ap).unwrap().size_changed);
+        assert_eq!(
+            map.fetch_tagged_data(&ap, &2, 4).unwrap(),
+            (
+                Ok((3, 1)),
+                ValueWithLayout::Exchanged(Arc::new(TestValue::creation_with_len(4)), None)
+            )
+        );
+    }
+
+    #[test]
+    fn group_committed_size() {
+        let ap = KeyType(b"/foo/h".to_vec());
+        let map = VersionedGroupData::<KeyType<Vec<u8>>, usize, TestValue>::new();
+        let expected_size = |map: &VersionedGroupData<KeyType<Vec<u8>>, usize, TestValue>| {
+            group_size_as_sum(
+                map.get_last_committed_group(&ap)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(tag, v)| (tag, v.bytes_len().unwrap())),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            map.get_last_committed_group_size(&ap),
+            ResourceGroupSize::zero_combined()
+        );
+        map.set_raw_base_values(
+            ap.clone(),
+            (0..3).map(|i| (i, TestValue::creation_with_len(i + 1))),
+        );
+        assert_eq!(map.get_last_committed_group_size(&ap), expected_size(&map));
+
+        map.write(ap.clone(), 2, 0, vec![
+            (0, (TestValue::deletion(), None)),
+            (1, (TestValue::modification_with_len(10), None)),
+            (3, (TestValue::creation_with_len(5), None)),
+        ]);
+        let _ = map.finalize_group(&ap, 2).unwrap();
+        assert_eq!(map.get_last_committed_group_size(&ap), expected_size(&map));
+        assert_matches!(
+            map.get_last_committed_group_size(&ap),
+            ResourceGroupSize::Combined {
+                num_tagged_resources: 3,
+                ..
+            }
+        );
+        assert_none!(map.get_last_committed_tagged_value(&ap, &0));
+        assert_some_eq!(
+            map.get_last_committed_tagged_value(&ap, &1),
+            ValueWithLayout::Exchanged(Arc::new(TestValue::modification_with_len(10)), None)
+        );
+    }
+
     fn finalize_group_as_hashmap(
         map: &VersionedGroupData<KeyType<Vec<u8>>, usize, TestValue>,
         key: &KeyType<Vec<u8>>,
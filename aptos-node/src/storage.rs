@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
-use aptos_backup_service::start_backup_service;
+use aptos_backup_service::{start_backup_service, start_grpc_backup_service};
 use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
 use aptos_db::{fast_sync_storage_wrapper::FastSyncStorageWrapper, AptosDB};
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
@@ -55,6 +55,7 @@ pub(crate) fn bootstrap_db(
                     node_config.storage.backup_service_address,
                     db_arc.clone(),
                 );
+                maybe_start_grpc_backup_service(node_config, db_arc.clone(), &db_backup_service);
                 maybe_apply_genesis(&db_rw, node_config)?;
                 (db_arc as Arc<dyn DbReader>, db_rw, Some(db_backup_service))
             },
@@ -79,14 +80,24 @@ pub(crate) fn bootstrap_db(
                     fast_sync_db.commit_genesis_ledger_info(&ledger_info)?;
                 }
 
-                let db_backup_service =
-                    start_backup_service(node_config.storage.backup_service_address, fast_sync_db);
+                let db_backup_service = start_backup_service(
+                    node_config.storage.backup_service_address,
+                    fast_sync_db.clone(),
+                );
+                maybe_start_grpc_backup_service(node_config, fast_sync_db, &db_backup_service);
                 (db_arc as Arc<dyn DbReader>, db_rw, Some(db_backup_service))
             },
         };
     Ok((aptos_db_reader, db_rw, backup_service, internal_indexer_db))
 }
 
+#[cfg(not(feature = "consensus-only-perf-test"))]
+fn maybe_start_grpc_backup_service(node_config: &NodeConfig, db: Arc<AptosDB>, runtime: &Runtime) {
+    if let Some(address) = node_config.storage.backup_grpc_service_address {
+        start_grpc_backup_service(address, db, runtime);
+    }
+}
+
 /// In consensus-only mode, return a in-memory based [FakeAptosDB] and
 /// do not run the backup service.
 #[cfg(feature = "consensus-only-perf-test")]
@@ -57,6 +57,10 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     };
     AptosVM::set_concurrency_level_once(effective_concurrency_level as usize);
     AptosVM::set_discard_failed_blocks(node_config.execution.discard_failed_blocks);
+    AptosVM::set_versioned_data_memory_budget(node_config.execution.versioned_data_memory_budget);
+    AptosVM::set_dependency_wait_strategy(node_config.execution.dependency_wait_strategy);
+    AptosVM::set_auto_select_execution_mode(node_config.execution.auto_select_execution_mode);
+    AptosVM::set_prefetch_predicted_reads(node_config.execution.prefetch_predicted_reads);
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
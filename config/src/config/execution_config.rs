@@ -8,7 +8,10 @@ use crate::config::{
     node_config_loader::NodeType, transaction_filter_type::Filter, utils::RootPath, Error,
     NodeConfig,
 };
-use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    block_executor::config::DependencyWaitStrategy, chain_id::ChainId, transaction::Transaction,
+    waypoint::Waypoint,
+};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 use std::{
@@ -46,6 +49,18 @@ pub struct ExecutionConfig {
     pub paranoid_type_verification: bool,
     /// Enabled discarding blocks that fail execution due to BlockSTM/VM issue.
     pub discard_failed_blocks: bool,
+    /// If set, bounds the memory (in bytes) held for the writes of a block in parallel execution,
+    /// by evicting the committed versions that can no longer be read
+    pub versioned_data_memory_budget: Option<u64>,
+    /// How transactions wait for the resolution of a dependency in parallel execution,
+    /// trading latency for CPU usage
+    pub dependency_wait_strategy: DependencyWaitStrategy,
+    /// Enables executing tiny or highly-conflicted blocks sequentially, to avoid the overhead
+    /// of parallel execution where it does not pay off
+    pub auto_select_execution_mode: bool,
+    /// Enables prefetching, before parallel execution of a block, the base values of the keys
+    /// its transactions are predicted to read (the accounts of the senders)
+    pub prefetch_predicted_reads: bool,
     /// Enables paranoid mode for hot potatoes, which adds extra runtime VM checks
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
@@ -83,6 +98,10 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             discard_failed_blocks: false,
+            versioned_data_memory_budget: None,
+            dependency_wait_strategy: DependencyWaitStrategy::default(),
+            auto_select_execution_mode: false,
+            prefetch_predicted_reads: false,
             processed_transactions_detailed_counters: false,
             transaction_filter: Filter::empty(),
             genesis_waypoint: None,
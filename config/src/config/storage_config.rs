@@ -170,6 +170,9 @@ impl Default for RocksdbConfigs {
 #[serde(default, deny_unknown_fields)]
 pub struct StorageConfig {
     pub backup_service_address: SocketAddr,
+    /// If set, the backup service is also served over gRPC at this address, streaming chunks along
+    /// with their proofs.
+    pub backup_grpc_service_address: Option<SocketAddr>,
     /// Top level directory to store the RocksDB
     pub dir: PathBuf,
     /// Storage pruning configuration
@@ -205,16 +208,19 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         prune_window: 0,
         batch_size: 0,
         user_pruning_window_offset: 0,
+        dry_run: false,
     },
     state_merkle_pruner_config: StateMerklePrunerConfig {
         enable: false,
         prune_window: 0,
         batch_size: 0,
+        dry_run: false,
     },
     epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig {
         enable: false,
         prune_window: 0,
         batch_size: 0,
+        dry_run: false,
     },
 };
 
@@ -234,6 +240,9 @@ pub struct LedgerPrunerConfig {
     pub batch_size: usize,
     /// The offset for user pruning window to adjust
     pub user_pruning_window_offset: u64,
+    /// If true, the pruner deletes nothing and only reports (via logs and metrics) how much it
+    /// would have pruned. The state KV pruner, which follows this config, is then disabled.
+    pub dry_run: bool,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -246,6 +255,9 @@ pub struct StateMerklePrunerConfig {
     pub prune_window: u64,
     /// Number of stale nodes to prune a time.
     pub batch_size: usize,
+    /// If true, the pruner deletes nothing and only reports (via logs and metrics) how much it
+    /// would have pruned.
+    pub dry_run: bool,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -257,6 +269,9 @@ pub struct EpochSnapshotPrunerConfig {
     pub prune_window: u64,
     /// Number of stale nodes to prune a time.
     pub batch_size: usize,
+    /// If true, the pruner deletes nothing and only reports (via logs and metrics) how much it
+    /// would have pruned.
+    pub dry_run: bool,
 }
 
 // Config for the epoch ending state pruner is actually in the same format as the state merkle
@@ -268,6 +283,7 @@ impl From<EpochSnapshotPrunerConfig> for StateMerklePrunerConfig {
             enable: config.enable,
             prune_window: config.prune_window,
             batch_size: config.batch_size,
+            dry_run: config.dry_run,
         }
     }
 }
@@ -289,6 +305,7 @@ impl Default for LedgerPrunerConfig {
             prune_window: 150_000_000,
             batch_size: 5_000,
             user_pruning_window_offset: 200_000,
+            dry_run: false,
         }
     }
 }
@@ -306,6 +323,7 @@ impl Default for StateMerklePrunerConfig {
             // A 10k transaction block (touching 60k state values, in the case of the account
             // creation benchmark) on a 4B items DB (or 1.33B accounts) yields 300k JMT nodes
             batch_size: 1_000,
+            dry_run: false,
         }
     }
 }
@@ -324,6 +342,7 @@ impl Default for EpochSnapshotPrunerConfig {
             // A 10k transaction block (touching 60k state values, in the case of the account
             // creation benchmark) on a 4B items DB (or 1.33B accounts) yields 300k JMT nodes
             batch_size: 1_000,
+            dry_run: false,
         }
     }
 }
@@ -332,6 +351,7 @@ impl Default for StorageConfig {
     fn default() -> StorageConfig {
         StorageConfig {
             backup_service_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 6186),
+            backup_grpc_service_address: None,
             dir: PathBuf::from("db"),
             // The prune window must at least out live a RPC request because its sub requests are
             // to return a consistent view of the DB at exactly same version. Considering a few
@@ -394,6 +414,9 @@ impl StorageConfig {
     pub fn randomize_ports(&mut self) {
         self.backup_service_address
             .set_port(utils::get_available_port());
+        if let Some(address) = self.backup_grpc_service_address.as_mut() {
+            address.set_port(utils::get_available_port());
+        }
     }
 }
 
@@ -3,7 +3,7 @@
 
 use crate::{
     utils::{convert_svg_to_string, create_file_with_parents},
-    CpuProfilerConfig, Profiler,
+    CpuProfilerConfig, Profiler, ThreadGroup,
 };
 use anyhow::Result;
 use pprof::ProfilerGuard;
@@ -13,6 +13,7 @@ use std::{path::PathBuf, thread, time};
 pub struct CpuProfiler<'a> {
     frequency: i32,
     svg_result_path: PathBuf,
+    thread_groups: Vec<ThreadGroup>,
     guard: Option<ProfilerGuard<'a>>,
 }
 
@@ -21,6 +22,7 @@ impl<'a> CpuProfiler<'a> {
         Self {
             frequency: config.frequency,
             svg_result_path: config.svg_result_path.clone(),
+            thread_groups: config.thread_groups.clone(),
             guard: None,
         }
     }
@@ -70,13 +72,31 @@ impl Profiler for CpuProfiler<'_> {
     /// End profiling
     fn end_profiling(&mut self, _binary_path: &str) -> Result<()> {
         if let Some(guard) = self.guard.take() {
-            if let Ok(report) = guard
-                .report()
-                .frames_post_processor(Self::frames_post_processor())
-                .build()
-            {
-                let file = create_file_with_parents(self.svg_result_path.as_path())?;
-                let _result = report.flamegraph(file);
+            if self.thread_groups.is_empty() {
+                if let Ok(report) = guard
+                    .report()
+                    .frames_post_processor(Self::frames_post_processor())
+                    .build()
+                {
+                    let file = create_file_with_parents(self.svg_result_path.as_path())?;
+                    let _result = report.flamegraph(file);
+                }
+            }
+            for group in &self.thread_groups {
+                if let Ok(mut report) = guard
+                    .report()
+                    .frames_post_processor(Self::frames_post_processor())
+                    .build()
+                {
+                    report.data.retain(|frames, _| {
+                        group
+                            .thread_name_prefixes
+                            .iter()
+                            .any(|prefix| frames.thread_name.starts_with(prefix))
+                    });
+                    let file = create_file_with_parents(group.svg_result_path.as_path())?;
+                    let _result = report.flamegraph(file);
+                }
             }
             self.destory_guard()?;
         }
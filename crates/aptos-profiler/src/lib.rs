@@ -22,12 +22,34 @@ impl ProfilerConfig {
             mem_profiler_config: MemProfilerConfig::new_with_defaults(),
         }
     }
+
+    /// CPU profiling only, writing a flamegraph of each of `thread_groups` instead of one of the
+    /// whole process.
+    pub fn new_with_cpu_thread_groups(thread_groups: Vec<ThreadGroup>) -> Self {
+        Self {
+            cpu_profiler_config: CpuProfilerConfig::new_with_defaults().map(|config| {
+                CpuProfilerConfig {
+                    thread_groups,
+                    ..config
+                }
+            }),
+            mem_profiler_config: None,
+        }
+    }
+}
+
+/// Threads to write a CPU flamegraph of, by thread name prefix.
+#[derive(Debug, Clone)]
+pub struct ThreadGroup {
+    pub thread_name_prefixes: Vec<String>,
+    pub svg_result_path: PathBuf,
 }
 
 #[derive(Debug, Clone)]
 struct CpuProfilerConfig {
     frequency: i32,
     svg_result_path: PathBuf,
+    thread_groups: Vec<ThreadGroup>,
 }
 
 impl CpuProfilerConfig {
@@ -35,6 +57,7 @@ impl CpuProfilerConfig {
         Some(Self {
             frequency: 100,
             svg_result_path: PathBuf::from("./profiling_results/cpu_flamegraph.svg"),
+            thread_groups: vec![],
         })
     }
 }
@@ -25,7 +25,7 @@ use aptos_sdk::{
     types::{transaction::SignedTransaction, AccountKey, LocalAccount},
 };
 use aptos_transaction_generator_lib::{
-    create_txn_generator_creator, AccountType, TransactionType, SEND_AMOUNT,
+    create_txn_generator_creator, AccountType, RngSource, TransactionType, SEND_AMOUNT,
 };
 use aptos_types::account_config::aptos_test_root_address;
 use futures::future::{try_join_all, FutureExt};
@@ -768,6 +768,7 @@ impl TxnEmitter {
             &txn_factory,
             &init_txn_factory,
             stats.get_cur_phase_obj(),
+            RngSource::default(),
         )
         .await;
 
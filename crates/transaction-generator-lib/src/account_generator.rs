@@ -1,14 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    create_account_transaction, ObjectPool, TransactionGenerator, TransactionGeneratorCreator,
+    create_account_transaction, ObjectPool, RngSource, TransactionGenerator,
+    TransactionGeneratorCreator,
 };
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::TransactionFactory,
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use rand::{rngs::StdRng, SeedableRng};
+use rand::rngs::StdRng;
 use std::sync::Arc;
 
 pub struct AccountGenerator {
@@ -84,6 +85,7 @@ pub struct AccountGeneratorCreator {
     accounts_pool: Option<Arc<ObjectPool<LocalAccount>>>,
     max_working_set: usize,
     creation_balance: u64,
+    rng_source: RngSource,
 }
 
 impl AccountGeneratorCreator {
@@ -93,6 +95,7 @@ impl AccountGeneratorCreator {
         accounts_pool: Option<Arc<ObjectPool<LocalAccount>>>,
         max_working_set: usize,
         creation_balance: u64,
+        rng_source: RngSource,
     ) -> Self {
         Self {
             txn_factory,
@@ -100,6 +103,7 @@ impl AccountGeneratorCreator {
             accounts_pool,
             max_working_set,
             creation_balance,
+            rng_source,
         }
     }
 }
@@ -107,7 +111,7 @@ impl AccountGeneratorCreator {
 impl TransactionGeneratorCreator for AccountGeneratorCreator {
     fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
         Box::new(AccountGenerator::new(
-            StdRng::from_entropy(),
+            self.rng_source.next_rng(),
             self.txn_factory.clone(),
             self.addresses_pool.clone(),
             self.accounts_pool.clone(),
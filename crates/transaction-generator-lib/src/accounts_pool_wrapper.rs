@@ -1,9 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ObjectPool, TransactionGenerator, TransactionGeneratorCreator};
+use crate::{ObjectPool, RngSource, TransactionGenerator, TransactionGeneratorCreator};
 use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
-use rand::{rngs::StdRng, SeedableRng};
+use rand::rngs::StdRng;
 use std::sync::Arc;
 
 /// Wrapper that allows inner transaction generator to have unique accounts
@@ -61,6 +61,7 @@ pub struct AccountsPoolWrapperCreator {
     creator: Box<dyn TransactionGeneratorCreator>,
     source_accounts_pool: Arc<ObjectPool<LocalAccount>>,
     destination_accounts_pool: Option<Arc<ObjectPool<LocalAccount>>>,
+    rng_source: RngSource,
 }
 
 impl AccountsPoolWrapperCreator {
@@ -68,11 +69,13 @@ impl AccountsPoolWrapperCreator {
         creator: Box<dyn TransactionGeneratorCreator>,
         source_accounts_pool: Arc<ObjectPool<LocalAccount>>,
         destination_accounts_pool: Option<Arc<ObjectPool<LocalAccount>>>,
+        rng_source: RngSource,
     ) -> Self {
         Self {
             creator,
             source_accounts_pool,
             destination_accounts_pool,
+            rng_source,
         }
     }
 }
@@ -80,7 +83,7 @@ impl AccountsPoolWrapperCreator {
 impl TransactionGeneratorCreator for AccountsPoolWrapperCreator {
     fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
         Box::new(AccountsPoolWrapperGenerator::new(
-            StdRng::from_entropy(),
+            self.rng_source.next_rng(),
             self.creator.create_transaction_generator(),
             self.source_accounts_pool.clone(),
             self.destination_accounts_pool.clone(),
@@ -1,13 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ObjectPool, TransactionGenerator, TransactionGeneratorCreator};
+use crate::{ObjectPool, RngSource, TransactionGenerator, TransactionGeneratorCreator};
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use rand::{rngs::StdRng, SeedableRng};
+use rand::rngs::StdRng;
 use std::sync::Arc;
 
 pub struct BatchTransferTransactionGenerator {
@@ -66,6 +66,7 @@ pub struct BatchTransferTransactionGeneratorCreator {
     amount: u64,
     all_addresses: Arc<ObjectPool<AccountAddress>>,
     batch_size: usize,
+    rng_source: RngSource,
 }
 
 impl BatchTransferTransactionGeneratorCreator {
@@ -74,12 +75,14 @@ impl BatchTransferTransactionGeneratorCreator {
         amount: u64,
         all_addresses: Arc<ObjectPool<AccountAddress>>,
         batch_size: usize,
+        rng_source: RngSource,
     ) -> Self {
         Self {
             txn_factory,
             amount,
             all_addresses,
             batch_size,
+            rng_source,
         }
     }
 }
@@ -87,7 +90,7 @@ impl BatchTransferTransactionGeneratorCreator {
 impl TransactionGeneratorCreator for BatchTransferTransactionGeneratorCreator {
     fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
         Box::new(BatchTransferTransactionGenerator::new(
-            StdRng::from_entropy(),
+            self.rng_source.next_rng(),
             self.batch_size,
             self.amount,
             self.txn_factory.clone(),
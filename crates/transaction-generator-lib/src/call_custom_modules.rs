@@ -3,8 +3,8 @@
 
 use super::{publishing::publish_util::Package, ReliableTransactionSubmitter};
 use crate::{
-    create_account_transaction, publishing::publish_util::PackageHandler, RootAccountHandle,
-    TransactionGenerator, TransactionGeneratorCreator,
+    create_account_transaction, publishing::publish_util::PackageHandler, RngSource,
+    RootAccountHandle, TransactionGenerator, TransactionGeneratorCreator,
 };
 use aptos_logger::{error, info};
 use aptos_sdk::{
@@ -12,7 +12,7 @@ use aptos_sdk::{
     types::{transaction::SignedTransaction, LocalAccount},
 };
 use async_trait::async_trait;
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rand::{rngs::StdRng, seq::SliceRandom};
 use std::{borrow::Borrow, sync::Arc};
 
 // Fn + Send + Sync, as it will be called from multiple threads simultaneously
@@ -61,6 +61,7 @@ pub struct CustomModulesDelegationGenerator {
     txn_factory: TransactionFactory,
     packages: Arc<Vec<(Package, LocalAccount)>>,
     txn_generator: Arc<TransactionGeneratorWorker>,
+    rng_source: RngSource,
 }
 
 impl CustomModulesDelegationGenerator {
@@ -116,11 +117,13 @@ impl CustomModulesDelegationGeneratorCreator {
         txn_factory: TransactionFactory,
         packages: Arc<Vec<(Package, LocalAccount)>>,
         txn_generator: Arc<TransactionGeneratorWorker>,
+        rng_source: RngSource,
     ) -> Self {
         Self {
             txn_factory,
             packages,
             txn_generator,
+            rng_source,
         }
     }
 
@@ -132,6 +135,7 @@ impl CustomModulesDelegationGeneratorCreator {
         num_modules: usize,
         package_name: &str,
         workload: &mut dyn UserModuleTransactionGenerator,
+        rng_source: RngSource,
     ) -> Self {
         let mut packages = Self::publish_package(
             init_txn_factory.clone(),
@@ -140,6 +144,7 @@ impl CustomModulesDelegationGeneratorCreator {
             num_modules,
             package_name,
             None,
+            &rng_source,
         )
         .await;
         let worker = Self::create_worker(
@@ -148,12 +153,14 @@ impl CustomModulesDelegationGeneratorCreator {
             txn_executor,
             &mut packages,
             workload,
+            &rng_source,
         )
         .await;
         Self {
             txn_factory,
             packages: Arc::new(packages),
             txn_generator: worker,
+            rng_source,
         }
     }
 
@@ -163,8 +170,9 @@ impl CustomModulesDelegationGeneratorCreator {
         txn_executor: &dyn ReliableTransactionSubmitter,
         packages: &mut [(Package, LocalAccount)],
         workload: &mut dyn UserModuleTransactionGenerator,
+        rng_source: &RngSource,
     ) -> Arc<TransactionGeneratorWorker> {
-        let mut rng = StdRng::from_entropy();
+        let mut rng = rng_source.next_rng();
         let mut requests_initialize = Vec::with_capacity(packages.len());
 
         for (package, publisher) in packages.iter_mut() {
@@ -201,8 +209,9 @@ impl CustomModulesDelegationGeneratorCreator {
         num_modules: usize,
         package_name: &str,
         publisher_balance: Option<u64>,
+        rng_source: &RngSource,
     ) -> Vec<(Package, LocalAccount)> {
-        let mut rng = StdRng::from_entropy();
+        let mut rng = rng_source.next_rng();
         let mut requests_create = Vec::with_capacity(num_modules);
         let mut requests_publish = Vec::with_capacity(num_modules);
         let mut package_handler = PackageHandler::new(package_name);
@@ -269,7 +278,7 @@ impl CustomModulesDelegationGeneratorCreator {
 impl TransactionGeneratorCreator for CustomModulesDelegationGeneratorCreator {
     fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
         Box::new(CustomModulesDelegationGenerator::new(
-            StdRng::from_entropy(),
+            self.rng_source.next_rng(),
             self.txn_factory.clone(),
             self.packages.clone(),
             self.txn_generator.clone(),
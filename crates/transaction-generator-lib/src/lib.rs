@@ -4,7 +4,7 @@
 #![forbid(unsafe_code)]
 
 use anyhow::Result;
-use aptos_infallible::{RwLock, RwLockWriteGuard};
+use aptos_infallible::{Mutex, RwLock, RwLockWriteGuard};
 use aptos_logger::{info, sample, sample::SampleRate, warn};
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
@@ -14,7 +14,7 @@ use aptos_sdk::{
 use args::TransactionTypeArg;
 use async_trait::async_trait;
 use clap::{Parser, ValueEnum};
-use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -234,6 +234,30 @@ impl RootAccountHandle for AlwaysApproveRootAccountHandle {
     }
 }
 
+/// Hands out the RNGs of the generators. Seeded, it hands out the same RNGs in the same order on
+/// every run, so the generated transactions are the same as long as the generators are created,
+/// and called, in the same order.
+#[derive(Clone, Default)]
+pub struct RngSource {
+    seeded: Option<Arc<Mutex<StdRng>>>,
+}
+
+impl RngSource {
+    /// From entropy without a `seed`.
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            seeded: seed.map(|seed| Arc::new(Mutex::new(StdRng::seed_from_u64(seed)))),
+        }
+    }
+
+    pub fn next_rng(&self) -> StdRng {
+        match &self.seeded {
+            Some(rng) => StdRng::seed_from_u64(rng.lock().next_u64()),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
 pub async fn create_txn_generator_creator(
     transaction_mix_per_phase: &[Vec<(TransactionType, usize)>],
     root_account: impl RootAccountHandle,
@@ -243,6 +267,7 @@ pub async fn create_txn_generator_creator(
     txn_factory: &TransactionFactory,
     init_txn_factory: &TransactionFactory,
     cur_phase: Arc<AtomicUsize>,
+    rng_source: RngSource,
 ) -> (
     Box<dyn TransactionGeneratorCreator>,
     Arc<ObjectPool<AccountAddress>>,
@@ -265,12 +290,14 @@ pub async fn create_txn_generator_creator(
         inner: Box<dyn TransactionGeneratorCreator>,
         use_account_pool: bool,
         accounts_pool: &Arc<ObjectPool<LocalAccount>>,
+        rng_source: &RngSource,
     ) -> Box<dyn TransactionGeneratorCreator> {
         if use_account_pool {
             Box::new(AccountsPoolWrapperCreator::new(
                 inner,
                 accounts_pool.clone(),
                 None,
+                rng_source.clone(),
             ))
         } else {
             inner
@@ -293,9 +320,11 @@ pub async fn create_txn_generator_creator(
                         addresses_pool.clone(),
                         *invalid_transaction_ratio,
                         SamplingMode::BurnAndRecycle(addresses_pool.len() / 2),
+                        rng_source.clone(),
                     )),
                     *sender_use_account_pool,
                     &accounts_pool,
+                    &rng_source,
                 ),
                 TransactionType::CoinTransfer {
                     invalid_transaction_ratio,
@@ -307,9 +336,11 @@ pub async fn create_txn_generator_creator(
                         addresses_pool.clone(),
                         *invalid_transaction_ratio,
                         SamplingMode::Basic,
+                        rng_source.clone(),
                     )),
                     *sender_use_account_pool,
                     &accounts_pool,
+                    &rng_source,
                 ),
                 TransactionType::AccountGeneration {
                     add_created_accounts_to_pool,
@@ -327,11 +358,16 @@ pub async fn create_txn_generator_creator(
                     }),
                     *max_account_working_set,
                     *creation_balance,
+                    rng_source.clone(),
                 )),
                 TransactionType::PublishPackage { use_account_pool } => wrap_accounts_pool(
-                    Box::new(PublishPackageCreator::new(txn_factory.clone())),
+                    Box::new(PublishPackageCreator::new(
+                        txn_factory.clone(),
+                        rng_source.clone(),
+                    )),
                     *use_account_pool,
                     &accounts_pool,
+                    &rng_source,
                 ),
                 TransactionType::CallCustomModules {
                     entry_point,
@@ -349,11 +385,13 @@ pub async fn create_txn_generator_creator(
                             &mut EntryPointTransactionGenerator {
                                 entry_point: *entry_point,
                             },
+                            rng_source.clone(),
                         )
                         .await,
                     ),
                     *use_account_pool,
                     &accounts_pool,
+                    &rng_source,
                 ),
                 TransactionType::BatchTransfer { batch_size } => {
                     Box::new(BatchTransferTransactionGeneratorCreator::new(
@@ -361,6 +399,7 @@ pub async fn create_txn_generator_creator(
                         SEND_AMOUNT,
                         addresses_pool.clone(),
                         *batch_size,
+                        rng_source.clone(),
                     ))
                 },
                 TransactionType::Workflow {
@@ -379,6 +418,7 @@ pub async fn create_txn_generator_creator(
                         use_account_pool.then(|| accounts_pool.clone()),
                         cur_phase.clone(),
                         *progress_type,
+                        rng_source.clone(),
                     )
                     .await,
                 ),
@@ -392,6 +432,7 @@ pub async fn create_txn_generator_creator(
         Box::new(PhasedTxnMixGeneratorCreator::new(
             txn_generator_creator_mix_per_phase,
             cur_phase,
+            rng_source,
         )),
         addresses_pool,
         accounts_pool,
@@ -1,6 +1,6 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{ObjectPool, TransactionGenerator, TransactionGeneratorCreator};
+use crate::{ObjectPool, RngSource, TransactionGenerator, TransactionGeneratorCreator};
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
@@ -10,7 +10,7 @@ use rand::{
     distributions::{Distribution, Standard},
     prelude::SliceRandom,
     rngs::StdRng,
-    Rng, RngCore, SeedableRng,
+    Rng, RngCore,
 };
 use std::{
     cmp::{max, min},
@@ -118,6 +118,7 @@ impl<T: Clone + Send + Sync> Sampler<T> for BurnAndRecycleSampler<T> {
 
 #[test]
 fn test_burn_and_recycle_sampler() {
+    use rand::SeedableRng;
     use std::collections::HashSet;
     let mut rng = StdRng::from_entropy();
     let mut sampler = BurnAndRecycleSampler::new(3);
@@ -298,6 +299,7 @@ pub struct P2PTransactionGeneratorCreator {
     all_addresses: Arc<ObjectPool<AccountAddress>>,
     invalid_transaction_ratio: usize,
     sampling_mode: SamplingMode,
+    rng_source: RngSource,
 }
 
 impl P2PTransactionGeneratorCreator {
@@ -307,8 +309,9 @@ impl P2PTransactionGeneratorCreator {
         all_addresses: Arc<ObjectPool<AccountAddress>>,
         invalid_transaction_ratio: usize,
         sampling_mode: SamplingMode,
+        rng_source: RngSource,
     ) -> Self {
-        let mut rng = StdRng::from_entropy();
+        let mut rng = rng_source.next_rng();
         all_addresses.shuffle(&mut rng);
 
         Self {
@@ -317,13 +320,14 @@ impl P2PTransactionGeneratorCreator {
             all_addresses,
             invalid_transaction_ratio,
             sampling_mode,
+            rng_source,
         }
     }
 }
 
 impl TransactionGeneratorCreator for P2PTransactionGeneratorCreator {
     fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
-        let rng = StdRng::from_entropy();
+        let rng = self.rng_source.next_rng();
         let sampler: Box<dyn Sampler<AccountAddress>> = match self.sampling_mode {
             SamplingMode::Basic => Box::new(BasicSampler::new()),
             SamplingMode::BurnAndRecycle(recycle_batch_size) => {
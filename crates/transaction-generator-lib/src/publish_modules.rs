@@ -1,14 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    publishing::publish_util::PackageHandler, TransactionGenerator, TransactionGeneratorCreator,
+    publishing::publish_util::PackageHandler, RngSource, TransactionGenerator,
+    TransactionGeneratorCreator,
 };
 use aptos_infallible::RwLock;
 use aptos_sdk::{
     transaction_builder::TransactionFactory,
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use rand::{rngs::StdRng, SeedableRng};
+use rand::rngs::StdRng;
 use std::sync::Arc;
 
 pub struct PublishPackageGenerator {
@@ -69,13 +70,15 @@ impl TransactionGenerator for PublishPackageGenerator {
 pub struct PublishPackageCreator {
     txn_factory: TransactionFactory,
     package_handler: Arc<RwLock<PackageHandler>>,
+    rng_source: RngSource,
 }
 
 impl PublishPackageCreator {
-    pub fn new(txn_factory: TransactionFactory) -> Self {
+    pub fn new(txn_factory: TransactionFactory, rng_source: RngSource) -> Self {
         Self {
             txn_factory,
             package_handler: Arc::new(RwLock::new(PackageHandler::new("simple"))),
+            rng_source,
         }
     }
 }
@@ -83,7 +86,7 @@ impl PublishPackageCreator {
 impl TransactionGeneratorCreator for PublishPackageCreator {
     fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
         Box::new(PublishPackageGenerator::new(
-            StdRng::from_entropy(),
+            self.rng_source.next_rng(),
             self.package_handler.clone(),
             self.txn_factory.clone(),
         ))
@@ -1,8 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use crate::{RngSource, TransactionGenerator, TransactionGeneratorCreator};
 use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, Rng};
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
@@ -65,16 +65,19 @@ impl TransactionGenerator for PhasedTxnMixGenerator {
 pub struct PhasedTxnMixGeneratorCreator {
     txn_mix_per_phase_creators: Vec<Vec<(Box<dyn TransactionGeneratorCreator>, usize)>>,
     phase: Arc<AtomicUsize>,
+    rng_source: RngSource,
 }
 
 impl PhasedTxnMixGeneratorCreator {
     pub fn new(
         txn_mix_per_phase_creators: Vec<Vec<(Box<dyn TransactionGeneratorCreator>, usize)>>,
         phase: Arc<AtomicUsize>,
+        rng_source: RngSource,
     ) -> Self {
         Self {
             txn_mix_per_phase_creators,
             phase,
+            rng_source,
         }
     }
 }
@@ -91,7 +94,7 @@ impl TransactionGeneratorCreator for PhasedTxnMixGeneratorCreator {
         }
 
         Box::new(PhasedTxnMixGenerator::new(
-            StdRng::from_entropy(),
+            self.rng_source.next_rng(),
             txn_mix_per_phase,
             self.phase.clone(),
         ))
@@ -5,7 +5,7 @@ use crate::{
     account_generator::AccountGeneratorCreator, accounts_pool_wrapper::AccountsPoolWrapperCreator,
     call_custom_modules::CustomModulesDelegationGeneratorCreator,
     entry_points::EntryPointTransactionGenerator, EntryPoints, ObjectPool,
-    ReliableTransactionSubmitter, RootAccountHandle, TransactionGenerator,
+    ReliableTransactionSubmitter, RngSource, RootAccountHandle, TransactionGenerator,
     TransactionGeneratorCreator, WorkflowKind, WorkflowProgress,
 };
 use aptos_logger::{info, sample, sample::SampleRate};
@@ -235,6 +235,7 @@ impl WorkflowTxnGeneratorCreator {
         _initial_account_pool: Option<Arc<ObjectPool<LocalAccount>>>,
         cur_phase: Arc<AtomicUsize>,
         progress_type: WorkflowProgress,
+        rng_source: RngSource,
     ) -> Self {
         assert_eq!(num_modules, 1, "Only one module is supported for now");
 
@@ -274,6 +275,7 @@ impl WorkflowTxnGeneratorCreator {
                     num_modules,
                     mint_entry_point.package_name(),
                     Some(20_00000000),
+                    &rng_source,
                 )
                 .await;
 
@@ -285,6 +287,7 @@ impl WorkflowTxnGeneratorCreator {
                     &mut EntryPointTransactionGenerator {
                         entry_point: mint_entry_point,
                     },
+                    &rng_source,
                 )
                 .await;
                 let burn_worker = CustomModulesDelegationGeneratorCreator::create_worker(
@@ -295,6 +298,7 @@ impl WorkflowTxnGeneratorCreator {
                     &mut EntryPointTransactionGenerator {
                         entry_point: burn_entry_point,
                     },
+                    &rng_source,
                 )
                 .await;
 
@@ -307,24 +311,29 @@ impl WorkflowTxnGeneratorCreator {
                         Some(created_pool.clone()),
                         count,
                         creation_balance,
+                        rng_source.clone(),
                     )),
                     Box::new(AccountsPoolWrapperCreator::new(
                         Box::new(CustomModulesDelegationGeneratorCreator::new_raw(
                             txn_factory.clone(),
                             packages.clone(),
                             mint_worker,
+                            rng_source.clone(),
                         )),
                         created_pool.clone(),
                         Some(minted_pool.clone()),
+                        rng_source.clone(),
                     )),
                     Box::new(AccountsPoolWrapperCreator::new(
                         Box::new(CustomModulesDelegationGeneratorCreator::new_raw(
                             txn_factory.clone(),
                             packages.clone(),
                             burn_worker,
+                            rng_source.clone(),
                         )),
                         minted_pool.clone(),
                         Some(burnt_pool.clone()),
+                        rng_source,
                     )),
                 ];
                 Self::new(
@@ -0,0 +1,106 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, Result};
+use aptos_backup_cli::{
+    backup_types::transaction::manifest::TransactionBackup,
+    metadata::cache::{self, MetadataCacheOpt},
+    storage::BackupStorage,
+    utils::{read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt},
+};
+use aptos_logger::info;
+use aptos_types::{
+    contract_event::ContractEvent,
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
+};
+use std::sync::{mpsc, Arc};
+use tokio::io::BufReader;
+
+/// Reads the transactions from `first_version` on out of a backup, and sends up to `num_blocks`
+/// blocks of them to `block_sender`, split as they were committed. Returns the number of blocks
+/// sent.
+///
+/// The state checkpoints and block epilogues in the backup are left out, as the executor adds
+/// its own. `first_version` has to start a block.
+pub async fn send_blocks_from_backup(
+    storage: Arc<dyn BackupStorage>,
+    metadata_cache_opt: &MetadataCacheOpt,
+    concurrent_downloads: usize,
+    first_version: Version,
+    num_blocks: usize,
+    block_sender: &mpsc::SyncSender<Vec<Transaction>>,
+) -> Result<usize> {
+    let metadata_view =
+        cache::sync_and_load(metadata_cache_opt, storage.clone(), concurrent_downloads).await?;
+    let backups = metadata_view.select_transaction_backups(first_version, Version::MAX)?;
+    ensure!(
+        backups
+            .first()
+            .map_or(false, |backup| backup.first_version <= first_version),
+        "No transaction backup has version {}.",
+        first_version,
+    );
+
+    let mut num_blocks_sent = 0;
+    let mut block = Vec::new();
+    for backup in backups {
+        let manifest: TransactionBackup = storage.load_json_file(&backup.manifest).await?;
+        for chunk in manifest.chunks {
+            if chunk.last_version < first_version {
+                continue;
+            }
+            info!(
+                "Reading transactions {} to {} from the backup.",
+                chunk.first_version, chunk.last_version
+            );
+            let mut file = BufReader::new(storage.open_for_read(&chunk.transactions).await?);
+            let mut version = chunk.first_version;
+            while let Some(record_bytes) = file.read_record_bytes().await? {
+                let (txn, _txn_info, _events, _write_set): (
+                    Transaction,
+                    TransactionInfo,
+                    Vec<ContractEvent>,
+                    WriteSet,
+                ) = bcs::from_bytes(&record_bytes)?;
+                let txn_version = version;
+                version += 1;
+                if txn_version < first_version {
+                    continue;
+                }
+
+                match txn {
+                    Transaction::BlockMetadata(_) | Transaction::BlockMetadataExt(_) => {
+                        if !block.is_empty() {
+                            // Blocks until the pipeline catches up.
+                            block_sender.send(std::mem::take(&mut block))?;
+                            num_blocks_sent += 1;
+                            if num_blocks_sent == num_blocks {
+                                return Ok(num_blocks_sent);
+                            }
+                        }
+                        block.push(txn);
+                    },
+                    Transaction::StateCheckpoint(_) | Transaction::BlockEpilogue(_) => {},
+                    Transaction::GenesisTransaction(_) => {
+                        bail!("Cannot replay the genesis at version {}.", txn_version)
+                    },
+                    Transaction::UserTransaction(_) | Transaction::ValidatorTransaction(_) => {
+                        ensure!(
+                            !block.is_empty(),
+                            "Version {} is in the middle of a block, the DB has to end with a block.",
+                            txn_version,
+                        );
+                        block.push(txn);
+                    },
+                }
+            }
+        }
+    }
+
+    if !block.is_empty() {
+        block_sender.send(block)?;
+        num_blocks_sent += 1;
+    }
+    Ok(num_blocks_sent)
+}
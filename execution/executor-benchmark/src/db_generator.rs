@@ -16,13 +16,19 @@ use aptos_executor::{
     db_bootstrapper::{generate_waypoint, maybe_bootstrap},
 };
 use aptos_storage_interface::DbReaderWriter;
+use aptos_transaction_generator_lib::TransactionType;
 use aptos_types::on_chain_config::Features;
 use aptos_vm::AptosVM;
 use std::{fs, path::Path};
 
+/// Creates the accounts, then, for each of `account_state`, runs that workload with about one
+/// transaction from each account, so they have more state than their coin store, e.g. resource
+/// groups, objects or fungible asset stores.
+#[allow(clippy::too_many_arguments)]
 pub fn create_db_with_accounts<V>(
     num_accounts: usize,
     init_account_balance: u64,
+    account_state: &[TransactionType],
     block_size: usize,
     db_dir: impl AsRef<Path>,
     storage_pruner_config: PrunerConfig,
@@ -58,8 +64,35 @@ pub fn create_db_with_accounts<V>(
         verify_sequence_numbers,
         enable_storage_sharding,
         pipeline_config,
-        init_features,
+        init_features.clone(),
     );
+
+    for transaction_type in account_state {
+        println!("Creating {:?} state for the accounts...", transaction_type);
+        // Runs in place, on the DB as the accounts creation left it.
+        crate::run_benchmark::<V>(
+            block_size,
+            num_accounts.div_ceil(block_size),
+            Some(vec![vec![(*transaction_type, 1)]]),
+            vec![], /* phase_blocks */
+            1,      /* transactions_per_sender */
+            0,      /* connected_tx_grps */
+            false,  /* shuffle_connected_txns */
+            None,   /* hotspot_probability */
+            None,   /* hot_accounts */
+            num_accounts,
+            0, /* num_additional_dst_pool_accounts */
+            &db_dir,
+            &db_dir,
+            true, /* reuse_checkpoint */
+            verify_sequence_numbers,
+            storage_pruner_config,
+            enable_storage_sharding,
+            PipelineConfig::default(),
+            init_features.clone(),
+            None, /* seed */
+        );
+    }
 }
 
 fn bootstrap_with_genesis(
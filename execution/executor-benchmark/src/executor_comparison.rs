@@ -0,0 +1,122 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::results::{write_csv_rows, BenchmarkResults, ResultsOutput};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_storage_interface::DbReader;
+use aptos_types::transaction::{TransactionInfo, Version};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// A field of a transaction info that differs between the two executors.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub version: Version,
+    pub field: String,
+    pub baseline: String,
+    pub candidate: String,
+}
+
+/// The same blocks run by a baseline and a candidate executor, each on its own checkpoint.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonResults {
+    pub baseline: BenchmarkResults,
+    pub candidate: BenchmarkResults,
+    /// Transaction accumulator root hashes after the last block.
+    pub baseline_root_hash: HashValue,
+    pub candidate_root_hash: HashValue,
+    /// At most `MAX_DIVERGENCES`, in version order.
+    pub divergences: Vec<Divergence>,
+}
+
+/// Divergences reported, past the first ones the rest usually follows from them.
+pub const MAX_DIVERGENCES: usize = 100;
+
+impl ComparisonResults {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty() && self.baseline_root_hash == self.candidate_root_hash
+    }
+}
+
+impl ResultsOutput for ComparisonResults {
+    fn write_csv_rows(&self, writer: &mut csv::Writer<Box<dyn Write>>, prefix: &str) -> Result<()> {
+        self.baseline
+            .write_csv_rows(writer, &format!("{}baseline ", prefix))?;
+        self.candidate
+            .write_csv_rows(writer, &format!("{}candidate ", prefix))?;
+        write_csv_rows(
+            writer,
+            &format!("{}comparison", prefix),
+            "",
+            "",
+            &serde_json::json!({ "divergences": self.divergences.len() }),
+        )?;
+        Ok(())
+    }
+}
+
+/// Compares the transaction infos of `num_txns` transactions from `first_version` on.
+pub fn diff_transaction_infos(
+    baseline: &dyn DbReader,
+    candidate: &dyn DbReader,
+    first_version: Version,
+    num_txns: u64,
+) -> Result<Vec<Divergence>> {
+    let mut divergences = Vec::new();
+    let baseline_infos = baseline.get_transaction_info_iterator(first_version, num_txns)?;
+    let candidate_infos = candidate.get_transaction_info_iterator(first_version, num_txns)?;
+    for (version, (baseline_info, candidate_info)) in
+        (first_version..).zip(baseline_infos.zip(candidate_infos))
+    {
+        diff_transaction_info(version, &baseline_info?, &candidate_info?, &mut divergences);
+        if divergences.len() >= MAX_DIVERGENCES {
+            divergences.truncate(MAX_DIVERGENCES);
+            break;
+        }
+    }
+    Ok(divergences)
+}
+
+fn diff_transaction_info(
+    version: Version,
+    baseline: &TransactionInfo,
+    candidate: &TransactionInfo,
+    divergences: &mut Vec<Divergence>,
+) {
+    let mut diff = |field: &str, baseline: String, candidate: String| {
+        if baseline != candidate {
+            divergences.push(Divergence {
+                version,
+                field: field.to_string(),
+                baseline,
+                candidate,
+            });
+        }
+    };
+    diff(
+        "status",
+        format!("{:?}", baseline.status()),
+        format!("{:?}", candidate.status()),
+    );
+    diff(
+        "gas_used",
+        baseline.gas_used().to_string(),
+        candidate.gas_used().to_string(),
+    );
+    diff(
+        "state_change_hash",
+        baseline.state_change_hash().to_hex(),
+        candidate.state_change_hash().to_hex(),
+    );
+    diff(
+        "event_root_hash",
+        baseline.event_root_hash().to_hex(),
+        candidate.event_root_hash().to_hex(),
+    );
+    diff(
+        "state_checkpoint_hash",
+        format!("{:?}", baseline.state_checkpoint_hash()),
+        format!("{:?}", candidate.state_checkpoint_hash()),
+    );
+}
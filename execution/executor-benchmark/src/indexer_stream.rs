@@ -0,0 +1,106 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::results::IndexerStreamResult;
+use aptos_api::context::Context;
+use aptos_config::config::NodeConfig;
+use aptos_indexer_grpc_fullnode::stream_coordinator::IndexerStreamCoordinator;
+use aptos_logger::info;
+use aptos_storage_interface::DbReader;
+use aptos_types::{chain_id::ChainId, transaction::Version};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{runtime::Runtime, task::JoinHandle};
+
+/// Responses buffered between the coordinator and the (discarding) client.
+const TRANSACTION_CHANNEL_SIZE: usize = 35;
+
+/// Streams the transactions as they get committed, the way the indexer-grpc fullnode service does
+/// for its clients, converting them without sending them anywhere.
+pub(crate) struct IndexerStream {
+    runtime: Runtime,
+    join_handle: JoinHandle<u64>,
+    end_version: Arc<AtomicU64>,
+    start_time: Instant,
+}
+
+impl IndexerStream {
+    pub fn start(db: Arc<dyn DbReader>, node_config: NodeConfig, start_version: Version) -> Self {
+        let runtime = aptos_runtimes::spawn_named_runtime("indexer-stream".to_string(), None);
+        let end_version = Arc::new(AtomicU64::new(Version::MAX));
+        let stream_end_version = end_version.clone();
+        let processor_task_count = node_config.indexer_grpc.processor_task_count;
+        let processor_batch_size = node_config.indexer_grpc.processor_batch_size;
+        let output_batch_size = node_config.indexer_grpc.output_batch_size;
+        let join_handle = runtime.spawn(async move {
+            // Not used, nothing is submitted.
+            let (mp_sender, _mp_receiver) = futures::channel::mpsc::channel(1);
+            let context = Arc::new(Context::new(
+                ChainId::test(),
+                db,
+                mp_sender,
+                node_config,
+                None, /* indexer_reader */
+            ));
+            let (sender, mut receiver) = tokio::sync::mpsc::channel(TRANSACTION_CHANNEL_SIZE);
+            tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+            let mut coordinator = IndexerStreamCoordinator::new(
+                context,
+                start_version,
+                processor_task_count,
+                processor_batch_size,
+                output_batch_size,
+                sender,
+            );
+            while coordinator.current_version <= stream_end_version.load(Ordering::Relaxed) {
+                // The coordinator would wait for the next version, which may never come.
+                coordinator
+                    .set_highest_known_version()
+                    .expect("Failed to read the latest ledger info.");
+                if coordinator.current_version > coordinator.highest_known_version {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    continue;
+                }
+                let results = coordinator.process_next_batch().await;
+                match IndexerStreamCoordinator::get_max_batch_version(results) {
+                    Ok(max_version) => coordinator.current_version = max_version + 1,
+                    Err(e) => panic!("Indexer stream failed: {}", e),
+                }
+            }
+            coordinator.current_version - start_version
+        });
+        Self {
+            runtime,
+            join_handle,
+            end_version,
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Waits for the stream to get to `end_version`, the last committed one.
+    pub fn finish(self, end_version: Version) -> IndexerStreamResult {
+        let commit_end_time = Instant::now();
+        self.end_version.store(end_version, Ordering::Relaxed);
+        let num_txns = self
+            .runtime
+            .block_on(self.join_handle)
+            .expect("Indexer stream panicked.");
+        let elapsed = self.start_time.elapsed();
+        let result = IndexerStreamResult {
+            num_txns,
+            elapsed_secs: elapsed.as_secs_f64(),
+            tps: num_txns as f64 / elapsed.as_secs_f64(),
+            lag_secs: commit_end_time.elapsed().as_secs_f64(),
+        };
+        info!(
+            "Indexer stream: {} txns, TPS: {:.0}, {:.1} s behind the commit at the end",
+            result.num_txns, result.tps, result.lag_secs,
+        );
+        result
+    }
+}
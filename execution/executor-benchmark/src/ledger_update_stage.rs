@@ -2,7 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::pipeline::{CommitBlockMessage, LedgerUpdateMessage};
+use crate::{
+    metrics::TIMER,
+    pipeline::{CommitBlockMessage, LedgerUpdateMessage},
+};
 use aptos_executor::block_executor::{BlockExecutor, TransactionBlockExecutor};
 use aptos_executor_types::BlockExecutorTrait;
 use aptos_types::transaction::Version;
@@ -67,6 +70,9 @@ where
                     execution_time,
                     num_txns: output.transactions_to_commit_len(),
                 };
+                let _timer = TIMER
+                    .with_label_values(&["send_commit_block"])
+                    .start_timer();
                 commit_sender.send(msg).unwrap();
             },
             CommitProcessing::ExecuteInline => {
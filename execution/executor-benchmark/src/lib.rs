@@ -3,26 +3,41 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod account_generator;
+pub mod backup_replay;
 pub mod block_preparation;
 pub mod db_access;
 pub mod db_generator;
 mod db_reliable_submitter;
+pub mod executor_comparison;
+mod indexer_stream;
 mod ledger_update_stage;
 mod metrics;
 pub mod native_executor;
+pub mod output_applier;
 pub mod pipeline;
+pub mod results;
+mod stage_profiler;
 pub mod transaction_committer;
 pub mod transaction_executor;
 pub mod transaction_generator;
 
 use crate::{
-    db_access::DbAccessUtil, pipeline::Pipeline, transaction_committer::TransactionCommitter,
-    transaction_executor::TransactionExecutor, transaction_generator::TransactionGenerator,
+    db_access::DbAccessUtil,
+    executor_comparison::{ComparisonResults, Divergence},
+    indexer_stream::IndexerStream,
+    output_applier::OutputApplier,
+    pipeline::Pipeline,
+    results::{BenchmarkResults, OverallResult, SoakReport, SoakResults, SweepResults, SweepRun},
+    transaction_committer::TransactionCommitter,
+    transaction_executor::TransactionExecutor,
+    transaction_generator::{HotAccounts, TransactionGenerator},
 };
+use aptos_backup_cli::{metadata::cache::MetadataCacheOpt, storage::DBToolStorageOpt};
 use aptos_block_executor::counters::{self as block_executor_counters, GasType};
 use aptos_block_partitioner::v2::counters::BLOCK_PARTITIONING_SECONDS;
 use aptos_config::config::{NodeConfig, PrunerConfig};
-use aptos_db::AptosDB;
+use aptos_crypto::HashValue;
+use aptos_db::{common::LEDGER_DB_NAME, metrics::PRUNER_VERSIONS, AptosDB};
 use aptos_executor::{
     block_executor::{BlockExecutor, TransactionBlockExecutor},
     metrics::{
@@ -39,18 +54,19 @@ use aptos_metrics_core::Histogram;
 use aptos_sdk::types::LocalAccount;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReader, DbReaderWriter};
 use aptos_transaction_generator_lib::{
-    create_txn_generator_creator, AlwaysApproveRootAccountHandle, TransactionGeneratorCreator,
+    create_txn_generator_creator, AlwaysApproveRootAccountHandle, RngSource,
+    TransactionGeneratorCreator,
     TransactionType::{self, NonConflictingCoinTransfer},
 };
-use aptos_types::on_chain_config::Features;
+use aptos_types::{on_chain_config::Features, transaction::Transaction};
 use db_reliable_submitter::DbReliableTransactionSubmitter;
 use pipeline::PipelineConfig;
 use std::{
     collections::HashMap,
     fs,
     path::Path,
-    sync::{atomic::AtomicUsize, Arc},
-    time::Instant,
+    sync::{atomic::AtomicUsize, mpsc, Arc},
+    time::{Duration, Instant},
 };
 use tokio::runtime::Runtime;
 
@@ -92,32 +108,56 @@ fn create_checkpoint(
         .expect("db checkpoint creation fails.");
 }
 
-/// Runs the benchmark with given parameters.
+/// With `reuse_checkpoint`, runs on the checkpoint as the previous run left it, if there is one,
+/// instead of creating it anew, which takes a while for large DBs.
+fn create_or_reuse_checkpoint(
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    enable_storage_sharding: bool,
+    reuse_checkpoint: bool,
+) {
+    if reuse_checkpoint && checkpoint_dir.as_ref().join(LEDGER_DB_NAME).exists() {
+        info!(
+            "Reusing the checkpoint at {}.",
+            checkpoint_dir.as_ref().display()
+        );
+    } else {
+        create_checkpoint(source_dir, checkpoint_dir, enable_storage_sharding);
+    }
+}
+
+/// Runs the benchmark with given parameters, returning what was measured.
 #[allow(clippy::too_many_arguments)]
 pub fn run_benchmark<V>(
     block_size: usize,
     num_blocks: usize,
-    transaction_mix: Option<Vec<(TransactionType, usize)>>,
+    transaction_mix_per_phase: Option<Vec<Vec<(TransactionType, usize)>>>,
+    phase_blocks: Vec<usize>,
     mut transactions_per_sender: usize,
     connected_tx_grps: usize,
     shuffle_connected_txns: bool,
     hotspot_probability: Option<f32>,
+    hot_accounts: Option<HotAccounts>,
     num_main_signer_accounts: usize,
     num_additional_dst_pool_accounts: usize,
     source_dir: impl AsRef<Path>,
     checkpoint_dir: impl AsRef<Path>,
+    reuse_checkpoint: bool,
     verify_sequence_numbers: bool,
     pruner_config: PrunerConfig,
     enable_storage_sharding: bool,
     pipeline_config: PipelineConfig,
     init_features: Features,
-) where
+    seed: Option<u64>,
+) -> BenchmarkResults
+where
     V: TransactionBlockExecutor + 'static,
 {
-    create_checkpoint(
+    create_or_reuse_checkpoint(
         source_dir.as_ref(),
         checkpoint_dir.as_ref(),
         enable_storage_sharding,
+        reuse_checkpoint,
     );
     let (mut config, genesis_key) =
         aptos_genesis::test_utils::test_config_with_custom_features(init_features);
@@ -127,7 +167,7 @@ pub fn run_benchmark<V>(
     let (db, executor) = init_db_and_executor::<V>(&config);
     let root_account = TransactionGenerator::read_root_account(genesis_key, &db);
     let root_account = Arc::new(root_account);
-    let transaction_generators = transaction_mix.clone().map(|transaction_mix| {
+    let transaction_generators = transaction_mix_per_phase.clone().map(|transaction_mix_per_phase| {
         let num_existing_accounts = TransactionGenerator::read_meta(&source_dir);
         let num_accounts_to_be_loaded = std::cmp::min(
             num_existing_accounts,
@@ -135,7 +175,7 @@ pub fn run_benchmark<V>(
         );
 
         let mut num_accounts_to_skip = 0;
-        for (transaction_type, _) in &transaction_mix {
+        for (transaction_type, _) in transaction_mix_per_phase.iter().flatten() {
             if let NonConflictingCoinTransfer{..} = transaction_type {
                 // In case of random non-conflicting coin transfer using `P2PTransactionGenerator`,
                 // `3*block_size` addresses is required:
@@ -153,7 +193,7 @@ pub fn run_benchmark<V>(
             accounts_cache.split(num_main_signer_accounts);
 
         let (transaction_generator_creator, phase) = init_workload::<V>(
-            transaction_mix,
+            transaction_mix_per_phase,
             root_account.clone(),
             main_signer_accounts,
             burner_accounts,
@@ -161,6 +201,7 @@ pub fn run_benchmark<V>(
             // Initialization pipeline is temporary, so needs to be fully committed.
             // No discards/aborts allowed during initialization, even if they are allowed later.
             &PipelineConfig::default(),
+            RngSource::new(seed),
         );
         // need to initialize all workers and finish with all transactions before we start the timer:
         ((0..pipeline_config.num_generator_workers).map(|_| transaction_generator_creator.create_transaction_generator()).collect::<Vec<_>>(), phase)
@@ -168,12 +209,20 @@ pub fn run_benchmark<V>(
 
     let version = db.reader.get_synced_version().unwrap();
 
-    let (pipeline, block_sender) =
-        Pipeline::new(executor, version, &pipeline_config, Some(num_blocks));
+    // An empty block starts each phase after the first.
+    let (pipeline, block_sender) = Pipeline::new(
+        executor,
+        version,
+        &pipeline_config,
+        Some(num_blocks + phase_blocks.len().saturating_sub(1)),
+    );
+    let indexer_stream = pipeline_config
+        .indexer_stream
+        .then(|| IndexerStream::start(db.reader.clone(), config.clone(), version + 1));
 
     let mut num_accounts_to_load = num_main_signer_accounts;
-    if let Some(mix) = &transaction_mix {
-        for (transaction_type, _) in mix {
+    if let Some(mix_per_phase) = &transaction_mix_per_phase {
+        for (transaction_type, _) in mix_per_phase.iter().flatten() {
             if let NonConflictingCoinTransfer { .. } = transaction_type {
                 // In case of non-conflicting coin transfer,
                 // `aptos_executor_benchmark::transaction_generator::TransactionGenerator` needs to hold
@@ -197,6 +246,9 @@ pub fn run_benchmark<V>(
         Some(num_accounts_to_load),
         pipeline_config.num_generator_workers,
     );
+    if let Some(seed) = seed {
+        generator.set_seed(seed);
+    }
 
     let mut overall_measuring = OverallMeasuring::start();
 
@@ -206,6 +258,7 @@ pub fn run_benchmark<V>(
             num_blocks,
             transaction_generators,
             phase,
+            &phase_blocks,
             transactions_per_sender,
         )
     } else {
@@ -216,6 +269,7 @@ pub fn run_benchmark<V>(
             connected_tx_grps,
             shuffle_connected_txns,
             hotspot_probability,
+            hot_accounts,
         )
     };
     if pipeline_config.delay_execution_start {
@@ -223,11 +277,12 @@ pub fn run_benchmark<V>(
     }
     pipeline.start_execution();
     generator.drop_sender();
-    pipeline.join();
+    let mut results = pipeline.join();
+    results.stage_samples.generation = generator.take_block_generation_times();
 
     info!(
         "Executed workload {}",
-        if let Some(mix) = transaction_mix {
+        if let Some(mix) = transaction_mix_per_phase {
             format!("{:?} via txn generator", mix)
         } else {
             "raw transfer".to_string()
@@ -237,7 +292,9 @@ pub fn run_benchmark<V>(
     if !pipeline_config.skip_commit {
         let num_txns =
             db.reader.get_synced_version().unwrap() - version - num_blocks_created as u64;
-        overall_measuring.print_end("Overall", num_txns);
+        results
+            .overall
+            .insert(0, overall_measuring.print_end("Overall", num_txns));
 
         if verify_sequence_numbers {
             generator.verify_sequence_numbers(db.reader.clone());
@@ -245,17 +302,661 @@ pub fn run_benchmark<V>(
         log_total_supply(&db.reader);
     }
 
+    results.summarize_stages();
+
     // Assert there were no error log lines in the run.
     assert_eq!(0, aptos_logger::ERROR_LOG_COUNT.get());
+
+    results.start_version = version;
+    results.end_version = db.reader.get_synced_version().unwrap();
+    results.indexer_stream = indexer_stream.map(|stream| stream.finish(results.end_version));
+    results.state_root_hash = state_root_hash(&db);
+    info!("State root hash: {}", results.state_root_hash);
+    results
+}
+
+/// Runs raw transfers with each of `block_sizes` and `transactions_per_sender_values`, one after
+/// the other on the same checkpoint, each run executing `num_blocks` blocks.
+#[allow(clippy::too_many_arguments)]
+pub fn sweep_block_sizes<V>(
+    block_sizes: &[usize],
+    transactions_per_sender_values: &[usize],
+    num_blocks: usize,
+    connected_tx_grps: usize,
+    shuffle_connected_txns: bool,
+    hotspot_probability: Option<f32>,
+    hot_accounts: Option<HotAccounts>,
+    num_main_signer_accounts: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    reuse_checkpoint: bool,
+    pruner_config: PrunerConfig,
+    enable_storage_sharding: bool,
+    pipeline_config: PipelineConfig,
+    init_features: Features,
+    seed: Option<u64>,
+) -> SweepResults
+where
+    V: TransactionBlockExecutor + 'static,
+{
+    assert!(
+        !pipeline_config.skip_commit,
+        "Each run starts from what the previous one committed."
+    );
+    create_or_reuse_checkpoint(
+        source_dir.as_ref(),
+        checkpoint_dir.as_ref(),
+        enable_storage_sharding,
+        reuse_checkpoint,
+    );
+    let (mut config, genesis_key) =
+        aptos_genesis::test_utils::test_config_with_custom_features(init_features);
+    config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
+    config.storage.storage_pruner_config = pruner_config;
+    config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    let (db, executor) = init_db_and_executor::<V>(&config);
+    let mut executor = Some(executor);
+    let mut genesis_key = Some(genesis_key);
+
+    // The generator keeps track of the sequence numbers across runs.
+    let mut generator: Option<TransactionGenerator> = None;
+    let mut sweep = SweepResults::default();
+    for &block_size in block_sizes {
+        for &transactions_per_sender in transactions_per_sender_values {
+            let version = db.reader.get_synced_version().unwrap();
+            let (pipeline, block_sender) = Pipeline::new(
+                executor
+                    .take()
+                    .unwrap_or_else(|| BlockExecutor::new(db.clone())),
+                version,
+                &pipeline_config,
+                Some(num_blocks),
+            );
+            match generator.as_mut() {
+                Some(generator) => generator.set_block_sender(block_sender),
+                None => {
+                    generator = Some(TransactionGenerator::new_with_existing_db(
+                        db.clone(),
+                        TransactionGenerator::read_root_account(genesis_key.take().unwrap(), &db),
+                        block_sender,
+                        &source_dir,
+                        Some(num_main_signer_accounts),
+                        pipeline_config.num_generator_workers,
+                    ));
+                    if let Some(seed) = seed {
+                        generator.as_mut().unwrap().set_seed(seed);
+                    }
+                },
+            }
+            let generator = generator.as_mut().unwrap();
+
+            let mut overall_measuring = OverallMeasuring::start();
+            let num_blocks_created = generator.run_transfer(
+                block_size,
+                num_blocks,
+                transactions_per_sender,
+                connected_tx_grps,
+                shuffle_connected_txns,
+                hotspot_probability,
+                hot_accounts,
+            );
+            if pipeline_config.delay_execution_start {
+                overall_measuring.start_time = Instant::now();
+            }
+            pipeline.start_execution();
+            generator.drop_sender();
+            let mut results = pipeline.join();
+            results.stage_samples.generation = generator.take_block_generation_times();
+
+            let end_version = db.reader.get_synced_version().unwrap();
+            let num_txns = end_version - version - num_blocks_created as u64;
+            results.overall.insert(
+                0,
+                overall_measuring.print_end(
+                    &format!(
+                        "Block size {}, {} txns per sender",
+                        block_size, transactions_per_sender
+                    ),
+                    num_txns,
+                ),
+            );
+            results.summarize_stages();
+            results.start_version = version;
+            results.end_version = end_version;
+            results.state_root_hash = state_root_hash(&db);
+            sweep.runs.push(SweepRun {
+                block_size,
+                transactions_per_sender,
+                tps: results.overall[0].tps,
+                results,
+            });
+        }
+    }
+
+    sweep.log_table();
+    sweep
+}
+
+/// Runs raw transfers for `duration` on a checkpoint, reporting every `report_blocks` blocks the
+/// TPS, the size of the DB, its compactions and how far the pruners got, for the decay of the
+/// throughput over long runs, and the interference of the pruners, to be measured.
+#[allow(clippy::too_many_arguments)]
+pub fn soak<V>(
+    block_size: usize,
+    report_blocks: usize,
+    duration: Duration,
+    transactions_per_sender: usize,
+    connected_tx_grps: usize,
+    shuffle_connected_txns: bool,
+    hotspot_probability: Option<f32>,
+    hot_accounts: Option<HotAccounts>,
+    num_main_signer_accounts: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    reuse_checkpoint: bool,
+    pruner_config: PrunerConfig,
+    enable_storage_sharding: bool,
+    pipeline_config: PipelineConfig,
+    init_features: Features,
+    seed: Option<u64>,
+) -> SoakResults
+where
+    V: TransactionBlockExecutor + 'static,
+{
+    assert!(
+        !pipeline_config.skip_commit,
+        "Each interval starts from what the previous one committed."
+    );
+    if !pruner_config.ledger_pruner_config.enable
+        && !pruner_config.state_merkle_pruner_config.enable
+    {
+        warn!("Soaking without the ledger and state merkle pruners.");
+    }
+    create_or_reuse_checkpoint(
+        source_dir.as_ref(),
+        checkpoint_dir.as_ref(),
+        enable_storage_sharding,
+        reuse_checkpoint,
+    );
+    let (mut config, genesis_key) =
+        aptos_genesis::test_utils::test_config_with_custom_features(init_features);
+    config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
+    config.storage.storage_pruner_config = pruner_config;
+    config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    let (db, executor) = init_db_and_executor::<V>(&config);
+    let mut executor = Some(executor);
+    let mut genesis_key = Some(genesis_key);
+
+    // The generator keeps track of the sequence numbers across intervals.
+    let mut generator: Option<TransactionGenerator> = None;
+    let mut soak = SoakResults::default();
+    let start_time = Instant::now();
+    while start_time.elapsed() < duration {
+        let version = db.reader.get_synced_version().unwrap();
+        let (pipeline, block_sender) = Pipeline::new(
+            executor
+                .take()
+                .unwrap_or_else(|| BlockExecutor::new(db.clone())),
+            version,
+            &pipeline_config,
+            Some(report_blocks),
+        );
+        match generator.as_mut() {
+            Some(generator) => generator.set_block_sender(block_sender),
+            None => {
+                generator = Some(TransactionGenerator::new_with_existing_db(
+                    db.clone(),
+                    TransactionGenerator::read_root_account(genesis_key.take().unwrap(), &db),
+                    block_sender,
+                    &source_dir,
+                    Some(num_main_signer_accounts),
+                    pipeline_config.num_generator_workers,
+                ));
+                if let Some(seed) = seed {
+                    generator.as_mut().unwrap().set_seed(seed);
+                }
+            },
+        }
+        let generator = generator.as_mut().unwrap();
+
+        let mut overall_measuring = OverallMeasuring::start();
+        let num_blocks_created = generator.run_transfer(
+            block_size,
+            report_blocks,
+            transactions_per_sender,
+            connected_tx_grps,
+            shuffle_connected_txns,
+            hotspot_probability,
+            hot_accounts,
+        );
+        if pipeline_config.delay_execution_start {
+            overall_measuring.start_time = Instant::now();
+        }
+        pipeline.start_execution();
+        generator.drop_sender();
+        let mut results = pipeline.join();
+        results.stage_samples.generation = generator.take_block_generation_times();
+
+        let end_version = db.reader.get_synced_version().unwrap();
+        let num_txns = end_version - version - num_blocks_created as u64;
+        let elapsed_secs = start_time.elapsed().as_secs_f64();
+        results.overall.insert(
+            0,
+            overall_measuring.print_end(&format!("Soak at {:.0} secs", elapsed_secs), num_txns),
+        );
+        results.summarize_stages();
+        results.start_version = version;
+        results.end_version = end_version;
+        results.state_root_hash = state_root_hash(&db);
+        let report = SoakReport {
+            elapsed_secs,
+            end_version,
+            tps: results.overall[0].tps,
+            db_size_bytes: dir_size(checkpoint_dir.as_ref()),
+            pending_compaction_bytes: rocksdb_property_sum(
+                "aptos_rocksdb_estimate_pending_compaction_bytes",
+            ),
+            running_compactions: rocksdb_property_sum("aptos_rocksdb_num_running_compactions"),
+            ledger_min_readable_version: PRUNER_VERSIONS
+                .with_label_values(&["ledger_pruner", "min_readable"])
+                .get(),
+            state_merkle_min_readable_version: PRUNER_VERSIONS
+                .with_label_values(&["state_merkle_pruner", "min_readable"])
+                .get(),
+            results,
+        };
+        info!(
+            "Soak at {:.0} secs: version {}, TPS {:.0}, DB {} bytes, pending compaction {} bytes, \
+             {} running compactions, ledger pruned to {}, state merkle pruned to {}",
+            report.elapsed_secs,
+            report.end_version,
+            report.tps,
+            report.db_size_bytes,
+            report.pending_compaction_bytes,
+            report.running_compactions,
+            report.ledger_min_readable_version,
+            report.state_merkle_min_readable_version,
+        );
+        soak.reports.push(report);
+    }
+
+    soak.log_table();
+    soak
+}
+
+/// Size of the files under `path`.
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Sum over the column families of a property the DB reports periodically.
+fn rocksdb_property_sum(property_name: &str) -> i64 {
+    aptos_metrics_core::gather()
+        .iter()
+        .filter(|family| family.get_name() == "aptos_rocksdb_properties")
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| {
+            metric.get_label().iter().any(|label| {
+                label.get_name() == "property_name" && label.get_value() == property_name
+            })
+        })
+        .map(|metric| metric.get_gauge().get_value() as i64)
+        .sum()
+}
+
+/// Replays up to `num_blocks` blocks of the transactions from a backup, the ones following the
+/// last version in the checkpoint of `source_dir`. `source_dir` has to be restored from the
+/// same chain up to the end of a block.
+#[allow(clippy::too_many_arguments)]
+pub fn replay_backup<V>(
+    storage_opt: DBToolStorageOpt,
+    metadata_cache_opt: MetadataCacheOpt,
+    concurrent_downloads: usize,
+    num_blocks: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    reuse_checkpoint: bool,
+    pruner_config: PrunerConfig,
+    enable_storage_sharding: bool,
+    mut pipeline_config: PipelineConfig,
+) -> BenchmarkResults
+where
+    V: TransactionBlockExecutor + 'static,
+{
+    create_or_reuse_checkpoint(
+        source_dir.as_ref(),
+        checkpoint_dir.as_ref(),
+        enable_storage_sharding,
+        reuse_checkpoint,
+    );
+    let mut config = NodeConfig::default();
+    config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
+    config.storage.storage_pruner_config = pruner_config;
+    config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    let (db, executor) = init_db_and_executor::<V>(&config);
+
+    // Real transactions do abort.
+    pipeline_config.allow_aborts = true;
+    let version = db.reader.get_synced_version().unwrap();
+    let (pipeline, block_sender) =
+        Pipeline::new(executor, version, &pipeline_config, Some(num_blocks));
+
+    let mut overall_measuring = OverallMeasuring::start();
+    let runtime = Runtime::new().unwrap();
+    let num_blocks_sent = runtime
+        .block_on(async {
+            backup_replay::send_blocks_from_backup(
+                storage_opt.init_storage().await?,
+                &metadata_cache_opt,
+                concurrent_downloads,
+                version + 1,
+                num_blocks,
+                &block_sender,
+            )
+            .await
+        })
+        .expect("Failed to read the transactions from the backup.");
+    info!("Read {} blocks from the backup.", num_blocks_sent);
+    if pipeline_config.delay_execution_start {
+        overall_measuring.start_time = Instant::now();
+    }
+    pipeline.start_execution();
+    drop(block_sender);
+    let mut results = pipeline.join();
+
+    if !pipeline_config.skip_commit {
+        let num_txns = db.reader.get_synced_version().unwrap() - version - num_blocks_sent as u64;
+        results
+            .overall
+            .insert(0, overall_measuring.print_end("Overall", num_txns));
+    }
+    results.summarize_stages();
+
+    results.start_version = version;
+    results.end_version = db.reader.get_synced_version().unwrap();
+    results.state_root_hash = state_root_hash(&db);
+    results
+}
+
+/// Runs the same blocks of raw transfers with a baseline and a candidate executor, each on its own
+/// checkpoint of `source_dir`, then compares the transaction infos and the root hashes.
+#[allow(clippy::too_many_arguments)]
+pub fn compare_executors<B, C>(
+    block_size: usize,
+    num_blocks: usize,
+    transactions_per_sender: usize,
+    connected_tx_grps: usize,
+    shuffle_connected_txns: bool,
+    hotspot_probability: Option<f32>,
+    hot_accounts: Option<HotAccounts>,
+    num_main_signer_accounts: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    pruner_config: PrunerConfig,
+    enable_storage_sharding: bool,
+    pipeline_config: PipelineConfig,
+    init_features: Features,
+    seed: Option<u64>,
+) -> ComparisonResults
+where
+    B: TransactionBlockExecutor + 'static,
+    C: TransactionBlockExecutor + 'static,
+{
+    assert!(
+        !pipeline_config.skip_commit,
+        "Executors are compared on what they commit."
+    );
+    let baseline_dir = checkpoint_dir.as_ref().join("baseline");
+    let candidate_dir = checkpoint_dir.as_ref().join("candidate");
+    create_checkpoint(source_dir.as_ref(), &baseline_dir, enable_storage_sharding);
+    create_checkpoint(source_dir.as_ref(), &candidate_dir, enable_storage_sharding);
+    let (mut config, genesis_key) =
+        aptos_genesis::test_utils::test_config_with_custom_features(init_features);
+    config.storage.storage_pruner_config = pruner_config;
+    config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    config.storage.dir = baseline_dir;
+    let (baseline_db, baseline_executor) = init_db_and_executor::<B>(&config);
+    config.storage.dir = candidate_dir;
+    let (candidate_db, candidate_executor) = init_db_and_executor::<C>(&config);
+
+    // Generate all blocks upfront, so both executors get the same ones.
+    let (block_sender, block_receiver) = mpsc::sync_channel(num_blocks);
+    let mut generator = TransactionGenerator::new_with_existing_db(
+        baseline_db.clone(),
+        TransactionGenerator::read_root_account(genesis_key, &baseline_db),
+        block_sender,
+        &source_dir,
+        Some(num_main_signer_accounts),
+        pipeline_config.num_generator_workers,
+    );
+    if let Some(seed) = seed {
+        generator.set_seed(seed);
+    }
+    generator.run_transfer(
+        block_size,
+        num_blocks,
+        transactions_per_sender,
+        connected_tx_grps,
+        shuffle_connected_txns,
+        hotspot_probability,
+        hot_accounts,
+    );
+    generator.drop_sender();
+    let blocks = block_receiver.iter().collect::<Vec<_>>();
+
+    let version = baseline_db.reader.get_synced_version().unwrap();
+    let baseline = execute_blocks(
+        "Baseline",
+        &baseline_db,
+        baseline_executor,
+        blocks.clone(),
+        &pipeline_config,
+    );
+    let candidate = execute_blocks(
+        "Candidate",
+        &candidate_db,
+        candidate_executor,
+        blocks,
+        &pipeline_config,
+    );
+
+    let end_version = baseline.end_version.min(candidate.end_version);
+    let mut divergences = executor_comparison::diff_transaction_infos(
+        baseline_db.reader.as_ref(),
+        candidate_db.reader.as_ref(),
+        version + 1,
+        end_version - version,
+    )
+    .expect("Failed to read the transaction infos.");
+    if baseline.end_version != candidate.end_version {
+        divergences.push(Divergence {
+            version: end_version + 1,
+            field: "end_version".to_string(),
+            baseline: baseline.end_version.to_string(),
+            candidate: candidate.end_version.to_string(),
+        });
+    }
+    let results = ComparisonResults {
+        baseline_root_hash: baseline_db
+            .reader
+            .get_accumulator_root_hash(baseline.end_version)
+            .unwrap(),
+        candidate_root_hash: candidate_db
+            .reader
+            .get_accumulator_root_hash(candidate.end_version)
+            .unwrap(),
+        baseline,
+        candidate,
+        divergences,
+    };
+
+    for divergence in &results.divergences {
+        warn!(
+            "Divergence at version {} in {}: baseline {}, candidate {}",
+            divergence.version, divergence.field, divergence.baseline, divergence.candidate
+        );
+    }
+    info!(
+        "Baseline TPS: {:.0}, candidate TPS: {:.0}, root hashes: {} and {}, {} divergences",
+        results.baseline.overall[0].tps,
+        results.candidate.overall[0].tps,
+        results.baseline_root_hash,
+        results.candidate_root_hash,
+        results.divergences.len(),
+    );
+    results
+}
+
+/// Executes blocks of raw transfers on a checkpoint of `source_dir`, then applies the outputs they
+/// committed on another checkpoint, through the same pipeline with execution skipped, the way
+/// state sync applies transaction outputs. Returns what was measured applying them.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_outputs<V>(
+    block_size: usize,
+    num_blocks: usize,
+    transactions_per_sender: usize,
+    connected_tx_grps: usize,
+    shuffle_connected_txns: bool,
+    hotspot_probability: Option<f32>,
+    hot_accounts: Option<HotAccounts>,
+    num_main_signer_accounts: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    pruner_config: PrunerConfig,
+    enable_storage_sharding: bool,
+    pipeline_config: PipelineConfig,
+    init_features: Features,
+    seed: Option<u64>,
+) -> BenchmarkResults
+where
+    V: TransactionBlockExecutor + 'static,
+{
+    assert!(
+        !pipeline_config.skip_commit,
+        "Outputs are applied from what was committed."
+    );
+    let execute_dir = checkpoint_dir.as_ref().join("execute");
+    let apply_dir = checkpoint_dir.as_ref().join("apply");
+    create_checkpoint(source_dir.as_ref(), &execute_dir, enable_storage_sharding);
+    create_checkpoint(source_dir.as_ref(), &apply_dir, enable_storage_sharding);
+    let (mut config, genesis_key) =
+        aptos_genesis::test_utils::test_config_with_custom_features(init_features);
+    config.storage.storage_pruner_config = pruner_config;
+    config.storage.rocksdb_configs.enable_storage_sharding = enable_storage_sharding;
+    config.storage.dir = execute_dir;
+    let (execute_db, executor) = init_db_and_executor::<V>(&config);
+    config.storage.dir = apply_dir;
+    let (apply_db, applier) = init_db_and_executor::<OutputApplier>(&config);
+
+    let (block_sender, block_receiver) = mpsc::sync_channel(num_blocks);
+    let mut generator = TransactionGenerator::new_with_existing_db(
+        execute_db.clone(),
+        TransactionGenerator::read_root_account(genesis_key, &execute_db),
+        block_sender,
+        &source_dir,
+        Some(num_main_signer_accounts),
+        pipeline_config.num_generator_workers,
+    );
+    if let Some(seed) = seed {
+        generator.set_seed(seed);
+    }
+    generator.run_transfer(
+        block_size,
+        num_blocks,
+        transactions_per_sender,
+        connected_tx_grps,
+        shuffle_connected_txns,
+        hotspot_probability,
+        hot_accounts,
+    );
+    generator.drop_sender();
+    let blocks = block_receiver.iter().collect::<Vec<_>>();
+
+    let version = execute_db.reader.get_synced_version().unwrap();
+    let executed = execute_blocks("Execution", &execute_db, executor, blocks, &pipeline_config);
+    let blocks = OutputApplier::load_committed_blocks(
+        execute_db.reader.as_ref(),
+        version + 1,
+        executed.end_version - version,
+    )
+    .expect("Failed to read the committed outputs.");
+    let applied = execute_blocks("Apply", &apply_db, applier, blocks, &pipeline_config);
+
+    info!(
+        "Execution TPS: {:.0}, apply TPS: {:.0}",
+        executed.overall[0].tps, applied.overall[0].tps,
+    );
+    applied
+}
+
+/// Runs `blocks` through a pipeline on `db`, `prefix` naming the overall result.
+fn execute_blocks<V>(
+    prefix: &str,
+    db: &DbReaderWriter,
+    executor: BlockExecutor<V>,
+    blocks: Vec<Vec<Transaction>>,
+    pipeline_config: &PipelineConfig,
+) -> BenchmarkResults
+where
+    V: TransactionBlockExecutor + 'static,
+{
+    let version = db.reader.get_synced_version().unwrap();
+    let num_blocks = blocks.len();
+    let (pipeline, block_sender) =
+        Pipeline::new(executor, version, pipeline_config, Some(num_blocks));
+
+    let mut overall_measuring = OverallMeasuring::start();
+    for block in blocks {
+        block_sender.send(block).unwrap();
+    }
+    if pipeline_config.delay_execution_start {
+        overall_measuring.start_time = Instant::now();
+    }
+    pipeline.start_execution();
+    drop(block_sender);
+    let mut results = pipeline.join();
+
+    let end_version = db.reader.get_synced_version().unwrap();
+    let num_txns = end_version - version - num_blocks as u64;
+    results
+        .overall
+        .insert(0, overall_measuring.print_end(prefix, num_txns));
+    results.summarize_stages();
+    results.start_version = version;
+    results.end_version = end_version;
+    results.state_root_hash = state_root_hash(db);
+    results
+}
+
+/// Root hash of the latest state in `db`.
+fn state_root_hash(db: &DbReaderWriter) -> HashValue {
+    db.reader
+        .get_latest_executed_trees()
+        .expect("Failed to read the latest state.")
+        .state()
+        .current
+        .root_hash()
 }
 
 fn init_workload<V>(
-    transaction_mix: Vec<(TransactionType, usize)>,
+    transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>>,
     root_account: Arc<LocalAccount>,
     mut main_signer_accounts: Vec<LocalAccount>,
     burner_accounts: Vec<LocalAccount>,
     db: DbReaderWriter,
     pipeline_config: &PipelineConfig,
+    rng_source: RngSource,
 ) -> (Box<dyn TransactionGeneratorCreator>, Arc<AtomicUsize>)
 where
     V: TransactionBlockExecutor + 'static,
@@ -279,7 +980,7 @@ where
         };
 
         create_txn_generator_creator(
-            &[transaction_mix],
+            &transaction_mix_per_phase,
             AlwaysApproveRootAccountHandle { root_account },
             &mut main_signer_accounts,
             burner_accounts,
@@ -287,6 +988,7 @@ where
             &transaction_factory,
             &transaction_factory,
             phase_clone,
+            rng_source,
         )
         .await
     });
@@ -598,80 +1300,86 @@ impl OverallMeasuring {
         }
     }
 
-    pub fn print_end(self, prefix: &str, num_txns: u64) {
+    pub fn print_end(self, prefix: &str, num_txns: u64) -> OverallResult {
         let elapsed = self.start_time.elapsed().as_secs_f64();
         let num_txns = num_txns as f64;
         let delta_execution = self.start_execution.elapsed_delta();
         let delta_gas = self.start_gas.elapsed_delta();
 
+        let result = OverallResult {
+            name: prefix.to_string(),
+            num_txns: num_txns as u64,
+            elapsed_secs: elapsed,
+            tps: num_txns / elapsed,
+            vm_tps: num_txns / delta_execution.vm_time,
+            gps: delta_gas.gas / elapsed,
+            effective_gps: delta_gas.effective_block_gas / elapsed,
+            io_gps: delta_gas.io_gas / elapsed,
+            execution_gps: delta_gas.execution_gas / elapsed,
+            gas_per_txn: delta_gas.gas / (delta_gas.gas_count as f64).max(1.0),
+            speculative_aborts_per_txn: delta_gas.speculative_abort_count as f64 / num_txns,
+            approx_output_bytes_per_sec: delta_gas.approx_block_output / elapsed,
+            output_bytes_per_sec: delta_execution.output_size / elapsed,
+            partitioning_fraction: delta_execution.partitioning_total / elapsed,
+            execution_fraction: delta_execution.execution_total / elapsed,
+            ledger_update_fraction: delta_execution.ledger_update_total / elapsed,
+            commit_fraction: delta_execution.commit_total / elapsed,
+            vm_fraction_of_execution: delta_execution.vm_only / delta_execution.execution_total,
+            other_fractions_of_execution: OTHER_LABELS
+                .iter()
+                .map(|(_prefix, _top_level, other_label)| {
+                    (
+                        other_label.to_string(),
+                        delta_execution.by_other.get(other_label).unwrap()
+                            / delta_execution.execution_total,
+                    )
+                })
+                .collect(),
+        };
+
         info!(
             "{} TPS: {} txn/s (over {} txns, in {} s)",
-            prefix,
-            num_txns / elapsed,
-            num_txns,
-            elapsed
+            prefix, result.tps, num_txns, elapsed
         );
         info!(
             "{} VM execution TPS {} txn/s; ({} / {})",
-            prefix,
-            (num_txns / delta_execution.vm_time) as usize,
-            num_txns,
-            delta_execution.vm_time
+            prefix, result.vm_tps as usize, num_txns, delta_execution.vm_time
         );
-        info!("{} GPS: {} gas/s", prefix, delta_gas.gas / elapsed);
+        info!("{} GPS: {} gas/s", prefix, result.gps);
         info!(
             "{} effectiveGPS: {} gas/s ({} effective block gas, in {} s)",
-            prefix,
-            delta_gas.effective_block_gas / elapsed,
-            delta_gas.effective_block_gas,
-            elapsed
+            prefix, result.effective_gps, delta_gas.effective_block_gas, elapsed
         );
         info!(
             "{} speculative aborts: {} aborts/txn ({} aborts over {} txns)",
-            prefix,
-            delta_gas.speculative_abort_count as f64 / num_txns,
-            delta_gas.speculative_abort_count,
-            num_txns
-        );
-        info!("{} ioGPS: {} gas/s", prefix, delta_gas.io_gas / elapsed);
-        info!(
-            "{} executionGPS: {} gas/s",
-            prefix,
-            delta_gas.execution_gas / elapsed
-        );
-        info!(
-            "{} GPT: {} gas/txn",
-            prefix,
-            delta_gas.gas / (delta_gas.gas_count as f64).max(1.0)
+            prefix, result.speculative_aborts_per_txn, delta_gas.speculative_abort_count, num_txns
         );
+        info!("{} ioGPS: {} gas/s", prefix, result.io_gps);
+        info!("{} executionGPS: {} gas/s", prefix, result.execution_gps);
+        info!("{} GPT: {} gas/txn", prefix, result.gas_per_txn);
         info!(
             "{} approx_output: {} bytes/s",
-            prefix,
-            delta_gas.approx_block_output / elapsed
-        );
-        info!(
-            "{} output: {} bytes/s",
-            prefix,
-            delta_execution.output_size / elapsed
+            prefix, result.approx_output_bytes_per_sec
         );
+        info!("{} output: {} bytes/s", prefix, result.output_bytes_per_sec);
 
         info!(
             "{} fraction of total: {:.3} in partitioning (component TPS: {})",
             prefix,
-            delta_execution.partitioning_total / elapsed,
+            result.partitioning_fraction,
             num_txns / delta_execution.partitioning_total
         );
 
         info!(
             "{} fraction of total: {:.3} in execution (component TPS: {})",
             prefix,
-            delta_execution.execution_total / elapsed,
+            result.execution_fraction,
             num_txns / delta_execution.execution_total
         );
         info!(
             "{} fraction of execution {:.3} in VM (component TPS: {})",
             prefix,
-            delta_execution.vm_only / delta_execution.execution_total,
+            result.vm_fraction_of_execution,
             num_txns / delta_execution.vm_only
         );
         for (prefix, top_level, other_label) in OTHER_LABELS {
@@ -691,16 +1399,18 @@ impl OverallMeasuring {
         info!(
             "{} fraction of total: {:.3} in ledger update (component TPS: {})",
             prefix,
-            delta_execution.ledger_update_total / elapsed,
+            result.ledger_update_fraction,
             num_txns / delta_execution.ledger_update_total
         );
 
         info!(
             "{} fraction of total: {:.4} in commit (component TPS: {})",
             prefix,
-            delta_execution.commit_total / elapsed,
+            result.commit_fraction,
             num_txns / delta_execution.commit_total
         );
+
+        result
     }
 }
 
@@ -737,6 +1447,7 @@ mod tests {
             100, /* num_accounts */
             // TODO(Gas): double check if this is correct
             100_000_000_000, /* init_account_balance */
+            &[],             /* account_state */
             5,               /* block_size */
             storage_dir.as_ref(),
             NO_OP_STORAGE_PRUNER_CONFIG, /* prune_window */
@@ -748,25 +1459,39 @@ mod tests {
 
         println!("run_benchmark");
 
-        super::run_benchmark::<E>(
+        let results = super::run_benchmark::<E>(
             10, /* block_size */
             30, /* num_blocks */
-            transaction_type
-                .map(|t| vec![(t.materialize(1, true, WorkflowProgress::MoveByPhases), 1)]),
-            2,     /* transactions per sender */
-            0,     /* connected txn groups in a block */
-            false, /* shuffle the connected txns in a block */
-            None,  /* maybe_hotspot_probability */
-            25,    /* num_main_signer_accounts */
-            30,    /* num_dst_pool_accounts */
+            transaction_type.map(|t| {
+                vec![vec![(
+                    t.materialize(1, true, WorkflowProgress::MoveByPhases),
+                    1,
+                )]]
+            }),
+            vec![], /* phase_blocks */
+            2,      /* transactions per sender */
+            0,      /* connected txn groups in a block */
+            false,  /* shuffle the connected txns in a block */
+            None,   /* maybe_hotspot_probability */
+            None,   /* hot_accounts */
+            25,     /* num_main_signer_accounts */
+            30,     /* num_dst_pool_accounts */
             storage_dir.as_ref(),
             checkpoint_dir,
+            false, /* reuse_checkpoint */
             verify_sequence_numbers,
             NO_OP_STORAGE_PRUNER_CONFIG,
             false,
-            PipelineConfig::default(),
+            PipelineConfig {
+                warmup_blocks: 5,
+                ..Default::default()
+            },
             Features::default(),
+            None, /* seed */
         );
+        assert_eq!(results.overall[0].name, "Overall");
+        assert_eq!(results.overall.last().unwrap().name, "Steady state");
+        assert_eq!(results.blocks.last().unwrap().version, results.end_version);
     }
 
     #[test]
@@ -791,4 +1516,86 @@ mod tests {
         // correct execution not yet implemented, so cannot be checked for validity
         test_generic_benchmark::<NativeExecutor>(None, false);
     }
+
+    #[test]
+    fn test_compare_executors() {
+        aptos_logger::Logger::new().init();
+
+        let storage_dir = TempPath::new();
+        let checkpoint_dir = TempPath::new();
+
+        crate::db_generator::create_db_with_accounts::<AptosVM>(
+            100,             /* num_accounts */
+            100_000_000_000, /* init_account_balance */
+            &[],             /* account_state */
+            5,               /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG, /* prune_window */
+            true,
+            false,
+            PipelineConfig::default(),
+            Features::default(),
+        );
+
+        let results = super::compare_executors::<AptosVM, AptosVM>(
+            10,    /* block_size */
+            10,    /* num_blocks */
+            2,     /* transactions per sender */
+            0,     /* connected txn groups in a block */
+            false, /* shuffle the connected txns in a block */
+            None,  /* maybe_hotspot_probability */
+            None,  /* hot_accounts */
+            25,    /* num_main_signer_accounts */
+            storage_dir.as_ref(),
+            checkpoint_dir,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            false,
+            PipelineConfig::default(),
+            Features::default(),
+            Some(42), /* seed */
+        );
+        assert!(results.is_consistent(), "{:?}", results.divergences);
+        assert_eq!(results.baseline.end_version, results.candidate.end_version);
+    }
+
+    #[test]
+    fn test_apply_outputs() {
+        aptos_logger::Logger::new().init();
+
+        let storage_dir = TempPath::new();
+        let checkpoint_dir = TempPath::new();
+
+        crate::db_generator::create_db_with_accounts::<AptosVM>(
+            100,             /* num_accounts */
+            100_000_000_000, /* init_account_balance */
+            &[],             /* account_state */
+            5,               /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG, /* prune_window */
+            true,
+            false,
+            PipelineConfig::default(),
+            Features::default(),
+        );
+
+        let results = super::apply_outputs::<AptosVM>(
+            10,    /* block_size */
+            10,    /* num_blocks */
+            2,     /* transactions per sender */
+            0,     /* connected txn groups in a block */
+            false, /* shuffle the connected txns in a block */
+            None,  /* maybe_hotspot_probability */
+            None,  /* hot_accounts */
+            25,    /* num_main_signer_accounts */
+            storage_dir.as_ref(),
+            checkpoint_dir,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            false,
+            PipelineConfig::default(),
+            Features::default(),
+            Some(42), /* seed */
+        );
+        // Each block of 10 transfers got a state checkpoint transaction.
+        assert_eq!(results.end_version - results.start_version, 10 * 11);
+    }
 }
@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_backup_cli::{metadata::cache::MetadataCacheOpt, storage::DBToolStorageOpt};
 use aptos_block_partitioner::{
     pre_partition::{
         connected_component::config::ConnectedComponentPartitionerConfig,
@@ -13,8 +14,14 @@ use aptos_block_partitioner::{
 use aptos_config::config::{
     EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
 };
+use aptos_crypto::HashValue;
 use aptos_executor::block_executor::TransactionBlockExecutor;
-use aptos_executor_benchmark::{native_executor::NativeExecutor, pipeline::PipelineConfig};
+use aptos_executor_benchmark::{
+    native_executor::NativeExecutor,
+    pipeline::PipelineConfig,
+    results::{BenchmarkResults, OutputFormat, ResultsOutput},
+    transaction_generator::HotAccounts,
+};
 use aptos_executor_service::remote_executor_client;
 use aptos_experimental_ptx_executor::PtxBlockExecutor;
 #[cfg(target_os = "linux")]
@@ -31,9 +38,12 @@ use aptos_vm::AptosVM;
 use clap::{ArgGroup, Parser, Subcommand};
 use once_cell::sync::Lazy;
 use std::{
+    fs::File,
     net::SocketAddr,
-    path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Child,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 #[cfg(unix)]
@@ -82,17 +92,20 @@ impl PrunerOpt {
                 enable: self.enable_state_pruner,
                 prune_window: self.state_prune_window,
                 batch_size: self.state_pruning_batch_size,
+                dry_run: false,
             },
             epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig {
                 enable: self.enable_epoch_snapshot_pruner,
                 prune_window: self.epoch_snapshot_prune_window,
                 batch_size: self.epoch_snapshot_pruning_batch_size,
+                dry_run: false,
             },
             ledger_pruner_config: LedgerPrunerConfig {
                 enable: self.enable_ledger_pruner,
                 prune_window: self.ledger_prune_window,
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
+                dry_run: false,
             },
         }
     }
@@ -114,10 +127,52 @@ pub struct PipelineOpt {
     allow_retries: bool,
     #[clap(long, default_value = "4")]
     num_generator_workers: usize,
+    /// Effective gas limit of each block. The transactions past it are retried, and the ones of
+    /// their senders that follow are discarded.
+    #[clap(long, requires = "allow_retries", requires = "allow_discards")]
+    block_gas_limit: Option<u64>,
+    /// Blocks reported as warm-up, apart from the steady state after them
+    #[clap(long, default_value_t = 0)]
+    warmup_blocks: usize,
+    /// Last committed blocks the rolling TPS, logged with each block, is over
+    #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(usize).range(1..))]
+    rolling_tps_blocks: usize,
+    /// Blocks to profile the CPU of, as START..END counting from 0, writing a flamegraph of
+    /// each stage to ./profiling_results
+    #[clap(long, value_parser = parse_block_range, conflicts_with = "cpu_profiling")]
+    profile_blocks: Option<Range<usize>>,
+    /// Streams the committed transactions as the indexer-grpc fullnode does for its clients,
+    /// alongside the pipeline of RunExecutor, to measure the impact of indexing
+    #[clap(long)]
+    indexer_stream: bool,
+    /// Blocks the channel from the generation to the partitioning stage holds
+    #[clap(long, default_value_t = 10)]
+    raw_block_channel_size: usize,
+    /// Blocks the channel from the partitioning to the execution stage holds
+    #[clap(long, default_value_t = 3)]
+    executable_block_channel_size: usize,
+    /// Blocks the channel from the execution to the ledger update stage holds
+    #[clap(long, default_value_t = 3)]
+    ledger_update_channel_size: usize,
+    /// Blocks the channel from the ledger update to the commit stage holds
+    #[clap(long, default_value_t = 3)]
+    commit_channel_size: usize,
     #[clap(flatten)]
     sharding_opt: ShardingOpt,
 }
 
+fn parse_block_range(range: &str) -> Result<Range<usize>, String> {
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| format!("Expected START..END, got {}", range))?;
+    let start = start.parse::<usize>().map_err(|e| e.to_string())?;
+    let end = end.parse::<usize>().map_err(|e| e.to_string())?;
+    if start >= end {
+        return Err(format!("Empty block range {}", range));
+    }
+    Ok(start..end)
+}
+
 impl PipelineOpt {
     fn pipeline_config(&self) -> PipelineConfig {
         PipelineConfig {
@@ -131,6 +186,15 @@ impl PipelineOpt {
             use_global_executor: self.sharding_opt.use_global_executor,
             num_generator_workers: self.num_generator_workers,
             partitioner_config: self.sharding_opt.partitioner_config(),
+            block_gas_limit: self.block_gas_limit,
+            warmup_blocks: self.warmup_blocks,
+            rolling_tps_blocks: self.rolling_tps_blocks,
+            profile_blocks: self.profile_blocks.clone(),
+            indexer_stream: self.indexer_stream,
+            raw_block_channel_size: self.raw_block_channel_size,
+            executable_block_channel_size: self.executable_block_channel_size,
+            ledger_update_channel_size: self.ledger_update_channel_size,
+            commit_channel_size: self.commit_channel_size,
         }
     }
 }
@@ -144,10 +208,14 @@ struct ShardingOpt {
     /// Gives an option to specify remote shard addresses. If specified, then we expect the number
     /// of remote addresses to be equal to 'num_executor_shards', and one coordinator address
     /// Address is specified as <IP>:<PORT>
-    #[clap(long, num_args = 1..)]
+    #[clap(long, num_args = 1.., requires = "coordinator_address")]
     remote_executor_addresses: Option<Vec<SocketAddr>>,
     #[clap(long)]
     coordinator_address: Option<SocketAddr>,
+    /// Path to the aptos-executor-service binary, to start the remote shards as processes on this
+    /// host, listening on the remote executor addresses, instead of starting them separately
+    #[clap(long, requires = "remote_executor_addresses")]
+    spawn_remote_executors: Option<PathBuf>,
     #[clap(long, default_value = "4")]
     max_partitioning_rounds: usize,
     #[clap(long, default_value = "0.90")]
@@ -236,6 +304,30 @@ struct Opt {
     #[clap(long, conflicts_with_all = &["connected_tx_grps", "transactions_per_sender"])]
     hotspot_probability: Option<f32>,
 
+    /// Number of hot accounts, the first ones of the pool, that `--hot-account-fraction` of the
+    /// raw transfers go to.
+    #[clap(
+        long,
+        requires = "hot_account_fraction",
+        conflicts_with_all = &["connected_tx_grps", "hotspot_probability"]
+    )]
+    hot_accounts: Option<usize>,
+
+    /// Fraction of the raw transfers that go to one of the `--hot-accounts`, in [0, 1].
+    #[clap(long, requires = "hot_accounts")]
+    hot_account_fraction: Option<f32>,
+
+    /// Runs on the checkpoint as the previous run left it, if there is one, instead of creating it
+    /// anew from data_dir. Ignored by add-accounts, compare-executors and apply-outputs.
+    #[clap(long)]
+    reuse_checkpoint: bool,
+
+    /// Seeds the picking of accounts and the workload generators, for reproducible runs.
+    /// Fully reproducible only with a single generator worker, as otherwise workers pick up
+    /// senders in whichever order they get to them.
+    #[clap(long)]
+    seed: Option<u64>,
+
     #[clap(
         long,
         help = "Number of threads to use for execution. Generally replaces --concurrency-level flag (directly for default case, and as a total across all shards for sharded case)"
@@ -266,6 +358,33 @@ struct Opt {
 
     #[clap(long)]
     skip_paranoid_checks: bool,
+
+    /// Write the per-block and overall results of RunExecutor in this format,
+    /// to --output-file or stdout
+    #[clap(long, value_enum)]
+    output_format: Option<OutputFormat>,
+
+    #[clap(long, value_parser)]
+    output_file: Option<PathBuf>,
+
+    /// Write the latencies of the blocks of RunExecutor, ApplyOutputs or ReplayBackup to this
+    /// file, as Prometheus summaries in the text format
+    #[clap(long, value_parser)]
+    latency_summary_file: Option<PathBuf>,
+
+    /// JSON results of a previous run of RunExecutor or ApplyOutputs to compare with, exiting
+    /// with an error if the TPS or the stage latencies regressed
+    #[clap(long, value_parser)]
+    baseline: Option<PathBuf>,
+
+    /// Percentage by which a number can be worse than in the --baseline
+    #[clap(long, default_value_t = 10.0, requires = "baseline")]
+    max_regression_pct: f64,
+
+    /// State root hash RunExecutor, ApplyOutputs or ReplayBackup must end with, exiting with an
+    /// error otherwise, e.g. the one of a run with AptosVM, to check the native or ptx executor
+    #[clap(long)]
+    expected_state_root: Option<HashValue>,
 }
 
 impl Opt {
@@ -293,6 +412,12 @@ enum Command {
         #[clap(long, default_value_t = 10000000000)]
         init_account_balance: u64,
 
+        /// Workloads to run with about one transaction from each account once created, for the
+        /// accounts to have more state, e.g. resource-groups-sender-multi-change1-kb,
+        /// create-objects10, fungible-asset-mint or account-resource10-kb
+        #[clap(long, value_enum, num_args = 0.., ignore_case = true)]
+        account_state: Vec<TransactionTypeArg>,
+
         #[clap(
             long,
             num_args=1..,
@@ -333,6 +458,17 @@ enum Command {
         #[clap(long, num_args = 0..)]
         transaction_weights: Vec<usize>,
 
+        /// Phase of each transaction type, a single phase if not set
+        #[clap(long, num_args = 0..)]
+        transaction_phases: Vec<usize>,
+
+        /// Blocks of each phase, replacing --blocks. If not set, the next phase starts once the
+        /// current one has nothing left to generate
+        #[clap(long, num_args = 0.., requires = "transaction_phases")]
+        phase_blocks: Vec<usize>,
+
+        /// Number of instances of the modules the custom module workloads call. E.g. with 1, all
+        /// the modify-global-resource transactions conflict on the same resource
         #[clap(long, default_value_t = 1)]
         module_working_set_size: usize,
 
@@ -361,6 +497,169 @@ enum Command {
             Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
         disable_feature: Vec<FeatureFlag>,
     },
+    /// Runs the same raw transfer blocks with AptosVM and with the executor selected by
+    /// --use-native-executor or --use-ptx-executor, then compares what they committed
+    CompareExecutors {
+        /// number of transfer blocks to run
+        #[clap(long, default_value_t = 1000)]
+        blocks: usize,
+
+        #[clap(long, default_value_t = 1000000)]
+        main_signer_accounts: usize,
+
+        #[clap(long, value_parser)]
+        data_dir: PathBuf,
+
+        /// the checkpoints of both executors are created under this directory
+        #[clap(long, value_parser)]
+        checkpoint_dir: PathBuf,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        enable_feature: Vec<FeatureFlag>,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        disable_feature: Vec<FeatureFlag>,
+    },
+    /// Executes raw transfer blocks with the selected executor, then applies the committed
+    /// outputs on another checkpoint without executing them, as state sync does
+    ApplyOutputs {
+        /// number of transfer blocks to run
+        #[clap(long, default_value_t = 1000)]
+        blocks: usize,
+
+        #[clap(long, default_value_t = 1000000)]
+        main_signer_accounts: usize,
+
+        #[clap(long, value_parser)]
+        data_dir: PathBuf,
+
+        /// the checkpoints to execute and to apply on are created under this directory
+        #[clap(long, value_parser)]
+        checkpoint_dir: PathBuf,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        enable_feature: Vec<FeatureFlag>,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        disable_feature: Vec<FeatureFlag>,
+    },
+    /// Runs raw transfers with each block size, and each transactions per sender value, one run
+    /// after the other on the same checkpoint
+    Sweep {
+        /// number of transfer blocks to run for each block size
+        #[clap(long, default_value_t = 100)]
+        blocks: usize,
+
+        #[clap(long, num_args = 1.., value_delimiter = ',', required = true)]
+        block_sizes: Vec<usize>,
+
+        /// --transactions-per-sender if not set
+        #[clap(long, num_args = 1.., value_delimiter = ',')]
+        transactions_per_sender_values: Vec<usize>,
+
+        #[clap(long, default_value_t = 1000000)]
+        main_signer_accounts: usize,
+
+        #[clap(long, value_parser)]
+        data_dir: PathBuf,
+
+        #[clap(long, value_parser)]
+        checkpoint_dir: PathBuf,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        enable_feature: Vec<FeatureFlag>,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        disable_feature: Vec<FeatureFlag>,
+    },
+    /// Runs raw transfers for a duration, with the pruners of --enable-*-pruner, reporting the
+    /// TPS, the size of the DB, its compactions and how far the pruners got at each interval
+    Soak {
+        /// how long to run for, in seconds, the last interval finishing after it
+        #[clap(long)]
+        duration_secs: u64,
+
+        /// number of blocks of each interval reported
+        #[clap(long, default_value_t = 1000)]
+        report_blocks: usize,
+
+        #[clap(long, default_value_t = 1000000)]
+        main_signer_accounts: usize,
+
+        #[clap(long, value_parser)]
+        data_dir: PathBuf,
+
+        #[clap(long, value_parser)]
+        checkpoint_dir: PathBuf,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        enable_feature: Vec<FeatureFlag>,
+
+        #[clap(
+            long,
+            num_args=1..,
+            value_delimiter = ' ',
+            help = "Optional custom enabling/disabling of the feature flags in the Move source. Enable / disable flags cannot overlap.\
+            Sample usage: --enable-feature=V1 --disable-feature=V2 V3 where V1, V2, V3 are FeatureFlag enum variants.")]
+        disable_feature: Vec<FeatureFlag>,
+    },
+    /// Replays the blocks from a backup that follow the DB in data_dir, a DB restored from the
+    /// same backup up to the end of a block
+    ReplayBackup {
+        /// number of blocks to replay
+        #[clap(long, default_value_t = 1000)]
+        blocks: usize,
+
+        #[clap(flatten)]
+        storage: DBToolStorageOpt,
+
+        #[clap(long, value_parser)]
+        metadata_cache_dir: Option<PathBuf>,
+
+        #[clap(long)]
+        concurrent_downloads: Option<usize>,
+
+        #[clap(long, value_parser)]
+        data_dir: PathBuf,
+
+        #[clap(long, value_parser)]
+        checkpoint_dir: PathBuf,
+    },
     AddAccounts {
         #[clap(long, value_parser)]
         data_dir: PathBuf,
@@ -396,21 +695,140 @@ fn get_init_features(
     init_features
 }
 
-fn run<E>(opt: Opt)
+/// The remote executor services spawned by the benchmark. They are killed and reaped on drop, so
+/// they don't outlive the benchmark when it fails or panics.
+#[derive(Default)]
+struct RemoteExecutors(Vec<Child>);
+
+impl Drop for RemoteExecutors {
+    fn drop(&mut self) {
+        for remote_executor in &mut self.0 {
+            if let Err(err) = remote_executor.kill() {
+                eprintln!("Failed to stop the remote executor service: {}", err);
+            }
+            if let Err(err) = remote_executor.wait() {
+                eprintln!("Failed to wait for the remote executor service: {}", err);
+            }
+        }
+    }
+}
+
+/// Starts a process executor service for each of `remote_addresses`, with `num_threads` each.
+fn spawn_remote_executors(
+    binary: &Path,
+    remote_addresses: &[SocketAddr],
+    coordinator_address: SocketAddr,
+    num_threads: usize,
+) -> RemoteExecutors {
+    let mut remote_executors = RemoteExecutors::default();
+    for shard_id in 0..remote_addresses.len() {
+        // Pushed one at a time, so the ones already started are stopped if a later one fails.
+        remote_executors.0.push(
+            std::process::Command::new(binary)
+                .arg("--shard-id")
+                .arg(shard_id.to_string())
+                .arg("--num-shards")
+                .arg(remote_addresses.len().to_string())
+                .arg("--num-executor-threads")
+                .arg(num_threads.to_string())
+                .arg("--coordinator-address")
+                .arg(coordinator_address.to_string())
+                .arg("--remote-executor-addresses")
+                .args(remote_addresses.iter().map(ToString::to_string))
+                .spawn()
+                .expect("Failed to start the remote executor service."),
+        );
+    }
+    remote_executors
+}
+
+/// Returns false if `results` did not end with the `expected` state root hash.
+fn check_state_root(expected: Option<HashValue>, results: &BenchmarkResults) -> bool {
+    if let Some(expected) = expected {
+        if results.state_root_hash != expected {
+            eprintln!(
+                "State root hash {} differs from the expected {}.",
+                results.state_root_hash, expected
+            );
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns false if `results` regressed from the JSON results at `baseline`.
+fn check_regressions(
+    baseline: Option<&Path>,
+    max_regression_pct: f64,
+    results: &BenchmarkResults,
+) -> bool {
+    if let Some(baseline) = baseline {
+        let baseline =
+            BenchmarkResults::read_json(baseline).expect("Failed to read the baseline results.");
+        let regressions = results.regressions(&baseline, max_regression_pct);
+        for regression in &regressions {
+            eprintln!(
+                "Regression of {:.1}% in {}: baseline {:.2}, current {:.2}",
+                regression.pct, regression.metric, regression.baseline, regression.current
+            );
+        }
+        if !regressions.is_empty() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Writes the Prometheus summaries of the block latencies of `results`, see
+/// `BenchmarkResults::write_latency_summaries`.
+fn write_latency_summaries(latency_summary_file: Option<&Path>, results: &BenchmarkResults) {
+    if let Some(path) = latency_summary_file {
+        let mut file = File::create(path).expect("Failed to create the latency summary file.");
+        results
+            .write_latency_summaries(&mut file)
+            .expect("Failed to write the latency summaries.");
+    }
+}
+
+fn write_results(
+    output_format: Option<OutputFormat>,
+    output_file: Option<&Path>,
+    results: &impl ResultsOutput,
+) {
+    if output_format.is_some() || output_file.is_some() {
+        results
+            .write(output_format.unwrap_or_default(), output_file)
+            .expect("Failed to write the results.");
+    }
+}
+
+/// Runs the benchmark command of `opt`, returning false if any of its checks failed.
+fn run<E>(opt: Opt) -> bool
 where
     E: TransactionBlockExecutor + 'static,
 {
+    let mut succeeded = true;
+    let hot_accounts = opt.hot_accounts.map(|num_accounts| HotAccounts {
+        num_accounts,
+        fraction: opt.hot_account_fraction.unwrap(),
+    });
     match opt.cmd {
         Command::CreateDb {
             data_dir,
             num_accounts,
             init_account_balance,
+            account_state,
             enable_feature,
             disable_feature,
         } => {
+            let account_state = account_state
+                .iter()
+                .map(|t| t.materialize(1, false, WorkflowProgress::MoveByPhases))
+                .collect::<Vec<_>>();
             aptos_executor_benchmark::db_generator::create_db_with_accounts::<E>(
                 num_accounts,
                 init_account_balance,
+                &account_state,
                 opt.block_size,
                 data_dir,
                 opt.pruner_opt.pruner_config(),
@@ -421,11 +839,13 @@ where
             );
         },
         Command::RunExecutor {
-            blocks,
+            mut blocks,
             main_signer_accounts,
             additional_dst_pool_accounts,
             transaction_type,
             transaction_weights,
+            transaction_phases,
+            phase_blocks,
             module_working_set_size,
             use_sender_account_pool,
             data_dir,
@@ -438,19 +858,26 @@ where
             //     disable_feature,
             // );
 
-            let transaction_mix = if transaction_type.is_empty() {
+            let transaction_mix_per_phase = if transaction_type.is_empty() {
                 None
             } else {
                 let mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
                     &transaction_type,
                     &transaction_weights,
-                    &[],
+                    &transaction_phases,
                     module_working_set_size,
                     use_sender_account_pool,
                     WorkflowProgress::MoveByPhases,
                 );
-                assert!(mix_per_phase.len() == 1);
-                Some(mix_per_phase[0].clone())
+                if !phase_blocks.is_empty() {
+                    assert_eq!(
+                        phase_blocks.len(),
+                        mix_per_phase.len(),
+                        "--phase-blocks needs the blocks of each phase"
+                    );
+                    blocks = phase_blocks.iter().sum();
+                }
+                Some(mix_per_phase)
             };
 
             if let Some(hotspot_probability) = opt.hotspot_probability {
@@ -461,24 +888,183 @@ where
                 }
             }
 
-            aptos_executor_benchmark::run_benchmark::<E>(
+            let results = aptos_executor_benchmark::run_benchmark::<E>(
                 opt.block_size,
                 blocks,
-                transaction_mix,
+                transaction_mix_per_phase,
+                phase_blocks,
                 opt.transactions_per_sender,
                 opt.connected_tx_grps,
                 opt.shuffle_connected_txns,
                 opt.hotspot_probability,
+                hot_accounts,
                 main_signer_accounts,
                 additional_dst_pool_accounts,
                 data_dir,
                 checkpoint_dir,
+                opt.reuse_checkpoint,
                 opt.verify_sequence_numbers,
                 opt.pruner_opt.pruner_config(),
                 opt.enable_storage_sharding,
                 opt.pipeline_opt.pipeline_config(),
                 get_init_features(enable_feature, disable_feature),
+                opt.seed,
+            );
+
+            write_results(opt.output_format, opt.output_file.as_deref(), &results);
+            write_latency_summaries(opt.latency_summary_file.as_deref(), &results);
+            succeeded &= check_state_root(opt.expected_state_root, &results);
+            succeeded &=
+                check_regressions(opt.baseline.as_deref(), opt.max_regression_pct, &results);
+        },
+        Command::CompareExecutors {
+            blocks,
+            main_signer_accounts,
+            data_dir,
+            checkpoint_dir,
+            enable_feature,
+            disable_feature,
+        } => {
+            let results = aptos_executor_benchmark::compare_executors::<AptosVM, E>(
+                opt.block_size,
+                blocks,
+                opt.transactions_per_sender,
+                opt.connected_tx_grps,
+                opt.shuffle_connected_txns,
+                opt.hotspot_probability,
+                hot_accounts,
+                main_signer_accounts,
+                data_dir,
+                checkpoint_dir,
+                opt.pruner_opt.pruner_config(),
+                opt.enable_storage_sharding,
+                opt.pipeline_opt.pipeline_config(),
+                get_init_features(enable_feature, disable_feature),
+                opt.seed,
+            );
+            write_results(opt.output_format, opt.output_file.as_deref(), &results);
+            if !results.is_consistent() {
+                eprintln!("The executors diverged, see the divergences in the results.");
+                succeeded = false;
+            }
+        },
+        Command::ApplyOutputs {
+            blocks,
+            main_signer_accounts,
+            data_dir,
+            checkpoint_dir,
+            enable_feature,
+            disable_feature,
+        } => {
+            let results = aptos_executor_benchmark::apply_outputs::<E>(
+                opt.block_size,
+                blocks,
+                opt.transactions_per_sender,
+                opt.connected_tx_grps,
+                opt.shuffle_connected_txns,
+                opt.hotspot_probability,
+                hot_accounts,
+                main_signer_accounts,
+                data_dir,
+                checkpoint_dir,
+                opt.pruner_opt.pruner_config(),
+                opt.enable_storage_sharding,
+                opt.pipeline_opt.pipeline_config(),
+                get_init_features(enable_feature, disable_feature),
+                opt.seed,
             );
+            write_results(opt.output_format, opt.output_file.as_deref(), &results);
+            write_latency_summaries(opt.latency_summary_file.as_deref(), &results);
+            succeeded &= check_state_root(opt.expected_state_root, &results);
+            succeeded &=
+                check_regressions(opt.baseline.as_deref(), opt.max_regression_pct, &results);
+        },
+        Command::Sweep {
+            blocks,
+            block_sizes,
+            mut transactions_per_sender_values,
+            main_signer_accounts,
+            data_dir,
+            checkpoint_dir,
+            enable_feature,
+            disable_feature,
+        } => {
+            if transactions_per_sender_values.is_empty() {
+                transactions_per_sender_values.push(opt.transactions_per_sender);
+            }
+            let results = aptos_executor_benchmark::sweep_block_sizes::<E>(
+                &block_sizes,
+                &transactions_per_sender_values,
+                blocks,
+                opt.connected_tx_grps,
+                opt.shuffle_connected_txns,
+                opt.hotspot_probability,
+                hot_accounts,
+                main_signer_accounts,
+                data_dir,
+                checkpoint_dir,
+                opt.reuse_checkpoint,
+                opt.pruner_opt.pruner_config(),
+                opt.enable_storage_sharding,
+                opt.pipeline_opt.pipeline_config(),
+                get_init_features(enable_feature, disable_feature),
+                opt.seed,
+            );
+            write_results(opt.output_format, opt.output_file.as_deref(), &results);
+        },
+        Command::Soak {
+            duration_secs,
+            report_blocks,
+            main_signer_accounts,
+            data_dir,
+            checkpoint_dir,
+            enable_feature,
+            disable_feature,
+        } => {
+            let results = aptos_executor_benchmark::soak::<E>(
+                opt.block_size,
+                report_blocks,
+                Duration::from_secs(duration_secs),
+                opt.transactions_per_sender,
+                opt.connected_tx_grps,
+                opt.shuffle_connected_txns,
+                opt.hotspot_probability,
+                hot_accounts,
+                main_signer_accounts,
+                data_dir,
+                checkpoint_dir,
+                opt.reuse_checkpoint,
+                opt.pruner_opt.pruner_config(),
+                opt.enable_storage_sharding,
+                opt.pipeline_opt.pipeline_config(),
+                get_init_features(enable_feature, disable_feature),
+                opt.seed,
+            );
+            write_results(opt.output_format, opt.output_file.as_deref(), &results);
+        },
+        Command::ReplayBackup {
+            blocks,
+            storage,
+            metadata_cache_dir,
+            concurrent_downloads,
+            data_dir,
+            checkpoint_dir,
+        } => {
+            let results = aptos_executor_benchmark::replay_backup::<E>(
+                storage,
+                MetadataCacheOpt::new(metadata_cache_dir),
+                concurrent_downloads.unwrap_or_else(num_cpus::get),
+                blocks,
+                data_dir,
+                checkpoint_dir,
+                opt.reuse_checkpoint,
+                opt.pruner_opt.pruner_config(),
+                opt.enable_storage_sharding,
+                opt.pipeline_opt.pipeline_config(),
+            );
+            write_results(opt.output_format, opt.output_file.as_deref(), &results);
+            write_latency_summaries(opt.latency_summary_file.as_deref(), &results);
+            succeeded &= check_state_root(opt.expected_state_root, &results);
         },
         Command::AddAccounts {
             data_dir,
@@ -500,6 +1086,7 @@ where
             );
         },
     }
+    succeeded
 }
 
 fn main() {
@@ -532,6 +1119,7 @@ fn main() {
         execution_threads_per_shard = execution_threads / execution_shards;
     }
 
+    let mut remote_executors = RemoteExecutors::default();
     if opt
         .pipeline_opt
         .sharding_opt
@@ -555,6 +1143,14 @@ fn main() {
         remote_executor_client::set_coordinator_address(
             opt.pipeline_opt.sharding_opt.coordinator_address.unwrap(),
         );
+        if let Some(binary) = &opt.pipeline_opt.sharding_opt.spawn_remote_executors {
+            remote_executors = spawn_remote_executors(
+                binary,
+                &remote_executor_client::get_remote_addresses(),
+                remote_executor_client::get_coordinator_address(),
+                execution_threads_per_shard,
+            );
+        }
         // it does not matter because shards are on remote node, but for sake of correctness lets
         // set it
         execution_threads_per_shard = execution_threads;
@@ -584,15 +1180,17 @@ fn main() {
         let _mem_start = memory_profiler.start_profiling();
     }
 
-    if opt.vm_selection_opt.use_native_executor {
-        run::<NativeExecutor>(opt);
+    let succeeded = if opt.vm_selection_opt.use_native_executor {
+        run::<NativeExecutor>(opt)
     } else if opt.vm_selection_opt.use_ptx_executor {
         #[cfg(target_os = "linux")]
         ThreadManagerBuilder::set_thread_config_strategy(ThreadConfigStrategy::ThreadsPriority(48));
-        run::<PtxBlockExecutor>(opt);
+        run::<PtxBlockExecutor>(opt)
     } else {
-        run::<AptosVM>(opt);
-    }
+        run::<AptosVM>(opt)
+    };
+
+    drop(remote_executors);
 
     if cpu_profiling {
         let _cpu_end = cpu_profiler.end_profiling("");
@@ -600,6 +1198,10 @@ fn main() {
     if memory_profiling {
         let _mem_end = memory_profiler.end_profiling("./target/release/aptos-executor-benchmark");
     }
+
+    if !succeeded {
+        std::process::exit(1);
+    }
 }
 
 #[test]
@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_executor::{
+    block_executor::TransactionBlockExecutor, components::chunk_output::ChunkOutput,
+};
+use aptos_storage_interface::{cached_state_view::CachedStateView, DbReader, MAX_REQUEST_LIMIT};
+use aptos_types::{
+    block_executor::{config::BlockExecutorConfigFromOnchain, partitioner::ExecutableTransactions},
+    transaction::{Transaction, TransactionOutput, Version},
+};
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Outputs to apply, by transaction hash.
+static OUTPUTS: Lazy<Mutex<HashMap<HashValue, TransactionOutput>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Applies the outputs the transactions got when they were executed and committed elsewhere,
+/// instead of executing them, as state sync does with the transaction outputs it receives.
+pub struct OutputApplier {}
+
+impl OutputApplier {
+    /// Reads `num_txns` committed transactions from `first_version` on, with their outputs to be
+    /// applied, returning the transactions split back into the blocks they were committed in.
+    pub fn load_committed_blocks(
+        db: &dyn DbReader,
+        first_version: Version,
+        num_txns: u64,
+    ) -> Result<Vec<Vec<Transaction>>> {
+        let ledger_version = first_version + num_txns - 1;
+        let mut outputs = OUTPUTS.lock().unwrap();
+        let mut blocks = vec![];
+        let mut block = vec![];
+        let mut version = first_version;
+        while version <= ledger_version {
+            let chunk = db.get_transaction_outputs(version, MAX_REQUEST_LIMIT, ledger_version)?;
+            if chunk.transactions_and_outputs.is_empty() {
+                return Err(anyhow!("No transaction at version {}.", version));
+            }
+            version += chunk.transactions_and_outputs.len() as u64;
+            for (txn, output) in chunk.transactions_and_outputs {
+                match txn {
+                    // Added by the executor at the end of each block.
+                    Transaction::StateCheckpoint(_) | Transaction::BlockEpilogue(_) => {
+                        if !block.is_empty() {
+                            blocks.push(std::mem::take(&mut block));
+                        }
+                    },
+                    _ => {
+                        outputs.insert(txn.hash(), output);
+                        block.push(txn);
+                    },
+                }
+            }
+        }
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+}
+
+impl TransactionBlockExecutor for OutputApplier {
+    fn execute_transaction_block(
+        transactions: ExecutableTransactions,
+        state_view: CachedStateView,
+        _onchain_config: BlockExecutorConfigFromOnchain,
+    ) -> Result<ChunkOutput> {
+        let transactions = match transactions {
+            ExecutableTransactions::Unsharded(txns) => txns,
+            _ => todo!("sharded execution not yet supported"),
+        };
+        let mut outputs = OUTPUTS.lock().unwrap();
+        let transactions_and_outputs = transactions
+            .into_iter()
+            .map(|txn| {
+                let txn = txn.into_inner();
+                let hash = txn.hash();
+                let output = outputs
+                    .remove(&hash)
+                    .ok_or_else(|| anyhow!("No output to apply for {}.", hash))?;
+                Ok((txn, output))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        drop(outputs);
+        ChunkOutput::by_transaction_output(transactions_and_outputs, state_view)
+    }
+}
@@ -4,7 +4,10 @@
 use crate::{
     block_preparation::BlockPreparationStage,
     ledger_update_stage::{CommitProcessing, LedgerUpdateStage},
-    metrics::NUM_TXNS,
+    metrics::{NUM_TXNS, TIMER},
+    results::{sample_memory, BenchmarkResults, SharedResults},
+    stage_profiler::StageProfiler,
+    transaction_executor::benchmarks_block_executor_onchain_config,
     OverallMeasuring, TransactionCommitter, TransactionExecutor,
 };
 use aptos_block_partitioner::v2::config::PartitionerV2Config;
@@ -19,9 +22,10 @@ use aptos_types::{
 use derivative::Derivative;
 use std::{
     marker::PhantomData,
+    ops::Range,
     sync::{
         mpsc::{self, SyncSender},
-        Arc,
+        Arc, Mutex,
     },
     thread::JoinHandle,
     time::{Duration, Instant},
@@ -42,12 +46,53 @@ pub struct PipelineConfig {
     #[derivative(Default(value = "4"))]
     pub num_generator_workers: usize,
     pub partitioner_config: PartitionerV2Config,
+    /// Cuts blocks at this effective gas, the transactions left out are retried.
+    pub block_gas_limit: Option<u64>,
+    /// First blocks, warming the caches, reported apart from the steady state that follows.
+    pub warmup_blocks: usize,
+    /// Last committed blocks the rolling TPS, logged with each block, is over.
+    #[derivative(Default(value = "10"))]
+    pub rolling_tps_blocks: usize,
+    /// Blocks to profile the CPU of, writing a flamegraph of each stage, see `StageProfiler`.
+    pub profile_blocks: Option<Range<usize>>,
+    /// Streams the committed transactions as the indexer-grpc fullnode does, alongside.
+    pub indexer_stream: bool,
+    /// Blocks each channel between the stages holds before its sender blocks, see
+    /// `CHANNEL_SEND_LABELS` for the time spent blocked. Channels that must hold all the blocks,
+    /// with `delay_execution_start`, `split_stages` or `skip_commit`, are never smaller.
+    #[derivative(Default(value = "10"))]
+    pub raw_block_channel_size: usize,
+    #[derivative(Default(value = "3"))]
+    pub executable_block_channel_size: usize,
+    #[derivative(Default(value = "3"))]
+    pub ledger_update_channel_size: usize,
+    #[derivative(Default(value = "3"))]
+    pub commit_channel_size: usize,
+}
+
+/// Labels of the `TIMER` of each stage sending to the next one, timing backpressure.
+pub(crate) const CHANNEL_SEND_LABELS: &[&str] = &[
+    "send_raw_block",
+    "send_executable_block",
+    "send_ledger_update",
+    "send_commit_block",
+];
+
+/// Time spent sending on each of `CHANNEL_SEND_LABELS` so far.
+fn channel_send_secs() -> Vec<f64> {
+    CHANNEL_SEND_LABELS
+        .iter()
+        .map(|label| TIMER.with_label_values(&[label]).get_sample_sum())
+        .collect()
 }
 
 pub struct Pipeline<V> {
     join_handles: Vec<JoinHandle<()>>,
     phantom: PhantomData<V>,
     start_execution_tx: Option<SyncSender<()>>,
+    results: SharedResults,
+    /// Time spent sending on each of `CHANNEL_SEND_LABELS` before the pipeline.
+    channel_send_secs_before: Vec<f64>,
 }
 
 impl<V> Pipeline<V>
@@ -68,9 +113,11 @@ where
 
         let (raw_block_sender, raw_block_receiver) = mpsc::sync_channel::<Vec<Transaction>>(
             if config.delay_execution_start {
-                (num_blocks.unwrap() + 1).max(50)
+                (num_blocks.unwrap() + 1)
+                    .max(50)
+                    .max(config.raw_block_channel_size)
             } else {
-                10
+                config.raw_block_channel_size
             }, /* bound */
         );
 
@@ -80,17 +127,17 @@ where
         let (ledger_update_sender, ledger_update_receiver) =
             mpsc::sync_channel::<LedgerUpdateMessage>(
                 if config.split_stages || config.skip_commit {
-                    (num_blocks.unwrap() + 1).max(3)
+                    (num_blocks.unwrap() + 1).max(config.ledger_update_channel_size)
                 } else {
-                    3
+                    config.ledger_update_channel_size
                 }, /* bound */
             );
 
         let (commit_sender, commit_receiver) = mpsc::sync_channel::<CommitBlockMessage>(
             if config.split_stages {
-                (num_blocks.unwrap() + 1).max(3)
+                (num_blocks.unwrap() + 1).max(config.commit_channel_size)
             } else {
-                3
+                config.commit_channel_size
             }, /* bound */
         );
 
@@ -109,6 +156,25 @@ where
         };
 
         let mut join_handles = vec![];
+        let results = Arc::new(Mutex::new(BenchmarkResults::default()));
+        let partitioning_results = results.clone();
+        let exe_results = results.clone();
+        let ledger_update_results = results.clone();
+        let commit_results = results.clone();
+        let warmup_blocks = config.warmup_blocks;
+        let rolling_tps_blocks = config.rolling_tps_blocks;
+        let (stage_profiler, stage_profiler_thread) = config
+            .profile_blocks
+            .clone()
+            .map(StageProfiler::spawn)
+            .unzip();
+        let exe_profiler = stage_profiler.clone();
+        // The last stage reports the blocks done.
+        let (ledger_update_profiler, commit_profiler) = if config.skip_commit {
+            (stage_profiler, None)
+        } else {
+            (None, stage_profiler)
+        };
 
         let mut partitioning_stage =
             BlockPreparationStage::new(num_partitioner_shards, &config.partitioner_config);
@@ -120,6 +186,7 @@ where
             config.allow_aborts,
             config.allow_discards,
             config.allow_retries,
+            benchmarks_block_executor_onchain_config(config.block_gas_limit),
         );
 
         let commit_processing = if config.skip_commit {
@@ -131,7 +198,7 @@ where
             LedgerUpdateStage::new(executor_2, commit_processing, version);
 
         let (executable_block_sender, executable_block_receiver) =
-            mpsc::sync_channel::<ExecuteBlockMessage>(3);
+            mpsc::sync_channel::<ExecuteBlockMessage>(config.executable_block_channel_size);
 
         let partitioning_thread = std::thread::Builder::new()
             .name("block_partitioning".to_string())
@@ -141,6 +208,16 @@ where
                         .with_label_values(&["partition"])
                         .inc_by(txns.len() as u64);
                     let exe_block_msg = partitioning_stage.process(txns);
+                    sample_memory(
+                        &mut partitioning_results
+                            .lock()
+                            .unwrap()
+                            .memory_samples
+                            .partitioning,
+                    );
+                    let _timer = TIMER
+                        .with_label_values(&["send_executable_block"])
+                        .start_timer();
                     executable_block_sender.send(exe_block_msg).unwrap();
                 }
             })
@@ -157,6 +234,7 @@ where
                 let mut stage_index = 0;
                 let mut stage_overall_measuring = overall_measuring.clone();
                 let mut stage_executed = 0;
+                let mut num_blocks_started = 0;
 
                 while let Ok(msg) = executable_block_receiver.recv() {
                     let ExecuteBlockMessage {
@@ -171,7 +249,16 @@ where
                     info!("Received block of size {:?} to execute", block_size);
                     executed += block_size;
                     stage_executed += block_size;
-                    exe.execute_block(current_block_start_time, partition_time, block);
+                    if let Some(profiler) = &exe_profiler {
+                        profiler.block_started(num_blocks_started);
+                    }
+                    num_blocks_started += 1;
+                    let execution_time =
+                        exe.execute_block(current_block_start_time, partition_time, block);
+                    let mut results = exe_results.lock().unwrap();
+                    results.stage_samples.execution.push(execution_time);
+                    sample_memory(&mut results.memory_samples.execution);
+                    drop(results);
                     info!("Finished executing block");
 
                     // Empty blocks indicate the end of a stage.
@@ -179,10 +266,11 @@ where
                     if block_size == 0 {
                         if stage_executed > 0 {
                             info!("Execution finished stage {}", stage_index);
-                            stage_overall_measuring.print_end(
+                            let stage_result = stage_overall_measuring.print_end(
                                 &format!("Staged execution: stage {}:", stage_index),
                                 stage_executed,
                             );
+                            exe_results.lock().unwrap().overall.push(stage_result);
                         }
                         stage_index += 1;
                         stage_overall_measuring = OverallMeasuring::start();
@@ -192,14 +280,20 @@ where
 
                 if stage_index > 0 && stage_executed > 0 {
                     info!("Execution finished stage {}", stage_index);
-                    stage_overall_measuring.print_end(
+                    let stage_result = stage_overall_measuring.print_end(
                         &format!("Staged execution: stage {}:", stage_index),
                         stage_executed,
                     );
+                    exe_results.lock().unwrap().overall.push(stage_result);
                 }
 
                 if num_blocks.is_some() {
-                    overall_measuring.print_end("Overall execution", executed);
+                    let overall_result = overall_measuring.print_end("Overall execution", executed);
+                    exe_results
+                        .lock()
+                        .unwrap()
+                        .overall
+                        .insert(0, overall_result);
                 }
                 start_commit_tx.map(|tx| tx.send(()));
             })
@@ -209,6 +303,7 @@ where
         let ledger_update_thread = std::thread::Builder::new()
             .name("ledger_update".to_string())
             .spawn(move || {
+                let mut num_blocks_done = 0;
                 while let Ok(ledger_update_msg) = ledger_update_receiver.recv() {
                     let input_block_size =
                         ledger_update_msg.state_checkpoint_output.input_txns_len();
@@ -216,6 +311,17 @@ where
                         .with_label_values(&["ledger_update"])
                         .inc_by(input_block_size as u64);
                     ledger_update_stage.ledger_update(ledger_update_msg);
+                    sample_memory(
+                        &mut ledger_update_results
+                            .lock()
+                            .unwrap()
+                            .memory_samples
+                            .ledger_update,
+                    );
+                    if let Some(profiler) = &ledger_update_profiler {
+                        profiler.block_done(num_blocks_done);
+                    }
+                    num_blocks_done += 1;
                 }
             })
             .expect("Failed to spawn ledger update thread.");
@@ -227,19 +333,31 @@ where
                 .spawn(move || {
                     start_commit_rx.map(|rx| rx.recv());
                     info!("Starting commit thread");
-                    let mut committer =
-                        TransactionCommitter::new(executor_3, version, commit_receiver);
+                    let mut committer = TransactionCommitter::new(
+                        executor_3,
+                        version,
+                        commit_receiver,
+                        commit_results,
+                        warmup_blocks,
+                        rolling_tps_blocks,
+                        commit_profiler,
+                    );
                     committer.run();
                 })
                 .expect("Failed to spawn transaction committer thread.");
             join_handles.push(commit_thread);
         }
 
+        // Last, as it ends once the stages are done.
+        join_handles.extend(stage_profiler_thread);
+
         (
             Self {
                 join_handles,
                 phantom: PhantomData,
                 start_execution_tx,
+                results,
+                channel_send_secs_before: channel_send_secs(),
             },
             raw_block_sender,
         )
@@ -249,10 +367,22 @@ where
         self.start_execution_tx.as_ref().map(|tx| tx.send(()));
     }
 
-    pub fn join(self) {
+    /// Waits for all stages to finish, returning the per-block and execution stage results.
+    pub fn join(self) -> BenchmarkResults {
         for handle in self.join_handles {
             handle.join().unwrap()
         }
+        for ((label, secs), secs_before) in CHANNEL_SEND_LABELS
+            .iter()
+            .zip(channel_send_secs())
+            .zip(self.channel_send_secs_before)
+        {
+            info!("Blocked in {}: {:.3} secs", label, secs - secs_before);
+        }
+        Arc::into_inner(self.results)
+            .expect("All stages are done.")
+            .into_inner()
+            .unwrap()
     }
 }
 
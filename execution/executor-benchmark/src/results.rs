@@ -0,0 +1,711 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine readable results of a benchmark run, alongside what is logged.
+
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_logger::info;
+use aptos_types::transaction::Version;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    /// One `scope,version,metric,value` row per number.
+    Csv,
+}
+
+/// Numbers of a single block, as reported once it is committed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockResult {
+    /// Version of the last transaction of the block.
+    pub version: Version,
+    pub num_txns: usize,
+    /// From the block entering the pipeline to the end of its commit.
+    pub latency_ms: f64,
+    pub partition_ms: f64,
+    pub execution_ms: f64,
+    pub commit_ms: f64,
+    /// TPS of the slowest of partitioning, execution and commit for this block.
+    pub tps: f64,
+    /// TPS since the first block entered the pipeline.
+    pub accumulative_tps: f64,
+    /// TPS over the last `PipelineConfig::rolling_tps_blocks` committed blocks, this one included.
+    #[serde(default)]
+    pub rolling_tps: f64,
+}
+
+/// Numbers over a set of blocks, as logged with `prefix` by `OverallMeasuring::print_end`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverallResult {
+    pub name: String,
+    pub num_txns: u64,
+    pub elapsed_secs: f64,
+    pub tps: f64,
+    pub vm_tps: f64,
+    pub gps: f64,
+    pub effective_gps: f64,
+    pub io_gps: f64,
+    pub execution_gps: f64,
+    pub gas_per_txn: f64,
+    pub speculative_aborts_per_txn: f64,
+    pub approx_output_bytes_per_sec: f64,
+    pub output_bytes_per_sec: f64,
+    /// Fractions of the total time spent in each stage.
+    pub partitioning_fraction: f64,
+    pub execution_fraction: f64,
+    pub ledger_update_fraction: f64,
+    pub commit_fraction: f64,
+    /// Fractions of the execution time spent in the VM and the other executor timers.
+    pub vm_fraction_of_execution: f64,
+    pub other_fractions_of_execution: Vec<(String, f64)>,
+}
+
+/// Percentiles of the time blocks spent in a stage.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageLatencies {
+    pub stage: String,
+    pub num_blocks: usize,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl StageLatencies {
+    /// None without samples.
+    pub fn from_samples(stage: &str, samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut samples_ms = samples
+            .iter()
+            .map(|sample| sample.as_secs_f64() * 1000.0)
+            .collect::<Vec<_>>();
+        samples_ms.sort_by(f64::total_cmp);
+        // nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (p * samples_ms.len() + 99) / 100;
+            samples_ms[rank - 1]
+        };
+        Some(Self {
+            stage: stage.to_string(),
+            num_blocks: samples_ms.len(),
+            p50_ms: percentile(50),
+            p90_ms: percentile(90),
+            p99_ms: percentile(99),
+            max_ms: percentile(100),
+        })
+    }
+}
+
+/// Resident memory of the process, sampled each time a stage is done with a block.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageMemory {
+    pub stage: String,
+    pub num_samples: usize,
+    pub peak_bytes: u64,
+    pub average_bytes: u64,
+}
+
+impl StageMemory {
+    /// None without samples.
+    pub fn from_samples(stage: &str, samples: &[u64]) -> Option<Self> {
+        Some(Self {
+            stage: stage.to_string(),
+            num_samples: samples.len(),
+            peak_bytes: *samples.iter().max()?,
+            average_bytes: samples.iter().sum::<u64>() / samples.len() as u64,
+        })
+    }
+}
+
+/// Resident memory samples of each stage, see `StageMemory`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct MemorySamples {
+    pub partitioning: Vec<u64>,
+    pub execution: Vec<u64>,
+    pub ledger_update: Vec<u64>,
+    pub commit: Vec<u64>,
+}
+
+/// Adds the current resident memory to `samples`, if the platform reports it.
+pub(crate) fn sample_memory(samples: &mut Vec<u64>) {
+    if let Some(stats) = memory_stats::memory_stats() {
+        samples.push(stats.physical_mem as u64);
+    }
+}
+
+/// Time each block spent in the stages, in the order blocks went through them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct StageSamples {
+    pub generation: Vec<Duration>,
+    pub execution: Vec<Duration>,
+    pub commit: Vec<Duration>,
+}
+
+/// The indexer-grpc fullnode stream, run alongside the pipeline.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexerStreamResult {
+    pub num_txns: u64,
+    /// From the start of the pipeline to the stream getting to the last committed version.
+    pub elapsed_secs: f64,
+    pub tps: f64,
+    /// Time the stream took to get to the last committed version, once committed.
+    pub lag_secs: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResults {
+    /// Committed version before the first block.
+    pub start_version: Version,
+    /// Committed version after the last block.
+    pub end_version: Version,
+    /// Root hash of the state after the last block.
+    #[serde(default)]
+    pub state_root_hash: HashValue,
+    /// "Overall" for the whole run, then the execution stage and its stages, if any, then the
+    /// warm-up and the steady state when there are warm-up blocks.
+    pub overall: Vec<OverallResult>,
+    /// Per stage percentiles, set by `summarize_stages`.
+    pub stage_latencies: Vec<StageLatencies>,
+    /// Per stage memory, set by `summarize_stages`.
+    pub stage_memory: Vec<StageMemory>,
+    /// Set when the indexer stream runs alongside.
+    #[serde(default)]
+    pub indexer_stream: Option<IndexerStreamResult>,
+    /// Empty when commit is skipped.
+    pub blocks: Vec<BlockResult>,
+    #[serde(skip)]
+    pub(crate) stage_samples: StageSamples,
+    #[serde(skip)]
+    pub(crate) memory_samples: MemorySamples,
+}
+
+/// Shared between the pipeline threads, each adding what it measures.
+pub(crate) type SharedResults = Arc<Mutex<BenchmarkResults>>;
+
+impl BenchmarkResults {
+    /// Computes and logs the latency percentiles and the memory of each stage with samples.
+    pub(crate) fn summarize_stages(&mut self) {
+        let StageSamples {
+            generation,
+            execution,
+            commit,
+        } = &self.stage_samples;
+        self.stage_latencies = [
+            ("generation", generation),
+            ("execution", execution),
+            ("commit", commit),
+        ]
+        .into_iter()
+        .filter_map(|(stage, samples)| StageLatencies::from_samples(stage, samples))
+        .collect();
+        for latencies in &self.stage_latencies {
+            info!(
+                "{} latency: p50 {:.1} ms, p90 {:.1} ms, p99 {:.1} ms, max {:.1} ms (over {} blocks)",
+                latencies.stage,
+                latencies.p50_ms,
+                latencies.p90_ms,
+                latencies.p99_ms,
+                latencies.max_ms,
+                latencies.num_blocks,
+            );
+        }
+
+        let MemorySamples {
+            partitioning,
+            execution,
+            ledger_update,
+            commit,
+        } = &self.memory_samples;
+        self.stage_memory = [
+            ("partitioning", partitioning),
+            ("execution", execution),
+            ("ledger_update", ledger_update),
+            ("commit", commit),
+        ]
+        .into_iter()
+        .filter_map(|(stage, samples)| StageMemory::from_samples(stage, samples))
+        .collect();
+        const MIB: f64 = (1 << 20) as f64;
+        for memory in &self.stage_memory {
+            info!(
+                "{} memory: peak {:.0} MiB, average {:.0} MiB (over {} blocks)",
+                memory.stage,
+                memory.peak_bytes as f64 / MIB,
+                memory.average_bytes as f64 / MIB,
+                memory.num_samples,
+            );
+        }
+    }
+}
+
+/// A number that got worse than in the baseline results, by `pct` percent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Regression {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    pub pct: f64,
+}
+
+impl BenchmarkResults {
+    /// Reads results a previous run wrote as JSON.
+    pub fn read_json(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_reader(File::open(path)?)?)
+    }
+
+    /// Compares the TPS of the overall results, and the latency percentiles of the stages, with
+    /// those of the same name in `baseline`, returning the ones worse by more than
+    /// `max_regression_pct` percent.
+    pub fn regressions(&self, baseline: &Self, max_regression_pct: f64) -> Vec<Regression> {
+        let mut regressions = vec![];
+        let mut compare = |metric: String, baseline: f64, current: f64, higher_is_better| {
+            if baseline == 0.0 {
+                return;
+            }
+            let pct = if higher_is_better {
+                (baseline - current) / baseline * 100.0
+            } else {
+                (current - baseline) / baseline * 100.0
+            };
+            if pct > max_regression_pct {
+                regressions.push(Regression {
+                    metric,
+                    baseline,
+                    current,
+                    pct,
+                });
+            }
+        };
+        for result in &self.overall {
+            if let Some(baseline) = baseline.overall.iter().find(|b| b.name == result.name) {
+                compare(
+                    format!("{} tps", result.name),
+                    baseline.tps,
+                    result.tps,
+                    true,
+                );
+            }
+        }
+        for latencies in &self.stage_latencies {
+            if let Some(baseline) = baseline
+                .stage_latencies
+                .iter()
+                .find(|b| b.stage == latencies.stage)
+            {
+                for (percentile, baseline_ms, current_ms) in [
+                    ("p50", baseline.p50_ms, latencies.p50_ms),
+                    ("p90", baseline.p90_ms, latencies.p90_ms),
+                    ("p99", baseline.p99_ms, latencies.p99_ms),
+                ] {
+                    compare(
+                        format!("{} latency {}_ms", latencies.stage, percentile),
+                        baseline_ms,
+                        current_ms,
+                        false,
+                    );
+                }
+            }
+        }
+        regressions
+    }
+}
+
+/// Name of the summaries written by `BenchmarkResults::write_latency_summaries`.
+const LATENCY_SUMMARY_NAME: &str = "aptos_executor_benchmark_block_latency_seconds";
+
+/// Quantiles of the latency summaries, in thousandths.
+const LATENCY_SUMMARY_QUANTILES: &[usize] = &[500, 900, 990, 999, 1000];
+
+impl BenchmarkResults {
+    /// Writes the latencies of the committed blocks in the Prometheus text format, as a summary
+    /// labeled with each stage, and "pipeline" for the whole of it.
+    pub fn write_latency_summaries(&self, writer: &mut impl Write) -> Result<()> {
+        writeln!(
+            writer,
+            "# HELP {} Time each block spent in the stages of the pipeline.",
+            LATENCY_SUMMARY_NAME
+        )?;
+        writeln!(writer, "# TYPE {} summary", LATENCY_SUMMARY_NAME)?;
+        if self.blocks.is_empty() {
+            return Ok(());
+        }
+        let stages: [(&str, fn(&BlockResult) -> f64); 4] = [
+            ("pipeline", |block| block.latency_ms),
+            ("partitioning", |block| block.partition_ms),
+            ("execution", |block| block.execution_ms),
+            ("commit", |block| block.commit_ms),
+        ];
+        for (stage, latency_ms) in stages {
+            let mut samples = self
+                .blocks
+                .iter()
+                .map(|block| latency_ms(block) / 1000.0)
+                .collect::<Vec<_>>();
+            samples.sort_by(f64::total_cmp);
+            for quantile in LATENCY_SUMMARY_QUANTILES {
+                // nearest-rank
+                let rank = ((quantile * samples.len() + 999) / 1000).max(1);
+                writeln!(
+                    writer,
+                    "{}{{stage=\"{}\",quantile=\"{}\"}} {}",
+                    LATENCY_SUMMARY_NAME,
+                    stage,
+                    *quantile as f64 / 1000.0,
+                    samples[rank - 1]
+                )?;
+            }
+            writeln!(
+                writer,
+                "{}_sum{{stage=\"{}\"}} {}",
+                LATENCY_SUMMARY_NAME,
+                stage,
+                samples.iter().sum::<f64>()
+            )?;
+            writeln!(
+                writer,
+                "{}_count{{stage=\"{}\"}} {}",
+                LATENCY_SUMMARY_NAME,
+                stage,
+                samples.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// One run of a sweep over block sizes and transactions per sender.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SweepRun {
+    pub block_size: usize,
+    pub transactions_per_sender: usize,
+    pub tps: f64,
+    pub results: BenchmarkResults,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SweepResults {
+    pub runs: Vec<SweepRun>,
+}
+
+impl SweepResults {
+    pub fn log_table(&self) {
+        info!("Sweep results:");
+        info!(
+            "{:>12} {:>24} {:>12}",
+            "block_size", "transactions_per_sender", "TPS"
+        );
+        for run in &self.runs {
+            info!(
+                "{:>12} {:>24} {:>12.0}",
+                run.block_size, run.transactions_per_sender, run.tps
+            );
+        }
+    }
+}
+
+/// What a soak run measured over one interval, and the state of the DB at its end.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoakReport {
+    /// Since the start of the soak run.
+    pub elapsed_secs: f64,
+    pub end_version: Version,
+    pub tps: f64,
+    /// Size of the files of the DB on disk.
+    pub db_size_bytes: u64,
+    /// Over all the column families, as last reported by the DB.
+    pub pending_compaction_bytes: i64,
+    pub running_compactions: i64,
+    /// Oldest version the ledger and the state merkle pruners kept, 0 with them disabled.
+    pub ledger_min_readable_version: i64,
+    pub state_merkle_min_readable_version: i64,
+    pub results: BenchmarkResults,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoakResults {
+    pub reports: Vec<SoakReport>,
+}
+
+impl SoakResults {
+    pub fn log_table(&self) {
+        const MIB: f64 = (1 << 20) as f64;
+        info!("Soak results:");
+        info!(
+            "{:>10} {:>14} {:>10} {:>12} {:>20} {:>12}",
+            "elapsed_s", "version", "TPS", "db_MiB", "pending_compact_MiB", "compactions"
+        );
+        for report in &self.reports {
+            info!(
+                "{:>10.0} {:>14} {:>10.0} {:>12.0} {:>20.0} {:>12}",
+                report.elapsed_secs,
+                report.end_version,
+                report.tps,
+                report.db_size_bytes as f64 / MIB,
+                report.pending_compaction_bytes as f64 / MIB,
+                report.running_compactions,
+            );
+        }
+        if let (Some(first), Some(last)) = (self.reports.first(), self.reports.last()) {
+            if first.tps > 0.0 {
+                info!(
+                    "TPS changed by {:.1}% from the first interval to the last.",
+                    (last.tps - first.tps) / first.tps * 100.0
+                );
+            }
+        }
+    }
+}
+
+/// Results written out as JSON, or as CSV rows of `scope,version,metric,value`.
+pub trait ResultsOutput: Serialize {
+    /// Writes the CSV rows, with `prefix` before each scope.
+    fn write_csv_rows(&self, writer: &mut csv::Writer<Box<dyn Write>>, prefix: &str) -> Result<()>;
+
+    /// Writes to `output_file`, or stdout.
+    fn write(&self, format: OutputFormat, output_file: Option<&Path>) -> Result<()> {
+        let mut output: Box<dyn Write> = match output_file {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        };
+        match format {
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut output, self)?;
+                writeln!(output)?;
+            },
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(output);
+                writer.write_record(["scope", "version", "metric", "value"])?;
+                self.write_csv_rows(&mut writer, "")?;
+                writer.flush()?;
+            },
+        }
+        Ok(())
+    }
+}
+
+impl ResultsOutput for BenchmarkResults {
+    fn write_csv_rows(&self, writer: &mut csv::Writer<Box<dyn Write>>, prefix: &str) -> Result<()> {
+        for result in &self.overall {
+            write_csv_rows(
+                writer,
+                &format!("{}{}", prefix, result.name),
+                "",
+                "",
+                &serde_json::to_value(result)?,
+            )?;
+        }
+        for latencies in &self.stage_latencies {
+            write_csv_rows(
+                writer,
+                &format!("{}{} latency", prefix, latencies.stage),
+                "",
+                "",
+                &serde_json::to_value(latencies)?,
+            )?;
+        }
+        for memory in &self.stage_memory {
+            write_csv_rows(
+                writer,
+                &format!("{}{} memory", prefix, memory.stage),
+                "",
+                "",
+                &serde_json::to_value(memory)?,
+            )?;
+        }
+        if let Some(indexer_stream) = &self.indexer_stream {
+            write_csv_rows(
+                writer,
+                &format!("{}indexer stream", prefix),
+                "",
+                "",
+                &serde_json::to_value(indexer_stream)?,
+            )?;
+        }
+        for block in &self.blocks {
+            write_csv_rows(
+                writer,
+                &format!("{}block", prefix),
+                &block.version.to_string(),
+                "",
+                &serde_json::to_value(block)?,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ResultsOutput for SweepResults {
+    fn write_csv_rows(&self, writer: &mut csv::Writer<Box<dyn Write>>, prefix: &str) -> Result<()> {
+        for run in &self.runs {
+            run.results.write_csv_rows(
+                writer,
+                &format!(
+                    "{}block_size={} transactions_per_sender={} ",
+                    prefix, run.block_size, run.transactions_per_sender
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ResultsOutput for SoakResults {
+    fn write_csv_rows(&self, writer: &mut csv::Writer<Box<dyn Write>>, prefix: &str) -> Result<()> {
+        for report in &self.reports {
+            let scope = format!("{}elapsed_secs={:.0} ", prefix, report.elapsed_secs);
+            write_csv_rows(
+                writer,
+                &format!("{}soak", scope),
+                &report.end_version.to_string(),
+                "",
+                &serde_json::json!({
+                    "tps": report.tps,
+                    "db_size_bytes": report.db_size_bytes,
+                    "pending_compaction_bytes": report.pending_compaction_bytes,
+                    "running_compactions": report.running_compactions,
+                    "ledger_min_readable_version": report.ledger_min_readable_version,
+                    "state_merkle_min_readable_version": report.state_merkle_min_readable_version,
+                }),
+            )?;
+            report.results.write_csv_rows(writer, &scope)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a row for each number in `value`, nested fields named `outer.inner`.
+pub(crate) fn write_csv_rows<W: Write>(
+    writer: &mut csv::Writer<W>,
+    scope: &str,
+    version: &str,
+    metric: &str,
+    value: &Value,
+) -> Result<()> {
+    let nested = |name: &str| {
+        if metric.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", metric, name)
+        }
+    };
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields {
+                write_csv_rows(writer, scope, version, &nested(name), field)?;
+            }
+        },
+        // (label, number) pairs
+        Value::Array(items) => {
+            for item in items {
+                if let Some([Value::String(name), number]) = item.as_array().map(Vec::as_slice) {
+                    write_csv_rows(writer, scope, version, &nested(name), number)?;
+                }
+            }
+        },
+        Value::Number(number) => {
+            writer.write_record([scope, version, metric, number.to_string().as_str()])?
+        },
+        Value::Null | Value::Bool(_) | Value::String(_) => (),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_stage_latencies_from_samples() {
+    assert_eq!(StageLatencies::from_samples("commit", &[]), None);
+
+    let samples = (1..=200)
+        .rev()
+        .map(Duration::from_millis)
+        .collect::<Vec<_>>();
+    let latencies = StageLatencies::from_samples("commit", &samples).unwrap();
+    assert_eq!(latencies.num_blocks, 200);
+    assert_eq!(latencies.p50_ms, 100.0);
+    assert_eq!(latencies.p90_ms, 180.0);
+    assert_eq!(latencies.p99_ms, 198.0);
+    assert_eq!(latencies.max_ms, 200.0);
+}
+
+#[test]
+fn test_stage_memory_from_samples() {
+    assert_eq!(StageMemory::from_samples("execution", &[]), None);
+
+    let memory = StageMemory::from_samples("execution", &[300, 100, 200]).unwrap();
+    assert_eq!(memory.num_samples, 3);
+    assert_eq!(memory.peak_bytes, 300);
+    assert_eq!(memory.average_bytes, 200);
+}
+
+#[test]
+fn test_regressions() {
+    let results = |tps, p50_ms| BenchmarkResults {
+        overall: vec![OverallResult {
+            name: "Overall".to_string(),
+            tps,
+            ..Default::default()
+        }],
+        stage_latencies: vec![StageLatencies {
+            stage: "commit".to_string(),
+            p50_ms,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let baseline = results(1000.0, 10.0);
+
+    assert!(results(950.0, 10.5).regressions(&baseline, 10.0).is_empty());
+    assert!(results(2000.0, 5.0).regressions(&baseline, 10.0).is_empty());
+    let regressions = results(800.0, 12.0).regressions(&baseline, 10.0);
+    assert_eq!(
+        regressions
+            .iter()
+            .map(|r| (r.metric.as_str(), r.pct.round()))
+            .collect::<Vec<_>>(),
+        vec![("Overall tps", 20.0), ("commit latency p50_ms", 20.0)]
+    );
+}
+
+#[test]
+fn test_write_latency_summaries() {
+    let mut results = BenchmarkResults::default();
+    let mut output = vec![];
+    results.write_latency_summaries(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap().lines().count(), 2);
+
+    results.blocks = (1..=4)
+        .map(|i| BlockResult {
+            latency_ms: i as f64 * 1000.0,
+            ..Default::default()
+        })
+        .collect();
+    let mut output = vec![];
+    results.write_latency_summaries(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let pipeline_lines = output
+        .lines()
+        .filter(|line| line.contains("stage=\"pipeline\""))
+        .collect::<Vec<_>>();
+    assert_eq!(pipeline_lines, vec![
+        "aptos_executor_benchmark_block_latency_seconds{stage=\"pipeline\",quantile=\"0.5\"} 2",
+        "aptos_executor_benchmark_block_latency_seconds{stage=\"pipeline\",quantile=\"0.9\"} 4",
+        "aptos_executor_benchmark_block_latency_seconds{stage=\"pipeline\",quantile=\"0.99\"} 4",
+        "aptos_executor_benchmark_block_latency_seconds{stage=\"pipeline\",quantile=\"0.999\"} 4",
+        "aptos_executor_benchmark_block_latency_seconds{stage=\"pipeline\",quantile=\"1\"} 4",
+        "aptos_executor_benchmark_block_latency_seconds_sum{stage=\"pipeline\"} 10",
+        "aptos_executor_benchmark_block_latency_seconds_count{stage=\"pipeline\"} 4",
+    ]);
+}
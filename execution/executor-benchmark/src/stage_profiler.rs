@@ -0,0 +1,92 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::info;
+use aptos_profiler::{ProfilerConfig, ProfilerHandler, ThreadGroup};
+use std::{
+    ops::Range,
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex},
+    thread::JoinHandle,
+};
+
+/// Name prefixes of the threads doing the work of each stage.
+const STAGE_THREADS: &[(&str, &[&str])] = &[
+    ("partitioning", &["block_partitioning"]),
+    ("execution", &[
+        "txn_executor",
+        "par_exec",
+        "sharded-executor",
+        "kv_reader",
+    ]),
+    ("ledger_update", &["ledger_update"]),
+    ("commit", &["txn_committer"]),
+];
+
+/// Profiles the CPU from the execution of the first of `blocks` to the commit of the last one,
+/// then writes a flamegraph of the threads of each stage under `./profiling_results`.
+/// Blocks are counted as they enter the pipeline, from 0.
+pub struct StageProfiler {
+    blocks: Range<usize>,
+    sender: Mutex<mpsc::Sender<()>>,
+}
+
+impl StageProfiler {
+    /// Spawns the thread that starts, then ends the profiling, as the stages notify it.
+    pub fn spawn(blocks: Range<usize>) -> (Arc<Self>, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel();
+        let thread_groups = STAGE_THREADS
+            .iter()
+            .map(|(stage, prefixes)| ThreadGroup {
+                thread_name_prefixes: prefixes.iter().map(ToString::to_string).collect(),
+                svg_result_path: PathBuf::from(format!(
+                    "./profiling_results/{}_cpu_flamegraph.svg",
+                    stage
+                )),
+            })
+            .collect();
+        let profiler_blocks = blocks.clone();
+        let join_handle = std::thread::Builder::new()
+            .name("stage_profiler".to_string())
+            .spawn(move || {
+                let handler =
+                    ProfilerHandler::new(ProfilerConfig::new_with_cpu_thread_groups(thread_groups));
+                let mut profiler = handler.get_cpu_profiler();
+                // The stages drop their end of the channel once done, blocks may be fewer.
+                if receiver.recv().is_err() {
+                    return;
+                }
+                info!("Profiling blocks {:?}", profiler_blocks);
+                profiler
+                    .start_profiling()
+                    .expect("Failed to start profiling.");
+                let _ = receiver.recv();
+                profiler
+                    .end_profiling("")
+                    .expect("Failed to write the flamegraphs.");
+                info!("Profiled blocks {:?}", profiler_blocks);
+            })
+            .expect("Failed to spawn stage profiler thread.");
+        (
+            Arc::new(Self {
+                blocks,
+                sender: Mutex::new(sender),
+            }),
+            join_handle,
+        )
+    }
+
+    /// Called by the execution stage as it starts on the block at `index`.
+    pub fn block_started(&self, index: usize) {
+        if index == self.blocks.start {
+            let _ = self.sender.lock().unwrap().send(());
+        }
+    }
+
+    /// Called by the last stage once done with the block at `index`.
+    pub fn block_done(&self, index: usize) {
+        if index + 1 == self.blocks.end {
+            let _ = self.sender.lock().unwrap().send(());
+        }
+    }
+}
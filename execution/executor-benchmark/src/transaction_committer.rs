@@ -2,7 +2,13 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{metrics::NUM_TXNS, pipeline::CommitBlockMessage};
+use crate::{
+    metrics::NUM_TXNS,
+    pipeline::CommitBlockMessage,
+    results::{sample_memory, BlockResult, SharedResults},
+    stage_profiler::StageProfiler,
+    OverallMeasuring,
+};
 use aptos_crypto::hash::HashValue;
 use aptos_db::metrics::API_LATENCY_SECONDS;
 use aptos_executor::{
@@ -21,6 +27,7 @@ use aptos_types::{
     transaction::Version,
 };
 use std::{
+    collections::VecDeque,
     sync::{mpsc, Arc},
     time::{Duration, Instant},
 };
@@ -51,6 +58,10 @@ pub struct TransactionCommitter<V> {
     executor: Arc<BlockExecutor<V>>,
     version: Version,
     block_receiver: mpsc::Receiver<CommitBlockMessage>,
+    results: SharedResults,
+    warmup_blocks: usize,
+    rolling_tps_blocks: usize,
+    stage_profiler: Option<Arc<StageProfiler>>,
 }
 
 impl<V> TransactionCommitter<V>
@@ -61,17 +72,34 @@ where
         executor: Arc<BlockExecutor<V>>,
         version: Version,
         block_receiver: mpsc::Receiver<CommitBlockMessage>,
+        results: SharedResults,
+        warmup_blocks: usize,
+        rolling_tps_blocks: usize,
+        stage_profiler: Option<Arc<StageProfiler>>,
     ) -> Self {
         Self {
             version,
             executor,
             block_receiver,
+            results,
+            warmup_blocks,
+            rolling_tps_blocks,
+            stage_profiler,
         }
     }
 
     pub fn run(&mut self) {
         let start_version = self.version;
         info!("Start with version: {}", start_version);
+        // Nothing is executed before the first block starts, so the measuring covers the warm-up
+        // once started at that time.
+        let mut warmup_measuring = (self.warmup_blocks > 0).then(OverallMeasuring::start);
+        let mut steady_measuring = None;
+        let mut num_blocks_committed = 0;
+        // User transactions, without the state checkpoint of each block.
+        let mut num_user_txns = 0;
+        // Commit end time and size of the last blocks, after the end of the one before them.
+        let mut rolling_window = VecDeque::with_capacity(self.rolling_tps_blocks + 1);
 
         while let Ok(msg) = self.block_receiver.recv() {
             let CommitBlockMessage {
@@ -93,17 +121,55 @@ where
             self.executor
                 .commit_blocks(vec![block_id], ledger_info_with_sigs)
                 .unwrap();
+            let commit_time = Instant::now().duration_since(commit_start);
+
+            if rolling_window.is_empty() {
+                rolling_window.push_back((first_block_start_time, 0));
+            }
+            rolling_window.push_back((Instant::now(), num_txns));
+            if rolling_window.len() > self.rolling_tps_blocks + 1 {
+                rolling_window.pop_front();
+            }
+            let rolling_tps = rolling_window.iter().skip(1).map(|(_, n)| n).sum::<usize>() as f64
+                / rolling_window[rolling_window.len() - 1]
+                    .0
+                    .duration_since(rolling_window[0].0)
+                    .as_secs_f64();
 
-            report_block(
+            let block_result = report_block(
                 start_version,
                 self.version,
                 first_block_start_time,
                 current_block_start_time,
                 partition_time,
                 execution_time,
-                Instant::now().duration_since(commit_start),
+                commit_time,
                 num_txns,
+                rolling_tps,
             );
+            let mut results = self.results.lock().unwrap();
+            results.stage_samples.commit.push(commit_time);
+            sample_memory(&mut results.memory_samples.commit);
+            results.blocks.push(block_result);
+
+            if let Some(profiler) = &self.stage_profiler {
+                profiler.block_done(num_blocks_committed);
+            }
+            num_blocks_committed += 1;
+            num_user_txns += num_txns.saturating_sub(1) as u64;
+            if num_blocks_committed == self.warmup_blocks {
+                let mut warmup_measuring = warmup_measuring.take().unwrap();
+                warmup_measuring.start_time = first_block_start_time;
+                let warmup_result = warmup_measuring.print_end("Warm-up", num_user_txns);
+                results.overall.push(warmup_result);
+                steady_measuring = Some(OverallMeasuring::start());
+                num_user_txns = 0;
+            }
+        }
+
+        if let Some(steady_measuring) = steady_measuring {
+            let steady_result = steady_measuring.print_end("Steady state", num_user_txns);
+            self.results.lock().unwrap().overall.push(steady_result);
         }
     }
 }
@@ -117,10 +183,27 @@ fn report_block(
     execution_time: Duration,
     commit_time: Duration,
     block_size: usize,
-) {
+    rolling_tps: f64,
+) -> BlockResult {
     let total_versions = (version - start_version) as f64;
+    let block_result = BlockResult {
+        version,
+        num_txns: block_size,
+        latency_ms: Instant::now()
+            .duration_since(current_block_start_time)
+            .as_secs_f64()
+            * 1000.0,
+        partition_ms: partition_time.as_secs_f64() * 1000.0,
+        execution_ms: execution_time.as_secs_f64() * 1000.0,
+        commit_ms: commit_time.as_secs_f64() * 1000.0,
+        tps: block_size as f64
+            / std::cmp::max(std::cmp::max(partition_time, execution_time), commit_time)
+                .as_secs_f64(),
+        accumulative_tps: total_versions / first_block_start_time.elapsed().as_secs_f64(),
+        rolling_tps,
+    };
     info!(
-        "Version: {}. latency: {} ms, partition time: {} ms, execute time: {} ms. commit time: {} ms. TPS: {:.0} (partition: {:.0}, execution: {:.0}, commit: {:.0}). Accumulative TPS: {:.0}",
+        "Version: {}. latency: {} ms, partition time: {} ms, execute time: {} ms. commit time: {} ms. TPS: {:.0} (partition: {:.0}, execution: {:.0}, commit: {:.0}). Accumulative TPS: {:.0}. Rolling TPS: {:.0}",
         version,
         Instant::now().duration_since(current_block_start_time).as_millis(),
         partition_time.as_millis(),
@@ -131,6 +214,7 @@ fn report_block(
         block_size as f64 / execution_time.as_secs_f64(),
         block_size as f64 / commit_time.as_secs_f64(),
         total_versions / first_block_start_time.elapsed().as_secs_f64(),
+        rolling_tps,
     );
     info!(
             "Accumulative total: VM time: {:.0} secs, executor time: {:.0} secs, commit time: {:.0} secs, DB commit time: {:.0} secs",
@@ -151,4 +235,5 @@ fn report_block(
             API_LATENCY_SECONDS.get_metric_with_label_values(&["save_transactions", "Ok"]).expect("must exist.").get_sample_sum() * NANOS_PER_SEC
                 / total_versions,
         );
+    block_result
 }
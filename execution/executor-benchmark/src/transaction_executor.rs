@@ -2,13 +2,14 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::pipeline::LedgerUpdateMessage;
+use crate::{metrics::TIMER, pipeline::LedgerUpdateMessage};
 use aptos_crypto::hash::HashValue;
 use aptos_executor::block_executor::{BlockExecutor, TransactionBlockExecutor};
 use aptos_executor_types::BlockExecutorTrait;
 use aptos_logger::info;
-use aptos_types::block_executor::{
-    config::BlockExecutorConfigFromOnchain, partitioner::ExecutableBlock,
+use aptos_types::{
+    block_executor::{config::BlockExecutorConfigFromOnchain, partitioner::ExecutableBlock},
+    on_chain_config::BlockGasLimitType,
 };
 use std::{
     sync::{mpsc, Arc},
@@ -18,6 +19,25 @@ use std::{
 pub const BENCHMARKS_BLOCK_EXECUTOR_ONCHAIN_CONFIG: BlockExecutorConfigFromOnchain =
     BlockExecutorConfigFromOnchain::on_but_large_for_test();
 
+/// `BENCHMARKS_BLOCK_EXECUTOR_ONCHAIN_CONFIG`, with `block_gas_limit` as its effective block gas
+/// limit if set.
+pub fn benchmarks_block_executor_onchain_config(
+    block_gas_limit: Option<u64>,
+) -> BlockExecutorConfigFromOnchain {
+    let mut config = BENCHMARKS_BLOCK_EXECUTOR_ONCHAIN_CONFIG;
+    if let (
+        Some(block_gas_limit),
+        BlockGasLimitType::ComplexLimitV1 {
+            effective_block_gas_limit,
+            ..
+        },
+    ) = (block_gas_limit, &mut config.block_gas_limit_type)
+    {
+        *effective_block_gas_limit = block_gas_limit;
+    }
+    config
+}
+
 pub struct TransactionExecutor<V> {
     num_blocks_processed: usize,
     executor: Arc<BlockExecutor<V>>,
@@ -27,6 +47,7 @@ pub struct TransactionExecutor<V> {
     allow_aborts: bool,
     allow_discards: bool,
     allow_retries: bool,
+    onchain_config: BlockExecutorConfigFromOnchain,
 }
 
 impl<V> TransactionExecutor<V>
@@ -40,6 +61,7 @@ where
         allow_aborts: bool,
         allow_discards: bool,
         allow_retries: bool,
+        onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Self {
         Self {
             num_blocks_processed: 0,
@@ -50,15 +72,17 @@ where
             allow_aborts,
             allow_discards,
             allow_retries,
+            onchain_config,
         }
     }
 
+    /// Returns how long the execution took.
     pub fn execute_block(
         &mut self,
         current_block_start_time: Instant,
         partition_time: Duration,
         executable_block: ExecutableBlock,
-    ) {
+    ) -> Duration {
         let execution_start_time = Instant::now();
         if self.maybe_first_block_start_time.is_none() {
             self.maybe_first_block_start_time = Some(current_block_start_time);
@@ -74,7 +98,7 @@ where
             .execute_and_state_checkpoint(
                 executable_block,
                 self.parent_block_id,
-                BENCHMARKS_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
+                self.onchain_config.clone(),
             )
             .unwrap();
 
@@ -88,17 +112,23 @@ where
             assert_eq!(output.txns_to_commit_len(), num_txns + 1);
         }
 
+        let execution_time = Instant::now().duration_since(execution_start_time);
         let msg = LedgerUpdateMessage {
             current_block_start_time,
             first_block_start_time: *self.maybe_first_block_start_time.as_ref().unwrap(),
             partition_time,
-            execution_time: Instant::now().duration_since(execution_start_time),
+            execution_time,
             block_id,
             parent_block_id: self.parent_block_id,
             state_checkpoint_output: output,
         };
+        let timer = TIMER
+            .with_label_values(&["send_ledger_update"])
+            .start_timer();
         self.ledger_update_sender.send(msg).unwrap();
+        drop(timer);
         self.parent_block_id = block_id;
         self.num_blocks_processed += 1;
+        execution_time
     }
 }
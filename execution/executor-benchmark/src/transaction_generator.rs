@@ -20,9 +20,7 @@ use aptos_types::{
 use chrono::Local;
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
-#[cfg(test)]
-use rand::SeedableRng;
-use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
 use rayon::{
     iter::{IntoParallelRefIterator, ParallelIterator},
     ThreadPool, ThreadPoolBuilder,
@@ -40,6 +38,7 @@ use std::{
         atomic::{AtomicUsize, Ordering},
         mpsc, Arc, Mutex,
     },
+    time::{Duration, Instant},
 };
 use thread_local::ThreadLocal;
 
@@ -80,6 +79,14 @@ struct P2pTestCase {
     num_accounts: usize,
 }
 
+/// Contention of the raw transfers: `fraction` of the transactions transfer to one of the first
+/// `num_accounts` accounts, the others to any account.
+#[derive(Clone, Copy, Debug)]
+pub struct HotAccounts {
+    pub num_accounts: usize,
+    pub fraction: f32,
+}
+
 pub struct TransactionGenerator {
     /// The current state of the accounts. The main purpose is to keep track of the sequence number
     /// so generated transactions are guaranteed to be successfully executed.
@@ -109,6 +116,12 @@ pub struct TransactionGenerator {
     // TODO(grao): Use a different pool, and pin threads to dedicate cores to avoid affecting the
     // rest parts of benchmark.
     worker_pool: ThreadPool,
+
+    /// Time taken to generate each block sent.
+    block_generation_times: Mutex<Vec<Duration>>,
+
+    /// Picks the accounts not picked from the account caches, see `set_seed`.
+    rng: StdRng,
 }
 
 impl TransactionGenerator {
@@ -204,6 +217,16 @@ impl TransactionGenerator {
                 .num_threads(num_workers)
                 .build()
                 .unwrap(),
+            block_generation_times: Mutex::new(Vec::new()),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Makes the generated transfers and workload reproducible, seeding the accounts picking.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        if let Some(main_signer_accounts) = self.main_signer_accounts.as_mut() {
+            main_signer_accounts.rng = StdRng::seed_from_u64(self.rng.next_u64());
         }
     }
 
@@ -282,6 +305,7 @@ impl TransactionGenerator {
         connected_tx_grps: usize,
         shuffle_connected_txns: bool,
         hotspot_probability: Option<f32>,
+        hot_accounts: Option<HotAccounts>,
     ) -> usize {
         assert!(self.block_sender.is_some());
         self.gen_transfer_transactions(
@@ -291,18 +315,28 @@ impl TransactionGenerator {
             connected_tx_grps,
             shuffle_connected_txns,
             hotspot_probability,
+            hot_accounts,
         );
         num_transfer_blocks
     }
 
+    /// With `phase_blocks`, moves to the next phase once the current one got its blocks, and
+    /// sends an empty block then, for the execution stage to report each phase. Otherwise, moves
+    /// to the next phase once the current one has nothing left to generate. Returns the number of
+    /// blocks sent.
     pub fn run_workload(
         &mut self,
         block_size: usize,
         num_blocks: usize,
         transaction_generators: Vec<Box<dyn aptos_transaction_generator_lib::TransactionGenerator>>,
         phase: Arc<AtomicUsize>,
+        phase_blocks: &[usize],
         transactions_per_sender: usize,
     ) -> usize {
+        assert!(phase_blocks.is_empty() || phase_blocks.iter().sum::<usize>() == num_blocks);
+        let mut current_phase = 0;
+        let mut phase_end = phase_blocks.first().copied();
+        let mut num_empty_blocks = 0;
         let last_non_empty_phase = Arc::new(AtomicUsize::new(0));
         let transaction_generators = Mutex::new(transaction_generators);
         assert!(self.block_sender.is_some());
@@ -311,14 +345,22 @@ impl TransactionGenerator {
         let account_pool_size = self.main_signer_accounts.as_ref().unwrap().accounts.len();
         let transaction_generator = ThreadLocal::with_capacity(self.num_workers);
         for i in 0..num_blocks {
-            let sender_indices = rand::seq::index::sample(
-                &mut thread_rng(),
-                account_pool_size,
-                num_senders_per_block,
-            )
-            .into_iter()
-            .flat_map(|sender_idx| vec![sender_idx; transactions_per_sender])
-            .collect();
+            while phase_end == Some(i) {
+                current_phase += 1;
+                phase.store(current_phase, Ordering::Relaxed);
+                phase_end = Some(i + phase_blocks[current_phase]);
+                self.block_sender
+                    .as_ref()
+                    .unwrap()
+                    .send(Vec::new())
+                    .unwrap();
+                num_empty_blocks += 1;
+            }
+            let sender_indices =
+                rand::seq::index::sample(&mut self.rng, account_pool_size, num_senders_per_block)
+                    .into_iter()
+                    .flat_map(|sender_idx| vec![sender_idx; transactions_per_sender])
+                    .collect();
             let terminate = self.generate_and_send_block(
                 self.main_signer_accounts.as_ref().unwrap(),
                 sender_indices,
@@ -339,10 +381,10 @@ impl TransactionGenerator {
                 |sender_idx| *sender_idx,
             );
             if terminate {
-                return i + 1;
+                return i + 1 + num_empty_blocks;
             }
         }
-        num_blocks
+        num_blocks + num_empty_blocks
     }
 
     pub fn create_seed_accounts(
@@ -518,17 +560,45 @@ impl TransactionGenerator {
         let num_accounts = self.main_signer_accounts.as_ref().unwrap().len();
         let num_hotspot_accounts =
             ((1.0 - hotspot_probability) * num_accounts as f32).ceil() as usize;
-        let mut rng = thread_rng();
         (0..block_size)
             .map(|_| {
                 (
-                    rand_with_hotspot(&mut rng, num_accounts, num_hotspot_accounts),
-                    rand_with_hotspot(&mut rng, num_accounts, num_hotspot_accounts),
+                    rand_with_hotspot(&mut self.rng, num_accounts, num_hotspot_accounts),
+                    rand_with_hotspot(&mut self.rng, num_accounts, num_hotspot_accounts),
                 )
             })
             .collect()
     }
 
+    /// Generates random P2P transfer transactions, with `hot_accounts.fraction` of them
+    /// transferring to one of the hot accounts, picked uniformly at random.
+    pub fn gen_random_transfers_with_hot_accounts(
+        &mut self,
+        block_size: usize,
+        num_blocks: usize,
+        hot_accounts: HotAccounts,
+    ) {
+        let num_accounts = self.main_signer_accounts.as_ref().unwrap().len();
+        assert!(hot_accounts.num_accounts > 0 && hot_accounts.num_accounts <= num_accounts);
+        assert!((0.0..=1.0).contains(&hot_accounts.fraction));
+        for _ in 0..num_blocks {
+            let transfer_indices = (0..block_size)
+                .map(|_| {
+                    let receiver_idx = if self.rng.gen_bool(hot_accounts.fraction as f64) {
+                        self.rng.gen_range(0, hot_accounts.num_accounts)
+                    } else {
+                        self.rng.gen_range(0, num_accounts)
+                    };
+                    (self.rng.gen_range(0, num_accounts), receiver_idx)
+                })
+                .collect();
+            self.generate_and_send_transfer_block(
+                self.main_signer_accounts.as_ref().unwrap(),
+                transfer_indices,
+            );
+        }
+    }
+
     /// 'Conflicting groups of txns' are a type of 'connected groups of txns'.
     /// Here we generate conflicts completely on one particular address (which can be sender or
     /// receiver).
@@ -656,6 +726,7 @@ impl TransactionGenerator {
         S: Fn(&T) -> usize,
     {
         let _timer = TIMER.with_label_values(&["generate_block"]).start_timer();
+        let generation_start = Instant::now();
         let block_size = inputs.len();
         let mut jobs = Vec::new();
         jobs.resize_with(self.num_workers, BTreeMap::new);
@@ -716,8 +787,13 @@ impl TransactionGenerator {
         NUM_TXNS
             .with_label_values(&["generation_done"])
             .inc_by(transactions.len() as u64);
+        self.block_generation_times
+            .lock()
+            .unwrap()
+            .push(generation_start.elapsed());
 
         if let Some(sender) = &self.block_sender {
+            let _timer = TIMER.with_label_values(&["send_raw_block"]).start_timer();
             sender.send(transactions).unwrap();
         }
         false
@@ -731,6 +807,7 @@ impl TransactionGenerator {
         connected_tx_grps: usize,
         shuffle_connected_txns: bool,
         hotspot_probability: Option<f32>,
+        hot_accounts: Option<HotAccounts>,
     ) {
         info!("Starting block generation.");
         info!("block_size={block_size}");
@@ -745,6 +822,11 @@ impl TransactionGenerator {
                 connected_tx_grps,
                 shuffle_connected_txns,
             );
+        } else if let Some(hot_accounts) = hot_accounts {
+            info!("block_generation_mode=sample_from_pool_with_hot_accounts");
+            info!("hot_accounts={}", hot_accounts.num_accounts);
+            info!("hot_account_fraction={}", hot_accounts.fraction);
+            self.gen_random_transfers_with_hot_accounts(block_size, num_blocks, hot_accounts);
         } else if hotspot_probability.is_some() {
             info!("block_generation_mode=sample_from_pool_with_hotspot");
             info!("hotspot_ratio={hotspot_probability:?}");
@@ -795,6 +877,16 @@ impl TransactionGenerator {
         println!("[{}] done.", now_fmt!());
     }
 
+    /// Returns the time taken to generate each block sent so far.
+    pub fn take_block_generation_times(&mut self) -> Vec<Duration> {
+        std::mem::take(self.block_generation_times.get_mut().unwrap())
+    }
+
+    /// Sends the blocks generated from now on to `block_sender`, e.g. that of a new pipeline.
+    pub fn set_block_sender(&mut self, block_sender: mpsc::SyncSender<Vec<Transaction>>) {
+        self.block_sender = Some(block_sender);
+    }
+
     /// Drops the sender to notify the receiving end of the channel.
     pub fn drop_sender(&mut self) {
         self.block_sender.take().unwrap();
@@ -23,7 +23,7 @@ use aptos_executor_types::{
     ChunkCommitNotification, ChunkExecutorTrait, ExecutedChunk, ParsedTransactionOutput,
     TransactionReplayer, VerifyExecutionMode,
 };
-use aptos_experimental_runtimes::thread_manager::{optimal_min_len, THREAD_MANAGER};
+use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_metrics_core::TimerHelper;
@@ -37,7 +37,7 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     state_store::StateViewId,
     transaction::{
-        signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
+        signature_verified_transaction::into_committed_signature_verified_block, Transaction,
         TransactionAuxiliaryData, TransactionInfo, TransactionListWithProof, TransactionOutput,
         TransactionOutputListWithProof, TransactionStatus, Version,
     },
@@ -46,20 +46,8 @@ use aptos_types::{
 use aptos_vm::VMExecutor;
 use fail::fail_point;
 use itertools::multizip;
-use once_cell::sync::Lazy;
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use std::{iter::once, marker::PhantomData, sync::Arc};
 
-pub static SIG_VERIFY_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
-    Arc::new(
-        rayon::ThreadPoolBuilder::new()
-            .num_threads(8) // More than 8 threads doesn't seem to help much
-            .thread_name(|index| format!("signature-checker-{}", index))
-            .build()
-            .unwrap(),
-    )
-});
-
 pub struct ChunkExecutor<V> {
     db: DbReaderWriter,
     inner: RwLock<Option<ChunkExecutorInner<V>>>,
@@ -257,16 +245,9 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             .map(|t| t.state_checkpoint_hash())
             .collect();
 
-        // TODO(skedia) In the chunk executor path, we ideally don't need to verify the signature
-        // as only transactions with verified signatures are committed to the storage.
-        let num_txns = transactions.len();
-        let sig_verified_txns = SIG_VERIFY_POOL.install(|| {
-            transactions
-                .into_par_iter()
-                .with_min_len(optimal_min_len(num_txns, 32))
-                .map(|t| t.into())
-                .collect::<Vec<_>>()
-        });
+        // The chunk is verified above, and only transactions with verified signatures are
+        // committed to the storage, so the signatures need not be verified again.
+        let sig_verified_txns = into_committed_signature_verified_block(transactions);
 
         // Execute transactions.
         let state_view = self.latest_state_view(&parent_state)?;
@@ -677,12 +658,15 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
     ) -> Result<Version> {
         // Execute transactions.
         let state_view = self.latest_state_view(latest_view.state())?;
-        let txns = transactions
-            .iter()
-            .take((end_version - begin_version) as usize)
-            .cloned()
-            .map(|t| t.into())
-            .collect::<Vec<SignatureVerifiedTransaction>>();
+        // Transactions to verify the execution of are already committed, so their signatures
+        // need not be verified again.
+        let txns = into_committed_signature_verified_block(
+            transactions
+                .iter()
+                .take((end_version - begin_version) as usize)
+                .cloned()
+                .collect(),
+        );
 
         // State sync executor shouldn't have block gas limit.
         let chunk_output = ChunkOutput::by_transaction_execution::<V>(
@@ -1,9 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use jsonwebtoken::{DecodingKey, TokenData, Validation};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 /// The claims required in a JWT.
 #[derive(Debug, Deserialize, Serialize)]
@@ -16,6 +18,27 @@ pub struct Claims {
     pub aud: String,
     pub iat: u64,
     pub exp: u64,
+    /// The other claims, any string one of which can be a `uid_key` if allowed.
+    #[serde(flatten)]
+    pub additional_claims: BTreeMap<String, Value>,
+}
+
+impl Claims {
+    /// The value of the string claim `uid_key`.
+    pub fn uid_val(&self, uid_key: &str) -> anyhow::Result<String> {
+        match uid_key {
+            "sub" => Ok(self.sub.clone()),
+            "email" => self
+                .email
+                .clone()
+                .ok_or_else(|| anyhow!("`email` required but not found in jwt")),
+            _ => match self.additional_claims.get(uid_key) {
+                Some(Value::String(val)) => Ok(val.clone()),
+                Some(_) => bail!("`{}` in jwt is not a string", uid_key),
+                None => bail!("`{}` required but not found in jwt", uid_key),
+            },
+        }
+    }
 }
 
 /// Simply parse the fields out without performing signature verification.
@@ -31,3 +54,31 @@ pub fn parse(jwt: &str) -> anyhow::Result<TokenData<Claims>> {
     jsonwebtoken::decode::<Claims>(jwt, &empty_decoding_key, &parse_only_validation)
         .map_err(|e| anyhow!("jwt decoding error: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uid_val_from_claims() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "nonce": "nonce",
+            "iss": "https://login.example.org",
+            "sub": "1234",
+            "aud": "client",
+            "iat": 1700000000,
+            "exp": 1700003600,
+            "preferred_username": "alice@example.org",
+            "groups": ["admins"],
+        }))
+        .unwrap();
+        assert_eq!(claims.uid_val("sub").unwrap(), "1234");
+        assert_eq!(
+            claims.uid_val("preferred_username").unwrap(),
+            "alice@example.org"
+        );
+        assert!(claims.uid_val("email").is_err());
+        assert!(claims.uid_val("groups").is_err());
+        assert!(claims.uid_val("oid").is_err());
+    }
+}
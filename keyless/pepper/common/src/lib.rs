@@ -6,6 +6,7 @@ use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
 pub mod account_recovery_db;
 pub mod jwt;
+pub mod verification;
 pub mod vuf;
 
 /// Custom serialization function to convert Vec<u8> into a hex string.
@@ -65,9 +66,49 @@ where
     Ok(pk)
 }
 
+/// Why a pepper request failed, for clients to branch on.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PepperErrorCode {
+    /// The request body could not be parsed.
+    MalformedRequest,
+    TooManyRequestsInBatch,
+    InvalidDerivationPath,
+    UnsupportedEpk,
+    MalformedJwt,
+    EpkExpired,
+    EpkExpiryTooFar,
+    UnsupportedUidKey,
+    /// The claim of the `uid_key` is missing from the JWT.
+    MissingUidClaim,
+    NonceMismatch,
+    MissingKid,
+    /// The JWT algorithm is not allowed for the issuer, or does not match the JWK.
+    UnsupportedAlg,
+    UnknownIssuer,
+    UnknownKid,
+    InvalidSignature,
+    AudienceNotAllowed,
+    RateLimited,
+    VufEvalFailed,
+    PepperDerivationFailed,
+    PepperEncryptionFailed,
+    AccountRecoveryDbFailed,
+    Internal,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct BadPepperRequestError {
+    pub code: PepperErrorCode,
+    pub message: String,
+}
+
+/// The response to a request rejected by a rate limit, to be retried later.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ThrottledPepperRequestError {
+    pub code: PepperErrorCode,
     pub message: String,
+    pub retry_after_secs: u64,
 }
 
 /// A pepper scheme where:
@@ -109,6 +150,123 @@ pub struct PepperResponse {
     pub address: Vec<u8>,
 }
 
+/// Up to `MAX_PEPPER_BATCH_SIZE` `PepperRequest`s processed in one call.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperBatchRequest {
+    pub requests: Vec<PepperRequest>,
+}
+
+pub const MAX_PEPPER_BATCH_SIZE: usize = 32;
+
+/// The response to `PepperBatchRequest`, with the result of each request, in the same order.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperBatchResponse {
+    pub responses: Vec<PepperBatchItemResponse>,
+}
+
+/// Either the response to one `PepperRequest` of a batch, or why it failed.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperBatchItemResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<PepperResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BadPepperRequestError>,
+}
+
+/// A pepper scheme where, on top of `PepperRequest`, the VUF output comes with a non-empty proof:
+/// - The request is the same as `PepperRequest`, wrapped in type `PepperV1Request`.
+/// - The output is the `BLS12381_G1_BLS_WITH_PROOF` VUF output of the input, with its proof,
+///   wrapped in type `PepperV1Response`.
+///
+/// The VUF input is `bcs(PepperInput)`, which clients reconstruct from their JWT,
+/// and the proof verifies against the public key of `/v0/vuf-pub-key`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperV1Request {
+    #[serde(rename = "jwt_b64")]
+    pub jwt: String,
+    #[serde(
+        serialize_with = "serialize_epk_to_hex",
+        deserialize_with = "deserialize_epk_from_hex"
+    )]
+    pub epk: EphemeralPublicKey,
+    pub exp_date_secs: u64,
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub epk_blinder: Vec<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+}
+
+/// The response to `PepperV1Request`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperV1Response {
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub pepper: Vec<u8>,
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex_with_0x",
+        deserialize_with = "deserialize_bytes_from_hex_with_0x"
+    )]
+    pub address: Vec<u8>,
+    /// The VUF output the pepper derives from, as `SignatureResponse::signature`.
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub vuf_output: Vec<u8>,
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub vuf_proof: Vec<u8>,
+    /// The pepper scope salt in the VUF input, see `PepperInput::vuf_input_bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pepper_scope_salt: Option<String>,
+}
+
+/// A pepper to verify against the VUF public key, together with the VUF output it derives from,
+/// e.g. from a `PepperV1Response`, or a `SignatureResponse` with an empty `vuf_proof`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperVerificationRequest {
+    pub input: PepperInput,
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub vuf_output: Vec<u8>,
+    #[serde(
+        default,
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub vuf_proof: Vec<u8>,
+    #[serde(
+        serialize_with = "serialize_bytes_to_hex",
+        deserialize_with = "deserialize_bytes_from_hex"
+    )]
+    pub pepper: Vec<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+    /// The pepper scope salt in the VUF input, see `PepperInput::vuf_input_bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pepper_scope_salt: Option<String>,
+}
+
+/// The response to `PepperVerificationRequest`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PepperVerificationResponse {
+    pub valid: bool,
+    /// Why the pepper is not valid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
 /// The response to /signature, which contains the VUF signature.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SignatureResponse {
@@ -137,3 +295,25 @@ pub struct PepperInput {
     pub uid_val: String,
     pub uid_key: String,
 }
+
+impl PepperInput {
+    /// The VUF input, `bcs(PepperInput)` without a pepper scope salt,
+    /// or `bcs(ScopedPepperInput)` with one.
+    pub fn vuf_input_bytes(&self, scope_salt: Option<&str>) -> Vec<u8> {
+        match scope_salt {
+            None => bcs::to_bytes(self),
+            Some(scope_salt) => bcs::to_bytes(&ScopedPepperInput {
+                input: self,
+                scope_salt,
+            }),
+        }
+        .expect("PepperInput serialization should not fail")
+    }
+}
+
+/// A `PepperInput` under a pepper scope, whose peppers are distinct from those of other scopes.
+#[derive(Debug, Serialize)]
+pub struct ScopedPepperInput<'a> {
+    pub input: &'a PepperInput,
+    pub scope_salt: &'a str,
+}
@@ -0,0 +1,121 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    vuf::{
+        bls12381_g1_bls::{Bls12381G1Bls, PinkasPepper},
+        bls12381_g1_bls_with_proof::Bls12381G1BlsWithProof,
+        slip_10::{get_aptos_derivation_path, ExtendedPepper},
+        VUF,
+    },
+    PepperInput,
+};
+use anyhow::{anyhow, ensure, Result};
+use ark_bls12_381::G2Projective;
+
+/// Verify that `pepper` was derived at `derivation_path` from `vuf_output`,
+/// the VUF output of `input` in the pepper scope of `scope_salt` under the public key `pk`.
+///
+/// An empty `vuf_proof` means a `Bls12381G1Bls` output, verified with pairings,
+/// otherwise it is the proof of a `Bls12381G1BlsWithProof` output.
+pub fn verify_pepper(
+    pk: &G2Projective,
+    input: &PepperInput,
+    scope_salt: Option<&str>,
+    vuf_output: &[u8],
+    vuf_proof: &[u8],
+    pepper: &[u8],
+    derivation_path: &str,
+) -> Result<()> {
+    let input_bytes = input.vuf_input_bytes(scope_salt);
+    if vuf_proof.is_empty() {
+        Bls12381G1Bls::verify(pk, &input_bytes, vuf_output, vuf_proof)?;
+    } else {
+        Bls12381G1BlsWithProof::verify(pk, &input_bytes, vuf_output, vuf_proof)?;
+    }
+
+    let checked_derivation_path = get_aptos_derivation_path(derivation_path)?;
+    let master_pepper = PinkasPepper::from_affine_bytes(vuf_output)
+        .map_err(|e| anyhow!("pinkas pepper derivation error: {e}"))?
+        .to_master_pepper();
+    let derived_pepper = ExtendedPepper::from_seed(master_pepper.to_bytes())?
+        .derive(&checked_derivation_path)?
+        .get_pepper();
+    ensure!(
+        derived_pepper.to_bytes() == pepper,
+        "pepper does not derive from the VUF output"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        verification::verify_pepper,
+        vuf::{
+            bls12381_g1_bls::{Bls12381G1Bls, PinkasPepper},
+            bls12381_g1_bls_with_proof::Bls12381G1BlsWithProof,
+            slip_10::{get_aptos_derivation_path, ExtendedPepper},
+            VUF,
+        },
+        PepperInput,
+    };
+
+    #[test]
+    fn verify_derived_pepper() {
+        let (sk, pk) = Bls12381G1Bls::setup(&mut ark_std::rand::thread_rng());
+        let input = PepperInput {
+            iss: "https://accounts.google.com".to_string(),
+            aud: "client".to_string(),
+            uid_val: "1234".to_string(),
+            uid_key: "sub".to_string(),
+        };
+        let input_bytes = bcs::to_bytes(&input).unwrap();
+        let path = "m/44'/637'/0'/0'/0'";
+        let derive = |vuf_output: &[u8], path: &str| {
+            let master_pepper = PinkasPepper::from_affine_bytes(vuf_output)
+                .unwrap()
+                .to_master_pepper();
+            ExtendedPepper::from_seed(master_pepper.to_bytes())
+                .unwrap()
+                .derive(&get_aptos_derivation_path(path).unwrap())
+                .unwrap()
+                .get_pepper()
+                .to_bytes()
+                .to_vec()
+        };
+
+        let (output, _) = Bls12381G1Bls::eval(&sk, &input_bytes).unwrap();
+        let pepper = derive(&output, path);
+        verify_pepper(&pk, &input, None, &output, &[], &pepper, path).unwrap();
+
+        let (output_with_proof, proof) = Bls12381G1BlsWithProof::eval(&sk, &input_bytes).unwrap();
+        verify_pepper(&pk, &input, None, &output_with_proof, &proof, &pepper, path).unwrap();
+
+        let scoped_input_bytes = input.vuf_input_bytes(Some("some-scope"));
+        let (scoped_output, _) = Bls12381G1Bls::eval(&sk, &scoped_input_bytes).unwrap();
+        let scoped_pepper = derive(&scoped_output, path);
+        assert_ne!(scoped_pepper, pepper);
+        verify_pepper(
+            &pk,
+            &input,
+            Some("some-scope"),
+            &scoped_output,
+            &[],
+            &scoped_pepper,
+            path,
+        )
+        .unwrap();
+        assert!(
+            verify_pepper(&pk, &input, None, &scoped_output, &[], &scoped_pepper, path).is_err()
+        );
+
+        let another_path = "m/44'/637'/1'/0'/0'";
+        assert!(verify_pepper(&pk, &input, None, &output, &[], &pepper, another_path).is_err());
+        let another_input = PepperInput {
+            uid_val: "5678".to_string(),
+            ..input
+        };
+        assert!(verify_pepper(&pk, &another_input, None, &output, &[], &pepper, path).is_err());
+    }
+}
@@ -60,7 +60,7 @@ impl PinkasPepper {
 }
 
 impl Bls12381G1Bls {
-    fn hash_to_g1(input: &[u8]) -> G1Affine {
+    pub(crate) fn hash_to_g1(input: &[u8]) -> G1Affine {
         let mapper = ark_ec::hashing::map_to_curve_hasher::MapToCurveBasedHasher::<
             Projective<ark_bls12_381::g1::Config>,
             ark_ff::fields::field_hashers::DefaultFieldHasher<sha2_0_10_6::Sha256, 128>,
@@ -0,0 +1,174 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::vuf::{bls12381_g1_bls::Bls12381G1Bls, VUF};
+use anyhow::{anyhow, ensure};
+use ark_bls12_381::{Fr, G1Affine, G2Affine, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    rand::{CryptoRng, RngCore},
+    UniformRand,
+};
+use sha2_0_10_6::{Digest, Sha512};
+use std::ops::Mul;
+
+/// The `Bls12381G1Bls` VUF, with the same outputs, and a proof that the output and the public key
+/// share the secret key as discrete log (w.r.t. the input hashed to G1, and the G2 generator).
+/// The proof is a Fiat-Shamir Chaum-Pedersen proof, serialized as `challenge || response`.
+pub struct Bls12381G1BlsWithProof {}
+
+pub static CHALLENGE_DST: &[u8] = b"APTOS_PEPPER_BLS12381_VUF_PROOF_CHALLENGE_DST";
+
+pub const SCHEME_NAME: &str = "BLS12381_G1_BLS_WITH_PROOF";
+
+/// Size of a serialized `Fr`.
+const FR_NUM_BYTES: usize = 32;
+
+impl Bls12381G1BlsWithProof {
+    fn challenge(
+        input_g1: &G1Affine,
+        output_g1: &G1Affine,
+        pk_g2: &G2Affine,
+        commitment_g1: &G1Affine,
+        commitment_g2: &G2Affine,
+    ) -> anyhow::Result<Fr> {
+        let mut bytes = CHALLENGE_DST.to_vec();
+        input_g1
+            .serialize_compressed(&mut bytes)
+            .and_then(|_| output_g1.serialize_compressed(&mut bytes))
+            .and_then(|_| pk_g2.serialize_compressed(&mut bytes))
+            .and_then(|_| commitment_g1.serialize_compressed(&mut bytes))
+            .and_then(|_| commitment_g2.serialize_compressed(&mut bytes))
+            .map_err(|e| anyhow!("challenge serialization error: {e}"))?;
+        Ok(Fr::from_be_bytes_mod_order(
+            Sha512::digest(bytes.as_slice()).as_slice(),
+        ))
+    }
+}
+
+impl VUF for Bls12381G1BlsWithProof {
+    type PrivateKey = Fr;
+    type PublicKey = G2Projective;
+
+    fn scheme_name() -> String {
+        SCHEME_NAME.to_string()
+    }
+
+    fn setup<R: CryptoRng + RngCore>(rng: &mut R) -> (Self::PrivateKey, Self::PublicKey) {
+        Bls12381G1Bls::setup(rng)
+    }
+
+    fn pk_from_sk(sk: &Fr) -> anyhow::Result<G2Projective> {
+        Bls12381G1Bls::pk_from_sk(sk)
+    }
+
+    fn eval(sk: &Fr, input: &[u8]) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+        let input_g1 = Bls12381G1Bls::hash_to_g1(input);
+        let output_g1 = input_g1.mul(sk).into_affine();
+        let pk_g2 = G2Affine::generator().mul(sk).into_affine();
+
+        let nonce = Fr::rand(&mut ark_std::rand::thread_rng());
+        let commitment_g1 = input_g1.mul(nonce).into_affine();
+        let commitment_g2 = G2Affine::generator().mul(nonce).into_affine();
+        let challenge = Self::challenge(
+            &input_g1,
+            &output_g1,
+            &pk_g2,
+            &commitment_g1,
+            &commitment_g2,
+        )
+        .map_err(|e| anyhow!("Bls12381G1BlsWithProof::eval failed with {e}"))?;
+        let response = nonce + challenge * sk;
+
+        let mut output_bytes = vec![];
+        output_g1
+            .serialize_compressed(&mut output_bytes)
+            .map_err(|e| {
+                anyhow!("Bls12381G1BlsWithProof::eval failed with output serialization error: {e}")
+            })?;
+        let mut proof_bytes = vec![];
+        challenge
+            .serialize_compressed(&mut proof_bytes)
+            .and_then(|_| response.serialize_compressed(&mut proof_bytes))
+            .map_err(|e| {
+                anyhow!("Bls12381G1BlsWithProof::eval failed with proof serialization error: {e}")
+            })?;
+        Ok((output_bytes, proof_bytes))
+    }
+
+    fn verify(
+        pk_g2: &G2Projective,
+        input: &[u8],
+        output: &[u8],
+        proof: &[u8],
+    ) -> anyhow::Result<()> {
+        ensure!(
+            proof.len() == 2 * FR_NUM_BYTES,
+            "Bls12381G1BlsWithProof::verify failed with proof size mismatch"
+        );
+        let (challenge, response) = Fr::deserialize_compressed(&proof[..FR_NUM_BYTES])
+            .and_then(|challenge| {
+                Ok((
+                    challenge,
+                    Fr::deserialize_compressed(&proof[FR_NUM_BYTES..])?,
+                ))
+            })
+            .map_err(|e| {
+                anyhow!(
+                    "Bls12381G1BlsWithProof::verify failed with proof deserialization error: {e}"
+                )
+            })?;
+        let input_g1 = Bls12381G1Bls::hash_to_g1(input);
+        let output_g1 = G1Affine::deserialize_compressed(output).map_err(|e| {
+            anyhow!("Bls12381G1BlsWithProof::verify failed with output deserialization error: {e}")
+        })?;
+        let pk_g2 = pk_g2.into_affine();
+
+        let commitment_g1 = (input_g1.mul(response) - output_g1.mul(challenge)).into_affine();
+        let commitment_g2 =
+            (G2Affine::generator().mul(response) - pk_g2.mul(challenge)).into_affine();
+        let expected_challenge = Self::challenge(
+            &input_g1,
+            &output_g1,
+            &pk_g2,
+            &commitment_g1,
+            &commitment_g2,
+        )
+        .map_err(|e| anyhow!("Bls12381G1BlsWithProof::verify failed with {e}"))?;
+        ensure!(
+            expected_challenge == challenge,
+            "Bls12381G1BlsWithProof::verify failed with final check failure"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vuf::{
+        bls12381_g1_bls::Bls12381G1Bls, bls12381_g1_bls_with_proof::Bls12381G1BlsWithProof, VUF,
+    };
+
+    #[test]
+    fn gen_eval_verify() {
+        let mut rng = ark_std::rand::thread_rng();
+        let (sk, pk) = Bls12381G1BlsWithProof::setup(&mut rng);
+        let input: &[u8] = b"hello world again and again and again and again and again and again";
+        let (output, proof) = Bls12381G1BlsWithProof::eval(&sk, input).unwrap();
+        Bls12381G1BlsWithProof::verify(&pk, input, &output, &proof).unwrap();
+
+        // Same output as without the proof, which it also verifies with.
+        let (output_without_proof, _) = Bls12381G1Bls::eval(&sk, input).unwrap();
+        assert_eq!(output, output_without_proof);
+        Bls12381G1Bls::verify(&pk, input, &output, &[]).unwrap();
+
+        let (_, pk_another) = Bls12381G1BlsWithProof::setup(&mut rng);
+        assert!(Bls12381G1BlsWithProof::verify(&pk_another, input, &output, &proof).is_err());
+        assert!(Bls12381G1BlsWithProof::verify(&pk, b"another input", &output, &proof).is_err());
+        let mut tampered_proof = proof.clone();
+        tampered_proof[0] ^= 1;
+        assert!(Bls12381G1BlsWithProof::verify(&pk, input, &output, &tampered_proof).is_err());
+    }
+}
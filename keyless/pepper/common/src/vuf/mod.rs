@@ -27,4 +27,6 @@ pub trait VUF {
 ///
 /// TODO: better name?
 pub mod bls12381_g1_bls;
+/// The `bls12381_g1_bls` VUF, with a proof of the evaluation verified without pairings.
+pub mod bls12381_g1_bls_with_proof;
 pub mod slip_10;
@@ -1,8 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{
+    jwk::{JWK_REFRESH_STATES, OBSERVED_CLOCK_SKEW_SECS},
+    vuf_keys::VUF_SIGNER,
+};
+use aptos_keyless_pepper_common::vuf::{bls12381_g1_bls::Bls12381G1Bls, VUF};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::{env, time::Duration};
 
 #[derive(Serialize, Deserialize)]
 struct AboutInfo {
@@ -15,3 +21,106 @@ pub static ABOUT_JSON: Lazy<String> = Lazy::new(|| {
     };
     serde_json::to_string_pretty(&obj).unwrap()
 });
+
+/// The VUF input evaluated to check the key material.
+const READINESS_VUF_INPUT: &[u8] = b"APTOS_PEPPER_READINESS_CHECK";
+
+/// The JWKs of a configured issuer are stale after this many refresh intervals without a success.
+const JWK_STALE_REFRESH_INTERVALS: u32 = 3;
+
+/// The JWKs of a configured issuer are never stale before this long.
+const MIN_JWK_STALENESS: Duration = Duration::from_secs(60);
+
+/// The clock skew tolerated, overridden by `READINESS_MAX_CLOCK_SKEW_SECS`.
+const DEFAULT_MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ReadinessCheck {
+    fn new(name: impl Into<String>, result: Result<(), String>) -> Self {
+        let (ok, message) = match result {
+            Ok(()) => (true, None),
+            Err(message) => (false, Some(message)),
+        };
+        Self {
+            name: name.into(),
+            ok,
+            message,
+        }
+    }
+}
+
+/// The response to `/ready`, for load balancers, which is ready when all the checks are ok.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+/// Check that the VUF key material evaluates and verifies, that the JWKs of the configured issuers
+/// are fresh, and that the clock agrees with the JWK endpoints.
+pub async fn readiness() -> Readiness {
+    let mut checks = vec![ReadinessCheck::new(
+        "vuf_keypair",
+        check_vuf_keypair().await,
+    )];
+    let mut issuers: Vec<_> = JWK_REFRESH_STATES
+        .iter()
+        .filter(|state| !state.discovered)
+        .map(|state| state.key().clone())
+        .collect();
+    issuers.sort();
+    for issuer in issuers {
+        let result = check_jwk_freshness(&issuer);
+        checks.push(ReadinessCheck::new(format!("jwks:{issuer}"), result));
+    }
+    checks.push(ReadinessCheck::new("clock_skew", check_clock_skew()));
+    Readiness {
+        ready: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
+
+async fn check_vuf_keypair() -> Result<(), String> {
+    let (output, _) = VUF_SIGNER
+        .eval(READINESS_VUF_INPUT, false)
+        .await
+        .map_err(|e| format!("vuf eval error: {e}"))?;
+    Bls12381G1Bls::verify(VUF_SIGNER.public_key(), READINESS_VUF_INPUT, &output, &[])
+        .map_err(|e| format!("vuf output does not verify against the public key: {e}"))
+}
+
+fn check_jwk_freshness(issuer: &str) -> Result<(), String> {
+    let state = JWK_REFRESH_STATES
+        .get(issuer)
+        .ok_or_else(|| "no refresh loop".to_string())?;
+    let max_age = (state.refresh_interval * JWK_STALE_REFRESH_INTERVALS).max(MIN_JWK_STALENESS);
+    match state.last_success {
+        None => Err("never fetched".to_string()),
+        Some(last_success) if last_success.elapsed() > max_age => Err(format!(
+            "last fetched {} secs ago",
+            last_success.elapsed().as_secs()
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+fn check_clock_skew() -> Result<(), String> {
+    let max_skew_secs = env::var("READINESS_MAX_CLOCK_SKEW_SECS")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .unwrap_or(DEFAULT_MAX_CLOCK_SKEW_SECS);
+    match *OBSERVED_CLOCK_SKEW_SECS.lock() {
+        None => Err("no JWK response observed yet".to_string()),
+        Some(skew_secs) if skew_secs.abs() > max_skew_secs => Err(format!(
+            "local clock is {skew_secs} secs off the JWK endpoints"
+        )),
+        Some(_) => Ok(()),
+    }
+}
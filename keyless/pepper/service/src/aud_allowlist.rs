@@ -0,0 +1,90 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Issuer;
+use aptos_logger::{info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{collections::HashMap, env};
+
+/// The `aud`s accepted for each issuer with an allowlist, as patterns where `*` matches anything.
+/// Issuers without an allowlist accept any `aud`.
+///
+/// ## How to use
+/// For an issuer `<some-issuer>` that should only give peppers to `<aud-1>` and the `aud`s
+/// matching `<aud-pattern>`, give it a unique short name `<some-short-name>` and put the
+/// following as the environment variables.
+/// `AUD_ALLOWLIST_<some-short-name>_ISSUER=<some-issuer>
+/// `AUD_ALLOWLIST_<some-short-name>_AUDS=<aud-1>,<aud-pattern>
+///
+/// Here is an example command.
+/// ```bash
+/// AUD_ALLOWLIST_1A_ISSUER=https://accounts.google.com \
+/// AUD_ALLOWLIST_1A_AUDS=407408718192.apps.googleusercontent.com,*-wallet.apps.googleusercontent.com \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static AUD_ALLOWLIST: Lazy<HashMap<Issuer, Vec<Regex>>> = Lazy::new(|| {
+    let re_issuer = Regex::new(r"AUD_ALLOWLIST_(\w+)_ISSUER").unwrap();
+    let re_auds = Regex::new(r"AUD_ALLOWLIST_(\w+)_AUDS").unwrap();
+    let mut working_set: HashMap<String, Collector> = HashMap::new();
+
+    env::vars().for_each(|(key, val)| {
+        if let Some(caps) = re_issuer.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            working_set.entry(uid).or_default().issuer = Some(val);
+        } else if let Some(caps) = re_auds.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            let patterns = val
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .filter_map(|pattern| match wildcard_pattern_to_regex(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        warn!("Ignoring aud pattern {pattern} of {key}: {e}");
+                        None
+                    },
+                })
+                .collect();
+            working_set.entry(uid).or_default().patterns = Some(patterns);
+        }
+    });
+
+    let ret: HashMap<Issuer, Vec<Regex>> = working_set
+        .into_values()
+        .filter_map(|collector| {
+            let Collector { issuer, patterns } = collector;
+            if let (Some(issuer), Some(patterns)) = (issuer, patterns) {
+                Some((issuer, patterns))
+            } else {
+                None
+            }
+        })
+        .collect();
+    info!("AUD_ALLOWLIST={:?}", ret);
+    ret
+});
+
+/// Whether `issuer` has no allowlist, or `aud` matches one of its patterns.
+pub fn is_allowed(issuer: &str, aud: &str) -> bool {
+    AUD_ALLOWLIST.get(issuer).map_or(true, |patterns| {
+        patterns.iter().any(|pattern| pattern.is_match(aud))
+    })
+}
+
+/// A regex matching the strings that `pattern` matches, where `*` matches anything.
+pub(crate) fn wildcard_pattern_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let regex = pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*");
+    Regex::new(&format!("^{regex}$"))
+}
+
+#[derive(Default)]
+struct Collector {
+    pub issuer: Option<Issuer>,
+    pub patterns: Option<Vec<Regex>>,
+}
@@ -0,0 +1,287 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account_db::{init_account_db, ACCOUNT_RECOVERY_DB};
+use anyhow::{anyhow, Result};
+use aptos_infallible::{duration_since_epoch, Mutex};
+use aptos_keyless_pepper_common::{PepperErrorCode, PepperInput};
+use aptos_logger::{info, warn};
+use chrono::{DateTime, Utc};
+use firestore::async_trait;
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha3::Digest;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::{Duration, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// How often the records past the retention get pruned.
+const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The audit record of a pepper request, where the user is only identified by a salted hash.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuditRecord {
+    pub session_id: String,
+    pub timestamp_unix_ms: u64,
+    /// Set once the JWT is verified.
+    pub iss: Option<String>,
+    /// Set once the JWT is verified.
+    pub aud: Option<String>,
+    /// The salted hash of the `PepperInput`, set once the JWT is verified, see `AuditLog::uid_hash`.
+    pub uid_hash: Option<String>,
+    /// Why the request failed, if it did.
+    pub error_code: Option<PepperErrorCode>,
+}
+
+impl AuditRecord {
+    pub fn new(session_id: &Uuid) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            timestamp_unix_ms: duration_since_epoch().as_millis() as u64,
+            iss: None,
+            aud: None,
+            uid_hash: None,
+            error_code: None,
+        }
+    }
+
+    pub fn set_input(&mut self, input: &PepperInput) {
+        self.iss = Some(input.iss.clone());
+        self.aud = Some(input.aud.clone());
+        self.uid_hash = Some(AUDIT_LOG.uid_hash(input));
+    }
+}
+
+/// Where the audit records go.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn record(&self, record: &AuditRecord) -> Result<()>;
+
+    /// Drop the records from before `cutoff_unix_ms`.
+    async fn prune(&self, cutoff_unix_ms: u64) -> Result<()>;
+}
+
+/// Appends the records as JSON lines to a file per day, `audit-<days-since-epoch>.jsonl` in a directory.
+pub struct FileAuditSink {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileAuditSink {
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn file_day(name: &str) -> Option<u64> {
+        name.strip_prefix("audit-")?
+            .strip_suffix(".jsonl")?
+            .parse()
+            .ok()
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn record(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let day = record.timestamp_unix_ms / MILLIS_PER_DAY;
+        let path = self.dir.join(format!("audit-{day}.jsonl"));
+        let _guard = self.lock.lock();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&line)?;
+        Ok(())
+    }
+
+    async fn prune(&self, cutoff_unix_ms: u64) -> Result<()> {
+        let cutoff_day = cutoff_unix_ms / MILLIS_PER_DAY;
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            match name.to_str().and_then(Self::file_day) {
+                Some(day) if day < cutoff_day => fs::remove_file(entry.path())?,
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An `AuditRecord` in firestore, with the time after which a TTL policy on `expire_at` deletes it.
+#[derive(Debug, Deserialize, Serialize)]
+struct FirestoreAuditRecord {
+    session_id: String,
+    timestamp_unix_ms: u64,
+    iss: Option<String>,
+    aud: Option<String>,
+    uid_hash: Option<String>,
+    error_code: Option<PepperErrorCode>,
+    #[serde(with = "firestore::serialize_as_timestamp")]
+    expire_at: DateTime<Utc>,
+}
+
+/// Writes the records to the `audit_log` collection of the account recovery DB.
+/// The retention is up to a TTL policy on the `expire_at` field of the collection.
+pub struct FirestoreAuditSink {
+    retention: Duration,
+}
+
+#[async_trait]
+impl AuditSink for FirestoreAuditSink {
+    async fn record(&self, record: &AuditRecord) -> Result<()> {
+        let db = ACCOUNT_RECOVERY_DB
+            .get_or_init(init_account_db)
+            .await
+            .as_ref()
+            .map_err(|e| anyhow!("account recovery db init error: {e}"))?;
+        let expire_at = DateTime::<Utc>::from(
+            UNIX_EPOCH + Duration::from_millis(record.timestamp_unix_ms) + self.retention,
+        );
+        let firestore_record = FirestoreAuditRecord {
+            session_id: record.session_id.clone(),
+            timestamp_unix_ms: record.timestamp_unix_ms,
+            iss: record.iss.clone(),
+            aud: record.aud.clone(),
+            uid_hash: record.uid_hash.clone(),
+            error_code: record.error_code,
+            expire_at,
+        };
+        let _: FirestoreAuditRecord = db
+            .fluent()
+            .insert()
+            .into("audit_log")
+            .document_id(&record.session_id)
+            .object(&firestore_record)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    async fn prune(&self, _cutoff_unix_ms: u64) -> Result<()> {
+        // Left to the TTL policy.
+        Ok(())
+    }
+}
+
+pub struct AuditLog {
+    salt: Vec<u8>,
+    retention: Duration,
+    sink: Option<Box<dyn AuditSink>>,
+}
+
+impl AuditLog {
+    /// The salted hash identifying the user of `input` in the records,
+    /// which cannot be linked back to the user without the salt.
+    pub fn uid_hash(&self, input: &PepperInput) -> String {
+        let mut hasher = sha3::Sha3_256::new();
+        hasher.update(&self.salt);
+        hasher.update(bcs::to_bytes(input).unwrap());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Write `record` in the background, if there is a sink.
+    pub fn record(&'static self, record: AuditRecord) {
+        if let Some(sink) = &self.sink {
+            let _handle = tokio::spawn(async move {
+                if let Err(e) = sink.record(&record).await {
+                    warn!(session_id = record.session_id, "audit record error: {e}");
+                }
+            });
+        }
+    }
+
+    /// Prune the records past the retention every `PRUNING_INTERVAL`, if there is a sink.
+    pub fn start_pruning_loop(&'static self) {
+        if let Some(sink) = &self.sink {
+            let _handle = tokio::spawn(async move {
+                loop {
+                    let now_unix_ms = duration_since_epoch().as_millis() as u64;
+                    let cutoff_unix_ms =
+                        now_unix_ms.saturating_sub(self.retention.as_millis() as u64);
+                    if let Err(e) = sink.prune(cutoff_unix_ms).await {
+                        warn!("audit log pruning error: {e}");
+                    }
+                    tokio::time::sleep(PRUNING_INTERVAL).await;
+                }
+            });
+        }
+    }
+}
+
+/// The audit log, from the environment.
+///
+/// ## How to use
+/// To record the pepper requests in daily files of a directory `<some-dir>`,
+/// put the following as the environment variables.
+/// `AUDIT_LOG_SINK=file`
+/// `AUDIT_LOG_DIR=<some-dir>`
+///
+/// To record them in the `audit_log` collection of the account recovery DB instead,
+/// put `AUDIT_LOG_SINK=firestore`, and set up a TTL policy on its `expire_at` field.
+///
+/// The users are identified by a hash salted with `AUDIT_LOG_SALT_HEX`, which should be kept secret,
+/// and stay the same for the records to be linked across restarts.
+/// Records are kept `AUDIT_LOG_RETENTION_DAYS` days, 30 by default.
+///
+/// Here is an example command.
+/// ```bash
+/// AUDIT_LOG_SINK=file \
+/// AUDIT_LOG_DIR=/var/log/pepper-audit \
+/// AUDIT_LOG_SALT_HEX=eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee \
+/// AUDIT_LOG_RETENTION_DAYS=90 \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static AUDIT_LOG: Lazy<AuditLog> = Lazy::new(|| {
+    let salt = match env::var("AUDIT_LOG_SALT_HEX").map(hex::decode) {
+        Ok(Ok(salt)) => salt,
+        Ok(Err(e)) => panic!("salt unhexlification error: {e}"),
+        Err(_) => {
+            warn!("`AUDIT_LOG_SALT_HEX` not set, using a random salt");
+            let mut salt = vec![0; 32];
+            rand::thread_rng().fill_bytes(&mut salt);
+            salt
+        },
+    };
+    let retention_days = env::var("AUDIT_LOG_RETENTION_DAYS")
+        .ok()
+        .map(|val| val.parse::<u64>().expect("bad AUDIT_LOG_RETENTION_DAYS"))
+        .unwrap_or(30);
+    let retention = Duration::from_secs(retention_days * 24 * 60 * 60);
+    let sink: Option<Box<dyn AuditSink>> = match env::var("AUDIT_LOG_SINK").as_deref() {
+        Ok("file") => {
+            let dir = env::var("AUDIT_LOG_DIR").expect("`AUDIT_LOG_DIR` required");
+            Some(Box::new(
+                FileAuditSink::new(PathBuf::from(dir)).expect("bad audit log dir"),
+            ))
+        },
+        Ok("firestore") => Some(Box::new(FirestoreAuditSink { retention })),
+        Ok(other) => panic!("unknown AUDIT_LOG_SINK {other}"),
+        Err(_) => None,
+    };
+    info!(
+        "AUDIT_LOG sink={:?} retention_days={}",
+        env::var("AUDIT_LOG_SINK").ok(),
+        retention_days
+    );
+    AuditLog {
+        salt,
+        retention,
+        sink,
+    }
+});
@@ -2,23 +2,80 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{metrics::JWK_FETCH_SECONDS, Issuer, KeyID};
-use anyhow::{anyhow, Result};
-use aptos_logger::warn;
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_infallible::Mutex as SyncMutex;
+use aptos_logger::{info, warn};
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use jsonwebtoken::{jwk::JwkSet, DecodingKey};
+use jsonwebtoken::{
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet},
+    Algorithm, DecodingKey,
+};
 use once_cell::sync::Lazy;
-use std::{sync::Arc, time::Duration};
-use tokio::time::Instant;
+use regex::Regex;
+use reqwest::Url;
+use serde::Deserialize;
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{sync::Mutex, time::Instant};
+
+/// Issuers discovered, beyond which no more get discovered, to bound the memory and the
+/// refresh loops.
+const MAX_NUM_DISCOVERY_ISSUERS: usize = 1_000;
+
+/// Issuers being discovered or whose discovery failed recently, beyond which no more get
+/// discovered, to bound the memory and the outgoing requests.
+const MAX_NUM_PENDING_DISCOVERY_ISSUERS: usize = 1_000;
+
+/// Timeout of each request of a discovery.
+const DISCOVERY_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A key from a JWK, with the JWT algorithms it verifies.
+pub struct JwkDecodingKey {
+    pub key: DecodingKey,
+    /// The `alg` of the JWK if set, otherwise all those of its key type and curve.
+    pub algorithms: Vec<Algorithm>,
+}
 
 /// The JWK in-mem cache.
-pub static DECODING_KEY_CACHE: Lazy<DashMap<Issuer, DashMap<KeyID, Arc<DecodingKey>>>> =
+pub static DECODING_KEY_CACHE: Lazy<DashMap<Issuer, DashMap<KeyID, Arc<JwkDecodingKey>>>> =
     Lazy::new(DashMap::new);
 
+/// The refresh loop of an issuer.
+pub struct JwkRefreshState {
+    pub refresh_interval: Duration,
+    /// Whether the issuer was discovered, see `JWK_DISCOVERY`, rather than configured.
+    pub discovered: bool,
+    pub last_success: Option<Instant>,
+}
+
+/// The refresh loops, by issuer.
+pub static JWK_REFRESH_STATES: Lazy<DashMap<Issuer, JwkRefreshState>> = Lazy::new(DashMap::new);
+
+/// How far ahead of the `Date` of the last JWK response the local clock is, in seconds.
+pub static OBSERVED_CLOCK_SKEW_SECS: Lazy<SyncMutex<Option<i64>>> =
+    Lazy::new(|| SyncMutex::new(None));
+
 /// Send a request to a JWK endpoint and return its JWK map.
-pub async fn fetch_jwks(jwk_url: &str) -> Result<DashMap<KeyID, Arc<DecodingKey>>> {
+pub async fn fetch_jwks(jwk_url: &str) -> Result<DashMap<KeyID, Arc<JwkDecodingKey>>> {
     let response = reqwest::get(jwk_url)
         .await
         .map_err(|e| anyhow!("jwk fetch error: {}", e))?;
+    if let Some(date) = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|val| DateTime::parse_from_rfc2822(val).ok())
+    {
+        let skew_secs = Utc::now().timestamp() - date.timestamp();
+        *OBSERVED_CLOCK_SKEW_SECS.lock() = Some(skew_secs);
+    }
     let text = response
         .text()
         .await
@@ -26,32 +83,92 @@ pub async fn fetch_jwks(jwk_url: &str) -> Result<DashMap<KeyID, Arc<DecodingKey>
     parse_jwks(&text)
 }
 
-pub fn parse_jwks(text: &str) -> Result<DashMap<KeyID, Arc<DecodingKey>>> {
+pub fn parse_jwks(text: &str) -> Result<DashMap<KeyID, Arc<JwkDecodingKey>>> {
     let JwkSet { keys } =
         serde_json::from_str(text).map_err(|e| anyhow!("error while parsing json: {}", e))?;
-    let key_map: DashMap<KeyID, Arc<DecodingKey>> = keys
+    let key_map: DashMap<KeyID, Arc<JwkDecodingKey>> = keys
         .into_iter()
-        .filter_map(
-            |jwk| match (&jwk.common.key_id, DecodingKey::from_jwk(&jwk)) {
-                (Some(kid), Ok(key)) => Some((kid.clone(), Arc::new(key))),
-                (Some(kid), Err(e)) => {
-                    warn!("error while parsing for kid {kid}: {e}");
-                    None
-                },
-                (None, _) => {
-                    warn!("Ignoring a kid-less jwk: {jwk:?}");
-                    None
-                },
+        .filter_map(|jwk| match (&jwk.common.key_id, parse_jwk(&jwk)) {
+            (Some(kid), Ok(key)) => Some((kid.clone(), Arc::new(key))),
+            (Some(kid), Err(e)) => {
+                warn!("error while parsing for kid {kid}: {e}");
+                None
             },
-        )
+            (None, _) => {
+                warn!("Ignoring a kid-less jwk: {jwk:?}");
+                None
+            },
+        })
         .collect();
     Ok(key_map)
 }
 
+/// Parse an RSA, EC (P-256 or P-384) or OKP (Ed25519) JWK.
+pub fn parse_jwk(jwk: &Jwk) -> Result<JwkDecodingKey> {
+    let (key, key_algorithms) = match &jwk.algorithm {
+        AlgorithmParameters::RSA(params) => (
+            DecodingKey::from_rsa_components(&params.n, &params.e)?,
+            vec![
+                Algorithm::RS256,
+                Algorithm::RS384,
+                Algorithm::RS512,
+                Algorithm::PS256,
+                Algorithm::PS384,
+                Algorithm::PS512,
+            ],
+        ),
+        AlgorithmParameters::EllipticCurve(params) => {
+            let algorithm = match params.curve {
+                EllipticCurve::P256 => Algorithm::ES256,
+                EllipticCurve::P384 => Algorithm::ES384,
+                ref curve => bail!("unsupported EC curve: {:?}", curve),
+            };
+            (
+                DecodingKey::from_ec_components(&params.x, &params.y)?,
+                vec![algorithm],
+            )
+        },
+        AlgorithmParameters::OctetKeyPair(params) => {
+            if params.curve != EllipticCurve::Ed25519 {
+                bail!("unsupported OKP curve: {:?}", params.curve);
+            }
+            (DecodingKey::from_ed_components(&params.x)?, vec![
+                Algorithm::EdDSA,
+            ])
+        },
+        AlgorithmParameters::OctetKey(_) => bail!("symmetric keys are not supported"),
+    };
+    let algorithms = match jwk.common.algorithm {
+        Some(algorithm) if key_algorithms.contains(&algorithm) => vec![algorithm],
+        Some(algorithm) => bail!("alg {:?} does not match the key type", algorithm),
+        None => key_algorithms,
+    };
+    Ok(JwkDecodingKey { key, algorithms })
+}
+
 pub fn start_jwk_refresh_loop(issuer: &str, jwk_url: &str, refresh_interval: Duration) {
+    spawn_jwk_refresh_loop(issuer, jwk_url, refresh_interval, false);
+}
+
+/// Refresh the JWKs of `issuer` every `refresh_interval`, starting right away,
+/// or after an interval for a discovered issuer, whose JWKs were just fetched.
+fn spawn_jwk_refresh_loop(
+    issuer: &str,
+    jwk_url: &str,
+    refresh_interval: Duration,
+    delay_first_refresh: bool,
+) {
     let issuer = issuer.to_string();
     let jwk_url = jwk_url.to_string();
+    JWK_REFRESH_STATES.insert(issuer.clone(), JwkRefreshState {
+        refresh_interval,
+        discovered: delay_first_refresh,
+        last_success: delay_first_refresh.then(Instant::now),
+    });
     let _handle = tokio::spawn(async move {
+        if delay_first_refresh {
+            tokio::time::sleep(refresh_interval).await;
+        }
         loop {
             let timer = Instant::now();
             let fetch_result = fetch_jwks(jwk_url.as_str()).await;
@@ -63,6 +180,9 @@ pub fn start_jwk_refresh_loop(issuer: &str, jwk_url: &str, refresh_interval: Dur
             match fetch_result {
                 Ok(key_set) => {
                     DECODING_KEY_CACHE.insert(issuer.clone(), key_set.clone());
+                    if let Some(mut state) = JWK_REFRESH_STATES.get_mut(&issuer) {
+                        state.last_success = Some(Instant::now());
+                    }
                 },
                 Err(msg) => {
                     warn!(
@@ -78,7 +198,7 @@ pub fn start_jwk_refresh_loop(issuer: &str, jwk_url: &str, refresh_interval: Dur
     });
 }
 
-pub fn cached_decoding_key(issuer: &String, kid: &String) -> Result<Arc<DecodingKey>> {
+pub fn cached_decoding_key(issuer: &String, kid: &String) -> Result<Arc<JwkDecodingKey>> {
     let key_set = DECODING_KEY_CACHE
         .get(issuer)
         .ok_or_else(|| anyhow!("unknown issuer: {}", issuer))?;
@@ -87,3 +207,413 @@ pub fn cached_decoding_key(issuer: &String, kid: &String) -> Result<Arc<Decoding
         .ok_or_else(|| anyhow!("unknown kid: {}", kid))?;
     Ok(key.clone())
 }
+
+/// How the JWKs of the issuers not configured statically get discovered.
+pub struct JwkDiscoveryConfig {
+    /// The hosts of the issuers that may be discovered, as patterns where `*` matches a single
+    /// DNS label.
+    pub issuers: Vec<Regex>,
+    /// How often the JWKs of a discovered issuer get refreshed.
+    pub refresh_interval: Duration,
+    /// How long before a failed discovery of an issuer gets retried.
+    pub negative_ttl: Duration,
+}
+
+/// The JWK discovery config, from the environment.
+///
+/// ## How to use
+/// To discover the JWKs of the `https` issuers whose host matches `<host-pattern-1>` or
+/// `<host-pattern-2>`, where `*` matches a single DNS label, through OIDC discovery
+/// (`<issuer>/.well-known/openid-configuration`) on their first request,
+/// put the following as the environment variable.
+/// `JWK_DISCOVERY_ISSUERS=<host-pattern-1>,<host-pattern-2>`
+///
+/// Optionally, also put the following to refresh their JWKs every `<refresh-secs>` seconds (default 600),
+/// and retry their failed discoveries after `<negative-ttl-secs>` seconds (default 60).
+/// `JWK_DISCOVERY_REFRESH_INTERVAL_SECS=<refresh-secs>`
+/// `JWK_DISCOVERY_NEGATIVE_TTL_SECS=<negative-ttl-secs>`
+///
+/// Here is an example command.
+/// ```bash
+/// JWK_DISCOVERY_ISSUERS=login.microsoftonline.com,*.example.com \
+/// JWK_DISCOVERY_REFRESH_INTERVAL_SECS=300 \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static JWK_DISCOVERY: Lazy<JwkDiscoveryConfig> = Lazy::new(|| {
+    let issuers = env::var("JWK_DISCOVERY_ISSUERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .filter_map(|pattern| match host_pattern_to_regex(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                warn!("Ignoring issuer pattern {pattern} of JWK_DISCOVERY_ISSUERS: {e}");
+                None
+            },
+        })
+        .collect();
+    let secs_from_env = |name: &str, default: u64| {
+        env::var(name)
+            .ok()
+            .and_then(|val| match val.parse::<u64>() {
+                Ok(secs) if secs > 0 => Some(secs),
+                _ => {
+                    warn!("Ignoring {name}={val}, which is not a positive integer");
+                    None
+                },
+            })
+            .map_or(Duration::from_secs(default), Duration::from_secs)
+    };
+    let config = JwkDiscoveryConfig {
+        issuers,
+        refresh_interval: secs_from_env("JWK_DISCOVERY_REFRESH_INTERVAL_SECS", 600),
+        negative_ttl: secs_from_env("JWK_DISCOVERY_NEGATIVE_TTL_SECS", 60),
+    };
+    info!(
+        "JWK_DISCOVERY issuers={:?} refresh_interval={:?} negative_ttl={:?}",
+        config.issuers, config.refresh_interval, config.negative_ttl
+    );
+    config
+});
+
+/// A regex matching the hosts that `pattern` matches, where `*` matches a single DNS label.
+/// An `https://` prefix is allowed, but no path.
+fn host_pattern_to_regex(pattern: &str) -> Result<Regex> {
+    let pattern = pattern.strip_prefix("https://").unwrap_or(pattern);
+    ensure!(
+        !pattern.is_empty() && !pattern.contains(['/', ':', '@']),
+        "not a host pattern"
+    );
+    let regex = pattern
+        .to_ascii_lowercase()
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join("[^./]+");
+    Ok(Regex::new(&format!("^{regex}$"))?)
+}
+
+impl JwkDiscoveryConfig {
+    /// Whether `issuer` is a plain `https` URL whose host matches one of the patterns.
+    pub fn may_discover(&self, issuer: &str) -> bool {
+        let Ok(url) = Url::parse(issuer) else {
+            return false;
+        };
+        if url.scheme() != "https"
+            || !url.username().is_empty()
+            || url.password().is_some()
+            || url.port().is_some()
+            || url.query().is_some()
+            || url.fragment().is_some()
+        {
+            return false;
+        }
+        url.host_str().map_or(false, |host| {
+            self.issuers.iter().any(|regex| regex.is_match(host))
+        })
+    }
+}
+
+/// For each issuer being discovered or whose discovery failed, until when its last failed
+/// discovery is not retried. Locked during its discovery, so that concurrent requests share one.
+/// Removed once the issuer is discovered, or once the failure expires.
+static DISCOVERY_STATES: Lazy<DashMap<Issuer, Arc<Mutex<Option<Instant>>>>> =
+    Lazy::new(DashMap::new);
+
+/// The number of issuers discovered, see `MAX_NUM_DISCOVERY_ISSUERS`.
+static NUM_DISCOVERED_ISSUERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Drop the states of the failed discoveries that may be retried, and of the abandoned ones.
+fn prune_discovery_states(now: Instant) {
+    DISCOVERY_STATES.retain(|_, state| match state.try_lock() {
+        Ok(failed_until) => (*failed_until).map_or(false, |until| now < until),
+        // Being discovered.
+        Err(_) => true,
+    });
+}
+
+#[derive(Deserialize)]
+struct OpenIdConfiguration {
+    issuer: String,
+    jwks_uri: String,
+}
+
+/// The decoding key of `kid` of `issuer`, discovering the JWKs of `issuer` if it is unknown,
+/// see `JWK_DISCOVERY`.
+pub async fn decoding_key(issuer: &String, kid: &String) -> Result<Arc<JwkDecodingKey>> {
+    if !DECODING_KEY_CACHE.contains_key(issuer) && JWK_DISCOVERY.may_discover(issuer) {
+        discover(issuer).await?;
+    }
+    cached_decoding_key(issuer, kid)
+}
+
+async fn discover(issuer: &Issuer) -> Result<()> {
+    let state = match DISCOVERY_STATES.get(issuer) {
+        Some(state) => state.clone(),
+        None => {
+            ensure!(
+                NUM_DISCOVERED_ISSUERS.load(Ordering::Relaxed) < MAX_NUM_DISCOVERY_ISSUERS,
+                "too many issuers discovered to discover {}",
+                issuer
+            );
+            prune_discovery_states(Instant::now());
+            ensure!(
+                DISCOVERY_STATES.len() < MAX_NUM_PENDING_DISCOVERY_ISSUERS,
+                "too many issuers being discovered to discover {}",
+                issuer
+            );
+            DISCOVERY_STATES.entry(issuer.clone()).or_default().clone()
+        },
+    };
+    let mut failed_until = state.lock().await;
+    if DECODING_KEY_CACHE.contains_key(issuer) {
+        // Discovered by a concurrent request.
+        return Ok(());
+    }
+    if let Some(until) = *failed_until {
+        ensure!(
+            Instant::now() >= until,
+            "discovery of issuer {} failed recently",
+            issuer
+        );
+    }
+
+    let timer = Instant::now();
+    let result = fetch_discovered_jwks(issuer).await;
+    JWK_FETCH_SECONDS
+        .with_label_values(&[issuer.as_str(), result.is_ok().to_string().as_str()])
+        .observe(timer.elapsed().as_secs_f64());
+    match result {
+        Ok((jwks_uri, key_set)) => {
+            info!(
+                issuer = issuer,
+                jwk_url = jwks_uri,
+                "discovered JWKs of {} kids",
+                key_set.len()
+            );
+            DECODING_KEY_CACHE.insert(issuer.clone(), key_set);
+            spawn_jwk_refresh_loop(issuer, &jwks_uri, JWK_DISCOVERY.refresh_interval, true);
+            NUM_DISCOVERED_ISSUERS.fetch_add(1, Ordering::Relaxed);
+            *failed_until = None;
+            // Concurrent requests holding the state find the issuer in the cache.
+            DISCOVERY_STATES.remove(issuer);
+            Ok(())
+        },
+        Err(e) => {
+            warn!(issuer = issuer, "error discovering JWKs: {}", e);
+            *failed_until = Some(Instant::now() + JWK_DISCOVERY.negative_ttl);
+            Err(anyhow!("discovery of issuer {} failed: {}", issuer, e))
+        },
+    }
+}
+
+/// Fetch the OIDC configuration of `issuer`, then its JWKs, returning its `jwks_uri` with them.
+async fn fetch_discovered_jwks(
+    issuer: &str,
+) -> Result<(String, DashMap<KeyID, Arc<JwkDecodingKey>>)> {
+    let config_url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let config: OpenIdConfiguration = tokio::time::timeout(DISCOVERY_REQUEST_TIMEOUT, async {
+        reqwest::get(config_url.as_str())
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    })
+    .await
+    .map_err(|_| anyhow!("openid configuration fetch timed out"))?
+    .map_err(|e| anyhow!("openid configuration fetch error: {}", e))?;
+    ensure!(
+        config.issuer == issuer,
+        "openid configuration is of another issuer: {}",
+        config.issuer
+    );
+    ensure!(
+        config.jwks_uri.starts_with("https://"),
+        "jwks_uri is not https: {}",
+        config.jwks_uri
+    );
+    let key_set = tokio::time::timeout(DISCOVERY_REQUEST_TIMEOUT, fetch_jwks(&config.jwks_uri))
+        .await
+        .map_err(|_| anyhow!("jwk fetch timed out"))??;
+    Ok((config.jwks_uri, key_set))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovery_config(patterns: &[&str]) -> JwkDiscoveryConfig {
+        JwkDiscoveryConfig {
+            issuers: patterns
+                .iter()
+                .map(|pattern| host_pattern_to_regex(pattern).unwrap())
+                .collect(),
+            refresh_interval: Duration::from_secs(600),
+            negative_ttl: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn discovery_matches_the_host_only() {
+        let config = discovery_config(&["*.example.com", "https://login.example.org"]);
+        assert!(config.may_discover("https://accounts.example.com"));
+        assert!(config.may_discover("https://ACCOUNTS.example.com/tenant/v2.0"));
+        assert!(config.may_discover("https://login.example.org/tenant"));
+
+        // `*` is a single label.
+        assert!(!config.may_discover("https://example.com"));
+        assert!(!config.may_discover("https://a.b.example.com"));
+        assert!(!config.may_discover("https://attacker.net/x.example.com"));
+        assert!(!config.may_discover("https://attacker.net?x.example.com"));
+        assert!(!config.may_discover("https://accounts.example.com.attacker.net"));
+        assert!(!config.may_discover("https://accounts.example.com@attacker.net"));
+        assert!(!config.may_discover("https://attacker.net\\.example.com"));
+
+        // Only plain https URLs.
+        assert!(!config.may_discover("http://accounts.example.com"));
+        assert!(!config.may_discover("https://accounts.example.com:8443"));
+        assert!(!config.may_discover("https://user@accounts.example.com"));
+        assert!(!config.may_discover("https://accounts.example.com/?x=1"));
+        assert!(!config.may_discover("accounts.example.com"));
+    }
+
+    #[test]
+    fn host_patterns_without_host_are_rejected() {
+        assert!(host_pattern_to_regex("").is_err());
+        assert!(host_pattern_to_regex("https://login.example.org/*").is_err());
+        assert!(host_pattern_to_regex("login.example.org:8443").is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_failed_discoveries_are_pruned() {
+        let now = Instant::now();
+        let state = |failed_until: Option<Instant>| Arc::new(Mutex::new(failed_until));
+        let expired = "https://expired.prune.test".to_string();
+        let failed = "https://failed.prune.test".to_string();
+        let abandoned = "https://abandoned.prune.test".to_string();
+        let in_flight = "https://in-flight.prune.test".to_string();
+        DISCOVERY_STATES.insert(expired.clone(), state(Some(now - Duration::from_secs(1))));
+        DISCOVERY_STATES.insert(failed.clone(), state(Some(now + Duration::from_secs(60))));
+        DISCOVERY_STATES.insert(abandoned.clone(), state(None));
+        let in_flight_state = state(None);
+        DISCOVERY_STATES.insert(in_flight.clone(), in_flight_state.clone());
+        let _guard = in_flight_state.lock().await;
+
+        prune_discovery_states(now);
+        assert!(!DISCOVERY_STATES.contains_key(&expired));
+        assert!(DISCOVERY_STATES.contains_key(&failed));
+        assert!(!DISCOVERY_STATES.contains_key(&abandoned));
+        assert!(DISCOVERY_STATES.contains_key(&in_flight));
+    }
+
+    fn parse(jwk: serde_json::Value) -> Result<JwkDecodingKey> {
+        parse_jwk(&serde_json::from_value(jwk).unwrap())
+    }
+
+    #[test]
+    fn parse_rsa_jwk() {
+        let key = parse(serde_json::json!({
+            "kty": "RSA",
+            "kid": "rsa",
+            "n": "sXchDaQebHnPiGvyDOAT4saGEUetSyo9MKLOoWFsueri23bOdgWp4Dy1WlUzewbgBHod5pcM9H95GQRV3JDXbw",
+            "e": "AQAB",
+        }))
+        .unwrap();
+        assert!(key.algorithms.contains(&Algorithm::RS256));
+        assert!(key.algorithms.contains(&Algorithm::PS512));
+        assert!(!key.algorithms.contains(&Algorithm::ES256));
+    }
+
+    #[test]
+    fn parse_ec_jwk() {
+        let key = parse(serde_json::json!({
+            "kty": "EC",
+            "kid": "ec",
+            "crv": "P-256",
+            "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+            "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+        }))
+        .unwrap();
+        assert_eq!(key.algorithms, vec![Algorithm::ES256]);
+    }
+
+    #[test]
+    fn parse_okp_jwk() {
+        let key = parse(serde_json::json!({
+            "kty": "OKP",
+            "kid": "okp",
+            "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+            "alg": "EdDSA",
+        }))
+        .unwrap();
+        assert_eq!(key.algorithms, vec![Algorithm::EdDSA]);
+    }
+
+    #[test]
+    fn jwk_alg_restricts_the_algorithms() {
+        let key = parse(serde_json::json!({
+            "kty": "RSA",
+            "kid": "rsa",
+            "n": "sXchDaQebHnPiGvyDOAT4saGEUetSyo9MKLOoWFsueri23bOdgWp4Dy1WlUzewbgBHod5pcM9H95GQRV3JDXbw",
+            "e": "AQAB",
+            "alg": "RS256",
+        }))
+        .unwrap();
+        assert_eq!(key.algorithms, vec![Algorithm::RS256]);
+    }
+
+    #[test]
+    fn jwk_alg_mismatching_the_key_type_is_rejected() {
+        assert!(parse(serde_json::json!({
+            "kty": "EC",
+            "kid": "ec",
+            "crv": "P-256",
+            "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+            "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+            "alg": "RS256",
+        }))
+        .is_err());
+        assert!(parse(serde_json::json!({
+            "kty": "EC",
+            "kid": "ec",
+            "crv": "P-384",
+            "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+            "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+            "alg": "ES256",
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn parse_jwks_skips_invalid_keys() {
+        let key_map = parse_jwks(
+            &serde_json::json!({
+                "keys": [
+                    {
+                        "kty": "OKP",
+                        "kid": "okp",
+                        "crv": "Ed25519",
+                        "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+                    },
+                    {
+                        "kty": "OKP",
+                        "kid": "mismatch",
+                        "crv": "Ed25519",
+                        "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+                        "alg": "ES256",
+                    },
+                ],
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(key_map.len(), 1);
+        assert!(key_map.contains_key("okp"));
+    }
+}
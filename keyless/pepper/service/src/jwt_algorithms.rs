@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Issuer;
+use aptos_logger::{info, warn};
+use jsonwebtoken::Algorithm;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{collections::HashMap, env, str::FromStr};
+
+/// The JWT signing algorithms accepted for an issuer with no allowlist in `JWT_ALGORITHMS`.
+pub const DEFAULT_JWT_ALGORITHMS: &[Algorithm] = &[Algorithm::RS256];
+
+/// The JWT signing algorithms accepted for each issuer, by the `alg` in the JWT header,
+/// as long as the JWK of its `kid` is of a matching key type.
+///
+/// ## How to use
+/// For an issuer `<some-issuer>` signing with algorithms `<alg-1>` and `<alg-2>`,
+/// give it a unique short name `<some-short-name>` and put the following as the environment variables.
+/// `JWT_ALGORITHMS_<some-short-name>_ISSUER=<some-issuer>
+/// `JWT_ALGORITHMS_<some-short-name>_ALGS=<alg-1>,<alg-2>
+///
+/// Algorithms are named as in the JWT header, e.g. `RS256`, `ES256` or `EdDSA`.
+/// Issuers not specified accept `DEFAULT_JWT_ALGORITHMS` only.
+///
+/// Here is an example command.
+/// ```bash
+/// JWT_ALGORITHMS_1A_ISSUER=https://accounts.example.com \
+/// JWT_ALGORITHMS_1A_ALGS=RS256,ES256 \
+/// JWT_ALGORITHMS_2B_ISSUER=https://login.example.org \
+/// JWT_ALGORITHMS_2B_ALGS=EdDSA \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static JWT_ALGORITHMS: Lazy<HashMap<Issuer, Vec<Algorithm>>> = Lazy::new(|| {
+    let ret = parse_jwt_algorithms(env::vars());
+    info!("JWT_ALGORITHMS={:?}", ret);
+    ret
+});
+
+/// The allowlists given by the `JWT_ALGORITHMS_*` variables of `vars`.
+fn parse_jwt_algorithms(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> HashMap<Issuer, Vec<Algorithm>> {
+    let re_issuer = Regex::new(r"JWT_ALGORITHMS_(\w+)_ISSUER").unwrap();
+    let re_algs = Regex::new(r"JWT_ALGORITHMS_(\w+)_ALGS").unwrap();
+    let mut working_set: HashMap<String, Collector> = HashMap::new();
+
+    vars.into_iter().for_each(|(key, val)| {
+        if let Some(caps) = re_issuer.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            working_set.entry(uid).or_default().issuer = Some(val);
+        } else if let Some(caps) = re_algs.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            let algorithms = val
+                .split(',')
+                .filter_map(|name| match Algorithm::from_str(name.trim()) {
+                    Ok(algorithm) => Some(algorithm),
+                    Err(e) => {
+                        warn!("Ignoring JWT algorithm {name} of {key}: {e}");
+                        None
+                    },
+                })
+                .collect();
+            working_set.entry(uid).or_default().algorithms = Some(algorithms);
+        }
+    });
+
+    working_set
+        .into_values()
+        .filter_map(|collector| {
+            let Collector { issuer, algorithms } = collector;
+            if let (Some(issuer), Some(algorithms)) = (issuer, algorithms) {
+                Some((issuer, algorithms))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Whether JWTs of `issuer` may be signed with `algorithm`.
+pub fn is_allowed(issuer: &str, algorithm: Algorithm) -> bool {
+    is_allowed_by(&JWT_ALGORITHMS, issuer, algorithm)
+}
+
+fn is_allowed_by(
+    allowlists: &HashMap<Issuer, Vec<Algorithm>>,
+    issuer: &str,
+    algorithm: Algorithm,
+) -> bool {
+    allowlists
+        .get(issuer)
+        .map_or(DEFAULT_JWT_ALGORITHMS, Vec::as_slice)
+        .contains(&algorithm)
+}
+
+#[derive(Default)]
+struct Collector {
+    pub issuer: Option<Issuer>,
+    pub algorithms: Option<Vec<Algorithm>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(key, val)| (key.to_string(), val.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn per_issuer_allowlist() {
+        let allowlists = parse_jwt_algorithms(vars(&[
+            ("JWT_ALGORITHMS_1A_ISSUER", "https://accounts.example.com"),
+            ("JWT_ALGORITHMS_1A_ALGS", "RS256, ES256,XX999"),
+            ("JWT_ALGORITHMS_2B_ISSUER", "https://login.example.org"),
+            ("JWT_ALGORITHMS_2B_ALGS", "EdDSA"),
+            ("JWT_ALGORITHMS_3C_ISSUER", "https://incomplete.example.net"),
+        ]));
+        assert_eq!(allowlists.len(), 2);
+        assert_eq!(allowlists["https://accounts.example.com"], vec![
+            Algorithm::RS256,
+            Algorithm::ES256
+        ]);
+
+        let issuer = "https://login.example.org";
+        assert!(is_allowed_by(&allowlists, issuer, Algorithm::EdDSA));
+        assert!(!is_allowed_by(&allowlists, issuer, Algorithm::RS256));
+        assert!(!is_allowed_by(&allowlists, issuer, Algorithm::ES256));
+    }
+
+    #[test]
+    fn issuers_without_allowlist_accept_rs256_only() {
+        let allowlists = parse_jwt_algorithms(vars(&[
+            ("JWT_ALGORITHMS_1A_ISSUER", "https://accounts.example.com"),
+            ("JWT_ALGORITHMS_1A_ALGS", "ES256"),
+        ]));
+        let issuer = "https://incomplete.example.net";
+        assert!(is_allowed_by(&allowlists, issuer, Algorithm::RS256));
+        assert!(!is_allowed_by(&allowlists, issuer, Algorithm::RS384));
+        assert!(!is_allowed_by(&allowlists, issuer, Algorithm::ES256));
+        assert!(!is_allowed_by(&allowlists, issuer, Algorithm::EdDSA));
+    }
+}
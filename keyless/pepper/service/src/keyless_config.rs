@@ -0,0 +1,142 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use aptos_infallible::RwLock;
+use aptos_logger::{info, warn};
+use aptos_types::keyless::Configuration;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::{env, time::Duration};
+
+/// The `Configuration` fields the pepper service depends on, which differ across networks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeylessParams {
+    /// How far after the JWT `iat` the epk may expire.
+    pub max_exp_horizon_secs: u64,
+    /// The nonce parameter bounding the committed epk.
+    pub max_commited_epk_bytes: u16,
+}
+
+impl KeylessParams {
+    /// The `Configuration` of the network, based on that of devnet.
+    pub fn configuration(&self) -> Configuration {
+        let mut config = Configuration::new_for_devnet();
+        config.max_exp_horizon_secs = self.max_exp_horizon_secs;
+        config.max_commited_epk_bytes = self.max_commited_epk_bytes;
+        config
+    }
+}
+
+/// The keyless params of the target network.
+///
+/// ## How to use
+/// By default, they are those of `Configuration::new_for_devnet()`.
+///
+/// To fetch them from `0x1::keyless_account::Configuration` of the target network instead,
+/// refreshing them every `<refresh-secs>` seconds (default 300),
+/// put the following as the environment variables.
+/// `KEYLESS_CONFIG_FULLNODE_URL=<fullnode-rest-api-url>`
+/// `KEYLESS_CONFIG_REFRESH_SECS=<refresh-secs>`
+///
+/// Otherwise, to override them, put the following as the environment variables.
+/// `KEYLESS_MAX_EXP_HORIZON_SECS=<max-exp-horizon-secs>`
+/// `KEYLESS_MAX_COMMITED_EPK_BYTES=<max-commited-epk-bytes>`
+///
+/// Here is an example command.
+/// ```bash
+/// KEYLESS_CONFIG_FULLNODE_URL=https://fullnode.mainnet.aptoslabs.com \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static KEYLESS_PARAMS: Lazy<RwLock<KeylessParams>> = Lazy::new(|| {
+    let devnet = Configuration::new_for_devnet();
+    let params = KeylessParams {
+        max_exp_horizon_secs: param_from_env("KEYLESS_MAX_EXP_HORIZON_SECS")
+            .unwrap_or(devnet.max_exp_horizon_secs),
+        max_commited_epk_bytes: param_from_env("KEYLESS_MAX_COMMITED_EPK_BYTES")
+            .unwrap_or(devnet.max_commited_epk_bytes),
+    };
+    info!("KEYLESS_PARAMS={:?}", params);
+    RwLock::new(params)
+});
+
+fn param_from_env<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let val = env::var(name).ok()?;
+    match val.parse() {
+        Ok(val) => Some(val),
+        Err(_) => {
+            warn!("Ignoring {name}={val}, which is not a valid number");
+            None
+        },
+    }
+}
+
+/// The `Configuration` of the target network, see `KEYLESS_PARAMS`.
+pub fn keyless_configuration() -> Configuration {
+    KEYLESS_PARAMS.read().configuration()
+}
+
+/// The fields of `0x1::keyless_account::Configuration` in the REST API, where a `u64` is a string.
+#[derive(Deserialize)]
+struct OnChainConfiguration {
+    max_exp_horizon_secs: String,
+    max_commited_epk_bytes: u16,
+}
+
+#[derive(Deserialize)]
+struct MoveResource {
+    data: OnChainConfiguration,
+}
+
+async fn fetch_keyless_params(fullnode_url: &str) -> Result<KeylessParams> {
+    let url = format!(
+        "{}/v1/accounts/0x1/resource/0x1::keyless_account::Configuration",
+        fullnode_url.trim_end_matches('/')
+    );
+    let resource: MoveResource = reqwest::get(url.as_str())
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(KeylessParams {
+        max_exp_horizon_secs: resource
+            .data
+            .max_exp_horizon_secs
+            .parse()
+            .map_err(|e| anyhow!("bad max_exp_horizon_secs: {e}"))?,
+        max_commited_epk_bytes: resource.data.max_commited_epk_bytes,
+    })
+}
+
+/// If `KEYLESS_CONFIG_FULLNODE_URL` is set, keep `KEYLESS_PARAMS` in sync with the target network.
+pub fn start_keyless_params_refresh_loop() {
+    let Ok(fullnode_url) = env::var("KEYLESS_CONFIG_FULLNODE_URL") else {
+        return;
+    };
+    let refresh_interval = Duration::from_secs(
+        param_from_env("KEYLESS_CONFIG_REFRESH_SECS")
+            .unwrap_or(300)
+            .max(1),
+    );
+    let _handle = tokio::spawn(async move {
+        loop {
+            match fetch_keyless_params(&fullnode_url).await {
+                Ok(params) => {
+                    let mut current = KEYLESS_PARAMS.write();
+                    if *current != params {
+                        info!("KEYLESS_PARAMS updated from chain: {:?}", params);
+                        *current = params;
+                    }
+                },
+                Err(e) => {
+                    warn!(
+                        fullnode_url = fullnode_url,
+                        "error fetching keyless configuration: {e}"
+                    );
+                },
+            }
+            tokio::time::sleep(refresh_interval).await;
+        }
+    });
+}
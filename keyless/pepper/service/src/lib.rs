@@ -4,8 +4,12 @@
 use crate::{
     account_db::{init_account_db, ACCOUNT_RECOVERY_DB},
     account_managers::ACCOUNT_MANAGERS,
-    vuf_keys::VUF_SK,
-    ProcessingFailure::{BadRequest, InternalError},
+    audit::{AuditRecord, AUDIT_LOG},
+    keyless_config::keyless_configuration,
+    rate_limiter::RATE_LIMITERS,
+    uid_keys::UID_KEY_ALLOWLIST,
+    vuf_keys::VUF_SIGNER,
+    ProcessingFailure::{AudienceNotAllowed, BadRequest, InternalError, TooManyRequests},
 };
 use aptos_crypto::asymmetric_encryption::{
     elgamal_curve25519_aes256_gcm::ElGamalCurve25519Aes256Gcm, AsymmetricEncryption,
@@ -14,41 +18,75 @@ use aptos_infallible::duration_since_epoch;
 use aptos_keyless_pepper_common::{
     account_recovery_db::AccountRecoveryDbEntry,
     jwt::Claims,
+    verification::verify_pepper,
     vuf::{
-        self,
         bls12381_g1_bls::PinkasPepper,
         slip_10::{get_aptos_derivation_path, ExtendedPepper},
-        VUF,
     },
-    PepperInput, PepperRequest, PepperResponse, SignatureResponse,
+    BadPepperRequestError, PepperBatchItemResponse, PepperBatchRequest, PepperBatchResponse,
+    PepperErrorCode, PepperInput, PepperRequest, PepperResponse, PepperV1Request, PepperV1Response,
+    PepperVerificationRequest, PepperVerificationResponse, SignatureResponse,
+    MAX_PEPPER_BATCH_SIZE,
 };
-use aptos_logger::{info, warn};
+use aptos_logger::{error, info, warn};
 use aptos_types::{
     account_address::AccountAddress,
-    keyless::{Configuration, IdCommitment, KeylessPublicKey, OpenIdSig},
+    keyless::{IdCommitment, KeylessPublicKey, OpenIdSig},
     transaction::authenticator::{AnyPublicKey, AuthenticationKey, EphemeralPublicKey},
 };
 use firestore::{async_trait, paths, struct_path::path};
-use jsonwebtoken::{Algorithm::RS256, Validation};
+use jsonwebtoken::Validation;
 use rand::thread_rng;
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 pub mod about;
 pub mod account_db;
 pub mod account_managers;
+pub mod aud_allowlist;
+pub mod audit;
 pub mod jwk;
+pub mod jwt_algorithms;
+pub mod keyless_config;
 pub mod metrics;
+pub mod pepper_scopes;
+pub mod rate_limiter;
+pub mod uid_keys;
 pub mod vuf_keys;
+pub mod vuf_signer;
 
 pub type Issuer = String;
 pub type KeyID = String;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum ProcessingFailure {
-    BadRequest(String),
-    InternalError(String),
+    BadRequest(PepperErrorCode, String),
+    InternalError(PepperErrorCode, String),
+    /// The `aud` is not in the allowlist of the issuer, see `aud_allowlist`.
+    AudienceNotAllowed(String),
+    TooManyRequests {
+        message: String,
+        retry_after_secs: u64,
+    },
+}
+
+impl ProcessingFailure {
+    /// A rate limit was hit, with a token back in `retry_after`.
+    pub fn throttled(message: String, retry_after: Duration) -> Self {
+        TooManyRequests {
+            message,
+            retry_after_secs: retry_after.as_secs_f64().ceil() as u64,
+        }
+    }
+
+    pub fn code(&self) -> PepperErrorCode {
+        match self {
+            BadRequest(code, _) | InternalError(code, _) => *code,
+            AudienceNotAllowed(_) => PepperErrorCode::AudienceNotAllowed,
+            TooManyRequests { .. } => PepperErrorCode::RateLimited,
+        }
+    }
 }
 
 pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/637'/0'/0'/0'";
@@ -73,7 +111,7 @@ impl HandlerTrait<PepperRequest, PepperResponse> for V0FetchHandler {
             derivation_path,
         } = request;
 
-        let (_pepper_base, pepper, address) = process_common(
+        let (_pepper_base, pepper, address, _vuf_proof, _pepper_scope_salt) = process_common(
             &session_id,
             jwt,
             epk,
@@ -84,6 +122,7 @@ impl HandlerTrait<PepperRequest, PepperResponse> for V0FetchHandler {
             false,
             None,
             true,
+            false,
         )
         .await?;
 
@@ -94,6 +133,134 @@ impl HandlerTrait<PepperRequest, PepperResponse> for V0FetchHandler {
     }
 }
 
+pub struct V0BatchFetchHandler {
+    /// The IP of the client, if its requests are rate limited. Each item is charged like a
+    /// request, the first one with the token the batch request took.
+    pub client_ip: Option<String>,
+}
+
+impl V0BatchFetchHandler {
+    /// Takes a token from the rate limiter of the client IP for the `index`-th item.
+    fn check_ip_rate_limit(&self, index: usize) -> Result<(), ProcessingFailure> {
+        match (&RATE_LIMITERS.ip, &self.client_ip) {
+            (Some(limiter), Some(ip)) if index > 0 => {
+                limiter.try_acquire(ip).map_err(|retry_after| {
+                    ProcessingFailure::throttled(
+                        format!("too many requests from {ip}"),
+                        retry_after,
+                    )
+                })
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl HandlerTrait<PepperBatchRequest, PepperBatchResponse> for V0BatchFetchHandler {
+    async fn handle(
+        &self,
+        request: PepperBatchRequest,
+    ) -> Result<PepperBatchResponse, ProcessingFailure> {
+        if request.requests.len() > MAX_PEPPER_BATCH_SIZE {
+            return Err(BadRequest(
+                PepperErrorCode::TooManyRequestsInBatch,
+                format!(
+                    "too many requests in a batch: {} > {}",
+                    request.requests.len(),
+                    MAX_PEPPER_BATCH_SIZE
+                ),
+            ));
+        }
+
+        let mut responses = Vec::with_capacity(request.requests.len());
+        for (index, request) in request.requests.into_iter().enumerate() {
+            let response = match self.check_ip_rate_limit(index) {
+                Ok(()) => V0FetchHandler.handle(request).await,
+                Err(failure) => Err(failure),
+            };
+            let item = match response {
+                Ok(response) => PepperBatchItemResponse {
+                    response: Some(response),
+                    error: None,
+                },
+                Err(BadRequest(code, message)) => PepperBatchItemResponse {
+                    response: None,
+                    error: Some(BadPepperRequestError { code, message }),
+                },
+                Err(AudienceNotAllowed(message)) => PepperBatchItemResponse {
+                    response: None,
+                    error: Some(BadPepperRequestError {
+                        code: PepperErrorCode::AudienceNotAllowed,
+                        message,
+                    }),
+                },
+                Err(TooManyRequests {
+                    message,
+                    retry_after_secs,
+                }) => PepperBatchItemResponse {
+                    response: None,
+                    error: Some(BadPepperRequestError {
+                        code: PepperErrorCode::RateLimited,
+                        message: format!("{message}, retry after {retry_after_secs} secs"),
+                    }),
+                },
+                Err(InternalError(code, e)) => {
+                    error!("Processing a batch item failed with internal error: {e}");
+                    PepperBatchItemResponse {
+                        response: None,
+                        error: Some(BadPepperRequestError {
+                            code,
+                            message: "internal error".to_string(),
+                        }),
+                    }
+                },
+            };
+            responses.push(item);
+        }
+        Ok(PepperBatchResponse { responses })
+    }
+}
+
+pub struct V0VerifyHandler;
+
+#[async_trait]
+impl HandlerTrait<PepperVerificationRequest, PepperVerificationResponse> for V0VerifyHandler {
+    async fn handle(
+        &self,
+        request: PepperVerificationRequest,
+    ) -> Result<PepperVerificationResponse, ProcessingFailure> {
+        let PepperVerificationRequest {
+            input,
+            vuf_output,
+            vuf_proof,
+            pepper,
+            derivation_path,
+            pepper_scope_salt,
+        } = request;
+        let derivation_path = derivation_path.unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_owned());
+        let response = match verify_pepper(
+            VUF_SIGNER.public_key(),
+            &input,
+            pepper_scope_salt.as_deref(),
+            &vuf_output,
+            &vuf_proof,
+            &pepper,
+            &derivation_path,
+        ) {
+            Ok(()) => PepperVerificationResponse {
+                valid: true,
+                message: None,
+            },
+            Err(e) => PepperVerificationResponse {
+                valid: false,
+                message: Some(e.to_string()),
+            },
+        };
+        Ok(response)
+    }
+}
+
 pub struct V0SignatureHandler;
 
 #[async_trait]
@@ -109,7 +276,7 @@ impl HandlerTrait<PepperRequest, SignatureResponse> for V0SignatureHandler {
             derivation_path,
         } = request;
 
-        let (pepper_base, _pepper, _address) = process_common(
+        let (pepper_base, _pepper, _address, _vuf_proof, _pepper_scope_salt) = process_common(
             &session_id,
             jwt,
             epk,
@@ -120,6 +287,7 @@ impl HandlerTrait<PepperRequest, SignatureResponse> for V0SignatureHandler {
             false,
             None,
             false,
+            false,
         )
         .await?;
 
@@ -129,6 +297,49 @@ impl HandlerTrait<PepperRequest, SignatureResponse> for V0SignatureHandler {
     }
 }
 
+pub struct V1FetchHandler;
+
+#[async_trait]
+impl HandlerTrait<PepperV1Request, PepperV1Response> for V1FetchHandler {
+    async fn handle(
+        &self,
+        request: PepperV1Request,
+    ) -> Result<PepperV1Response, ProcessingFailure> {
+        let session_id = Uuid::new_v4();
+        let PepperV1Request {
+            jwt,
+            epk,
+            exp_date_secs,
+            epk_blinder,
+            uid_key,
+            derivation_path,
+        } = request;
+
+        let (pepper_base, pepper, address, vuf_proof, pepper_scope_salt) = process_common(
+            &session_id,
+            jwt,
+            epk,
+            exp_date_secs,
+            epk_blinder,
+            uid_key,
+            derivation_path,
+            false,
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+        Ok(PepperV1Response {
+            pepper,
+            address: address.to_vec(),
+            vuf_output: pepper_base,
+            vuf_proof,
+            pepper_scope_salt,
+        })
+    }
+}
+
 async fn process_common(
     session_id: &Uuid,
     jwt: String,
@@ -140,39 +351,93 @@ async fn process_common(
     encrypts_pepper: bool,
     aud: Option<String>,
     should_update_account_recovery_db: bool,
-) -> Result<(Vec<u8>, Vec<u8>, AccountAddress), ProcessingFailure> {
-    let config = Configuration::new_for_devnet();
+    with_vuf_proof: bool,
+) -> Result<(Vec<u8>, Vec<u8>, AccountAddress, Vec<u8>, Option<String>), ProcessingFailure> {
+    let mut audit_record = AuditRecord::new(session_id);
+    let result = process_common_audited(
+        &mut audit_record,
+        session_id,
+        jwt,
+        epk,
+        exp_date_secs,
+        epk_blinder,
+        uid_key,
+        derivation_path,
+        encrypts_pepper,
+        aud,
+        should_update_account_recovery_db,
+        with_vuf_proof,
+    )
+    .await;
+    audit_record.error_code = result.as_ref().err().map(ProcessingFailure::code);
+    AUDIT_LOG.record(audit_record);
+    result
+}
+
+async fn process_common_audited(
+    audit_record: &mut AuditRecord,
+    session_id: &Uuid,
+    jwt: String,
+    epk: EphemeralPublicKey,
+    exp_date_secs: u64,
+    epk_blinder: Vec<u8>,
+    uid_key: Option<String>,
+    derivation_path: Option<String>,
+    encrypts_pepper: bool,
+    aud: Option<String>,
+    should_update_account_recovery_db: bool,
+    with_vuf_proof: bool,
+) -> Result<(Vec<u8>, Vec<u8>, AccountAddress, Vec<u8>, Option<String>), ProcessingFailure> {
+    let config = keyless_configuration();
 
     let derivation_path = if let Some(path) = derivation_path {
         path
     } else {
         DEFAULT_DERIVATION_PATH.to_owned()
     };
-    let checked_derivation_path =
-        get_aptos_derivation_path(&derivation_path).map_err(|e| BadRequest(e.to_string()))?;
+    let checked_derivation_path = get_aptos_derivation_path(&derivation_path)
+        .map_err(|e| BadRequest(PepperErrorCode::InvalidDerivationPath, e.to_string()))?;
 
     let curve25519_pk_point = match &epk {
         EphemeralPublicKey::Ed25519 { public_key } => public_key
             .to_compressed_edwards_y()
             .decompress()
-            .ok_or_else(|| BadRequest("the pk point is off-curve".to_string()))?,
+            .ok_or_else(|| {
+                BadRequest(
+                    PepperErrorCode::UnsupportedEpk,
+                    "the pk point is off-curve".to_string(),
+                )
+            })?,
         _ => {
-            return Err(BadRequest("Only Ed25519 epk is supported".to_string()));
+            return Err(BadRequest(
+                PepperErrorCode::UnsupportedEpk,
+                "Only Ed25519 epk is supported".to_string(),
+            ));
         },
     };
 
-    let claims = aptos_keyless_pepper_common::jwt::parse(jwt.as_str())
-        .map_err(|e| BadRequest(format!("JWT decoding error: {e}")))?;
+    let claims = aptos_keyless_pepper_common::jwt::parse(jwt.as_str()).map_err(|e| {
+        BadRequest(
+            PepperErrorCode::MalformedJwt,
+            format!("JWT decoding error: {e}"),
+        )
+    })?;
     let now_secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs();
     if exp_date_secs <= now_secs {
-        return Err(BadRequest("epk expired".to_string()));
+        return Err(BadRequest(
+            PepperErrorCode::EpkExpired,
+            "epk expired".to_string(),
+        ));
     }
 
     if exp_date_secs >= claims.claims.iat + config.max_exp_horizon_secs {
-        return Err(BadRequest("epk expiry date too far".to_string()));
+        return Err(BadRequest(
+            PepperErrorCode::EpkExpiryTooFar,
+            "epk expiry date too far".to_string(),
+        ));
     }
 
     let actual_uid_key = if let Some(uid_key) = uid_key.as_ref() {
@@ -181,44 +446,83 @@ async fn process_common(
         "sub"
     };
 
-    let uid_val = if actual_uid_key == "email" {
-        claims
-            .claims
-            .email
-            .clone()
-            .ok_or_else(|| BadRequest("`email` required but not found in jwt".to_string()))?
-    } else if actual_uid_key == "sub" {
-        claims.claims.sub.clone()
-    } else {
-        return Err(BadRequest(format!(
-            "unsupported uid key: {}",
-            actual_uid_key
-        )));
-    };
+    if !UID_KEY_ALLOWLIST.is_allowed(&claims.claims.iss, actual_uid_key) {
+        return Err(BadRequest(
+            PepperErrorCode::UnsupportedUidKey,
+            format!("unsupported uid key: {}", actual_uid_key),
+        ));
+    }
+    let uid_val = claims
+        .claims
+        .uid_val(actual_uid_key)
+        .map_err(|e| BadRequest(PepperErrorCode::MissingUidClaim, e.to_string()))?;
 
     let recalculated_nonce =
         OpenIdSig::reconstruct_oauth_nonce(epk_blinder.as_slice(), exp_date_secs, &epk, &config)
-            .map_err(|e| BadRequest(format!("nonce reconstruction error: {e}")))?;
+            .map_err(|e| {
+                BadRequest(
+                    PepperErrorCode::NonceMismatch,
+                    format!("nonce reconstruction error: {e}"),
+                )
+            })?;
 
     if claims.claims.nonce != recalculated_nonce {
-        return Err(BadRequest("with nonce mismatch".to_string()));
+        return Err(BadRequest(
+            PepperErrorCode::NonceMismatch,
+            "with nonce mismatch".to_string(),
+        ));
     }
 
-    let key_id = claims
-        .header
-        .kid
-        .ok_or_else(|| BadRequest("missing kid in JWT".to_string()))?;
-
-    let sig_pub_key = jwk::cached_decoding_key(&claims.claims.iss, &key_id)
-        .map_err(|e| BadRequest(format!("JWK not found: {e}")))?;
-    let mut validation_with_sig_verification = Validation::new(RS256);
+    let key_id = claims.header.kid.ok_or_else(|| {
+        BadRequest(
+            PepperErrorCode::MissingKid,
+            "missing kid in JWT".to_string(),
+        )
+    })?;
+
+    let algorithm = claims.header.alg;
+    if !jwt_algorithms::is_allowed(&claims.claims.iss, algorithm) {
+        return Err(BadRequest(
+            PepperErrorCode::UnsupportedAlg,
+            format!(
+                "JWT algorithm {:?} not allowed for issuer {}",
+                algorithm, claims.claims.iss
+            ),
+        ));
+    }
+    let sig_pub_key = jwk::decoding_key(&claims.claims.iss, &key_id)
+        .await
+        .map_err(|e| {
+            let code = if jwk::DECODING_KEY_CACHE.contains_key(&claims.claims.iss) {
+                PepperErrorCode::UnknownKid
+            } else {
+                PepperErrorCode::UnknownIssuer
+            };
+            BadRequest(code, format!("JWK not found: {e}"))
+        })?;
+    if !sig_pub_key.algorithms.contains(&algorithm) {
+        return Err(BadRequest(
+            PepperErrorCode::UnsupportedAlg,
+            format!("JWT algorithm {:?} does not match the JWK", algorithm),
+        ));
+    }
+    let mut validation_with_sig_verification = Validation::new(algorithm);
     validation_with_sig_verification.validate_exp = false; // Don't validate the exp time
     let _claims = jsonwebtoken::decode::<Claims>(
         jwt.as_str(),
-        sig_pub_key.as_ref(),
+        &sig_pub_key.key,
         &validation_with_sig_verification,
     ) // Signature verification happens here.
-    .map_err(|e| BadRequest(format!("JWT signature verification failed: {e}")))?;
+    .map_err(|e| {
+        BadRequest(
+            PepperErrorCode::InvalidSignature,
+            format!("JWT signature verification failed: {e}"),
+        )
+    })?;
+
+    // Only verified JWTs count towards the limits of their issuer and audience,
+    // so that forged ones cannot throttle the actual users.
+    check_rate_limits(&claims.claims.iss, &claims.claims.aud)?;
 
     // If the pepper request is is from an account manager, and has a target aud specified, compute the pepper for the target aud.
     let mut aud_overridden = false;
@@ -229,6 +533,14 @@ async fn process_common(
             aud_overridden = true;
         }
     };
+    for aud in [&claims.claims.aud, &final_aud] {
+        if !aud_allowlist::is_allowed(&claims.claims.iss, aud) {
+            return Err(AudienceNotAllowed(format!(
+                "aud {} not allowed for issuer {}",
+                aud, claims.claims.iss
+            )));
+        }
+    }
 
     let input = PepperInput {
         iss: claims.claims.iss.clone(),
@@ -236,13 +548,14 @@ async fn process_common(
         uid_val,
         aud: final_aud,
     };
+    audit_record.set_input(&input);
 
     if !aud_overridden {
         info!(
             session_id = session_id,
             iss = input.iss.clone(),
             aud = input.aud.clone(),
-            uid_val = input.uid_val.clone(),
+            uid_hash = audit_record.uid_hash.clone(),
             uid_key = input.uid_key.clone(),
             "PepperInput is available."
         );
@@ -251,20 +564,35 @@ async fn process_common(
         }
     }
 
-    let input_bytes = bcs::to_bytes(&input).unwrap();
-    let (pepper_base, vuf_proof) = vuf::bls12381_g1_bls::Bls12381G1Bls::eval(&VUF_SK, &input_bytes)
-        .map_err(|e| InternalError(format!("bls12381_g1_bls eval error: {e}")))?;
-    if !vuf_proof.is_empty() {
-        return Err(InternalError("proof size should be 0".to_string()));
+    let pepper_scope_salt = pepper_scopes::scope_salt(&input.iss, &input.aud);
+    let input_bytes = input.vuf_input_bytes(pepper_scope_salt);
+    let (pepper_base, vuf_proof) = VUF_SIGNER
+        .eval(&input_bytes, with_vuf_proof)
+        .await
+        .map_err(|e| {
+            InternalError(
+                PepperErrorCode::VufEvalFailed,
+                format!("vuf eval error: {e}"),
+            )
+        })?;
+    if !with_vuf_proof && !vuf_proof.is_empty() {
+        return Err(InternalError(
+            PepperErrorCode::VufEvalFailed,
+            "proof size should be 0".to_string(),
+        ));
     }
 
-    let pinkas_pepper = PinkasPepper::from_affine_bytes(&pepper_base)
-        .map_err(|_| InternalError("Failed to derive pinkas pepper".to_string()))?;
+    let pinkas_pepper = PinkasPepper::from_affine_bytes(&pepper_base).map_err(|_| {
+        InternalError(
+            PepperErrorCode::PepperDerivationFailed,
+            "Failed to derive pinkas pepper".to_string(),
+        )
+    })?;
     let master_pepper = pinkas_pepper.to_master_pepper();
     let derived_pepper = ExtendedPepper::from_seed(master_pepper.to_bytes())
-        .map_err(|e| InternalError(e.to_string()))?
+        .map_err(|e| InternalError(PepperErrorCode::PepperDerivationFailed, e.to_string()))?
         .derive(&checked_derivation_path)
-        .map_err(|e| InternalError(e.to_string()))?
+        .map_err(|e| InternalError(PepperErrorCode::PepperDerivationFailed, e.to_string()))?
         .get_pepper();
 
     let idc = IdCommitment::new_from_preimage(
@@ -273,7 +601,7 @@ async fn process_common(
         &input.uid_key,
         &input.uid_val,
     )
-    .map_err(|e| InternalError(e.to_string()))?;
+    .map_err(|e| InternalError(PepperErrorCode::PepperDerivationFailed, e.to_string()))?;
     let public_key = KeylessPublicKey {
         iss_val: input.iss,
         idc,
@@ -290,18 +618,60 @@ async fn process_common(
             &curve25519_pk_point,
             &pepper_base,
         )
-        .map_err(|e| InternalError(format!("ElGamalCurve25519Aes256Gcm enc error: {e}")))?;
+        .map_err(|e| {
+            InternalError(
+                PepperErrorCode::PepperEncryptionFailed,
+                format!("ElGamalCurve25519Aes256Gcm enc error: {e}"),
+            )
+        })?;
         let pepper_encrypted = ElGamalCurve25519Aes256Gcm::enc(
             &mut main_rng,
             &mut aead_rng,
             &curve25519_pk_point,
             derived_pepper.to_bytes(),
         )
-        .map_err(|e| InternalError(format!("ElGamalCurve25519Aes256Gcm enc error: {e}")))?;
-        Ok((pepper_base_encrypted, pepper_encrypted, address))
+        .map_err(|e| {
+            InternalError(
+                PepperErrorCode::PepperEncryptionFailed,
+                format!("ElGamalCurve25519Aes256Gcm enc error: {e}"),
+            )
+        })?;
+        Ok((
+            pepper_base_encrypted,
+            pepper_encrypted,
+            address,
+            vuf_proof,
+            pepper_scope_salt.map(str::to_string),
+        ))
     } else {
-        Ok((pepper_base, derived_pepper.to_bytes().to_vec(), address))
+        Ok((
+            pepper_base,
+            derived_pepper.to_bytes().to_vec(),
+            address,
+            vuf_proof,
+            pepper_scope_salt.map(str::to_string),
+        ))
+    }
+}
+
+/// Takes a token from the rate limiters of the issuer and of the audience, if any.
+fn check_rate_limits(iss: &str, aud: &str) -> Result<(), ProcessingFailure> {
+    if let Some(limiter) = &RATE_LIMITERS.issuer {
+        limiter.try_acquire(iss).map_err(|retry_after| {
+            ProcessingFailure::throttled(format!("too many requests for issuer {iss}"), retry_after)
+        })?;
+    }
+    if let Some(limiter) = &RATE_LIMITERS.aud {
+        limiter
+            .try_acquire(&format!("{iss} {aud}"))
+            .map_err(|retry_after| {
+                ProcessingFailure::throttled(
+                    format!("too many requests for aud {aud}"),
+                    retry_after,
+                )
+            })?;
     }
+    Ok(())
 }
 
 /// Save a pepper request into the account recovery DB.
@@ -340,10 +710,12 @@ async fn update_account_recovery_db(input: &PepperInput) -> Result<(), Processin
             // which is defined as `first_request_unix_ms - 1_000_000_000_000_000`,
             // where 1_000_000_000_000_000 milliseconds is roughly 31710 years.
 
-            let mut txn = db
-                .begin_transaction()
-                .await
-                .map_err(|e| InternalError(format!("begin_transaction error: {e}")))?;
+            let mut txn = db.begin_transaction().await.map_err(|e| {
+                InternalError(
+                    PepperErrorCode::AccountRecoveryDbFailed,
+                    format!("begin_transaction error: {e}"),
+                )
+            })?;
             db.fluent()
                 .update()
                 .fields(paths!(AccountRecoveryDbEntry::{iss, aud, uid_key, uid_val}))
@@ -366,7 +738,12 @@ async fn update_account_recovery_db(input: &PepperInput) -> Result<(), Processin
                     ])
                 })
                 .add_to_transaction(&mut txn)
-                .map_err(|e| InternalError(format!("add_to_transaction error: {e}")))?;
+                .map_err(|e| {
+                    InternalError(
+                        PepperErrorCode::AccountRecoveryDbFailed,
+                        format!("add_to_transaction error: {e}"),
+                    )
+                })?;
             let txn_result = txn.commit().await;
 
             if let Err(e) = txn_result {
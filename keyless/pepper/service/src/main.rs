@@ -1,17 +1,26 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_keyless_pepper_common::BadPepperRequestError;
+use aptos_keyless_pepper_common::{
+    BadPepperRequestError, PepperErrorCode, ThrottledPepperRequestError,
+};
 use aptos_keyless_pepper_service::{
-    about::ABOUT_JSON,
+    about::{readiness, ABOUT_JSON},
     account_db::{init_account_db, ACCOUNT_RECOVERY_DB},
     account_managers::ACCOUNT_MANAGERS,
-    jwk::{self, parse_jwks, DECODING_KEY_CACHE},
+    aud_allowlist::AUD_ALLOWLIST,
+    audit::AUDIT_LOG,
+    jwk::{self, parse_jwks, DECODING_KEY_CACHE, JWK_DISCOVERY},
+    jwt_algorithms::JWT_ALGORITHMS,
+    keyless_config::{start_keyless_params_refresh_loop, KEYLESS_PARAMS},
     metrics::start_metric_server,
-    vuf_keys::{PEPPER_VUF_VERIFICATION_KEY_JSON, VUF_SK},
-    HandlerTrait,
-    ProcessingFailure::{BadRequest, InternalError},
-    V0FetchHandler, V0SignatureHandler,
+    pepper_scopes::PEPPER_SCOPES,
+    rate_limiter::RATE_LIMITERS,
+    uid_keys::UID_KEY_ALLOWLIST,
+    vuf_keys::{PEPPER_VUF_VERIFICATION_KEY_JSON, VUF_SIGNER},
+    HandlerTrait, ProcessingFailure,
+    ProcessingFailure::{AudienceNotAllowed, BadRequest, InternalError, TooManyRequests},
+    V0BatchFetchHandler, V0FetchHandler, V0SignatureHandler, V0VerifyHandler, V1FetchHandler,
 };
 use aptos_logger::{error, info};
 use aptos_types::keyless::test_utils::get_sample_iss;
@@ -20,23 +29,55 @@ use hyper::{
         ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
         ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_TYPE,
     },
+    server::conn::AddrStream,
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use std::{convert::Infallible, fmt::Debug, net::SocketAddr, ops::Deref, time::Duration};
 
-async fn handle_request(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+async fn handle_request(
+    req: Request<Body>,
+    remote_addr: SocketAddr,
+) -> Result<Response<Body>, Infallible> {
     let origin = req
         .headers()
         .get("origin")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("")
         .to_owned();
+    // The IP of the client, if its requests are rate limited.
+    let client_ip = match (req.method(), &RATE_LIMITERS.ip) {
+        (&Method::POST, Some(limiter)) => {
+            let ip = RATE_LIMITERS.client_ip(req.headers(), remote_addr);
+            if let Err(retry_after) = limiter.try_acquire(&ip) {
+                let failure = ProcessingFailure::throttled(
+                    format!("too many requests from {ip}"),
+                    retry_after,
+                );
+                return Ok(build_failure_response(origin, failure));
+            }
+            Some(ip)
+        },
+        _ => None,
+    };
     let response = match (req.method(), req.uri().path()) {
         (&Method::GET, "/about") => {
             build_response(origin, StatusCode::OK, ABOUT_JSON.deref().clone())
         },
+        (&Method::GET, "/ready") => {
+            let readiness = readiness().await;
+            let status_code = if readiness.ready {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            build_response(
+                origin,
+                status_code,
+                serde_json::to_string_pretty(&readiness).unwrap(),
+            )
+        },
         (&Method::GET, "/v0/vuf-pub-key") => build_response(
             origin,
             StatusCode::OK,
@@ -46,6 +87,11 @@ async fn handle_request(req: Request<Body>) -> Result<Response<Body>, Infallible
             generate_response(origin, req, &V0SignatureHandler).await
         },
         (&Method::POST, "/v0/fetch") => generate_response(origin, req, &V0FetchHandler).await,
+        (&Method::POST, "/v0/fetch-batch") => {
+            generate_response(origin, req, &V0BatchFetchHandler { client_ip }).await
+        },
+        (&Method::POST, "/v0/verify") => generate_response(origin, req, &V0VerifyHandler).await,
+        (&Method::POST, "/v1/fetch") => generate_response(origin, req, &V1FetchHandler).await,
         (&Method::OPTIONS, _) => hyper::Response::builder()
             .status(StatusCode::OK)
             .header(ACCESS_CONTROL_ALLOW_ORIGIN, origin)
@@ -66,13 +112,23 @@ async fn handle_request(req: Request<Body>) -> Result<Response<Body>, Infallible
 #[tokio::main]
 async fn main() {
     // Trigger private key loading.
-    let _ = VUF_SK.deref();
+    let _ = VUF_SIGNER.deref();
     let _ = ACCOUNT_MANAGERS.deref();
+    let _ = AUD_ALLOWLIST.deref();
+    let _ = JWT_ALGORITHMS.deref();
+    let _ = UID_KEY_ALLOWLIST.deref();
+    let _ = RATE_LIMITERS.deref();
+    let _ = JWK_DISCOVERY.deref();
+    let _ = PEPPER_SCOPES.deref();
+    let _ = AUDIT_LOG.deref();
+    let _ = KEYLESS_PARAMS.deref();
     {
         let _db = ACCOUNT_RECOVERY_DB.get_or_init(init_account_db).await;
     }
     aptos_logger::Logger::new().init();
     start_metric_server();
+    AUDIT_LOG.start_pruning_loop();
+    start_keyless_params_refresh_loop();
 
     // TODO: JWKs should be from on-chain states?
     jwk::start_jwk_refresh_loop(
@@ -94,8 +150,10 @@ async fn main() {
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 8000));
 
-    let make_svc =
-        make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(handle_request)) });
+    let make_svc = make_service_fn(|conn: &AddrStream| {
+        let remote_addr = conn.remote_addr();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle_request(req, remote_addr))) }
+    });
 
     let server = Server::bind(&addr).serve(make_svc);
 
@@ -129,20 +187,7 @@ where
                         serde_json::to_string_pretty(&pepper_response).unwrap(),
                     )
                 },
-                Err(BadRequest(err)) => {
-                    info!("Processing failed with bad request: {err}");
-                    (
-                        StatusCode::BAD_REQUEST,
-                        serde_json::to_string_pretty(&BadPepperRequestError {
-                            message: err.to_string(),
-                        })
-                        .unwrap(),
-                    )
-                },
-                Err(InternalError(e)) => {
-                    error!("Processing failed with internal error: {e}");
-                    (StatusCode::INTERNAL_SERVER_ERROR, String::new())
-                },
+                Err(failure) => failure_status_and_body(failure),
             }
         },
         Err(err) => {
@@ -150,6 +195,7 @@ where
             (
                 StatusCode::BAD_REQUEST,
                 serde_json::to_string_pretty(&BadPepperRequestError {
+                    code: PepperErrorCode::MalformedRequest,
                     message: err.to_string(),
                 })
                 .unwrap(),
@@ -160,6 +206,64 @@ where
     build_response(origin, status_code, body_json)
 }
 
+fn failure_status_and_body(failure: ProcessingFailure) -> (StatusCode, String) {
+    match failure {
+        BadRequest(code, err) => {
+            info!("Processing failed with bad request: {err}");
+            (
+                StatusCode::BAD_REQUEST,
+                serde_json::to_string_pretty(&BadPepperRequestError {
+                    code,
+                    message: err.to_string(),
+                })
+                .unwrap(),
+            )
+        },
+        AudienceNotAllowed(err) => {
+            info!("Processing failed with audience not allowed: {err}");
+            (
+                StatusCode::FORBIDDEN,
+                serde_json::to_string_pretty(&BadPepperRequestError {
+                    code: PepperErrorCode::AudienceNotAllowed,
+                    message: err,
+                })
+                .unwrap(),
+            )
+        },
+        InternalError(code, e) => {
+            error!("Processing failed with internal error: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                serde_json::to_string_pretty(&BadPepperRequestError {
+                    code,
+                    message: "internal error".to_string(),
+                })
+                .unwrap(),
+            )
+        },
+        TooManyRequests {
+            message,
+            retry_after_secs,
+        } => {
+            info!("Processing failed with too many requests: {message}");
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                serde_json::to_string_pretty(&ThrottledPepperRequestError {
+                    code: PepperErrorCode::RateLimited,
+                    message,
+                    retry_after_secs,
+                })
+                .unwrap(),
+            )
+        },
+    }
+}
+
+fn build_failure_response(origin: String, failure: ProcessingFailure) -> Response<Body> {
+    let (status_code, body_json) = failure_status_and_body(failure);
+    build_response(origin, status_code, body_json)
+}
+
 fn build_response(origin: String, status_code: StatusCode, body_str: String) -> Response<Body> {
     hyper::Response::builder()
         .status(status_code)
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_inspection_service::utils::get_encoded_metrics;
-use aptos_metrics_core::{exponential_buckets, register_histogram_vec, HistogramVec, TextEncoder};
+use aptos_metrics_core::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
+    IntCounterVec, TextEncoder,
+};
 use hyper::{
     header::CONTENT_TYPE,
     service::{make_service_fn, service_fn},
@@ -31,6 +34,15 @@ pub static REQUEST_HANDLING_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static RATE_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "keyless_pepper_rate_limited_requests",
+        "Number of pepper requests rejected by the rate limiter of each scope.",
+        &["scope"]
+    )
+    .unwrap()
+});
+
 pub fn start_metric_server() {
     let _handle = tokio::spawn(async move {
         let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
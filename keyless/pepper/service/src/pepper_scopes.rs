@@ -0,0 +1,102 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{aud_allowlist::wildcard_pattern_to_regex, Issuer};
+use aptos_logger::{info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{collections::BTreeMap, env};
+
+/// A scope salt mixed into the VUF input of the `PepperInput`s of an issuer and some of its `aud`s,
+/// so that their peppers, and hence their keyless accounts, are distinct from those of any other scope.
+#[derive(Debug)]
+pub struct PepperScope {
+    pub issuer: Issuer,
+    /// The `aud`s in the scope, as patterns where `*` matches anything.
+    pub auds: Vec<Regex>,
+    pub salt: String,
+}
+
+/// The pepper scopes, in the order of their short names, the first one matching a `PepperInput` applying.
+/// `PepperInput`s without a scope get the peppers they have always had.
+///
+/// ## How to use
+/// To isolate the peppers of the `aud`s of an issuer `<some-issuer>` matching `<aud-pattern>`
+/// under the scope salt `<some-salt>`, give the scope a unique short name `<some-short-name>`
+/// and put the following as the environment variables.
+/// `PEPPER_SCOPE_<some-short-name>_ISSUER=<some-issuer>`
+/// `PEPPER_SCOPE_<some-short-name>_AUDS=<aud-pattern>`, which defaults to `*`
+/// `PEPPER_SCOPE_<some-short-name>_SALT=<some-salt>`
+///
+/// The salt is not a secret, as clients need it to verify their VUF outputs,
+/// but changing it changes the peppers of the scope.
+///
+/// Here is an example command.
+/// ```bash
+/// PEPPER_SCOPE_1A_ISSUER=https://accounts.google.com \
+/// PEPPER_SCOPE_1A_AUDS=*-game.apps.googleusercontent.com \
+/// PEPPER_SCOPE_1A_SALT=game-studio-a \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static PEPPER_SCOPES: Lazy<Vec<PepperScope>> = Lazy::new(|| {
+    let re_issuer = Regex::new(r"PEPPER_SCOPE_(\w+)_ISSUER").unwrap();
+    let re_auds = Regex::new(r"PEPPER_SCOPE_(\w+)_AUDS").unwrap();
+    let re_salt = Regex::new(r"PEPPER_SCOPE_(\w+)_SALT").unwrap();
+    let mut working_set: BTreeMap<String, Collector> = BTreeMap::new();
+
+    env::vars().for_each(|(key, val)| {
+        if let Some(caps) = re_issuer.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            working_set.entry(uid).or_default().issuer = Some(val);
+        } else if let Some(caps) = re_auds.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            let patterns = val
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .filter_map(|pattern| match wildcard_pattern_to_regex(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        warn!("Ignoring aud pattern {pattern} of {key}: {e}");
+                        None
+                    },
+                })
+                .collect();
+            working_set.entry(uid).or_default().auds = Some(patterns);
+        } else if let Some(caps) = re_salt.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            working_set.entry(uid).or_default().salt = Some(val);
+        }
+    });
+
+    let ret: Vec<PepperScope> = working_set
+        .into_values()
+        .filter_map(|collector| {
+            let Collector { issuer, auds, salt } = collector;
+            if let (Some(issuer), Some(salt)) = (issuer, salt) {
+                let auds = auds.unwrap_or_else(|| vec![wildcard_pattern_to_regex("*").unwrap()]);
+                Some(PepperScope { issuer, auds, salt })
+            } else {
+                None
+            }
+        })
+        .collect();
+    info!("PEPPER_SCOPES={:?}", ret);
+    ret
+});
+
+/// The scope salt of the `PepperInput`s of `issuer` and `aud`, if any.
+pub fn scope_salt(issuer: &str, aud: &str) -> Option<&'static str> {
+    PEPPER_SCOPES
+        .iter()
+        .find(|scope| scope.issuer == issuer && scope.auds.iter().any(|regex| regex.is_match(aud)))
+        .map(|scope| scope.salt.as_str())
+}
+
+#[derive(Default)]
+struct Collector {
+    pub issuer: Option<Issuer>,
+    pub auds: Option<Vec<Regex>>,
+    pub salt: Option<String>,
+}
@@ -0,0 +1,326 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::RATE_LIMITED_REQUESTS;
+use aptos_logger::{info, warn};
+use dashmap::DashMap;
+use hyper::HeaderMap;
+use once_cell::sync::Lazy;
+use std::{env, net::SocketAddr, time::Duration};
+use tokio::time::Instant;
+
+/// Buckets kept per limiter, to bound the memory. Beyond it, the full buckets get dropped,
+/// then the least recently used ones until half of the buckets are left.
+const MAX_NUM_BUCKETS: usize = 100_000;
+
+/// Up to `burst` requests at once, then `per_sec` requests per second.
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimit {
+    pub per_sec: f64,
+    pub burst: f64,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token buckets of a `RateLimit`, by key.
+pub struct RateLimiter {
+    scope: &'static str,
+    limit: RateLimit,
+    buckets: DashMap<String, TokenBucket>,
+    max_num_buckets: usize,
+}
+
+impl RateLimiter {
+    pub fn new(scope: &'static str, limit: RateLimit) -> Self {
+        Self {
+            scope,
+            limit,
+            buckets: DashMap::new(),
+            max_num_buckets: MAX_NUM_BUCKETS,
+        }
+    }
+
+    /// Takes a token from the bucket of `key`, or returns how long until there is one.
+    pub fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.buckets.len() >= self.max_num_buckets && !self.buckets.contains_key(key) {
+            self.evict_buckets(now);
+        }
+        let mut bucket = self
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket {
+                tokens: self.limit.burst,
+                last_refill: now,
+            });
+        let refilled = now
+            .saturating_duration_since(bucket.last_refill)
+            .as_secs_f64()
+            * self.limit.per_sec;
+        bucket.tokens = (bucket.tokens + refilled).min(self.limit.burst);
+        bucket.last_refill = bucket.last_refill.max(now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            RATE_LIMITED_REQUESTS.with_label_values(&[self.scope]).inc();
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.limit.per_sec,
+            ))
+        }
+    }
+
+    /// Drops the full buckets, which are the same as new ones, then the least recently used
+    /// ones until half of `max_num_buckets` are left, so that this runs at most once per
+    /// `max_num_buckets / 2` new keys.
+    fn evict_buckets(&self, now: Instant) {
+        let RateLimit { per_sec, burst } = self.limit;
+        self.buckets.retain(|_, bucket| {
+            let refilled = now
+                .saturating_duration_since(bucket.last_refill)
+                .as_secs_f64()
+                * per_sec;
+            bucket.tokens + refilled < burst
+        });
+
+        let num_kept = self.max_num_buckets / 2;
+        let mut last_refills: Vec<Instant> = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.last_refill)
+            .collect();
+        if last_refills.len() > num_kept {
+            let num_evicted = last_refills.len() - num_kept;
+            let (_, &mut cutoff, _) = last_refills.select_nth_unstable(num_evicted - 1);
+            self.buckets.retain(|_, bucket| bucket.last_refill > cutoff);
+        }
+    }
+}
+
+/// The rate limiters of each scope, the ones not configured being unlimited.
+pub struct RateLimiters {
+    /// By the `iss` of the JWT.
+    pub issuer: Option<RateLimiter>,
+    /// By the `iss` and `aud` of the JWT.
+    pub aud: Option<RateLimiter>,
+    /// By the client IP.
+    pub ip: Option<RateLimiter>,
+    /// The proxies in front of the service, e.g. a load balancer, each appending the address it
+    /// got the request from to the `X-Forwarded-For` header. The client IP is the entry that the
+    /// farthest of them appended, the `trusted_proxies`-th from the right, since the ones before
+    /// are set by the client. With no proxies, it is the address of the connection.
+    pub trusted_proxies: usize,
+}
+
+/// The rate limiters, from the environment.
+///
+/// ## How to use
+/// For each scope `<scope>` among `ISSUER`, `AUD` and `IP`, to limit the requests to
+/// `<per-sec>` per second, with bursts of up to `<burst>` requests,
+/// put the following as the environment variables.
+/// `RATE_LIMIT_<scope>_PER_SEC=<per-sec>`
+/// `RATE_LIMIT_<scope>_BURST=<burst>`, which defaults to `<per-sec>`
+///
+/// Each item of a `/v0/fetch-batch` request counts as a request.
+///
+/// Behind `<n>` proxies appending to the `X-Forwarded-For` header, e.g. a load balancer,
+/// also put `RATE_LIMIT_IP_TRUSTED_PROXIES=<n>`.
+///
+/// Here is an example command.
+/// ```bash
+/// RATE_LIMIT_AUD_PER_SEC=100 \
+/// RATE_LIMIT_AUD_BURST=500 \
+/// RATE_LIMIT_IP_PER_SEC=5 \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static RATE_LIMITERS: Lazy<RateLimiters> = Lazy::new(|| {
+    let rate_limiters = RateLimiters {
+        issuer: rate_limit_from_env("ISSUER").map(|limit| RateLimiter::new("issuer", limit)),
+        aud: rate_limit_from_env("AUD").map(|limit| RateLimiter::new("aud", limit)),
+        ip: rate_limit_from_env("IP").map(|limit| RateLimiter::new("ip", limit)),
+        trusted_proxies: env::var("RATE_LIMIT_IP_TRUSTED_PROXIES").map_or(0, |val| {
+            val.parse::<usize>().unwrap_or_else(|_| {
+                warn!("Ignoring RATE_LIMIT_IP_TRUSTED_PROXIES={val}, which is not an integer");
+                0
+            })
+        }),
+    };
+    info!(
+        "RATE_LIMITERS issuer={:?} aud={:?} ip={:?} trusted_proxies={}",
+        rate_limiters.issuer.as_ref().map(|limiter| limiter.limit),
+        rate_limiters.aud.as_ref().map(|limiter| limiter.limit),
+        rate_limiters.ip.as_ref().map(|limiter| limiter.limit),
+        rate_limiters.trusted_proxies,
+    );
+    rate_limiters
+});
+
+fn rate_limit_from_env(scope: &str) -> Option<RateLimit> {
+    let parse = |name: String| {
+        let val = env::var(&name).ok()?;
+        match val.parse::<f64>() {
+            Ok(val) if val > 0.0 => Some(val),
+            _ => {
+                warn!("Ignoring {name}={val}, which is not a positive number");
+                None
+            },
+        }
+    };
+    let per_sec = parse(format!("RATE_LIMIT_{scope}_PER_SEC"))?;
+    let burst = parse(format!("RATE_LIMIT_{scope}_BURST"))
+        .unwrap_or(per_sec)
+        .max(1.0);
+    Some(RateLimit { per_sec, burst })
+}
+
+impl RateLimiters {
+    /// The IP the rate limit of `ip` applies to, for a request from `remote_addr`.
+    /// Falls back to the address of the connection when the header has fewer entries than
+    /// `trusted_proxies`.
+    pub fn client_ip(&self, headers: &HeaderMap, remote_addr: SocketAddr) -> String {
+        if self.trusted_proxies > 0 {
+            // A proxy may append its own header rather than extend the last one.
+            let forwarded_for: Vec<&str> = headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|val| val.to_str().ok())
+                .flat_map(|val| val.split(','))
+                .map(str::trim)
+                .collect();
+            if let Some(ip) = forwarded_for.iter().rev().nth(self.trusted_proxies - 1) {
+                return ip.to_string();
+            }
+        }
+        remote_addr.ip().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_sec: f64, burst: f64) -> RateLimiter {
+        RateLimiter::new("test", RateLimit { per_sec, burst })
+    }
+
+    #[test]
+    fn burst_then_retry_after() {
+        let limiter = limiter(2.0, 3.0);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("key", now).is_ok());
+        }
+        assert_eq!(
+            limiter.try_acquire_at("key", now),
+            Err(Duration::from_millis(500))
+        );
+        // Other keys have their own buckets.
+        assert!(limiter.try_acquire_at("other", now).is_ok());
+    }
+
+    #[test]
+    fn refill() {
+        let limiter = limiter(2.0, 3.0);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("key", now).is_ok());
+        }
+        let later = now + Duration::from_millis(250);
+        assert_eq!(
+            limiter.try_acquire_at("key", later),
+            Err(Duration::from_millis(250))
+        );
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at("key", later).is_ok());
+        assert!(limiter.try_acquire_at("key", later).is_err());
+    }
+
+    #[test]
+    fn refill_is_capped_by_burst() {
+        let limiter = limiter(2.0, 3.0);
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("key", now).is_ok());
+        let later = now + Duration::from_secs(3600);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("key", later).is_ok());
+        }
+        assert!(limiter.try_acquire_at("key", later).is_err());
+    }
+
+    #[test]
+    fn least_recently_used_buckets_are_evicted() {
+        let mut limiter = limiter(0.001, 2.0);
+        limiter.max_num_buckets = 4;
+        let now = Instant::now();
+        for i in 0..4 {
+            let at = now + Duration::from_secs(i);
+            assert!(limiter.try_acquire_at(&i.to_string(), at).is_ok());
+        }
+        // Used again, so not the least recently used anymore.
+        assert!(limiter
+            .try_acquire_at("0", now + Duration::from_secs(4))
+            .is_ok());
+        assert_eq!(limiter.buckets.len(), 4);
+
+        assert!(limiter
+            .try_acquire_at("4", now + Duration::from_secs(5))
+            .is_ok());
+        let mut keys: Vec<String> = limiter
+            .buckets
+            .iter()
+            .map(|bucket| bucket.key().clone())
+            .collect();
+        keys.sort();
+        assert_eq!(keys, vec!["0", "3", "4"]);
+    }
+
+    #[test]
+    fn full_buckets_are_evicted_first() {
+        let mut limiter = limiter(1.0, 2.0);
+        limiter.max_num_buckets = 4;
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("refilled", now).is_ok());
+        for i in 0..3 {
+            let at = now + Duration::from_secs(10 + i);
+            assert!(limiter.try_acquire_at(&i.to_string(), at).is_ok());
+        }
+        assert!(limiter
+            .try_acquire_at("new", now + Duration::from_secs(12))
+            .is_ok());
+        assert!(!limiter.buckets.contains_key("refilled"));
+        assert!(!limiter.buckets.contains_key("0"));
+        assert!(limiter.buckets.contains_key("new"));
+    }
+
+    fn client_ip(trusted_proxies: usize, forwarded_for: &[&str]) -> String {
+        let rate_limiters = RateLimiters {
+            issuer: None,
+            aud: None,
+            ip: None,
+            trusted_proxies,
+        };
+        let mut headers = HeaderMap::new();
+        for val in forwarded_for {
+            headers.append("x-forwarded-for", val.parse().unwrap());
+        }
+        rate_limiters.client_ip(&headers, "10.0.0.1:443".parse().unwrap())
+    }
+
+    #[test]
+    fn client_ip_from_x_forwarded_for() {
+        let spoofed = "1.1.1.1, 2.2.2.2, 3.3.3.3";
+        assert_eq!(client_ip(0, &[spoofed]), "10.0.0.1");
+        assert_eq!(client_ip(1, &[spoofed]), "3.3.3.3");
+        assert_eq!(client_ip(2, &[spoofed]), "2.2.2.2");
+        assert_eq!(client_ip(2, &["1.1.1.1, 2.2.2.2", "3.3.3.3"]), "2.2.2.2");
+        assert_eq!(client_ip(4, &[spoofed]), "10.0.0.1");
+        assert_eq!(client_ip(1, &[]), "10.0.0.1");
+    }
+}
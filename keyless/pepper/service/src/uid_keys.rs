@@ -0,0 +1,107 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Issuer;
+use aptos_logger::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{collections::HashMap, env};
+
+/// The `uid_key`s accepted for any issuer.
+pub const DEFAULT_UID_KEYS: &[&str] = &["sub", "email"];
+
+/// The JWT claims accepted as `uid_key`, in addition to `DEFAULT_UID_KEYS`.
+pub struct UidKeyAllowlist {
+    /// For the issuers without an override.
+    pub additional: Vec<String>,
+    /// Replaces `additional` for an issuer.
+    pub per_issuer: HashMap<Issuer, Vec<String>>,
+}
+
+impl UidKeyAllowlist {
+    /// Whether the claim `uid_key` may identify the users of `issuer`.
+    pub fn is_allowed(&self, issuer: &str, uid_key: &str) -> bool {
+        DEFAULT_UID_KEYS.contains(&uid_key)
+            || self
+                .per_issuer
+                .get(issuer)
+                .unwrap_or(&self.additional)
+                .iter()
+                .any(|key| key == uid_key)
+    }
+}
+
+/// The `uid_key` allowlist, from the environment.
+///
+/// ## How to use
+/// To accept more claims as `uid_key` for all the issuers, e.g. `<claim-1>` and `<claim-2>`,
+/// put the following as the environment variable.
+/// `UID_KEYS=<claim-1>,<claim-2>`
+///
+/// To accept other claims for an issuer `<some-issuer>` instead,
+/// give it a unique short name `<some-short-name>` and put the following as the environment variables.
+/// `UID_KEYS_<some-short-name>_ISSUER=<some-issuer>
+/// `UID_KEYS_<some-short-name>_KEYS=<claim-3>,<claim-4>
+///
+/// `sub` and `email` are always accepted.
+///
+/// Here is an example command.
+/// ```bash
+/// UID_KEYS=preferred_username \
+/// UID_KEYS_1A_ISSUER=https://login.microsoftonline.com/9188040d-6c67-4c5b-b112-36a304b66dad/v2.0 \
+/// UID_KEYS_1A_KEYS=oid,preferred_username \
+/// VUF_KEY_SEED_HEX=ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static UID_KEY_ALLOWLIST: Lazy<UidKeyAllowlist> = Lazy::new(|| {
+    let re_issuer = Regex::new(r"UID_KEYS_(\w+)_ISSUER").unwrap();
+    let re_keys = Regex::new(r"UID_KEYS_(\w+)_KEYS").unwrap();
+    let mut additional = vec![];
+    let mut working_set: HashMap<String, Collector> = HashMap::new();
+
+    env::vars().for_each(|(key, val)| {
+        if key == "UID_KEYS" {
+            additional = parse_keys(&val);
+        } else if let Some(caps) = re_issuer.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            working_set.entry(uid).or_default().issuer = Some(val);
+        } else if let Some(caps) = re_keys.captures(key.as_str()) {
+            let uid = caps.get(1).unwrap().as_str().to_string();
+            working_set.entry(uid).or_default().keys = Some(parse_keys(&val));
+        }
+    });
+
+    let per_issuer: HashMap<Issuer, Vec<String>> = working_set
+        .into_values()
+        .filter_map(|collector| {
+            let Collector { issuer, keys } = collector;
+            if let (Some(issuer), Some(keys)) = (issuer, keys) {
+                Some((issuer, keys))
+            } else {
+                None
+            }
+        })
+        .collect();
+    info!(
+        "UID_KEY_ALLOWLIST additional={:?} per_issuer={:?}",
+        additional, per_issuer
+    );
+    UidKeyAllowlist {
+        additional,
+        per_issuer,
+    }
+});
+
+fn parse_keys(val: &str) -> Vec<String> {
+    val.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Default)]
+struct Collector {
+    pub issuer: Option<Issuer>,
+    pub keys: Option<Vec<String>>,
+}
@@ -1,12 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::vuf_signer::{InMemoryVufSigner, RemoteVufSigner, VufSigner};
 use anyhow::{anyhow, ensure};
-use aptos_keyless_pepper_common::{
-    vuf::{bls12381_g1_bls::Bls12381G1Bls, VUF},
-    PepperV0VufPubKey,
-};
-use aptos_logger::warn;
+use aptos_keyless_pepper_common::PepperV0VufPubKey;
+use aptos_logger::{info, warn};
 use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
@@ -39,7 +37,7 @@ fn deserialize_sk_from_env() -> anyhow::Result<ark_bls12_381::Fr> {
     Ok(sk)
 }
 
-pub static VUF_SK: Lazy<ark_bls12_381::Fr> = Lazy::new(|| {
+fn sk_from_env() -> ark_bls12_381::Fr {
     match derive_sk_from_env_seed() {
         Ok(sk) => {
             return sk;
@@ -52,12 +50,52 @@ pub static VUF_SK: Lazy<ark_bls12_381::Fr> = Lazy::new(|| {
     }
 
     deserialize_sk_from_env().expect("fallback sk also failed")
+}
+
+/// Build a remote signer from the environment.
+fn remote_signer_from_env(url: String) -> anyhow::Result<RemoteVufSigner> {
+    let pk_hex = std::env::var("VUF_REMOTE_SIGNER_PUBLIC_KEY_HEX").map_err(|e| {
+        anyhow!("error while reading envvar `VUF_REMOTE_SIGNER_PUBLIC_KEY_HEX`: {e}")
+    })?;
+    let pk_bytes = hex::decode(pk_hex).map_err(|e| anyhow!("pk unhexlification error: {e}"))?;
+    let auth_token = std::env::var("VUF_REMOTE_SIGNER_AUTH_TOKEN").ok();
+    RemoteVufSigner::new(url, auth_token, &pk_bytes)
+}
+
+/// The VUF signer.
+///
+/// ## How to use
+/// By default, the secret key is in memory, from `VUF_KEY_SEED_HEX` (or the legacy `VUF_KEY_HEX`).
+///
+/// To keep it in a KMS/HSM-backed signing service instead (see `RemoteVufSigner`),
+/// put the following as the environment variables.
+/// `VUF_REMOTE_SIGNER_URL=<eval-endpoint>`
+/// `VUF_REMOTE_SIGNER_PUBLIC_KEY_HEX=<public-key-as-compressed-g2-hex>`
+/// `VUF_REMOTE_SIGNER_AUTH_TOKEN=<bearer-token>`, which is optional
+///
+/// Here is an example command.
+/// ```bash
+/// VUF_REMOTE_SIGNER_URL=http://localhost:8090/v0/eval \
+/// VUF_REMOTE_SIGNER_PUBLIC_KEY_HEX=<public-key-as-compressed-g2-hex> \
+/// cargo run -p aptos-keyless-pepper-service
+/// ```
+pub static VUF_SIGNER: Lazy<Box<dyn VufSigner>> = Lazy::new(|| {
+    if let Ok(url) = std::env::var("VUF_REMOTE_SIGNER_URL") {
+        info!("VUF_SIGNER is remote at {url}");
+        Box::new(remote_signer_from_env(url).expect("bad remote signer config"))
+    } else {
+        info!("VUF_SIGNER is in memory");
+        Box::new(InMemoryVufSigner::new(sk_from_env()).expect("bad sk"))
+    }
 });
 
 pub static PEPPER_VUF_VERIFICATION_KEY_JSON: Lazy<String> = Lazy::new(|| {
-    let pk = Bls12381G1Bls::pk_from_sk(&VUF_SK).expect("bad sk");
     let mut buf = vec![];
-    pk.into_affine().serialize_compressed(&mut buf).unwrap();
+    VUF_SIGNER
+        .public_key()
+        .into_affine()
+        .serialize_compressed(&mut buf)
+        .unwrap();
     let obj = PepperV0VufPubKey { public_key: buf };
     serde_json::to_string_pretty(&obj).unwrap()
 });
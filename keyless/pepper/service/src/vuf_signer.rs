@@ -0,0 +1,129 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, ensure, Result};
+use aptos_keyless_pepper_common::vuf::{
+    bls12381_g1_bls::Bls12381G1Bls, bls12381_g1_bls_with_proof::Bls12381G1BlsWithProof, VUF,
+};
+use ark_bls12_381::{Fr, G2Affine, G2Projective};
+use ark_serialize::CanonicalDeserialize;
+use firestore::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Timeout of each evaluation by a `RemoteVufSigner`.
+const REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Evaluates the `Bls12381G1Bls` VUF, with or without a `Bls12381G1BlsWithProof` proof,
+/// under a secret key that may not reside in the process memory.
+#[async_trait]
+pub trait VufSigner: Send + Sync {
+    fn public_key(&self) -> &G2Projective;
+
+    /// Return `(output, proof)`, the proof being empty if `with_proof` is false.
+    async fn eval(&self, input: &[u8], with_proof: bool) -> Result<(Vec<u8>, Vec<u8>)>;
+}
+
+/// A signer holding the secret key in memory.
+pub struct InMemoryVufSigner {
+    sk: Fr,
+    pk: G2Projective,
+}
+
+impl InMemoryVufSigner {
+    pub fn new(sk: Fr) -> Result<Self> {
+        let pk = Bls12381G1Bls::pk_from_sk(&sk)?;
+        Ok(Self { sk, pk })
+    }
+}
+
+#[async_trait]
+impl VufSigner for InMemoryVufSigner {
+    fn public_key(&self) -> &G2Projective {
+        &self.pk
+    }
+
+    async fn eval(&self, input: &[u8], with_proof: bool) -> Result<(Vec<u8>, Vec<u8>)> {
+        if with_proof {
+            Bls12381G1BlsWithProof::eval(&self.sk, input)
+        } else {
+            Bls12381G1Bls::eval(&self.sk, input)
+        }
+    }
+}
+
+/// The request to a remote signer.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RemoteVufEvalRequest {
+    pub input: Vec<u8>,
+    pub with_proof: bool,
+}
+
+/// The response of a remote signer.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RemoteVufEvalResponse {
+    pub output: Vec<u8>,
+    pub proof: Vec<u8>,
+}
+
+/// A signer delegating to a KMS/HSM-backed service, which gets a `RemoteVufEvalRequest` POSTed
+/// and responds with a `RemoteVufEvalResponse`.
+/// The outputs and proofs are verified against the public key before being used.
+pub struct RemoteVufSigner {
+    url: String,
+    auth_token: Option<String>,
+    pk: G2Projective,
+    client: reqwest::Client,
+}
+
+impl RemoteVufSigner {
+    /// `pk_bytes` is the public key of the remote secret key, serialized as a compressed G2 point.
+    pub fn new(url: String, auth_token: Option<String>, pk_bytes: &[u8]) -> Result<Self> {
+        let pk = G2Affine::deserialize_compressed(pk_bytes)
+            .map_err(|e| anyhow!("public key deserialization error: {e}"))?
+            .into();
+        let client = reqwest::Client::builder()
+            .timeout(REMOTE_SIGNER_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            url,
+            auth_token,
+            pk,
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl VufSigner for RemoteVufSigner {
+    fn public_key(&self) -> &G2Projective {
+        &self.pk
+    }
+
+    async fn eval(&self, input: &[u8], with_proof: bool) -> Result<(Vec<u8>, Vec<u8>)> {
+        let request = RemoteVufEvalRequest {
+            input: input.to_vec(),
+            with_proof,
+        };
+        let mut builder = self.client.post(self.url.as_str()).json(&request);
+        if let Some(auth_token) = &self.auth_token {
+            builder = builder.bearer_auth(auth_token);
+        }
+        let RemoteVufEvalResponse { output, proof } = builder
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow!("remote signer request error: {e}"))?
+            .json()
+            .await
+            .map_err(|e| anyhow!("remote signer response error: {e}"))?;
+        if with_proof {
+            Bls12381G1BlsWithProof::verify(&self.pk, input, &output, &proof)
+        } else {
+            ensure!(proof.is_empty(), "remote signer gave an unexpected proof");
+            Bls12381G1Bls::verify(&self.pk, input, &output, &proof)
+        }
+        .map_err(|e| anyhow!("remote signer output verification error: {e}"))?;
+        Ok((output, proof))
+    }
+}
@@ -0,0 +1,908 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// @generated
+impl serde::Serialize for BackupChunk {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.first_index != 0 {
+            len += 1;
+        }
+        if self.num_records != 0 {
+            len += 1;
+        }
+        if !self.records.is_empty() {
+            len += 1;
+        }
+        if !self.proof.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.BackupChunk", len)?;
+        if self.first_index != 0 {
+            struct_ser.serialize_field("firstIndex", ToString::to_string(&self.first_index).as_str())?;
+        }
+        if self.num_records != 0 {
+            struct_ser.serialize_field("numRecords", ToString::to_string(&self.num_records).as_str())?;
+        }
+        if !self.records.is_empty() {
+            struct_ser.serialize_field("records", pbjson::private::base64::encode(&self.records).as_str())?;
+        }
+        if !self.proof.is_empty() {
+            struct_ser.serialize_field("proof", pbjson::private::base64::encode(&self.proof).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BackupChunk {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "first_index",
+            "firstIndex",
+            "num_records",
+            "numRecords",
+            "records",
+            "proof",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            FirstIndex,
+            NumRecords,
+            Records,
+            Proof,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "firstIndex" | "first_index" => Ok(GeneratedField::FirstIndex),
+                            "numRecords" | "num_records" => Ok(GeneratedField::NumRecords),
+                            "records" => Ok(GeneratedField::Records),
+                            "proof" => Ok(GeneratedField::Proof),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BackupChunk;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.BackupChunk")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<BackupChunk, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut first_index__ = None;
+                let mut num_records__ = None;
+                let mut records__ = None;
+                let mut proof__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::FirstIndex => {
+                            if first_index__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("firstIndex"));
+                            }
+                            first_index__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::NumRecords => {
+                            if num_records__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("numRecords"));
+                            }
+                            num_records__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Records => {
+                            if records__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("records"));
+                            }
+                            records__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Proof => {
+                            if proof__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("proof"));
+                            }
+                            proof__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(BackupChunk {
+                    first_index: first_index__.unwrap_or_default(),
+                    num_records: num_records__.unwrap_or_default(),
+                    records: records__.unwrap_or_default(),
+                    proof: proof__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.BackupChunk", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for GetDbStateRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 0;
+        let struct_ser = serializer.serialize_struct("aptos.backup.v1.GetDbStateRequest", len)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetDbStateRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                            Err(serde::de::Error::unknown_field(value, FIELDS))
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetDbStateRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.GetDbStateRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetDbStateRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                while map.next_key::<GeneratedField>()?.is_some() {
+                    let _ = map.next_value::<serde::de::IgnoredAny>()?;
+                }
+                Ok(GetDbStateRequest {
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.GetDbStateRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for GetDbStateResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.db_state.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.GetDbStateResponse", len)?;
+        if !self.db_state.is_empty() {
+            struct_ser.serialize_field("dbState", pbjson::private::base64::encode(&self.db_state).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetDbStateResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "db_state",
+            "dbState",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            DbState,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "dbState" | "db_state" => Ok(GeneratedField::DbState),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetDbStateResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.GetDbStateResponse")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetDbStateResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut db_state__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::DbState => {
+                            if db_state__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("dbState"));
+                            }
+                            db_state__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(GetDbStateResponse {
+                    db_state: db_state__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.GetDbStateResponse", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for GetStateRootProofRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.version != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.GetStateRootProofRequest", len)?;
+        if self.version != 0 {
+            struct_ser.serialize_field("version", ToString::to_string(&self.version).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetStateRootProofRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "version",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Version,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "version" => Ok(GeneratedField::Version),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetStateRootProofRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.GetStateRootProofRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetStateRootProofRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut version__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Version => {
+                            if version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("version"));
+                            }
+                            version__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(GetStateRootProofRequest {
+                    version: version__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.GetStateRootProofRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for GetStateRootProofResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.proof.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.GetStateRootProofResponse", len)?;
+        if !self.proof.is_empty() {
+            struct_ser.serialize_field("proof", pbjson::private::base64::encode(&self.proof).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetStateRootProofResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "proof",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Proof,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "proof" => Ok(GeneratedField::Proof),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetStateRootProofResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.GetStateRootProofResponse")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetStateRootProofResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut proof__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Proof => {
+                            if proof__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("proof"));
+                            }
+                            proof__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(GetStateRootProofResponse {
+                    proof: proof__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.GetStateRootProofResponse", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StreamEpochEndingLedgerInfosRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.start_epoch != 0 {
+            len += 1;
+        }
+        if self.end_epoch != 0 {
+            len += 1;
+        }
+        if self.chunk_size != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.StreamEpochEndingLedgerInfosRequest", len)?;
+        if self.start_epoch != 0 {
+            struct_ser.serialize_field("startEpoch", ToString::to_string(&self.start_epoch).as_str())?;
+        }
+        if self.end_epoch != 0 {
+            struct_ser.serialize_field("endEpoch", ToString::to_string(&self.end_epoch).as_str())?;
+        }
+        if self.chunk_size != 0 {
+            struct_ser.serialize_field("chunkSize", ToString::to_string(&self.chunk_size).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StreamEpochEndingLedgerInfosRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "start_epoch",
+            "startEpoch",
+            "end_epoch",
+            "endEpoch",
+            "chunk_size",
+            "chunkSize",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            StartEpoch,
+            EndEpoch,
+            ChunkSize,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "startEpoch" | "start_epoch" => Ok(GeneratedField::StartEpoch),
+                            "endEpoch" | "end_epoch" => Ok(GeneratedField::EndEpoch),
+                            "chunkSize" | "chunk_size" => Ok(GeneratedField::ChunkSize),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StreamEpochEndingLedgerInfosRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.StreamEpochEndingLedgerInfosRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<StreamEpochEndingLedgerInfosRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut start_epoch__ = None;
+                let mut end_epoch__ = None;
+                let mut chunk_size__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::StartEpoch => {
+                            if start_epoch__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("startEpoch"));
+                            }
+                            start_epoch__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EndEpoch => {
+                            if end_epoch__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("endEpoch"));
+                            }
+                            end_epoch__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ChunkSize => {
+                            if chunk_size__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("chunkSize"));
+                            }
+                            chunk_size__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(StreamEpochEndingLedgerInfosRequest {
+                    start_epoch: start_epoch__.unwrap_or_default(),
+                    end_epoch: end_epoch__.unwrap_or_default(),
+                    chunk_size: chunk_size__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.StreamEpochEndingLedgerInfosRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StreamStateSnapshotRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.version != 0 {
+            len += 1;
+        }
+        if self.start_idx != 0 {
+            len += 1;
+        }
+        if self.chunk_size != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.StreamStateSnapshotRequest", len)?;
+        if self.version != 0 {
+            struct_ser.serialize_field("version", ToString::to_string(&self.version).as_str())?;
+        }
+        if self.start_idx != 0 {
+            struct_ser.serialize_field("startIdx", ToString::to_string(&self.start_idx).as_str())?;
+        }
+        if self.chunk_size != 0 {
+            struct_ser.serialize_field("chunkSize", ToString::to_string(&self.chunk_size).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StreamStateSnapshotRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "version",
+            "start_idx",
+            "startIdx",
+            "chunk_size",
+            "chunkSize",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Version,
+            StartIdx,
+            ChunkSize,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "version" => Ok(GeneratedField::Version),
+                            "startIdx" | "start_idx" => Ok(GeneratedField::StartIdx),
+                            "chunkSize" | "chunk_size" => Ok(GeneratedField::ChunkSize),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StreamStateSnapshotRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.StreamStateSnapshotRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<StreamStateSnapshotRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut version__ = None;
+                let mut start_idx__ = None;
+                let mut chunk_size__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Version => {
+                            if version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("version"));
+                            }
+                            version__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::StartIdx => {
+                            if start_idx__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("startIdx"));
+                            }
+                            start_idx__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ChunkSize => {
+                            if chunk_size__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("chunkSize"));
+                            }
+                            chunk_size__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(StreamStateSnapshotRequest {
+                    version: version__.unwrap_or_default(),
+                    start_idx: start_idx__.unwrap_or_default(),
+                    chunk_size: chunk_size__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.StreamStateSnapshotRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StreamTransactionsRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.start_version != 0 {
+            len += 1;
+        }
+        if self.num_transactions != 0 {
+            len += 1;
+        }
+        if self.chunk_size != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.backup.v1.StreamTransactionsRequest", len)?;
+        if self.start_version != 0 {
+            struct_ser.serialize_field("startVersion", ToString::to_string(&self.start_version).as_str())?;
+        }
+        if self.num_transactions != 0 {
+            struct_ser.serialize_field("numTransactions", ToString::to_string(&self.num_transactions).as_str())?;
+        }
+        if self.chunk_size != 0 {
+            struct_ser.serialize_field("chunkSize", ToString::to_string(&self.chunk_size).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StreamTransactionsRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "start_version",
+            "startVersion",
+            "num_transactions",
+            "numTransactions",
+            "chunk_size",
+            "chunkSize",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            StartVersion,
+            NumTransactions,
+            ChunkSize,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "startVersion" | "start_version" => Ok(GeneratedField::StartVersion),
+                            "numTransactions" | "num_transactions" => Ok(GeneratedField::NumTransactions),
+                            "chunkSize" | "chunk_size" => Ok(GeneratedField::ChunkSize),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StreamTransactionsRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.backup.v1.StreamTransactionsRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<StreamTransactionsRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut start_version__ = None;
+                let mut num_transactions__ = None;
+                let mut chunk_size__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::StartVersion => {
+                            if start_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("startVersion"));
+                            }
+                            start_version__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::NumTransactions => {
+                            if num_transactions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("numTransactions"));
+                            }
+                            num_transactions__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ChunkSize => {
+                            if chunk_size__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("chunkSize"));
+                            }
+                            chunk_size__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(StreamTransactionsRequest {
+                    start_version: start_version__.unwrap_or_default(),
+                    num_transactions: num_transactions__.unwrap_or_default(),
+                    chunk_size: chunk_size__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.backup.v1.StreamTransactionsRequest", FIELDS, GeneratedVisitor)
+    }
+}
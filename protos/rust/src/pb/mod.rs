@@ -3,6 +3,13 @@
 
 // @generated
 pub mod aptos {
+    pub mod backup {
+        // @@protoc_insertion_point(attribute:aptos.backup.v1)
+        pub mod v1 {
+            include!("aptos.backup.v1.rs");
+            // @@protoc_insertion_point(aptos.backup.v1)
+        }
+    }
     pub mod indexer {
         // @@protoc_insertion_point(attribute:aptos.indexer.v1)
         pub mod v1 {
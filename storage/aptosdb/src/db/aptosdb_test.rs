@@ -104,6 +104,7 @@ fn test_pruner_config() {
                 enable,
                 prune_window: 20,
                 batch_size: 1,
+                dry_run: false,
             },
         );
         assert_eq!(state_merkle_pruner.is_pruner_enabled(), enable);
@@ -116,6 +117,7 @@ fn test_pruner_config() {
                 prune_window: 100,
                 batch_size: 1,
                 user_pruning_window_offset: 0,
+                dry_run: false,
             },
             None,
         );
@@ -224,16 +226,19 @@ pub fn test_state_merkle_pruning_impl(
                 prune_window: 10,
                 batch_size: 1,
                 user_pruning_window_offset: 0,
+                dry_run: false,
             },
             state_merkle_pruner_config: StateMerklePrunerConfig {
                 enable: true,
                 prune_window: 5,
                 batch_size: 1,
+                dry_run: false,
             },
             epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig {
                 enable: true,
                 prune_window: 10,
                 batch_size: 1,
+                dry_run: false,
             },
         },
         RocksdbConfigs::default(),
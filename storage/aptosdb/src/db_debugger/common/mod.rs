@@ -6,11 +6,27 @@ use crate::{
     state_merkle_db::StateMerkleDb,
 };
 use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
+use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_storage_interface::Result;
-use aptos_types::nibble::{nibble_path::NibblePath, Nibble};
+use aptos_types::{
+    access_path,
+    nibble::{nibble_path::NibblePath, Nibble},
+    state_store::{
+        errors::StateviewError,
+        state_key::{inner::StateKeyInner, StateKey},
+        state_storage_usage::StateStorageUsage,
+        state_value::StateValue,
+        TStateView,
+    },
+    transaction::Version,
+};
 use clap::Parser;
 use core::default::Default;
-use std::path::{Path, PathBuf};
+use move_core_types::language_storage::StructTag;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 pub const PAGE_SIZE: usize = 10;
 
@@ -25,6 +41,13 @@ pub struct DbDir {
 }
 
 impl DbDir {
+    pub fn new(db_dir: PathBuf, sharding_config: ShardingConfig) -> Self {
+        Self {
+            db_dir,
+            sharding_config,
+        }
+    }
+
     pub fn open_state_merkle_db(&self) -> Result<StateMerkleDb> {
         StateMerkleDb::new(
             &StorageDirPaths::from_path(&self.db_dir),
@@ -73,3 +96,68 @@ pub fn parse_nibble_path(src: &str) -> Result<NibblePath> {
         .map(|c| Ok(Nibble::from(u8::from_str_radix(&c.to_string(), 16)?)))
         .collect()
 }
+
+/// State view over the state KV DB at a version, so that the modules stored in the DB can be used
+/// to decode the resources.
+pub struct StateKvView<'a> {
+    db: &'a StateKvDb,
+    version: Version,
+}
+
+impl<'a> StateKvView<'a> {
+    pub fn new(db: &'a StateKvDb, version: Version) -> Self {
+        Self { db, version }
+    }
+}
+
+impl<'a> TStateView for StateKvView<'a> {
+    type Key = StateKey;
+
+    fn get_state_value(
+        &self,
+        state_key: &StateKey,
+    ) -> std::result::Result<Option<StateValue>, StateviewError> {
+        Ok(self
+            .db
+            .get_state_value_with_version_by_version(state_key, self.version)?
+            .map(|(_version, value)| value))
+    }
+
+    fn get_usage(&self) -> std::result::Result<StateStorageUsage, StateviewError> {
+        Ok(StateStorageUsage::new_untracked())
+    }
+}
+
+/// Decodes the bytes of a resource or a resource group into human-readable Move values, returns
+/// None for the keys that carry no type information, e.g. modules and table items.
+pub fn decode_state_value<'a>(
+    annotator: &AptosValueAnnotator<'a, StateKvView<'a>>,
+    key: &StateKey,
+    bytes: &[u8],
+) -> Option<String> {
+    let StateKeyInner::AccessPath(path) = key.inner() else {
+        return None;
+    };
+    let decoded = match path.get_path() {
+        access_path::Path::Resource(tag) => annotator
+            .view_resource(&tag, bytes)
+            .map(|resource| resource.to_string()),
+        access_path::Path::ResourceGroup(_group_tag) => {
+            bcs::from_bytes::<BTreeMap<StructTag, Vec<u8>>>(bytes)
+                .map_err(Into::into)
+                .and_then(|members| {
+                    members
+                        .iter()
+                        .map(|(tag, member_bytes)| {
+                            annotator
+                                .view_resource(tag, member_bytes)
+                                .map(|resource| resource.to_string())
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()
+                })
+                .map(|members| members.join("\n"))
+        },
+        access_path::Path::Code(_) => return None,
+    };
+    Some(decoded.unwrap_or_else(|err| format!("Failed to decode: {err}")))
+}
@@ -0,0 +1,231 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::{common::DbDir, ShardingConfig},
+    ledger_db::LedgerDb,
+    state_kv_db::StateKvDb,
+    state_merkle_db::StateMerkleDb,
+};
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_types::transaction::Version;
+use clap::Parser;
+use owo_colors::OwoColorize;
+use std::{cmp::min, path::PathBuf};
+
+#[derive(Parser)]
+#[clap(
+    about = "Compare two DBs, reporting the first divergence in the transaction infos, the state \
+    roots of the snapshots and optionally the state values written in a version range."
+)]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long, value_parser)]
+    other_db_dir: PathBuf,
+
+    #[clap(flatten)]
+    sharding_config: ShardingConfig,
+
+    #[clap(long, default_value_t = 0)]
+    start_version: Version,
+
+    /// Exclusive, defaults to right after the lower of the synced versions of the two DBs.
+    #[clap(long)]
+    end_version: Option<Version>,
+
+    /// Also compare the write sets and the state values they wrote in the range.
+    #[clap(long)]
+    compare_state_kv: bool,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let db = DbDir::new(self.db_dir.clone(), self.sharding_config.clone());
+        let other_db = DbDir::new(self.other_db_dir.clone(), self.sharding_config.clone());
+
+        let ledger_db = db.open_ledger_db()?;
+        let other_ledger_db = other_db.open_ledger_db()?;
+        let synced_version = ledger_db.metadata_db().get_synced_version()?;
+        let other_synced_version = other_ledger_db.metadata_db().get_synced_version()?;
+        println!("synced version: {synced_version}, other synced version: {other_synced_version}");
+
+        let max_end_version = min(synced_version, other_synced_version) + 1;
+        let end_version = self
+            .end_version
+            .map_or(max_end_version, |v| min(v, max_end_version));
+        ensure!(
+            self.start_version < end_version,
+            "Empty version range [{}, {}).",
+            self.start_version,
+            end_version,
+        );
+        println!(
+            "{}",
+            format!(
+                "* Compare {:?} and {:?} in version range [{}, {}). \n",
+                self.db_dir, self.other_db_dir, self.start_version, end_version,
+            )
+            .yellow()
+        );
+
+        let ledger_divergence = self.diff_ledger(&ledger_db, &other_ledger_db, end_version)?;
+        let state_root_divergence = self.diff_state_roots(
+            &db.open_state_merkle_db()?,
+            &other_db.open_state_merkle_db()?,
+            end_version,
+        )?;
+        let state_kv_divergence = if self.compare_state_kv {
+            self.diff_state_kv(
+                &ledger_db,
+                &other_ledger_db,
+                &db.open_state_kv_db()?,
+                &other_db.open_state_kv_db()?,
+                end_version,
+            )?
+        } else {
+            None
+        };
+
+        let divergence = [
+            ledger_divergence,
+            state_root_divergence,
+            state_kv_divergence,
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        match divergence {
+            Some(version) => println!(
+                "{}",
+                format!("DBs diverge first at version {version}.").red()
+            ),
+            None => println!("{}", "No divergence found.".to_string().yellow()),
+        }
+
+        Ok(())
+    }
+
+    fn diff_ledger(
+        &self,
+        ledger_db: &LedgerDb,
+        other_ledger_db: &LedgerDb,
+        end_version: Version,
+    ) -> Result<Option<Version>> {
+        println!("Comparing transaction infos...");
+        let num_versions = (end_version - self.start_version) as usize;
+        let txn_infos = ledger_db
+            .transaction_info_db()
+            .get_transaction_info_iter(self.start_version, num_versions)?;
+        let other_txn_infos = other_ledger_db
+            .transaction_info_db()
+            .get_transaction_info_iter(self.start_version, num_versions)?;
+
+        for (version, (txn_info, other_txn_info)) in
+            (self.start_version..).zip(txn_infos.zip(other_txn_infos))
+        {
+            let (txn_info, other_txn_info) = (txn_info?, other_txn_info?);
+            if txn_info != other_txn_info {
+                println!("{}", "- TransactionInfo mismatch!".to_string().red());
+                println!("     version: {version}");
+                println!("        info: {:?}", txn_info);
+                println!("  other info: {:?}\n", other_txn_info);
+                return Ok(Some(version));
+            }
+
+            if version % 1_000_000 == 0 {
+                println!("Transaction infos match until version {version}.");
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn diff_state_roots(
+        &self,
+        state_merkle_db: &StateMerkleDb,
+        other_state_merkle_db: &StateMerkleDb,
+        end_version: Version,
+    ) -> Result<Option<Version>> {
+        println!("Comparing state roots of the snapshots...");
+        let mut divergence = None;
+        let mut num_compared = 0;
+        let mut next_version = end_version;
+        while let Some(version) = state_merkle_db.get_state_snapshot_version_before(next_version)? {
+            if version < self.start_version {
+                break;
+            }
+            // The other DB might have been pruned or snapshotted at different versions.
+            if let Ok(other_root_hash) = other_state_merkle_db.get_root_hash(version) {
+                let root_hash = state_merkle_db.get_root_hash(version)?;
+                num_compared += 1;
+                if root_hash != other_root_hash {
+                    // Keep looking backwards, for the earliest mismatching snapshot.
+                    divergence = Some((version, root_hash, other_root_hash));
+                }
+            }
+            next_version = version;
+        }
+        println!("{num_compared} snapshots present in both DBs compared.");
+
+        Ok(divergence.map(|(version, root_hash, other_root_hash)| {
+            println!("{}", "- State root mismatch!".to_string().red());
+            println!("     version: {version}");
+            println!("        root: {root_hash}");
+            println!("  other root: {other_root_hash}\n");
+            version
+        }))
+    }
+
+    fn diff_state_kv(
+        &self,
+        ledger_db: &LedgerDb,
+        other_ledger_db: &LedgerDb,
+        state_kv_db: &StateKvDb,
+        other_state_kv_db: &StateKvDb,
+        end_version: Version,
+    ) -> Result<Option<Version>> {
+        println!("Comparing write sets and state values...");
+        let num_versions = (end_version - self.start_version) as usize;
+        let write_sets = ledger_db
+            .write_set_db()
+            .get_write_set_iter(self.start_version, num_versions)?;
+        let other_write_sets = other_ledger_db
+            .write_set_db()
+            .get_write_set_iter(self.start_version, num_versions)?;
+
+        for (version, (write_set, other_write_set)) in
+            (self.start_version..).zip(write_sets.zip(other_write_sets))
+        {
+            let (write_set, other_write_set) = (write_set?, other_write_set?);
+            if write_set != other_write_set {
+                println!("{}", "- WriteSet mismatch!".to_string().red());
+                println!("           version: {version}");
+                println!("         write set: {:?}", write_set);
+                println!("   other write set: {:?}\n", other_write_set);
+                return Ok(Some(version));
+            }
+
+            for (key, _write_op) in &write_set {
+                let value = state_kv_db.get_state_value_with_version_by_version(key, version)?;
+                let other_value =
+                    other_state_kv_db.get_state_value_with_version_by_version(key, version)?;
+                if value != other_value {
+                    println!("{}", "- State value mismatch!".to_string().red());
+                    println!("       version: {version}");
+                    println!("     state key: {:?}", key);
+                    println!("         value: {:?}", value);
+                    println!("   other value: {:?}\n", other_value);
+                    return Ok(Some(version));
+                }
+            }
+
+            if version % 1_000_000 == 0 {
+                println!("State values match until version {version}.");
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod print_db_versions;
+mod pruner_dry_run;
 
 use aptos_storage_interface::Result;
 
@@ -9,12 +10,14 @@ use aptos_storage_interface::Result;
 #[clap(about = "Examine databases.")]
 pub enum Cmd {
     PrintDbVersions(print_db_versions::Cmd),
+    PrunerDryRun(pruner_dry_run::Cmd),
 }
 
 impl Cmd {
     pub fn run(self) -> Result<()> {
         match self {
             Self::PrintDbVersions(cmd) => cmd.run(),
+            Self::PrunerDryRun(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::common::DbDir,
+    pruner::dry_run::{
+        estimate_epoch_snapshot_pruning, estimate_ledger_pruning, estimate_state_merkle_pruning,
+        PruningEstimate,
+    },
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema},
+    state_merkle_db::StateMerkleDb,
+};
+use aptos_config::config::{
+    EpochSnapshotPrunerConfig, LedgerPrunerConfig, StateMerklePrunerConfig,
+};
+use aptos_storage_interface::Result;
+use aptos_types::transaction::Version;
+use clap::Parser;
+use owo_colors::OwoColorize;
+
+#[derive(Parser)]
+#[clap(
+    about = "Estimate what the pruners would prune with the given prune windows, without \
+    deleting anything."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long, default_value_t = LedgerPrunerConfig::default().prune_window)]
+    ledger_prune_window: Version,
+
+    #[clap(long, default_value_t = StateMerklePrunerConfig::default().prune_window)]
+    state_merkle_prune_window: Version,
+
+    #[clap(long, default_value_t = EpochSnapshotPrunerConfig::default().prune_window)]
+    epoch_snapshot_prune_window: Version,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let ledger_db = self.db_dir.open_ledger_db()?;
+        let state_merkle_db = self.db_dir.open_state_merkle_db()?;
+        let latest_version = ledger_db.metadata_db().get_synced_version()?;
+        println!(
+            "{}",
+            format!("* Estimate pruning at latest version {latest_version}. \n").yellow()
+        );
+
+        let progress = ledger_db.metadata_db().get_pruner_progress()?;
+        let target = Self::target(progress, latest_version, self.ledger_prune_window);
+        print_estimate(
+            "ledger_pruner",
+            progress,
+            target,
+            estimate_ledger_pruning(&ledger_db, progress, target)?,
+        );
+
+        let progress = Self::state_merkle_progress(
+            &state_merkle_db,
+            &DbMetadataKey::StateMerklePrunerProgress,
+        )?;
+        let target = Self::target(progress, latest_version, self.state_merkle_prune_window);
+        print_estimate(
+            "state_merkle_pruner",
+            progress,
+            target,
+            estimate_state_merkle_pruning(&state_merkle_db, progress, target)?,
+        );
+
+        let progress = Self::state_merkle_progress(
+            &state_merkle_db,
+            &DbMetadataKey::EpochEndingStateMerklePrunerProgress,
+        )?;
+        let target = Self::target(progress, latest_version, self.epoch_snapshot_prune_window);
+        print_estimate(
+            "epoch_snapshot_pruner",
+            progress,
+            target,
+            estimate_epoch_snapshot_pruning(&state_merkle_db, progress, target)?,
+        );
+
+        Ok(())
+    }
+
+    fn target(progress: Version, latest_version: Version, prune_window: Version) -> Version {
+        std::cmp::max(progress, latest_version.saturating_sub(prune_window))
+    }
+
+    fn state_merkle_progress(
+        state_merkle_db: &StateMerkleDb,
+        key: &DbMetadataKey,
+    ) -> Result<Version> {
+        Ok(state_merkle_db
+            .metadata_db()
+            .get::<DbMetadataSchema>(key)?
+            .map_or(0, |v| v.expect_version()))
+    }
+}
+
+fn print_estimate(name: &str, progress: Version, target: Version, estimate: PruningEstimate) {
+    println!("{}", format!("- {name}:").yellow());
+    println!("   progress: {progress}");
+    println!("     target: {target}");
+    println!("   versions: {}", estimate.versions);
+    println!("      items: {}", estimate.items);
+    println!("      bytes: {}", estimate.bytes);
+}
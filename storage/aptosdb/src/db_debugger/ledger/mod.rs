@@ -3,6 +3,7 @@
 
 mod check_range_proof;
 mod check_txn_info_hashes;
+mod print_events;
 
 use aptos_storage_interface::Result;
 
@@ -11,6 +12,7 @@ use aptos_storage_interface::Result;
 pub enum Cmd {
     CheckTransactionInfoHashes(check_txn_info_hashes::Cmd),
     CheckRangeProof(check_range_proof::Cmd),
+    PrintEvents(print_events::Cmd),
 }
 
 impl Cmd {
@@ -18,6 +20,7 @@ impl Cmd {
         match self {
             Self::CheckTransactionInfoHashes(cmd) => cmd.run(),
             Self::CheckRangeProof(cmd) => cmd.run(),
+            Self::PrintEvents(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,191 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::common::{DbDir, StateKvView, PAGE_SIZE},
+    event_store::EventStore,
+    state_kv_db::StateKvDb,
+};
+use aptos_resource_viewer::{AnnotatedMoveValue, AptosValueAnnotator};
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_types::{
+    account_address::AccountAddress, contract_event::ContractEvent, event::EventKey,
+    transaction::Version,
+};
+use clap::Parser;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+#[derive(Parser)]
+#[clap(
+    about = "Print a page of events with their data decoded, either of the transactions starting \
+    at a version or of an event handle starting at a sequence number."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    /// In version range mode, the page covers the events of this and the following versions.
+    #[clap(long, default_value_t = 0)]
+    start_version: Version,
+
+    /// Address of the event handle, to print the events of the handle instead.
+    #[clap(long, requires = "event_creation_number")]
+    event_creator: Option<AccountAddress>,
+
+    #[clap(long, requires = "event_creator")]
+    event_creation_number: Option<u64>,
+
+    #[clap(long, default_value_t = 0)]
+    start_seq_num: u64,
+
+    /// Print the events as JSON lines.
+    #[clap(long)]
+    json: bool,
+}
+
+#[derive(Serialize)]
+struct EventRecord {
+    version: Version,
+    index: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<EventKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sequence_number: Option<u64>,
+    type_tag: String,
+    /// Present if the data could be decoded with the modules at the version of the event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<AnnotatedMoveValue>,
+    /// Present if the data could not be decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data_hex: Option<String>,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let ledger_db = self.db_dir.open_ledger_db()?;
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let synced_version = ledger_db.metadata_db().get_synced_version()?;
+
+        match (self.event_creator, self.event_creation_number) {
+            (Some(creator), Some(creation_number)) => {
+                let event_key = EventKey::new(creation_number, creator);
+                if !self.json {
+                    println!(
+                        "{}",
+                        format!(
+                            "* Print events of handle {} starting at sequence number {}. \n",
+                            event_key, self.start_seq_num,
+                        )
+                        .yellow()
+                    );
+                }
+
+                let event_store = EventStore::new(ledger_db.event_db().db_arc());
+                let indices = event_store.lookup_events_by_key(
+                    &event_key,
+                    self.start_seq_num,
+                    PAGE_SIZE as u64,
+                    synced_version,
+                )?;
+                for (_seq_num, version, index) in &indices {
+                    let event = event_store.get_event_by_version_and_index(*version, *index)?;
+                    self.print_event(&state_kv_db, *version, *index, &event)?;
+                }
+
+                if indices.len() == PAGE_SIZE && !self.json {
+                    println!(
+                        "Next page: --start-seq-num {}",
+                        self.start_seq_num + PAGE_SIZE as u64
+                    );
+                }
+            },
+            _ => {
+                ensure!(
+                    self.start_version <= synced_version,
+                    "Start version {} is greater than latest version {}.",
+                    self.start_version,
+                    synced_version,
+                );
+                let num_versions =
+                    std::cmp::min(PAGE_SIZE as u64, synced_version + 1 - self.start_version);
+                if !self.json {
+                    println!(
+                        "{}",
+                        format!(
+                            "* Print events in version range [{}, {}). \n",
+                            self.start_version,
+                            self.start_version + num_versions,
+                        )
+                        .yellow()
+                    );
+                }
+
+                let events_iter = ledger_db
+                    .event_db()
+                    .get_events_by_version_iter(self.start_version, num_versions as usize)?;
+                for (version, events) in (self.start_version..).zip(events_iter) {
+                    for (index, event) in events?.iter().enumerate() {
+                        self.print_event(&state_kv_db, version, index as u64, event)?;
+                    }
+                }
+
+                let next_version = self.start_version + num_versions;
+                if next_version <= synced_version && !self.json {
+                    println!("Next page: --start-version {next_version}");
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    fn print_event(
+        &self,
+        state_kv_db: &StateKvDb,
+        version: Version,
+        index: u64,
+        event: &ContractEvent,
+    ) -> Result<()> {
+        // Decode with the modules as they were when the event was emitted.
+        let view = StateKvView::new(state_kv_db, version);
+        let annotator = AptosValueAnnotator::new(&view);
+        let (data, data_hex) = match annotator.view_value(event.type_tag(), event.event_data()) {
+            Ok(value) => (Some(value), None),
+            Err(_) => (None, Some(hex::encode(event.event_data()))),
+        };
+        let record = EventRecord {
+            version,
+            index,
+            key: event.event_key().copied(),
+            sequence_number: event.v1().ok().map(|v1| v1.sequence_number()),
+            type_tag: event.type_tag().to_canonical_string(),
+            data,
+            data_hex,
+        };
+
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string(&record).map_err(anyhow::Error::from)?
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!("- Event {} of version {}:", record.index, record.version).yellow()
+        );
+        println!("       type: {}", record.type_tag);
+        if let (Some(key), Some(seq_num)) = (record.key, record.sequence_number) {
+            println!("        key: {key}");
+            println!("    seq num: {seq_num}");
+        }
+        match record.data {
+            Some(data) => println!("       data: {data}"),
+            None => println!("   raw data: {}", record.data_hex.unwrap_or_default()),
+        }
+
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@
 
 pub mod checkpoint;
 mod common;
+pub mod diff_db;
 mod examine;
 pub mod ledger;
 pub mod state_kv;
@@ -33,6 +34,8 @@ pub enum Cmd {
 
     Truncate(truncate::Cmd),
 
+    DiffDb(diff_db::Cmd),
+
     #[clap(subcommand)]
     Examine(examine::Cmd),
 }
@@ -45,6 +48,7 @@ impl Cmd {
             Cmd::Checkpoint(cmd) => cmd.run(),
             Cmd::Ledger(cmd) => cmd.run(),
             Cmd::Truncate(cmd) => cmd.run(),
+            Cmd::DiffDb(cmd) => cmd.run(),
             Cmd::Examine(cmd) => cmd.run(),
         }
     }
@@ -0,0 +1,88 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_debugger::{
+    common::DbDir,
+    state_kv::decompile_modules::{decompile, module_id},
+};
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_storage_interface::Result;
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
+use clap::Parser;
+use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use owo_colors::OwoColorize;
+use std::sync::Arc;
+
+#[derive(Parser)]
+#[clap(about = "Decompile the modules of an address as they were live at a version.")]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long)]
+    version: Version,
+
+    #[clap(long)]
+    address: AccountAddress,
+
+    /// Only decompile the module with this name, otherwise all modules under the address, which
+    /// requires scanning the whole snapshot.
+    #[clap(long)]
+    name: Option<Identifier>,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        println!(
+            "{}",
+            format!(
+                "* Decompile module {}::{} at version {}. \n",
+                self.address.to_hex_literal(),
+                self.name.as_ref().map_or("*", |name| name.as_str()),
+                self.version,
+            )
+            .yellow()
+        );
+
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let keys = match &self.name {
+            Some(name) => vec![StateKey::module(&self.address, name)],
+            None => {
+                let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+                let mut keys = Vec::new();
+                for leaf_res in
+                    JellyfishMerkleIterator::new_by_index(state_merkle_db, self.version, 0)?
+                {
+                    let (_key_hash, (key, _key_version)) = leaf_res?;
+                    if module_id(&key).map_or(false, |id| id.address() == &self.address) {
+                        keys.push(key);
+                    }
+                }
+                keys
+            },
+        };
+
+        if keys.is_empty() {
+            println!("{}", "No module found.".to_string().yellow());
+        }
+        for key in keys {
+            let module_id = module_id(&key).expect("Must be a module key.");
+            match state_kv_db.get_state_value_with_version_by_version(&key, self.version)? {
+                None => println!("{}", format!("Module {module_id} not found.").yellow()),
+                Some((version, value)) => {
+                    println!(
+                        "{}",
+                        format!("// Module {module_id}, last written at version {version}.")
+                            .yellow()
+                    );
+                    match decompile(value.bytes()) {
+                        Ok(source) => println!("{source}"),
+                        Err(err) => println!("{}", format!("Failed: {err}").red()),
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
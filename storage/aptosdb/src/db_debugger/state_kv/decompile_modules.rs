@@ -0,0 +1,157 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, state_kv_db::StateKvDb};
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_storage_interface::Result;
+use aptos_types::{
+    access_path::Path,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    transaction::Version,
+};
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressStyle};
+use move_binary_format::{binary_views::BinaryIndexedView, CompiledModule};
+use move_core_types::language_storage::ModuleId;
+use owo_colors::OwoColorize;
+use rayon::prelude::*;
+use revela::decompiler::Decompiler;
+use std::{
+    fs,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    sync::Arc,
+    time::Instant,
+};
+
+#[derive(Parser)]
+#[clap(about = "Decompile all modules in the snapshot at a version into a directory tree.")]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long)]
+    version: Version,
+
+    /// Modules are written to `<output-dir>/<address>/<name>.move`.
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+
+    #[clap(long, default_value = "32")]
+    concurrency: usize,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        println!(
+            "{}",
+            format!(
+                "* Decompile all modules in snapshot at version {} into {:?}. \n",
+                self.version, self.output_dir,
+            )
+            .yellow()
+        );
+
+        fs::create_dir_all(&self.output_dir)?;
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+
+        let start_time = Instant::now();
+        let mut module_keys = Vec::new();
+        for leaf_res in JellyfishMerkleIterator::new_by_index(state_merkle_db, self.version, 0)? {
+            let (_key_hash, (key, _key_version)) = leaf_res?;
+            if let Some(module_id) = module_id(&key) {
+                module_keys.push((key, module_id));
+            }
+        }
+        println!(
+            "{} modules found, scan time: {:?}",
+            module_keys.len(),
+            start_time.elapsed()
+        );
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency)
+            .build()
+            .expect("Failed to create thread pool.");
+        let bar = ProgressBar::new(module_keys.len() as u64);
+        bar.set_style(ProgressStyle::default_bar().template(
+            "[{elapsed_precise} {per_sec}] {bar:100.cyan/blue} {pos} / {len} {percent}% ETA {eta_precise}",
+        ));
+
+        let failures: Vec<(ModuleId, String)> = pool.install(|| {
+            module_keys
+                .par_iter()
+                .filter_map(|(key, module_id)| {
+                    let res = self.decompile_module(&state_kv_db, key, module_id);
+                    bar.inc(1);
+                    res.err().map(|err| (module_id.clone(), err))
+                })
+                .collect()
+        });
+        bar.finish();
+
+        let mut summary = format!(
+            "version: {}\nmodules: {}\ndecompiled: {}\nfailed: {}\n",
+            self.version,
+            module_keys.len(),
+            module_keys.len() - failures.len(),
+            failures.len(),
+        );
+        for (module_id, err) in &failures {
+            summary.push_str(&format!("  {}: {}\n", module_id, err));
+        }
+        fs::write(self.output_dir.join("summary.txt"), &summary)?;
+
+        println!();
+        println!("{}", summary);
+        println!(
+            "{}",
+            format!("Decompile complete in {:?}.", start_time.elapsed()).yellow()
+        );
+
+        Ok(())
+    }
+
+    fn decompile_module(
+        &self,
+        state_kv_db: &StateKvDb,
+        key: &StateKey,
+        module_id: &ModuleId,
+    ) -> std::result::Result<(), String> {
+        let (_version, value) = state_kv_db
+            .get_state_value_with_version_by_version(key, self.version)
+            .map_err(|err| format!("failed to read module: {}", err))?
+            .ok_or_else(|| "module not found".to_string())?;
+        let source = decompile(value.bytes())?;
+
+        let dir = self.output_dir.join(module_id.address().to_hex_literal());
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(dir.join(format!("{}.move", module_id.name())), source))
+            .map_err(|err| format!("failed to write source: {}", err))
+    }
+}
+
+/// Decompiles the module bytecode into Move source.
+pub(super) fn decompile(bytes: &[u8]) -> std::result::Result<String, String> {
+    let module = CompiledModule::deserialize(bytes)
+        .map_err(|err| format!("failed to deserialize module: {}", err))?;
+
+    // a bug in the decompiler must not abort the whole job
+    catch_unwind(AssertUnwindSafe(|| {
+        Decompiler::new(vec![BinaryIndexedView::Module(&module)], Default::default())
+            .decompile()
+            .map_err(|err| format!("failed to decompile: {}", err))
+    }))
+    .map_err(|_| "decompiler panicked".to_string())?
+}
+
+pub(super) fn module_id(key: &StateKey) -> Option<ModuleId> {
+    match key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            Path::Code(module_id) => Some(module_id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
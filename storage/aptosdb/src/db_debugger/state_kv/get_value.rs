@@ -1,7 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::db_debugger::common::DbDir;
+use crate::db_debugger::common::{decode_state_value, DbDir, StateKvView};
+use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_storage_interface::Result;
 use aptos_types::{state_store::state_key::StateKey, transaction::Version};
 use clap::Parser;
@@ -18,6 +19,10 @@ pub struct Cmd {
 
     #[clap(long)]
     version: Version,
+
+    /// Decode resources into Move values, using the modules in the DB at the same version.
+    #[clap(long)]
+    decode: bool,
 }
 
 impl Cmd {
@@ -60,6 +65,13 @@ impl Cmd {
                 } else {
                     println!("     value: {:?}", value.bytes())
                 }
+                if self.decode {
+                    let view = StateKvView::new(&db, self.version);
+                    let annotator = AptosValueAnnotator::new(&view);
+                    if let Some(decoded) = decode_state_value(&annotator, &key, value.bytes()) {
+                        println!("   decoded: {decoded}");
+                    }
+                }
                 println!("  metadata: {:?}", value.into_metadata());
             },
         }
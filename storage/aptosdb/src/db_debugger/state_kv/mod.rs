@@ -1,21 +1,30 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod decompile_module;
+mod decompile_modules;
 mod get_value;
+mod scan_prefix;
 mod scan_snapshot;
 
 use aptos_storage_interface::Result;
 
 #[derive(clap::Subcommand)]
 pub enum Cmd {
+    DecompileModule(decompile_module::Cmd),
+    DecompileModules(decompile_modules::Cmd),
     GetValue(get_value::Cmd),
+    ScanPrefix(scan_prefix::Cmd),
     ScanSnapshot(scan_snapshot::Cmd),
 }
 
 impl Cmd {
     pub fn run(self) -> Result<()> {
         match self {
+            Self::DecompileModule(cmd) => cmd.run(),
+            Self::DecompileModules(cmd) => cmd.run(),
             Self::GetValue(cmd) => cmd.run(),
+            Self::ScanPrefix(cmd) => cmd.run(),
             Self::ScanSnapshot(cmd) => cmd.run(),
         }
     }
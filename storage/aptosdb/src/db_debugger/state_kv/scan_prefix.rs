@@ -0,0 +1,143 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_debugger::common::{decode_state_value, DbDir, StateKvView, PAGE_SIZE};
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_storage_interface::Result;
+use aptos_types::{
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    transaction::Version,
+};
+use clap::Parser;
+use move_core_types::account_address::AccountAddress;
+use owo_colors::OwoColorize;
+use std::sync::Arc;
+
+#[derive(Parser)]
+#[clap(
+    about = "Print a page of the state values at a version whose keys match the given account \
+    and/or serialized key prefix, in the key hash order."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long)]
+    version: Version,
+
+    /// Only match access paths under this account.
+    #[clap(long)]
+    account: Option<AccountAddress>,
+
+    /// Only match keys whose BCS serialization (as taken by `get-value --key-hex`) starts with
+    /// these bytes.
+    #[clap(long)]
+    prefix_hex: Option<String>,
+
+    /// Number of matching keys to skip, i.e. the start of the page.
+    #[clap(long, default_value_t = 0)]
+    start_index: usize,
+
+    /// Print the value bytes in addition to their size.
+    #[clap(long)]
+    print_bytes: bool,
+
+    /// Decode resources into Move values, using the modules in the DB at the same version.
+    #[clap(long)]
+    decode: bool,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let prefix = self
+            .prefix_hex
+            .as_ref()
+            .map(hex::decode)
+            .transpose()
+            .map_err(anyhow::Error::from)?
+            .unwrap_or_default();
+        println!(
+            "{}",
+            format!(
+                "* Scan key values in snapshot at version {} for account {:?} and key prefix {:?}, \
+                starting at match {}. \n",
+                self.version,
+                self.account,
+                hex::encode(&prefix),
+                self.start_index,
+            )
+            .yellow()
+        );
+
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+        let view = StateKvView::new(&state_kv_db, self.version);
+        let annotator = AptosValueAnnotator::new(&view);
+
+        let mut num_matches = 0;
+        let mut num_printed = 0;
+        let mut page_bytes = 0;
+        for leaf_res in JellyfishMerkleIterator::new_by_index(state_merkle_db, self.version, 0)? {
+            let (_key_hash, (key, _key_version)) = leaf_res?;
+            let serialized = bcs::to_bytes(&key)?;
+            if !serialized.starts_with(&prefix) || !self.matches_account(&key) {
+                continue;
+            }
+
+            let index = num_matches;
+            num_matches += 1;
+            if index < self.start_index {
+                continue;
+            }
+            if index >= self.start_index + PAGE_SIZE {
+                break;
+            }
+
+            let (value_version, value) = state_kv_db
+                .get_state_value_with_version_by_version(&key, self.version)?
+                .expect("Value must exist.");
+            num_printed += 1;
+            page_bytes += value.size();
+
+            println!("{}", format!("- Match {index}:").yellow());
+            println!("    state key: {:?}", key);
+            println!("   serialized: {}", hex::encode(&serialized));
+            println!("      version: {value_version}");
+            println!("         size: {} bytes", value.size());
+            if self.print_bytes {
+                println!("        bytes: {:?}", value.bytes());
+            }
+            if self.decode {
+                if let Some(decoded) = decode_state_value(&annotator, &key, value.bytes()) {
+                    println!("      decoded: {decoded}");
+                }
+            }
+            println!("     metadata: {:?}", value.into_metadata());
+        }
+
+        if num_printed == 0 {
+            println!("{}", "No more matching keys.".to_string().yellow());
+        } else {
+            println!(
+                "{}",
+                format!("{num_printed} keys, {page_bytes} bytes printed.").yellow()
+            );
+            if num_matches > self.start_index + PAGE_SIZE {
+                println!("Next page: --start-index {}", self.start_index + PAGE_SIZE);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn matches_account(&self, key: &StateKey) -> bool {
+        match (self.account, key.inner()) {
+            (None, _) => true,
+            (Some(account), StateKeyInner::AccessPath(access_path)) => {
+                access_path.address == account
+            },
+            (Some(_), _) => false,
+        }
+    }
+}
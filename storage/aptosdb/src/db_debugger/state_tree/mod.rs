@@ -4,6 +4,7 @@
 mod get_leaf;
 mod get_path;
 mod get_snapshots;
+mod verify_proofs;
 
 use aptos_storage_interface::Result;
 
@@ -12,6 +13,7 @@ pub enum Cmd {
     GetSnapshots(get_snapshots::Cmd),
     GetPath(get_path::Cmd),
     GetLeaf(get_leaf::Cmd),
+    VerifyProofs(verify_proofs::Cmd),
 }
 
 impl Cmd {
@@ -20,6 +22,7 @@ impl Cmd {
             Self::GetSnapshots(cmd) => Ok(cmd.run()?),
             Self::GetPath(cmd) => cmd.run(),
             Self::GetLeaf(cmd) => cmd.run(),
+            Self::VerifyProofs(cmd) => cmd.run(),
         }
     }
 }
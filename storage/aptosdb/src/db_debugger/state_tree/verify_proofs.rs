@@ -0,0 +1,115 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, state_kv_db::StateKvDb, state_merkle_db::StateMerkleDb};
+use aptos_crypto::HashValue;
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
+use aptos_types::transaction::Version;
+use clap::Parser;
+use owo_colors::OwoColorize;
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Parser)]
+#[clap(
+    about = "Verify the proofs of state keys sampled evenly across a snapshot against its root hash."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long)]
+    version: Version,
+
+    #[clap(long, default_value_t = 1000)]
+    num_samples: usize,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        ensure!(self.num_samples > 0, "num_samples must be greater than 0.");
+        println!(
+            "{}",
+            format!(
+                "* Verify proofs of {} sampled keys in snapshot at version {}. \n",
+                self.num_samples, self.version,
+            )
+            .yellow()
+        );
+
+        let state_kv_db = self.db_dir.open_state_kv_db()?;
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+        let root_hash = state_merkle_db.get_root_hash(self.version)?;
+        let total_leaves = state_merkle_db.get_leaf_count(self.version)?;
+        println!("root hash: {root_hash}");
+        println!("total leaves: {total_leaves}");
+
+        let num_samples = std::cmp::min(self.num_samples, total_leaves);
+        let mut num_failures = 0;
+        // Failures by the first nibble of the key hash, i.e. the top level subtree.
+        let mut failures_by_subtree = BTreeMap::<u8, usize>::new();
+        for n in 0..num_samples {
+            let leaf_index = n * total_leaves / num_samples;
+            if let Err((key_hash, err)) =
+                self.verify_leaf(&state_merkle_db, &state_kv_db, root_hash, leaf_index)
+            {
+                num_failures += 1;
+                println!("{}", "- Verification failed!".to_string().red());
+                println!("   leaf index: {leaf_index}");
+                if let Some(key_hash) = key_hash {
+                    println!("     key hash: {key_hash:x}");
+                    *failures_by_subtree.entry(key_hash.nibble(0)).or_default() += 1;
+                }
+                println!("        error: {err}\n");
+            }
+        }
+
+        if num_failures == 0 {
+            println!(
+                "{}",
+                format!("All {num_samples} sampled proofs verified.").yellow()
+            );
+        } else {
+            println!(
+                "{}",
+                format!("{num_failures} of {num_samples} sampled proofs failed.").red()
+            );
+            println!("Failures by top level subtree:");
+            for (nibble, count) in failures_by_subtree {
+                println!("  {nibble:x}: {count}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the key hash of the leaf if it could be read, along with the error.
+    fn verify_leaf(
+        &self,
+        state_merkle_db: &Arc<StateMerkleDb>,
+        state_kv_db: &StateKvDb,
+        root_hash: HashValue,
+        leaf_index: usize,
+    ) -> std::result::Result<(), (Option<HashValue>, String)> {
+        let (key_hash, (state_key, _leaf_version)) = JellyfishMerkleIterator::new_by_index(
+            state_merkle_db.clone(),
+            self.version,
+            leaf_index,
+        )
+        .and_then(|mut iter| iter.next().transpose())
+        .map_err(|err| (None, format!("failed to read leaf: {err}")))?
+        .ok_or_else(|| (None, "leaf not found".to_string()))?;
+
+        let value = state_kv_db
+            .get_state_value_with_version_by_version(&state_key, self.version)
+            .map_err(|err| (Some(key_hash), format!("failed to read value: {err}")))?
+            .map(|(_version, value)| value)
+            .ok_or_else(|| (Some(key_hash), "value not found".to_string()))?;
+        let (_leaf, proof) = state_merkle_db
+            .get_with_proof_ext(&state_key, self.version, 0)
+            .map_err(|err| (Some(key_hash), format!("failed to read proof: {err}")))?;
+        proof
+            .verify(root_hash, key_hash, Some(&value))
+            .map_err(|err| (Some(key_hash), format!("invalid proof: {err}")))
+    }
+}
@@ -89,6 +89,20 @@ pub static PRUNER_VERSIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Running totals of what the pruners in dry-run mode would have pruned, with `tag` being one of
+/// `versions`, `items` and `bytes`.
+pub static PRUNER_DRY_RUN: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_pruner_dry_run",
+        // metric description
+        "Aptos pruner dry run totals",
+        // metric labels (dimensions)
+        &["pruner_name", "tag"]
+    )
+    .unwrap()
+});
+
 /// Pruner batch size. For ledger pruner, this means the number of versions to be pruned at a time.
 /// For state store pruner, this means the number of stale nodes to be pruned at a time.
 pub static PRUNER_BATCH_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
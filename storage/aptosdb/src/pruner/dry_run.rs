@@ -0,0 +1,303 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ledger_db::LedgerDb,
+    metrics::PRUNER_DRY_RUN,
+    pruner::{db_pruner::DBPruner, state_merkle_pruner::generics::StaleNodeIndexSchemaTrait},
+    schema::{
+        event::EventSchema, jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        transaction::TransactionSchema, transaction_auxiliary_data::TransactionAuxiliaryDataSchema,
+        transaction_info::TransactionInfoSchema, version_data::VersionDataSchema,
+        write_set::WriteSetSchema,
+    },
+    state_merkle_db::StateMerkleDb,
+};
+use aptos_infallible::Mutex;
+use aptos_jellyfish_merkle::{node_type::NodeKey, StaleNodeIndex};
+use aptos_logger::info;
+use aptos_schemadb::{
+    schema::{KeyCodec, Schema, ValueCodec},
+    DB,
+};
+use aptos_storage_interface::Result;
+use aptos_types::transaction::{AtomicVersion, Version};
+use std::{
+    cmp::{max, min},
+    ops::AddAssign,
+    sync::atomic::Ordering,
+};
+
+/// The data a pruner would delete.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PruningEstimate {
+    /// Number of versions the pruner would advance by.
+    pub versions: u64,
+    /// Number of DB entries, e.g. transactions, events or tree nodes.
+    pub items: u64,
+    /// Size of the encoded keys and values of the entries.
+    pub bytes: u64,
+}
+
+impl AddAssign for PruningEstimate {
+    fn add_assign(&mut self, other: Self) {
+        self.versions += other.versions;
+        self.items += other.items;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Estimates what the ledger pruner would delete to prune the versions in `[begin, end)`. The
+/// indices derived from the counted entries, e.g. the transaction accumulator and the transaction
+/// by hash index, are not counted.
+pub fn estimate_ledger_pruning(
+    ledger_db: &LedgerDb,
+    begin: Version,
+    end: Version,
+) -> Result<PruningEstimate> {
+    let mut estimate = PruningEstimate {
+        versions: end.saturating_sub(begin),
+        ..Default::default()
+    };
+    estimate += estimate_by_version::<TransactionSchema>(
+        ledger_db.transaction_db_raw(),
+        &begin,
+        end,
+        |version| *version,
+    )?;
+    estimate += estimate_by_version::<TransactionInfoSchema>(
+        ledger_db.transaction_info_db_raw(),
+        &begin,
+        end,
+        |version| *version,
+    )?;
+    estimate += estimate_by_version::<TransactionAuxiliaryDataSchema>(
+        ledger_db.transaction_auxiliary_data_db_raw(),
+        &begin,
+        end,
+        |version| *version,
+    )?;
+    estimate += estimate_by_version::<WriteSetSchema>(
+        ledger_db.write_set_db_raw(),
+        &begin,
+        end,
+        |version| *version,
+    )?;
+    estimate += estimate_by_version::<EventSchema>(
+        ledger_db.event_db_raw(),
+        &(begin, 0),
+        end,
+        |(version, _index)| *version,
+    )?;
+    estimate += estimate_by_version::<VersionDataSchema>(
+        &ledger_db.metadata_db_arc(),
+        &begin,
+        end,
+        |version| *version,
+    )?;
+    Ok(estimate)
+}
+
+/// Estimates what the state merkle pruner would delete to advance from `begin` to `end`.
+#[cfg(any(test, feature = "db-debugger"))]
+pub fn estimate_state_merkle_pruning(
+    state_merkle_db: &StateMerkleDb,
+    begin: Version,
+    end: Version,
+) -> Result<PruningEstimate> {
+    estimate_stale_node_pruning::<crate::schema::stale_node_index::StaleNodeIndexSchema>(
+        state_merkle_db,
+        begin,
+        end,
+    )
+}
+
+/// Estimates what the epoch snapshot pruner would delete to advance from `begin` to `end`.
+#[cfg(feature = "db-debugger")]
+pub fn estimate_epoch_snapshot_pruning(
+    state_merkle_db: &StateMerkleDb,
+    begin: Version,
+    end: Version,
+) -> Result<PruningEstimate> {
+    estimate_stale_node_pruning::<
+        crate::schema::stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
+    >(state_merkle_db, begin, end)
+}
+
+/// Estimates what the pruner tracking the stale nodes in `S` would delete to advance from `begin`
+/// to `end`, i.e. the nodes that became stale in `(begin, end]`.
+pub(in crate::pruner) fn estimate_stale_node_pruning<S: StaleNodeIndexSchemaTrait>(
+    state_merkle_db: &StateMerkleDb,
+    begin: Version,
+    end: Version,
+) -> Result<PruningEstimate>
+where
+    StaleNodeIndex: KeyCodec<S>,
+{
+    let mut estimate = PruningEstimate {
+        versions: end.saturating_sub(begin),
+        ..Default::default()
+    };
+    estimate += estimate_stale_nodes::<S>(state_merkle_db.metadata_db(), begin, end)?;
+    if state_merkle_db.sharding_enabled() {
+        for shard_id in 0..state_merkle_db.num_shards() {
+            estimate += estimate_stale_nodes::<S>(state_merkle_db.db_shard(shard_id), begin, end)?;
+        }
+    }
+    Ok(estimate)
+}
+
+fn estimate_by_version<S: Schema>(
+    db: &DB,
+    seek_key: &S::Key,
+    end: Version,
+    version_of: impl Fn(&S::Key) -> Version,
+) -> Result<PruningEstimate> {
+    let mut estimate = PruningEstimate::default();
+    let mut iter = db.iter::<S>()?;
+    iter.seek(seek_key)?;
+    for res in iter {
+        let (key, value) = res?;
+        if version_of(&key) >= end {
+            break;
+        }
+        estimate.items += 1;
+        estimate.bytes += (key.encode_key()?.len() + value.encode_value()?.len()) as u64;
+    }
+    Ok(estimate)
+}
+
+fn estimate_stale_nodes<S: StaleNodeIndexSchemaTrait>(
+    db: &DB,
+    begin: Version,
+    end: Version,
+) -> Result<PruningEstimate>
+where
+    StaleNodeIndex: KeyCodec<S>,
+{
+    let mut estimate = PruningEstimate::default();
+    let mut iter = db.iter::<S>()?;
+    // The nodes that became stale at `begin` were pruned when reaching it.
+    iter.seek(&StaleNodeIndex {
+        stale_since_version: begin + 1,
+        node_key: NodeKey::new_empty_path(0),
+    })?;
+    for res in iter {
+        let (index, _) = res?;
+        if index.stale_since_version > end {
+            break;
+        }
+        estimate.items += 1;
+        estimate.bytes += <StaleNodeIndex as KeyCodec<S>>::encode_key(&index)?.len() as u64;
+        if let Some(node) = db.get::<JellyfishMerkleNodeSchema>(&index.node_key)? {
+            estimate.bytes +=
+                (<NodeKey as KeyCodec<JellyfishMerkleNodeSchema>>::encode_key(&index.node_key)?
+                    .len()
+                    + node.encode_value()?.len()) as u64;
+        }
+    }
+    Ok(estimate)
+}
+
+/// Stands in for a pruner in dry-run mode: on its way to the target version, it estimates what
+/// would be pruned instead of deleting it, and reports the running totals via logs and metrics.
+/// The progress is kept in memory only, so the totals restart from the persisted pruner progress
+/// after a restart.
+pub(crate) struct DryRunPruner<F> {
+    name: &'static str,
+    /// Keeps track of the target version that the pruner needs to achieve.
+    target_version: AtomicVersion,
+    /// Version up to which the data has been estimated.
+    progress: AtomicVersion,
+    /// Estimates the data pruned to advance from the first version to the second.
+    estimate_fn: F,
+    total: Mutex<PruningEstimate>,
+}
+
+impl<F> DryRunPruner<F>
+where
+    F: Fn(Version, Version) -> Result<PruningEstimate> + Send + Sync,
+{
+    pub fn new(name: &'static str, progress: Version, estimate_fn: F) -> Self {
+        info!(
+            name = name,
+            progress = progress,
+            "Initialized in dry-run mode."
+        );
+
+        Self {
+            name,
+            target_version: AtomicVersion::new(progress),
+            progress: AtomicVersion::new(progress),
+            estimate_fn,
+            total: Mutex::new(PruningEstimate::default()),
+        }
+    }
+}
+
+impl<F> DBPruner for DryRunPruner<F>
+where
+    F: Fn(Version, Version) -> Result<PruningEstimate> + Send + Sync,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The batch size is taken in versions, regardless of the pruner.
+    fn prune(&self, batch_size: usize) -> Result<Version> {
+        let mut progress = self.progress();
+        let target_version = self.target_version();
+        if progress >= target_version {
+            return Ok(progress);
+        }
+
+        while progress < target_version {
+            let end = min(progress + max(batch_size, 1) as u64, target_version);
+            let estimate = (self.estimate_fn)(progress, end)?;
+            let total = {
+                let mut total = self.total.lock();
+                *total += estimate;
+                *total
+            };
+            for (tag, value) in [
+                ("versions", total.versions),
+                ("items", total.items),
+                ("bytes", total.bytes),
+            ] {
+                PRUNER_DRY_RUN
+                    .with_label_values(&[self.name, tag])
+                    .set(value as i64);
+            }
+            self.record_progress(end);
+            progress = end;
+        }
+
+        let total = *self.total.lock();
+        info!(
+            name = self.name,
+            progress = target_version,
+            versions = total.versions,
+            items = total.items,
+            bytes = total.bytes,
+            "Dry run: would have pruned."
+        );
+
+        Ok(target_version)
+    }
+
+    fn progress(&self) -> Version {
+        self.progress.load(Ordering::SeqCst)
+    }
+
+    fn set_target_version(&self, target_version: Version) {
+        self.target_version.store(target_version, Ordering::SeqCst);
+    }
+
+    fn target_version(&self) -> Version {
+        self.target_version.load(Ordering::SeqCst)
+    }
+
+    fn record_progress(&self, progress: Version) {
+        self.progress.store(progress, Ordering::SeqCst);
+    }
+}
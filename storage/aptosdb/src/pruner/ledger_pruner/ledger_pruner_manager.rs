@@ -5,7 +5,10 @@ use crate::{
     ledger_db::LedgerDb,
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
-        ledger_pruner::LedgerPruner, pruner_manager::PrunerManager, pruner_utils,
+        dry_run::{estimate_ledger_pruning, DryRunPruner},
+        ledger_pruner::LedgerPruner,
+        pruner_manager::PrunerManager,
+        pruner_utils,
         pruner_worker::PrunerWorker,
     },
 };
@@ -24,6 +27,8 @@ pub(crate) struct LedgerPrunerManager {
     prune_window: Version,
     /// It is None iff the pruner is not enabled.
     pruner_worker: Option<PrunerWorker>,
+    /// If true, the worker only estimates what would be pruned.
+    dry_run: bool,
     /// Ideal batch size of the versions to be sent to the ledger pruner
     pruning_batch_size: usize,
     /// latest version
@@ -38,7 +43,7 @@ impl PrunerManager for LedgerPrunerManager {
     type Pruner = LedgerPruner;
 
     fn is_pruner_enabled(&self) -> bool {
-        self.pruner_worker.is_some()
+        self.pruner_worker.is_some() && !self.dry_run
     }
 
     fn get_prune_window(&self) -> Version {
@@ -69,7 +74,7 @@ impl PrunerManager for LedgerPrunerManager {
         let min_readable_version = self.get_min_readable_version();
         // Only wake up the ledger pruner if there are `ledger_pruner_pruning_batch_size` pending
         // versions.
-        if self.is_pruner_enabled()
+        if self.pruner_worker.is_some()
             && latest_version
                 >= min_readable_version + self.pruning_batch_size as u64 + self.prune_window
         {
@@ -110,19 +115,25 @@ impl LedgerPrunerManager {
         ledger_pruner_config: LedgerPrunerConfig,
         internal_indexer_db: Option<InternalIndexerDB>,
     ) -> Self {
-        let pruner_worker = if ledger_pruner_config.enable {
+        let min_readable_version =
+            pruner_utils::get_ledger_pruner_progress(&ledger_db).expect("Must succeed.");
+
+        let pruner_worker = if !ledger_pruner_config.enable {
+            None
+        } else if ledger_pruner_config.dry_run {
+            Some(Self::init_dry_run_pruner(
+                Arc::clone(&ledger_db),
+                ledger_pruner_config,
+                min_readable_version,
+            ))
+        } else {
             Some(Self::init_pruner(
                 Arc::clone(&ledger_db),
                 ledger_pruner_config,
                 internal_indexer_db,
             ))
-        } else {
-            None
         };
 
-        let min_readable_version =
-            pruner_utils::get_ledger_pruner_progress(&ledger_db).expect("Must succeed.");
-
         PRUNER_VERSIONS
             .with_label_values(&["ledger_pruner", "min_readable"])
             .set(min_readable_version as i64);
@@ -131,6 +142,7 @@ impl LedgerPrunerManager {
             ledger_db,
             prune_window: ledger_pruner_config.prune_window,
             pruner_worker,
+            dry_run: ledger_pruner_config.dry_run,
             pruning_batch_size: ledger_pruner_config.batch_size,
             latest_version: Arc::new(Mutex::new(min_readable_version)),
             user_pruning_window_offset: ledger_pruner_config.user_pruning_window_offset,
@@ -159,15 +171,32 @@ impl LedgerPrunerManager {
         PrunerWorker::new(pruner, ledger_pruner_config.batch_size, "ledger")
     }
 
+    fn init_dry_run_pruner(
+        ledger_db: Arc<LedgerDb>,
+        ledger_pruner_config: LedgerPrunerConfig,
+        progress: Version,
+    ) -> PrunerWorker {
+        let pruner = Arc::new(DryRunPruner::new(
+            "ledger_pruner",
+            progress,
+            move |begin, end| estimate_ledger_pruning(&ledger_db, begin, end),
+        ));
+
+        PrunerWorker::new(pruner, ledger_pruner_config.batch_size, "ledger_dry_run")
+    }
+
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
         let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
-
-        PRUNER_VERSIONS
-            .with_label_values(&["ledger_pruner", "min_readable"])
-            .set(min_readable_version as i64);
+        // Nothing gets deleted in dry-run mode, so everything stays readable.
+        if !self.dry_run {
+            self.min_readable_version
+                .store(min_readable_version, Ordering::SeqCst);
+
+            PRUNER_VERSIONS
+                .with_label_values(&["ledger_pruner", "min_readable"])
+                .set(min_readable_version as i64);
+        }
 
         self.pruner_worker
             .as_ref()
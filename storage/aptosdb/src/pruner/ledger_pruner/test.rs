@@ -54,6 +54,7 @@ fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
         prune_window: 0,
         batch_size: 1,
         user_pruning_window_offset: 0,
+        dry_run: false,
     });
 
     // write sets
@@ -127,6 +128,7 @@ fn verify_txn_store_pruner(
                 prune_window: 0,
                 batch_size: 1,
                 user_pruning_window_offset: 0,
+                dry_run: false,
             });
         pruner
             .wake_and_wait_pruner(i as u64 /* latest_version */)
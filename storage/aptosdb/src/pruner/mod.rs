@@ -4,6 +4,7 @@
 
 mod db_pruner;
 mod db_sub_pruner;
+pub(crate) mod dry_run;
 mod ledger_pruner;
 mod pruner_manager;
 mod pruner_utils;
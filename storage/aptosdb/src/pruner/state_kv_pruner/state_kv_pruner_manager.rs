@@ -82,7 +82,8 @@ impl PrunerManager for StateKvPrunerManager {
 
 impl StateKvPrunerManager {
     pub fn new(state_kv_db: Arc<StateKvDb>, state_kv_pruner_config: LedgerPrunerConfig) -> Self {
-        let pruner_worker = if state_kv_pruner_config.enable {
+        // The state values are kept in dry-run mode, see `LedgerPrunerConfig::dry_run`.
+        let pruner_worker = if state_kv_pruner_config.enable && !state_kv_pruner_config.dry_run {
             Some(Self::init_pruner(
                 Arc::clone(&state_kv_db),
                 state_kv_pruner_config,
@@ -7,6 +7,7 @@
 use crate::{
     metrics::{PRUNER_BATCH_SIZE, PRUNER_VERSIONS, PRUNER_WINDOW},
     pruner::{
+        dry_run::{estimate_stale_node_pruning, DryRunPruner},
         pruner_manager::PrunerManager,
         pruner_utils,
         pruner_worker::PrunerWorker,
@@ -39,6 +40,8 @@ where
     prune_window: Version,
     /// It is None iff the pruner is not enabled.
     pruner_worker: Option<PrunerWorker>,
+    /// If true, the worker only estimates what would be pruned.
+    dry_run: bool,
     /// The minimal readable version for the state merkle data.
     min_readable_version: AtomicVersion,
 
@@ -52,7 +55,7 @@ where
     type Pruner = StateMerklePruner<S>;
 
     fn is_pruner_enabled(&self) -> bool {
-        self.pruner_worker.is_some()
+        self.pruner_worker.is_some() && !self.dry_run
     }
 
     fn get_prune_window(&self) -> Version {
@@ -66,7 +69,9 @@ where
     /// Sets pruner target version when necessary.
     fn maybe_set_pruner_target_db_version(&self, latest_version: Version) {
         let min_readable_version = self.get_min_readable_version();
-        if self.is_pruner_enabled() && latest_version >= min_readable_version + self.prune_window {
+        if self.pruner_worker.is_some()
+            && latest_version >= min_readable_version + self.prune_window
+        {
             self.set_pruner_target_db_version(latest_version);
         }
     }
@@ -107,18 +112,24 @@ where
         state_merkle_db: Arc<StateMerkleDb>,
         state_merkle_pruner_config: StateMerklePrunerConfig,
     ) -> Self {
-        let pruner_worker = if state_merkle_pruner_config.enable {
-            Some(Self::init_pruner(
+        let min_readable_version = pruner_utils::get_state_merkle_pruner_progress(&state_merkle_db)
+            .expect("Must succeed.");
+
+        let pruner_worker = if !state_merkle_pruner_config.enable {
+            None
+        } else if state_merkle_pruner_config.dry_run {
+            Some(Self::init_dry_run_pruner(
                 Arc::clone(&state_merkle_db),
                 state_merkle_pruner_config,
+                min_readable_version,
             ))
         } else {
-            None
+            Some(Self::init_pruner(
+                Arc::clone(&state_merkle_db),
+                state_merkle_pruner_config,
+            ))
         };
 
-        let min_readable_version = pruner_utils::get_state_merkle_pruner_progress(&state_merkle_db)
-            .expect("Must succeed.");
-
         PRUNER_VERSIONS
             .with_label_values(&[S::name(), "min_readable"])
             .set(min_readable_version as i64);
@@ -127,6 +138,7 @@ where
             state_merkle_db,
             prune_window: state_merkle_pruner_config.prune_window,
             pruner_worker,
+            dry_run: state_merkle_pruner_config.dry_run,
             min_readable_version: AtomicVersion::new(min_readable_version),
             _phantom: PhantomData,
         }
@@ -156,16 +168,35 @@ where
         )
     }
 
+    fn init_dry_run_pruner(
+        state_merkle_db: Arc<StateMerkleDb>,
+        state_merkle_pruner_config: StateMerklePrunerConfig,
+        progress: Version,
+    ) -> PrunerWorker {
+        let pruner = Arc::new(DryRunPruner::new(S::name(), progress, move |begin, end| {
+            estimate_stale_node_pruning::<S>(&state_merkle_db, begin, end)
+        }));
+
+        PrunerWorker::new(
+            pruner,
+            state_merkle_pruner_config.batch_size,
+            "state_merkle_dry_run",
+        )
+    }
+
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
 
         let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
-
-        PRUNER_VERSIONS
-            .with_label_values(&[S::name(), "min_readable"])
-            .set(min_readable_version as i64);
+        // Nothing gets deleted in dry-run mode, so everything stays readable.
+        if !self.dry_run {
+            self.min_readable_version
+                .store(min_readable_version, Ordering::SeqCst);
+
+            PRUNER_VERSIONS
+                .with_label_values(&[S::name(), "min_readable"])
+                .set(min_readable_version as i64);
+        }
 
         self.pruner_worker
             .as_ref()
@@ -7,7 +7,10 @@ use crate::{
         test_helper::{arb_state_kv_sets, update_store},
         AptosDB,
     },
-    pruner::{PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
+    pruner::{
+        dry_run::estimate_state_merkle_pruning, PrunerManager, StateKvPrunerManager,
+        StateMerklePrunerManager,
+    },
     schema::{
         stale_node_index::StaleNodeIndexSchema,
         stale_state_value_index::StaleStateValueIndexSchema,
@@ -32,7 +35,7 @@ use aptos_types::{
 };
 use arr_macro::arr;
 use proptest::{prelude::*, proptest};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, thread::sleep, time::Duration};
 
 fn put_value_set(
     state_store: &StateStore,
@@ -108,6 +111,7 @@ fn create_state_merkle_pruner_manager(
         enable: true,
         prune_window: 0,
         batch_size: prune_batch_size,
+        dry_run: false,
     })
 }
 
@@ -350,6 +354,56 @@ fn test_state_store_pruner_disabled() {
     }
 }
 
+#[test]
+fn test_state_store_pruner_dry_run() {
+    let key = StateKey::raw(b"test_key1");
+
+    let num_versions = 25;
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test_no_cache(&tmp_dir);
+    let state_store = &aptos_db.state_store;
+
+    // Insert 25 values in the db.
+    for i in 0..num_versions {
+        put_value_set(
+            state_store,
+            vec![(key.clone(), StateValue::from(vec![i as u8]))],
+            i, /* version */
+        );
+    }
+
+    let estimate = estimate_state_merkle_pruning(&aptos_db.state_merkle_db(), 0, 10).unwrap();
+    assert_eq!(estimate.versions, 10);
+    assert!(estimate.items > 0);
+    assert!(estimate.bytes > 0);
+
+    let pruner = StateMerklePrunerManager::<StaleNodeIndexSchema>::new(
+        aptos_db.state_merkle_db(),
+        StateMerklePrunerConfig {
+            enable: true,
+            prune_window: 0,
+            batch_size: 1,
+            dry_run: true,
+        },
+    );
+    assert!(!pruner.is_pruner_enabled());
+    pruner.maybe_set_pruner_target_db_version(10 /* latest_version */);
+    while pruner.is_pruning_pending() {
+        sleep(Duration::from_millis(1));
+    }
+
+    // Nothing is pruned.
+    assert_eq!(pruner.get_min_readable_version(), 0);
+    for i in 0..num_versions {
+        verify_state_in_store(
+            state_store,
+            key.clone(),
+            Some(&StateValue::from(vec![i as u8])),
+            i,
+        );
+    }
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -373,6 +427,7 @@ fn verify_state_value_pruner(inputs: Vec<Vec<(StateKey, Option<StateValue>)>>) {
         prune_window: 0,
         batch_size: 1,
         user_pruning_window_offset: 0,
+        dry_run: false,
     });
     for batch in inputs {
         update_store(store, batch.clone().into_iter(), version);
@@ -7,7 +7,7 @@ use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_db_indexer_schemas::metadata::StateSnapshotProgress;
 use aptos_infallible::Mutex;
 use aptos_jellyfish_merkle::{restore::JellyfishMerkleRestore, Key, TreeReader, TreeWriter, Value};
-use aptos_storage_interface::{Result, StateSnapshotReceiver};
+use aptos_storage_interface::{AptosDbError, Result, StateSnapshotReceiver};
 use aptos_types::{
     proof::SparseMerkleRangeProof, state_store::state_storage_usage::StateStorageUsage,
     transaction::Version,
@@ -15,7 +15,17 @@ use aptos_types::{
 use once_cell::sync::Lazy;
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, hash::Hash, str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    str::FromStr,
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
 
 #[cfg(test)]
 mod restore_test;
@@ -74,14 +84,78 @@ impl FromStr for StateSnapshotRestoreMode {
     }
 }
 
+/// Progress of a state snapshot restore, reported after each chunk is restored.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StateRestoreProgress {
+    pub version: Version,
+    /// Hash of the last key restored. The keys are restored in the order of their hashes.
+    pub key_hash: HashValue,
+    pub keys_restored: usize,
+    /// Total size of the keys and values restored.
+    pub bytes_written: usize,
+}
+
+impl StateRestoreProgress {
+    pub fn new(version: Version, key_hash: HashValue, usage: StateStorageUsage) -> Self {
+        Self {
+            version,
+            key_hash,
+            keys_restored: usage.items(),
+            bytes_written: usage.bytes(),
+        }
+    }
+
+    /// Estimates the share of the snapshot restored. Since the key hashes are uniformly
+    /// distributed, it's the share of the key hash space up to the last key restored.
+    pub fn fraction_done(&self) -> f64 {
+        let hash: &[u8; HashValue::LENGTH] = self.key_hash.as_ref();
+        let prefix = u64::from_be_bytes(hash[..8].try_into().expect("Hash is 32 bytes."));
+        prefix as f64 / u64::MAX as f64
+    }
+
+    /// Estimates the total number of keys in the snapshot.
+    pub fn estimated_total_keys(&self) -> usize {
+        match self.fraction_done() {
+            f if f > 0.0 => (self.keys_restored as f64 / f) as usize,
+            _ => self.keys_restored,
+        }
+    }
+
+    /// Estimates the time left to complete the restore, extrapolating from the progress made
+    /// since `start`, which was reported `elapsed` ago. `None` if no progress was made since.
+    pub fn estimated_time_left(&self, start: &Self, elapsed: Duration) -> Option<Duration> {
+        let fraction_done = self.fraction_done();
+        let progress = fraction_done - start.fraction_done();
+        if progress <= 0.0 {
+            return None;
+        }
+        Some(elapsed.mul_f64((1.0 - fraction_done) / progress))
+    }
+}
+
+/// Called with the progress of a restore after each chunk is restored, by the thread adding the
+/// chunk, so it should return quickly.
+pub type StateRestoreProgressCallback = Arc<dyn Fn(&StateRestoreProgress) + Send + Sync>;
+
 struct StateValueRestore<K, V> {
     version: Version,
     db: Arc<dyn StateValueWriter<K, V>>,
+    progress_callback: Option<StateRestoreProgressCallback>,
 }
 
 impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
     pub fn new<D: 'static + StateValueWriter<K, V>>(db: Arc<D>, version: Version) -> Self {
-        Self { version, db }
+        Self {
+            version,
+            db,
+            progress_callback: None,
+        }
+    }
+
+    /// Reports the progress after each chunk is written, including the keys restored before the
+    /// restore was resumed.
+    pub fn set_progress_callback(&mut self, callback: StateRestoreProgressCallback) {
+        self.progress_callback = Some(callback);
     }
 
     pub fn add_chunk(&mut self, mut chunk: Vec<(K, V)>) -> Result<()> {
@@ -122,7 +196,16 @@ impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
             self.version,
             &kv_batch,
             StateSnapshotProgress::new(last_key_hash, usage),
-        )
+        )?;
+
+        if let Some(callback) = &self.progress_callback {
+            callback(&StateRestoreProgress::new(
+                self.version,
+                last_key_hash,
+                usage,
+            ));
+        }
+        Ok(())
     }
 
     pub fn finish(self) -> Result<()> {
@@ -141,10 +224,163 @@ impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
     }
 }
 
+/// Reports the progress of a `TreeOnly` restore, which doesn't go through `StateValueRestore`.
+/// The keys and bytes are counted from the chunks added since the restore was created, as they
+/// are not persisted.
+struct TreeOnlyProgress {
+    version: Version,
+    usage: Mutex<StateStorageUsage>,
+    callback: StateRestoreProgressCallback,
+}
+
+impl TreeOnlyProgress {
+    fn new(version: Version, callback: StateRestoreProgressCallback) -> Self {
+        Self {
+            version,
+            usage: Mutex::new(StateStorageUsage::zero()),
+            callback,
+        }
+    }
+
+    fn report<K: Key + CryptoHash, V: Value>(&self, chunk: &[(K, V)]) {
+        if let Some((last_key, _last_value)) = chunk.last() {
+            let mut usage = self.usage.lock();
+            for (k, v) in chunk {
+                usage.add_item(k.key_size() + v.value_size());
+            }
+            (self.callback)(&StateRestoreProgress::new(
+                self.version,
+                CryptoHash::hash(last_key),
+                *usage,
+            ));
+        }
+    }
+}
+
+/// A chunk queued for verification, along with the hashes of its values.
+struct PipelinedChunk<K, V> {
+    chunk: Vec<(K, V)>,
+    value_hashes: Vec<HashValue>,
+    proof: SparseMerkleRangeProof,
+}
+
+/// Verifies the queued chunks in one thread and writes the state values of the verified ones in
+/// another, so the proof of a chunk is verified while the previous chunk is being written. The
+/// state values of a chunk are only written once its proof is verified.
+struct RestorePipeline<K, V> {
+    chunk_sender: SyncSender<PipelinedChunk<K, V>>,
+    verifier: JoinHandle<Result<()>>,
+    writer: JoinHandle<Result<()>>,
+}
+
+impl<K: Key + CryptoHash + Hash + Eq, V: Value + 'static> RestorePipeline<K, V> {
+    fn start(
+        tree_restore: Arc<Mutex<Option<JellyfishMerkleRestore<K>>>>,
+        kv_restore: Arc<Mutex<Option<StateValueRestore<K, V>>>>,
+        restore_mode: StateSnapshotRestoreMode,
+        tree_only_progress: Option<Arc<TreeOnlyProgress>>,
+        depth: usize,
+    ) -> Self {
+        let (chunk_sender, chunk_receiver) = mpsc::sync_channel(depth);
+        let (verified_sender, verified_receiver) = mpsc::sync_channel(depth);
+        let verifier = std::thread::Builder::new()
+            .name("state_restore_verifier".to_string())
+            .spawn(move || {
+                Self::verify_chunks(
+                    tree_restore,
+                    restore_mode,
+                    tree_only_progress,
+                    chunk_receiver,
+                    verified_sender,
+                )
+            })
+            .expect("Failed to spawn state restore verifier thread.");
+        let writer = std::thread::Builder::new()
+            .name("state_restore_writer".to_string())
+            .spawn(move || Self::write_chunks(kv_restore, verified_receiver))
+            .expect("Failed to spawn state restore writer thread.");
+
+        Self {
+            chunk_sender,
+            verifier,
+            writer,
+        }
+    }
+
+    /// Stops at the first chunk that fails verification, or once the writer stops.
+    fn verify_chunks(
+        tree_restore: Arc<Mutex<Option<JellyfishMerkleRestore<K>>>>,
+        restore_mode: StateSnapshotRestoreMode,
+        tree_only_progress: Option<Arc<TreeOnlyProgress>>,
+        chunk_receiver: Receiver<PipelinedChunk<K, V>>,
+        verified_sender: SyncSender<Vec<(K, V)>>,
+    ) -> Result<()> {
+        while let Ok(PipelinedChunk {
+            chunk,
+            value_hashes,
+            proof,
+        }) = chunk_receiver.recv()
+        {
+            if restore_mode != StateSnapshotRestoreMode::KvOnly {
+                let _timer = OTHER_TIMERS_SECONDS
+                    .with_label_values(&["jmt_add_chunk"])
+                    .start_timer();
+                tree_restore.lock().as_mut().unwrap().add_chunk_impl(
+                    chunk.iter().map(|(k, _v)| k).zip(value_hashes).collect(),
+                    proof,
+                )?;
+            }
+            if let Some(progress) = &tree_only_progress {
+                progress.report(&chunk);
+            }
+            if restore_mode != StateSnapshotRestoreMode::TreeOnly
+                && verified_sender.send(chunk).is_err()
+            {
+                // The writer failed, its error is returned when joining it.
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_chunks(
+        kv_restore: Arc<Mutex<Option<StateValueRestore<K, V>>>>,
+        verified_receiver: Receiver<Vec<(K, V)>>,
+    ) -> Result<()> {
+        while let Ok(chunk) = verified_receiver.recv() {
+            let _timer = OTHER_TIMERS_SECONDS
+                .with_label_values(&["state_value_add_chunk"])
+                .start_timer();
+            kv_restore.lock().as_mut().unwrap().add_chunk(chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for the queued chunks to be processed, returning the error that stopped the pipeline
+    /// if any.
+    fn finish(self) -> Result<()> {
+        drop(self.chunk_sender);
+        let verified = self
+            .verifier
+            .join()
+            .expect("State restore verifier thread panicked.");
+        let written = self
+            .writer
+            .join()
+            .expect("State restore writer thread panicked.");
+        verified.and(written)
+    }
+}
+
 pub struct StateSnapshotRestore<K, V> {
     tree_restore: Arc<Mutex<Option<JellyfishMerkleRestore<K>>>>,
     kv_restore: Arc<Mutex<Option<StateValueRestore<K, V>>>>,
     restore_mode: StateSnapshotRestoreMode,
+    /// Number of chunks queued ahead of the verification and ahead of the writing of the
+    /// verified chunks. 0 if not pipelined.
+    pipeline_depth: usize,
+    pipeline: Mutex<Option<RestorePipeline<K, V>>>,
+    tree_only_progress: Option<Arc<TreeOnlyProgress>>,
 }
 
 impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
@@ -168,6 +404,9 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
                 version,
             )))),
             restore_mode,
+            pipeline_depth: 0,
+            pipeline: Mutex::new(None),
+            tree_only_progress: None,
         })
     }
 
@@ -189,10 +428,51 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
                 version,
             )))),
             restore_mode,
+            pipeline_depth: 0,
+            pipeline: Mutex::new(None),
+            tree_only_progress: None,
         })
     }
 
+    /// Pipelines the restore: `add_chunk()` returns once the chunk is queued, the proofs of the
+    /// queued chunks are verified in the background, and the state values of the verified chunks
+    /// are written while the following chunks are being verified. Up to `depth` chunks are queued
+    /// ahead of each of the two stages, 0 disables the pipeline. An error stops the pipeline and
+    /// is returned by the next call, since `previous_key_hash()`, `wait_for_async_commit()` and
+    /// `finish()` wait for the queued chunks first.
+    pub fn with_pipeline_depth(mut self, depth: usize) -> Self {
+        self.pipeline_depth = depth;
+        self
+    }
+
+    /// Reports the progress of the restore after each chunk is restored. In `TreeOnly` mode, the
+    /// keys and bytes restored before the restore was resumed are not counted.
+    pub fn with_progress_callback(mut self, callback: StateRestoreProgressCallback) -> Self {
+        match self.restore_mode {
+            StateSnapshotRestoreMode::TreeOnly => {
+                let version = self.kv_restore.lock().as_ref().unwrap().version;
+                self.tree_only_progress = Some(Arc::new(TreeOnlyProgress::new(version, callback)));
+            },
+            _ => self
+                .kv_restore
+                .lock()
+                .as_mut()
+                .unwrap()
+                .set_progress_callback(callback),
+        }
+        self
+    }
+
+    /// Waits for the chunks queued in the pipeline, if any, to be verified and written.
+    fn drain_pipeline(&self) -> Result<()> {
+        match self.pipeline.lock().take() {
+            Some(pipeline) => pipeline.finish(),
+            None => Ok(()),
+        }
+    }
+
     pub fn previous_key_hash(&self) -> Result<Option<HashValue>> {
+        self.drain_pipeline()?;
         let hash_opt = match (
             self.kv_restore
                 .lock()
@@ -213,6 +493,7 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
     }
 
     pub fn wait_for_async_commit(&self) -> Result<()> {
+        self.drain_pipeline()?;
         self.tree_restore
             .lock()
             .as_mut()
@@ -222,10 +503,50 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
     }
 }
 
-impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
+impl<K: Key + CryptoHash + Hash + Eq, V: Value + 'static> StateSnapshotRestore<K, V> {
+    fn add_chunk_pipelined(&self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()> {
+        // Hashed here, so the values of a chunk are hashed while the previous chunk is verified.
+        let value_hashes = match self.restore_mode {
+            StateSnapshotRestoreMode::KvOnly => vec![],
+            _ => chunk.iter().map(|(_k, v)| v.hash()).collect(),
+        };
+
+        let mut pipeline = self.pipeline.lock();
+        let sent = pipeline
+            .get_or_insert_with(|| {
+                RestorePipeline::start(
+                    self.tree_restore.clone(),
+                    self.kv_restore.clone(),
+                    self.restore_mode,
+                    self.tree_only_progress.clone(),
+                    self.pipeline_depth,
+                )
+            })
+            .chunk_sender
+            .send(PipelinedChunk {
+                chunk,
+                value_hashes,
+                proof,
+            });
+        if sent.is_err() {
+            // The pipeline stopped on an error.
+            pipeline.take().expect("Pipeline must exist.").finish()?;
+            return Err(AptosDbError::Other(
+                "State restore pipeline stopped unexpectedly.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<K: Key + CryptoHash + Hash + Eq, V: Value + 'static> StateSnapshotReceiver<K, V>
     for StateSnapshotRestore<K, V>
 {
     fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()> {
+        if self.pipeline_depth > 0 {
+            return self.add_chunk_pipelined(chunk, proof);
+        }
+
         let kv_fn = || {
             let _timer = OTHER_TIMERS_SECONDS
                 .with_label_values(&["state_value_add_chunk"])
@@ -249,7 +570,12 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
         };
         match self.restore_mode {
             StateSnapshotRestoreMode::KvOnly => kv_fn()?,
-            StateSnapshotRestoreMode::TreeOnly => tree_fn()?,
+            StateSnapshotRestoreMode::TreeOnly => {
+                tree_fn()?;
+                if let Some(progress) = &self.tree_only_progress {
+                    progress.report(&chunk);
+                }
+            },
             StateSnapshotRestoreMode::Default => {
                 // We run kv_fn with TreeOnly to restore the usage of DB
                 let (r1, r2) = IO_POOL.join(kv_fn, tree_fn);
@@ -262,6 +588,7 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
     }
 
     fn finish(self) -> Result<()> {
+        self.drain_pipeline()?;
         match self.restore_mode {
             StateSnapshotRestoreMode::KvOnly => self.kv_restore.lock().take().unwrap().finish()?,
             StateSnapshotRestoreMode::TreeOnly => {
@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::state_restore::{
-    StateSnapshotRestore, StateSnapshotRestoreMode, StateValueBatch, StateValueWriter,
+    StateRestoreProgress, StateSnapshotRestore, StateSnapshotRestoreMode, StateValueBatch,
+    StateValueWriter,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_db_indexer_schemas::metadata::StateSnapshotProgress;
-use aptos_infallible::RwLock;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_jellyfish_merkle::{
     mock_tree_store::MockTreeStore,
     node_type::{LeafNode, Node, NodeKey},
@@ -19,6 +20,7 @@ use proptest::{collection::btree_map, prelude::*};
 use std::{
     collections::{BTreeMap, HashMap},
     sync::Arc,
+    time::Duration,
 };
 
 #[derive(Default)]
@@ -164,7 +166,71 @@ proptest! {
     ) {
         let restore_db = Arc::new(MockSnapshotStore::default());
         // For this test, restore everything without interruption.
-        restore_without_interruption(&btree, target_version, &restore_db, true);
+        restore_without_interruption(&btree, target_version, &restore_db, true, 0);
+    }
+
+    #[test]
+    fn test_restore_pipelined(
+        btree in arb_btree_map(1),
+        target_version in 0u64..2000,
+    ) {
+        let restore_db = Arc::new(MockSnapshotStore::default());
+        restore_without_interruption(&btree, target_version, &restore_db, true, 2 /* pipeline_depth */);
+    }
+
+    #[test]
+    fn test_restore_pipelined_with_bad_proof(
+        btree in arb_btree_map(1),
+    ) {
+        let (db, version) = init_mock_store(&btree.clone().into_values().collect());
+        let tree = JellyfishMerkleTree::new(&db);
+
+        let restore_db = Arc::new(MockSnapshotStore::default());
+        let mut restore = StateSnapshotRestore::new(&restore_db, &restore_db, version, HashValue::random(), true /* async_commit */, StateSnapshotRestoreMode::Default)
+            .unwrap()
+            .with_pipeline_depth(2);
+        let res = btree
+            .iter()
+            .try_for_each(|(hashed_key, (k, v))| {
+                let proof = tree.get_range_proof(*hashed_key, version).unwrap();
+                restore.add_chunk(vec![(k.clone(), v.clone())], proof)
+            })
+            .and_then(|()| restore.finish());
+
+        prop_assert!(res.is_err());
+        // Values are only written once their chunk is verified.
+        prop_assert!(restore_db.kv_store.read().is_empty());
+    }
+
+    #[test]
+    fn test_restore_progress(
+        btree in arb_btree_map(1),
+    ) {
+        let (db, version) = init_mock_store(&btree.clone().into_values().collect());
+        let tree = JellyfishMerkleTree::new(&db);
+        let expected_root_hash = tree.get_root_hash(version).unwrap();
+
+        let restore_db = Arc::new(MockSnapshotStore::default());
+        let reports = Arc::new(Mutex::new(Vec::<StateRestoreProgress>::new()));
+        let reports_clone = reports.clone();
+        let mut restore = StateSnapshotRestore::new(&restore_db, &restore_db, version, expected_root_hash, true /* async_commit */, StateSnapshotRestoreMode::Default)
+            .unwrap()
+            .with_progress_callback(Arc::new(move |progress: &StateRestoreProgress| reports_clone.lock().push(*progress)));
+        for (hashed_key, (k, v)) in &btree {
+            let proof = tree.get_range_proof(*hashed_key, version).unwrap();
+            restore.add_chunk(vec![(k.clone(), v.clone())], proof).unwrap();
+        }
+        restore.finish().unwrap();
+
+        let reports = reports.lock();
+        prop_assert_eq!(reports.len(), btree.len());
+        for (n, (report, hashed_key)) in reports.iter().zip(btree.keys()).enumerate() {
+            prop_assert_eq!(report.key_hash, *hashed_key);
+            prop_assert_eq!(report.keys_restored, n + 1);
+        }
+        let last = reports.last().unwrap();
+        prop_assert_eq!(last.bytes_written, restore_db.get_stored_usage(version).bytes());
+        prop_assert!(reports.windows(2).all(|w| w[0].fraction_done() <= w[1].fraction_done()));
     }
 
     #[test]
@@ -222,12 +288,40 @@ proptest! {
         target_version in 0u64..2000,
     ) {
         let restore_db = Arc::new(MockSnapshotStore::new(true /* allow_overwrite */));
-        restore_without_interruption(&btree, target_version, &restore_db, true);
+        restore_without_interruption(&btree, target_version, &restore_db, true, 0);
         // overwrite, an entirely different tree
-        restore_without_interruption(&btree, target_version, &restore_db, false);
+        restore_without_interruption(&btree, target_version, &restore_db, false, 0);
     }
 }
 
+#[test]
+fn test_restore_progress_estimates() {
+    let progress_at = |first_byte: u8, keys_restored: usize| {
+        let mut key_hash = [0u8; HashValue::LENGTH];
+        key_hash[0] = first_byte;
+        StateRestoreProgress {
+            version: 0,
+            key_hash: HashValue::new(key_hash),
+            keys_restored,
+            bytes_written: 0,
+        }
+    };
+    let start = progress_at(0x40, 100);
+    let now = progress_at(0x80, 200);
+
+    assert!((now.fraction_done() - 0.5).abs() < 1e-6);
+    assert_eq!(now.estimated_total_keys(), 400);
+    // A quarter of the key space in 10 seconds, half of it left.
+    let time_left = now
+        .estimated_time_left(&start, Duration::from_secs(10))
+        .unwrap();
+    assert!((time_left.as_secs_f64() - 20.0).abs() < 1e-3);
+    assert_eq!(
+        start.estimated_time_left(&start, Duration::from_secs(10)),
+        None
+    );
+}
+
 fn assert_success<V>(
     db: &MockSnapshotStore<V, V>,
     expected_root_hash: HashValue,
@@ -261,6 +355,7 @@ fn restore_without_interruption<V>(
     target_version: Version,
     target_db: &Arc<MockSnapshotStore<V, V>>,
     try_resume: bool,
+    pipeline_depth: usize,
 ) where
     V: TestKey + TestValue,
 {
@@ -292,7 +387,8 @@ fn restore_without_interruption<V>(
             StateSnapshotRestoreMode::Default,
         )
         .unwrap()
-    };
+    }
+    .with_pipeline_depth(pipeline_depth);
     for (hashed_key, (k, v)) in btree {
         let proof = tree.get_range_proof(*hashed_key, source_version).unwrap();
         restore
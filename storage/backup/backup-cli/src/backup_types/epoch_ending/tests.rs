@@ -13,7 +13,7 @@ use crate::{
     utils::{
         backup_service_client::BackupServiceClient, test_utils::tmp_db_with_random_content,
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
-        RocksdbOpt, TrustedWaypointOpt,
+        RocksdbOpt, StateSnapshotPipelineDepthOpt, TrustedWaypointOpt,
     },
 };
 use aptos_backup_service::start_backup_service;
@@ -88,6 +88,7 @@ fn end_to_end() {
                 rocksdb_opt: RocksdbOpt::default(),
                 concurrent_downloads: ConcurrentDownloadsOpt::default(),
                 replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt::default(),
                 enable_state_indices: false,
             }
             .try_into()
@@ -222,6 +223,7 @@ async fn test_trusted_waypoints_impl(
             rocksdb_opt: RocksdbOpt::default(),
             concurrent_downloads: ConcurrentDownloadsOpt::default(),
             replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+            state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt::default(),
             enable_state_indices: false,
         }
         .try_into()
@@ -244,6 +246,7 @@ async fn test_trusted_waypoints_impl(
             rocksdb_opt: RocksdbOpt::default(),
             concurrent_downloads: ConcurrentDownloadsOpt::default(),
             replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+            state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt::default(),
             enable_state_indices: false,
         }
         .try_into()
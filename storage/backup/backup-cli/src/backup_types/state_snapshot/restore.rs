@@ -23,7 +23,9 @@ use crate::{
     },
 };
 use anyhow::{anyhow, ensure, Result};
-use aptos_db::state_restore::StateSnapshotRestoreMode;
+use aptos_db::state_restore::{
+    StateRestoreProgress, StateRestoreProgressCallback, StateSnapshotRestoreMode,
+};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_storage_interface::StateSnapshotReceiver;
@@ -71,6 +73,7 @@ pub struct StateSnapshotRestoreController {
     concurrent_downloads: usize,
     validate_modules: bool,
     restore_mode: StateSnapshotRestoreMode,
+    pipeline_depth: usize,
 }
 
 impl StateSnapshotRestoreController {
@@ -90,6 +93,7 @@ impl StateSnapshotRestoreController {
             concurrent_downloads: global_opt.concurrent_downloads,
             validate_modules: opt.validate_modules,
             restore_mode: opt.restore_mode,
+            pipeline_depth: global_opt.state_snapshot_pipeline_depth,
         }
     }
 
@@ -138,11 +142,12 @@ impl StateSnapshotRestoreController {
             epoch_history.verify_ledger_info(&li)?;
         }
 
-        let receiver = Arc::new(Mutex::new(Some(self.run_mode.get_state_restore_receiver(
-            self.version,
-            manifest.root_hash,
-            self.restore_mode,
-        )?)));
+        let receiver = Arc::new(Mutex::new(Some(
+            self.run_mode
+                .get_state_restore_receiver(self.version, manifest.root_hash, self.restore_mode)?
+                .with_pipeline_depth(self.pipeline_depth)
+                .with_progress_callback(Self::progress_logger()),
+        )));
 
         let (ver_gauge, tgt_leaf_idx, leaf_idx) = if self.run_mode.is_verify() {
             (
@@ -232,6 +237,25 @@ impl StateSnapshotRestoreController {
         Ok(())
     }
 
+    /// Logs the progress of the restore with an ETA extrapolated from the progress made since the
+    /// first chunk restored.
+    fn progress_logger() -> StateRestoreProgressCallback {
+        let start: Mutex<Option<(Instant, StateRestoreProgress)>> = Mutex::new(None);
+        Arc::new(move |progress: &StateRestoreProgress| {
+            let now = Instant::now();
+            let (start_time, start_progress) = *start.lock().get_or_insert((now, *progress));
+            let eta = progress.estimated_time_left(&start_progress, now - start_time);
+            info!(
+                keys_restored = progress.keys_restored,
+                estimated_total_keys = progress.estimated_total_keys(),
+                bytes_written = progress.bytes_written,
+                percent_done = format!("{:.2}", progress.fraction_done() * 100.0),
+                eta_seconds = eta.map(|eta| eta.as_secs()),
+                "State snapshot restore progress.",
+            );
+        })
+    }
+
     fn validate_modules(blob: &[(StateKey, StateValue)]) {
         // TODO: Instead of using default features, fetch them from the the state.
         let features = Features::default();
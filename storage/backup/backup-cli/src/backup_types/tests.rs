@@ -17,7 +17,7 @@ use crate::{
     utils::{
         backup_service_client::BackupServiceClient, test_utils::start_local_backup_service,
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, GlobalRestoreOptions,
-        ReplayConcurrencyLevelOpt, RocksdbOpt, TrustedWaypointOpt,
+        ReplayConcurrencyLevelOpt, RocksdbOpt, StateSnapshotPipelineDepthOpt, TrustedWaypointOpt,
     },
 };
 use aptos_db::{state_restore::StateSnapshotRestoreMode, AptosDB};
@@ -125,6 +125,7 @@ fn test_end_to_end_impl(d: TestData) {
         rocksdb_opt: RocksdbOpt::default(),
         concurrent_downloads: ConcurrentDownloadsOpt::default(),
         replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+        state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt::default(),
         enable_state_indices: false,
     }
     .try_into()
@@ -12,7 +12,7 @@ use crate::{
         backup_service_client::BackupServiceClient,
         test_utils::{start_local_backup_service, tmp_db_with_random_content},
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
-        RocksdbOpt, TrustedWaypointOpt,
+        RocksdbOpt, StateSnapshotPipelineDepthOpt, TrustedWaypointOpt,
     },
 };
 use aptos_db::AptosDB;
@@ -107,6 +107,7 @@ fn end_to_end() {
                 rocksdb_opt: RocksdbOpt::default(),
                 concurrent_downloads: ConcurrentDownloadsOpt::default(),
                 replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt::default(),
                 enable_state_indices: false,
             }
             .try_into()
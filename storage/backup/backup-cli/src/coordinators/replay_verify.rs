@@ -10,7 +10,9 @@ use crate::{
     metadata,
     metadata::cache::MetadataCacheOpt,
     storage::BackupStorage,
-    utils::{GlobalRestoreOptions, RestoreRunMode, TrustedWaypointOpt},
+    utils::{
+        GlobalRestoreOptions, RestoreRunMode, StateSnapshotPipelineDepthOpt, TrustedWaypointOpt,
+    },
 };
 use anyhow::Result;
 use aptos_db::backup::restore_handler::RestoreHandler;
@@ -171,6 +173,7 @@ impl ReplayVerifyCoordinator {
             run_mode,
             concurrent_downloads: self.concurrent_downloads,
             replay_concurrency_level: 0, // won't replay, doesn't matter
+            state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt::default().get(),
         };
 
         if !skip_snapshot {
@@ -159,6 +159,9 @@ pub struct GlobalRestoreOpt {
     #[clap(flatten)]
     pub replay_concurrency_level: ReplayConcurrencyLevelOpt,
 
+    #[clap(flatten)]
+    pub state_snapshot_pipeline_depth: StateSnapshotPipelineDepthOpt,
+
     #[clap(long, help = "Restore the state indices when restore the snapshot")]
     pub enable_state_indices: bool,
 }
@@ -282,6 +285,7 @@ pub struct GlobalRestoreOptions {
     pub run_mode: Arc<RestoreRunMode>,
     pub concurrent_downloads: usize,
     pub replay_concurrency_level: usize,
+    pub state_snapshot_pipeline_depth: usize,
 }
 
 impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
@@ -291,6 +295,7 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
         let target_version = opt.target_version.unwrap_or(Version::max_value());
         let concurrent_downloads = opt.concurrent_downloads.get();
         let replay_concurrency_level = opt.replay_concurrency_level.get();
+        let state_snapshot_pipeline_depth = opt.state_snapshot_pipeline_depth.get();
         let run_mode = if let Some(db_dir) = &opt.db_dir {
             // for restore, we can always start state store with empty buffered_state since we will restore
             // TODO(grao): Support path override here.
@@ -321,6 +326,7 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
             run_mode: Arc::new(run_mode),
             concurrent_downloads,
             replay_concurrency_level,
+            state_snapshot_pipeline_depth,
         })
     }
 }
@@ -405,6 +411,31 @@ impl ReplayConcurrencyLevelOpt {
     }
 }
 
+#[derive(Clone, Copy, Default, Parser)]
+pub struct StateSnapshotPipelineDepthOpt {
+    #[clap(
+        long,
+        help = "Number of state snapshot chunks queued ahead of the proof verification, and of the \
+        verified chunks queued ahead of the DB writes, so a chunk is verified while the previous \
+        one is being written. 0 verifies and writes each chunk before taking the next one. \
+        [Defaults to 4]"
+    )]
+    state_snapshot_pipeline_depth: Option<usize>,
+}
+
+impl StateSnapshotPipelineDepthOpt {
+    const DEFAULT: usize = 4;
+
+    pub fn get(&self) -> usize {
+        let ret = self.state_snapshot_pipeline_depth.unwrap_or(Self::DEFAULT);
+        info!(
+            pipeline_depth = ret,
+            "Determined pipeline depth for state snapshot restore."
+        );
+        ret
+    }
+}
+
 pub(crate) fn should_cut_chunk(chunk: &[u8], record: &[u8], max_chunk_size: usize) -> bool {
     !chunk.is_empty() && chunk.len() + record.len() + size_of::<u32>() > max_chunk_size
 }
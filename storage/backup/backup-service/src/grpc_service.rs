@@ -0,0 +1,294 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::hash::CryptoHash;
+use aptos_db::{backup::backup_handler::BackupHandler, metrics::BACKUP_TIMER};
+use aptos_logger::prelude::*;
+use aptos_metrics_core::{register_int_counter_vec, IntCounterHelper, IntCounterVec, TimerHelper};
+use aptos_protos::backup::v1::{
+    backup_service_server::BackupService, BackupChunk, GetDbStateRequest, GetDbStateResponse,
+    GetStateRootProofRequest, GetStateRootProofResponse, StreamEpochEndingLedgerInfosRequest,
+    StreamStateSnapshotRequest, StreamTransactionsRequest,
+};
+use aptos_storage_interface::{AptosDbError, Result as DbResult};
+use aptos_types::transaction::Version;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+static THROUGHPUT_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_backup_grpc_service_sent_bytes",
+        "gRPC backup service throughput in bytes.",
+        &["endpoint"]
+    )
+    .unwrap()
+});
+
+static STATE_SNAPSHOT: &str = "state_snapshot";
+static TRANSACTIONS: &str = "transactions";
+static EPOCH_ENDING_LEDGER_INFOS: &str = "epoch_ending_ledger_infos";
+
+type ChunkStream = ReceiverStream<Result<BackupChunk, Status>>;
+
+/// Serves the records of the HTTP backup service in self-contained chunks over gRPC. The records
+/// are read by blocking tasks that are throttled by the stream, so a slow sink doesn't make the
+/// node buffer the whole backup.
+pub(crate) struct BackupServiceImpl {
+    backup_handler: BackupHandler,
+}
+
+impl BackupServiceImpl {
+    const DEFAULT_CHUNK_SIZE: usize = 1000;
+    /// Chunks are closed early once they get this large, so they are likely to stay under the
+    /// default 4MB message size limit of the clients.
+    const MAX_CHUNK_BYTES: usize = 1024 * 1024;
+    const MAX_PENDING_CHUNKS: usize = 4;
+
+    pub fn new(backup_handler: BackupHandler) -> Self {
+        Self { backup_handler }
+    }
+
+    fn chunk_size(requested: u64) -> usize {
+        match requested {
+            0 => Self::DEFAULT_CHUNK_SIZE,
+            n => n as usize,
+        }
+    }
+
+    async fn spawn_blocking<T, F>(&self, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(BackupHandler) -> DbResult<T> + Send + 'static,
+    {
+        let bh = self.backup_handler.clone();
+        tokio::task::spawn_blocking(move || f(bh))
+            .await
+            .map_err(|e| Status::internal(format!("Backup task failed. {e}")))?
+            .map_err(to_status)
+    }
+
+    /// Runs `f` in a blocking task feeding the returned stream. An error raised by `f` is sent as
+    /// the last item of the stream.
+    fn stream_chunks<F>(&self, endpoint: &'static str, f: F) -> ChunkStream
+    where
+        F: FnOnce(BackupHandler, &mut ChunkSender) -> DbResult<()> + Send + 'static,
+    {
+        let (chunk_tx, chunk_rx) = mpsc::channel(Self::MAX_PENDING_CHUNKS);
+        let bh = self.backup_handler.clone();
+
+        // spawn and forget, error propagates through the stream
+        let _join_handle = tokio::task::spawn_blocking(move || {
+            let _timer = BACKUP_TIMER
+                .timer_with(&[&format!("backup_grpc_service_chunk_sender_{}", endpoint)]);
+            let mut sender = ChunkSender::new(chunk_tx, endpoint);
+            if let Err(e) = f(bh, &mut sender) {
+                warn!(endpoint = endpoint, "Backup stream aborted: {:#}", e);
+                // ignore error, the client is probably gone
+                let _res = sender.chunk_tx.blocking_send(Err(to_status(e)));
+            }
+        });
+
+        ReceiverStream::new(chunk_rx)
+    }
+}
+
+#[tonic::async_trait]
+impl BackupService for BackupServiceImpl {
+    type StreamEpochEndingLedgerInfosStream = ChunkStream;
+    type StreamStateSnapshotStream = ChunkStream;
+    type StreamTransactionsStream = ChunkStream;
+
+    async fn get_db_state(
+        &self,
+        _request: Request<GetDbStateRequest>,
+    ) -> Result<Response<GetDbStateResponse>, Status> {
+        let db_state = self
+            .spawn_blocking(|bh| Ok(bcs::to_bytes(&bh.get_db_state()?)?))
+            .await?;
+        Ok(Response::new(GetDbStateResponse { db_state }))
+    }
+
+    async fn get_state_root_proof(
+        &self,
+        request: Request<GetStateRootProofRequest>,
+    ) -> Result<Response<GetStateRootProofResponse>, Status> {
+        let version = request.into_inner().version;
+        let proof = self
+            .spawn_blocking(move |bh| Ok(bcs::to_bytes(&bh.get_state_root_proof(version)?)?))
+            .await?;
+        Ok(Response::new(GetStateRootProofResponse { proof }))
+    }
+
+    async fn stream_state_snapshot(
+        &self,
+        request: Request<StreamStateSnapshotRequest>,
+    ) -> Result<Response<Self::StreamStateSnapshotStream>, Status> {
+        let StreamStateSnapshotRequest {
+            version,
+            start_idx,
+            chunk_size,
+        } = request.into_inner();
+        let chunk_size = Self::chunk_size(chunk_size);
+
+        Ok(Response::new(self.stream_chunks(
+            STATE_SNAPSHOT,
+            move |bh, sender| {
+                let mut chunk_first_idx = start_idx;
+                let mut last_key = None;
+                for record_res in bh.get_state_item_iter(version, start_idx as usize, usize::MAX)? {
+                    let (key, value) = record_res?;
+                    sender.add_record(&(&key, &value))?;
+                    last_key = Some(key);
+                    if sender.is_full(chunk_size) {
+                        let rightmost_key = last_key.take().expect("Just set.").hash();
+                        let proof = bh.get_account_state_range_proof(rightmost_key, version)?;
+                        chunk_first_idx = sender.send_chunk(chunk_first_idx, &proof)?;
+                    }
+                }
+                if let Some(key) = last_key {
+                    let proof = bh.get_account_state_range_proof(key.hash(), version)?;
+                    sender.send_chunk(chunk_first_idx, &proof)?;
+                }
+                Ok(())
+            },
+        )))
+    }
+
+    async fn stream_transactions(
+        &self,
+        request: Request<StreamTransactionsRequest>,
+    ) -> Result<Response<Self::StreamTransactionsStream>, Status> {
+        let StreamTransactionsRequest {
+            start_version,
+            num_transactions,
+            chunk_size,
+        } = request.into_inner();
+        let chunk_size = Self::chunk_size(chunk_size);
+
+        Ok(Response::new(self.stream_chunks(
+            TRANSACTIONS,
+            move |bh, sender| {
+                let mut chunk_first_version = start_version;
+                for record_res in
+                    bh.get_transaction_iter(start_version, num_transactions as usize)?
+                {
+                    sender.add_record(&record_res?)?;
+                    if sender.is_full(chunk_size) {
+                        chunk_first_version =
+                            send_transaction_chunk(&bh, sender, chunk_first_version)?;
+                    }
+                }
+                if sender.num_records > 0 {
+                    send_transaction_chunk(&bh, sender, chunk_first_version)?;
+                }
+                Ok(())
+            },
+        )))
+    }
+
+    async fn stream_epoch_ending_ledger_infos(
+        &self,
+        request: Request<StreamEpochEndingLedgerInfosRequest>,
+    ) -> Result<Response<Self::StreamEpochEndingLedgerInfosStream>, Status> {
+        let StreamEpochEndingLedgerInfosRequest {
+            start_epoch,
+            end_epoch,
+            chunk_size,
+        } = request.into_inner();
+        let chunk_size = Self::chunk_size(chunk_size);
+
+        Ok(Response::new(self.stream_chunks(
+            EPOCH_ENDING_LEDGER_INFOS,
+            move |bh, sender| {
+                let mut chunk_first_epoch = start_epoch;
+                for record_res in bh.get_epoch_ending_ledger_info_iter(start_epoch, end_epoch)? {
+                    sender.add_record(&record_res?)?;
+                    if sender.is_full(chunk_size) {
+                        chunk_first_epoch = sender.send_chunk(chunk_first_epoch, &())?;
+                    }
+                }
+                if sender.num_records > 0 {
+                    sender.send_chunk(chunk_first_epoch, &())?;
+                }
+                Ok(())
+            },
+        )))
+    }
+}
+
+/// Sends the pending transactions along with the proof of their range, returning the version of
+/// the next chunk.
+fn send_transaction_chunk(
+    bh: &BackupHandler,
+    sender: &mut ChunkSender,
+    first_version: Version,
+) -> DbResult<Version> {
+    let last_version = first_version + sender.num_records - 1;
+    let proof = bh.get_transaction_range_proof(first_version, last_version)?;
+    sender.send_chunk(first_version, &proof)
+}
+
+fn to_status(e: AptosDbError) -> Status {
+    match e {
+        AptosDbError::NotFound(msg) => Status::not_found(msg),
+        e => Status::internal(format!("{e:#}")),
+    }
+}
+
+struct ChunkSender {
+    /// Records of the pending chunk, each prefixed by its size.
+    records: Vec<u8>,
+    num_records: u64,
+    chunk_tx: mpsc::Sender<Result<BackupChunk, Status>>,
+    endpoint: &'static str,
+}
+
+impl ChunkSender {
+    fn new(chunk_tx: mpsc::Sender<Result<BackupChunk, Status>>, endpoint: &'static str) -> Self {
+        Self {
+            records: Vec::new(),
+            num_records: 0,
+            chunk_tx,
+            endpoint,
+        }
+    }
+
+    fn add_record<Record: Serialize>(&mut self, record: &Record) -> DbResult<()> {
+        let record_bytes = bcs::to_bytes(record)?;
+        self.records
+            .extend_from_slice(&(record_bytes.len() as u32).to_be_bytes());
+        self.records.extend(record_bytes);
+        self.num_records += 1;
+        Ok(())
+    }
+
+    fn is_full(&self, chunk_size: usize) -> bool {
+        self.num_records as usize >= chunk_size
+            || self.records.len() >= BackupServiceImpl::MAX_CHUNK_BYTES
+    }
+
+    /// Sends the pending records as a chunk, returning the index of the first record of the next
+    /// chunk.
+    fn send_chunk<Proof: Serialize>(&mut self, first_index: u64, proof: &Proof) -> DbResult<u64> {
+        let chunk = BackupChunk {
+            first_index,
+            num_records: self.num_records,
+            records: std::mem::take(&mut self.records),
+            proof: bcs::to_bytes(proof)?,
+        };
+        self.num_records = 0;
+        THROUGHPUT_COUNTER.inc_with_by(
+            &[self.endpoint],
+            (chunk.records.len() + chunk.proof.len()) as u64,
+        );
+
+        let next_index = first_index + chunk.num_records;
+        self.chunk_tx
+            .blocking_send(Ok(chunk))
+            .map_err(|e| AptosDbError::Other(format!("Failed to send to response stream. {e}")))?;
+        Ok(next_index)
+    }
+}
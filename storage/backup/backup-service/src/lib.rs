@@ -2,13 +2,16 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod grpc_service;
 mod handlers;
 
-use crate::handlers::get_routes;
+use crate::{grpc_service::BackupServiceImpl, handlers::get_routes};
 use aptos_db::AptosDB;
 use aptos_logger::prelude::*;
+use aptos_protos::backup::v1::backup_service_server::BackupServiceServer;
 use std::{net::SocketAddr, sync::Arc};
 use tokio::runtime::Runtime;
+use tonic::transport::{server::TcpIncoming, Server};
 
 pub fn start_backup_service(address: SocketAddr, db: Arc<AptosDB>) -> Runtime {
     let backup_handler = db.get_backup_handler();
@@ -30,11 +33,36 @@ pub fn start_backup_service(address: SocketAddr, db: Arc<AptosDB>) -> Runtime {
     runtime
 }
 
+/// Spawns the gRPC backup service on the `runtime` of the HTTP backup service, so sinks can stream
+/// backups out of the node while it keeps committing.
+pub fn start_grpc_backup_service(address: SocketAddr, db: Arc<AptosDB>, runtime: &Runtime) {
+    let service = BackupServiceServer::new(BackupServiceImpl::new(db.get_backup_handler()));
+
+    // Bind before spawning the server task, for the same reason as in `start_backup_service()`.
+    let _guard = runtime.enter();
+    let incoming = TcpIncoming::new(address, true /* nodelay */, None /* keepalive */)
+        .unwrap_or_else(|e| panic!("Failed to bind gRPC backup service to {}: {}", address, e));
+    runtime.spawn(async move {
+        if let Err(e) = Server::builder()
+            .add_service(service)
+            .serve_with_incoming(incoming)
+            .await
+        {
+            error!("gRPC backup service stopped: {}", e);
+        }
+    });
+    info!("gRPC backup service spawned.");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use aptos_config::utils::get_available_port;
     use aptos_crypto::hash::HashValue;
+    use aptos_db::backup::backup_handler::DbState;
+    use aptos_protos::backup::v1::{
+        backup_service_client::BackupServiceClient, GetDbStateRequest, StreamStateSnapshotRequest,
+    };
     use aptos_temppath::TempPath;
     use reqwest::blocking::get;
     use std::net::{IpAddr, Ipv4Addr};
@@ -90,4 +118,43 @@ mod tests {
         let res = get(format!("http://127.0.0.1:{}/state_snapshot/1", port));
         assert!(res.is_err() || res.unwrap().bytes().is_err());
     }
+
+    #[test]
+    fn grpc_service() {
+        let tmpdir = TempPath::new();
+        let db = Arc::new(AptosDB::new_for_test(&tmpdir));
+        let http_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), get_available_port());
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), get_available_port());
+        let rt = start_backup_service(http_address, db.clone());
+        start_grpc_backup_service(address, db, &rt);
+
+        rt.block_on(async {
+            let mut client = BackupServiceClient::connect(format!("http://{}", address))
+                .await
+                .unwrap();
+
+            // Non-bootstrapped DB.
+            let db_state = client
+                .get_db_state(GetDbStateRequest {})
+                .await
+                .unwrap()
+                .into_inner()
+                .db_state;
+            assert!(bcs::from_bytes::<Option<DbState>>(&db_state)
+                .unwrap()
+                .is_none());
+
+            // The stream starts, and the error raised by the request handler ends it.
+            let mut stream = client
+                .stream_state_snapshot(StreamStateSnapshotRequest {
+                    version: 1,
+                    start_idx: 0,
+                    chunk_size: 0,
+                })
+                .await
+                .unwrap()
+                .into_inner();
+            assert!(stream.message().await.is_err());
+        });
+    }
 }
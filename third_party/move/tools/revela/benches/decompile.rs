@@ -0,0 +1,50 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use move_binary_format::{binary_views::BinaryIndexedView, CompiledModule};
+use revela::decompiler::Decompiler;
+
+/// The framework modules bundled with the tests, the largest inputs at hand
+fn framework_modules() -> Vec<CompiledModule> {
+    let dependencies = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("bytecode")
+        .join("dependencies");
+    let mut modules = Vec::new();
+    for package in ["MoveStdlib", "AptosStdlib", "AptosFramework"] {
+        let mut paths = std::fs::read_dir(dependencies.join(package))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map_or(false, |ext| ext == "mv"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            let bytes = std::fs::read(&path).unwrap();
+            modules.push(CompiledModule::deserialize(&bytes).unwrap());
+        }
+    }
+    modules
+}
+
+fn decompile_framework(c: &mut Criterion) {
+    let modules = framework_modules();
+    c.bench_function("decompile_framework", |b| {
+        b.iter(|| {
+            for module in &modules {
+                let mut decompiler =
+                    Decompiler::new(vec![BinaryIndexedView::Module(module)], Default::default());
+                decompiler.decompile().unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(
+    name = decompile_benches;
+    config = Criterion::default().sample_size(10);
+    targets = decompile_framework
+);
+
+criterion_main!(decompile_benches);
@@ -0,0 +1,100 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recompute the `acquires` clause of module functions from their bytecode.
+//!
+//! A function acquires a resource of its own module when it moves it from or borrows it
+//! from global storage, either directly or through a call to another function of the
+//! same module.
+
+use std::collections::{BTreeSet, HashMap};
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CompiledModule, FunctionDefinitionIndex, StructDefinitionIndex},
+};
+
+/// Acquired resources of every function definition, `None` for native functions
+pub(crate) fn infer_acquires(
+    module: &CompiledModule,
+) -> Vec<Option<BTreeSet<StructDefinitionIndex>>> {
+    let def_of_handle = module
+        .function_defs()
+        .iter()
+        .enumerate()
+        .map(|(idx, def)| (def.function, idx))
+        .collect::<HashMap<_, _>>();
+
+    let mut direct = Vec::new();
+    let mut callees = Vec::new();
+    for def in module.function_defs() {
+        let Some(code) = &def.code else {
+            direct.push(None);
+            callees.push(Vec::new());
+            continue;
+        };
+        let mut acquired = BTreeSet::new();
+        let mut called = Vec::new();
+        for instr in code.code.iter() {
+            match instr {
+                Bytecode::MoveFrom(idx)
+                | Bytecode::ImmBorrowGlobal(idx)
+                | Bytecode::MutBorrowGlobal(idx) => {
+                    acquired.insert(*idx);
+                }
+                Bytecode::MoveFromGeneric(idx)
+                | Bytecode::ImmBorrowGlobalGeneric(idx)
+                | Bytecode::MutBorrowGlobalGeneric(idx) => {
+                    acquired.insert(module.struct_instantiation_at(*idx).def);
+                }
+                Bytecode::Call(handle) => called.extend(def_of_handle.get(handle)),
+                Bytecode::CallGeneric(idx) => {
+                    called.extend(def_of_handle.get(&module.function_instantiation_at(*idx).handle))
+                }
+                _ => {}
+            }
+        }
+        direct.push(Some(acquired));
+        callees.push(called);
+    }
+
+    // propagate through same-module calls until nothing changes
+    let mut inferred = direct;
+    loop {
+        let mut changed = false;
+        for idx in 0..inferred.len() {
+            if inferred[idx].is_none() {
+                continue;
+            }
+            let from_callees = callees[idx]
+                .iter()
+                .filter_map(|callee| inferred[*callee].as_ref())
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            let acquired = inferred[idx].as_mut().unwrap();
+            for resource in from_callees {
+                changed |= acquired.insert(resource);
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    inferred
+}
+
+/// Resources listed in the `acquires` clause of the function definition
+pub(crate) fn declared_acquires(
+    module: &CompiledModule,
+    idx: FunctionDefinitionIndex,
+) -> BTreeSet<StructDefinitionIndex> {
+    module
+        .function_def_at(idx)
+        .acquires_global_resources
+        .iter()
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Named addresses used in place of numeric ones when rendering module paths, such as
+//! `aptos_framework::coin::Coin` for `0x1::coin::Coin`.
+//!
+//! Names are resolved with their numeric addresses while decompiling, so that optimizations keep
+//! matching e.g. `0x1::vector::length`, and only aliased when rendered.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use move_core_types::account_address::AccountAddress;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddressAliases {
+    names: BTreeMap<AccountAddress, String>,
+}
+
+impl AddressAliases {
+    /// The named addresses of the Aptos framework packages
+    pub fn defaults() -> Self {
+        let mut aliases = Self::default();
+        for (address, name) in [
+            (AccountAddress::ONE, "aptos_framework"),
+            (AccountAddress::THREE, "aptos_token"),
+            (AccountAddress::FOUR, "aptos_token_objects"),
+        ] {
+            aliases.insert(address, name.to_string());
+        }
+        aliases
+    }
+
+    /// Aliases given as `name = "0x..."`, either at the top level or in an `[addresses]` table
+    /// as in a `Move.toml`
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let document: toml::Table = toml::from_str(source)?;
+        let table = match document.get("addresses") {
+            Some(addresses) => addresses
+                .as_table()
+                .ok_or_else(|| anyhow!("`addresses` must be a table"))?,
+            None => &document,
+        };
+
+        let mut aliases = Self::default();
+        for (name, address) in table {
+            if !is_identifier(name) {
+                bail!("invalid address name `{}`", name);
+            }
+            let address = address
+                .as_str()
+                .ok_or_else(|| anyhow!("address of `{}` must be a string", name))?;
+            let address = AccountAddress::from_hex_literal(address)
+                .map_err(|err| anyhow!("invalid address of `{}`: {}", name, err))?;
+            aliases.insert(address, name.clone());
+        }
+        Ok(aliases)
+    }
+
+    /// Name `address` as `name`, replacing any previous name of it
+    pub fn insert(&mut self, address: AccountAddress, name: String) {
+        self.names.insert(address, name);
+    }
+
+    /// Add the aliases of `other`, which take precedence
+    pub fn extend(&mut self, other: AddressAliases) {
+        self.names.extend(other.names);
+    }
+
+    pub fn name(&self, address: &AccountAddress) -> Option<&str> {
+        self.names.get(address).map(|x| x.as_str())
+    }
+
+    /// `address` as rendered in module paths: its alias if any, the hex literal otherwise
+    pub fn display_address(&self, address: &AccountAddress) -> String {
+        self.name(address)
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| address.to_hex_literal())
+    }
+
+    /// `path` such as `0x1::coin::Coin` with its leading address aliased
+    pub fn path(&self, path: &str) -> String {
+        let alias = path.split_once("::").and_then(|(address, rest)| {
+            let address = AccountAddress::from_hex_literal(address).ok()?;
+            Some(format!("{}::{}", self.name(&address)?, rest))
+        });
+        alias.unwrap_or_else(|| path.to_string())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&AccountAddress, &str)> {
+        self.names
+            .iter()
+            .map(|(address, name)| (address, name.as_str()))
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,24 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{
+    binary_views::BinaryIndexedView,
+    file_format::{CompiledModule, CompiledScript},
+};
+
+/// An owned module or script, viewed as the `BinaryIndexedView` taken by `Decompiler::new`
+#[derive(Clone, Debug)]
+pub enum CompiledBinary {
+    Script(CompiledScript),
+    Module(CompiledModule),
+}
+
+impl CompiledBinary {
+    pub fn as_binary_view(&self) -> BinaryIndexedView<'_> {
+        match self {
+            CompiledBinary::Script(script) => BinaryIndexedView::Script(script),
+            CompiledBinary::Module(module) => BinaryIndexedView::Module(module),
+        }
+    }
+}
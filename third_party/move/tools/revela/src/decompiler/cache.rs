@@ -0,0 +1,148 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use move_model::model::{FunctionEnv, ModuleEnv, ModuleId};
+use move_stackless_bytecode::{
+    function_target::FunctionTarget,
+    stackless_bytecode::{Bytecode, Operation},
+};
+use sha3::{Digest, Sha3_256};
+
+use super::{naming::Naming, FormatOptions, OptimizerSettings};
+
+/// Storage of decompiled function bodies, keyed by a digest of everything the body depends on
+pub trait DecompileCache {
+    fn get(&self, key: &str) -> Option<String>;
+
+    fn put(&self, key: &str, body: &str);
+}
+
+/// In-memory cache, clones share the same entries
+#[derive(Clone, Default)]
+pub struct MemoryCache {
+    entries: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DecompileCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, body: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), body.to_string());
+    }
+}
+
+/// Digests of the bytecode of the modules in the model, computed on first use
+#[derive(Default)]
+pub(crate) struct ModuleHashes {
+    hashes: RefCell<HashMap<ModuleId, Option<String>>>,
+}
+
+impl ModuleHashes {
+    /// `None` for modules without bytecode, e.g. the ones only referred to
+    fn get(&self, module_env: &ModuleEnv<'_>) -> Option<String> {
+        self.hashes
+            .borrow_mut()
+            .entry(module_env.get_id())
+            .or_insert_with(|| {
+                let mut bytecode = Vec::new();
+                module_env
+                    .get_verified_module()?
+                    .serialize(&mut bytecode)
+                    .ok()?;
+                Some(hex::encode(Sha3_256::digest(&bytecode)))
+            })
+            .clone()
+    }
+}
+
+/// The key of a function body: the decompiler version, the options, and the function bytecode
+/// with its callees, types and constants resolved by name. The signatures of the callees decide
+/// e.g. between receiver style calls and explicit type arguments, so they are part of the key,
+/// along with the bytecode of the modules of callees in other modules.
+pub(crate) fn function_cache_key(
+    function_target: &FunctionTarget<'_>,
+    naming: &Naming,
+    module_hashes: &ModuleHashes,
+    optimizer_settings: &OptimizerSettings,
+    format_options: &FormatOptions,
+    error_constants: &BTreeMap<u64, String>,
+) -> String {
+    let mut buf = String::new();
+    writeln!(buf, "revela {}", env!("CARGO_PKG_VERSION")).unwrap();
+    writeln!(buf, "{:?}", optimizer_settings).unwrap();
+    writeln!(buf, "{:?}", format_options).unwrap();
+    writeln!(buf, "{:?}", error_constants).unwrap();
+    writeln!(buf, "{:?}", naming.address_aliases()).unwrap();
+    writeln!(buf, "{}", function_target.func_env.get_full_name_str()).unwrap();
+    writeln!(buf, "{}", function_target.get_parameter_count()).unwrap();
+    for idx in 0..function_target.get_parameter_count() {
+        writeln!(buf, "{}", naming.argument(idx)).unwrap();
+    }
+    for idx in 0..function_target.get_local_count() {
+        writeln!(buf, "{}", naming.ty(function_target.get_local_type(idx))).unwrap();
+    }
+    let label_offsets = Default::default();
+    for bytecode in function_target.get_bytecode() {
+        writeln!(buf, "{}", bytecode.display(function_target, &label_offsets)).unwrap();
+    }
+    let env = function_target.global_env();
+    let mut callees = BTreeMap::new();
+    for bytecode in function_target.get_bytecode() {
+        if let Bytecode::Call(_, _, Operation::Function(mid, fid, _), _, _) = bytecode {
+            let callee = env.get_function(mid.qualified(*fid));
+            callees
+                .entry(callee.get_full_name_str())
+                .or_insert_with(|| {
+                    callee_signature(&callee, function_target, naming, module_hashes)
+                });
+        }
+    }
+    for (name, signature) in callees {
+        writeln!(buf, "{} {}", name, signature).unwrap();
+    }
+
+    hex::encode(Sha3_256::digest(buf.as_bytes()))
+}
+
+fn callee_signature(
+    callee: &FunctionEnv<'_>,
+    caller: &FunctionTarget<'_>,
+    naming: &Naming,
+    module_hashes: &ModuleHashes,
+) -> String {
+    let mut buf = String::new();
+    write!(buf, "<{}>(", callee.get_type_parameter_count()).unwrap();
+    for ty in callee.get_parameter_types() {
+        write!(buf, "{}, ", naming.ty(&ty)).unwrap();
+    }
+    write!(buf, "): {}", naming.ty(&callee.get_result_type())).unwrap();
+    for struct_id in callee.get_acquires_global_resources().unwrap_or_default() {
+        let struct_env = callee.module_env.get_struct(struct_id);
+        write!(buf, " acquires {}", struct_env.get_full_name_str()).unwrap();
+    }
+    // the bytecode of the caller's own module is covered by the caller's key
+    if callee.module_env.get_id() != caller.func_env.module_env.get_id() {
+        let hash = module_hashes.get(&callee.module_env);
+        write!(buf, " module {}", hash.as_deref().unwrap_or("-")).unwrap();
+    }
+    buf
+}
@@ -368,12 +368,15 @@ fn rewrite_labels(
         }
     }
 
-    fn update(label2block: &BTreeMap<Label, usize>, label: &mut Label) {
-        if let Some(idx) = label2block.get(label) {
-            *label = Label::new(*idx);
-        } else {
-            panic!("Error: label {} not found", label.as_usize());
-        }
+    fn update(
+        label2block: &BTreeMap<Label, usize>,
+        label: &mut Label,
+    ) -> Result<(), anyhow::Error> {
+        let idx = label2block
+            .get(label)
+            .ok_or_else(|| anyhow::anyhow!("Label {} not found", label.as_usize()))?;
+        *label = Label::new(*idx);
+        Ok(())
     }
 
     for block in blocks.iter_mut() {
@@ -391,14 +394,14 @@ fn rewrite_labels(
                 }
                 match &mut bytecode.bytecode {
                     Bytecode::Branch(_, t, f, _) => {
-                        update(&label2block, t);
-                        update(&label2block, f);
+                        update(&label2block, t)?;
+                        update(&label2block, f)?;
                     }
                     Bytecode::Jump(_, d) => {
-                        update(&label2block, d);
+                        update(&label2block, d)?;
                     }
                     Bytecode::Label(_, l) => {
-                        update(&label2block, l);
+                        update(&label2block, l)?;
                     }
                     _ => {}
                 }
@@ -420,25 +423,28 @@ fn debug_dump_program(
     for block in program.blocks.iter().map(to_inner) {
         match block {
             HyperBlock::ConnectedBlocks(blocks) => {
-                println!("{}//Connected block", prefix);
+                log::trace!("{}//Connected block", prefix);
                 for block in blocks.iter().map(to_inner) {
-                    println!("{}Block {} {:?}", prefix, block.idx, block.next);
+                    log::trace!("{}Block {} {:?}", prefix, block.idx, block.next);
                     if show_bytecode {
                         block.content.code.iter().for_each(|bytecode| {
-                            println!(
+                            log::trace!(
                                 "{}  {:?} removed={:?} jump_type={:?}",
-                                prefix, bytecode.bytecode, bytecode.removed, bytecode.jump_type
+                                prefix,
+                                bytecode.bytecode,
+                                bytecode.removed,
+                                bytecode.jump_type
                             );
                         });
                     }
                 }
-                println!("{}//End connected block", prefix);
+                log::trace!("{}//End connected block", prefix);
             }
 
             HyperBlock::IfElseBlocks { if_unit, else_unit } => {
-                println!("{}If", prefix);
+                log::trace!("{}If", prefix);
                 debug_dump_program(if_unit.inner(), lvl + 1, show_bytecode);
-                println!("{}Else", prefix);
+                log::trace!("{}Else", prefix);
                 debug_dump_program(else_unit.inner(), lvl + 1, show_bytecode);
             }
 
@@ -449,12 +455,15 @@ fn debug_dump_program(
                 start_block: start_label,
                 exit_block: exit_label,
             } => {
-                println!(
+                log::trace!(
                     "{}While unconditional={}, start_label={}, exit_label={}",
-                    prefix, unconditional, start_label, exit_label
+                    prefix,
+                    unconditional,
+                    start_label,
+                    exit_label
                 );
                 debug_dump_program(inner.inner(), lvl + 1, show_bytecode);
-                println!("{}EndWhile", prefix);
+                log::trace!("{}EndWhile", prefix);
 
                 debug_dump_program(outer.inner(), lvl + 1, show_bytecode);
             }
@@ -466,13 +475,15 @@ fn debug_dump_program(
 #[allow(dead_code)]
 fn debug_dump_blocks(blocks: &[BasicBlock<usize, StacklessBlockContent>]) {
     for block in blocks {
-        println!(
+        log::trace!(
             "Block {} {:?} unconditional_loop_entry={:?}",
-            block.idx, block.next, block.unconditional_loop_entry
+            block.idx,
+            block.next,
+            block.unconditional_loop_entry
         );
 
         block.content.code.iter().for_each(|bytecode| {
-            println!("  {:?}", bytecode.bytecode);
+            log::trace!("  {:?}", bytecode.bytecode);
         });
     }
 }
@@ -480,13 +491,13 @@ fn debug_dump_blocks(blocks: &[BasicBlock<usize, StacklessBlockContent>]) {
 #[cfg(debug_assertions)]
 #[allow(dead_code)]
 fn debug_dump_blocks_graph(blocks: &[BasicBlock<usize, StacklessBlockContent>]) {
-    println!("digraph G {{");
+    log::trace!("digraph G {{");
     for block in blocks {
         for nxt in block.next.next_blocks() {
-            println!("{} -> {}", block.idx, nxt);
+            log::trace!("{} -> {}", block.idx, nxt);
         }
     }
-    println!("}}");
+    log::trace!("}}");
 }
 
 fn cleanup_tail_jumps_for_terminated_blocks(
@@ -666,8 +677,7 @@ fn cleanup_dummy_dispatch_blocks(
                     }
                     break;
                 } else {
-                    panic!("Unexpected terminator");
-                    // return Err(anyhow::anyhow!("Unexpected terminator"));
+                    return Err(anyhow::anyhow!("Unexpected terminator"));
                 }
             }
         }
@@ -0,0 +1,44 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use thiserror::Error;
+
+/// Why a decompilation failed, declarations are named by their full name, e.g.
+/// `0x1::coin::transfer`
+#[derive(Debug, Error)]
+pub enum DecompileError {
+    #[error("{module} uses bytecode version {version}, supported versions are {min} to {max}")]
+    UnsupportedBytecodeVersion {
+        module: String,
+        version: u32,
+        min: u32,
+        max: u32,
+    },
+
+    #[error("module {0} is given more than once")]
+    DuplicateModule(String),
+
+    #[error("unable to build the model of the binaries: {0}")]
+    ModelBuild(anyhow::Error),
+
+    #[error("unable to decompile struct {name}: {source}")]
+    Struct { name: String, source: anyhow::Error },
+
+    #[error("unable to decompile function {name}: {source}")]
+    Function { name: String, source: anyhow::Error },
+
+    /// The other declarations are complete, the bodies of these functions are their stackless
+    /// bytecode as comments, which does not implement them
+    #[error("unable to structure the control flow of {}", .0.join(", "))]
+    UnstructuredControlFlow(Vec<String>),
+
+    #[error("decompilation cancelled")]
+    Cancelled,
+
+    #[error("unable to write the decompiled source: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
@@ -0,0 +1,138 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Move literals of the constants loaded by the bytecode.
+//!
+//! Integers beyond the u64 range carry their type, and masks or powers of two are written in
+//! hex. Byte vectors holding text are written as `b"..."` with escapes for the other bytes,
+//! anything else as `x"..."`. Vectors of integers type their first element, which is enough for
+//! the compiler to type the whole vector, and empty vectors spell out their element type when
+//! the type of the constant is known.
+
+use move_model::{ast::Address, ty::Type};
+use move_stackless_bytecode::stackless_bytecode::Constant;
+
+use crate::decompiler::naming::Naming;
+
+const U64_MAX: &str = "18446744073709551615";
+
+/// `ty` is the type of `constant`, if known
+pub(crate) fn constant_literal(constant: &Constant, ty: Option<&Type>, naming: &Naming) -> String {
+    match constant {
+        Constant::Bool(x) => x.to_string(),
+        Constant::U8(x) => x.to_string(),
+        Constant::U16(x) => x.to_string(),
+        Constant::U32(x) => x.to_string(),
+        Constant::U64(x) => x.to_string(),
+        Constant::U128(x) => wide_integer(x.to_string(), format!("{:x}", x), "u128"),
+        Constant::U256(x) => wide_integer(x.to_string(), format!("{:x}", x), "u256"),
+        Constant::Address(x) => address_literal(x),
+        Constant::ByteArray(bytes) => byte_string(bytes),
+        Constant::AddressArray(addresses) if addresses.is_empty() => {
+            "vector<address>[]".to_string()
+        }
+        Constant::AddressArray(addresses) => format!(
+            "vector[{}]",
+            addresses
+                .iter()
+                .map(address_literal)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Constant::Vector(items) => {
+            let element_type = match ty {
+                Some(Type::Vector(element_type)) => Some(element_type.as_ref()),
+                _ => None,
+            };
+            match element_type {
+                Some(element_type) if items.is_empty() => {
+                    format!("vector<{}>[]", naming.ty(element_type))
+                }
+                _ => format!(
+                    "vector[{}]",
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, item)| match idx {
+                            0 => typed_literal(item, element_type, naming),
+                            _ => constant_literal(item, element_type, naming),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }
+        }
+    }
+}
+
+fn address_literal(address: &Address) -> String {
+    match address {
+        Address::Numerical(x) => format!("@{}", x.to_hex_literal()),
+        Address::Symbolic(_) => {
+            unreachable!("There must be no symbolic address in compiled binary")
+        }
+    }
+}
+
+/// `constant` with its type spelled out when it is an integer that would default to u64
+fn typed_literal(constant: &Constant, ty: Option<&Type>, naming: &Naming) -> String {
+    let suffix = match constant {
+        Constant::U8(_) => "u8",
+        Constant::U16(_) => "u16",
+        Constant::U32(_) => "u32",
+        Constant::U128(_) => "u128",
+        Constant::U256(_) => "u256",
+        _ => "",
+    };
+    let literal = constant_literal(constant, ty, naming);
+    if literal.ends_with(suffix) {
+        literal
+    } else {
+        format!("{}{}", literal, suffix)
+    }
+}
+
+/// u128 and u256 values, typed when they do not fit in a u64
+fn wide_integer(decimal: String, hex: String, suffix: &str) -> String {
+    if decimal.len() < U64_MAX.len()
+        || (decimal.len() == U64_MAX.len() && decimal.as_str() <= U64_MAX)
+    {
+        return decimal;
+    }
+    let (head, tail) = hex.split_at(1);
+    let power_of_two = matches!(head, "1" | "2" | "4" | "8") && tail.chars().all(|c| c == '0');
+    let mask = matches!(head, "1" | "3" | "7" | "f") && tail.chars().all(|c| c == 'f');
+    if power_of_two || mask {
+        format!("0x{}{}", hex, suffix)
+    } else {
+        format!("{}{}", decimal, suffix)
+    }
+}
+
+/// `b"..."` when `bytes` are mostly printable text, UTF-8 included, `x"..."` otherwise
+fn byte_string(bytes: &[u8]) -> String {
+    let is_text = std::str::from_utf8(bytes).is_ok_and(|text| {
+        text.chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+    });
+    let printable = bytes.iter().filter(|x| (0x20..=0x7e).contains(*x)).count();
+    if !is_text || printable * 2 < bytes.len() {
+        return format!("x\"{}\"", hex::encode(bytes));
+    }
+
+    let mut literal = String::from("b\"");
+    for byte in bytes {
+        match byte {
+            b'\n' => literal.push_str("\\n"),
+            b'\r' => literal.push_str("\\r"),
+            b'\t' => literal.push_str("\\t"),
+            b'\\' => literal.push_str("\\\\"),
+            b'"' => literal.push_str("\\\""),
+            0x20..=0x7e => literal.push(*byte as char),
+            _ => literal.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -24,13 +24,18 @@ pub enum ExprNodeOperation {
     Deleted,
     NonTrivial,
     Raw(String),
-    Const(Constant),
+    /// a constant along with its type when known, which empty vectors spell out
+    Const(Constant, Option<Type>),
+    /// a constant rendered by the name it is declared with
+    NamedConst(String, Constant),
     LocalVariable(usize),
     Field(ExprNodeRef, String),
     Unary(String, ExprNodeRef),
     Cast(String, ExprNodeRef),
     Binary(String, ExprNodeRef, ExprNodeRef),
     Func(String, Vec<ExprNodeRef>, Vec<Type>),
+    /// a call in receiver style `args[0].name(args[1..])`, type arguments are inferred
+    MethodCall(String, Vec<ExprNodeRef>, Vec<Type>),
 
     Destroy(ExprNodeRef),
     FreezeRef(ExprNodeRef),
@@ -132,6 +137,11 @@ impl ExprNodeOperation {
                 args.iter().map(|x| x.borrow().copy_as_ref()).collect(),
                 types.clone(),
             ),
+            ExprNodeOperation::MethodCall(name, args, types) => ExprNodeOperation::MethodCall(
+                name.clone(),
+                args.iter().map(|x| x.borrow().copy_as_ref()).collect(),
+                types.clone(),
+            ),
             ExprNodeOperation::StructPack(name, args, types) => ExprNodeOperation::StructPack(
                 name.clone(),
                 args.iter()
@@ -166,7 +176,10 @@ impl ExprNodeOperation {
                 ExprNodeOperation::WriteRef(lhs.borrow().copy_as_ref(), rhs.borrow().copy_as_ref())
             }
             ExprNodeOperation::Raw(name) => ExprNodeOperation::Raw(name.clone()),
-            ExprNodeOperation::Const(c) => ExprNodeOperation::Const(c.clone()),
+            ExprNodeOperation::Const(c, ty) => ExprNodeOperation::Const(c.clone(), ty.clone()),
+            ExprNodeOperation::NamedConst(name, c) => {
+                ExprNodeOperation::NamedConst(name.clone(), c.clone())
+            }
             ExprNodeOperation::Ignored => ExprNodeOperation::Ignored,
             ExprNodeOperation::Deleted => ExprNodeOperation::Deleted,
             ExprNodeOperation::NonTrivial => ExprNodeOperation::NonTrivial,
@@ -208,61 +221,6 @@ impl ExprNodeOperation {
         }
     }
 
-    fn const_to_source(val: &Constant) -> Result<String, anyhow::Error> {
-        match val {
-            Constant::Bool(v) => Ok(format!("{}", v)),
-            Constant::U8(x) => Ok(format!("{}", x)),
-            Constant::U16(x) => Ok(format!("{}", x)),
-            Constant::U32(x) => Ok(format!("{}", x)),
-            Constant::U64(x) => Ok(format!("{}", x)),
-            Constant::U128(x) => Ok(format!("{}", x)),
-            Constant::U256(x) => Ok(format!("{}", x)),
-            Constant::Address(x) => match x {
-                move_model::ast::Address::Numerical(val) => {
-                    Ok(format!("@{}", val.to_hex_literal()))
-                }
-                move_model::ast::Address::Symbolic(_val) => {
-                    unreachable!("There must be no symbolic address in compiled binary")
-                }
-            },
-            Constant::ByteArray(v) => {
-                let is_safe = v.iter().all(|x| *x >= 0x20 && *x <= 0x7e);
-                if is_safe {
-                    Ok(format!(
-                        "b\"{}\"",
-                        v.iter()
-                            .map(|x| *x as char)
-                            .collect::<String>()
-                            .replace("\\", "\\\\")
-                            .replace("\"", "\\\"")
-                    ))
-                } else {
-                    Ok(format!(
-                        "x\"{}\"",
-                        v.iter()
-                            .map(|x| format!("{:02x}", x))
-                            .collect::<Vec<_>>()
-                            .join(""),
-                    ))
-                }
-            }
-            Constant::AddressArray(v) => Ok(format!(
-                "vector[{}]",
-                v.iter()
-                    .map(|x| Self::const_to_source(&Constant::Address(x.clone())))
-                    .collect::<Result<Vec<_>, _>>()?
-                    .join(", "),
-            )),
-            Constant::Vector(v) => Ok(format!(
-                "vector[{}]",
-                v.iter()
-                    .map(|x| Self::const_to_source(x))
-                    .collect::<Result<Vec<_>, _>>()?
-                    .join(", "),
-            )),
-        }
-    }
-
     pub fn to_source_decl(&self, naming: &Naming) -> Result<String, anyhow::Error> {
         match self {
             ExprNodeOperation::StructPack(name, args, types) => {
@@ -272,7 +230,7 @@ impl ExprNodeOperation {
                 let k_width = args.iter().map(|x| x.0.len()).max().unwrap();
                 Ok(format!(
                     "{}{}{{\n{},\n}}",
-                    name,
+                    naming.path(name),
                     Self::typeparams_to_source(types, naming),
                     args.iter()
                         .map(|x| x.1.borrow().to_source(naming).and_then(|v| Ok(format!(
@@ -289,6 +247,32 @@ impl ExprNodeOperation {
         }
     }
 
+    /// The receiver of a method call, `&x` and `&mut x` are implied by the call
+    fn receiver_to_source(
+        receiver: &ExprNodeRef,
+        naming: &Naming,
+        ctx: &ToSourceCtx,
+    ) -> Result<String, anyhow::Error> {
+        effective_operation(&[receiver], &mut |[receiver]| {
+            let borrowed = match &receiver.borrow().operation {
+                ExprNodeOperation::BorrowLocal(inner, _) => Some(inner.clone()),
+                ExprNodeOperation::FreezeRef(inner) => match &inner.borrow().operation {
+                    ExprNodeOperation::BorrowLocal(inner, _) => Some(inner.clone()),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let receiver = borrowed.as_ref().unwrap_or(receiver);
+            let receiver_str = receiver.borrow().to_source_with_ctx(naming, ctx)?;
+            Ok(match &receiver.borrow().operation {
+                ExprNodeOperation::Binary(..) => format!("({})", receiver_str),
+                // `*r` and `!x` bind looser than the call
+                _ if receiver_str.starts_with(['*', '!']) => format!("({})", receiver_str),
+                _ => receiver_str,
+            })
+        })
+    }
+
     pub fn to_source(&self, naming: &Naming) -> Result<String, anyhow::Error> {
         let ctx = ToSourceCtx::default();
         self.to_source_with_ctx(naming, &ctx)
@@ -316,7 +300,10 @@ impl ExprNodeOperation {
             ExprNodeOperation::Deleted => Ok("<<< !!! deleted !!! >>>".to_string()),
             ExprNodeOperation::NonTrivial => Ok("!!non-trivial!!".to_string()),
             ExprNodeOperation::Raw(x) => Ok(format!("((/*raw:*/{}))", x)),
-            ExprNodeOperation::Const(c) => Self::const_to_source(c),
+            ExprNodeOperation::Const(c, ty) => {
+                Ok(literals::constant_literal(c, ty.as_ref(), naming))
+            }
+            ExprNodeOperation::NamedConst(name, _) => Ok(name.clone()),
             ExprNodeOperation::Field(expr, name) => {
                 // &(&object).field -> & object.field
                 if ctx.in_borrow {
@@ -361,13 +348,23 @@ impl ExprNodeOperation {
             }
             ExprNodeOperation::Func(name, args, types) => Ok(format!(
                 "{}{}({})",
-                name,
+                naming.path(name),
                 Self::typeparams_to_source(types, naming),
                 args.iter()
                     .map(|x| x.borrow().to_source_with_ctx(naming, &ctx))
                     .collect::<Result<Vec<String>, anyhow::Error>>()?
                     .join(", ")
             )),
+            ExprNodeOperation::MethodCall(name, args, _) => Ok(format!(
+                "{}.{}({})",
+                Self::receiver_to_source(&args[0], naming, &ctx)?,
+                name,
+                args[1..]
+                    .iter()
+                    .map(|x| x.borrow().to_source_with_ctx(naming, &ctx))
+                    .collect::<Result<Vec<String>, anyhow::Error>>()?
+                    .join(", ")
+            )),
             ExprNodeOperation::Destroy(expr) => Ok(format!(
                 "/*destroyed:{}*/",
                 expr.borrow().to_source_with_ctx(naming, &ctx)?
@@ -410,7 +407,7 @@ impl ExprNodeOperation {
             )),
             ExprNodeOperation::StructPack(name, args, types) => Ok(format!(
                 "{}{}{{{}}}",
-                name,
+                naming.path(name),
                 Self::typeparams_to_source(types, naming),
                 args.iter()
                     .map(|x| x
@@ -423,7 +420,7 @@ impl ExprNodeOperation {
             )),
             ExprNodeOperation::StructUnpack(name, keys, val, types) => Ok(format!(
                 "{}{}{{{}}} = {}",
-                name,
+                naming.path(name),
                 Self::typeparams_to_source(types, naming),
                 keys.iter()
                     .map(|x| x.to_string())
@@ -455,7 +452,8 @@ impl ExprNodeOperation {
             | ExprNodeOperation::Deleted
             | ExprNodeOperation::NonTrivial
             | ExprNodeOperation::Raw(..)
-            | ExprNodeOperation::Const(..) => {}
+            | ExprNodeOperation::Const(..)
+            | ExprNodeOperation::NamedConst(..) => {}
             ExprNodeOperation::Field(expr, _) => expr.borrow().collect_variables(
                 result_variables,
                 implicit_variables,
@@ -483,7 +481,7 @@ impl ExprNodeOperation {
                     in_implicit_expr,
                 );
             }
-            ExprNodeOperation::Func(_, args, _) => {
+            ExprNodeOperation::Func(_, args, _) | ExprNodeOperation::MethodCall(_, args, _) => {
                 for arg in args {
                     arg.borrow().collect_variables(
                         result_variables,
@@ -546,7 +544,8 @@ impl ExprNodeOperation {
             | ExprNodeOperation::Deleted
             | ExprNodeOperation::NonTrivial
             | ExprNodeOperation::Raw(..)
-            | ExprNodeOperation::Const(..) => false,
+            | ExprNodeOperation::Const(..)
+            | ExprNodeOperation::NamedConst(..) => false,
             ExprNodeOperation::Field(expr, _) => expr
                 .borrow()
                 .operation
@@ -567,11 +566,13 @@ impl ExprNodeOperation {
                         .operation
                         .has_reference_to_any_variable(variables)
             }
-            ExprNodeOperation::Func(_, args, _) => args.iter().any(|arg| {
-                arg.borrow()
-                    .operation
-                    .has_reference_to_any_variable(variables)
-            }),
+            ExprNodeOperation::Func(_, args, _) | ExprNodeOperation::MethodCall(_, args, _) => {
+                args.iter().any(|arg| {
+                    arg.borrow()
+                        .operation
+                        .has_reference_to_any_variable(variables)
+                })
+            }
             ExprNodeOperation::Destroy(expr)
             | ExprNodeOperation::FreezeRef(expr)
             | ExprNodeOperation::ReadRef(expr)
@@ -612,6 +613,82 @@ impl ExprNodeOperation {
         }
     }
 
+    /// Check whether `f` holds for this operation or any of its sub-operations
+    pub fn any_sub_operation(&self, f: &mut dyn FnMut(&ExprNodeOperation) -> bool) -> bool {
+        if f(self) {
+            return true;
+        }
+        match self {
+            ExprNodeOperation::LocalVariable(..)
+            | ExprNodeOperation::Ignored
+            | ExprNodeOperation::Deleted
+            | ExprNodeOperation::NonTrivial
+            | ExprNodeOperation::Raw(..)
+            | ExprNodeOperation::Const(..)
+            | ExprNodeOperation::NamedConst(..) => false,
+            ExprNodeOperation::Binary(_, a, b) | ExprNodeOperation::WriteRef(a, b) => {
+                a.borrow().operation.any_sub_operation(f)
+                    || b.borrow().operation.any_sub_operation(f)
+            }
+            ExprNodeOperation::Func(_, args, _) | ExprNodeOperation::MethodCall(_, args, _) => args
+                .iter()
+                .any(|arg| arg.borrow().operation.any_sub_operation(f)),
+            ExprNodeOperation::StructPack(_, args, _) => args
+                .iter()
+                .any(|arg| arg.1.borrow().operation.any_sub_operation(f)),
+            ExprNodeOperation::Field(expr, _)
+            | ExprNodeOperation::Unary(_, expr)
+            | ExprNodeOperation::Cast(_, expr)
+            | ExprNodeOperation::Destroy(expr)
+            | ExprNodeOperation::FreezeRef(expr)
+            | ExprNodeOperation::ReadRef(expr)
+            | ExprNodeOperation::BorrowLocal(expr, _)
+            | ExprNodeOperation::StructUnpack(_, _, expr, _)
+            | ExprNodeOperation::VariableSnapshot { value: expr, .. } => {
+                expr.borrow().operation.any_sub_operation(f)
+            }
+        }
+    }
+
+    /// Apply `f` to every sub-operation, children first, allowing `f` to replace them in place
+    pub fn rewrite_operations(&mut self, f: &mut dyn FnMut(&mut ExprNodeOperation)) {
+        match self {
+            ExprNodeOperation::LocalVariable(..)
+            | ExprNodeOperation::Ignored
+            | ExprNodeOperation::Deleted
+            | ExprNodeOperation::NonTrivial
+            | ExprNodeOperation::Raw(..)
+            | ExprNodeOperation::Const(..)
+            | ExprNodeOperation::NamedConst(..) => {}
+            ExprNodeOperation::Binary(_, a, b) | ExprNodeOperation::WriteRef(a, b) => {
+                a.borrow_mut().operation.rewrite_operations(f);
+                b.borrow_mut().operation.rewrite_operations(f);
+            }
+            ExprNodeOperation::Func(_, args, _) | ExprNodeOperation::MethodCall(_, args, _) => {
+                for arg in args.iter() {
+                    arg.borrow_mut().operation.rewrite_operations(f);
+                }
+            }
+            ExprNodeOperation::StructPack(_, args, _) => {
+                for arg in args.iter() {
+                    arg.1.borrow_mut().operation.rewrite_operations(f);
+                }
+            }
+            ExprNodeOperation::Field(expr, _)
+            | ExprNodeOperation::Unary(_, expr)
+            | ExprNodeOperation::Cast(_, expr)
+            | ExprNodeOperation::Destroy(expr)
+            | ExprNodeOperation::FreezeRef(expr)
+            | ExprNodeOperation::ReadRef(expr)
+            | ExprNodeOperation::BorrowLocal(expr, _)
+            | ExprNodeOperation::StructUnpack(_, _, expr, _)
+            | ExprNodeOperation::VariableSnapshot { value: expr, .. } => {
+                expr.borrow_mut().operation.rewrite_operations(f);
+            }
+        }
+        f(self);
+    }
+
     pub fn rename_variables(&mut self, renamed_variables: &HashMap<usize, usize>) {
         match self {
             ExprNodeOperation::LocalVariable(idx) => {
@@ -626,12 +703,13 @@ impl ExprNodeOperation {
             ExprNodeOperation::Ignored
             | ExprNodeOperation::Deleted
             | ExprNodeOperation::Raw(..)
-            | ExprNodeOperation::Const(..) => {}
+            | ExprNodeOperation::Const(..)
+            | ExprNodeOperation::NamedConst(..) => {}
             ExprNodeOperation::Binary(_, a, b) | ExprNodeOperation::WriteRef(a, b) => {
                 a.borrow_mut().rename_variables(renamed_variables);
                 b.borrow_mut().rename_variables(renamed_variables);
             }
-            ExprNodeOperation::Func(_, args, _) => {
+            ExprNodeOperation::Func(_, args, _) | ExprNodeOperation::MethodCall(_, args, _) => {
                 for arg in args {
                     arg.borrow_mut().rename_variables(renamed_variables);
                 }
@@ -671,7 +749,8 @@ impl ExprNodeOperation {
             ExprNodeOperation::Deleted => self.to_node(),
             ExprNodeOperation::NonTrivial => self.to_node(),
             ExprNodeOperation::Raw(_) => self.to_node(),
-            ExprNodeOperation::Const(_) => self.to_node(),
+            ExprNodeOperation::Const(..) => self.to_node(),
+            ExprNodeOperation::NamedConst(..) => self.to_node(),
             ExprNodeOperation::LocalVariable(_) => self.to_node(),
             ExprNodeOperation::Field(expr, name) => ExprNodeOperation::Field(
                 expr.borrow().commit_pending_variables(variables),
@@ -702,6 +781,14 @@ impl ExprNodeOperation {
                 typs.clone(),
             )
             .to_node(),
+            ExprNodeOperation::MethodCall(name, args, typs) => ExprNodeOperation::MethodCall(
+                name.clone(),
+                args.iter()
+                    .map(|x| x.borrow().commit_pending_variables(variables))
+                    .collect(),
+                typs.clone(),
+            )
+            .to_node(),
             ExprNodeOperation::Destroy(expr) => {
                 ExprNodeOperation::Destroy(expr.borrow().commit_pending_variables(variables))
                     .to_node()
@@ -840,7 +927,8 @@ impl Display for ExprNodeOperation {
             ExprNodeOperation::Ignored => write!(f, "_"),
             ExprNodeOperation::NonTrivial => write!(f, "!!non-trivial!!"),
             ExprNodeOperation::Raw(s) => write!(f, "((/*raw:*/{}))", s),
-            ExprNodeOperation::Const(c) => write!(f, "{}", c),
+            ExprNodeOperation::Const(c, _) => write!(f, "{}", c),
+            ExprNodeOperation::NamedConst(name, _) => write!(f, "{}", name),
             ExprNodeOperation::LocalVariable(idx) => write!(f, "_$local$_{}", idx),
             ExprNodeOperation::Unary(op, expr) => {
                 write!(
@@ -930,6 +1018,19 @@ impl Display for ExprNodeOperation {
                         .join(", ")
                 )
             }
+            ExprNodeOperation::MethodCall(name, args, _) => {
+                write!(
+                    f,
+                    "{}.{}({})",
+                    bracket_if_binary_with_ctx(&args[0], None, &ToSourceCtx::default()).unwrap(),
+                    name,
+                    args[1..]
+                        .iter()
+                        .map(|x| x.borrow().to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
             ExprNodeOperation::StructPack(name, args, types) => {
                 write!(
                     f,
@@ -1073,6 +1174,10 @@ impl Expr {
         self.node.borrow_mut().rename_variables(renamed_variables);
     }
 
+    pub fn rewrite_operations(&mut self, f: &mut dyn FnMut(&mut ExprNodeOperation)) {
+        self.node.borrow_mut().operation.rewrite_operations(f);
+    }
+
     pub fn non_trivial() -> Self {
         Self {
             node: ExprNodeOperation::NonTrivial.to_node(),
@@ -1207,7 +1312,9 @@ impl<'a> Clone for StacklessEvaluationContext<'a> {
     }
 }
 
+mod literals;
 pub mod operations;
+mod simplify;
 
 use operations::*;
 
@@ -1239,8 +1346,8 @@ impl<'a> StacklessEvaluationContext<'a> {
         }
     }
 
-    pub fn shortest_prefix(&self, mod_id: &ModuleId) -> String {
-        super::super::utils::shortest_prefix(&self.func_env.module_env, mod_id)
+    pub fn shortest_prefix(&self, mod_id: &ModuleId, name: &str) -> String {
+        super::super::utils::shortest_prefix(&self.func_env.module_env, mod_id, name)
     }
 
     pub fn defined(&self, idx: usize) -> bool {
@@ -1477,7 +1584,11 @@ impl<'a> StacklessEvaluationContext<'a> {
                 if self.defined(dst) && self.get_var(dst).is_flushed() {
                     flushed_variables.insert(dst);
                 }
-                let expr = ExprNodeOperation::Const(value.clone()).to_expr();
+                let ty = dst_types
+                    .first()
+                    .and_then(|hint| hint.as_ref())
+                    .map(|hint| hint.ty.clone());
+                let expr = ExprNodeOperation::Const(value.clone(), ty).to_expr();
                 if self.run_assignment(dst, expr.copy()) {
                     new_variables.insert(dst);
                 }
@@ -1566,6 +1677,7 @@ impl<'a> StacklessEvaluationContext<'a> {
     }
 
     /// Assume that branches are starting from current context, merge them and return the variables that need to be flushed
+    /// The returned variables are sorted so that the flush order is deterministic
     /// Currently not handling ignored and deleted variables, just consider these actions as an assignment
     pub(crate) fn merge_branches(
         &mut self,
@@ -1621,7 +1733,9 @@ impl<'a> StacklessEvaluationContext<'a> {
             self.finalized_pending_variables.insert(var_id);
         }
 
-        need_flushes.into_iter().collect()
+        let mut need_flushes = need_flushes.into_iter().collect::<Vec<_>>();
+        need_flushes.sort();
+        need_flushes
     }
 
     #[allow(dead_code)]
@@ -7,7 +7,9 @@ use std::mem::MaybeUninit;
 use move_model::ty::Type;
 use move_stackless_bytecode::stackless_bytecode::Operation;
 
-use super::{Expr, ExprNodeOperation, ExprNodeRef, ReturnValueHint, StacklessEvaluationContext};
+use super::{
+    simplify, Expr, ExprNodeOperation, ExprNodeRef, ReturnValueHint, StacklessEvaluationContext,
+};
 
 pub struct OperationEvaluatorResult {
     pub cannot_keep: bool,
@@ -311,7 +313,7 @@ fn shortest_name(
     mid: &move_model::model::ModuleId,
     name: String,
 ) -> String {
-    format!("{}{}", ctx.shortest_prefix(&mid), name)
+    format!("{}{}", ctx.shortest_prefix(&mid, &name), name)
 }
 
 fn only_one(args: &Vec<Expr>, operation_name: &str) -> Result<ExprNodeRef, anyhow::Error> {
@@ -355,22 +357,15 @@ fn only_n<const N: usize>(
 fn unary(arg: &str, args: &Vec<Expr>) -> Result<OperationEvaluatorResult, anyhow::Error> {
     Ok(OperationEvaluatorResult {
         cannot_keep: false,
-        expr: ExprNodeOperation::Unary(
-            arg.to_string(),
-            only_one(args, &format!("unary operation '{}'", arg))?,
-        )
-        .to_expr(),
+        expr: simplify::unary(arg, only_one(args, &format!("unary operation '{}'", arg))?)
+            .to_expr(),
     })
 }
 
 fn cast(ty: &str, args: &Vec<Expr>) -> Result<OperationEvaluatorResult, anyhow::Error> {
     Ok(OperationEvaluatorResult {
         cannot_keep: false,
-        expr: ExprNodeOperation::Cast(
-            ty.to_string(),
-            only_one(args, &format!("unary operation '{}'", ty))?,
-        )
-        .to_expr(),
+        expr: simplify::cast(ty, only_one(args, &format!("unary operation '{}'", ty))?).to_expr(),
     })
 }
 
@@ -378,6 +373,6 @@ fn binary(arg: &str, args: &Vec<Expr>) -> Result<OperationEvaluatorResult, anyho
     let [l, r] = only_n::<2>(args, &format!("binary operation '{}'", arg))?;
     Ok(OperationEvaluatorResult {
         cannot_keep: false,
-        expr: ExprNodeOperation::Binary(arg.to_string(), l, r).to_expr(),
+        expr: simplify::binary(arg, l, r).to_expr(),
     })
 }
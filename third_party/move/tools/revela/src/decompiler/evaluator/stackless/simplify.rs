@@ -0,0 +1,188 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Folding of constant operands and removal of no-op arithmetic left by lowering.
+//!
+//! Operations that would abort at runtime (overflow, division by zero, too large shifts or
+//! narrowing casts) are never folded, and operands are only dropped when they are constants,
+//! so evaluation order and side effects are preserved. u256 constants are left as they are.
+
+use move_stackless_bytecode::stackless_bytecode::Constant;
+
+use super::{ExprNodeOperation, ExprNodeRef};
+
+fn int_bits(ty: &str) -> Option<u32> {
+    match ty {
+        "u8" => Some(8),
+        "u16" => Some(16),
+        "u32" => Some(32),
+        "u64" => Some(64),
+        "u128" => Some(128),
+        _ => None,
+    }
+}
+
+fn max_value(bits: u32) -> u128 {
+    if bits == 128 {
+        u128::MAX
+    } else {
+        (1u128 << bits) - 1
+    }
+}
+
+/// Width in bits and value of an integer constant
+fn int_const(c: &Constant) -> Option<(u32, u128)> {
+    match c {
+        Constant::U8(x) => Some((8, *x as u128)),
+        Constant::U16(x) => Some((16, *x as u128)),
+        Constant::U32(x) => Some((32, *x as u128)),
+        Constant::U64(x) => Some((64, *x as u128)),
+        Constant::U128(x) => Some((128, *x)),
+        _ => None,
+    }
+}
+
+fn make_int(bits: u32, value: u128) -> Option<Constant> {
+    if value > max_value(bits) {
+        return None;
+    }
+    match bits {
+        8 => Some(Constant::U8(value as u8)),
+        16 => Some(Constant::U16(value as u16)),
+        32 => Some(Constant::U32(value as u32)),
+        64 => Some(Constant::U64(value as u64)),
+        128 => Some(Constant::U128(value)),
+        _ => None,
+    }
+}
+
+fn const_of(expr: &ExprNodeRef) -> Option<Constant> {
+    match &expr.borrow().operation {
+        ExprNodeOperation::Const(c, _) => Some(c.clone()),
+        _ => None,
+    }
+}
+
+fn is_int_const(expr: &ExprNodeRef, value: u128) -> bool {
+    const_of(expr)
+        .and_then(|c| int_const(&c))
+        .map_or(false, |(_, x)| x == value)
+}
+
+fn is_bool_const(expr: &ExprNodeRef, value: bool) -> bool {
+    matches!(const_of(expr), Some(Constant::Bool(x)) if x == value)
+}
+
+fn fold_binary(op: &str, l: &Constant, r: &Constant) -> Option<Constant> {
+    if let (Constant::Bool(a), Constant::Bool(b)) = (l, r) {
+        return match op {
+            "&&" => Some(Constant::Bool(*a && *b)),
+            "||" => Some(Constant::Bool(*a || *b)),
+            "==" => Some(Constant::Bool(a == b)),
+            "!=" => Some(Constant::Bool(a != b)),
+            _ => None,
+        };
+    }
+
+    let (bits, a) = int_const(l)?;
+    let (r_bits, b) = int_const(r)?;
+    // the shift amount is always a u8
+    if op == "<<" || op == ">>" {
+        if r_bits != 8 || b >= bits as u128 {
+            return None;
+        }
+        return match op {
+            "<<" => make_int(bits, (a << b) & max_value(bits)),
+            _ => make_int(bits, a >> b),
+        };
+    }
+    if bits != r_bits {
+        return None;
+    }
+    match op {
+        "+" => make_int(bits, a.checked_add(b)?),
+        "-" => make_int(bits, a.checked_sub(b)?),
+        "*" => make_int(bits, a.checked_mul(b)?),
+        "/" => make_int(bits, a.checked_div(b)?),
+        "%" => make_int(bits, a.checked_rem(b)?),
+        "&" => make_int(bits, a & b),
+        "|" => make_int(bits, a | b),
+        "^" => make_int(bits, a ^ b),
+        "<" => Some(Constant::Bool(a < b)),
+        ">" => Some(Constant::Bool(a > b)),
+        "<=" => Some(Constant::Bool(a <= b)),
+        ">=" => Some(Constant::Bool(a >= b)),
+        "==" => Some(Constant::Bool(a == b)),
+        "!=" => Some(Constant::Bool(a != b)),
+        _ => None,
+    }
+}
+
+/// `l op r`, folded when both operands are constants, or reduced to the operand when the
+/// other one is the identity of `op`
+pub(crate) fn binary(op: &str, l: ExprNodeRef, r: ExprNodeRef) -> ExprNodeOperation {
+    if let (Some(a), Some(b)) = (const_of(&l), const_of(&r)) {
+        if let Some(c) = fold_binary(op, &a, &b) {
+            return ExprNodeOperation::Const(c, None);
+        }
+    }
+
+    let keep_left = match op {
+        "+" | "-" | "|" | "^" | "<<" | ">>" => is_int_const(&r, 0),
+        "*" | "/" => is_int_const(&r, 1),
+        "&&" => is_bool_const(&r, true),
+        "||" => is_bool_const(&r, false),
+        _ => false,
+    };
+    if keep_left {
+        return l.borrow().operation.clone();
+    }
+
+    let keep_right = match op {
+        "+" | "|" | "^" => is_int_const(&l, 0),
+        "*" => is_int_const(&l, 1),
+        "&&" => is_bool_const(&l, true),
+        "||" => is_bool_const(&l, false),
+        _ => false,
+    };
+    if keep_right {
+        return r.borrow().operation.clone();
+    }
+
+    ExprNodeOperation::Binary(op.to_string(), l, r)
+}
+
+/// `op arg` with constant booleans folded and double negations removed
+pub(crate) fn unary(op: &str, arg: ExprNodeRef) -> ExprNodeOperation {
+    if op == "!" {
+        let simplified = match &arg.borrow().operation {
+            ExprNodeOperation::Const(Constant::Bool(x), _) => {
+                Some(ExprNodeOperation::Const(Constant::Bool(!x), None))
+            }
+            ExprNodeOperation::Unary(inner_op, inner) if inner_op == "!" => {
+                Some(inner.borrow().operation.clone())
+            }
+            _ => None,
+        };
+        if let Some(simplified) = simplified {
+            return simplified;
+        }
+    }
+    ExprNodeOperation::Unary(op.to_string(), arg)
+}
+
+/// `(arg as ty)` with constants converted when they fit, and repeated casts merged
+pub(crate) fn cast(ty: &str, arg: ExprNodeRef) -> ExprNodeOperation {
+    let simplified = match &arg.borrow().operation {
+        ExprNodeOperation::Const(c, _) => int_bits(ty)
+            .zip(int_const(c))
+            .and_then(|(bits, (_, value))| make_int(bits, value))
+            .map(|c| ExprNodeOperation::Const(c, None)),
+        ExprNodeOperation::Cast(inner_ty, _) if inner_ty == ty => {
+            Some(arg.borrow().operation.clone())
+        }
+        _ => None,
+    };
+    simplified.unwrap_or_else(|| ExprNodeOperation::Cast(ty.to_string(), arg))
+}
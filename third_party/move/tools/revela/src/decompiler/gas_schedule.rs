@@ -0,0 +1,51 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Gas costs used to annotate decompiled statements, see `Decompiler::with_gas_annotations`.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "gas-schedule")]
+use aptos_gas_schedule::{
+    AptosGasParameters, InitialGasSchedule, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
+};
+
+/// A gas schedule in its on-chain form: costs in internal gas units keyed by parameter name,
+/// e.g. `instr.call.base` or `txn.storage_io_per_state_slot_read`
+#[derive(Clone, Debug, Default)]
+pub struct GasSchedule {
+    entries: BTreeMap<String, u64>,
+}
+
+impl GasSchedule {
+    pub fn new(entries: BTreeMap<String, u64>) -> Self {
+        Self { entries }
+    }
+
+    /// The schedule of the latest gas feature version
+    #[cfg(feature = "gas-schedule")]
+    pub fn latest() -> Self {
+        Self::new(
+            AptosGasParameters::initial()
+                .to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION)
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    /// Cost of the parameter `key`, 0 when the schedule does not define it
+    pub(crate) fn get(&self, key: &str) -> u64 {
+        self.entries.get(key).copied().unwrap_or(0)
+    }
+
+    /// Base cost of the native function `module::function`, when the schedule names it after
+    /// the function, as in `move_stdlib.signer.borrow_address.base`
+    pub(crate) fn native_base(&self, module: &str, function: &str) -> Option<u64> {
+        let suffix = format!(".{}.{}.base", module, function);
+        self.entries
+            .iter()
+            .find(|(key, _)| key.ends_with(&suffix))
+            .map(|(_, cost)| *cost)
+    }
+}
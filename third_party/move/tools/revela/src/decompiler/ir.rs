@@ -0,0 +1,432 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The stackless bytecode revela reconstructs source from, after its optimizations and with the
+//! control flow structured, for tools that do their own analysis.
+//!
+//! The format is versioned by `IR_FORMAT_VERSION`, which is bumped on any change that could break
+//! a consumer: a renamed or removed field, a new node or terminator kind. Instructions are kept
+//! as rendered by `move-stackless-bytecode`, e.g. `$t3 := +($t1, $t2)`.
+//!
+//! A function body is a list of nodes:
+//!  - `block`: a basic block with its instructions and how it ends
+//!  - `if_else`: the branches taken on the condition ending the previous block
+//!  - `loop`: a loop whose condition, unless `unconditional`, ends the previous block, followed by
+//!    the code run once it exits
+//!
+//! The branch or jump instruction a structured terminator stands for is left out of the block.
+
+use std::{collections::HashSet, fmt};
+
+use log::Level;
+use move_binary_format::binary_views::BinaryIndexedView;
+use move_stackless_bytecode::{
+    function_target::FunctionTarget,
+    function_target_pipeline::{FunctionTargetPipeline, FunctionTargetsHolder, FunctionVariant},
+    stackless_bytecode::Bytecode,
+};
+use serde::Serialize;
+
+use super::{
+    cfg::{
+        self,
+        datastructs::{BasicBlock, CodeUnitBlock, HyperBlock, Terminator},
+        metadata::WithMetadata,
+        StacklessBlockContent,
+    },
+    naming::Naming,
+    DecompileError, Decompiler,
+};
+
+pub const IR_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IrFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IrModule {
+    pub format_version: u32,
+    /// `0x1::coin`, or `script`
+    pub name: String,
+    pub bytecode_version: u32,
+    pub functions: Vec<IrFunction>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IrFunction {
+    pub name: String,
+    /// the parameters are the first locals
+    pub parameter_count: usize,
+    /// types of the locals `$t0`, `$t1`, ...
+    pub locals: Vec<String>,
+    pub body: IrBody,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IrBody {
+    Native,
+    Structured {
+        nodes: Vec<IrNode>,
+    },
+    /// the control flow could not be structured, the instructions are given in order
+    Unstructured {
+        error: String,
+        instructions: Vec<IrInstruction>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IrNode {
+    Block {
+        index: usize,
+        instructions: Vec<IrInstruction>,
+        terminator: IrTerminator,
+    },
+    IfElse {
+        then_nodes: Vec<IrNode>,
+        else_nodes: Vec<IrNode>,
+    },
+    Loop {
+        unconditional: bool,
+        body: Vec<IrNode>,
+        exit: Vec<IrNode>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IrInstruction {
+    /// offset of the instruction in the stackless bytecode before structuring
+    pub offset: usize,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IrTerminator {
+    /// falls through to the next node
+    Next,
+    Return,
+    Abort,
+    /// branches on the local `condition`, the following `if_else` node holds the branches
+    IfElse {
+        condition: Option<usize>,
+    },
+    /// loops while the local `condition` holds, the following `loop` node holds the body
+    While {
+        condition: Option<usize>,
+    },
+    Jump {
+        target: usize,
+    },
+    Break {
+        target: usize,
+    },
+    Continue {
+        target: usize,
+    },
+}
+
+impl<'a> Decompiler<'a> {
+    /// The stackless bytecode of every function, after the optimizations and with the control
+    /// flow structured, as decompilation would start from
+    pub fn emit_ir(&mut self) -> Result<Vec<IrModule>, DecompileError> {
+        let pipeline = self.module_pipeline();
+        let script_pipeline = FunctionTargetPipeline::default();
+        self.build_model(&Naming::new())?;
+
+        let mut modules = Vec::new();
+        for binary in self.binaries.clone() {
+            let module = self.module_for_binary(&binary);
+            let is_script = matches!(binary, BinaryIndexedView::Script(_));
+
+            let mut targets = FunctionTargetsHolder::default();
+            for f in module.get_functions() {
+                targets.add_target(&f);
+            }
+            if is_script {
+                script_pipeline.run(&self.env, &mut targets);
+            } else {
+                pipeline.run(&self.env, &mut targets);
+            }
+
+            let mut functions = module.get_functions().collect::<Vec<_>>();
+            functions.sort_by_key(|f| (f.get_def_idx(), f.get_name_str()));
+            let functions = functions
+                .iter()
+                .map(|f| {
+                    if f.is_native() {
+                        return IrFunction {
+                            name: f.get_name_str(),
+                            parameter_count: f.get_parameter_count(),
+                            locals: Vec::new(),
+                            body: IrBody::Native,
+                        };
+                    }
+                    let target = targets.get_target(f, &FunctionVariant::Baseline);
+                    let body = self.ir_body(&target);
+                    let type_ctx = f.get_type_display_ctx();
+                    IrFunction {
+                        name: f.get_name_str(),
+                        parameter_count: target.get_parameter_count(),
+                        locals: (0..target.get_local_count())
+                            .map(|idx| target.get_local_type(idx).display(&type_ctx).to_string())
+                            .collect(),
+                        body,
+                    }
+                })
+                .collect();
+
+            modules.push(IrModule {
+                format_version: IR_FORMAT_VERSION,
+                name: if is_script {
+                    "script".to_string()
+                } else {
+                    module.get_name().display_full(&self.env).to_string()
+                },
+                bytecode_version: binary.version(),
+                functions,
+            });
+        }
+        Ok(modules)
+    }
+
+    /// Write the IR of every function to `out` in `format`
+    pub fn emit_ir_to<W: std::io::Write>(
+        &mut self,
+        format: IrFormat,
+        out: &mut W,
+    ) -> Result<(), DecompileError> {
+        let modules = self.emit_ir()?;
+        match format {
+            IrFormat::Json => {
+                serde_json::to_writer_pretty(&mut *out, &modules)
+                    .map_err(|err| DecompileError::Io(err.into()))?;
+                writeln!(out)?;
+            }
+            IrFormat::Text => {
+                for module in &modules {
+                    write!(out, "{}", module)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn ir_body(&self, target: &FunctionTarget<'_>) -> IrBody {
+        let defined_vars: HashSet<_> = (0..target.get_parameter_count()).collect();
+        match cfg::stackless::decompile(target.get_bytecode(), &defined_vars) {
+            Ok(structured) => IrBody::Structured {
+                nodes: unit_nodes(target, &structured),
+            },
+            Err(err) => {
+                self.log(Level::Warn, || {
+                    format!(
+                        "{}: unable to structure the control flow: {}",
+                        target.func_env.get_full_name_str(),
+                        err
+                    )
+                });
+                IrBody::Unstructured {
+                    error: err.to_string(),
+                    instructions: target
+                        .get_bytecode()
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, bytecode)| instruction(target, offset, bytecode))
+                        .collect(),
+                }
+            }
+        }
+    }
+}
+
+fn instruction(target: &FunctionTarget<'_>, offset: usize, bytecode: &Bytecode) -> IrInstruction {
+    let label_offsets = Default::default();
+    IrInstruction {
+        offset,
+        text: bytecode.display(target, &label_offsets).to_string(),
+    }
+}
+
+fn unit_nodes(
+    target: &FunctionTarget<'_>,
+    unit: &WithMetadata<CodeUnitBlock<usize, StacklessBlockContent>>,
+) -> Vec<IrNode> {
+    let mut nodes = Vec::new();
+    for block in &unit.inner().blocks {
+        match block.inner() {
+            HyperBlock::ConnectedBlocks(blocks) => {
+                nodes.extend(blocks.iter().map(|b| block_node(target, b.inner())));
+            }
+            HyperBlock::IfElseBlocks { if_unit, else_unit } => nodes.push(IrNode::IfElse {
+                then_nodes: unit_nodes(target, if_unit),
+                else_nodes: unit_nodes(target, else_unit),
+            }),
+            HyperBlock::WhileBlocks {
+                inner,
+                outer,
+                unconditional,
+                ..
+            } => nodes.push(IrNode::Loop {
+                unconditional: *unconditional,
+                body: unit_nodes(target, inner),
+                exit: unit_nodes(target, outer),
+            }),
+        }
+    }
+    nodes
+}
+
+fn block_node(
+    target: &FunctionTarget<'_>,
+    block: &BasicBlock<usize, StacklessBlockContent>,
+) -> IrNode {
+    let mut code: Vec<_> = block.content.code.iter().filter(|x| !x.removed).collect();
+    let condition = match code.last().map(|x| &x.bytecode) {
+        Some(Bytecode::Branch(_, _, _, condition)) => Some(*condition),
+        _ => None,
+    };
+    let terminator = match &block.next {
+        Terminator::Normal => IrTerminator::Next,
+        Terminator::Ret => IrTerminator::Return,
+        Terminator::Abort => IrTerminator::Abort,
+        Terminator::IfElse { .. } => IrTerminator::IfElse { condition },
+        Terminator::While { .. } => IrTerminator::While { condition },
+        Terminator::Branch { target } => IrTerminator::Jump { target: *target },
+        Terminator::Break { target } => IrTerminator::Break { target: *target },
+        Terminator::Continue { target } => IrTerminator::Continue { target: *target },
+    };
+    let structured_jump = matches!(
+        (&block.next, code.last().map(|x| &x.bytecode)),
+        (
+            Terminator::IfElse { .. } | Terminator::While { .. },
+            Some(Bytecode::Branch(..))
+        ) | (
+            Terminator::Branch { .. } | Terminator::Break { .. } | Terminator::Continue { .. },
+            Some(Bytecode::Jump(..))
+        )
+    );
+    if structured_jump {
+        code.pop();
+    }
+
+    IrNode::Block {
+        index: block.idx,
+        instructions: code
+            .iter()
+            .map(|x| instruction(target, x.original_offset, &x.bytecode))
+            .collect(),
+        terminator,
+    }
+}
+
+impl fmt::Display for IrModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "// revela IR v{}, bytecode v{}",
+            self.format_version, self.bytecode_version
+        )?;
+        writeln!(f, "{} {{", self.name)?;
+        for function in &self.functions {
+            writeln!(f)?;
+            let params = function.locals.iter().take(function.parameter_count);
+            let params: Vec<_> = params
+                .enumerate()
+                .map(|(idx, ty)| format!("$t{}: {}", idx, ty))
+                .collect();
+            writeln!(f, "    fun {}({}) {{", function.name, params.join(", "))?;
+            for (idx, ty) in function.locals.iter().enumerate() {
+                if idx >= function.parameter_count {
+                    writeln!(f, "        local $t{}: {}", idx, ty)?;
+                }
+            }
+            match &function.body {
+                IrBody::Native => writeln!(f, "        native")?,
+                IrBody::Structured { nodes } => write_nodes(f, nodes, 2)?,
+                IrBody::Unstructured {
+                    error,
+                    instructions,
+                } => {
+                    writeln!(f, "        unstructured: {}", error)?;
+                    for instruction in instructions {
+                        write_instruction(f, instruction, 2)?;
+                    }
+                }
+            }
+            writeln!(f, "    }}")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+fn write_instruction(f: &mut fmt::Formatter<'_>, x: &IrInstruction, depth: usize) -> fmt::Result {
+    writeln!(f, "{}{:>4}: {}", "    ".repeat(depth), x.offset, x.text)
+}
+
+fn write_nodes(f: &mut fmt::Formatter<'_>, nodes: &[IrNode], depth: usize) -> fmt::Result {
+    let indent = "    ".repeat(depth);
+    for node in nodes {
+        match node {
+            IrNode::Block {
+                index,
+                instructions,
+                terminator,
+            } => {
+                writeln!(f, "{}block {}:", indent, index)?;
+                for instruction in instructions {
+                    write_instruction(f, instruction, depth + 1)?;
+                }
+                let local =
+                    |x: &Option<usize>| x.map_or_else(|| "?".to_string(), |x| format!("$t{}", x));
+                let terminator = match terminator {
+                    IrTerminator::Next => None,
+                    IrTerminator::Return => Some("return".to_string()),
+                    IrTerminator::Abort => Some("abort".to_string()),
+                    IrTerminator::IfElse { condition } => Some(format!("if {}", local(condition))),
+                    IrTerminator::While { condition } => {
+                        Some(format!("while {}", local(condition)))
+                    }
+                    IrTerminator::Jump { target } => Some(format!("jump block {}", target)),
+                    IrTerminator::Break { target } => Some(format!("break to block {}", target)),
+                    IrTerminator::Continue { target } => {
+                        Some(format!("continue to block {}", target))
+                    }
+                };
+                if let Some(terminator) = terminator {
+                    writeln!(f, "{}    => {}", indent, terminator)?;
+                }
+            }
+            IrNode::IfElse {
+                then_nodes,
+                else_nodes,
+            } => {
+                writeln!(f, "{}then {{", indent)?;
+                write_nodes(f, then_nodes, depth + 1)?;
+                if !else_nodes.is_empty() {
+                    writeln!(f, "{}}} else {{", indent)?;
+                    write_nodes(f, else_nodes, depth + 1)?;
+                }
+                writeln!(f, "{}}}", indent)?;
+            }
+            IrNode::Loop {
+                unconditional,
+                body,
+                exit,
+            } => {
+                let keyword = if *unconditional { "loop" } else { "do" };
+                writeln!(f, "{}{} {{", indent, keyword)?;
+                write_nodes(f, body, depth + 1)?;
+                writeln!(f, "{}}}", indent)?;
+                write_nodes(f, exit, depth)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Aptos runtime module metadata, as attached by the Aptos framework builder.
+//!
+//! The layout mirrors `RuntimeModuleMetadataV1` from `aptos-framework`, which cannot be
+//! depended on from here.
+
+use std::collections::BTreeMap;
+
+use move_core_types::{errmap::ErrorDescription, metadata::Metadata};
+use serde::Deserialize;
+
+const APTOS_METADATA_KEY: &[u8] = "aptos::metadata_v0".as_bytes();
+const APTOS_METADATA_KEY_V1: &[u8] = "aptos::metadata_v1".as_bytes();
+
+const LEGACY_VIEW_FUNCTION: u8 = 0;
+const VIEW_FUNCTION: u8 = 1;
+const RESOURCE_GROUP: u8 = 2;
+const RESOURCE_GROUP_MEMBER: u8 = 3;
+const EVENT: u8 = 4;
+const RANDOMNESS: u8 = 5;
+
+#[derive(Deserialize)]
+struct RuntimeModuleMetadata {
+    error_map: BTreeMap<u64, ErrorDescription>,
+}
+
+#[derive(Deserialize, Default)]
+pub(crate) struct RuntimeModuleMetadataV1 {
+    pub(crate) error_map: BTreeMap<u64, ErrorDescription>,
+    struct_attributes: BTreeMap<String, Vec<KnownAttribute>>,
+    fun_attributes: BTreeMap<String, Vec<KnownAttribute>>,
+}
+
+#[derive(Deserialize)]
+struct KnownAttribute {
+    kind: u8,
+    args: Vec<String>,
+}
+
+impl KnownAttribute {
+    fn to_source(&self) -> Option<String> {
+        match self.kind {
+            LEGACY_VIEW_FUNCTION | VIEW_FUNCTION => Some("#[view]".to_string()),
+            EVENT => Some("#[event]".to_string()),
+            RESOURCE_GROUP => Some(format!("#[resource_group(scope = {})]", self.args.first()?)),
+            RESOURCE_GROUP_MEMBER => Some(format!(
+                "#[resource_group_member(group = {})]",
+                self.args.first()?
+            )),
+            RANDOMNESS => match self.args.first() {
+                Some(max_gas) => Some(format!("#[randomness(max_gas = {})]", max_gas)),
+                None => Some("#[randomness]".to_string()),
+            },
+            _ => None,
+        }
+    }
+}
+
+fn is_constant_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl RuntimeModuleMetadataV1 {
+    /// Extract the metadata, upgrading V0 to V1 representation as needed
+    pub(crate) fn from_metadata(metadata: &[Metadata]) -> Option<Self> {
+        if let Some(data) = metadata.iter().find(|md| md.key == APTOS_METADATA_KEY_V1) {
+            bcs::from_bytes::<RuntimeModuleMetadataV1>(&data.value).ok()
+        } else if let Some(data) = metadata.iter().find(|md| md.key == APTOS_METADATA_KEY) {
+            bcs::from_bytes::<RuntimeModuleMetadata>(&data.value)
+                .ok()
+                .map(|md| RuntimeModuleMetadataV1 {
+                    error_map: md.error_map,
+                    ..Default::default()
+                })
+        } else {
+            None
+        }
+    }
+
+    fn attributes(attributes: Option<&Vec<KnownAttribute>>) -> Vec<String> {
+        attributes
+            .map(|attributes| attributes.iter().filter_map(|x| x.to_source()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Error codes whose constant name can be declared in source, by value
+    pub(crate) fn error_constants(&self) -> BTreeMap<u64, String> {
+        self.error_map
+            .iter()
+            .filter(|(_, description)| is_constant_name(&description.code_name))
+            .map(|(code, description)| (*code, description.code_name.clone()))
+            .collect()
+    }
+
+    /// Source attributes of the struct, e.g. `#[event]`
+    pub(crate) fn struct_attributes(&self, name: &str) -> Vec<String> {
+        Self::attributes(self.struct_attributes.get(name))
+    }
+
+    /// Source attributes of the function, e.g. `#[view]`
+    pub(crate) fn fun_attributes(&self, name: &str) -> Vec<String> {
+        Self::attributes(self.fun_attributes.get(name))
+    }
+}
@@ -2,48 +2,130 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet},
+    io::Write,
+    rc::Rc,
+};
 
 use anyhow::{Ok, Result};
+use log::{Level, LevelFilter};
 use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
-    file_format::{AbilitySet, StructHandle},
+    file_format::{
+        AbilitySet, AccessKind, AddressSpecifier, ModuleHandleIndex, ResourceSpecifier,
+        SignatureToken, StructDefinitionIndex,
+    },
+    file_format_common::{VERSION_MAX, VERSION_MIN},
 };
-use move_bytecode_source_map::source_map::SourceMap;
+use move_bytecode_source_map::source_map::SourceMap as BytecodeSourceMap;
+use move_core_types::{account_address::AccountAddress, language_storage};
+use sha3::{Digest, Sha3_256};
 
 use move_model::{
     ast::Address,
-    model::{FunctionEnv, GlobalEnv, ModuleEnv, StructEnv},
+    model::{FunctionEnv, GlobalEnv, ModuleEnv, ModuleId, StructEnv},
     ty::{PrimitiveType, ReferenceKind, Type},
 };
 use move_stackless_bytecode::{
     function_target::FunctionTarget,
-    function_target_pipeline::{FunctionTargetPipeline, FunctionTargetsHolder, FunctionVariant},
+    function_target_pipeline::{
+        FunctionTargetPipeline, FunctionTargetProcessor, FunctionTargetsHolder, FunctionVariant,
+    },
     reaching_def_analysis::ReachingDefProcessor,
+    stackless_bytecode::Bytecode,
 };
 
+use model::deobfuscation::DeobfuscationProcessor;
 use model::livevar_analysis::LiveVarAnalysisProcessor;
 use model::peephole_analysis::PeepHoleProcessor;
 
+pub use self::aliases::AddressAliases;
+pub use self::binary::CompiledBinary;
+pub use self::cache::{DecompileCache, MemoryCache};
+pub use self::error::DecompileError;
+pub use self::gas_schedule::GasSchedule;
+pub use self::ir::{
+    IrBody, IrFormat, IrFunction, IrInstruction, IrModule, IrNode, IrTerminator, IR_FORMAT_VERSION,
+};
+pub use self::progress::{CancellationToken, DecompileProgress};
 use self::reconstruct::code_unit::SourceCodeUnit;
-pub use self::reconstruct::OptimizerSettings;
-
+pub use self::reconstruct::{BraceStyle, FormatOptions, OptimizerSettings};
+pub use self::source_map::{FunctionSpan, ModuleSpan, SourceMap};
+#[cfg(feature = "verify")]
+pub use self::verify::compile_package;
+pub use self::verify::{verify_modules, MatchStatus, ModuleVerification, VerificationReport};
+
+mod acquires;
+mod aliases;
 mod bin_to_compiler_translator;
+mod binary;
+mod cache;
 mod cfg;
+mod error;
 mod evaluator;
+mod gas_schedule;
+mod ir;
+mod metadata;
 mod model;
 mod naming;
+mod package;
+mod progress;
 mod reconstruct;
+mod source_map;
 mod stackless_bytecode_display;
+mod test_only;
 mod utils;
+mod verify;
 
+use self::metadata::RuntimeModuleMetadataV1;
 use self::naming::Naming;
+use self::source_map::LineCounter;
+use self::test_only::TestAttribute;
 
 pub struct Decompiler<'a> {
     env: GlobalEnv,
     binaries: Vec<BinaryIndexedView<'a>>,
     optimizer_settings: OptimizerSettings,
+    format_options: FormatOptions,
+    progress: Option<Box<dyn DecompileProgress + 'a>>,
+    cache: Option<Box<dyn DecompileCache + 'a>>,
+    module_hashes: cache::ModuleHashes,
+    cancellation: Option<CancellationToken>,
+    verbosity: LevelFilter,
+    spec_skeletons: bool,
+    provenance: bool,
+    interleaved_bytecode: bool,
+    raw_bytecode: bool,
+    structured_bytecode: bool,
+    /// names of the only functions decompiled, all of them if `None`
+    function_selector: Option<HashSet<String>>,
+    address_aliases: Option<AddressAliases>,
+    gas_schedule: Option<Rc<GasSchedule>>,
+    /// declare the friends of each module, only valid when they are decompiled together
+    friend_declarations: bool,
+    processors: Vec<Box<dyn FunctionTargetProcessor>>,
+    /// processors were added with `with_processor`, their effect on function bodies is not
+    /// captured by cache keys
+    custom_processors: bool,
+    /// rendered struct and vector types, type parameters are always named `T<idx>` so the
+    /// rendering only depends on the module it appears in
+    type_cache: RefCell<HashMap<(ModuleId, Type), String>>,
+}
+
+/// A function signature split so that parameters can be wrapped
+struct FunctionHeader {
+    head: String,
+    params: Vec<String>,
+    tail: String,
+}
+
+impl FunctionHeader {
+    fn single_line(&self) -> String {
+        format!("{}({}){}", self.head, self.params.join(", "), self.tail)
+    }
 }
 
 impl<'a> Decompiler<'a> {
@@ -56,6 +138,152 @@ impl<'a> Decompiler<'a> {
             env,
             binaries,
             optimizer_settings,
+            format_options: FormatOptions::default(),
+            progress: None,
+            cache: None,
+            module_hashes: Default::default(),
+            cancellation: None,
+            verbosity: LevelFilter::Warn,
+            spec_skeletons: false,
+            provenance: false,
+            interleaved_bytecode: false,
+            raw_bytecode: false,
+            structured_bytecode: false,
+            function_selector: None,
+            address_aliases: None,
+            gas_schedule: None,
+            friend_declarations: false,
+            processors: Vec::new(),
+            custom_processors: false,
+            type_cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_format_options(mut self, format_options: FormatOptions) -> Self {
+        self.format_options = format_options;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: impl DecompileProgress + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Reuse function bodies decompiled by earlier runs sharing the cache, bypassed once custom
+    /// processors are added with `with_processor`
+    pub fn with_cache(mut self, cache: impl DecompileCache + 'a) -> Self {
+        self.cache = Some(Box::new(cache));
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Follow every module function with a `spec` block, holding the `aborts_if` conditions
+    /// of its opening guards
+    pub fn with_spec_skeletons(mut self, enabled: bool) -> Self {
+        self.spec_skeletons = enabled;
+        self
+    }
+
+    /// Extend the footer of every module with its address and name, the sha3-256 of its
+    /// bytecode, the decompiler version and the options used
+    pub fn with_provenance(mut self, enabled: bool) -> Self {
+        self.provenance = enabled;
+        self
+    }
+
+    /// Follow each reconstructed statement with a comment listing the stackless bytecode
+    /// instructions it was built from, with their offsets in the original bytecode
+    pub fn with_interleaved_bytecode(mut self, enabled: bool) -> Self {
+        self.interleaved_bytecode = enabled;
+        self
+    }
+
+    /// Precede each function body with its stackless bytecode as comments, as produced by the
+    /// bytecode processors
+    pub fn with_raw_bytecode(mut self, enabled: bool) -> Self {
+        self.raw_bytecode = enabled;
+        self
+    }
+
+    /// Precede each function body with its stackless bytecode as comments, once its control
+    /// flow is restructured
+    pub fn with_structured_bytecode(mut self, enabled: bool) -> Self {
+        self.structured_bytecode = enabled;
+        self
+    }
+
+    /// Only decompile the functions with these names, leaving the others out of the output,
+    /// e.g. to investigate a single function of a large module
+    pub fn with_function_selector(mut self, names: impl IntoIterator<Item = String>) -> Self {
+        self.function_selector = Some(names.into_iter().collect());
+        self
+    }
+
+    /// Render module paths with the named addresses of `aliases`, e.g.
+    /// `aptos_framework::coin::Coin` instead of `0x1::coin::Coin`
+    pub fn with_address_aliases(mut self, aliases: AddressAliases) -> Self {
+        self.address_aliases = Some(aliases);
+        self
+    }
+
+    /// Annotate storage accesses, native calls and loops with their estimated cost under
+    /// `schedule`, as trailing comments such as `// gas ~302.4k, 1 storage read`
+    pub fn with_gas_annotations(mut self, schedule: GasSchedule) -> Self {
+        self.gas_schedule = Some(Rc::new(schedule));
+        self
+    }
+
+    /// Most verbose diagnostics forwarded to the `log` facade, `Trace` also dumps the stackless
+    /// bytecode of every function
+    pub fn with_verbosity(mut self, verbosity: LevelFilter) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Run `processor` on the stackless bytecode of module functions, after the peephole
+    /// optimization and before the analyses source generation relies on. Processors run in the
+    /// order they are added.
+    pub fn with_processor(mut self, processor: Box<dyn FunctionTargetProcessor>) -> Self {
+        self.processors.push(processor);
+        self.custom_processors = true;
+        self
+    }
+
+    /// `address` as rendered in module paths, aliased if requested
+    fn display_address(&self, address: &AccountAddress) -> String {
+        match &self.address_aliases {
+            Some(aliases) => aliases.display_address(address),
+            None => address.to_hex_literal(),
+        }
+    }
+
+    fn provenance_lines(&self, binary: &BinaryIndexedView, name: String) -> Result<Vec<String>> {
+        let mut bytecode = Vec::new();
+        match binary {
+            BinaryIndexedView::Module(compiled) => compiled.serialize(&mut bytecode)?,
+            BinaryIndexedView::Script(compiled) => compiled.serialize(&mut bytecode)?,
+        }
+        Ok(vec![
+            format!("source: {}", name),
+            format!(
+                "bytecode sha3-256: {}",
+                hex::encode(Sha3_256::digest(&bytecode))
+            ),
+            format!("decompiler: revela {}", env!("CARGO_PKG_VERSION")),
+            format!("optimizer settings: {:?}", self.optimizer_settings),
+            format!("format options: {:?}", self.format_options),
+            format!("spec skeletons: {}", self.spec_skeletons),
+            format!("gas annotations: {}", self.gas_schedule.is_some()),
+        ])
+    }
+
+    fn log(&self, level: Level, message: impl FnOnce() -> String) {
+        if level <= self.verbosity && log::log_enabled!(level) {
+            log::log!(level, "{}", message());
         }
     }
 
@@ -64,6 +292,25 @@ impl<'a> Decompiler<'a> {
         current_module: &ModuleEnv<'_>,
         ty: &Type,
         naming: &Naming,
+    ) -> Result<String> {
+        if !matches!(ty, Type::Struct(..) | Type::Vector(..)) {
+            return self.render_type(current_module, ty, naming);
+        }
+
+        let key = (current_module.get_id(), ty.clone());
+        if let Some(rendered) = self.type_cache.borrow().get(&key) {
+            return Ok(rendered.clone());
+        }
+        let rendered = self.render_type(current_module, ty, naming)?;
+        self.type_cache.borrow_mut().insert(key, rendered.clone());
+        Ok(rendered)
+    }
+
+    fn render_type(
+        &self,
+        current_module: &ModuleEnv<'_>,
+        ty: &Type,
+        naming: &Naming,
     ) -> Result<String> {
         match ty {
             Type::Primitive(PrimitiveType::Bool) => Ok("bool".to_string()),
@@ -112,11 +359,15 @@ impl<'a> Decompiler<'a> {
                 let module = env.get_module(*mid);
                 let struct_env = module.get_struct(*sid);
                 let struct_name = struct_env.get_name();
-                let struct_name_display = struct_name.display(env.symbol_pool());
+                let struct_name_display = struct_name.display(env.symbol_pool()).to_string();
                 let mut buf = String::new();
 
-                buf.push_str(utils::shortest_prefix(current_module, mid).as_str());
-                buf.push_str(struct_name_display.to_string().as_str());
+                buf.push_str(&naming.path(&utils::shortest_prefix(
+                    current_module,
+                    mid,
+                    &struct_name_display,
+                )));
+                buf.push_str(struct_name_display.as_str());
                 if !tys.is_empty() {
                     buf.push_str("<");
                     buf.push_str(
@@ -164,7 +415,6 @@ impl<'a> Decompiler<'a> {
 
     fn decompile_struct(
         &self,
-        struct_bin: &StructHandle,
         struct_env: &StructEnv<'_>,
         naming: &Naming,
     ) -> Result<SourceCodeUnit> {
@@ -186,19 +436,13 @@ impl<'a> Decompiler<'a> {
             buf.push_str(
                 type_parameters
                     .iter()
-                    .zip(struct_bin.type_parameters.iter())
                     .enumerate()
-                    .map(|(idx, (tp_from_env, tp_from_binary))| {
+                    .map(|(idx, x)| {
                         format!(
                             "{}{}{}",
-                            // phantom information is not populated to struct_env
-                            if tp_from_binary.is_phantom {
-                                "phantom "
-                            } else {
-                                ""
-                            },
+                            if x.1.is_phantom { "phantom " } else { "" },
                             naming.templated_type(idx),
-                            self.decompile_abilityset(tp_from_env.1.abilities, ": ", " + ")
+                            self.decompile_abilityset(x.1.abilities, ": ", " + ")
                         )
                     })
                     .collect::<Vec<_>>()
@@ -216,10 +460,7 @@ impl<'a> Decompiler<'a> {
             );
         }
 
-        buf.push_str(" {");
-        res.add_line(buf);
-
-        let mut fields_block = SourceCodeUnit::new(1);
+        let mut fields = Vec::new();
         for field in struct_env.get_fields() {
             let mut buf = String::new();
             buf.push_str(
@@ -234,8 +475,25 @@ impl<'a> Decompiler<'a> {
                 self.inline_decompile_type(&struct_env.module_env, &field.get_type(), naming)?
                     .as_str(),
             );
-            buf.push_str(",");
-            fields_block.add_line(buf);
+            fields.push(buf);
+        }
+
+        // structs are emitted at module level, one indentation deep
+        let single_line = if fields.is_empty() {
+            format!("{} {{}}", buf)
+        } else {
+            format!("{} {{ {} }}", buf, fields.join(", "))
+        };
+        if self.format_options.fits_in_line(1, &single_line) {
+            res.add_line(single_line);
+            return Ok(res);
+        }
+
+        res.add_block_header(buf, &self.format_options);
+
+        let mut fields_block = SourceCodeUnit::new(1);
+        for field in fields {
+            fields_block.add_line(format!("{},", field));
         }
 
         res.add_block(fields_block);
@@ -249,23 +507,28 @@ impl<'a> Decompiler<'a> {
         function_env: &FunctionEnv<'_>,
         naming: &Naming,
         is_script: bool,
-    ) -> Result<String> {
+        acquires: Option<&BTreeSet<StructDefinitionIndex>>,
+        binary: &BinaryIndexedView,
+    ) -> Result<FunctionHeader> {
         let mut buf = String::new();
 
-        if function_env.is_native() {
-            buf.push_str("native ");
-        }
+        // the main function of a script has no visibility, and any name
+        if !is_script {
+            if function_env.is_native() {
+                buf.push_str("native ");
+            }
 
-        buf.push_str(function_env.visibility_str());
+            buf.push_str(function_env.visibility_str());
 
-        if function_env.is_entry() {
-            buf.push_str("entry ");
+            if function_env.is_entry() {
+                buf.push_str("entry ");
+            }
         }
 
         buf.push_str("fun ");
 
         if is_script && function_env.is_entry() {
-            buf.push_str("script$main");
+            buf.push_str("main");
         } else {
             buf.push_str(
                 function_env
@@ -298,25 +561,20 @@ impl<'a> Decompiler<'a> {
             buf.push_str(">");
         }
 
-        buf.push_str("(");
-        buf.push_str(
-            function_env
-                .get_parameters()
-                .iter()
-                .enumerate()
-                .map(|(idx, x)| {
-                    format!(
-                        "{}: {}",
-                        naming.argument(idx),
-                        self.inline_decompile_type(&function_env.module_env, &x.1, &naming)
-                            .unwrap()
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join(", ")
-                .as_str(),
-        );
-        buf.push_str(")");
+        let head = std::mem::take(&mut buf);
+        let params = function_env
+            .get_parameters()
+            .iter()
+            .enumerate()
+            .map(|(idx, x)| {
+                format!(
+                    "{}: {}",
+                    naming.argument(idx),
+                    self.inline_decompile_type(&function_env.module_env, &x.1, &naming)
+                        .unwrap()
+                )
+            })
+            .collect::<Vec<_>>();
 
         if function_env.get_return_count() > 0 {
             buf.push_str(" : ");
@@ -331,28 +589,213 @@ impl<'a> Decompiler<'a> {
             );
         }
 
-        if let Some(resources) = function_env.get_acquires_global_resources() {
-            if !resources.is_empty() {
-                buf.push_str(" acquires ");
-                buf.push_str(
-                    resources
-                        .iter()
-                        .map(|x| {
-                            let module_env = &function_env.module_env;
-                            let struct_env = module_env.get_struct(*x);
-                            struct_env
-                                .get_name()
-                                .display(module_env.symbol_pool())
-                                .to_string()
-                        })
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                        .as_str(),
-                );
+        let module_env = &function_env.module_env;
+        let resources = match acquires {
+            Some(acquires) => self.struct_names(module_env, acquires),
+            None => function_env
+                .get_acquires_global_resources()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|x| {
+                    module_env
+                        .get_struct(x)
+                        .get_name()
+                        .display(module_env.symbol_pool())
+                        .to_string()
+                })
+                .collect(),
+        };
+        if !resources.is_empty() {
+            buf.push_str(" acquires ");
+            buf.push_str(resources.join(", ").as_str());
+        }
+
+        if let Some(specifiers) = self.decompile_access_specifiers(function_env, naming, binary)? {
+            buf.push_str(specifiers.as_str());
+        }
+
+        Ok(FunctionHeader {
+            head,
+            params,
+            tail: buf,
+        })
+    }
+
+    /// Resource access clauses of the function, e.g. ` reads R(arg0) writes 0x1::m::*`
+    fn decompile_access_specifiers(
+        &self,
+        function_env: &FunctionEnv<'_>,
+        naming: &Naming,
+        binary: &BinaryIndexedView,
+    ) -> Result<Option<String>> {
+        let (BinaryIndexedView::Module(_), Some(def_idx)) = (binary, function_env.get_def_idx())
+        else {
+            return Ok(None);
+        };
+        let handle = binary.function_handle_at(binary.function_def_at(def_idx)?.function);
+        let Some(specifiers) = &handle.access_specifiers else {
+            return Ok(None);
+        };
+        if specifiers.is_empty() {
+            return Ok(Some(" pure".to_string()));
+        }
+
+        let module_env = &function_env.module_env;
+        let module_prefix = |module: ModuleHandleIndex| {
+            if Some(module) == binary.self_handle_idx() {
+                String::new()
+            } else {
+                let id = binary.module_id_for_handle(binary.module_handle_at(module));
+                format!("{}::{}::", self.display_address(id.address()), id.name())
+            }
+        };
+        let resource_type = |token: SignatureToken| match module_env.globalize_signature(&token) {
+            Some(ty) => self.inline_decompile_type(module_env, &ty, naming),
+            None => Err(anyhow::anyhow!("Cannot resolve access specifier resource")),
+        };
+
+        let mut clauses: Vec<(String, Vec<String>)> = Vec::new();
+        // old style acquires are already rendered in the acquires clause
+        for specifier in specifiers.iter().filter(|x| !x.is_old_style_acquires()) {
+            let kind = match specifier.kind {
+                AccessKind::Reads => "reads",
+                AccessKind::Writes => "writes",
+                AccessKind::Acquires => "acquires",
+            };
+            let kind = if specifier.negated {
+                format!("!{}", kind)
+            } else {
+                kind.to_string()
+            };
+
+            let resource = match &specifier.resource {
+                ResourceSpecifier::Any => "*".to_string(),
+                ResourceSpecifier::DeclaredAtAddress(address) => format!(
+                    "{}::*::*",
+                    self.display_address(binary.address_identifier_at(*address))
+                ),
+                ResourceSpecifier::DeclaredInModule(module) => {
+                    format!("{}*", module_prefix(*module))
+                }
+                ResourceSpecifier::Resource(handle) => {
+                    resource_type(SignatureToken::Struct(*handle))?
+                }
+                ResourceSpecifier::ResourceInstantiation(handle, types) => {
+                    resource_type(SignatureToken::StructInstantiation(
+                        *handle,
+                        binary.signature_at(*types).0.clone(),
+                    ))?
+                }
+            };
+
+            let address = match &specifier.address {
+                AddressSpecifier::Any => String::new(),
+                AddressSpecifier::Literal(address) => format!(
+                    "(@{})",
+                    self.display_address(binary.address_identifier_at(*address))
+                ),
+                AddressSpecifier::Parameter(idx, None) => {
+                    format!("({})", naming.argument(*idx as usize))
+                }
+                AddressSpecifier::Parameter(idx, Some(fun)) => {
+                    let handle =
+                        binary.function_handle_at(binary.function_instantiation_at(*fun).handle);
+                    format!(
+                        "({}{}({}))",
+                        module_prefix(handle.module),
+                        binary.identifier_at(handle.name),
+                        naming.argument(*idx as usize)
+                    )
+                }
+            };
+
+            let access = format!("{}{}", resource, address);
+            match clauses.last_mut() {
+                Some((last_kind, accesses)) if *last_kind == kind => accesses.push(access),
+                _ => clauses.push((kind, vec![access])),
             }
         }
 
-        Ok(buf)
+        Ok(Some(
+            clauses
+                .into_iter()
+                .map(|(kind, accesses)| format!(" {} {}", kind, accesses.join(", ")))
+                .collect(),
+        ))
+    }
+
+    fn struct_names(
+        &self,
+        module_env: &ModuleEnv<'_>,
+        structs: &BTreeSet<StructDefinitionIndex>,
+    ) -> Vec<String> {
+        structs
+            .iter()
+            .map(|x| {
+                let name = module_env
+                    .get_struct_by_def_idx(*x)
+                    .get_name()
+                    .display(module_env.symbol_pool())
+                    .to_string();
+                format!(
+                    "{}{}",
+                    utils::shortest_prefix(module_env, &module_env.get_id(), &name),
+                    name
+                )
+            })
+            .collect()
+    }
+
+    fn add_function_header(
+        &self,
+        unit: &mut SourceCodeUnit,
+        header: &FunctionHeader,
+        is_native: bool,
+    ) {
+        let mut last_line = header.single_line();
+        // functions are emitted at module level, one indentation deep
+        if !header.params.is_empty() && self.format_options.exceeds_line(1, &last_line) {
+            unit.add_line(format!("{}(", header.head));
+            let mut params_block = SourceCodeUnit::new(1);
+            for param in &header.params {
+                params_block.add_line(format!("{},", param));
+            }
+            unit.add_block(params_block);
+            last_line = format!("){}", header.tail);
+        }
+
+        if is_native {
+            unit.add_line(format!("{};", last_line));
+        } else {
+            unit.add_block_header(last_line, &self.format_options);
+        }
+    }
+
+    /// Body of a function whose control flow cannot be structured: its stackless bytecode as
+    /// comments, one section per label. It does not implement the function, the decompilation
+    /// reports `DecompileError::UnstructuredControlFlow` once the other declarations are written.
+    fn unstructured_body(
+        &self,
+        function_target: &FunctionTarget<'_>,
+        err: &anyhow::Error,
+    ) -> SourceCodeUnit {
+        let mut unit = SourceCodeUnit::new(0);
+        unit.add_line(format!(
+            "// warning: unable to structure the control flow: {}",
+            err.to_string().replace('\n', " ")
+        ));
+        unit.add_line("// the bytecode is kept below, this body does not implement it".to_string());
+        let label_offsets = Default::default();
+        for bytecode in function_target.get_bytecode() {
+            let line = bytecode
+                .display(function_target, &label_offsets)
+                .to_string();
+            match bytecode {
+                Bytecode::Label(..) => unit.add_line(format!("// {}:", line)),
+                _ => unit.add_line(format!("//     {}", line)),
+            }
+        }
+        unit
     }
 
     fn module_for_binary(&self, binary: &BinaryIndexedView) -> ModuleEnv<'_> {
@@ -393,18 +836,67 @@ impl<'a> Decompiler<'a> {
         }
     }
 
-    pub fn decompile(&mut self) -> Result<String> {
+    pub fn decompile(&mut self) -> std::result::Result<String, DecompileError> {
+        let (source, result) = self.decompile_partial();
+        result.map(|_| source)
+    }
+
+    /// Decompile into a string, keeping the declarations reconstructed before a failure
+    pub fn decompile_partial(&mut self) -> (String, std::result::Result<(), DecompileError>) {
+        let mut buf = Vec::new();
+        let result = self.decompile_to(&mut buf);
+        // declarations are written whole, so the output is valid UTF-8
+        (String::from_utf8_lossy(&buf).into_owned(), result)
+    }
+
+    /// Processors run on the stackless bytecode of module functions, scripts are left as is
+    fn module_pipeline(&mut self) -> FunctionTargetPipeline {
         let mut pipeline = FunctionTargetPipeline::default();
         pipeline.set_max_loop(32);
         pipeline.add_processor(PeepHoleProcessor::new(32));
+        if self.optimizer_settings.deobfuscate {
+            pipeline.add_processor(DeobfuscationProcessor::new(32));
+            pipeline.add_processor(PeepHoleProcessor::new(32));
+        }
+        for processor in std::mem::take(&mut self.processors) {
+            pipeline.add_processor(processor);
+        }
         pipeline.add_processor(ReachingDefProcessor::new());
         pipeline.add_processor(LiveVarAnalysisProcessor::new());
+        pipeline
+    }
 
-        let script_pipeline = FunctionTargetPipeline::default();
-
-        let naming = Naming::new();
+    /// Check the binaries and populate the model with them, returning the ids of the modules
+    fn build_model(
+        &mut self,
+        naming: &Naming,
+    ) -> std::result::Result<HashSet<language_storage::ModuleId>, DecompileError> {
+        // members of other modules are referred to by their full name, so the combined output
+        // compiles as long as every module is defined once
+        let mut module_ids = HashSet::new();
+        for binary in &self.binaries {
+            if let BinaryIndexedView::Module(compiled) = binary {
+                if !module_ids.insert(compiled.self_id()) {
+                    return Err(DecompileError::DuplicateModule(
+                        compiled.self_id().to_string(),
+                    ));
+                }
+            }
+            let version = binary.version();
+            if !(VERSION_MIN..=VERSION_MAX).contains(&version) {
+                return Err(DecompileError::UnsupportedBytecodeVersion {
+                    module: binary
+                        .self_id()
+                        .map_or_else(|| "script".to_string(), |id| id.to_string()),
+                    version,
+                    min: VERSION_MIN,
+                    max: VERSION_MAX,
+                });
+            }
+        }
 
-        let program = bin_to_compiler_translator::create_program(&self.binaries, &naming).unwrap();
+        let program = bin_to_compiler_translator::create_program(&self.binaries, naming)
+            .map_err(DecompileError::ModelBuild)?;
         model::demove_helper::run_stackless_compiler(&mut self.env, program);
 
         // all module must be populated before decompiling
@@ -413,18 +905,58 @@ impl<'a> Decompiler<'a> {
                 BinaryIndexedView::Module(compiled) => self.env.attach_compiled_module(
                     self.module_for_binary(&binary).get_id(),
                     (*compiled).clone(),
-                    SourceMap::new(bin_to_compiler_translator::fake_loc(), None),
+                    BytecodeSourceMap::new(bin_to_compiler_translator::fake_loc(), None),
                 ),
 
                 BinaryIndexedView::Script(compiled) => self.env.attach_compiled_module(
                     self.module_for_binary(&binary).get_id(),
                     bin_to_compiler_translator::script_into_module((*compiled).clone()),
-                    SourceMap::new(bin_to_compiler_translator::fake_loc(), None),
+                    BytecodeSourceMap::new(bin_to_compiler_translator::fake_loc(), None),
                 ),
             };
+            let module_id = self.module_for_binary(binary).get_id();
+            model::demove_helper::populate_binary_flags(&mut self.env, module_id);
         }
+        Ok(module_ids)
+    }
+
+    /// Decompile into `out`, writing each declaration as soon as it is reconstructed
+    pub fn decompile_to<W: std::io::Write>(
+        &mut self,
+        out: &mut W,
+    ) -> std::result::Result<(), DecompileError> {
+        self.decompile_mapped(out, &mut SourceMap::default())
+    }
+
+    /// Decompile into a string along with the lines taken by each module and function
+    pub fn decompile_with_source_map(
+        &mut self,
+    ) -> std::result::Result<(String, SourceMap), DecompileError> {
+        let mut buf = Vec::new();
+        let mut source_map = SourceMap::default();
+        self.decompile_mapped(&mut buf, &mut source_map)?;
+        std::result::Result::Ok((String::from_utf8_lossy(&buf).into_owned(), source_map))
+    }
+
+    fn decompile_mapped<W: std::io::Write>(
+        &mut self,
+        out: &mut W,
+        source_map: &mut SourceMap,
+    ) -> std::result::Result<(), DecompileError> {
+        let out = &mut LineCounter::new(out);
+        let pipeline = self.module_pipeline();
+        let script_pipeline = FunctionTargetPipeline::default();
+
+        let naming = Naming::new();
+        let module_ids = self.build_model(&naming)?;
+        let naming = match &self.address_aliases {
+            Some(aliases) => naming.with_address_aliases(aliases.clone()),
+            None => naming,
+        };
 
         let mut result = SourceCodeUnit::new(0);
+        // functions left as bytecode, reported once everything else is written
+        let mut unstructured = Vec::new();
 
         // decompile
         for binary in self.binaries.clone() {
@@ -437,130 +969,398 @@ impl<'a> Decompiler<'a> {
             }
 
             let is_script = matches!(binary, BinaryIndexedView::Script(_));
+            let mut module_span = ModuleSpan {
+                name: if is_script {
+                    "script".to_string()
+                } else {
+                    module.get_name().display_full(&self.env).to_string()
+                },
+                start_line: out.next_line(),
+                end_line: 0,
+                functions: Vec::new(),
+            };
+            self.log(Level::Info, || {
+                format!("decompiling {}", module.get_name().display_full(&self.env))
+            });
 
             if is_script {
                 script_pipeline.run(&self.env, &mut targets);
-                result.add_line(format!("script {{",));
+                result.add_block_header("script".to_string(), &self.format_options);
             } else {
                 pipeline.run(&self.env, &mut targets);
-                result.add_line(format!(
-                    "module {} {{",
-                    module.get_name().display_full(&self.env)
-                ));
+                result.add_block_header(
+                    format!(
+                        "module {}",
+                        naming.path(&module.get_name().display_full(&self.env).to_string())
+                    ),
+                    &self.format_options,
+                );
             }
 
             let naming = naming.with_type_display(|t, naming| {
                 self.inline_decompile_type(&module, t, naming).unwrap()
             });
+            // script arguments are named after their types, e.g. `account: &signer`
+            let naming = match module.get_functions().next().filter(|_| is_script) {
+                Some(main) => naming.with_argument_names(naming::argument_names_from_types(
+                    &self.env,
+                    &main.get_parameter_types(),
+                )),
+                None => naming,
+            };
+
+            let metadata = match binary {
+                BinaryIndexedView::Module(compiled) => {
+                    RuntimeModuleMetadataV1::from_metadata(&compiled.metadata)
+                }
+                BinaryIndexedView::Script(_) => None,
+            };
+
+            if let (true, Some(friends)) = (self.friend_declarations, binary.friend_decls()) {
+                let mut unit = SourceCodeUnit::new(1);
+                for friend in friends {
+                    let friend_id = binary.module_id_for_handle(friend);
+                    let declaration = format!(
+                        "friend {}::{};",
+                        self.display_address(friend_id.address()),
+                        friend_id.name()
+                    );
+                    if module_ids.contains(&friend_id) {
+                        unit.add_line(declaration);
+                    } else {
+                        // friends must be part of the same package
+                        unit.add_line(format!("// {} (not decompiled)", declaration));
+                    }
+                }
+                if !unit.is_empty() {
+                    unit.add_line("".to_string());
+                    result.add_block(unit);
+                }
+            }
+
+            let inferred_acquires = match binary {
+                BinaryIndexedView::Module(compiled) => acquires::infer_acquires(compiled),
+                BinaryIndexedView::Script(_) => Vec::new(),
+            };
+
+            let test_only = match binary {
+                BinaryIndexedView::Module(compiled) => test_only::infer_test_only(compiled),
+                BinaryIndexedView::Script(_) => Default::default(),
+            };
+
+            let error_constants = metadata
+                .as_ref()
+                .map(|x| x.error_constants())
+                .unwrap_or_default();
+
+            if let Some(metadata) = metadata.as_ref().filter(|x| !x.error_map.is_empty()) {
+                let mut unit = SourceCodeUnit::new(1);
+                for (code, description) in metadata.error_map.iter() {
+                    let doc = description
+                        .code_description
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    if error_constants.contains_key(code) {
+                        if !doc.is_empty() {
+                            unit.add_line(format!("/// {}", doc));
+                        }
+                        unit.add_line(format!("const {}: u64 = {};", description.code_name, code));
+                    } else if doc.is_empty() {
+                        unit.add_line(format!("// error {}: {}", code, description.code_name));
+                    } else {
+                        unit.add_line(format!(
+                            "// error {}: {} - {}",
+                            code, description.code_name, doc
+                        ));
+                    }
+                }
+                unit.add_line("".to_string());
+                result.add_block(unit);
+            }
 
             if let Some(defs) = binary.struct_defs() {
                 for idx in 0..defs.len() {
                     let s_idx = move_binary_format::file_format::StructDefinitionIndex(idx as u16);
                     let s = module.get_struct_by_def_idx(s_idx);
-                    let s_bin = binary.struct_handle_at(binary.struct_def_at(s_idx)?.struct_handle);
-                    let mut unit = self.decompile_struct(&s_bin, &s, &naming)?;
+                    let struct_error = |err: anyhow::Error| DecompileError::Struct {
+                        name: s.get_full_name_str(),
+                        source: err,
+                    };
+                    let mut unit = SourceCodeUnit::new(0);
+                    if test_only.is_test_only_struct(s_idx) {
+                        unit.add_line(TestAttribute::TestOnly.to_source());
+                    }
+                    if let Some(metadata) = &metadata {
+                        let name = s.get_name().display(s.symbol_pool()).to_string();
+                        for attribute in metadata.struct_attributes(&name) {
+                            unit.add_line(attribute);
+                        }
+                    }
+                    unit.add_block(self.decompile_struct(&s, &naming).map_err(struct_error)?);
                     unit.add_line("".to_string());
                     unit.add_indent(1);
                     result.add_block(unit);
                 }
             }
 
-            let env_decompiler_function_selector =
-                std::env::var("DECOMPILER_FUNCTION_SELECTOR").ok();
-            let env_decompiler_function_selector = env_decompiler_function_selector
-                .as_ref()
-                .map(|s| s.split(",").collect::<Vec<_>>());
-            let env_decompiler_function_selector = env_decompiler_function_selector.as_ref();
-            let env_decompiler_show_stackless_raw =
-                std::env::var("DECOMPILER_SHOW_STACKLESS_RAW").is_ok();
+            result.flush_to(&self.format_options, out)?;
 
-            let env_decompiler_show_stackless_decompiled =
-                std::env::var("DECOMPILER_SHOW_STACKLESS_DECOMPILED").is_ok();
+            // emit functions in definition order so output does not depend on symbol interning
+            let mut functions = module.get_functions().collect::<Vec<_>>();
+            functions.sort_by_key(|f| (f.get_def_idx(), f.get_name_str()));
 
-            for f in module.get_functions() {
-                if env_decompiler_function_selector
-                    .map(|x| !x.contains(&f.get_name_str().as_str()))
-                    .unwrap_or(false)
+            for f in functions {
+                if self
+                    .function_selector
+                    .as_ref()
+                    .is_some_and(|x| !x.contains(&f.get_name_str()))
                 {
                     continue;
                 }
-                let mut func_unit = SourceCodeUnit::new(1);
-                let f_sig = self.decompile_function_header(&f, &naming, is_script)?;
-                if f.is_native() {
-                    func_unit.add_line(format!("{};", f_sig));
-                } else {
-                    func_unit.add_line(format!("{} {{", f_sig));
-
-                    let function_target: FunctionTarget<'_> =
-                        targets.get_target(&f, &FunctionVariant::Baseline);
-
-                    if env_decompiler_show_stackless_raw {
-                        let bytecode = function_target.get_bytecode();
-                        let mut code_unit = SourceCodeUnit::new(1);
-                        code_unit.add_line(format!("// Raw stackless bytecode"));
-                        let label_offsets = Default::default();
-                        for bytecode in bytecode.iter() {
-                            code_unit.add_line(format!(
-                                "//   {:}",
-                                bytecode.display(&function_target, &label_offsets)
-                            ));
+                if self.cancellation.as_ref().is_some_and(|x| x.is_cancelled()) {
+                    return Err(DecompileError::Cancelled);
+                }
+
+                let function_name = f.get_full_name_str();
+                self.log(Level::Debug, || {
+                    format!("decompiling function {}", function_name)
+                });
+                if let Some(progress) = &self.progress {
+                    progress.function_started(&function_name);
+                }
+
+                let func_unit = (|| -> Result<SourceCodeUnit> {
+                    let mut func_unit = SourceCodeUnit::new(1);
+                    let def_idx = f.get_def_idx();
+                    if let Some(attribute) =
+                        def_idx.and_then(|idx| test_only.function(idx.0 as usize))
+                    {
+                        func_unit.add_line(attribute.to_source());
+                    }
+                    if let Some(metadata) = &metadata {
+                        for attribute in metadata.fun_attributes(&f.get_name_str()) {
+                            func_unit.add_line(attribute);
                         }
-                        code_unit.add_line(format!("// End raw stackless bytecode"));
-                        func_unit.add_block(code_unit);
                     }
-
-                    let mut defined_vars = HashSet::new();
-                    for idx in 0..function_target.get_parameter_count() {
-                        defined_vars.insert(idx);
+                    // regenerate the acquires clause from the bytecode, keeping note of the
+                    // declared one when they disagree
+                    let acquires = def_idx
+                        .and_then(|idx| inferred_acquires.get(idx.0 as usize))
+                        .and_then(|x| x.as_ref());
+                    if let (Some(acquires), Some(def_idx), BinaryIndexedView::Module(compiled)) =
+                        (acquires, def_idx, binary)
+                    {
+                        let declared = acquires::declared_acquires(compiled, def_idx);
+                        if &declared != acquires {
+                            let message = format!(
+                                "declared acquires [{}] differ from inferred [{}]",
+                                self.struct_names(&module, &declared).join(", "),
+                                self.struct_names(&module, acquires).join(", ")
+                            );
+                            self.log(Level::Warn, || format!("{}: {}", function_name, message));
+                            func_unit.add_line(format!("// warning: {}", message));
+                        }
                     }
+                    let f_sig =
+                        self.decompile_function_header(&f, &naming, is_script, acquires, &binary)?;
+                    self.add_function_header(&mut func_unit, &f_sig, f.is_native());
+                    if !f.is_native() {
+                        let function_target: FunctionTarget<'_> =
+                            targets.get_target(&f, &FunctionVariant::Baseline);
+
+                        self.log(Level::Trace, || {
+                            let label_offsets = Default::default();
+                            let mut dump = format!("stackless bytecode of {}:", function_name);
+                            for bytecode in function_target.get_bytecode() {
+                                dump.push_str(&format!(
+                                    "\n  {}",
+                                    bytecode.display(&function_target, &label_offsets)
+                                ));
+                            }
+                            dump
+                        });
+
+                        if self.raw_bytecode {
+                            let bytecode = function_target.get_bytecode();
+                            let mut code_unit = SourceCodeUnit::new(1);
+                            code_unit.add_line(format!("// Raw stackless bytecode"));
+                            let label_offsets = Default::default();
+                            for bytecode in bytecode.iter() {
+                                code_unit.add_line(format!(
+                                    "//   {:}",
+                                    bytecode.display(&function_target, &label_offsets)
+                                ));
+                            }
+                            code_unit.add_line(format!("// End raw stackless bytecode"));
+                            func_unit.add_block(code_unit);
+                        }
 
-                    let mut cfg_decompiled =
-                        cfg::stackless::decompile(function_target.get_bytecode(), &defined_vars)?;
-
-                    // much of data from function_target should not be used because
-                    // cfg_decompiled changed the bytecodes.
-                    // variables offsets are still keeped
-
-                    if env_decompiler_show_stackless_decompiled {
-                        let mut stackless_bytecode_display_ctx =
-                            stackless_bytecode_display::StacklessBycodeDisplayContext::new(
+                        // the cache is bypassed while dumping intermediate representations, and
+                        // with custom processors that the keys do not identify
+                        let function_cache = self.cache.as_ref().filter(|_| {
+                            !self.custom_processors
+                                && !self.structured_bytecode
+                                && !self.interleaved_bytecode
+                                && !self.spec_skeletons
+                                && self.gas_schedule.is_none()
+                        });
+                        let cache_key = function_cache.map(|_| {
+                            cache::function_cache_key(
                                 &function_target,
+                                &naming,
+                                &self.module_hashes,
+                                &self.optimizer_settings,
+                                &self.format_options,
+                                &error_constants,
+                            )
+                        });
+                        let cached = function_cache
+                            .zip(cache_key.as_ref())
+                            .and_then(|(cache, key)| cache.get(key));
+
+                        let mut aborts_if = Vec::new();
+                        let mut code_unit = if let Some(body) = cached {
+                            SourceCodeUnit::from_text(&body)
+                        } else {
+                            let mut defined_vars = HashSet::new();
+                            for idx in 0..function_target.get_parameter_count() {
+                                defined_vars.insert(idx);
+                            }
+
+                            match cfg::stackless::decompile(
+                                function_target.get_bytecode(),
+                                &defined_vars,
+                            ) {
+                                Err(err) => {
+                                    self.log(Level::Warn, || {
+                                        format!(
+                                            "{}: unable to structure the control flow, \
+                                             emitting the unstructured bytecode: {}",
+                                            function_name, err
+                                        )
+                                    });
+                                    unstructured.push(function_name.clone());
+                                    self.unstructured_body(&function_target, &err)
+                                }
+                                std::result::Result::Ok(mut cfg_decompiled) => {
+                                    // much of data from function_target should not be used because
+                                    // cfg_decompiled changed the bytecodes.
+                                    // variables offsets are still keeped
+
+                                    if self.structured_bytecode {
+                                        let mut stackless_bytecode_display_ctx =
+                                            stackless_bytecode_display::StacklessBycodeDisplayContext::new(
+                                                &function_target,
+                                            );
+                                        cfg_decompiled.display(&mut stackless_bytecode_display_ctx);
+                                        let mut code_unit = SourceCodeUnit::new(1);
+                                        code_unit.add_line(format!("// Bytecode"));
+                                        for line in
+                                            stackless_bytecode_display_ctx.result().split("\n")
+                                        {
+                                            code_unit.add_line(format!("//   {}", line));
+                                        }
+                                        code_unit.add_line(format!("// End Bytecode"));
+                                        func_unit.add_block(code_unit);
+                                    }
+
+                                    let mut sgen = reconstruct::SourceGen::new(
+                                        &mut cfg_decompiled,
+                                        &f,
+                                        &function_target,
+                                        &naming,
+                                    )
+                                    .with_interleaved_bytecode(self.interleaved_bytecode)
+                                    .with_error_constants(&error_constants)
+                                    .with_spec_inference(self.spec_skeletons)
+                                    .with_gas_annotations(self.gas_schedule.clone());
+
+                                    let code_unit = sgen.generate(&self.optimizer_settings)?;
+                                    aborts_if = sgen.aborts_if().to_vec();
+                                    if let (Some(cache), Some(key)) = (function_cache, &cache_key) {
+                                        cache.put(
+                                            key,
+                                            &code_unit.to_string_with_options(&self.format_options),
+                                        );
+                                    }
+                                    code_unit
+                                }
+                            }
+                        };
+
+                        code_unit.add_indent(1);
+                        func_unit.add_block(code_unit);
+                        func_unit.add_line("}".to_string());
+
+                        if self.spec_skeletons && !is_script {
+                            func_unit.add_block_header(
+                                format!("spec {}", f.get_name_str()),
+                                &self.format_options,
                             );
-                        cfg_decompiled.display(&mut stackless_bytecode_display_ctx);
-                        let mut code_unit = SourceCodeUnit::new(1);
-                        code_unit.add_line(format!("// Bytecode"));
-                        for line in stackless_bytecode_display_ctx.result().split("\n") {
-                            code_unit.add_line(format!("//   {}", line));
+                            let mut spec_unit = SourceCodeUnit::new(1);
+                            if !aborts_if.is_empty() {
+                                // the inferred conditions do not cover every abort
+                                spec_unit.add_line("pragma aborts_if_is_partial;".to_string());
+                            }
+                            for condition in aborts_if {
+                                spec_unit.add_line(format!("aborts_if {};", condition));
+                            }
+                            func_unit.add_block(spec_unit);
+                            func_unit.add_line("}".to_string());
                         }
-                        code_unit.add_line(format!("// End Bytecode"));
-                        func_unit.add_block(code_unit);
-                    }
 
-                    let mut sgen = reconstruct::SourceGen::new(
-                        &mut cfg_decompiled,
-                        &f,
-                        &function_target,
-                        &naming,
-                    );
+                        func_unit.add_line("".to_string());
+                    }
 
-                    let mut code_unit = sgen.generate(&self.optimizer_settings)?;
+                    Ok(func_unit)
+                })();
 
-                    code_unit.add_indent(1);
-                    func_unit.add_block(code_unit);
-                    func_unit.add_line("}".to_string());
-                    func_unit.add_line("".to_string());
+                let func_unit = match func_unit {
+                    std::result::Result::Ok(func_unit) => func_unit,
+                    Err(err) => {
+                        if let Some(progress) = &self.progress {
+                            progress.function_failed(&function_name, &err);
+                        }
+                        return Err(DecompileError::Function {
+                            name: function_name,
+                            source: err,
+                        });
+                    }
+                };
+                if let Some(progress) = &self.progress {
+                    progress.function_finished(&function_name);
                 }
 
+                let start_line = out.next_line();
                 result.add_block(func_unit);
+                result.flush_to(&self.format_options, out)?;
+                module_span.functions.push(FunctionSpan {
+                    name: f.get_name_str(),
+                    start_line,
+                    end_line: out.last_content_line(),
+                });
             }
 
             let mut footer = SourceCodeUnit::new(1);
             footer.add_line(format!("// decompiled from Move bytecode v{}", version));
+            if self.provenance {
+                for line in self.provenance_lines(&binary, module_span.name.clone())? {
+                    footer.add_line(format!("// {}", line));
+                }
+            }
 
             result.add_block(footer);
             result.add_line("}".to_string());
+            result.flush_to(&self.format_options, out)?;
+            module_span.end_line = out.last_content_line();
+            source_map.modules.push(module_span);
         }
 
-        Ok(result.to_string())
+        out.flush()?;
+        if !unstructured.is_empty() {
+            return Err(DecompileError::UnstructuredControlFlow(unstructured));
+        }
+        std::result::Result::Ok(())
     }
 }
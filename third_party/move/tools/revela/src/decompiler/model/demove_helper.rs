@@ -2,6 +2,7 @@ use std::{borrow::Borrow, collections::BTreeMap, rc::Rc};
 
 use codespan::Files;
 use itertools::Itertools;
+use move_binary_format::access::ModuleAccess;
 use move_command_line_common::files::FileHash;
 use move_compiler::expansion::ast::Program;
 
@@ -134,3 +135,42 @@ pub fn run_stackless_compiler(env: &mut GlobalEnv, program: Program) {
         }
     }
 }
+
+/// Copy the type parameter kinds of an attached module from its bytecode, which is the
+/// authority on them, so that struct and function environments can be rendered without
+/// looking up the binary again
+pub fn populate_binary_flags(env: &mut GlobalEnv, module_id: ModuleId) {
+    let data = &mut env.module_data[module_id.to_usize()];
+    let Some(compiled) = &data.compiled_module else {
+        return;
+    };
+    for struct_data in data.struct_data.values_mut() {
+        let Some(def_idx) = struct_data.def_idx else {
+            continue;
+        };
+        let handle = compiled.struct_handle_at(compiled.struct_def_at(def_idx).struct_handle);
+        for (param, binary_param) in struct_data
+            .type_params
+            .iter_mut()
+            .zip(handle.type_parameters.iter())
+        {
+            param.1 = TypeParameterKind {
+                abilities: binary_param.constraints,
+                is_phantom: binary_param.is_phantom,
+            };
+        }
+    }
+    for fun_data in data.function_data.values_mut() {
+        let Some(def_idx) = fun_data.def_idx else {
+            continue;
+        };
+        let handle = compiled.function_handle_at(compiled.function_def_at(def_idx).function);
+        for (param, abilities) in fun_data
+            .type_params
+            .iter_mut()
+            .zip(handle.type_parameters.iter())
+        {
+            param.1 = TypeParameterKind::new(*abilities);
+        }
+    }
+}
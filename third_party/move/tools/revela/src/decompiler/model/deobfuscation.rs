@@ -0,0 +1,528 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Undo control flow obfuscation in the stackless bytecode, before it is structured.
+//!
+//! Two patterns are recognized, both by evaluating what a block knows about its temporaries:
+//! - opaque predicates, branches whose condition is fixed, either computed from constants or an
+//!   identity such as `x == x`, `x >= 0` or `x * (x + 1) % 2 == 0`. They become jumps.
+//! - dispatcher loops of flattened control flow, where every block stores the next state in a
+//!   variable and jumps back to a chain of comparisons on it. A jump into the dispatcher with a
+//!   known state goes straight to the block the dispatcher would select.
+//!
+//! Code that is no longer reachable afterwards, usually the dispatcher, is removed.
+
+use move_model::model::FunctionEnv;
+use move_stackless_bytecode::{
+    function_target::FunctionData,
+    function_target_pipeline::{FunctionTargetProcessor, FunctionTargetsHolder},
+    stackless_bytecode::{Bytecode, Constant, Label, Operation},
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+type TempIndex = usize;
+
+/// A temporary at one of its definitions in a block, version 0 is its value on block entry
+type Origin = (TempIndex, usize);
+
+pub struct DeobfuscationProcessor {
+    max_loop: usize,
+}
+
+impl DeobfuscationProcessor {
+    pub fn new(max_loop: usize) -> Box<Self> {
+        Box::new(Self { max_loop })
+    }
+}
+
+impl FunctionTargetProcessor for DeobfuscationProcessor {
+    fn process(
+        &self,
+        _targets: &mut FunctionTargetsHolder,
+        func_env: &FunctionEnv,
+        mut data: FunctionData,
+        _scc_opt: Option<&[FunctionEnv]>,
+    ) -> FunctionData {
+        if func_env.is_native() {
+            return data;
+        }
+
+        // locals behind a reference may change without an assignment, nothing is assumed on them
+        let borrowed = data
+            .code
+            .iter()
+            .filter_map(|insn| match insn {
+                Bytecode::Call(_, _, Operation::BorrowLoc, srcs, _) => Some(srcs[0]),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        let mut code = std::mem::take(&mut data.code);
+        for _ in 0..self.max_loop {
+            let threaded = thread_branches(&mut code, &borrowed);
+            let removed = remove_unreachable(&mut code);
+            if !threaded && !removed {
+                break;
+            }
+        }
+
+        data.code = code;
+        data
+    }
+
+    fn name(&self) -> String {
+        "deobfuscation".to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    Bool(bool),
+    /// an unsigned integer and its width in bits, u256 values are not tracked
+    Int(u128, u32),
+}
+
+impl Value {
+    fn from_constant(constant: &Constant) -> Option<Self> {
+        match constant {
+            Constant::Bool(x) => Some(Value::Bool(*x)),
+            Constant::U8(x) => Some(Value::Int(*x as u128, 8)),
+            Constant::U16(x) => Some(Value::Int(*x as u128, 16)),
+            Constant::U32(x) => Some(Value::Int(*x as u128, 32)),
+            Constant::U64(x) => Some(Value::Int(*x as u128, 64)),
+            Constant::U128(x) => Some(Value::Int(*x, 128)),
+            _ => None,
+        }
+    }
+}
+
+fn mask(bits: u32) -> u128 {
+    if bits >= 128 {
+        u128::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Result of `op` on known operands, None when it is not a pure operation or it would abort
+fn fold(op: &Operation, args: &[Value]) -> Option<Value> {
+    use Value::*;
+    let int = |x: Option<u128>, bits: u32| x.filter(|x| *x <= mask(bits)).map(|x| Int(x, bits));
+    match (op, args) {
+        (Operation::Not, [Bool(a)]) => Some(Bool(!a)),
+        (Operation::And, [Bool(a), Bool(b)]) => Some(Bool(*a && *b)),
+        (Operation::Or, [Bool(a), Bool(b)]) => Some(Bool(*a || *b)),
+        (Operation::Eq, [a, b]) => Some(Bool(a == b)),
+        (Operation::Neq, [a, b]) => Some(Bool(a != b)),
+        (Operation::Lt, [Int(a, _), Int(b, _)]) => Some(Bool(a < b)),
+        (Operation::Le, [Int(a, _), Int(b, _)]) => Some(Bool(a <= b)),
+        (Operation::Gt, [Int(a, _), Int(b, _)]) => Some(Bool(a > b)),
+        (Operation::Ge, [Int(a, _), Int(b, _)]) => Some(Bool(a >= b)),
+        (Operation::Add, [Int(a, w), Int(b, _)]) => int(a.checked_add(*b), *w),
+        (Operation::Sub, [Int(a, w), Int(b, _)]) => int(a.checked_sub(*b), *w),
+        (Operation::Mul, [Int(a, w), Int(b, _)]) => int(a.checked_mul(*b), *w),
+        (Operation::Div, [Int(a, w), Int(b, _)]) => int(a.checked_div(*b), *w),
+        (Operation::Mod, [Int(a, w), Int(b, _)]) => int(a.checked_rem(*b), *w),
+        (Operation::BitAnd, [Int(a, w), Int(b, _)]) => Some(Int(a & b, *w)),
+        (Operation::BitOr, [Int(a, w), Int(b, _)]) => Some(Int(a | b, *w)),
+        (Operation::Xor, [Int(a, w), Int(b, _)]) => Some(Int(a ^ b, *w)),
+        (Operation::Shl, [Int(a, w), Int(b, _)]) if *b < *w as u128 => {
+            Some(Int((a << b) & mask(*w), *w))
+        }
+        (Operation::Shr, [Int(a, w), Int(b, _)]) if *b < *w as u128 => Some(Int(a >> b, *w)),
+        (Operation::CastU8, [Int(a, _)]) => int(Some(*a), 8),
+        (Operation::CastU16, [Int(a, _)]) => int(Some(*a), 16),
+        (Operation::CastU32, [Int(a, _)]) => int(Some(*a), 32),
+        (Operation::CastU64, [Int(a, _)]) => int(Some(*a), 64),
+        (Operation::CastU128, [Int(a, _)]) => int(Some(*a), 128),
+        _ => None,
+    }
+}
+
+/// What is known about the temporaries after some instructions of a block
+#[derive(Clone)]
+struct Facts<'a> {
+    borrowed: &'a BTreeSet<TempIndex>,
+    versions: BTreeMap<TempIndex, usize>,
+    /// temporaries holding a copy of another one
+    copies: BTreeMap<TempIndex, Origin>,
+    values: BTreeMap<Origin, Value>,
+    /// operation and operands of the definitions by a single-result operation
+    defs: BTreeMap<Origin, (Operation, Vec<Option<Origin>>)>,
+}
+
+impl<'a> Facts<'a> {
+    fn new(borrowed: &'a BTreeSet<TempIndex>) -> Self {
+        Self {
+            borrowed,
+            versions: BTreeMap::new(),
+            copies: BTreeMap::new(),
+            values: BTreeMap::new(),
+            defs: BTreeMap::new(),
+        }
+    }
+
+    fn origin(&self, temp: TempIndex) -> Option<Origin> {
+        if self.borrowed.contains(&temp) {
+            return None;
+        }
+        Some(
+            self.copies
+                .get(&temp)
+                .copied()
+                .unwrap_or((temp, self.versions.get(&temp).copied().unwrap_or(0))),
+        )
+    }
+
+    fn value(&self, temp: TempIndex) -> Option<Value> {
+        self.known(&self.origin(temp))
+    }
+
+    fn known(&self, origin: &Option<Origin>) -> Option<Value> {
+        origin.and_then(|origin| self.values.get(&origin).copied())
+    }
+
+    fn known_int(&self, origin: &Option<Origin>) -> Option<(u128, u32)> {
+        match self.known(origin) {
+            Some(Value::Int(x, bits)) => Some((x, bits)),
+            _ => None,
+        }
+    }
+
+    fn define(&mut self, temp: TempIndex) -> Option<Origin> {
+        self.copies.remove(&temp);
+        if self.borrowed.contains(&temp) {
+            return None;
+        }
+        let version = self.versions.entry(temp).or_default();
+        *version += 1;
+        Some((temp, *version))
+    }
+
+    /// Record the effect of `insn`, returns whether it only defines temporaries to known values
+    fn apply(&mut self, insn: &Bytecode) -> bool {
+        match insn {
+            Bytecode::Label(..) | Bytecode::Nop(..) => true,
+            Bytecode::Load(_, dst, constant) => {
+                let value = Value::from_constant(constant);
+                match (self.define(*dst), value) {
+                    (Some(origin), Some(value)) => {
+                        self.values.insert(origin, value);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Bytecode::Assign(_, dst, src, _) => {
+                let source = self.origin(*src);
+                match (self.define(*dst), source) {
+                    (Some(_), Some(source)) => {
+                        self.copies.insert(*dst, source);
+                        self.values.contains_key(&source)
+                    }
+                    _ => false,
+                }
+            }
+            Bytecode::Call(_, dsts, op, srcs, _) => {
+                let operands = srcs.iter().map(|src| self.origin(*src)).collect::<Vec<_>>();
+                let value = self.evaluate(op, &operands);
+                let origins = dsts.iter().map(|dst| self.define(*dst)).collect::<Vec<_>>();
+                match origins.as_slice() {
+                    [Some(origin)] => {
+                        self.defs.insert(*origin, (op.clone(), operands));
+                        if let Some(value) = value {
+                            self.values.insert(*origin, value);
+                        }
+                        value.is_some()
+                    }
+                    _ => false,
+                }
+            }
+            _ => {
+                for dst in insn.dests() {
+                    self.define(dst);
+                }
+                false
+            }
+        }
+    }
+
+    fn evaluate(&self, op: &Operation, operands: &[Option<Origin>]) -> Option<Value> {
+        let values = operands
+            .iter()
+            .map(|operand| self.known(operand))
+            .collect::<Option<Vec<_>>>();
+        match values {
+            Some(values) => fold(op, &values),
+            None => self.identity(op, operands),
+        }
+    }
+
+    /// Value of `op` that does not depend on its unknown operands
+    fn identity(&self, op: &Operation, operands: &[Option<Origin>]) -> Option<Value> {
+        let is_zero = |operand: &Option<Origin>| matches!(self.known_int(operand), Some((0, _)));
+        match (op, operands) {
+            (Operation::Eq | Operation::Le | Operation::Ge, [Some(a), Some(b)]) if a == b => {
+                Some(Value::Bool(true))
+            }
+            (Operation::Neq | Operation::Lt | Operation::Gt, [Some(a), Some(b)]) if a == b => {
+                Some(Value::Bool(false))
+            }
+            (Operation::Ge, [_, zero]) | (Operation::Le, [zero, _]) if is_zero(zero) => {
+                Some(Value::Bool(true))
+            }
+            (Operation::Lt, [_, zero]) | (Operation::Gt, [zero, _]) if is_zero(zero) => {
+                Some(Value::Bool(false))
+            }
+            // the product of two consecutive integers is even
+            (Operation::Mod, [Some(product), divisor]) => match self.known_int(divisor) {
+                Some((2, bits)) if self.is_consecutive_product(product) => {
+                    Some(Value::Int(0, bits))
+                }
+                _ => None,
+            },
+            (Operation::BitAnd, [Some(product), mask]) => match self.known_int(mask) {
+                Some((1, bits)) if self.is_consecutive_product(product) => {
+                    Some(Value::Int(0, bits))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether `origin` is defined as `x * (x + 1)` or `(x + 1) * x`
+    fn is_consecutive_product(&self, origin: &Origin) -> bool {
+        match self.defs.get(origin) {
+            Some((Operation::Mul, operands)) => match operands.as_slice() {
+                [Some(a), Some(b)] => self.is_successor(b, a) || self.is_successor(a, b),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether `origin` is defined as `of + 1`
+    fn is_successor(&self, origin: &Origin, of: &Origin) -> bool {
+        let is_one = |operand: &Option<Origin>| matches!(self.known_int(operand), Some((1, _)));
+        match self.defs.get(origin) {
+            Some((Operation::Add, operands)) => match operands.as_slice() {
+                [a, b] => {
+                    (a.as_ref() == Some(of) && is_one(b)) || (b.as_ref() == Some(of) && is_one(a))
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Blocks a jump with known facts passes through before reaching a block that does real work
+struct Dispatch {
+    target: Label,
+    blocks: Vec<Label>,
+    defined: BTreeSet<TempIndex>,
+}
+
+/// Follow the blocks from `label` while they only compare known values and branch on them
+fn dispatch(
+    code: &[Bytecode],
+    label_offsets: &BTreeMap<Label, u16>,
+    label: Label,
+    facts: &Facts,
+) -> Option<Dispatch> {
+    let mut facts = facts.clone();
+    let mut current = label;
+    let mut blocks = vec![];
+    let mut defined = BTreeSet::new();
+    let mut branched = false;
+
+    'blocks: loop {
+        let start = *label_offsets.get(&current)? as usize;
+        let mut block_defined = BTreeSet::new();
+        let mut block_branched = false;
+        let mut next = None;
+        for insn in &code[start + 1..] {
+            match insn {
+                Bytecode::Label(_, label) | Bytecode::Jump(_, label) => {
+                    next = Some(*label);
+                    break;
+                }
+                Bytecode::Branch(_, then_label, else_label, cond) => match facts.value(*cond) {
+                    Some(Value::Bool(cond)) => {
+                        next = Some(if cond { *then_label } else { *else_label });
+                        block_branched = true;
+                        break;
+                    }
+                    _ => break 'blocks,
+                },
+                _ => {
+                    if !facts.apply(insn) {
+                        break 'blocks;
+                    }
+                    block_defined.extend(insn.dests());
+                }
+            }
+        }
+        let next = match next {
+            Some(next) => next,
+            None => break,
+        };
+        blocks.push(current);
+        defined.extend(block_defined);
+        branched |= block_branched;
+        if next == label || blocks.contains(&next) {
+            // the state never leaves the dispatcher
+            return None;
+        }
+        current = next;
+    }
+
+    if branched && current != label {
+        Some(Dispatch {
+            target: current,
+            blocks,
+            defined,
+        })
+    } else {
+        None
+    }
+}
+
+/// Turn branches on known conditions into jumps and let jumps into a dispatcher skip it,
+/// returns whether the code changed
+fn thread_branches(code: &mut Vec<Bytecode>, borrowed: &BTreeSet<TempIndex>) -> bool {
+    let label_offsets = Bytecode::label_offsets(code);
+    let mut folded = BTreeMap::new();
+    let mut threaded = BTreeMap::new();
+    // jumps to add before a label that the previous block falls through to
+    let mut fall_throughs = BTreeMap::new();
+    let mut dispatch_blocks = BTreeSet::new();
+    let mut dispatch_defined = BTreeSet::new();
+
+    let mut facts = Facts::new(borrowed);
+    for (offset, insn) in code.iter().enumerate() {
+        let terminator = match insn {
+            Bytecode::Branch(id, then_label, else_label, cond) => match facts.value(*cond) {
+                Some(Value::Bool(cond)) => {
+                    let jump = Bytecode::Jump(*id, if cond { *then_label } else { *else_label });
+                    folded.insert(offset, jump.clone());
+                    jump
+                }
+                _ => insn.clone(),
+            },
+            Bytecode::Jump(..) => insn.clone(),
+            Bytecode::Label(id, label) if offset > 0 && !code[offset - 1].is_always_branching() => {
+                Bytecode::Jump(*id, *label)
+            }
+            Bytecode::Label(..) | Bytecode::Ret(..) | Bytecode::Abort(..) => {
+                facts = Facts::new(borrowed);
+                continue;
+            }
+            _ => {
+                facts.apply(insn);
+                continue;
+            }
+        };
+
+        let mut resolve = |label: &Label| {
+            dispatch(code, &label_offsets, *label, &facts).map(|dispatch| {
+                dispatch_blocks.extend(dispatch.blocks);
+                dispatch_defined.extend(dispatch.defined);
+                dispatch.target
+            })
+        };
+        match &terminator {
+            Bytecode::Jump(id, label) => {
+                if let Some(target) = resolve(label) {
+                    let jump = Bytecode::Jump(*id, target);
+                    if matches!(insn, Bytecode::Label(..)) {
+                        fall_throughs.insert(offset, jump);
+                    } else {
+                        threaded.insert(offset, jump);
+                    }
+                }
+            }
+            Bytecode::Branch(id, then_label, else_label, cond) => {
+                let then_target = resolve(then_label);
+                let else_target = resolve(else_label);
+                if then_target.is_some() || else_target.is_some() {
+                    threaded.insert(
+                        offset,
+                        Bytecode::Branch(
+                            *id,
+                            then_target.unwrap_or(*then_label),
+                            else_target.unwrap_or(*else_label),
+                            *cond,
+                        ),
+                    );
+                }
+            }
+            _ => unreachable!(),
+        }
+        facts = Facts::new(borrowed);
+    }
+
+    // skipping the dispatcher leaves its temporaries unset, which is only fine if nothing else
+    // reads them
+    let mut in_dispatch = false;
+    let mut dispatch_is_private = true;
+    for insn in code.iter() {
+        if let Bytecode::Label(_, label) = insn {
+            in_dispatch = dispatch_blocks.contains(label);
+        }
+        if !in_dispatch
+            && insn
+                .sources()
+                .iter()
+                .any(|src| dispatch_defined.contains(src))
+        {
+            dispatch_is_private = false;
+            break;
+        }
+    }
+    if dispatch_is_private {
+        folded.extend(threaded);
+    } else {
+        fall_throughs.clear();
+    }
+
+    let mut changed = !fall_throughs.is_empty();
+    for (offset, insn) in folded {
+        if code[offset] != insn {
+            code[offset] = insn;
+            changed = true;
+        }
+    }
+    for (offset, jump) in fall_throughs.into_iter().rev() {
+        code.insert(offset, jump);
+    }
+    changed
+}
+
+/// Remove the instructions control never reaches, returns whether the code changed
+fn remove_unreachable(code: &mut Vec<Bytecode>) -> bool {
+    if code.is_empty() {
+        return false;
+    }
+    let label_offsets = Bytecode::label_offsets(code);
+    let mut reachable = vec![false; code.len()];
+    let mut queue = vec![0u16];
+    while let Some(offset) = queue.pop() {
+        if (offset as usize) >= code.len() || reachable[offset as usize] {
+            continue;
+        }
+        reachable[offset as usize] = true;
+        queue.extend(Bytecode::get_successors(offset, code, &label_offsets));
+    }
+    if reachable.iter().all(|x| *x) {
+        return false;
+    }
+    let mut offset = 0;
+    code.retain(|_| {
+        offset += 1;
+        reachable[offset - 1]
+    });
+    true
+}
@@ -1,3 +1,4 @@
+pub mod deobfuscation;
 pub mod livevar_analysis;
 pub mod peephole_analysis;
 pub mod demove_helper;
@@ -2,9 +2,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use move_model::ty::Type;
+use move_model::{
+    model::GlobalEnv,
+    ty::{PrimitiveType, Type},
+};
+
+use super::aliases::AddressAliases;
 
 fn default_display(ty: &Type, _: &Naming) -> String {
     format!("{:?}", ty)
@@ -14,6 +23,8 @@ pub struct Naming<'a> {
     arg_count: usize,
     type_display: Rc<RefCell<dyn Fn(&Type, &Naming) -> String + 'a>>,
     referenced_vairables: Option<HashSet<usize>>,
+    argument_names: Option<Rc<Vec<String>>>,
+    address_aliases: Option<Rc<AddressAliases>>,
 }
 
 impl Clone for Naming<'_> {
@@ -22,6 +33,8 @@ impl Clone for Naming<'_> {
             arg_count: self.arg_count,
             type_display: self.type_display.clone(),
             referenced_vairables: self.referenced_vairables.clone(),
+            argument_names: self.argument_names.clone(),
+            address_aliases: self.address_aliases.clone(),
         }
     }
 }
@@ -32,6 +45,8 @@ impl<'a> Naming<'a> {
             arg_count: 0,
             type_display: Rc::new(RefCell::new(default_display)),
             referenced_vairables: None,
+            argument_names: None,
+            address_aliases: None,
         }
     }
 
@@ -67,6 +82,42 @@ impl<'a> Naming<'a> {
             referenced_vairables: Some(referenced_vairables.clone()),
             type_display: self.type_display.clone(),
             arg_count: self.arg_count,
+            argument_names: self.argument_names.clone(),
+            address_aliases: self.address_aliases.clone(),
+        }
+    }
+
+    /// Name the arguments `names[idx]` instead of `arg<idx>`
+    pub fn with_argument_names<'b>(&self, names: Vec<String>) -> Naming<'b>
+    where
+        'a: 'b,
+    {
+        Naming {
+            argument_names: Some(Rc::new(names)),
+            ..self.clone()
+        }
+    }
+
+    /// Render the paths of other modules with named addresses
+    pub fn with_address_aliases<'b>(&self, aliases: AddressAliases) -> Naming<'b>
+    where
+        'a: 'b,
+    {
+        Naming {
+            address_aliases: Some(Rc::new(aliases)),
+            ..self.clone()
+        }
+    }
+
+    pub fn address_aliases(&self) -> Option<&AddressAliases> {
+        self.address_aliases.as_deref()
+    }
+
+    /// `path` such as `0x1::coin::transfer` as rendered, with its address aliased if requested
+    pub fn path(&self, path: &str) -> String {
+        match &self.address_aliases {
+            Some(aliases) => aliases.path(path),
+            None => path.to_string(),
         }
     }
 
@@ -92,6 +143,9 @@ impl<'a> Naming<'a> {
     }
 
     pub fn argument(&self, idx: usize) -> String {
+        if let Some(name) = self.argument_names.as_ref().and_then(|x| x.get(idx)) {
+            return name.clone();
+        }
         format!("arg{}", idx)
     }
 
@@ -103,3 +157,52 @@ impl<'a> Naming<'a> {
         (self.type_display.borrow())(ty, &self)
     }
 }
+
+fn type_based_name(env: &GlobalEnv, ty: &Type) -> String {
+    match ty {
+        Type::Reference(_, inner) => type_based_name(env, inner),
+        Type::Primitive(PrimitiveType::Signer) => "account".to_string(),
+        Type::Primitive(PrimitiveType::Address) => "addr".to_string(),
+        Type::Primitive(PrimitiveType::Bool) => "flag".to_string(),
+        Type::Vector(inner) if **inner == Type::Primitive(PrimitiveType::U8) => "bytes".to_string(),
+        Type::Vector(_) => "items".to_string(),
+        Type::Struct(mid, sid, _) => {
+            let struct_env = env.get_module(*mid).into_struct(*sid);
+            let name = struct_env.get_name().display(env.symbol_pool()).to_string();
+            let mut snake = String::new();
+            for (idx, c) in name.chars().enumerate() {
+                if c.is_ascii_uppercase() && idx > 0 {
+                    snake.push('_');
+                }
+                snake.push(c.to_ascii_lowercase());
+            }
+            // a struct named after a builtin would shadow it
+            match snake.as_str() {
+                "address" | "bool" | "signer" | "vector" | "u8" | "u16" | "u32" | "u64"
+                | "u128" | "u256" => format!("{}_value", snake),
+                _ => snake,
+            }
+        }
+        _ => "value".to_string(),
+    }
+}
+
+/// Argument names derived from their types, e.g. `account` for a `&signer`, numbered when
+/// several arguments share a name
+pub fn argument_names_from_types(env: &GlobalEnv, types: &[Type]) -> Vec<String> {
+    let names: Vec<_> = types.iter().map(|ty| type_based_name(env, ty)).collect();
+    let mut seen = HashMap::<&str, usize>::new();
+    names
+        .iter()
+        .map(|name| {
+            let total = names.iter().filter(|x| *x == name).count();
+            let count = seen.entry(name.as_str()).or_default();
+            *count += 1;
+            if total > 1 {
+                format!("{}{}", name, count)
+            } else {
+                name.clone()
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,341 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decompilation of a set of binaries into a Move package that builds on its own.
+//!
+//! The provided modules are emitted in dependency order, one source file each. Modules they
+//! refer to without being provided are declared in a local `Stubs` dependency, with the
+//! signatures recovered from the handles of the referring binaries and bodies that abort.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::Result;
+use move_binary_format::{
+    binary_views::BinaryIndexedView,
+    file_format::{AbilitySet, SignatureToken, StructHandleIndex, StructTypeParameter},
+};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+
+use super::Decompiler;
+
+/// Declarations of a module that is referred to but not provided
+#[derive(Default)]
+struct StubModule {
+    structs: BTreeMap<String, String>,
+    functions: BTreeMap<String, String>,
+}
+
+impl<'a> Decompiler<'a> {
+    /// Decompile into a Move package named `name` under `dir`, with a `Move.toml` declaring a
+    /// named address for every address involved and stubs for the modules not provided
+    pub fn decompile_package(&mut self, dir: &Path, name: &str) -> Result<()> {
+        self.binaries = dependency_order(&self.binaries);
+        let provided: HashSet<ModuleId> =
+            self.binaries.iter().filter_map(|b| b.self_id()).collect();
+        let stubs = collect_stubs(&self.binaries, &provided);
+
+        let mut addresses: BTreeSet<AccountAddress> =
+            provided.iter().map(|id| *id.address()).collect();
+        addresses.extend(stubs.keys().map(|id| *id.address()));
+
+        self.friend_declarations = true;
+        let source = self.decompile()?;
+
+        fs::create_dir_all(dir.join("sources"))?;
+        let mut file_names = HashSet::new();
+        let mut script_count = 0;
+        for unit in split_units(&source) {
+            let path = match unit_module_name(&unit) {
+                Some((address, module_name)) => {
+                    let mut file_name = format!("{}.move", module_name);
+                    if !file_names.insert(file_name.clone()) {
+                        file_name = format!("{}_{}.move", address, module_name);
+                    }
+                    dir.join("sources").join(file_name)
+                }
+                None => {
+                    fs::create_dir_all(dir.join("scripts"))?;
+                    script_count += 1;
+                    dir.join("scripts")
+                        .join(format!("script_{}.move", script_count))
+                }
+            };
+            fs::write(path, unit)?;
+        }
+
+        let mut manifest = format!("[package]\nname = \"{}\"\nversion = \"0.0.0\"\n", name);
+        manifest.push_str("\n[addresses]\n");
+        for address in &addresses {
+            let alias = self.address_aliases.as_ref().and_then(|x| x.name(address));
+            for named in named_addresses(address, alias) {
+                manifest.push_str(&format!("{} = \"{}\"\n", named, address.to_hex_literal()));
+            }
+        }
+        if !stubs.is_empty() {
+            manifest.push_str("\n[dependencies]\nStubs = { local = \"stubs\" }\n");
+            self.write_stubs(&dir.join("stubs"), &stubs)?;
+        }
+        fs::write(dir.join("Move.toml"), manifest)?;
+        Ok(())
+    }
+
+    fn write_stubs(&self, dir: &Path, stubs: &BTreeMap<ModuleId, StubModule>) -> Result<()> {
+        fs::create_dir_all(dir.join("sources"))?;
+        fs::write(
+            dir.join("Move.toml"),
+            "[package]\nname = \"Stubs\"\nversion = \"0.0.0\"\n",
+        )?;
+
+        let indent = " ".repeat(self.format_options.indent_width);
+        let mut source = String::new();
+        for (module_id, module) in stubs {
+            source.push_str(&format!(
+                "// stub of a dependency that was not decompiled, bodies abort\nmodule {}::{} {{\n",
+                module_id.address().to_hex_literal(),
+                module_id.name()
+            ));
+            for declaration in module.structs.values().chain(module.functions.values()) {
+                source.push_str(&format!("{}{}\n", indent, declaration));
+            }
+            source.push_str("}\n\n");
+        }
+        fs::write(dir.join("sources").join("stubs.move"), source)?;
+        Ok(())
+    }
+}
+
+/// Modules before the modules using them, keeping the given order otherwise; scripts come last
+fn dependency_order<'a>(binaries: &[BinaryIndexedView<'a>]) -> Vec<BinaryIndexedView<'a>> {
+    let ids: Vec<Option<ModuleId>> = binaries.iter().map(|b| b.self_id()).collect();
+    let dependencies: Vec<BTreeSet<usize>> = binaries
+        .iter()
+        .enumerate()
+        .map(|(idx, binary)| {
+            binary
+                .module_handles()
+                .iter()
+                .map(|handle| binary.module_id_for_handle(handle))
+                .filter_map(|id| ids.iter().position(|x| x.as_ref() == Some(&id)))
+                .filter(|dep| *dep != idx)
+                .collect()
+        })
+        .collect();
+
+    let mut emitted = vec![false; binaries.len()];
+    let mut order = Vec::new();
+    while order.len() < binaries.len() {
+        let next = (0..binaries.len())
+            .find(|idx| !emitted[*idx] && dependencies[*idx].iter().all(|dep| emitted[*dep]))
+            // cyclic dependencies are rejected by the bytecode verifier, keep going anyway
+            .or_else(|| (0..binaries.len()).find(|idx| !emitted[*idx]))
+            .unwrap();
+        emitted[next] = true;
+        order.push(next);
+    }
+    order.sort_by_key(|idx| ids[*idx].is_none());
+    order.into_iter().map(|idx| binaries[idx]).collect()
+}
+
+fn collect_stubs(
+    binaries: &[BinaryIndexedView],
+    provided: &HashSet<ModuleId>,
+) -> BTreeMap<ModuleId, StubModule> {
+    let mut stubs = BTreeMap::<ModuleId, StubModule>::new();
+    for binary in binaries {
+        for handle in binary.struct_handles() {
+            let module_id = binary.module_id_for_handle(binary.module_handle_at(handle.module));
+            if provided.contains(&module_id) {
+                continue;
+            }
+            let name = binary.identifier_at(handle.name).to_string();
+            let declaration = format!(
+                "struct {}{}{} {{}}",
+                name,
+                struct_type_parameters(&handle.type_parameters),
+                abilities(handle.abilities, " has ", ", ")
+            );
+            stubs
+                .entry(module_id)
+                .or_default()
+                .structs
+                .entry(name)
+                .or_insert(declaration);
+        }
+
+        for handle in binary.function_handles() {
+            let module_id = binary.module_id_for_handle(binary.module_handle_at(handle.module));
+            if provided.contains(&module_id) {
+                continue;
+            }
+            let name = binary.identifier_at(handle.name).to_string();
+            let type_parameters = if handle.type_parameters.is_empty() {
+                String::new()
+            } else {
+                let params = handle
+                    .type_parameters
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, constraints)| {
+                        format!("T{}{}", idx, abilities(*constraints, ": ", " + "))
+                    })
+                    .collect::<Vec<_>>();
+                format!("<{}>", params.join(", "))
+            };
+            let params = binary
+                .signature_at(handle.parameters)
+                .0
+                .iter()
+                .enumerate()
+                .map(|(idx, token)| format!("arg{}: {}", idx, render_token(binary, token)))
+                .collect::<Vec<_>>();
+            let returns = binary
+                .signature_at(handle.return_)
+                .0
+                .iter()
+                .map(|token| render_token(binary, token))
+                .collect::<Vec<_>>();
+            let returns = match returns.len() {
+                0 => String::new(),
+                1 => format!(": {}", returns[0]),
+                _ => format!(": ({})", returns.join(", ")),
+            };
+            let declaration = format!(
+                "public fun {}{}({}){} {{ abort 0 }}",
+                name,
+                type_parameters,
+                params.join(", "),
+                returns
+            );
+            stubs
+                .entry(module_id)
+                .or_default()
+                .functions
+                .entry(name)
+                .or_insert(declaration);
+        }
+    }
+    stubs
+}
+
+fn abilities(set: AbilitySet, prefix: &str, join: &str) -> String {
+    let names: Vec<_> = [
+        (set.has_copy(), "copy"),
+        (set.has_drop(), "drop"),
+        (set.has_store(), "store"),
+        (set.has_key(), "key"),
+    ]
+    .into_iter()
+    .filter(|(has, _)| *has)
+    .map(|(_, name)| name)
+    .collect();
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", prefix, names.join(join))
+    }
+}
+
+fn struct_type_parameters(params: &[StructTypeParameter]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params = params
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| {
+            format!(
+                "{}T{}{}",
+                if param.is_phantom { "phantom " } else { "" },
+                idx,
+                abilities(param.constraints, ": ", " + ")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("<{}>", params.join(", "))
+}
+
+pub(crate) fn render_token(binary: &BinaryIndexedView, token: &SignatureToken) -> String {
+    match token {
+        SignatureToken::Bool => "bool".to_string(),
+        SignatureToken::U8 => "u8".to_string(),
+        SignatureToken::U16 => "u16".to_string(),
+        SignatureToken::U32 => "u32".to_string(),
+        SignatureToken::U64 => "u64".to_string(),
+        SignatureToken::U128 => "u128".to_string(),
+        SignatureToken::U256 => "u256".to_string(),
+        SignatureToken::Address => "address".to_string(),
+        SignatureToken::Signer => "signer".to_string(),
+        SignatureToken::Vector(ty) => format!("vector<{}>", render_token(binary, ty)),
+        SignatureToken::Reference(ty) => format!("&{}", render_token(binary, ty)),
+        SignatureToken::MutableReference(ty) => format!("&mut {}", render_token(binary, ty)),
+        SignatureToken::TypeParameter(idx) => format!("T{}", idx),
+        SignatureToken::Struct(idx) => struct_name(binary, *idx),
+        SignatureToken::StructInstantiation(idx, tys) => format!(
+            "{}<{}>",
+            struct_name(binary, *idx),
+            tys.iter()
+                .map(|ty| render_token(binary, ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+pub(crate) fn struct_name(binary: &BinaryIndexedView, idx: StructHandleIndex) -> String {
+    let handle = binary.struct_handle_at(idx);
+    let module_id = binary.module_id_for_handle(binary.module_handle_at(handle.module));
+    format!(
+        "{}::{}::{}",
+        module_id.address().to_hex_literal(),
+        module_id.name(),
+        binary.identifier_at(handle.name)
+    )
+}
+
+/// Names bound to `address` in the manifest, the framework ones for the reserved addresses and
+/// the alias the sources are rendered with
+fn named_addresses(address: &AccountAddress, alias: Option<&str>) -> Vec<String> {
+    let mut names: Vec<String> = match address.to_hex_literal().as_str() {
+        "0x1" => vec!["std", "aptos_std", "aptos_framework"],
+        "0x3" => vec!["aptos_token"],
+        "0x4" => vec!["aptos_token_objects"],
+        _ => vec![],
+    }
+    .into_iter()
+    .map(|x| x.to_string())
+    .collect();
+    match alias {
+        Some(alias) if !names.iter().any(|x| x == alias) => names.push(alias.to_string()),
+        None if names.is_empty() => names.push(format!("addr_{}", address.short_str_lossless())),
+        _ => {}
+    }
+    names
+}
+
+/// The decompiled output split into its top-level `module` and `script` declarations
+fn split_units(source: &str) -> Vec<String> {
+    let mut units: Vec<String> = Vec::new();
+    for line in source.lines() {
+        if line.starts_with("module ") || line.starts_with("script") || units.is_empty() {
+            units.push(String::new());
+        }
+        let unit = units.last_mut().unwrap();
+        unit.push_str(line);
+        unit.push('\n');
+    }
+    units.retain(|unit| !unit.trim().is_empty());
+    units
+}
+
+/// Address and name of the module declared by `unit`, `None` for scripts
+fn unit_module_name(unit: &str) -> Option<(String, String)> {
+    let header = unit.strip_prefix("module ")?;
+    let path = header.split_whitespace().next()?;
+    let (address, name) = path.rsplit_once("::")?;
+    Some((address.to_string(), name.to_string()))
+}
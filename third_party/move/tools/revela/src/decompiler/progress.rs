@@ -0,0 +1,38 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Notifications sent while decompiling, functions are identified by their full name,
+/// e.g. `0x1::coin::transfer`
+pub trait DecompileProgress {
+    fn function_started(&self, _function: &str) {}
+
+    fn function_finished(&self, _function: &str) {}
+
+    fn function_failed(&self, _function: &str, _error: &anyhow::Error) {}
+}
+
+/// Shared flag aborting a running decompilation, checked between functions
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,381 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use move_stackless_bytecode::{function_target::FunctionTarget, stackless_bytecode::Constant};
+
+use crate::decompiler::{
+    evaluator::stackless::{ExprNodeOperation, ExprNodeRef},
+    gas_schedule::GasSchedule,
+};
+
+use super::{
+    optimizers::utils::resolve_function, DecompiledCodeItem, DecompiledCodeUnit, DecompiledExpr,
+};
+
+/// Gas spent by a statement, in internal gas units
+#[derive(Default)]
+struct Cost {
+    gas: u64,
+    storage_reads: usize,
+    storage_writes: usize,
+    natives: BTreeSet<String>,
+}
+
+impl Cost {
+    fn gas(gas: u64) -> Self {
+        Self {
+            gas,
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, other: Cost) {
+        self.gas += other.gas;
+        self.storage_reads += other.storage_reads;
+        self.storage_writes += other.storage_writes;
+        self.natives.extend(other.natives);
+    }
+
+    fn is_notable(&self) -> bool {
+        self.storage_reads > 0 || self.storage_writes > 0 || !self.natives.is_empty()
+    }
+
+    fn describe(&self, suffix: &str) -> String {
+        let mut parts = vec![format!("gas ~{}{}", format_gas(self.gas), suffix)];
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        if self.storage_reads > 0 {
+            parts.push(format!(
+                "{} storage read{}",
+                self.storage_reads,
+                plural(self.storage_reads)
+            ));
+        }
+        if self.storage_writes > 0 {
+            parts.push(format!(
+                "{} storage write{}",
+                self.storage_writes,
+                plural(self.storage_writes)
+            ));
+        }
+        for native in &self.natives {
+            parts.push(format!("native {}", native));
+        }
+        parts.join(", ")
+    }
+}
+
+fn format_gas(gas: u64) -> String {
+    match gas {
+        0..=999 => gas.to_string(),
+        1_000..=999_999 => format!("{:.1}k", gas as f64 / 1e3),
+        _ => format!("{:.1}M", gas as f64 / 1e6),
+    }
+}
+
+/// Annotate statements with their estimated cost from `schedule` as trailing comments
+///  - storage accesses: `let v0 = borrow_global<R>(a); // gas ~302.4k, 1 storage read`
+///  - native calls: `0x1::hash::sha3_256(v0); // gas ~18.4k, native 0x1::hash::sha3_256`
+///  - loops: `while (v0 < v1) { // gas ~6.2k per iteration`
+///
+/// Costs are lower bounds: sizes of values are unknown, so per-byte costs are left out, and
+/// calls are counted without the body of the callee.
+pub(crate) fn annotate_gas(
+    unit: &mut DecompiledCodeUnit,
+    func_target: &FunctionTarget<'_>,
+    schedule: &GasSchedule,
+) {
+    Estimator {
+        schedule,
+        func_target,
+    }
+    .annotate(unit);
+}
+
+struct Estimator<'a, 'env> {
+    schedule: &'a GasSchedule,
+    func_target: &'a FunctionTarget<'env>,
+}
+
+impl Estimator<'_, '_> {
+    fn instr(&self, name: &str) -> u64 {
+        self.schedule.get(&format!("instr.{}", name))
+    }
+
+    /// Annotate the notable statements of `unit`, returning the cost of running it once
+    fn annotate(&self, unit: &mut DecompiledCodeUnit) -> Cost {
+        let mut total = Cost::default();
+        for idx in 0..unit.blocks.len() {
+            let (cost, comment) = self.item(&mut unit.blocks[idx]);
+            if let Some(comment) = comment {
+                unit.trailing_comments.insert(idx, comment);
+            }
+            total.add(cost);
+        }
+        if let Some(exit) = &unit.exit {
+            total.add(self.expr(exit));
+        }
+        total
+    }
+
+    fn item(&self, item: &mut DecompiledCodeItem) -> (Cost, Option<String>) {
+        let mut cost = Cost::default();
+        match item {
+            DecompiledCodeItem::WhileStatement { cond, body } => {
+                let mut iteration = Cost::gas(self.instr("branch"));
+                if let Some(cond) = cond {
+                    iteration.add(self.expr(cond));
+                    iteration.add(Cost::gas(self.instr("br_false")));
+                }
+                iteration.add(self.annotate(body));
+                let comment = iteration.describe(" per iteration");
+                return (iteration, Some(comment));
+            }
+            DecompiledCodeItem::ForStatement {
+                start, end, body, ..
+            } => {
+                cost.add(self.expr(start));
+                cost.add(self.expr(end));
+                let mut iteration =
+                    Cost::gas(self.instr("lt") + self.instr("add") + self.instr("br_false"));
+                iteration.add(self.annotate(body));
+                let comment = iteration.describe(" per iteration");
+                cost.add(iteration);
+                return (cost, Some(comment));
+            }
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                ..
+            } => {
+                cost.add(self.expr(cond));
+                cost.add(Cost::gas(self.instr("br_false")));
+                let comment = cost.is_notable().then(|| cost.describe(""));
+                let if_cost = self.annotate(if_unit);
+                let else_cost = self.annotate(else_unit);
+                cost.add(if if_cost.gas >= else_cost.gas {
+                    if_cost
+                } else {
+                    else_cost
+                });
+                return (cost, comment);
+            }
+            DecompiledCodeItem::ReturnStatement(expr) => {
+                cost.add(self.expr(expr));
+                cost.add(Cost::gas(self.instr("ret")));
+            }
+            DecompiledCodeItem::AbortStatement(expr) => {
+                cost.add(self.expr(expr));
+                cost.add(Cost::gas(self.instr("abort")));
+            }
+            DecompiledCodeItem::BreakStatement | DecompiledCodeItem::ContinueStatement => {
+                cost.add(Cost::gas(self.instr("branch")));
+            }
+            DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_)
+            | DecompiledCodeItem::PreDeclareStatement { .. } => {}
+            DecompiledCodeItem::PossibleAssignStatement { value, .. }
+            | DecompiledCodeItem::AssignStatement { value, .. } => {
+                cost.add(self.expr(value));
+                cost.add(Cost::gas(self.instr("st_loc.base")));
+            }
+            DecompiledCodeItem::AssignTupleStatement {
+                variables, value, ..
+            } => {
+                cost.add(self.expr(value));
+                cost.add(Cost::gas(
+                    self.instr("st_loc.base") * variables.len() as u64,
+                ));
+            }
+            DecompiledCodeItem::AssignStructureStatement {
+                variables, value, ..
+            } => {
+                cost.add(self.expr(value));
+                let fields = variables.len() as u64;
+                cost.add(Cost::gas(
+                    self.instr("unpack.base")
+                        + (self.instr("unpack.per_field") + self.instr("st_loc.base")) * fields,
+                ));
+            }
+            DecompiledCodeItem::Statement { expr } => {
+                cost.add(self.expr(expr));
+            }
+        }
+        let comment = cost.is_notable().then(|| cost.describe(""));
+        (cost, comment)
+    }
+
+    fn expr(&self, expr: &DecompiledExpr) -> Cost {
+        match expr {
+            DecompiledExpr::Undefined => Cost::default(),
+            DecompiledExpr::EvaluationExpr(expr) => self.node(expr.value()),
+            DecompiledExpr::Variable(_) => Cost::gas(self.instr("move_loc.base")),
+            DecompiledExpr::Tuple(exprs) => {
+                let mut cost = Cost::default();
+                for expr in exprs {
+                    cost.add(self.expr(expr));
+                }
+                cost
+            }
+        }
+    }
+
+    fn node(&self, node: &ExprNodeRef) -> Cost {
+        let node = node.borrow();
+        let (operands, own): (Vec<&ExprNodeRef>, Cost) = match &node.operation {
+            ExprNodeOperation::Ignored
+            | ExprNodeOperation::Deleted
+            | ExprNodeOperation::NonTrivial
+            | ExprNodeOperation::Raw(_) => (vec![], Cost::default()),
+            ExprNodeOperation::Const(c, _) | ExprNodeOperation::NamedConst(_, c) => {
+                (vec![], Cost::gas(self.constant(c)))
+            }
+            ExprNodeOperation::LocalVariable(_) => (vec![], Cost::gas(self.instr("copy_loc.base"))),
+            ExprNodeOperation::Field(expr, _) => {
+                (vec![expr], Cost::gas(self.instr("imm_borrow_field")))
+            }
+            ExprNodeOperation::Unary(_, expr) => (vec![expr], Cost::gas(self.instr("not"))),
+            ExprNodeOperation::Cast(ty, expr) => {
+                (vec![expr], Cost::gas(self.instr(&format!("cast_{}", ty))))
+            }
+            ExprNodeOperation::Binary(op, a, b) => (vec![a, b], Cost::gas(self.binary(op))),
+            ExprNodeOperation::Func(name, args, types) => (
+                args.iter().collect(),
+                self.call(name, args.len(), types.len()),
+            ),
+            ExprNodeOperation::MethodCall(_, args, types) => (
+                args.iter().collect(),
+                Cost::gas(self.call_cost(args.len(), types.len())),
+            ),
+            ExprNodeOperation::Destroy(expr) => (vec![expr], Cost::gas(self.instr("pop"))),
+            ExprNodeOperation::FreezeRef(expr) => (vec![expr], Cost::gas(self.instr("freeze_ref"))),
+            ExprNodeOperation::ReadRef(expr) => {
+                (vec![expr], Cost::gas(self.instr("read_ref.base")))
+            }
+            ExprNodeOperation::BorrowLocal(expr, _) => {
+                (vec![expr], Cost::gas(self.instr("imm_borrow_loc")))
+            }
+            ExprNodeOperation::WriteRef(dst, src) => {
+                (vec![dst, src], Cost::gas(self.instr("write_ref.base")))
+            }
+            ExprNodeOperation::StructPack(_, fields, _) => (
+                fields.iter().map(|(_, value)| value).collect(),
+                Cost::gas(
+                    self.instr("pack.base") + self.instr("pack.per_field") * fields.len() as u64,
+                ),
+            ),
+            ExprNodeOperation::StructUnpack(_, fields, value, _) => (
+                vec![value],
+                Cost::gas(
+                    self.instr("unpack.base")
+                        + self.instr("unpack.per_field") * fields.len() as u64,
+                ),
+            ),
+            ExprNodeOperation::VariableSnapshot { value, .. } => (vec![value], Cost::default()),
+        };
+
+        let mut cost = own;
+        for operand in operands {
+            cost.add(self.node(operand));
+        }
+        cost
+    }
+
+    fn constant(&self, constant: &Constant) -> u64 {
+        let vector = |bytes: usize| {
+            self.instr("ld_const.base") + self.instr("ld_const.per_byte") * bytes as u64
+        };
+        match constant {
+            Constant::U128(_) => self.instr("ld_u128"),
+            Constant::U256(_) => self.instr("ld_u256"),
+            Constant::ByteArray(bytes) => vector(bytes.len()),
+            Constant::AddressArray(addresses) => vector(addresses.len() * 32),
+            Constant::Vector(items) => vector(items.len() * 8),
+            _ => self.instr("ld_u64"),
+        }
+    }
+
+    fn binary(&self, op: &str) -> u64 {
+        let name = match op {
+            "+" => "add",
+            "-" => "sub",
+            "*" => "mul",
+            "/" => "div",
+            "%" => "mod",
+            "&" => "bit_and",
+            "|" => "bit_or",
+            "^" => "bit_xor",
+            "<<" => "bit_shl",
+            ">>" => "bit_shr",
+            "&&" => "and",
+            "||" => "or",
+            "==" => "eq.base",
+            "!=" => "neq.base",
+            "<" => "lt",
+            ">" => "gt",
+            "<=" => "le",
+            ">=" => "ge",
+            _ => return 0,
+        };
+        self.instr(name)
+    }
+
+    fn call_cost(&self, args: usize, types: usize) -> u64 {
+        if types == 0 {
+            self.instr("call.base") + self.instr("call.per_arg") * args as u64
+        } else {
+            self.instr("call_generic.base")
+                + self.instr("call_generic.per_arg") * args as u64
+                + self.instr("call_generic.per_ty_arg") * types as u64
+        }
+    }
+
+    fn call(&self, name: &str, args: usize, types: usize) -> Cost {
+        let read = || self.schedule.get("txn.storage_io_per_state_slot_read");
+        let write = || self.schedule.get("txn.storage_io_per_state_slot_write");
+        let storage = |gas: u64, reads: usize, writes: usize| Cost {
+            gas,
+            storage_reads: reads,
+            storage_writes: writes,
+            natives: BTreeSet::new(),
+        };
+        match name {
+            "borrow_global" => storage(self.instr("imm_borrow_global.base") + read(), 1, 0),
+            // the resource is written back when the transaction ends
+            "borrow_global_mut" => storage(
+                self.instr("mut_borrow_global.base") + read() + write(),
+                1,
+                1,
+            ),
+            "exists" => storage(self.instr("exists.base") + read(), 1, 0),
+            "move_from" => storage(self.instr("move_from.base") + read() + write(), 1, 1),
+            "move_to" => storage(self.instr("move_to.base") + read() + write(), 1, 1),
+            "assert!" => Cost::gas(self.instr("br_false")),
+            "0x1::vector::empty" => Cost::gas(self.instr("vec_pack.base")),
+            "0x1::vector::length" => Cost::gas(self.instr("vec_len.base")),
+            "0x1::vector::borrow" => Cost::gas(self.instr("vec_imm_borrow.base")),
+            "0x1::vector::borrow_mut" => Cost::gas(self.instr("vec_mut_borrow.base")),
+            "0x1::vector::push_back" => Cost::gas(self.instr("vec_push_back.base")),
+            "0x1::vector::pop_back" => Cost::gas(self.instr("vec_pop_back.base")),
+            "0x1::vector::swap" => Cost::gas(self.instr("vec_swap.base")),
+            "0x1::vector::destroy_empty" => Cost::gas(self.instr("vec_unpack.base")),
+            _ => {
+                let mut cost = Cost::gas(self.call_cost(args, types));
+                let native = resolve_function(self.func_target, name).filter(|f| f.is_native());
+                if let Some(native) = native {
+                    let module = native.module_env.get_name().name();
+                    let module = module.display(native.symbol_pool()).to_string();
+                    cost.gas += self
+                        .schedule
+                        .native_base(&module, &native.get_name_str())
+                        .unwrap_or(0);
+                    cost.natives.insert(name.to_string());
+                }
+                cost
+            }
+        }
+    }
+}
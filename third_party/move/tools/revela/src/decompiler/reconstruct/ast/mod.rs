@@ -2,7 +2,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::decompiler::evaluator::stackless::{ExprNodeOperation, ExprNodeRef};
 
@@ -10,7 +10,9 @@ use super::super::naming::Naming;
 
 use super::{super::evaluator::stackless::Expr, code_unit::SourceCodeUnit};
 
+pub mod gas;
 pub mod optimizers;
+pub mod spec;
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum DecompiledExpr {
@@ -106,6 +108,18 @@ impl DecompiledExpr {
         }
     }
 
+    pub fn any_sub_operation(&self, f: &mut dyn FnMut(&ExprNodeOperation) -> bool) -> bool {
+        match self {
+            DecompiledExpr::Undefined | DecompiledExpr::Variable(_) => false,
+
+            DecompiledExpr::EvaluationExpr(expr) => {
+                expr.value().borrow().operation.any_sub_operation(f)
+            }
+
+            DecompiledExpr::Tuple(exprs) => exprs.iter().any(|e| e.any_sub_operation(f)),
+        }
+    }
+
     pub fn rename_variables(&mut self, renamed_variables: &HashMap<usize, usize>) {
         match self {
             DecompiledExpr::Undefined => {}
@@ -126,6 +140,20 @@ impl DecompiledExpr {
         }
     }
 
+    pub fn rewrite_operations(&mut self, f: &mut dyn FnMut(&mut ExprNodeOperation)) {
+        match self {
+            DecompiledExpr::Undefined | DecompiledExpr::Variable(_) => {}
+
+            DecompiledExpr::EvaluationExpr(expr) => expr.rewrite_operations(f),
+
+            DecompiledExpr::Tuple(exprs) => {
+                for expr in exprs {
+                    expr.rewrite_operations(f);
+                }
+            }
+        }
+    }
+
     pub fn collect_variables(
         &self,
         result_variables: &mut HashSet<usize>,
@@ -234,6 +262,7 @@ pub(crate) enum DecompiledCodeItem {
     BreakStatement,
     ContinueStatement,
     CommentStatement(String),
+    BytecodeCommentStatement(Vec<String>),
     PossibleAssignStatement {
         #[allow(dead_code)]
         assigment_id: usize,
@@ -274,6 +303,13 @@ pub(crate) enum DecompiledCodeItem {
         cond: Option<DecompiledExprRef>,
         body: DecompiledCodeUnitRef,
     },
+    /// `for (variable in start..end) body`, requires Move 2
+    ForStatement {
+        variable: usize,
+        start: DecompiledExprRef,
+        end: DecompiledExprRef,
+        body: DecompiledCodeUnitRef,
+    },
 }
 
 pub(crate) type DecompiledCodeUnitRef = Box<DecompiledCodeUnit>;
@@ -284,6 +320,8 @@ pub(crate) struct DecompiledCodeUnit {
     exit: Option<DecompiledExprRef>,
     // sorted by variable index
     result_variables: Vec<usize>,
+    /// comments appended to the first line of `blocks[idx]`, set once the unit is final
+    trailing_comments: BTreeMap<usize, String>,
 }
 
 impl DecompiledCodeUnit {
@@ -292,6 +330,7 @@ impl DecompiledCodeUnit {
             blocks: Vec::new(),
             exit: None,
             result_variables: Vec::new(),
+            trailing_comments: BTreeMap::new(),
         })
     }
 
@@ -324,6 +363,14 @@ impl DecompiledCodeUnit {
         self.blocks.push(item);
     }
 
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn last(&self) -> Option<&DecompiledCodeItem> {
+        self.blocks.last()
+    }
+
     pub fn exit(
         &mut self,
         variables: Vec<usize>,
@@ -371,7 +418,8 @@ impl DecompiledCodeUnit {
 
                 DecompiledCodeItem::BreakStatement
                 | DecompiledCodeItem::ContinueStatement
-                | DecompiledCodeItem::CommentStatement(_) => {}
+                | DecompiledCodeItem::CommentStatement(_)
+                | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
                 DecompiledCodeItem::AssignStatement {
                     variable, value, ..
                 } => {
@@ -428,21 +476,69 @@ impl DecompiledCodeUnit {
                         return true;
                     }
                 }
+
+                DecompiledCodeItem::ForStatement {
+                    variable,
+                    start,
+                    end,
+                    body,
+                } => {
+                    if variables.contains(variable)
+                        || start.has_reference_to_any_variable(variables)
+                        || end.has_reference_to_any_variable(variables)
+                        || body.has_reference_to_any_variable(variables)
+                    {
+                        return true;
+                    }
+                }
             }
         }
 
         false
     }
 
+    /// The `if` making up the whole unit, so that an else branch holding it is rendered as
+    /// `else if` and ladders of conditions stay flat
+    fn cascaded_if(
+        &self,
+        produces_value: bool,
+    ) -> Option<(
+        &DecompiledExprRef,
+        &DecompiledCodeUnitRef,
+        &DecompiledCodeUnitRef,
+    )> {
+        if self.exit.is_some() || self.blocks.len() != 1 {
+            return None;
+        }
+        let expected_usage = if produces_value {
+            ResultUsageType::BlockResult
+        } else {
+            ResultUsageType::None
+        };
+        match &self.blocks[0] {
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                result_variables,
+                use_as_result,
+            } if result_variables.is_empty() && *use_as_result == expected_usage => {
+                Some((cond, if_unit, else_unit))
+            }
+            _ => None,
+        }
+    }
+
     pub fn to_source(
         &self,
         naming: &Naming,
         root_block: bool,
     ) -> Result<SourceCodeUnit, anyhow::Error> {
         let mut source = SourceCodeUnit::new(0);
-        let mut iter = self.blocks.iter().peekable();
+        let mut iter = self.blocks.iter().enumerate().peekable();
 
-        while let Some(item) = iter.next() {
+        while let Some((idx, item)) = iter.next() {
+            let first_line = source.item_count();
             let can_obmit_return = root_block && iter.peek().is_none() && self.exit.is_none();
             match item {
                 DecompiledCodeItem::PreDeclareStatement { variable } => {
@@ -520,6 +616,12 @@ impl DecompiledCodeUnit {
                     source.add_line(format!("/* {} */", comment));
                 }
 
+                DecompiledCodeItem::BytecodeCommentStatement(lines) => {
+                    for line in lines {
+                        source.add_line(format!("//   {}", line));
+                    }
+                }
+
                 DecompiledCodeItem::AssignStatement {
                     variable,
                     value,
@@ -565,7 +667,7 @@ impl DecompiledCodeUnit {
                     value,
                 } => {
                     if variables.len() >= 2 {
-                        source.add_line(format!("let {} {{", structure_visible_name));
+                        source.add_line(format!("let {} {{", naming.path(structure_visible_name)));
                         let mut inner_unit = SourceCodeUnit::new(1);
                         let k_max_width = variables.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
 
@@ -583,7 +685,7 @@ impl DecompiledCodeUnit {
                     } else {
                         source.add_line(format!(
                             "let {} {{ {} }} = {};",
-                            structure_visible_name,
+                            naming.path(structure_visible_name),
                             variables
                                 .iter()
                                 .map(|(k, v)| format!("{}: {}", k, naming.variable(*v)))
@@ -624,6 +726,19 @@ impl DecompiledCodeUnit {
                     if_b.add_indent(1);
                     source.add_block(if_b);
 
+                    let produces_value =
+                        use_as_result != &ResultUsageType::None || !result_variables.is_empty();
+                    let mut else_unit = else_unit;
+                    while let Some((cond, if_unit, next_else_unit)) =
+                        else_unit.cascaded_if(produces_value)
+                    {
+                        source.add_line(format!("}} else if ({}) {{", cond.to_source(naming)?));
+                        let mut if_b = if_unit.to_source(naming, false)?;
+                        if_b.add_indent(1);
+                        source.add_block(if_b);
+                        else_unit = next_else_unit;
+                    }
+
                     let mut else_b = else_unit.to_source(naming, false)?;
                     else_b.add_indent(1);
 
@@ -654,6 +769,29 @@ impl DecompiledCodeUnit {
                     source.add_block(b);
                     source.add_line(format!("}};"));
                 }
+
+                DecompiledCodeItem::ForStatement {
+                    variable,
+                    start,
+                    end,
+                    body,
+                } => {
+                    source.add_line(format!(
+                        "for ({} in {}..{}) {{",
+                        naming.variable(*variable),
+                        start.to_source(naming)?,
+                        end.to_source(naming)?
+                    ));
+
+                    let mut b = body.to_source(naming, false)?;
+                    b.add_indent(1);
+                    source.add_block(b);
+                    source.add_line(format!("}};"));
+                }
+            }
+
+            if let Some(comment) = self.trailing_comments.get(&idx) {
+                source.append_to_line(first_line, &format!(" // {}", comment));
             }
         }
 
@@ -4,7 +4,7 @@
 
 use std::{
     cmp::Ordering,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
 };
 
 use move_stackless_bytecode::function_target::FunctionTarget;
@@ -12,26 +12,43 @@ use move_stackless_bytecode::function_target::FunctionTarget;
 use crate::decompiler::{naming::Naming, reconstruct::ast::DecompiledExprRef};
 
 use self::transform::{
-    assert::*, cleanup_tail_exit::*, if_else::*, let_return::*, loops::*, non_source_blocks::*,
+    abort_codes::*, assert::*, cleanup_tail_exit::*, dead_stores::*, idioms::*, if_else::*,
+    let_return::*, loops::*, non_source_blocks::*, receiver_calls::*, references::*, type_args::*,
     variables::*,
 };
 
 use super::super::DecompiledCodeUnitRef;
 mod transform;
-mod utils;
+pub(super) mod utils;
 mod variable_declaration;
 
 use utils::*;
 use variable_declaration::*;
 
+#[derive(Debug)]
 pub struct OptimizerSettings {
     pub disable_optimize_variables_declaration: bool,
+    /// emit `for (i in start..end)` for counted loops, the output then requires Move 2
+    pub enable_for_loop_syntax: bool,
+    /// emit calls such as `vector::length(&v)` as `v.length()`, the output then requires Move 2
+    pub enable_receiver_call_syntax: bool,
+    /// keep assignments that are never read, so that the output follows the bytecode closely
+    pub keep_dead_stores: bool,
+    /// omit type arguments of calls where the compiler infers them from the value arguments
+    pub elide_inferable_type_args: bool,
+    /// fold opaque predicates and unflatten dispatcher loops before the control flow is structured
+    pub deobfuscate: bool,
 }
 
 impl Default for OptimizerSettings {
     fn default() -> Self {
         Self {
             disable_optimize_variables_declaration: false,
+            enable_for_loop_syntax: false,
+            enable_receiver_call_syntax: false,
+            keep_dead_stores: false,
+            elide_inferable_type_args: false,
+            deobfuscate: false,
         }
     }
 }
@@ -41,6 +58,7 @@ pub(crate) fn run(
     func_target: &FunctionTarget<'_>,
     naming: &Naming,
     settings: &OptimizerSettings,
+    error_constants: &BTreeMap<u64, String>,
 ) -> Result<(DecompiledCodeUnitRef, HashSet<usize>), anyhow::Error> {
     let mut unit = unit.clone();
 
@@ -48,8 +66,11 @@ pub(crate) fn run(
     let mut unit = rewrite_short_circuit_if_else(&unit, func_target, true)?;
 
     rewrite_loop(&mut unit)?;
+    rewrite_stdlib_idioms(&mut unit, func_target)?;
+    simplify_reference_chains(&mut unit);
     rewrite_let_var_return(&mut unit)?;
     let mut unit = rewrite_assert(&unit)?;
+    rewrite_abort_codes(&mut unit, error_constants);
     rewrite_let_if_return(&mut unit)?;
 
     if !settings.disable_optimize_variables_declaration {
@@ -59,8 +80,24 @@ pub(crate) fn run(
 
     let mut unit = remove_non_source_blocks(&unit)?;
 
+    if !settings.keep_dead_stores {
+        remove_dead_stores(&mut unit, func_target);
+    }
+
     rename_variables_by_order(&mut unit, func_target);
 
+    if settings.enable_for_loop_syntax {
+        rewrite_for_loop(&mut unit, func_target)?;
+    }
+
+    if settings.enable_receiver_call_syntax {
+        rewrite_receiver_calls(&mut unit, func_target)?;
+    }
+
+    if settings.elide_inferable_type_args {
+        elide_inferable_type_args(&mut unit, func_target)?;
+    }
+
     let mut referenced_variables = HashSet::new();
     let mut implicit_referenced_variables = HashSet::new();
     collect_referenced_variables(
@@ -86,7 +123,8 @@ fn rename_variables_by_order(unit: &mut DecompiledCodeUnitRef, func_target: &Fun
         .map(|x| *x)
         .collect();
 
-    let live_variables = live_variables.into_iter().collect::<Vec<_>>();
+    let mut live_variables = live_variables.into_iter().collect::<Vec<_>>();
+    live_variables.sort();
 
     let mut variables_declaration_order = Vec::new();
     get_variable_declaration_order(unit, &mut variables_declaration_order);
@@ -141,6 +179,13 @@ fn optimize_variables_declaration(
                     }
                     initialize_solver(solver, body);
                 }
+                I::ForStatement {
+                    start, end, body, ..
+                } => {
+                    solver.add_expr(start);
+                    solver.add_expr(end);
+                    initialize_solver(solver, body);
+                }
                 I::ReturnStatement(expr)
                 | I::AbortStatement(expr)
                 | I::AssignStatement { value: expr, .. }
@@ -152,6 +197,7 @@ fn optimize_variables_declaration(
                 I::BreakStatement
                 | I::ContinueStatement
                 | I::CommentStatement(_)
+                | I::BytecodeCommentStatement(_)
                 | I::PreDeclareStatement { .. } => {}
                 I::PossibleAssignStatement {
                     assigment_id: _,
@@ -201,6 +247,20 @@ fn optimize_variables_declaration(
                             .map(|c| c.commit_pending_variables(should_declare)),
                     });
                 }
+                I::ForStatement {
+                    variable,
+                    start,
+                    end,
+                    body,
+                } => {
+                    let new_body = apply_variable_declaration(body, should_declare)?;
+                    new_unit.blocks.push(I::ForStatement {
+                        variable: *variable,
+                        start: start.commit_pending_variables(should_declare),
+                        end: end.commit_pending_variables(should_declare),
+                        body: new_body,
+                    });
+                }
                 I::ReturnStatement(expr) => {
                     new_unit.blocks.push(I::ReturnStatement(
                         expr.commit_pending_variables(should_declare),
@@ -249,7 +309,10 @@ fn optimize_variables_declaration(
                         expr: expr.commit_pending_variables(should_declare),
                     });
                 }
-                I::BreakStatement | I::ContinueStatement | I::CommentStatement(_) => {
+                I::BreakStatement
+                | I::ContinueStatement
+                | I::CommentStatement(_)
+                | I::BytecodeCommentStatement(_) => {
                     new_unit.blocks.push(item.clone());
                 }
                 I::PreDeclareStatement { variable } => {
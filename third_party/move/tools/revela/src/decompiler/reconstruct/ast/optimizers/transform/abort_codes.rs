@@ -0,0 +1,109 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use move_stackless_bytecode::stackless_bytecode::Constant;
+
+use crate::decompiler::{
+    evaluator::stackless::{ExprNodeOperation, ExprNodeRef},
+    reconstruct::{DecompiledCodeItem, DecompiledCodeUnit, DecompiledExpr},
+};
+
+/// Helpers of `std::error` taking the error reason as their only argument
+const ERROR_CATEGORY_HELPERS: &[&str] = &[
+    "0x1::error::invalid_argument",
+    "0x1::error::out_of_range",
+    "0x1::error::invalid_state",
+    "0x1::error::unauthenticated",
+    "0x1::error::permission_denied",
+    "0x1::error::not_found",
+    "0x1::error::aborted",
+    "0x1::error::already_exists",
+    "0x1::error::resource_exhausted",
+    "0x1::error::internal",
+    "0x1::error::not_implemented",
+    "0x1::error::unavailable",
+];
+
+/// Render abort codes with the module error constants
+///  - abort 1 -> abort E_FOO
+///  - abort error::invalid_argument(1) -> abort error::invalid_argument(E_FOO)
+///  - assert!(cond, error::invalid_argument(1)) -> assert!(cond, error::invalid_argument(E_FOO))
+pub(crate) fn rewrite_abort_codes(
+    unit: &mut DecompiledCodeUnit,
+    error_constants: &BTreeMap<u64, String>,
+) {
+    if error_constants.is_empty() {
+        return;
+    }
+
+    for item in unit.blocks.iter_mut() {
+        match item {
+            DecompiledCodeItem::AbortStatement(expr) => {
+                if let DecompiledExpr::EvaluationExpr(code) = expr.as_ref() {
+                    name_abort_code(code.value(), error_constants);
+                }
+            }
+            DecompiledCodeItem::Statement { expr } => {
+                expr.rewrite_operations(&mut |op| {
+                    if let ExprNodeOperation::Func(name, args, _) = op {
+                        if name == "assert!" && args.len() == 2 {
+                            name_abort_code(&args[1], error_constants);
+                        }
+                    }
+                });
+            }
+            DecompiledCodeItem::IfElseStatement {
+                if_unit, else_unit, ..
+            } => {
+                rewrite_abort_codes(if_unit, error_constants);
+                rewrite_abort_codes(else_unit, error_constants);
+            }
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
+                rewrite_abort_codes(body, error_constants);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn name_const(expr: &ExprNodeRef, error_constants: &BTreeMap<u64, String>) {
+    let mut node = expr.borrow_mut();
+    let named = match &node.operation {
+        ExprNodeOperation::Const(c @ Constant::U64(code), _) => error_constants
+            .get(code)
+            .map(|name| ExprNodeOperation::NamedConst(name.clone(), c.clone())),
+        _ => None,
+    };
+    if let Some(named) = named {
+        node.operation = named;
+    }
+}
+
+fn name_abort_code(expr: &ExprNodeRef, error_constants: &BTreeMap<u64, String>) {
+    let reason = match &expr.borrow().operation {
+        ExprNodeOperation::Const(..) => Some(expr.clone()),
+        ExprNodeOperation::VariableSnapshot { value, .. } => {
+            name_abort_code(value, error_constants);
+            None
+        }
+        ExprNodeOperation::Func(name, args, _)
+            if args.len() == 1 && ERROR_CATEGORY_HELPERS.contains(&name.as_str()) =>
+        {
+            Some(args[0].clone())
+        }
+        ExprNodeOperation::Func(name, args, _)
+            if args.len() == 2 && name == "0x1::error::canonical" =>
+        {
+            Some(args[1].clone())
+        }
+        _ => None,
+    };
+
+    if let Some(reason) = reason {
+        name_const(&reason, error_constants);
+    }
+}
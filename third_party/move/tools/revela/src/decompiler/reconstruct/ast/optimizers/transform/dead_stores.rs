@@ -0,0 +1,293 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use move_stackless_bytecode::function_target::FunctionTarget;
+
+use crate::decompiler::{
+    evaluator::stackless::ExprNodeOperation,
+    reconstruct::{DecompiledCodeItem, DecompiledCodeUnit, DecompiledExpr},
+};
+
+/// Remove assignments to local variables that are never read afterwards, e.g.
+///  - let v3 = &mut v1; (v3 is not used) -> (removed)
+///
+/// Only values that cannot abort nor have side effects are removed, parameters are kept. A
+/// removed declaration moves to the next assignment of the variable, or is kept if that
+/// assignment is not in the same block.
+pub(crate) fn remove_dead_stores(unit: &mut DecompiledCodeUnit, func_target: &FunctionTarget<'_>) {
+    let param_count = func_target.get_parameter_count();
+    // removing a store may leave the variables of its value unread
+    loop {
+        let mut read_variables = HashSet::new();
+        collect_read_variables(unit, &mut read_variables);
+        let mut kept_stores = HashSet::new();
+        collect_kept_stores(unit, &mut kept_stores);
+        if !remove_unread_stores(unit, &read_variables, &kept_stores, param_count) {
+            break;
+        }
+    }
+
+    let mut read_variables = HashSet::new();
+    collect_read_variables(unit, &mut read_variables);
+    let mut assigned_variables = HashSet::new();
+    collect_assigned_variables(unit, &mut assigned_variables);
+    remove_unused_declarations(unit, &read_variables, &assigned_variables);
+}
+
+fn collect_expr_variables(expr: &DecompiledExpr, read_variables: &mut HashSet<usize>) {
+    let mut implicit_variables = HashSet::new();
+    expr.collect_variables(read_variables, &mut implicit_variables, false);
+    read_variables.extend(implicit_variables);
+}
+
+fn collect_read_variables(unit: &DecompiledCodeUnit, read_variables: &mut HashSet<usize>) {
+    if let Some(exit) = unit.exit.as_ref() {
+        collect_expr_variables(exit, read_variables);
+    }
+    for item in unit.blocks.iter() {
+        match item {
+            DecompiledCodeItem::PossibleAssignStatement {
+                variable, value, ..
+            } => {
+                read_variables.insert(*variable);
+                collect_expr_variables(value, read_variables);
+            }
+            DecompiledCodeItem::AssignStatement { value, .. }
+            | DecompiledCodeItem::AssignStructureStatement { value, .. }
+            | DecompiledCodeItem::Statement { expr: value }
+            | DecompiledCodeItem::ReturnStatement(value)
+            | DecompiledCodeItem::AbortStatement(value) => {
+                collect_expr_variables(value, read_variables);
+            }
+            DecompiledCodeItem::AssignTupleStatement {
+                variables, value, ..
+            } => {
+                read_variables.extend(variables.iter());
+                collect_expr_variables(value, read_variables);
+            }
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                result_variables,
+                ..
+            } => {
+                read_variables.extend(result_variables.iter());
+                collect_expr_variables(cond, read_variables);
+                collect_read_variables(if_unit, read_variables);
+                collect_read_variables(else_unit, read_variables);
+            }
+            DecompiledCodeItem::WhileStatement { cond, body } => {
+                if let Some(cond) = cond {
+                    collect_expr_variables(cond, read_variables);
+                }
+                collect_read_variables(body, read_variables);
+            }
+            DecompiledCodeItem::ForStatement {
+                variable,
+                start,
+                end,
+                body,
+            } => {
+                read_variables.insert(*variable);
+                collect_expr_variables(start, read_variables);
+                collect_expr_variables(end, read_variables);
+                collect_read_variables(body, read_variables);
+            }
+            DecompiledCodeItem::PreDeclareStatement { .. }
+            | DecompiledCodeItem::BreakStatement
+            | DecompiledCodeItem::ContinueStatement
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
+        }
+    }
+}
+
+fn collect_assigned_variables(unit: &DecompiledCodeUnit, assigned_variables: &mut HashSet<usize>) {
+    for item in unit.blocks.iter() {
+        match item {
+            DecompiledCodeItem::AssignStatement { variable, .. } => {
+                assigned_variables.insert(*variable);
+            }
+            DecompiledCodeItem::IfElseStatement {
+                if_unit, else_unit, ..
+            } => {
+                collect_assigned_variables(if_unit, assigned_variables);
+                collect_assigned_variables(else_unit, assigned_variables);
+            }
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
+                collect_assigned_variables(body, assigned_variables);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Variables with assignments that are not removed whether they are read or not
+fn collect_kept_stores(unit: &DecompiledCodeUnit, kept_stores: &mut HashSet<usize>) {
+    for item in unit.blocks.iter() {
+        match item {
+            DecompiledCodeItem::AssignStatement {
+                variable, value, ..
+            } if !is_removable_value(value) => {
+                kept_stores.insert(*variable);
+            }
+            DecompiledCodeItem::IfElseStatement {
+                if_unit, else_unit, ..
+            } => {
+                collect_kept_stores(if_unit, kept_stores);
+                collect_kept_stores(else_unit, kept_stores);
+            }
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
+                collect_kept_stores(body, kept_stores);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The item assigns `variable`, directly or in a nested block
+fn assigns_variable(item: &DecompiledCodeItem, variable: usize) -> bool {
+    match item {
+        DecompiledCodeItem::AssignStatement { variable: v, .. }
+        | DecompiledCodeItem::PossibleAssignStatement { variable: v, .. } => *v == variable,
+        DecompiledCodeItem::AssignTupleStatement { variables, .. } => variables.contains(&variable),
+        DecompiledCodeItem::AssignStructureStatement { variables, .. } => {
+            variables.iter().any(|(_, v)| *v == variable)
+        }
+        DecompiledCodeItem::IfElseStatement {
+            if_unit,
+            else_unit,
+            result_variables,
+            ..
+        } => {
+            result_variables.contains(&variable)
+                || if_unit.blocks.iter().any(|x| assigns_variable(x, variable))
+                || else_unit
+                    .blocks
+                    .iter()
+                    .any(|x| assigns_variable(x, variable))
+        }
+        DecompiledCodeItem::WhileStatement { body, .. } => {
+            body.blocks.iter().any(|x| assigns_variable(x, variable))
+        }
+        DecompiledCodeItem::ForStatement {
+            variable: v, body, ..
+        } => *v == variable || body.blocks.iter().any(|x| assigns_variable(x, variable)),
+        _ => false,
+    }
+}
+
+/// The value can be dropped without changing what the function does
+fn is_removable_value(value: &DecompiledExpr) -> bool {
+    match value {
+        DecompiledExpr::EvaluationExpr(expr) => {
+            !expr
+                .value()
+                .borrow()
+                .operation
+                .any_sub_operation(&mut |op| match op {
+                    ExprNodeOperation::Const(..)
+                    | ExprNodeOperation::NamedConst(..)
+                    | ExprNodeOperation::LocalVariable(..)
+                    | ExprNodeOperation::Field(..)
+                    | ExprNodeOperation::FreezeRef(..)
+                    | ExprNodeOperation::ReadRef(..)
+                    | ExprNodeOperation::BorrowLocal(..) => false,
+                    ExprNodeOperation::Unary(op, _) => op != "!",
+                    _ => true,
+                })
+        }
+        DecompiledExpr::Variable(_) => true,
+        DecompiledExpr::Undefined | DecompiledExpr::Tuple(_) => false,
+    }
+}
+
+fn remove_unread_stores(
+    unit: &mut DecompiledCodeUnit,
+    read_variables: &HashSet<usize>,
+    kept_stores: &HashSet<usize>,
+    param_count: usize,
+) -> bool {
+    let mut removed = false;
+    let mut idx = 0;
+    while idx < unit.blocks.len() {
+        let declared = match &mut unit.blocks[idx] {
+            DecompiledCodeItem::AssignStatement {
+                variable,
+                value,
+                is_decl,
+            } if *variable >= param_count
+                && !read_variables.contains(variable)
+                && is_removable_value(value) =>
+            {
+                Some((*variable, *is_decl))
+            }
+            DecompiledCodeItem::IfElseStatement {
+                if_unit, else_unit, ..
+            } => {
+                removed |= remove_unread_stores(if_unit, read_variables, kept_stores, param_count);
+                removed |=
+                    remove_unread_stores(else_unit, read_variables, kept_stores, param_count);
+                None
+            }
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
+                removed |= remove_unread_stores(body, read_variables, kept_stores, param_count);
+                None
+            }
+            _ => None,
+        };
+        let Some((variable, is_decl)) = declared else {
+            idx += 1;
+            continue;
+        };
+
+        if is_decl && kept_stores.contains(&variable) {
+            // other assignments of the variable remain, the first of them must declare it
+            match unit.blocks[idx + 1..]
+                .iter_mut()
+                .find(|x| assigns_variable(x, variable))
+            {
+                Some(DecompiledCodeItem::AssignStatement { is_decl, .. }) => *is_decl = true,
+                _ => {
+                    idx += 1;
+                    continue;
+                }
+            }
+        }
+        unit.blocks.remove(idx);
+        removed = true;
+    }
+    removed
+}
+
+fn remove_unused_declarations(
+    unit: &mut DecompiledCodeUnit,
+    read_variables: &HashSet<usize>,
+    assigned_variables: &HashSet<usize>,
+) {
+    unit.blocks.retain_mut(|item| match item {
+        DecompiledCodeItem::PreDeclareStatement { variable } => {
+            read_variables.contains(variable) || assigned_variables.contains(variable)
+        }
+        DecompiledCodeItem::IfElseStatement {
+            if_unit, else_unit, ..
+        } => {
+            remove_unused_declarations(if_unit, read_variables, assigned_variables);
+            remove_unused_declarations(else_unit, read_variables, assigned_variables);
+            true
+        }
+        DecompiledCodeItem::WhileStatement { body, .. }
+        | DecompiledCodeItem::ForStatement { body, .. } => {
+            remove_unused_declarations(body, read_variables, assigned_variables);
+            true
+        }
+        _ => true,
+    });
+}
@@ -0,0 +1,519 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use move_model::ty::Type;
+use move_stackless_bytecode::{function_target::FunctionTarget, stackless_bytecode::Constant};
+
+use crate::decompiler::{
+    evaluator::stackless::{ExprNodeOperation, ExprNodeRef},
+    reconstruct::{ast::ResultUsageType, DecompiledCodeItem, DecompiledCodeUnit, DecompiledExpr},
+};
+
+use super::super::utils::{has_effective_statement, rewrite_expr_operations};
+
+/// Rewrite common lowerings of standard library calls back into their concise form
+///  - !option::is_none(x) -> option::is_some(x), and vice versa
+///  - vector::length(x) == 0 -> vector::is_empty(x)
+///  - vector::length(x) != 0 -> !vector::is_empty(x)
+///  - if (table::contains(t, k)) { *table::borrow(t, k) } else { d }
+///       -> *table::borrow_with_default(t, k, &d)
+///  - while (!vector::is_empty(&v)) { vector::push_back(r, vector::pop_back(&mut v)) };
+///    vector::destroy_empty(v);
+///       -> vector::reverse_append(r, v)
+pub(crate) fn rewrite_stdlib_idioms(
+    unit: &mut DecompiledCodeUnit,
+    func_target: &FunctionTarget<'_>,
+) -> Result<(), anyhow::Error> {
+    rewrite_expr_operations(unit, &mut rewrite_call_idiom);
+
+    // (parameter, number of references in the function)
+    let vector_parameters: Vec<_> = (0..func_target.get_parameter_count())
+        .filter(|i| matches!(func_target.get_local_type(*i), Type::Vector(_)))
+        .map(|i| (i, count_variable_references(unit, i)))
+        .collect();
+    let parameters = (0..func_target.get_parameter_count()).collect();
+
+    rewrite_statement_idioms(unit, &vector_parameters, false, &parameters)
+}
+
+/// Whether `name` refers to `function`, e.g. `vector::length`, whatever the prefix of the
+/// module path it is rendered with
+fn is_function(name: &str, function: &str) -> bool {
+    name == function
+        || name
+            .strip_suffix(function)
+            .is_some_and(|prefix| prefix.ends_with("::"))
+}
+
+/// `name` calling `function` of the same module instead, keeping the prefix of the path
+fn sibling_function(name: &str, function: &str) -> String {
+    match name.rsplit_once("::") {
+        Some((module, _)) => format!("{}::{}", module, function),
+        None => function.to_string(),
+    }
+}
+
+fn call_args<'a>(
+    op: &'a ExprNodeOperation,
+    functions: &[&str],
+) -> Option<(&'a str, &'a Vec<ExprNodeRef>, &'a Vec<Type>)> {
+    match op {
+        ExprNodeOperation::Func(name, args, types)
+            if functions.iter().any(|function| is_function(name, function)) =>
+        {
+            Some((name.as_str(), args, types))
+        }
+        _ => None,
+    }
+}
+
+fn is_zero(expr: &ExprNodeRef) -> bool {
+    matches!(
+        &expr.borrow().operation,
+        ExprNodeOperation::Const(Constant::U64(0), _)
+    )
+}
+
+fn rewrite_call_idiom(op: &mut ExprNodeOperation) {
+    let new_op = match &*op {
+        ExprNodeOperation::Unary(unary, inner) if unary == "!" => {
+            match call_args(
+                &inner.borrow().operation,
+                &["option::is_none", "option::is_some"],
+            ) {
+                Some((name, args, types)) => Some(ExprNodeOperation::Func(
+                    if is_function(name, "option::is_none") {
+                        sibling_function(name, "is_some")
+                    } else {
+                        sibling_function(name, "is_none")
+                    },
+                    args.clone(),
+                    types.clone(),
+                )),
+                None => None,
+            }
+        }
+        ExprNodeOperation::Binary(binop, a, b) if binop == "==" || binop == "!=" => {
+            let length = if is_zero(b) {
+                a
+            } else if is_zero(a) {
+                b
+            } else {
+                return;
+            };
+            match call_args(&length.borrow().operation, &["vector::length"]) {
+                Some((name, args, types)) => {
+                    let is_empty = ExprNodeOperation::Func(
+                        sibling_function(name, "is_empty"),
+                        args.clone(),
+                        types.clone(),
+                    );
+                    if binop == "==" {
+                        Some(is_empty)
+                    } else {
+                        Some(ExprNodeOperation::Unary(
+                            "!".to_string(),
+                            is_empty.to_node(),
+                        ))
+                    }
+                }
+                None => None,
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(new_op) = new_op {
+        *op = new_op;
+    }
+}
+
+/// `declared` holds the variables declared before `unit`, in the enclosing units
+fn rewrite_statement_idioms(
+    unit: &mut DecompiledCodeUnit,
+    vector_parameters: &[(usize, usize)],
+    in_loop: bool,
+    declared: &HashSet<usize>,
+) -> Result<(), anyhow::Error> {
+    let mut declared = declared.clone();
+    for item in unit.blocks.iter_mut() {
+        match item {
+            DecompiledCodeItem::IfElseStatement {
+                if_unit, else_unit, ..
+            } => {
+                rewrite_statement_idioms(if_unit, vector_parameters, in_loop, &declared)?;
+                rewrite_statement_idioms(else_unit, vector_parameters, in_loop, &declared)?;
+            }
+            DecompiledCodeItem::WhileStatement { body, .. } => {
+                rewrite_statement_idioms(body, vector_parameters, true, &declared)?;
+            }
+            DecompiledCodeItem::ForStatement { variable, body, .. } => {
+                let mut body_declared = declared.clone();
+                body_declared.insert(*variable);
+                rewrite_statement_idioms(body, vector_parameters, true, &body_declared)?;
+            }
+            _ => {}
+        }
+
+        if let Some(new_item) = table_borrow_with_default(item, &declared)? {
+            *item = new_item;
+        }
+        declared.extend(declared_variables(item));
+    }
+
+    if in_loop {
+        return Ok(());
+    }
+
+    let mut idx = 0;
+    while idx < unit.blocks.len() {
+        if let Some((new_item, destroy_idx)) = vector_reverse_append(unit, idx, vector_parameters)?
+        {
+            unit.blocks[idx] = new_item;
+            if let Some(destroy_idx) = destroy_idx {
+                unit.blocks.remove(destroy_idx);
+            }
+        }
+        idx += 1;
+    }
+
+    Ok(())
+}
+
+/// Variables that `item` declares for the statements following it
+fn declared_variables(item: &DecompiledCodeItem) -> Vec<usize> {
+    match item {
+        DecompiledCodeItem::PreDeclareStatement { variable }
+        | DecompiledCodeItem::AssignStatement {
+            variable,
+            is_decl: true,
+            ..
+        }
+        | DecompiledCodeItem::PossibleAssignStatement {
+            variable,
+            is_decl: true,
+            ..
+        } => vec![*variable],
+        DecompiledCodeItem::AssignTupleStatement {
+            variables,
+            is_decl: true,
+            ..
+        } => variables.clone(),
+        DecompiledCodeItem::AssignStructureStatement { variables, .. } => {
+            variables.iter().map(|(_, v)| *v).collect()
+        }
+        DecompiledCodeItem::IfElseStatement {
+            result_variables,
+            use_as_result: ResultUsageType::None,
+            ..
+        } => result_variables.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn table_borrow_with_default(
+    item: &DecompiledCodeItem,
+    declared: &HashSet<usize>,
+) -> Result<Option<DecompiledCodeItem>, anyhow::Error> {
+    let (cond, if_unit, else_unit, variable) = match item {
+        DecompiledCodeItem::IfElseStatement {
+            cond,
+            if_unit,
+            else_unit,
+            result_variables,
+            use_as_result: ResultUsageType::None,
+        } if result_variables.len() == 1 => (cond, if_unit, else_unit, result_variables[0]),
+        _ => return Ok(None),
+    };
+
+    if has_effective_statement(&if_unit.blocks) || has_effective_statement(&else_unit.blocks) {
+        return Ok(None);
+    }
+    let (if_exit, else_exit) = match (&if_unit.exit, &else_unit.exit) {
+        (Some(if_exit), Some(else_exit)) => (if_exit.to_expr()?, else_exit.to_expr()?),
+        _ => return Ok(None),
+    };
+
+    let cond = cond.to_expr()?;
+    let cond = cond.borrow();
+    let (contains, args, types) = match call_args(
+        &cond.operation,
+        &["table::contains", "smart_table::contains"],
+    ) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let if_exit = if_exit.borrow();
+    let borrowed = match &if_exit.operation {
+        ExprNodeOperation::ReadRef(borrowed) => borrowed.borrow(),
+        _ => return Ok(None),
+    };
+    let borrow_name = sibling_function(contains, "borrow");
+    match call_args(&borrowed.operation, &["borrow"]) {
+        Some((name, borrow_args, borrow_types))
+            if name == borrow_name
+                && borrow_types == types
+                && borrow_args.len() == 2
+                && args.len() == 2
+                && borrow_args
+                    .iter()
+                    .zip(args.iter())
+                    .all(|(a, b)| a.borrow().to_string() == b.borrow().to_string()) => {}
+        _ => return Ok(None),
+    }
+
+    // arguments are evaluated once instead of twice, and the default is evaluated eagerly
+    let is_pure = |expr: &ExprNodeRef| {
+        matches!(
+            &expr.borrow().operation,
+            ExprNodeOperation::LocalVariable(_) | ExprNodeOperation::Const(..)
+        )
+    };
+    let has_call = args[0].borrow().operation.any_sub_operation(&mut |op| {
+        matches!(
+            op,
+            ExprNodeOperation::Func(..) | ExprNodeOperation::VariableSnapshot { .. }
+        )
+    });
+    if has_call || !is_pure(&args[1]) || !is_pure(&else_exit) {
+        return Ok(None);
+    }
+
+    let value = ExprNodeOperation::ReadRef(
+        ExprNodeOperation::Func(
+            sibling_function(contains, "borrow_with_default"),
+            vec![
+                args[0].clone(),
+                args[1].clone(),
+                ExprNodeOperation::BorrowLocal(else_exit.clone(), false).to_node(),
+            ],
+            types.clone(),
+        )
+        .to_node(),
+    );
+
+    // the if-else declares its result unless it assigns a variable declared before, e.g. a
+    // parameter
+    Ok(Some(DecompiledCodeItem::AssignStatement {
+        variable,
+        value: DecompiledExpr::EvaluationExpr(value.to_expr()).boxed(),
+        is_decl: !declared.contains(&variable),
+    }))
+}
+
+fn is_borrow_of(expr: &ExprNodeRef, variable: usize, mutable: bool) -> bool {
+    match &expr.borrow().operation {
+        ExprNodeOperation::BorrowLocal(inner, m) if *m == mutable => matches!(
+            &inner.borrow().operation,
+            ExprNodeOperation::LocalVariable(v) if *v == variable
+        ),
+        _ => false,
+    }
+}
+
+fn is_comment(item: &DecompiledCodeItem) -> bool {
+    matches!(
+        item,
+        DecompiledCodeItem::CommentStatement(..) | DecompiledCodeItem::BytecodeCommentStatement(..)
+    )
+}
+
+/// Returns the rewritten statement for the loop at `idx`, and the index of the
+/// `vector::destroy_empty` call to remove
+fn vector_reverse_append(
+    unit: &DecompiledCodeUnit,
+    idx: usize,
+    vector_parameters: &[(usize, usize)],
+) -> Result<Option<(DecompiledCodeItem, Option<usize>)>, anyhow::Error> {
+    let (cond, body) = match &unit.blocks[idx] {
+        DecompiledCodeItem::WhileStatement {
+            cond: Some(cond),
+            body,
+        } => (cond.to_expr()?, body),
+        _ => return Ok(None),
+    };
+
+    let variable = match &cond.borrow().operation {
+        ExprNodeOperation::Unary(op, inner) if op == "!" => {
+            match call_args(&inner.borrow().operation, &["vector::is_empty"]) {
+                Some((_, args, _)) if args.len() == 1 => vector_parameters
+                    .iter()
+                    .map(|(v, _)| *v)
+                    .find(|v| is_borrow_of(&args[0], *v, false)),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    let variable = match variable {
+        Some(variable) => variable,
+        None => return Ok(None),
+    };
+
+    let mut body_items = body.blocks.iter().filter(|x| !is_comment(x));
+    let push_back = match (body_items.next(), body_items.next(), &body.exit) {
+        (Some(DecompiledCodeItem::Statement { expr }), None, None) => expr.to_expr()?,
+        _ => return Ok(None),
+    };
+    let push_back = push_back.borrow();
+    let (push_back_name, dst, types) = match call_args(&push_back.operation, &["vector::push_back"])
+    {
+        Some((name, args, types)) if args.len() == 2 => {
+            match call_args(&args[1].borrow().operation, &["vector::pop_back"]) {
+                Some((_, pop_args, _))
+                    if pop_args.len() == 1 && is_borrow_of(&pop_args[0], variable, true) => {}
+                _ => return Ok(None),
+            }
+            (name.to_string(), args[0].clone(), types.clone())
+        }
+        _ => return Ok(None),
+    };
+    if dst
+        .borrow()
+        .operation
+        .has_reference_to_any_variable(&HashSet::from([variable]))
+    {
+        return Ok(None);
+    }
+
+    let destroy_idx = unit.blocks[idx + 1..]
+        .iter()
+        .position(|x| !is_comment(x))
+        .map(|x| x + idx + 1)
+        .filter(|destroy_idx| {
+            if let DecompiledCodeItem::Statement { expr } = &unit.blocks[*destroy_idx] {
+                expr.to_expr().map_or(false, |expr| {
+                    match call_args(&expr.borrow().operation, &["vector::destroy_empty"]) {
+                        Some((_, args, _)) => {
+                            args.len() == 1
+                                && matches!(
+                                    &args[0].borrow().operation,
+                                    ExprNodeOperation::LocalVariable(v) if *v == variable
+                                )
+                        }
+                        None => false,
+                    }
+                })
+            } else {
+                false
+            }
+        });
+
+    // the vector is moved, so it must not be used anywhere else in the function
+    let expected_references = if destroy_idx.is_some() { 3 } else { 2 };
+    if !vector_parameters.contains(&(variable, expected_references)) {
+        return Ok(None);
+    }
+
+    let value = ExprNodeOperation::Func(
+        sibling_function(&push_back_name, "reverse_append"),
+        vec![dst, ExprNodeOperation::LocalVariable(variable).to_node()],
+        types,
+    );
+
+    Ok(Some((
+        DecompiledCodeItem::Statement {
+            expr: DecompiledExpr::EvaluationExpr(value.to_expr()).boxed(),
+        },
+        destroy_idx,
+    )))
+}
+
+fn count_expr_references(expr: &DecompiledExpr, variable: usize) -> usize {
+    let mut count = 0;
+    match expr {
+        DecompiledExpr::Variable(v) if *v == variable => count += 1,
+        _ => {
+            expr.any_sub_operation(&mut |op| {
+                match op {
+                    ExprNodeOperation::LocalVariable(v)
+                    | ExprNodeOperation::VariableSnapshot { variable: v, .. }
+                        if *v == variable =>
+                    {
+                        count += 1
+                    }
+                    _ => {}
+                }
+                false
+            });
+        }
+    }
+    count
+}
+
+fn count_variable_references(unit: &DecompiledCodeUnit, variable: usize) -> usize {
+    let mut count = 0;
+    for item in unit.blocks.iter() {
+        count += match item {
+            DecompiledCodeItem::ReturnStatement(expr)
+            | DecompiledCodeItem::AbortStatement(expr)
+            | DecompiledCodeItem::Statement { expr } => count_expr_references(expr, variable),
+            DecompiledCodeItem::PossibleAssignStatement {
+                variable: v, value, ..
+            }
+            | DecompiledCodeItem::AssignStatement {
+                variable: v, value, ..
+            } => (*v == variable) as usize + count_expr_references(value, variable),
+            DecompiledCodeItem::AssignTupleStatement {
+                variables, value, ..
+            } => {
+                variables.iter().filter(|v| **v == variable).count()
+                    + count_expr_references(value, variable)
+            }
+            DecompiledCodeItem::AssignStructureStatement {
+                variables, value, ..
+            } => {
+                variables.iter().filter(|(_, v)| *v == variable).count()
+                    + count_expr_references(value, variable)
+            }
+            DecompiledCodeItem::PreDeclareStatement { variable: v } => (*v == variable) as usize,
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                result_variables,
+                ..
+            } => {
+                result_variables.iter().filter(|v| **v == variable).count()
+                    + count_expr_references(cond, variable)
+                    + count_variable_references(if_unit, variable)
+                    + count_variable_references(else_unit, variable)
+            }
+            DecompiledCodeItem::WhileStatement { cond, body } => {
+                cond.as_ref()
+                    .map_or(0, |cond| count_expr_references(cond, variable))
+                    + count_variable_references(body, variable)
+            }
+            DecompiledCodeItem::ForStatement {
+                variable: v,
+                start,
+                end,
+                body,
+            } => {
+                (*v == variable) as usize
+                    + count_expr_references(start, variable)
+                    + count_expr_references(end, variable)
+                    + count_variable_references(body, variable)
+            }
+            DecompiledCodeItem::BreakStatement
+            | DecompiledCodeItem::ContinueStatement
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => 0,
+        };
+    }
+
+    count
+        + unit
+            .exit
+            .as_ref()
+            .map_or(0, |exit| count_expr_references(exit, variable))
+        + unit
+            .result_variables
+            .iter()
+            .filter(|v| **v == variable)
+            .count()
+}
@@ -6,8 +6,9 @@ use move_stackless_bytecode::function_target::FunctionTarget;
 
 use super::super::{
     super::ResultUsageType,
-    utils::{expr_and, expr_or},
+    utils::{expr_and, expr_bool_const, expr_not, expr_or},
 };
+use crate::decompiler::evaluator::stackless::ExprNodeRef;
 use crate::decompiler::reconstruct::{
     ast::optimizers::utils::{
         blocks_iter_with_last_effective_indicator, has_effective_statement,
@@ -16,7 +17,27 @@ use crate::decompiler::reconstruct::{
     DecompiledCodeItem, DecompiledCodeUnit, DecompiledCodeUnitRef, DecompiledExpr,
 };
 
-/// if (cond) { expr1 } else { expr2 } -> cond && expr1 || expr2
+/// Rewrite `if (cond) { expr1 } else { expr2 }` into a boolean operator, this is only
+/// possible when one of the branches is a constant:
+///  - if (cond) { expr1 } else { false } -> cond && expr1
+///  - if (cond) { true } else { expr2 }  -> cond || expr2
+///  - if (cond) { false } else { expr2 } -> !cond && expr2
+///  - if (cond) { expr1 } else { true }  -> !cond || expr1
+fn short_circuit_expr(
+    cond: ExprNodeRef,
+    if_expr: ExprNodeRef,
+    else_expr: ExprNodeRef,
+) -> Option<ExprNodeRef> {
+    match (expr_bool_const(&if_expr), expr_bool_const(&else_expr)) {
+        (_, Some(false)) => Some(expr_and(cond, if_expr)),
+        (Some(true), _) => Some(expr_or(cond, else_expr)),
+        (Some(false), _) => Some(expr_and(expr_not(cond), else_expr)),
+        (_, Some(true)) => Some(expr_or(expr_not(cond), if_expr)),
+        _ => None,
+    }
+}
+
+/// if (cond) { expr1 } else { expr2 } -> cond && expr1, cond || expr2, ...
 pub(crate) fn rewrite_short_circuit_if_else(
     unit: &DecompiledCodeUnitRef,
     func_target: &FunctionTarget<'_>,
@@ -71,27 +92,27 @@ pub(crate) fn rewrite_short_circuit_if_else(
                 let if_unit = rewrite_short_circuit_if_else(if_unit, func_target, false)?;
                 let else_unit = rewrite_short_circuit_if_else(else_unit, func_target, false)?;
 
-                if result_variables.len() == 1
+                let short_circuit = if result_variables.len() == 1
                     && func_target.get_local_type(result_variables[0]).is_bool()
                     && !has_effective_statement(&if_unit.blocks)
                     && !has_effective_statement(&else_unit.blocks)
                     && if_unit.exit.is_some()
-                    && else_unit.exit.is_some() {
+                    && else_unit.exit.is_some()
+                {
+                    short_circuit_expr(
+                        cond.to_expr()?,
+                        if_unit.exit.as_ref().unwrap().to_expr()?,
+                        else_unit.exit.as_ref().unwrap().to_expr()?,
+                    )
+                } else {
+                    None
+                };
+
+                if let Some(value) = short_circuit {
                     new_unit.add(DecompiledCodeItem::AssignStatement {
                         variable: result_variables[0],
-                        value: DecompiledExpr::EvaluationExpr(
-                            expr_or(
-                                expr_and(
-                                    cond.to_expr()?,
-                                    if_unit.exit.as_ref().unwrap().to_expr()?,
-                                ),
-                                else_unit.exit.as_ref().unwrap().to_expr()?,
-                            )
-                            .borrow()
-                            .operation
-                            .to_expr(),
-                        )
-                        .boxed(),
+                        value: DecompiledExpr::EvaluationExpr(value.borrow().operation.to_expr())
+                            .boxed(),
 
                         is_decl: use_as_result == &ResultUsageType::None,
                     });
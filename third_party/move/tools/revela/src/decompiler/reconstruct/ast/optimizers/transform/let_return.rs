@@ -31,7 +31,8 @@ pub(crate) fn rewrite_let_var_return(unit: &mut DecompiledCodeUnit) -> Result<()
                 rewrite_let_var_return(else_unit)?;
             }
 
-            DecompiledCodeItem::WhileStatement { body, .. } => {
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
                 rewrite_let_var_return(body)?;
             }
 
@@ -40,6 +41,7 @@ pub(crate) fn rewrite_let_var_return(unit: &mut DecompiledCodeUnit) -> Result<()
             | DecompiledCodeItem::BreakStatement
             | DecompiledCodeItem::ContinueStatement
             | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_)
             | DecompiledCodeItem::PossibleAssignStatement { .. }
             | DecompiledCodeItem::PreDeclareStatement { .. }
             | DecompiledCodeItem::AssignStatement { .. }
@@ -192,7 +194,8 @@ pub(crate) fn rewrite_let_if_return(unit: &mut DecompiledCodeUnit) -> Result<(),
                 rewrite_let_if_return(else_unit)?;
             }
 
-            DecompiledCodeItem::WhileStatement { body, .. } => {
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
                 rewrite_let_if_return(body)?;
             }
 
@@ -201,6 +204,7 @@ pub(crate) fn rewrite_let_if_return(unit: &mut DecompiledCodeUnit) -> Result<(),
             | DecompiledCodeItem::BreakStatement
             | DecompiledCodeItem::ContinueStatement
             | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_)
             | DecompiledCodeItem::PossibleAssignStatement { .. }
             | DecompiledCodeItem::PreDeclareStatement { .. }
             | DecompiledCodeItem::AssignStatement { .. }
@@ -2,13 +2,19 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
+use move_stackless_bytecode::{function_target::FunctionTarget, stackless_bytecode::Constant};
+
+use crate::decompiler::evaluator::stackless::{ExprNodeOperation, ExprNodeRef};
 use crate::decompiler::reconstruct::ast::ResultUsageType;
 
-use crate::decompiler::reconstruct::{DecompiledCodeItem, DecompiledCodeUnit};
+use crate::decompiler::reconstruct::{
+    DecompiledCodeItem, DecompiledCodeUnit, DecompiledCodeUnitRef, DecompiledExpr,
+    DecompiledExprRef,
+};
 
-use super::super::utils::blocks_iter_with_last_effective_indicator;
+use super::super::utils::{blocks_iter_with_last_effective_indicator, expr_not};
 
 /// Rewrite loop to while loop
 /// ```ignore
@@ -33,11 +39,24 @@ use super::super::utils::blocks_iter_with_last_effective_indicator;
 ///     }                   |
 ///   }                     |
 /// ```
+///
+/// And loops starting with a conditional break
+/// ```ignore
+///   loop {                | while(!expr) {
+///     if (expr) {         |   [body]
+///         break;          | }
+///     };                  |
+///     [body]              |
+///   }                     |
+/// ```
 pub(crate) fn rewrite_loop(unit: &mut DecompiledCodeUnit) -> Result<(), anyhow::Error> {
     for item in unit.blocks.iter_mut() {
         match item {
             DecompiledCodeItem::WhileStatement { cond, body } => {
                 rewrite_loop(body)?;
+                if cond.is_none() {
+                    rewrite_leading_break(cond, body)?;
+                }
                 if cond.is_none() {
                     let effective_body_blocks: Vec<_> =
                         blocks_iter_with_last_effective_indicator(&body.blocks)
@@ -137,3 +156,372 @@ pub(crate) fn rewrite_loop(unit: &mut DecompiledCodeUnit) -> Result<(), anyhow::
 
     Ok(())
 }
+
+fn is_comment(item: &DecompiledCodeItem) -> bool {
+    matches!(
+        item,
+        DecompiledCodeItem::CommentStatement(..) | DecompiledCodeItem::BytecodeCommentStatement(..)
+    )
+}
+
+fn is_empty_unit(unit: &DecompiledCodeUnit) -> bool {
+    unit.exit.is_none() && unit.blocks.iter().all(is_comment)
+}
+
+fn is_single_break_unit(unit: &DecompiledCodeUnit) -> bool {
+    let mut items = unit.blocks.iter().filter(|x| !is_comment(x));
+    unit.exit.is_none()
+        && matches!(items.next(), Some(DecompiledCodeItem::BreakStatement))
+        && items.next().is_none()
+}
+
+fn rewrite_leading_break(
+    cond: &mut Option<DecompiledExprRef>,
+    body: &mut DecompiledCodeUnitRef,
+) -> Result<(), anyhow::Error> {
+    let idx = match body.blocks.iter().position(|x| !is_comment(x)) {
+        Some(idx) => idx,
+        None => return Ok(()),
+    };
+
+    let new_cond = if let DecompiledCodeItem::IfElseStatement {
+        cond: if_cond,
+        if_unit,
+        else_unit,
+        result_variables,
+        use_as_result,
+    } = &body.blocks[idx]
+    {
+        if !result_variables.is_empty() || use_as_result != &ResultUsageType::None {
+            return Ok(());
+        }
+
+        if is_single_break_unit(if_unit) && is_empty_unit(else_unit) {
+            let negated = expr_not(if_cond.to_expr()?);
+            DecompiledExpr::EvaluationExpr(negated.borrow().operation.to_expr()).boxed()
+        } else if is_empty_unit(if_unit) && is_single_break_unit(else_unit) {
+            if_cond.clone()
+        } else {
+            return Ok(());
+        }
+    } else {
+        return Ok(());
+    };
+
+    body.blocks.remove(idx);
+    *cond = Some(new_cond);
+
+    Ok(())
+}
+
+/// Rewrite counted while loop to Move 2 for loop
+/// ```ignore
+///   let i = start;        | for (i in start..end) {
+///   while (i < end) {     |   [body]
+///     [body]              | }
+///     i = i + 1;          |
+///   }                     |
+/// ```
+/// (only when `end` is loop invariant, `i` is not modified nor used after the loop
+/// and the body has no `continue`).
+pub(crate) fn rewrite_for_loop(
+    unit: &mut DecompiledCodeUnit,
+    func_target: &FunctionTarget<'_>,
+) -> Result<(), anyhow::Error> {
+    for item in unit.blocks.iter_mut() {
+        match item {
+            DecompiledCodeItem::WhileStatement { body, .. }
+            | DecompiledCodeItem::ForStatement { body, .. } => {
+                rewrite_for_loop(body, func_target)?;
+            }
+            DecompiledCodeItem::IfElseStatement {
+                if_unit, else_unit, ..
+            } => {
+                rewrite_for_loop(if_unit, func_target)?;
+                rewrite_for_loop(else_unit, func_target)?;
+            }
+            _ => {}
+        }
+    }
+
+    let mut idx = 0;
+    while idx < unit.blocks.len() {
+        if let Some(init_idx) = for_loop_candidate(unit, idx, func_target)? {
+            let (variable, start) = match unit.blocks.remove(init_idx) {
+                DecompiledCodeItem::AssignStatement {
+                    variable, value, ..
+                } => (variable, value),
+                _ => unreachable!(),
+            };
+            idx -= 1;
+
+            if let DecompiledCodeItem::WhileStatement {
+                cond: Some(cond),
+                body,
+            } = &unit.blocks[idx]
+            {
+                let end = match &cond.to_expr()?.borrow().operation {
+                    ExprNodeOperation::Binary(_, _, end) => end.clone(),
+                    _ => unreachable!(),
+                };
+                let mut body = body.clone();
+                let increment_idx = body.blocks.iter().rposition(|x| !is_comment(x)).unwrap();
+                body.blocks.remove(increment_idx);
+
+                unit.blocks[idx] = DecompiledCodeItem::ForStatement {
+                    variable,
+                    start,
+                    end: DecompiledExpr::EvaluationExpr(end.borrow().operation.to_expr()).boxed(),
+                    body,
+                };
+            }
+        }
+        idx += 1;
+    }
+
+    Ok(())
+}
+
+/// Check whether the loop at `idx` can be rewritten as a for loop, returns the index of
+/// the induction variable initialization
+fn for_loop_candidate(
+    unit: &DecompiledCodeUnit,
+    idx: usize,
+    func_target: &FunctionTarget<'_>,
+) -> Result<Option<usize>, anyhow::Error> {
+    let (cond, body) = match &unit.blocks[idx] {
+        DecompiledCodeItem::WhileStatement {
+            cond: Some(cond),
+            body,
+        } => (cond, body),
+        _ => return Ok(None),
+    };
+
+    let init_idx = match unit.blocks[..idx].iter().rposition(|x| !is_comment(x)) {
+        Some(init_idx) => init_idx,
+        None => return Ok(None),
+    };
+    let variable = match &unit.blocks[init_idx] {
+        DecompiledCodeItem::AssignStatement {
+            variable,
+            is_decl: true,
+            ..
+        } => *variable,
+        _ => return Ok(None),
+    };
+
+    let cond = cond.to_expr()?;
+    let end = match &cond.borrow().operation {
+        ExprNodeOperation::Binary(op, i, end) if op == "<" && is_variable(i, variable) => {
+            end.clone()
+        }
+        _ => return Ok(None),
+    };
+
+    let increment = match body.blocks.iter().rev().find(|x| !is_comment(x)) {
+        Some(DecompiledCodeItem::AssignStatement {
+            variable: v,
+            value,
+            is_decl: false,
+        }) if *v == variable => value.to_expr()?,
+        _ => return Ok(None),
+    };
+    let is_increment = match &increment.borrow().operation {
+        ExprNodeOperation::Binary(op, i, one) => {
+            op == "+" && is_variable(i, variable) && is_const_one(one)
+        }
+        _ => false,
+    };
+    if !is_increment {
+        return Ok(None);
+    }
+
+    let mut modified = HashSet::new();
+    let increment_idx = body.blocks.iter().rposition(|x| !is_comment(x)).unwrap();
+    for item in body.blocks[..increment_idx].iter() {
+        if !collect_modified_variables(item, &mut modified) {
+            return Ok(None);
+        }
+    }
+    if modified.contains(&variable) {
+        return Ok(None);
+    }
+
+    match loop_invariant_bound_variable(&end, func_target) {
+        Some(Some(bound)) if bound == variable || modified.contains(&bound) => return Ok(None),
+        Some(_) => {}
+        None => return Ok(None),
+    }
+
+    let variables = HashSet::from([variable]);
+    let rest = DecompiledCodeUnit {
+        blocks: unit.blocks[idx + 1..].to_vec(),
+        exit: None,
+        result_variables: Vec::new(),
+        trailing_comments: BTreeMap::new(),
+    };
+    if rest.has_reference_to_any_variable(&variables)
+        || unit
+            .exit
+            .as_ref()
+            .map_or(false, |x| x.has_reference_to_any_variable(&variables))
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(init_idx))
+}
+
+fn is_variable(expr: &ExprNodeRef, variable: usize) -> bool {
+    matches!(&expr.borrow().operation, ExprNodeOperation::LocalVariable(v) if *v == variable)
+}
+
+fn is_const_one(expr: &ExprNodeRef) -> bool {
+    matches!(
+        &expr.borrow().operation,
+        ExprNodeOperation::Const(
+            Constant::U8(1)
+                | Constant::U16(1)
+                | Constant::U32(1)
+                | Constant::U64(1)
+                | Constant::U128(1),
+            _
+        )
+    )
+}
+
+/// Returns `Some(variable)` if the bound depends on a local variable, `Some(None)` if it is
+/// a constant, or `None` if the bound may change between iterations
+fn loop_invariant_bound_variable(
+    end: &ExprNodeRef,
+    func_target: &FunctionTarget<'_>,
+) -> Option<Option<usize>> {
+    match &end.borrow().operation {
+        ExprNodeOperation::Const(..) => Some(None),
+        ExprNodeOperation::LocalVariable(v) => Some(Some(*v)),
+        ExprNodeOperation::Func(name, args, _)
+            if (name == "vector::length" || name.ends_with("::vector::length"))
+                && args.len() == 1 =>
+        {
+            match &args[0].borrow().operation {
+                ExprNodeOperation::BorrowLocal(v, false) => match &v.borrow().operation {
+                    ExprNodeOperation::LocalVariable(v) => Some(Some(*v)),
+                    _ => None,
+                },
+                // only parameters keep their original index, and an immutable reference
+                // guarantees the vector is not modified in the body
+                ExprNodeOperation::LocalVariable(v)
+                    if *v < func_target.get_parameter_count()
+                        && func_target.get_local_type(*v).is_immutable_reference() =>
+                {
+                    Some(Some(*v))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Collect variables that may be modified by `item`, returns false if `item` contains a
+/// `continue` of the current loop
+fn collect_modified_variables(item: &DecompiledCodeItem, modified: &mut HashSet<usize>) -> bool {
+    fn collect_borrowed(expr: &DecompiledExpr, modified: &mut HashSet<usize>) {
+        expr.any_sub_operation(&mut |op| {
+            if let ExprNodeOperation::BorrowLocal(v, true) = op {
+                if let ExprNodeOperation::LocalVariable(v) = &v.borrow().operation {
+                    modified.insert(*v);
+                }
+            }
+            false
+        });
+    }
+    fn collect_unit(
+        unit: &DecompiledCodeUnit,
+        modified: &mut HashSet<usize>,
+        in_nested_loop: bool,
+    ) -> bool {
+        for item in unit.blocks.iter() {
+            if !collect_item(item, modified, in_nested_loop) {
+                return false;
+            }
+        }
+        if let Some(exit) = &unit.exit {
+            collect_borrowed(exit, modified);
+        }
+        true
+    }
+    fn collect_item(
+        item: &DecompiledCodeItem,
+        modified: &mut HashSet<usize>,
+        in_nested_loop: bool,
+    ) -> bool {
+        match item {
+            DecompiledCodeItem::ContinueStatement => return in_nested_loop,
+            DecompiledCodeItem::BreakStatement
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
+            DecompiledCodeItem::ReturnStatement(expr)
+            | DecompiledCodeItem::AbortStatement(expr)
+            | DecompiledCodeItem::Statement { expr } => collect_borrowed(expr, modified),
+            DecompiledCodeItem::PreDeclareStatement { variable } => {
+                modified.insert(*variable);
+            }
+            DecompiledCodeItem::PossibleAssignStatement {
+                variable, value, ..
+            } => {
+                modified.insert(*variable);
+                collect_borrowed(value, modified);
+            }
+            DecompiledCodeItem::AssignStatement {
+                variable, value, ..
+            } => {
+                modified.insert(*variable);
+                collect_borrowed(value, modified);
+            }
+            DecompiledCodeItem::AssignTupleStatement {
+                variables, value, ..
+            } => {
+                modified.extend(variables.iter());
+                collect_borrowed(value, modified);
+            }
+            DecompiledCodeItem::AssignStructureStatement {
+                variables, value, ..
+            } => {
+                modified.extend(variables.iter().map(|(_, v)| *v));
+                collect_borrowed(value, modified);
+            }
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                result_variables,
+                ..
+            } => {
+                modified.extend(result_variables.iter());
+                collect_borrowed(cond, modified);
+                return collect_unit(if_unit, modified, in_nested_loop)
+                    && collect_unit(else_unit, modified, in_nested_loop);
+            }
+            DecompiledCodeItem::WhileStatement { cond, body } => {
+                if let Some(cond) = cond {
+                    collect_borrowed(cond, modified);
+                }
+                return collect_unit(body, modified, true);
+            }
+            DecompiledCodeItem::ForStatement {
+                variable,
+                start,
+                end,
+                body,
+            } => {
+                modified.insert(*variable);
+                collect_borrowed(start, modified);
+                collect_borrowed(end, modified);
+                return collect_unit(body, modified, true);
+            }
+        }
+        true
+    }
+    collect_item(item, modified, false)
+}
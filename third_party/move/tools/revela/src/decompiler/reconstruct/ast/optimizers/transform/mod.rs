@@ -2,6 +2,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod abort_codes;
 pub mod cleanup_tail_exit;
 pub mod non_source_blocks;
 pub mod variables;
@@ -9,3 +10,8 @@ pub mod assert;
 pub mod let_return;
 pub mod loops;
 pub mod if_else;
+pub mod idioms;
+pub mod receiver_calls;
+pub mod dead_stores;
+pub mod type_args;
+pub mod references;
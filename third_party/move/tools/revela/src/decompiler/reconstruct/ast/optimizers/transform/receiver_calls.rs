@@ -0,0 +1,84 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_model::{
+    model::{FunctionEnv, GlobalEnv, Parameter},
+    ty::Type,
+};
+use move_stackless_bytecode::function_target::FunctionTarget;
+
+use crate::decompiler::{
+    evaluator::stackless::ExprNodeOperation, reconstruct::DecompiledCodeUnit,
+};
+
+use super::super::utils::rewrite_expr_operations;
+
+/// Render calls to functions of other modules in receiver style, the output then requires Move 2
+///  - 0x1::vector::length<u64>(&v) -> v.length()
+///  - 0x1::option::extract<T0>(&mut o) -> o.extract()
+///
+/// A call is eligible when the first parameter of the callee is named `self` and is, or refers
+/// to, a type declared in the callee module (a vector for `0x1::vector`), and every type
+/// argument can be inferred from the parameters. Functions of the decompiled module are left
+/// untouched since their first parameter is not named `self` in the output.
+pub(crate) fn rewrite_receiver_calls(
+    unit: &mut DecompiledCodeUnit,
+    func_target: &FunctionTarget<'_>,
+) -> Result<(), anyhow::Error> {
+    let env = func_target.global_env();
+    rewrite_expr_operations(unit, &mut |op| {
+        let method = match &*op {
+            ExprNodeOperation::Func(name, args, types) if !args.is_empty() => {
+                receiver_function(env, name).map(|method| (method, args.clone(), types.clone()))
+            }
+            _ => None,
+        };
+        if let Some((method, args, types)) = method {
+            *op = ExprNodeOperation::MethodCall(method, args, types);
+        }
+    });
+    Ok(())
+}
+
+/// The method name of `name` if it can be called in receiver style
+fn receiver_function(env: &GlobalEnv, name: &str) -> Option<String> {
+    let (module_name, function_name) = name.rsplit_once("::")?;
+    let module = env
+        .get_modules()
+        .find(|m| m.get_full_name_str() == module_name)?;
+    let function = module
+        .get_functions()
+        .find(|f| f.get_name_str() == function_name)?;
+    is_receiver_function(&function).then(|| function_name.to_string())
+}
+
+fn is_receiver_function(function: &FunctionEnv<'_>) -> bool {
+    let params = function.get_parameters_ref();
+    let Some(Parameter(receiver_name, receiver, _)) = params.first() else {
+        return false;
+    };
+    if receiver_name.display(function.symbol_pool()).to_string() != "self" {
+        return false;
+    }
+    let declared_in_module = match receiver.skip_reference() {
+        Type::Struct(mid, _, _) => *mid == function.module_env.get_id(),
+        Type::Vector(_) => function.module_env.get_full_name_str() == "0x1::vector",
+        _ => false,
+    };
+    if !declared_in_module {
+        return false;
+    }
+
+    let mut inferable = vec![false; function.get_type_parameter_count()];
+    for Parameter(_, param, _) in params.iter() {
+        param.visit(&mut |ty| {
+            if let Type::TypeParameter(idx) = ty {
+                if let Some(x) = inferable.get_mut(*idx as usize) {
+                    *x = true;
+                }
+            }
+        });
+    }
+    inferable.into_iter().all(|x| x)
+}
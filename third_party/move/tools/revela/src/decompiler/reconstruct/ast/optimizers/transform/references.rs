@@ -0,0 +1,48 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::decompiler::{evaluator::stackless::ExprNodeOperation, reconstruct::DecompiledCodeUnit};
+
+use super::super::utils::rewrite_expr_operations;
+
+/// Collapse borrow/dereference pairs left by the bytecode
+///  - &mut *r -> r (r is a mutable reference)
+///  - &*r -> freeze(r), rendered as r
+///  - freeze(&mut x) -> &x
+///  - freeze(freeze(r)) -> freeze(r)
+///
+/// so that e.g. `*&mut *&x.f` becomes `*&x.f`, which is rendered as `x.f`. Mutable borrows are
+/// only replaced by references that are mutable themselves.
+pub(crate) fn simplify_reference_chains(unit: &mut DecompiledCodeUnit) {
+    rewrite_expr_operations(unit, &mut |op| {
+        // the operands are already simplified, as the rewrite is post-order
+        while let Some(simplified) = simplify(op) {
+            *op = simplified;
+        }
+    });
+}
+
+fn simplify(op: &ExprNodeOperation) -> Option<ExprNodeOperation> {
+    match op {
+        ExprNodeOperation::BorrowLocal(inner, mutable) => match &inner.borrow().operation {
+            ExprNodeOperation::ReadRef(reference) if *mutable => {
+                Some(reference.borrow().operation.clone())
+            }
+            ExprNodeOperation::ReadRef(reference) => {
+                Some(ExprNodeOperation::FreezeRef(reference.clone()))
+            }
+            _ => None,
+        },
+        ExprNodeOperation::FreezeRef(inner) => match &inner.borrow().operation {
+            ExprNodeOperation::BorrowLocal(value, true) => {
+                Some(ExprNodeOperation::BorrowLocal(value.clone(), false))
+            }
+            ExprNodeOperation::FreezeRef(reference) => {
+                Some(ExprNodeOperation::FreezeRef(reference.clone()))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
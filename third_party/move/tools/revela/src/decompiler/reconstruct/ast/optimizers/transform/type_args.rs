@@ -0,0 +1,79 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_model::{model::FunctionEnv, ty::Type};
+use move_stackless_bytecode::{function_target::FunctionTarget, stackless_bytecode::Constant};
+
+use crate::decompiler::{
+    evaluator::stackless::{ExprNodeOperation, ExprNodeRef},
+    reconstruct::DecompiledCodeUnit,
+};
+
+use super::super::utils::{resolve_function, rewrite_expr_operations};
+
+/// Drop the type arguments of calls when the compiler infers them from the value arguments
+///  - 0x1::vector::length<u64>(&v) -> 0x1::vector::length(&v)
+///  - 0x1::coin::balance<T0>(arg0) (T0 only appears in the result) -> unchanged
+///  - 0x1::option::some<u8>(1) (the literal would default to u64) -> unchanged
+///
+/// Type arguments are kept as soon as one of them cannot be inferred, since Move accepts either
+/// all of them or none.
+pub(crate) fn elide_inferable_type_args(
+    unit: &mut DecompiledCodeUnit,
+    func_target: &FunctionTarget<'_>,
+) -> Result<(), anyhow::Error> {
+    rewrite_expr_operations(unit, &mut |op| {
+        if let ExprNodeOperation::Func(name, args, types) = op {
+            if types.is_empty() {
+                return;
+            }
+            let Some(callee) = resolve_function(func_target, name) else {
+                return;
+            };
+            if all_type_args_inferable(&callee, args) {
+                types.clear();
+            }
+        }
+    });
+    Ok(())
+}
+
+fn all_type_args_inferable(callee: &FunctionEnv<'_>, args: &[ExprNodeRef]) -> bool {
+    let params = callee.get_parameter_types();
+    if params.len() != args.len() {
+        return false;
+    }
+
+    let mut inferable = vec![false; callee.get_type_parameter_count()];
+    for (param, arg) in params.iter().zip(args.iter()) {
+        if has_literal_type(arg) {
+            continue;
+        }
+        param.visit(&mut |ty| {
+            if let Type::TypeParameter(idx) = ty {
+                if let Some(x) = inferable.get_mut(*idx as usize) {
+                    *x = true;
+                }
+            }
+        });
+    }
+    inferable.into_iter().all(|x| x)
+}
+
+/// Literals whose type is only fixed by their context: integers and vectors of them
+fn has_literal_type(arg: &ExprNodeRef) -> bool {
+    match &arg.borrow().operation {
+        ExprNodeOperation::Const(c, _) => !matches!(
+            c,
+            Constant::Bool(_)
+                | Constant::Address(_)
+                | Constant::ByteArray(_)
+                | Constant::AddressArray(_)
+        ),
+        ExprNodeOperation::BorrowLocal(inner, _) | ExprNodeOperation::FreezeRef(inner) => {
+            has_literal_type(inner)
+        }
+        _ => false,
+    }
+}
@@ -55,7 +55,8 @@ pub(crate) fn rename_variables(
 
             DecompiledCodeItem::BreakStatement
             | DecompiledCodeItem::ContinueStatement
-            | DecompiledCodeItem::CommentStatement(_) => {}
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
             DecompiledCodeItem::AssignStructureStatement {
                 variables, value, ..
             } => {
@@ -88,6 +89,18 @@ pub(crate) fn rename_variables(
                 cond.as_mut().map(|x| x.rename_variables(renamed_variables));
                 rename_variables(body, renamed_variables);
             }
+
+            DecompiledCodeItem::ForStatement {
+                variable,
+                start,
+                end,
+                body,
+            } => {
+                *variable = renamed_variables[variable];
+                start.rename_variables(renamed_variables);
+                end.rename_variables(renamed_variables);
+                rename_variables(body, renamed_variables);
+            }
         }
     }
 }
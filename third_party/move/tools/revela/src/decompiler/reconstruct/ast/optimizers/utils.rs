@@ -4,7 +4,8 @@
 
 use std::collections::HashSet;
 
-use move_stackless_bytecode::stackless_bytecode::Constant;
+use move_model::model::FunctionEnv;
+use move_stackless_bytecode::{function_target::FunctionTarget, stackless_bytecode::Constant};
 
 use crate::decompiler::evaluator::stackless::{
     effective_operation, ExprNodeOperation, ExprNodeRef,
@@ -93,6 +94,21 @@ pub(crate) fn collect_referenced_variables(
                     x.collect_variables(referenced_variables, implicit_referenced_variables, false)
                 });
             }
+            DecompiledCodeItem::ForStatement {
+                variable,
+                start,
+                end,
+                body,
+            } => {
+                referenced_variables.insert(*variable);
+                start.collect_variables(referenced_variables, implicit_referenced_variables, false);
+                end.collect_variables(referenced_variables, implicit_referenced_variables, false);
+                collect_referenced_variables(
+                    body,
+                    referenced_variables,
+                    implicit_referenced_variables,
+                );
+            }
             DecompiledCodeItem::Statement { expr: e }
             | DecompiledCodeItem::ReturnStatement(e)
             | DecompiledCodeItem::AbortStatement(e) => {
@@ -100,7 +116,8 @@ pub(crate) fn collect_referenced_variables(
             }
             DecompiledCodeItem::BreakStatement
             | DecompiledCodeItem::ContinueStatement
-            | DecompiledCodeItem::CommentStatement(_) => {}
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
         }
     }
 }
@@ -164,12 +181,24 @@ pub(crate) fn collect_live_variables(
                 }
                 collect_live_variables(body, live_variables, implicit_variables);
             }
+            DecompiledCodeItem::ForStatement {
+                variable,
+                start,
+                end,
+                body,
+            } => {
+                live_variables.insert(*variable);
+                start.collect_variables(live_variables, implicit_variables, false);
+                end.collect_variables(live_variables, implicit_variables, false);
+                collect_live_variables(body, live_variables, implicit_variables);
+            }
             DecompiledCodeItem::ReturnStatement(e) | DecompiledCodeItem::AbortStatement(e) => {
                 e.collect_variables(live_variables, implicit_variables, false);
             }
             DecompiledCodeItem::BreakStatement
             | DecompiledCodeItem::ContinueStatement
-            | DecompiledCodeItem::CommentStatement(_) => {}
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
             DecompiledCodeItem::Statement { expr } => {
                 expr.collect_variables(live_variables, implicit_variables, false);
             }
@@ -217,19 +246,77 @@ pub(crate) fn get_variable_declaration_order(
             DecompiledCodeItem::WhileStatement { body, .. } => {
                 get_variable_declaration_order(body, result_variables);
             }
+            DecompiledCodeItem::ForStatement { variable, body, .. } => {
+                result_variables.push(*variable);
+                get_variable_declaration_order(body, result_variables);
+            }
             DecompiledCodeItem::ReturnStatement(..) | DecompiledCodeItem::AbortStatement(..) => {}
             DecompiledCodeItem::BreakStatement
             | DecompiledCodeItem::ContinueStatement
-            | DecompiledCodeItem::CommentStatement(_) => {}
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_) => {}
             DecompiledCodeItem::Statement { .. } => {}
         }
     }
 }
 
+/// Apply `f` to every expression operation of `unit`, including nested units
+pub(crate) fn rewrite_expr_operations(
+    unit: &mut DecompiledCodeUnit,
+    f: &mut dyn FnMut(&mut ExprNodeOperation),
+) {
+    for item in unit.blocks.iter_mut() {
+        match item {
+            DecompiledCodeItem::ReturnStatement(expr)
+            | DecompiledCodeItem::AbortStatement(expr)
+            | DecompiledCodeItem::Statement { expr }
+            | DecompiledCodeItem::PossibleAssignStatement { value: expr, .. }
+            | DecompiledCodeItem::AssignStatement { value: expr, .. }
+            | DecompiledCodeItem::AssignTupleStatement { value: expr, .. }
+            | DecompiledCodeItem::AssignStructureStatement { value: expr, .. } => {
+                expr.rewrite_operations(f);
+            }
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                ..
+            } => {
+                cond.rewrite_operations(f);
+                rewrite_expr_operations(if_unit, f);
+                rewrite_expr_operations(else_unit, f);
+            }
+            DecompiledCodeItem::WhileStatement { cond, body } => {
+                if let Some(cond) = cond {
+                    cond.rewrite_operations(f);
+                }
+                rewrite_expr_operations(body, f);
+            }
+            DecompiledCodeItem::ForStatement {
+                start, end, body, ..
+            } => {
+                start.rewrite_operations(f);
+                end.rewrite_operations(f);
+                rewrite_expr_operations(body, f);
+            }
+            DecompiledCodeItem::BreakStatement
+            | DecompiledCodeItem::ContinueStatement
+            | DecompiledCodeItem::CommentStatement(_)
+            | DecompiledCodeItem::BytecodeCommentStatement(_)
+            | DecompiledCodeItem::PreDeclareStatement { .. } => {}
+        }
+    }
+
+    if let Some(exit) = unit.exit.as_mut() {
+        exit.rewrite_operations(f);
+    }
+}
+
 pub(crate) fn is_effective_code_item(item: &DecompiledCodeItem) -> bool {
     !matches!(
         item,
         DecompiledCodeItem::CommentStatement(..)
+            | DecompiledCodeItem::BytecodeCommentStatement(..)
             | DecompiledCodeItem::PossibleAssignStatement { .. }
     )
 }
@@ -325,17 +412,17 @@ pub(crate) fn has_effective_statement(blocks: &Vec<DecompiledCodeItem>) -> bool
     blocks.iter().any(|x| is_effective_code_item(x))
 }
 
-#[allow(dead_code)]
 pub(crate) fn expr_not(expr: ExprNodeRef) -> ExprNodeRef {
     if let Some(v) = effective_operation(&[&expr], &mut |[expr]| match &expr.borrow().operation {
-        ExprNodeOperation::Const(Constant::Bool(x)) => {
+        ExprNodeOperation::Const(Constant::Bool(x), _) => {
             let toggled_value = !x;
             Some(
-                ExprNodeOperation::Const(Constant::Bool(toggled_value))
+                ExprNodeOperation::Const(Constant::Bool(toggled_value), None)
                     .to_expr()
                     .value_copied(),
             )
         }
+        ExprNodeOperation::Unary(op, inner) if op == "!" => Some(inner.clone()),
         _ => None,
     }) {
         return v;
@@ -345,14 +432,21 @@ pub(crate) fn expr_not(expr: ExprNodeRef) -> ExprNodeRef {
         .value_copied()
 }
 
+pub(crate) fn expr_bool_const(expr: &ExprNodeRef) -> Option<bool> {
+    effective_operation(&[expr], &mut |[expr]| match &expr.borrow().operation {
+        ExprNodeOperation::Const(Constant::Bool(x), _) => Some(*x),
+        _ => None,
+    })
+}
+
 pub(crate) fn expr_and(expr1: ExprNodeRef, expr2: ExprNodeRef) -> ExprNodeRef {
     if let Some(v) = effective_operation(&[&expr1, &expr2], &mut |&[expr1, expr2]| match (
         &expr1.borrow().operation,
         &expr2.borrow().operation,
     ) {
-        (ExprNodeOperation::Const(Constant::Bool(true)), _) => Some(expr2.clone()),
-        (_, ExprNodeOperation::Const(Constant::Bool(true))) => Some(expr1.clone()),
-        (ExprNodeOperation::Const(Constant::Bool(false)), _) => Some(expr1.clone()),
+        (ExprNodeOperation::Const(Constant::Bool(true), _), _) => Some(expr2.clone()),
+        (_, ExprNodeOperation::Const(Constant::Bool(true), _)) => Some(expr1.clone()),
+        (ExprNodeOperation::Const(Constant::Bool(false), _), _) => Some(expr1.clone()),
         _ => None,
     }) {
         return v;
@@ -367,9 +461,9 @@ pub(crate) fn expr_or(expr1: ExprNodeRef, expr2: ExprNodeRef) -> ExprNodeRef {
         &expr1.borrow().operation,
         &expr2.borrow().operation,
     ) {
-        (ExprNodeOperation::Const(Constant::Bool(true)), _) => Some(expr1.clone()),
-        (ExprNodeOperation::Const(Constant::Bool(false)), _) => Some(expr2.clone()),
-        (_, ExprNodeOperation::Const(Constant::Bool(false))) => Some(expr1.clone()),
+        (ExprNodeOperation::Const(Constant::Bool(true), _), _) => Some(expr1.clone()),
+        (ExprNodeOperation::Const(Constant::Bool(false), _), _) => Some(expr2.clone()),
+        (_, ExprNodeOperation::Const(Constant::Bool(false), _)) => Some(expr1.clone()),
         _ => None,
     }) {
         return v;
@@ -379,3 +473,24 @@ pub(crate) fn expr_or(expr1: ExprNodeRef, expr2: ExprNodeRef) -> ExprNodeRef {
         .to_expr()
         .value_copied()
 }
+
+/// The function called as `name`, either `addr::module::fun` or a member of the current module
+pub(crate) fn resolve_function<'env>(
+    func_target: &FunctionTarget<'env>,
+    name: &str,
+) -> Option<FunctionEnv<'env>> {
+    let module = &func_target.func_env.module_env;
+    match name.rsplit_once("::") {
+        Some(("Self", function_name)) => module
+            .get_functions()
+            .find(|f| f.get_name_str() == function_name),
+        Some((module_name, function_name)) => module
+            .env
+            .get_modules()
+            .find(|m| m.get_full_name_str() == module_name)?
+            .into_functions()
+            .find(|f| f.get_name_str() == function_name),
+        // builtins such as `borrow_global` are not declared by any module
+        None => module.get_functions().find(|f| f.get_name_str() == name),
+    }
+}
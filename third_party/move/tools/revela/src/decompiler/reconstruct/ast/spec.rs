@@ -0,0 +1,85 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::decompiler::evaluator::stackless::{ExprNodeOperation, ExprNodeRef};
+
+use super::{
+    optimizers::utils::{expr_not, has_effective_statement, is_effective_code_item},
+    DecompiledCodeItem, DecompiledCodeUnit, DecompiledExpr,
+};
+
+/// Abort conditions of the guards opening the function, usable as `aborts_if` in a spec
+///  - assert!(cond, code) -> !cond
+///  - if (cond) { abort code } -> cond
+///
+/// Only guards before any other statement are considered, and only when the condition reads
+/// nothing but the parameters, so that it holds in the pre-state of the function.
+pub(crate) fn infer_aborts_if(unit: &DecompiledCodeUnit, param_count: usize) -> Vec<ExprNodeRef> {
+    let mut conditions = Vec::new();
+    for item in unit.blocks.iter().filter(|x| is_effective_code_item(x)) {
+        let condition = match item {
+            DecompiledCodeItem::PreDeclareStatement { .. } => continue,
+            DecompiledCodeItem::Statement { expr } => match assert_condition(expr) {
+                Some(cond) => expr_not(cond),
+                None => break,
+            },
+            DecompiledCodeItem::IfElseStatement {
+                cond,
+                if_unit,
+                else_unit,
+                ..
+            } if !has_effective_statement(&else_unit.blocks) && is_abort_unit(if_unit) => {
+                match cond.as_ref() {
+                    DecompiledExpr::EvaluationExpr(cond) => cond.value_copied(),
+                    _ => break,
+                }
+            }
+            _ => break,
+        };
+        if !reads_only_parameters(&condition, param_count) {
+            break;
+        }
+        conditions.push(condition);
+    }
+    conditions
+}
+
+fn assert_condition(expr: &DecompiledExpr) -> Option<ExprNodeRef> {
+    let DecompiledExpr::EvaluationExpr(expr) = expr else {
+        return None;
+    };
+    match &expr.value().borrow().operation {
+        ExprNodeOperation::Func(name, args, _) if name == "assert!" && args.len() == 2 => {
+            Some(args[0].clone())
+        }
+        _ => None,
+    }
+}
+
+fn is_abort_unit(unit: &DecompiledCodeUnit) -> bool {
+    let mut items = unit.blocks.iter().filter(|x| is_effective_code_item(x));
+    matches!(
+        (items.next(), items.next()),
+        (Some(DecompiledCodeItem::AbortStatement(_)), None)
+    )
+}
+
+fn reads_only_parameters(expr: &ExprNodeRef, param_count: usize) -> bool {
+    !expr
+        .borrow()
+        .operation
+        .any_sub_operation(&mut |op| match op {
+            ExprNodeOperation::LocalVariable(idx) => *idx >= param_count,
+            ExprNodeOperation::Const(..)
+            | ExprNodeOperation::NamedConst(..)
+            | ExprNodeOperation::Field(..)
+            | ExprNodeOperation::Unary(..)
+            | ExprNodeOperation::Cast(..)
+            | ExprNodeOperation::Binary(..)
+            | ExprNodeOperation::FreezeRef(..)
+            | ExprNodeOperation::ReadRef(..)
+            | ExprNodeOperation::BorrowLocal(..) => false,
+            _ => true,
+        })
+}
@@ -2,7 +2,55 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use std::fmt::Display;
+use std::{
+    fmt::{Display, Write},
+    io,
+};
+
+/// Placement of the opening brace of a declaration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BraceStyle {
+    /// `fun f() {`
+    SameLine,
+    /// `fun f()` followed by `{` on its own line
+    NextLine,
+}
+
+/// Layout settings used when printing the decompiled source
+#[derive(Clone, Debug)]
+pub struct FormatOptions {
+    /// number of spaces per indentation level
+    pub indent_width: usize,
+    /// when set, function headers longer than this are wrapped one parameter per line,
+    /// and struct declarations fitting within it are printed on a single line
+    pub max_line_width: Option<usize>,
+    /// brace placement for module, script, struct and function declarations
+    pub brace_style: BraceStyle,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            max_line_width: None,
+            brace_style: BraceStyle::SameLine,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub(crate) fn fits_in_line(&self, indent: usize, line: &str) -> bool {
+        self.max_line_width
+            .map(|width| indent * self.indent_width + line.len() <= width)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn exceeds_line(&self, indent: usize, line: &str) -> bool {
+        self.max_line_width
+            .map(|width| indent * self.indent_width + line.len() > width)
+            .unwrap_or(false)
+    }
+}
 
 pub(crate) enum SourceCodeItem {
     Line(String),
@@ -22,6 +70,15 @@ impl SourceCodeUnit {
         }
     }
 
+    /// A unit made of already printed lines
+    pub fn from_text(text: &str) -> SourceCodeUnit {
+        let mut unit = SourceCodeUnit::new(0);
+        for line in text.lines() {
+            unit.add_line(line.to_string());
+        }
+        unit
+    }
+
     pub fn is_empty(&self) -> bool {
         self.code.is_empty()
     }
@@ -34,36 +91,103 @@ impl SourceCodeUnit {
         self.code.push(SourceCodeItem::Line(line));
     }
 
+    pub fn item_count(&self) -> usize {
+        self.code.len()
+    }
+
+    /// Append `suffix` to the item at `idx` when it is a line
+    pub fn append_to_line(&mut self, idx: usize, suffix: &str) {
+        if let Some(SourceCodeItem::Line(line)) = self.code.get_mut(idx) {
+            line.push_str(suffix);
+        }
+    }
+
     pub fn add_block(&mut self, block: SourceCodeUnit) {
         self.code.push(SourceCodeItem::Block(block));
     }
 
-    pub fn print(&self, base_indent: i32, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    /// Add a declaration header followed by its opening brace
+    pub fn add_block_header(&mut self, header: String, options: &FormatOptions) {
+        match options.brace_style {
+            BraceStyle::SameLine => self.add_line(format!("{} {{", header)),
+            BraceStyle::NextLine => {
+                self.add_line(header);
+                self.add_line("{".to_string());
+            }
+        }
+    }
+
+    pub fn print<W: Write>(
+        &self,
+        base_indent: i32,
+        indent_width: usize,
+        f: &mut W,
+    ) -> std::fmt::Result {
         let indent = base_indent + self.indent;
 
         for item in self.code.iter() {
             match item {
                 SourceCodeItem::Line(line) => {
                     for _ in 0..indent {
-                        f.write_str("    ")?;
+                        write!(f, "{:width$}", "", width = indent_width)?;
                     }
                     f.write_str(line)?;
                     f.write_str("\n")?;
                 }
 
                 SourceCodeItem::Block(block) => {
-                    block.print(indent, f)?;
+                    block.print(indent, indent_width, f)?;
                 }
             }
         }
 
         std::fmt::Result::Ok(())
     }
+
+    pub fn to_string_with_options(&self, options: &FormatOptions) -> String {
+        let mut buf = String::new();
+        self.print(0, options.indent_width, &mut buf).unwrap();
+        buf
+    }
+
+    /// Write the code printed so far to `out` and drop it, keeping the indentation
+    pub fn flush_to<W: io::Write>(
+        &mut self,
+        options: &FormatOptions,
+        out: &mut W,
+    ) -> io::Result<()> {
+        let mut writer = IoWriter {
+            inner: out,
+            error: None,
+        };
+        if self.print(0, options.indent_width, &mut writer).is_err() {
+            return Err(writer
+                .error
+                .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatter error")));
+        }
+        self.code.clear();
+        io::Result::Ok(())
+    }
+}
+
+/// Adapter printing through `fmt::Write` into an `io::Write` sink
+struct IoWriter<'a, W: io::Write> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            std::fmt::Error
+        })
+    }
 }
 
 //impl Display for DecompiledCodeUnit
 impl Display for SourceCodeUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.print(0, f)
+        self.print(0, FormatOptions::default().indent_width, f)
     }
 }
@@ -2,7 +2,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeMap, HashSet},
+    rc::Rc,
+};
 
 use crate::decompiler::evaluator::stackless::StacklessEvaluationRunResult;
 
@@ -25,9 +28,11 @@ use super::{
         StacklessBlockContent,
     },
     evaluator::stackless::{ReturnValueHint, StacklessEvaluationContext},
+    gas_schedule::GasSchedule,
 };
 
 pub use self::ast::optimizers::OptimizerSettings;
+pub use self::code_unit::{BraceStyle, FormatOptions};
 
 mod ast;
 pub mod code_unit;
@@ -42,6 +47,11 @@ pub struct SourceGen<'a> {
     func_target: &'a FunctionTarget<'a>,
     naming: Naming<'a>,
     body: &'a mut WithMetadata<CodeUnitBlock<usize, StacklessBlockContent>>,
+    interleave_bytecode: bool,
+    error_constants: BTreeMap<u64, String>,
+    infer_spec: bool,
+    aborts_if: Vec<String>,
+    gas_schedule: Option<Rc<GasSchedule>>,
 }
 
 #[derive(Clone, Debug)]
@@ -89,9 +99,46 @@ impl<'a> SourceGen<'a> {
             func_target,
             naming: naming.with_arg_count(func_env.get_parameter_count()),
             var_usage: VarPipelineState::new().boxed(),
+            interleave_bytecode: false,
+            error_constants: BTreeMap::new(),
+            infer_spec: false,
+            aborts_if: Vec::new(),
+            gas_schedule: None,
         }
     }
 
+    /// When enabled, each reconstructed statement is followed by a comment block listing
+    /// the stackless bytecode instructions it was built from
+    pub fn with_interleaved_bytecode(mut self, enabled: bool) -> Self {
+        self.interleave_bytecode = enabled;
+        self
+    }
+
+    /// Names of the module error constants, used to render abort codes
+    pub fn with_error_constants(mut self, error_constants: &BTreeMap<u64, String>) -> Self {
+        self.error_constants = error_constants.clone();
+        self
+    }
+
+    /// When enabled, the abort conditions of the guards opening the function are collected
+    /// for its spec, see `aborts_if`
+    pub fn with_spec_inference(mut self, enabled: bool) -> Self {
+        self.infer_spec = enabled;
+        self
+    }
+
+    /// When set, storage accesses, native calls and loops are annotated with their estimated
+    /// cost under `schedule`
+    pub fn with_gas_annotations(mut self, schedule: Option<Rc<GasSchedule>>) -> Self {
+        self.gas_schedule = schedule;
+        self
+    }
+
+    /// `aborts_if` conditions inferred by the last `generate`
+    pub(crate) fn aborts_if(&self) -> &[String] {
+        &self.aborts_if
+    }
+
     pub(crate) fn generate(
         &mut self,
         optimizer_settings: &OptimizerSettings,
@@ -113,11 +160,29 @@ impl<'a> SourceGen<'a> {
             return Err(anyhow::anyhow!("final branch condition stack not empty"));
         }
 
-        let (ast, referenced_vairables) =
-            ast::optimizers::run(&ast, self.func_target, &self.naming, optimizer_settings)?;
+        let (mut ast, referenced_vairables) = ast::optimizers::run(
+            &ast,
+            self.func_target,
+            &self.naming,
+            optimizer_settings,
+            &self.error_constants,
+        )?;
+
+        if let Some(schedule) = &self.gas_schedule {
+            ast::gas::annotate_gas(&mut ast, self.func_target, schedule);
+        }
 
         let final_naming = self.naming.with_referenced_variables(&referenced_vairables);
 
+        self.aborts_if = if self.infer_spec {
+            ast::spec::infer_aborts_if(&ast, self.func_target.get_parameter_count())
+                .iter()
+                .map(|x| x.borrow().to_source(&final_naming))
+                .collect::<Result<_, _>>()?
+        } else {
+            Vec::new()
+        };
+
         Ok(ast.to_source(&final_naming, true)?)
     }
 
@@ -464,12 +529,13 @@ impl<'a> SourceGen<'a> {
                 let inner_var_meta = inner.as_ref().meta();
                 let inner_var_usage = inner_var_meta.get::<VarUsageSnapshot<VarUsage>>().unwrap();
 
-                let local_variables = inner_var_usage
+                let mut local_variables = inner_var_usage
                     .forward_run_post
                     .iter()
                     .filter(|(v, _)| !evaluation_ctx.defined_or_pending(**v))
                     .map(|(v, _)| *v)
                     .collect::<Vec<_>>();
+                local_variables.sort();
 
                 let need_pre_declaring_variables = local_variables
                     .iter()
@@ -544,6 +610,10 @@ impl<'a> SourceGen<'a> {
             .filter(|x| !x.removed)
             .peekable();
 
+        // bytecode consumed since the last emitted statement, only used in interleaved mode
+        let mut pending_bytecode = Vec::new();
+        let label_offsets = Default::default();
+
         while let Some(bytecode) = iter.next() {
             let node_var_usage = bytecode
                 .meta()
@@ -551,6 +621,15 @@ impl<'a> SourceGen<'a> {
                 .unwrap()
                 .clone();
 
+            if self.interleave_bytecode {
+                let insn = bytecode.bytecode.display(self.func_target, &label_offsets);
+                if bytecode.original_offset == usize::MAX {
+                    pending_bytecode.push(insn.to_string());
+                } else {
+                    pending_bytecode.push(format!("@{}: {}", bytecode.original_offset, insn));
+                }
+            }
+
             use move_stackless_bytecode::stackless_bytecode::Bytecode::*;
 
             let dst_tmps = match &bytecode.bytecode {
@@ -581,6 +660,8 @@ impl<'a> SourceGen<'a> {
                 continue;
             }
 
+            let items_before = codeunit.len();
+
             match &bytecode.bytecode {
                 Assign(_, dst, _, _) => {
                     let dst = *dst;
@@ -752,6 +833,7 @@ impl<'a> SourceGen<'a> {
                             value: DecompiledExpr::EvaluationExpr(
                                 crate::decompiler::evaluator::stackless::ExprNodeOperation::Const(
                                     val.clone(),
+                                    Some(self.func_target.get_local_type(dst).clone()),
                                 )
                                 .to_expr(),
                             )
@@ -802,6 +884,21 @@ impl<'a> SourceGen<'a> {
                     unreachable!("specification opcode should have been removed")
                 }
             }
+
+            // pending assignments may still be inlined, keep their bytecode for the next statement
+            if self.interleave_bytecode
+                && codeunit.len() > items_before
+                && !matches!(
+                    codeunit.last(),
+                    Some(DecompiledCodeItem::PossibleAssignStatement { .. })
+                )
+            {
+                flush_bytecode_comment(&mut codeunit, &mut pending_bytecode);
+            }
+        }
+
+        if self.interleave_bytecode {
+            flush_bytecode_comment(&mut codeunit, &mut pending_bytecode);
         }
 
         let block = block.inner();
@@ -830,6 +927,18 @@ impl<'a> SourceGen<'a> {
     }
 }
 
+fn flush_bytecode_comment(
+    codeunit: &mut DecompiledCodeUnitRef,
+    pending_bytecode: &mut Vec<String>,
+) {
+    if pending_bytecode.is_empty() {
+        return;
+    }
+    codeunit.add(DecompiledCodeItem::BytecodeCommentStatement(
+        std::mem::take(pending_bytecode),
+    ));
+}
+
 fn find_need_propagate_inner_defining_variables(
     current: &WithMetadata<CodeUnitBlock<usize, StacklessBlockContent>>,
     evaluation_ctx: &StacklessEvaluationContext<'_>,
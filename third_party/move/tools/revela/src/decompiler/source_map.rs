@@ -0,0 +1,87 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Where the declarations of each module end up in the decompiled source, see
+//! `Decompiler::decompile_with_source_map`.
+
+use std::io;
+
+use serde::Serialize;
+
+/// Lines of the decompiled source, numbered from 1, taken by each module and function
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SourceMap {
+    pub modules: Vec<ModuleSpan>,
+}
+
+/// A module or script, `name` is its full name such as `0x1::coin`, or `script`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ModuleSpan {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub functions: Vec<FunctionSpan>,
+}
+
+/// A function from its attributes to its closing brace, or its spec block when there is one
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FunctionSpan {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Forwards writes while counting the complete lines written
+pub(crate) struct LineCounter<'w, W: io::Write> {
+    inner: &'w mut W,
+    lines: usize,
+    /// lines at the end of the output that are empty or only indented
+    trailing_empty: usize,
+    current_empty: bool,
+}
+
+impl<'w, W: io::Write> LineCounter<'w, W> {
+    pub(crate) fn new(inner: &'w mut W) -> Self {
+        Self {
+            inner,
+            lines: 0,
+            trailing_empty: 0,
+            current_empty: true,
+        }
+    }
+
+    /// Number of the line being written
+    pub(crate) fn next_line(&self) -> usize {
+        self.lines + 1
+    }
+
+    /// Number of the last line written that is not empty
+    pub(crate) fn last_content_line(&self) -> usize {
+        self.lines - self.trailing_empty
+    }
+}
+
+impl<W: io::Write> io::Write for LineCounter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        for byte in &buf[..written] {
+            if *byte == b'\n' {
+                self.lines += 1;
+                if self.current_empty {
+                    self.trailing_empty += 1;
+                } else {
+                    self.trailing_empty = 0;
+                }
+                self.current_empty = true;
+            } else if !byte.is_ascii_whitespace() {
+                self.current_empty = false;
+            }
+        }
+        io::Result::Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
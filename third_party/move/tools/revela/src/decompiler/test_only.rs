@@ -0,0 +1,275 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recognize the test scaffolding of modules compiled in test mode.
+//!
+//! Test attributes are not kept in the bytecode, so test-only functions are found by the
+//! `unit_test` conventions: they call `0x1::unit_test` or a `*_for_test(ing)` helper, are named
+//! like such a helper, or call another test-only function of the module. Structs only used by
+//! test-only functions are test-only as well.
+
+use std::collections::{BTreeSet, HashMap};
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        Bytecode, CompiledModule, FunctionHandleIndex, SignatureToken, StructDefinitionIndex,
+        StructFieldInformation, Visibility,
+    },
+};
+use move_core_types::account_address::AccountAddress;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestAttribute {
+    /// a unit test, run by `aptos move test`
+    Test,
+    /// a helper that only exists in test builds
+    TestOnly,
+}
+
+impl TestAttribute {
+    pub(crate) fn to_source(self) -> String {
+        match self {
+            TestAttribute::Test => "#[test]".to_string(),
+            TestAttribute::TestOnly => "#[test_only]".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TestOnlyItems {
+    functions: HashMap<usize, TestAttribute>,
+    structs: BTreeSet<StructDefinitionIndex>,
+}
+
+impl TestOnlyItems {
+    pub(crate) fn function(&self, def_idx: usize) -> Option<TestAttribute> {
+        self.functions.get(&def_idx).copied()
+    }
+
+    pub(crate) fn is_test_only_struct(&self, def_idx: StructDefinitionIndex) -> bool {
+        self.structs.contains(&def_idx)
+    }
+}
+
+fn is_test_helper_name(name: &str) -> bool {
+    name.ends_with("_for_test") || name.ends_with("_for_testing")
+}
+
+fn is_test_callee(module: &CompiledModule, handle: FunctionHandleIndex) -> bool {
+    let handle = module.function_handle_at(handle);
+    let callee_module = module.module_handle_at(handle.module);
+    (*module.address_identifier_at(callee_module.address) == AccountAddress::ONE
+        && module.identifier_at(callee_module.name).as_str() == "unit_test")
+        || is_test_helper_name(module.identifier_at(handle.name).as_str())
+}
+
+pub(crate) fn infer_test_only(module: &CompiledModule) -> TestOnlyItems {
+    let def_of_handle = module
+        .function_defs()
+        .iter()
+        .enumerate()
+        .map(|(idx, def)| (def.function, idx))
+        .collect::<HashMap<_, _>>();
+    let struct_def_of_handle = module
+        .struct_defs()
+        .iter()
+        .enumerate()
+        .map(|(idx, def)| (def.struct_handle, StructDefinitionIndex(idx as u16)))
+        .collect::<HashMap<_, _>>();
+
+    let mut test_only = vec![false; module.function_defs().len()];
+    let mut callees = Vec::new();
+    let mut used_structs = Vec::new();
+    for (idx, def) in module.function_defs().iter().enumerate() {
+        let handle = module.function_handle_at(def.function);
+        test_only[idx] = is_test_helper_name(module.identifier_at(handle.name).as_str());
+
+        let mut called = Vec::new();
+        let mut structs = BTreeSet::new();
+        let mut tokens: Vec<&SignatureToken> = module
+            .signature_at(handle.parameters)
+            .0
+            .iter()
+            .chain(module.signature_at(handle.return_).0.iter())
+            .collect();
+        if let Some(code) = &def.code {
+            tokens.extend(module.signature_at(code.locals).0.iter());
+            for instr in code.code.iter() {
+                match instr {
+                    Bytecode::Call(callee) => {
+                        test_only[idx] |= is_test_callee(module, *callee);
+                        called.extend(def_of_handle.get(callee));
+                    }
+                    Bytecode::CallGeneric(inst) => {
+                        let inst = module.function_instantiation_at(*inst);
+                        test_only[idx] |= is_test_callee(module, inst.handle);
+                        called.extend(def_of_handle.get(&inst.handle));
+                        tokens.extend(module.signature_at(inst.type_parameters).0.iter());
+                    }
+                    Bytecode::Pack(s)
+                    | Bytecode::Unpack(s)
+                    | Bytecode::MutBorrowGlobal(s)
+                    | Bytecode::ImmBorrowGlobal(s)
+                    | Bytecode::Exists(s)
+                    | Bytecode::MoveFrom(s)
+                    | Bytecode::MoveTo(s) => {
+                        structs.insert(*s);
+                    }
+                    Bytecode::PackGeneric(s)
+                    | Bytecode::UnpackGeneric(s)
+                    | Bytecode::MutBorrowGlobalGeneric(s)
+                    | Bytecode::ImmBorrowGlobalGeneric(s)
+                    | Bytecode::ExistsGeneric(s)
+                    | Bytecode::MoveFromGeneric(s)
+                    | Bytecode::MoveToGeneric(s) => {
+                        let inst = module.struct_instantiation_at(*s);
+                        structs.insert(inst.def);
+                        tokens.extend(module.signature_at(inst.type_parameters).0.iter());
+                    }
+                    Bytecode::PackVariant(v)
+                    | Bytecode::UnpackVariant(v)
+                    | Bytecode::TestVariant(v) => {
+                        structs.insert(module.struct_variant_handle_at(*v).struct_index);
+                    }
+                    Bytecode::PackVariantGeneric(v)
+                    | Bytecode::UnpackVariantGeneric(v)
+                    | Bytecode::TestVariantGeneric(v) => {
+                        let inst = module.struct_variant_instantiation_at(*v);
+                        let handle = module.struct_variant_handle_at(inst.handle);
+                        structs.insert(handle.struct_index);
+                        tokens.extend(module.signature_at(inst.type_parameters).0.iter());
+                    }
+                    Bytecode::MutBorrowField(f) | Bytecode::ImmBorrowField(f) => {
+                        structs.insert(module.field_handle_at(*f).owner);
+                    }
+                    Bytecode::MutBorrowFieldGeneric(f) | Bytecode::ImmBorrowFieldGeneric(f) => {
+                        let inst = module.field_instantiation_at(*f);
+                        structs.insert(module.field_handle_at(inst.handle).owner);
+                        tokens.extend(module.signature_at(inst.type_parameters).0.iter());
+                    }
+                    Bytecode::MutBorrowVariantField(f) | Bytecode::ImmBorrowVariantField(f) => {
+                        structs.insert(module.variant_field_handle_at(*f).struct_index);
+                    }
+                    Bytecode::MutBorrowVariantFieldGeneric(f)
+                    | Bytecode::ImmBorrowVariantFieldGeneric(f) => {
+                        let inst = module.variant_field_instantiation_at(*f);
+                        let handle = module.variant_field_handle_at(inst.handle);
+                        structs.insert(handle.struct_index);
+                        tokens.extend(module.signature_at(inst.type_parameters).0.iter());
+                    }
+                    Bytecode::VecPack(sig, _)
+                    | Bytecode::VecLen(sig)
+                    | Bytecode::VecImmBorrow(sig)
+                    | Bytecode::VecMutBorrow(sig)
+                    | Bytecode::VecPushBack(sig)
+                    | Bytecode::VecPopBack(sig)
+                    | Bytecode::VecUnpack(sig, _)
+                    | Bytecode::VecSwap(sig) => {
+                        tokens.extend(module.signature_at(*sig).0.iter());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for token in tokens {
+            for ty in token.preorder_traversal() {
+                if let SignatureToken::Struct(s) | SignatureToken::StructInstantiation(s, _) = ty {
+                    structs.extend(struct_def_of_handle.get(s));
+                }
+            }
+        }
+        callees.push(called);
+        used_structs.push(structs);
+    }
+
+    // callers of test-only functions cannot be built without them
+    loop {
+        let mut changed = false;
+        for idx in 0..test_only.len() {
+            if !test_only[idx] && callees[idx].iter().any(|callee| test_only[*callee]) {
+                test_only[idx] = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut production_structs = BTreeSet::new();
+    let mut test_structs = BTreeSet::new();
+    for (idx, structs) in used_structs.into_iter().enumerate() {
+        if test_only[idx] {
+            test_structs.extend(structs);
+        } else {
+            production_structs.extend(structs);
+        }
+    }
+    // fields of production structs are production structs too
+    loop {
+        let mut changed = false;
+        for (idx, def) in module.struct_defs().iter().enumerate() {
+            if !production_structs.contains(&StructDefinitionIndex(idx as u16)) {
+                continue;
+            }
+            let fields: Vec<_> = match &def.field_information {
+                StructFieldInformation::Native => Vec::new(),
+                StructFieldInformation::Declared(fields) => fields.iter().collect(),
+                StructFieldInformation::DeclaredVariants(variants) => {
+                    variants.iter().flat_map(|v| v.fields.iter()).collect()
+                }
+            };
+            for field in fields {
+                for ty in field.signature.0.preorder_traversal() {
+                    if let SignatureToken::Struct(s) | SignatureToken::StructInstantiation(s, _) =
+                        ty
+                    {
+                        if let Some(s) = struct_def_of_handle.get(s) {
+                            changed |= production_structs.insert(*s);
+                        }
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let called = callees.iter().flatten().copied().collect::<BTreeSet<_>>();
+    let functions = test_only
+        .iter()
+        .enumerate()
+        .filter(|(_, test_only)| **test_only)
+        .map(|(idx, _)| {
+            let def = &module.function_defs()[idx];
+            let handle = module.function_handle_at(def.function);
+            // unit tests take no arguments here: signers would need addresses we do not know
+            let is_test = def.visibility == Visibility::Private
+                && !def.is_entry
+                && !called.contains(&idx)
+                && module
+                    .identifier_at(handle.name)
+                    .as_str()
+                    .starts_with("test")
+                && module.signature_at(handle.parameters).is_empty()
+                && module.signature_at(handle.return_).is_empty();
+            let attribute = if is_test {
+                TestAttribute::Test
+            } else {
+                TestAttribute::TestOnly
+            };
+            (idx, attribute)
+        })
+        .collect();
+
+    TestOnlyItems {
+        functions,
+        structs: test_structs
+            .difference(&production_structs)
+            .copied()
+            .collect(),
+    }
+}
@@ -4,9 +4,43 @@
 
 use move_model::model::{ModuleEnv, ModuleId};
 
-pub fn shortest_prefix(module_env: &ModuleEnv<'_>, target_mod_id: &ModuleId) -> String {
+/// Names that resolve to something else than a member of the current module when left
+/// unqualified: builtins, primitive types and the generic type parameters `T0`, `T1`, ...
+fn is_shadowed_name(name: &str) -> bool {
+    const BUILTIN_NAMES: &[&str] = &[
+        "address",
+        "assert",
+        "bool",
+        "borrow_global",
+        "borrow_global_mut",
+        "exists",
+        "freeze",
+        "move_from",
+        "move_to",
+        "signer",
+        "u8",
+        "u16",
+        "u32",
+        "u64",
+        "u128",
+        "u256",
+        "vector",
+    ];
+    BUILTIN_NAMES.contains(&name)
+        || name.strip_prefix('T').map_or(false, |idx| {
+            !idx.is_empty() && idx.bytes().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Prefix referring to `name` of module `target_mod_id` from `module_env`, members of other
+/// modules are fully qualified so that modules decompiled together never clash
+pub fn shortest_prefix(module_env: &ModuleEnv<'_>, target_mod_id: &ModuleId, name: &str) -> String {
     if *target_mod_id == module_env.get_id() {
-        String::new()
+        if is_shadowed_name(name) {
+            "Self::".to_string()
+        } else {
+            String::new()
+        }
     } else {
         let module = module_env.env.get_module(*target_mod_id);
         format!("{}::", module.get_full_name_str())
@@ -0,0 +1,493 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of a source package against on-chain bytecode.
+//!
+//! Modules built from the sources are compared to the deployed ones without their metadata, which
+//! holds the compiler version and Aptos attributes rather than code. Functions are compared one by
+//! one with pool indices resolved to the names, types and constants they point to, so a function
+//! still matches when an unrelated change shifted the pools of its module.
+
+#[cfg(feature = "verify")]
+use std::path::Path;
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::Result;
+use move_binary_format::{
+    access::ModuleAccess,
+    binary_views::BinaryIndexedView,
+    file_format::{
+        Bytecode, CompiledModule, FieldDefinition, FunctionDefinition, FunctionHandleIndex,
+        SignatureIndex, StructDefinition, StructDefinitionIndex, StructFieldInformation,
+        Visibility,
+    },
+};
+#[cfg(feature = "verify")]
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::ModuleId;
+#[cfg(feature = "verify")]
+use move_model::metadata::CompilerVersion;
+
+use super::package::{render_token, struct_name};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchStatus {
+    Match,
+    /// visibility, entry flag, type parameters, parameters, results or acquired resources differ
+    SignatureMismatch,
+    CodeMismatch,
+    MissingFromSource,
+    MissingOnChain,
+}
+
+impl MatchStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            MatchStatus::Match => "match",
+            MatchStatus::SignatureMismatch => "signature mismatch",
+            MatchStatus::CodeMismatch => "code mismatch",
+            MatchStatus::MissingFromSource => "missing from source",
+            MatchStatus::MissingOnChain => "missing on chain",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ModuleVerification {
+    pub module: ModuleId,
+    pub status: MatchStatus,
+    /// whether both modules serialize to the same bytes once their metadata is stripped
+    pub bytecode_identical: bool,
+    pub structs: BTreeMap<String, MatchStatus>,
+    pub functions: BTreeMap<String, MatchStatus>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VerificationReport {
+    pub modules: Vec<ModuleVerification>,
+}
+
+impl VerificationReport {
+    /// Every on-chain module is built from the sources, with all of its functions and structs
+    pub fn is_verified(&self) -> bool {
+        self.modules.iter().all(|m| {
+            m.status == MatchStatus::MissingOnChain
+                || m.status == MatchStatus::Match
+                    && m.structs.values().all(|s| *s == MatchStatus::Match)
+                    && m.functions.values().all(|s| *s == MatchStatus::Match)
+        })
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for module in &self.modules {
+            let identical = if module.bytecode_identical {
+                ", identical bytecode"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "module {}: {}{}",
+                module.module.short_str_lossless(),
+                module.status.as_str(),
+                identical
+            )?;
+            for (name, status) in &module.structs {
+                writeln!(f, "    struct {}: {}", name, status.as_str())?;
+            }
+            for (name, status) in &module.functions {
+                writeln!(f, "    fun {}: {}", name, status.as_str())?;
+            }
+        }
+        write!(
+            f,
+            "{}",
+            if self.is_verified() {
+                "verified"
+            } else {
+                "NOT verified"
+            }
+        )
+    }
+}
+
+/// Build the Move package at `path`, returning the modules of the package itself. The Aptos
+/// attributes are not checked and no metadata is attached, which verification ignores anyway.
+#[cfg(feature = "verify")]
+pub fn compile_package(
+    path: &Path,
+    named_addresses: BTreeMap<String, AccountAddress>,
+    compiler_version: Option<CompilerVersion>,
+) -> Result<Vec<CompiledModule>> {
+    use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+    use move_package::{BuildConfig, CompilerConfig};
+
+    let config = BuildConfig {
+        additional_named_addresses: named_addresses,
+        skip_fetch_latest_git_deps: true,
+        compiler_config: CompilerConfig {
+            skip_attribute_checks: true,
+            compiler_version,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    let (package, _) = config
+        .compile_package_no_exit(path, &mut output)
+        .map_err(|err| err.context(String::from_utf8_lossy(&output).into_owned()))?;
+    Ok(package
+        .root_modules()
+        .filter_map(|unit| match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule { module, .. }) => Some(module.clone()),
+            CompiledUnit::Script(_) => None,
+        })
+        .collect())
+}
+
+/// Compare the modules built from the sources against the on-chain ones. Source modules with no
+/// on-chain counterpart are reported as missing on chain and do not fail the verification, so a
+/// single deployed module can be checked against its whole package.
+pub fn verify_modules(
+    source: &[CompiledModule],
+    on_chain: &[CompiledModule],
+) -> Result<VerificationReport> {
+    let source: BTreeMap<_, _> = source.iter().map(|m| (m.self_id(), m)).collect();
+    let on_chain: BTreeMap<_, _> = on_chain.iter().map(|m| (m.self_id(), m)).collect();
+
+    let mut modules = Vec::new();
+    for (id, deployed) in &on_chain {
+        modules.push(match source.get(id) {
+            Some(built) => verify_module(built, deployed)?,
+            None => ModuleVerification {
+                module: id.clone(),
+                status: MatchStatus::MissingFromSource,
+                bytecode_identical: false,
+                structs: BTreeMap::new(),
+                functions: BTreeMap::new(),
+            },
+        });
+    }
+    for id in source.keys().filter(|id| !on_chain.contains_key(*id)) {
+        modules.push(ModuleVerification {
+            module: id.clone(),
+            status: MatchStatus::MissingOnChain,
+            bytecode_identical: false,
+            structs: BTreeMap::new(),
+            functions: BTreeMap::new(),
+        });
+    }
+    Ok(VerificationReport { modules })
+}
+
+fn verify_module(built: &CompiledModule, deployed: &CompiledModule) -> Result<ModuleVerification> {
+    let bytecode_identical =
+        without_metadata(built, deployed.version)? == without_metadata(deployed, deployed.version)?;
+
+    let structs = compare(
+        &struct_fingerprints(built),
+        &struct_fingerprints(deployed),
+        |_, _| MatchStatus::CodeMismatch,
+    );
+    let functions = compare(
+        &function_fingerprints(built),
+        &function_fingerprints(deployed),
+        |built: &FunctionFingerprint, deployed: &FunctionFingerprint| {
+            if built.signature != deployed.signature {
+                MatchStatus::SignatureMismatch
+            } else {
+                MatchStatus::CodeMismatch
+            }
+        },
+    );
+
+    let friends = |module: &CompiledModule| {
+        let mut friends: Vec<_> = module
+            .immediate_friends()
+            .iter()
+            .map(|id| id.short_str_lossless())
+            .collect();
+        friends.sort();
+        friends
+    };
+    let status = if friends(built) == friends(deployed) {
+        MatchStatus::Match
+    } else {
+        MatchStatus::SignatureMismatch
+    };
+
+    Ok(ModuleVerification {
+        module: deployed.self_id(),
+        status,
+        bytecode_identical,
+        structs,
+        functions,
+    })
+}
+
+fn without_metadata(module: &CompiledModule, version: u32) -> Result<Vec<u8>> {
+    let mut module = module.clone();
+    module.metadata.clear();
+    let mut bytes = Vec::new();
+    module.serialize_for_version(Some(version), &mut bytes)?;
+    Ok(bytes)
+}
+
+fn compare<T: PartialEq>(
+    built: &BTreeMap<String, T>,
+    deployed: &BTreeMap<String, T>,
+    mismatch: impl Fn(&T, &T) -> MatchStatus,
+) -> BTreeMap<String, MatchStatus> {
+    let mut statuses = BTreeMap::new();
+    for (name, deployed) in deployed {
+        let status = match built.get(name) {
+            Some(built) if built == deployed => MatchStatus::Match,
+            Some(built) => mismatch(built, deployed),
+            None => MatchStatus::MissingFromSource,
+        };
+        statuses.insert(name.clone(), status);
+    }
+    for name in built.keys().filter(|name| !deployed.contains_key(*name)) {
+        statuses.insert(name.clone(), MatchStatus::MissingOnChain);
+    }
+    statuses
+}
+
+fn struct_fingerprints(module: &CompiledModule) -> BTreeMap<String, Vec<String>> {
+    let view = BinaryIndexedView::Module(module);
+    let fields = |fields: &[FieldDefinition]| {
+        fields
+            .iter()
+            .map(|field| {
+                format!(
+                    "{}: {}",
+                    module.identifier_at(field.name),
+                    render_token(&view, &field.signature.0)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    module
+        .struct_defs()
+        .iter()
+        .map(|def: &StructDefinition| {
+            let handle = module.struct_handle_at(def.struct_handle);
+            let mut fingerprint = vec![
+                format!("{:?}", handle.abilities),
+                format!("{:?}", handle.type_parameters),
+            ];
+            match &def.field_information {
+                StructFieldInformation::Native => fingerprint.push("native".to_string()),
+                StructFieldInformation::Declared(declared) => fingerprint.push(fields(declared)),
+                StructFieldInformation::DeclaredVariants(variants) => {
+                    fingerprint.extend(variants.iter().map(|variant| {
+                        format!(
+                            "{}{{{}}}",
+                            module.identifier_at(variant.name),
+                            fields(&variant.fields)
+                        )
+                    }))
+                }
+            }
+            (module.identifier_at(handle.name).to_string(), fingerprint)
+        })
+        .collect()
+}
+
+#[derive(PartialEq)]
+struct FunctionFingerprint {
+    signature: Vec<String>,
+    code: Option<Vec<String>>,
+}
+
+fn function_fingerprints(module: &CompiledModule) -> BTreeMap<String, FunctionFingerprint> {
+    module
+        .function_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.function_handle_at(def.function);
+            let name = module.identifier_at(handle.name).to_string();
+            (name, function_fingerprint(module, def))
+        })
+        .collect()
+}
+
+fn function_fingerprint(module: &CompiledModule, def: &FunctionDefinition) -> FunctionFingerprint {
+    let handle = module.function_handle_at(def.function);
+    let signature = |idx: SignatureIndex| render_signature(module, idx);
+    let visibility = match def.visibility {
+        Visibility::Private => "private",
+        Visibility::Public => "public",
+        Visibility::Friend => "friend",
+    };
+    let mut acquires: Vec<_> = def
+        .acquires_global_resources
+        .iter()
+        .map(|idx| struct_def_name(module, *idx))
+        .collect();
+    acquires.sort();
+
+    FunctionFingerprint {
+        signature: vec![
+            visibility.to_string(),
+            def.is_entry.to_string(),
+            format!("{:?}", handle.type_parameters),
+            signature(handle.parameters),
+            signature(handle.return_),
+            acquires.join(", "),
+        ],
+        code: def.code.as_ref().map(|code| {
+            let mut lines = vec![signature(code.locals)];
+            lines.extend(
+                code.code
+                    .iter()
+                    .map(|instr| render_instruction(module, instr)),
+            );
+            lines
+        }),
+    }
+}
+
+fn render_signature(module: &CompiledModule, idx: SignatureIndex) -> String {
+    let view = BinaryIndexedView::Module(module);
+    module
+        .signature_at(idx)
+        .0
+        .iter()
+        .map(|token| render_token(&view, token))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn struct_def_name(module: &CompiledModule, idx: StructDefinitionIndex) -> String {
+    struct_name(
+        &BinaryIndexedView::Module(module),
+        module.struct_def_at(idx).struct_handle,
+    )
+}
+
+fn function_name(module: &CompiledModule, idx: FunctionHandleIndex) -> String {
+    let handle = module.function_handle_at(idx);
+    let module_id = module.module_id_for_handle(module.module_handle_at(handle.module));
+    format!(
+        "{}::{}",
+        module_id.short_str_lossless(),
+        module.identifier_at(handle.name)
+    )
+}
+
+/// `instr` with the pool entries it refers to resolved
+fn render_instruction(module: &CompiledModule, instr: &Bytecode) -> String {
+    let generic = |name: String, types: SignatureIndex| {
+        format!("{}<{}>", name, render_signature(module, types))
+    };
+    let struct_inst = |idx| {
+        let inst = module.struct_instantiation_at(idx);
+        generic(struct_def_name(module, inst.def), inst.type_parameters)
+    };
+    let variant = |idx| {
+        let handle = module.struct_variant_handle_at(idx);
+        format!(
+            "{}::{}",
+            struct_def_name(module, handle.struct_index),
+            handle.variant
+        )
+    };
+    let variant_inst = |idx| {
+        let inst = module.struct_variant_instantiation_at(idx);
+        generic(variant(inst.handle), inst.type_parameters)
+    };
+    let field = |idx| {
+        let handle = module.field_handle_at(idx);
+        format!("{}.{}", struct_def_name(module, handle.owner), handle.field)
+    };
+    let field_inst = |idx| {
+        let inst = module.field_instantiation_at(idx);
+        generic(field(inst.handle), inst.type_parameters)
+    };
+    let variant_field = |idx| {
+        let handle = module.variant_field_handle_at(idx);
+        format!(
+            "{}::{:?}.{}",
+            struct_def_name(module, handle.struct_index),
+            handle.variants,
+            handle.field
+        )
+    };
+    let variant_field_inst = |idx| {
+        let inst = module.variant_field_instantiation_at(idx);
+        generic(variant_field(inst.handle), inst.type_parameters)
+    };
+
+    let (op, operand) = match instr {
+        Bytecode::LdConst(idx) => {
+            let constant = module.constant_at(*idx);
+            let ty = render_token(&BinaryIndexedView::Module(module), &constant.type_);
+            (
+                "LdConst",
+                format!("{}: {}", ty, hex::encode(&constant.data)),
+            )
+        }
+        Bytecode::Call(idx) => ("Call", function_name(module, *idx)),
+        Bytecode::CallGeneric(idx) => {
+            let inst = module.function_instantiation_at(*idx);
+            (
+                "Call",
+                generic(function_name(module, inst.handle), inst.type_parameters),
+            )
+        }
+        Bytecode::Pack(idx) => ("Pack", struct_def_name(module, *idx)),
+        Bytecode::Unpack(idx) => ("Unpack", struct_def_name(module, *idx)),
+        Bytecode::MutBorrowGlobal(idx) => ("MutBorrowGlobal", struct_def_name(module, *idx)),
+        Bytecode::ImmBorrowGlobal(idx) => ("ImmBorrowGlobal", struct_def_name(module, *idx)),
+        Bytecode::Exists(idx) => ("Exists", struct_def_name(module, *idx)),
+        Bytecode::MoveFrom(idx) => ("MoveFrom", struct_def_name(module, *idx)),
+        Bytecode::MoveTo(idx) => ("MoveTo", struct_def_name(module, *idx)),
+        Bytecode::PackGeneric(idx) => ("Pack", struct_inst(*idx)),
+        Bytecode::UnpackGeneric(idx) => ("Unpack", struct_inst(*idx)),
+        Bytecode::MutBorrowGlobalGeneric(idx) => ("MutBorrowGlobal", struct_inst(*idx)),
+        Bytecode::ImmBorrowGlobalGeneric(idx) => ("ImmBorrowGlobal", struct_inst(*idx)),
+        Bytecode::ExistsGeneric(idx) => ("Exists", struct_inst(*idx)),
+        Bytecode::MoveFromGeneric(idx) => ("MoveFrom", struct_inst(*idx)),
+        Bytecode::MoveToGeneric(idx) => ("MoveTo", struct_inst(*idx)),
+        Bytecode::PackVariant(idx) => ("PackVariant", variant(*idx)),
+        Bytecode::UnpackVariant(idx) => ("UnpackVariant", variant(*idx)),
+        Bytecode::TestVariant(idx) => ("TestVariant", variant(*idx)),
+        Bytecode::PackVariantGeneric(idx) => ("PackVariant", variant_inst(*idx)),
+        Bytecode::UnpackVariantGeneric(idx) => ("UnpackVariant", variant_inst(*idx)),
+        Bytecode::TestVariantGeneric(idx) => ("TestVariant", variant_inst(*idx)),
+        Bytecode::MutBorrowField(idx) => ("MutBorrowField", field(*idx)),
+        Bytecode::ImmBorrowField(idx) => ("ImmBorrowField", field(*idx)),
+        Bytecode::MutBorrowFieldGeneric(idx) => ("MutBorrowField", field_inst(*idx)),
+        Bytecode::ImmBorrowFieldGeneric(idx) => ("ImmBorrowField", field_inst(*idx)),
+        Bytecode::MutBorrowVariantField(idx) => ("MutBorrowVariantField", variant_field(*idx)),
+        Bytecode::ImmBorrowVariantField(idx) => ("ImmBorrowVariantField", variant_field(*idx)),
+        Bytecode::MutBorrowVariantFieldGeneric(idx) => {
+            ("MutBorrowVariantField", variant_field_inst(*idx))
+        }
+        Bytecode::ImmBorrowVariantFieldGeneric(idx) => {
+            ("ImmBorrowVariantField", variant_field_inst(*idx))
+        }
+        Bytecode::VecPack(sig, n) => (
+            "VecPack",
+            format!("{}, {}", render_signature(module, *sig), n),
+        ),
+        Bytecode::VecUnpack(sig, n) => (
+            "VecUnpack",
+            format!("{}, {}", render_signature(module, *sig), n),
+        ),
+        Bytecode::VecLen(sig) => ("VecLen", render_signature(module, *sig)),
+        Bytecode::VecImmBorrow(sig) => ("VecImmBorrow", render_signature(module, *sig)),
+        Bytecode::VecMutBorrow(sig) => ("VecMutBorrow", render_signature(module, *sig)),
+        Bytecode::VecPushBack(sig) => ("VecPushBack", render_signature(module, *sig)),
+        Bytecode::VecPopBack(sig) => ("VecPopBack", render_signature(module, *sig)),
+        Bytecode::VecSwap(sig) => ("VecSwap", render_signature(module, *sig)),
+        // the remaining instructions only hold offsets, locals and literals
+        _ => return format!("{:?}", instr),
+    };
+    format!("{}({})", op, operand)
+}
@@ -3,3 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod decompiler;
+
+#[cfg(feature = "server")]
+pub mod server;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -4,15 +4,17 @@
 
 #![forbid(unsafe_code)]
 
-use std::fs;
+use std::{collections::BTreeMap, fs, process};
 
 use clap::{AppSettings, Parser};
+use log::LevelFilter;
 
-use move_binary_format::{
-    binary_views::BinaryIndexedView,
-    file_format::{CompiledModule, CompiledScript},
+use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use move_core_types::account_address::AccountAddress;
+use revela::decompiler::{
+    compile_package, verify_modules, AddressAliases, BraceStyle, CompiledBinary, Decompiler,
+    FormatOptions, GasSchedule, IrFormat, OptimizerSettings,
 };
-use revela::decompiler::{Decompiler, OptimizerSettings};
 #[derive(Debug, Parser)]
 #[clap(setting = AppSettings::ArgRequiredElseHelp)]
 #[clap(version, about = "Decompile Move bytecode back to source code. By verichains.io", name = "revela")]
@@ -30,16 +32,224 @@ struct Args {
         default_value = "false"
     )]
     pub disable_variable_declaration_optimization: bool,
+
+    /// Emit Move 2 `for` loops for counted loops
+    #[clap(long = "for-loops")]
+    pub for_loops: bool,
+
+    /// Emit Move 2 receiver-style calls such as `v.length()`
+    #[clap(long = "method-calls")]
+    pub method_calls: bool,
+
+    /// Keep assignments whose value is never read, following the bytecode closely
+    #[clap(long = "keep-dead-stores")]
+    pub keep_dead_stores: bool,
+
+    /// Omit type arguments of calls where they can be inferred from the value arguments
+    #[clap(long = "infer-type-args")]
+    pub infer_type_args: bool,
+
+    /// Fold opaque predicates and unflatten dispatcher loops of obfuscated control flow
+    #[clap(long = "deobfuscate")]
+    pub deobfuscate: bool,
+
+    /// Follow each function with a skeleton `spec` block for the Move prover
+    #[clap(long = "spec-skeletons")]
+    pub spec_skeletons: bool,
+
+    /// Follow each statement with a comment listing the stackless bytecode it was built from
+    #[clap(long = "interleave-bytecode")]
+    pub interleave_bytecode: bool,
+
+    /// Precede each function body with its stackless bytecode, as produced by the bytecode
+    /// processors
+    #[clap(long = "dump-raw-bytecode")]
+    pub dump_raw_bytecode: bool,
+
+    /// Precede each function body with its stackless bytecode once its control flow is
+    /// restructured
+    #[clap(long = "dump-structured-bytecode")]
+    pub dump_structured_bytecode: bool,
+
+    /// (multiple) Only decompile the functions with this name
+    #[clap(long = "function")]
+    pub functions: Vec<String>,
+
+    /// Record the module, bytecode hash, decompiler version and options in each footer
+    #[clap(long = "provenance")]
+    pub provenance: bool,
+
+    /// Annotate storage accesses, native calls and loops with their estimated cost under the
+    /// latest Aptos gas schedule, in internal gas units
+    #[clap(long = "gas-annotations")]
+    pub gas_annotations: bool,
+
+    /// Number of spaces per indentation level
+    #[clap(long = "indent-width", default_value = "4")]
+    pub indent_width: usize,
+
+    /// Wrap long function headers and keep short structs on one line
+    #[clap(long = "max-line-width")]
+    pub max_line_width: Option<usize>,
+
+    /// Put the opening brace of declarations on its own line
+    #[clap(long = "brace-next-line")]
+    pub brace_next_line: bool,
+
+    /// Render module paths with the named addresses of the Aptos framework, e.g.
+    /// `aptos_framework::coin::Coin` instead of `0x1::coin::Coin`
+    #[clap(long = "address-aliases")]
+    pub address_aliases: bool,
+
+    /// TOML file of further named addresses, `name = "0x..."` entries or an `[addresses]`
+    /// table as in a Move.toml, implies `--address-aliases`
+    #[clap(long = "address-alias-file")]
+    pub address_alias_file: Option<String>,
+
+    /// Write a Move package with one source file per module to this directory instead of
+    /// printing the source, modules that are referred to but not given are stubbed
+    #[clap(long = "package")]
+    pub package: Option<String>,
+
+    /// Name of the package written with `--package`
+    #[clap(long = "package-name", default_value = "Decompiled")]
+    pub package_name: String,
+
+    /// Instead of source, print the optimized stackless bytecode with its control flow
+    /// structured, as `text` or `json`
+    #[clap(long = "emit-ir", possible_values = ["text", "json"])]
+    pub emit_ir: Option<String>,
+
+    /// Instead of decompiling, build the Move package in this directory and check that it
+    /// matches the given modules function by function, ignoring metadata
+    #[clap(long = "verify")]
+    pub verify: Option<String>,
+
+    /// Named address of the package verified with `--verify`, as `name=0x...`
+    #[clap(long = "named-address")]
+    pub named_addresses: Vec<String>,
+
+    /// Compiler building the package verified with `--verify`, e.g. `1` or `2.0`
+    #[clap(long = "compiler-version")]
+    pub compiler_version: Option<String>,
+
+    /// Instead of decompiling the given files, serve `POST /decompile` over HTTP on this
+    /// address, e.g. `127.0.0.1:8080`
+    #[cfg(feature = "server")]
+    #[clap(long = "serve")]
+    pub serve: Option<std::net::SocketAddr>,
+
+    /// Decompilations the server runs at once, further requests are refused, defaults to the
+    /// number of CPUs
+    #[cfg(feature = "server")]
+    #[clap(long = "max-concurrent")]
+    pub max_concurrent: Option<usize>,
+
+    /// Seconds a decompilation may take on the server
+    #[cfg(feature = "server")]
+    #[clap(long = "timeout", default_value = "30")]
+    pub timeout: u64,
+
+    /// Instructions of the largest function the server decompiles, larger ones are refused
+    #[cfg(feature = "server")]
+    #[clap(long = "max-function-instructions", default_value = "10000")]
+    pub max_function_instructions: usize,
+
+    /// Print diagnostics to stderr, repeat for more details (-vvv dumps stackless bytecode)
+    #[clap(short = 'v', long = "verbose", parse(from_occurrences))]
+    pub verbose: u64,
 }
 
-enum CompiledBinary {
-    Script(CompiledScript),
-    Module(CompiledModule),
+/// Diagnostics go to stderr so that they never mix with the decompiled source
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        eprintln!("[{}] {}", record.level(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+fn verify(args: &Args, package: &str, binaries: &[CompiledBinary]) {
+    let named_addresses: BTreeMap<_, _> = args
+        .named_addresses
+        .iter()
+        .map(|entry| {
+            let (name, address) = entry.split_once('=').unwrap_or_else(|| {
+                panic!("Error: expected `name=address`, got {}", entry);
+            });
+            let address = AccountAddress::from_hex_literal(address).unwrap_or_else(|err| {
+                panic!("Error: invalid address of {}: {}", name, err);
+            });
+            (name.to_string(), address)
+        })
+        .collect();
+    let compiler_version = args.compiler_version.as_ref().map(|version| {
+        version.parse().unwrap_or_else(|err| {
+            panic!("Error: invalid compiler version {}: {}", version, err);
+        })
+    });
+
+    let built = compile_package(
+        std::path::Path::new(package),
+        named_addresses,
+        compiler_version,
+    )
+    .unwrap_or_else(|err| panic!("Error: unable to build {}: {:#}", package, err));
+    let on_chain: Vec<_> = binaries
+        .iter()
+        .filter_map(|binary| match binary {
+            CompiledBinary::Module(module) => Some(module.clone()),
+            CompiledBinary::Script(_) => None,
+        })
+        .collect();
+    let report = verify_modules(&built, &on_chain).expect("Error: unable to verify");
+    println!("{}", report);
+    if !report.is_verified() {
+        process::exit(1);
+    }
+}
+
+#[cfg(feature = "server")]
+fn serve(args: &Args, addr: std::net::SocketAddr) {
+    let mut config = revela::server::ServerConfig {
+        timeout: std::time::Duration::from_secs(args.timeout),
+        max_function_instructions: args.max_function_instructions,
+        ..Default::default()
+    };
+    if let Some(max_concurrent) = args.max_concurrent {
+        config.max_concurrent = max_concurrent;
+    }
+    let runtime = tokio::runtime::Runtime::new().expect("Error: unable to start the runtime");
+    log::info!("serving on {}", addr);
+    runtime
+        .block_on(revela::server::serve(addr, config))
+        .unwrap_or_else(|err| panic!("Error: unable to serve on {}: {}", addr, err));
 }
 
 fn main() {
     let args = Args::parse();
 
+    let verbosity = match args.verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    log::set_logger(&StderrLogger).expect("Error: unable to set logger");
+    log::set_max_level(verbosity);
+
+    #[cfg(feature = "server")]
+    if let Some(addr) = args.serve {
+        serve(&args, addr);
+        return;
+    }
+
     let binaries_store: Vec<_> = args
         .files
         .iter()
@@ -64,20 +274,78 @@ fn main() {
         })
         .collect();
 
+    if let Some(package) = &args.verify {
+        verify(&args, package, &binaries_store);
+        return;
+    }
+
     let binaries: Vec<_> = binaries_store
         .iter()
-        .map(|binary| match binary {
-            CompiledBinary::Script(script) => BinaryIndexedView::Script(script),
-            CompiledBinary::Module(module) => BinaryIndexedView::Module(module),
-        })
+        .map(CompiledBinary::as_binary_view)
         .collect();
 
     let mut decompiler = Decompiler::new(
         binaries,
         OptimizerSettings {
             disable_optimize_variables_declaration: args.disable_variable_declaration_optimization,
+            enable_for_loop_syntax: args.for_loops,
+            enable_receiver_call_syntax: args.method_calls,
+            keep_dead_stores: args.keep_dead_stores,
+            elide_inferable_type_args: args.infer_type_args,
+            deobfuscate: args.deobfuscate,
+        },
+    )
+    .with_format_options(FormatOptions {
+        indent_width: args.indent_width,
+        max_line_width: args.max_line_width,
+        brace_style: if args.brace_next_line {
+            BraceStyle::NextLine
+        } else {
+            BraceStyle::SameLine
         },
-    );
-    let output = decompiler.decompile().expect("Error: unable to decompile");
-    println!("{}", output);
+    })
+    .with_verbosity(verbosity)
+    .with_spec_skeletons(args.spec_skeletons)
+    .with_interleaved_bytecode(args.interleave_bytecode)
+    .with_raw_bytecode(args.dump_raw_bytecode)
+    .with_structured_bytecode(args.dump_structured_bytecode)
+    .with_provenance(args.provenance);
+    if !args.functions.is_empty() {
+        decompiler = decompiler.with_function_selector(args.functions.iter().cloned());
+    }
+    if args.address_aliases || args.address_alias_file.is_some() {
+        let mut aliases = AddressAliases::defaults();
+        if let Some(file) = &args.address_alias_file {
+            let source = fs::read_to_string(file).unwrap_or_else(|err| {
+                panic!("Error: failed to read file {}: {}", file, err);
+            });
+            aliases.extend(AddressAliases::from_toml(&source).unwrap_or_else(|err| {
+                panic!("Error: invalid address aliases in {}: {}", file, err);
+            }));
+        }
+        decompiler = decompiler.with_address_aliases(aliases);
+    }
+    if args.gas_annotations {
+        decompiler = decompiler.with_gas_annotations(GasSchedule::latest());
+    }
+    if let Some(format) = &args.emit_ir {
+        let format = match format.as_str() {
+            "json" => IrFormat::Json,
+            _ => IrFormat::Text,
+        };
+        decompiler
+            .emit_ir_to(format, &mut std::io::stdout().lock())
+            .expect("Error: unable to emit the IR");
+        return;
+    }
+    if let Some(package) = &args.package {
+        decompiler
+            .decompile_package(std::path::Path::new(package), &args.package_name)
+            .expect("Error: unable to decompile");
+        return;
+    }
+    decompiler
+        .decompile_to(&mut std::io::stdout().lock())
+        .expect("Error: unable to decompile");
+    println!();
 }
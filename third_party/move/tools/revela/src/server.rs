@@ -0,0 +1,242 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP mode, to run the decompiler as a sidecar of e.g. an explorer:
+//!
+//! ```text
+//! POST /decompile {"modules": ["a11ceb0b..."]}
+//! 200 {"source": "module 0x1::coin {...}", "source_map": {"modules": [...]}}
+//! ```
+//!
+//! Binaries are hex encoded, `0x` prefix optional. Decompilations run on blocking threads, at
+//! most `max_concurrent` at once, further requests get a 503. A decompilation taking longer than
+//! `timeout` gets a 504 and is cancelled before its next function.
+//!
+//! Binaries must pass the bytecode verifier with the production limits and functions may have at
+//! most `max_function_instructions` instructions, which bounds the work done on a single
+//! function, hence the time a slot stays taken after a timeout.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{DefaultBodyLimit, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use move_bytecode_verifier::{
+    verify_module_with_config, verify_script_with_config, VerifierConfig,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use crate::decompiler::{
+    CancellationToken, CompiledBinary, Decompiler, OptimizerSettings, SourceMap,
+};
+
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    /// decompilations running at once
+    pub max_concurrent: usize,
+    /// time a decompilation may take
+    pub timeout: Duration,
+    /// size of the largest request body accepted, in bytes
+    pub max_body_bytes: usize,
+    /// instructions of the largest function accepted
+    pub max_function_instructions: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: std::thread::available_parallelism()
+                .map(|x| x.get())
+                .unwrap_or(4),
+            timeout: Duration::from_secs(30),
+            max_body_bytes: 4 * 1024 * 1024,
+            max_function_instructions: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DecompileRequest {
+    #[serde(default)]
+    pub modules: Vec<String>,
+    #[serde(default)]
+    pub scripts: Vec<String>,
+    /// emit Move 2 `for` loops for counted loops
+    #[serde(default)]
+    pub for_loops: bool,
+    /// emit Move 2 receiver-style calls such as `v.length()`
+    #[serde(default)]
+    pub method_calls: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecompileResponse {
+    pub source: String,
+    pub source_map: SourceMap,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, message: impl Into<String>) -> HandlerError {
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+}
+
+struct ServerState {
+    config: ServerConfig,
+    permits: Arc<Semaphore>,
+}
+
+pub fn router(config: ServerConfig) -> Router {
+    let body_limit = config.max_body_bytes;
+    let state = ServerState {
+        permits: Arc::new(Semaphore::new(config.max_concurrent)),
+        config,
+    };
+    Router::new()
+        .route("/decompile", post(decompile))
+        .layer(DefaultBodyLimit::max(body_limit))
+        .with_state(Arc::new(state))
+}
+
+pub async fn serve(addr: SocketAddr, config: ServerConfig) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(config)).await
+}
+
+fn decode(hex_bytecode: &str) -> Result<Vec<u8>, HandlerError> {
+    let hex_bytecode = hex_bytecode.trim();
+    hex::decode(hex_bytecode.strip_prefix("0x").unwrap_or(hex_bytecode))
+        .map_err(|err| error(StatusCode::BAD_REQUEST, format!("invalid hex: {}", err)))
+}
+
+fn check_function_size(instructions: usize, config: &ServerConfig) -> Result<(), HandlerError> {
+    if instructions > config.max_function_instructions {
+        return Err(error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "function of {} instructions, at most {} are accepted",
+                instructions, config.max_function_instructions
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_binaries(
+    request: &DecompileRequest,
+    config: &ServerConfig,
+) -> Result<Vec<CompiledBinary>, HandlerError> {
+    let verifier_config = VerifierConfig::production();
+    let mut binaries = Vec::new();
+    for module in &request.modules {
+        let module = CompiledModule::deserialize(&decode(module)?).map_err(|err| {
+            error(
+                StatusCode::BAD_REQUEST,
+                format!("failed to deserialize module: {}", err),
+            )
+        })?;
+        verify_module_with_config(&verifier_config, &module).map_err(|err| {
+            error(
+                StatusCode::BAD_REQUEST,
+                format!("module rejected by the bytecode verifier: {}", err),
+            )
+        })?;
+        for code in module.function_defs.iter().filter_map(|f| f.code.as_ref()) {
+            check_function_size(code.code.len(), config)?;
+        }
+        binaries.push(CompiledBinary::Module(module));
+    }
+    for script in &request.scripts {
+        let script = CompiledScript::deserialize(&decode(script)?).map_err(|err| {
+            error(
+                StatusCode::BAD_REQUEST,
+                format!("failed to deserialize script: {}", err),
+            )
+        })?;
+        verify_script_with_config(&verifier_config, &script).map_err(|err| {
+            error(
+                StatusCode::BAD_REQUEST,
+                format!("script rejected by the bytecode verifier: {}", err),
+            )
+        })?;
+        check_function_size(script.code.code.len(), config)?;
+        binaries.push(CompiledBinary::Script(script));
+    }
+    if binaries.is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "no modules or scripts given",
+        ));
+    }
+    Ok(binaries)
+}
+
+async fn decompile(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<DecompileRequest>,
+) -> Result<Json<DecompileResponse>, HandlerError> {
+    let permit = state.permits.clone().try_acquire_owned().map_err(|_| {
+        error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "too many decompilations in progress",
+        )
+    })?;
+    let binaries = parse_binaries(&request, &state.config)?;
+    let settings = OptimizerSettings {
+        enable_for_loop_syntax: request.for_loops,
+        enable_receiver_call_syntax: request.method_calls,
+        ..Default::default()
+    };
+
+    let cancellation = CancellationToken::new();
+    let task = tokio::task::spawn_blocking({
+        let cancellation = cancellation.clone();
+        move || {
+            // the slot is taken until the decompilation really stops, even after a timeout
+            let _permit = permit;
+            let views = binaries
+                .iter()
+                .map(CompiledBinary::as_binary_view)
+                .collect();
+            Decompiler::new(views, settings)
+                .with_cancellation(cancellation)
+                .decompile_with_source_map()
+                .map_err(|err| err.to_string())
+        }
+    });
+
+    match tokio::time::timeout(state.config.timeout, task).await {
+        Err(_) => {
+            cancellation.cancel();
+            Err(error(
+                StatusCode::GATEWAY_TIMEOUT,
+                "decompilation timed out",
+            ))
+        }
+        Ok(Err(err)) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("decompilation failed: {}", err),
+        )),
+        Ok(Ok(Err(err))) => Err(error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("unable to decompile: {}", err),
+        )),
+        Ok(Ok(Ok((source, source_map)))) => Ok(Json(DecompileResponse { source, source_map })),
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Verichains
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! JavaScript binding of the decompiler, e.g.
+//!
+//! ```js
+//! const decompiler = new Decompiler();
+//! decompiler.addModule(bytecode);
+//! console.log(decompiler.decompile());
+//! ```
+
+use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use wasm_bindgen::prelude::*;
+
+use crate::decompiler::{self, CompiledBinary, OptimizerSettings};
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Decompiler {
+    binaries: Vec<CompiledBinary>,
+    for_loops: bool,
+    method_calls: bool,
+}
+
+#[wasm_bindgen]
+impl Decompiler {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[wasm_bindgen(js_name = addModule)]
+    pub fn add_module(&mut self, bytecode: &[u8]) -> Result<(), JsError> {
+        let module = CompiledModule::deserialize(bytecode)
+            .map_err(|err| JsError::new(&format!("failed to deserialize module: {}", err)))?;
+        self.binaries.push(CompiledBinary::Module(module));
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = addScript)]
+    pub fn add_script(&mut self, bytecode: &[u8]) -> Result<(), JsError> {
+        let script = CompiledScript::deserialize(bytecode)
+            .map_err(|err| JsError::new(&format!("failed to deserialize script: {}", err)))?;
+        self.binaries.push(CompiledBinary::Script(script));
+        Ok(())
+    }
+
+    /// Emit Move 2 `for` loops for counted loops
+    #[wasm_bindgen(js_name = setForLoops)]
+    pub fn set_for_loops(&mut self, enabled: bool) {
+        self.for_loops = enabled;
+    }
+
+    /// Emit Move 2 receiver-style calls such as `v.length()`
+    #[wasm_bindgen(js_name = setMethodCalls)]
+    pub fn set_method_calls(&mut self, enabled: bool) {
+        self.method_calls = enabled;
+    }
+
+    /// Decompile all added binaries into a single source
+    pub fn decompile(&self) -> Result<String, JsError> {
+        let binaries = self
+            .binaries
+            .iter()
+            .map(CompiledBinary::as_binary_view)
+            .collect();
+
+        let mut decompiler = decompiler::Decompiler::new(
+            binaries,
+            OptimizerSettings {
+                enable_for_loop_syntax: self.for_loops,
+                enable_receiver_call_syntax: self.method_calls,
+                ..Default::default()
+            },
+        );
+        decompiler
+            .decompile()
+            .map_err(|err| JsError::new(&format!("unable to decompile: {}", err)))
+    }
+}
@@ -0,0 +1,81 @@
+//! Decompile the bundled mainnet framework binaries one module at a time and compare the
+//! output with the golden sources under `tests/corpus/<package>/<module>.move`.
+//!
+//! Missing goldens are recorded on the first run, except on CI where they fail the test.
+//! `FORCE_UPDATE_EXPECTED_OUTPUT=1` blesses the current output of every module.
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path};
+
+    use move_binary_format::{binary_views::BinaryIndexedView, CompiledModule};
+    use revela::decompiler::Decompiler;
+
+    /// The first line where `output` and `expected` differ, with its line number
+    fn first_difference(output: &str, expected: &str) -> Option<(usize, String, String)> {
+        let mut output_lines = output.lines();
+        let mut expected_lines = expected.lines();
+        for line in 1.. {
+            match (output_lines.next(), expected_lines.next()) {
+                (None, None) => return None,
+                (a, b) if a == b => continue,
+                (a, b) => {
+                    return Some((
+                        line,
+                        a.unwrap_or("<end of output>").to_string(),
+                        b.unwrap_or("<end of golden>").to_string(),
+                    ))
+                }
+            }
+        }
+        None
+    }
+
+    pub fn decompile_corpus_module(path: &Path) -> datatest_stable::Result<()> {
+        let package = path.parent().unwrap().file_name().unwrap();
+        let module_name = path.file_stem().unwrap().to_str().unwrap();
+        let golden_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("corpus")
+            .join(package)
+            .join(format!("{}.move", module_name));
+
+        let bytecode = fs::read(path)?;
+        let module = CompiledModule::deserialize(&bytecode)?;
+        let mut decompiler =
+            Decompiler::new(vec![BinaryIndexedView::Module(&module)], Default::default());
+        let output = decompiler.decompile()?;
+
+        let expected = fs::read_to_string(&golden_path);
+        if env::var("FORCE_UPDATE_EXPECTED_OUTPUT").is_ok() {
+            fs::create_dir_all(golden_path.parent().unwrap())?;
+            fs::write(&golden_path, &output)?;
+        } else if let Ok(expected) = expected {
+            if let Some((line, actual, golden)) = first_difference(&output, &expected) {
+                panic!(
+                    "{} differs from {} at line {}\n  output: {}\n  golden: {}\n\
+                     rerun with FORCE_UPDATE_EXPECTED_OUTPUT=1 if the change is intended",
+                    module_name,
+                    golden_path.display(),
+                    line,
+                    actual,
+                    golden
+                );
+            }
+        } else if env::var("CI").is_err() || env::var("UPDATE_EXPECTED_OUTPUT").is_ok() {
+            fs::create_dir_all(golden_path.parent().unwrap())?;
+            fs::write(&golden_path, &output)?;
+            println!("recorded golden {}", golden_path.display());
+        } else {
+            panic!("missing golden {}", golden_path.display());
+        }
+
+        Ok(())
+    }
+}
+
+datatest_stable::harness!(
+    test::decompile_corpus_module,
+    "tests/bytecode/dependencies",
+    r"\.mv$"
+);
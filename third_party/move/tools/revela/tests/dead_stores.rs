@@ -0,0 +1,61 @@
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use revela::decompiler::{Decompiler, OptimizerSettings};
+
+    use super::utils;
+
+    const SOURCE: &str = "module 0x1234::dead_stores {
+        fun value(): u64 { 1 }
+
+        public fun overwritten(): u64 {
+            let x = 1;
+            x = value();
+            value()
+        }
+
+        public fun overwritten_in_branch(c: bool) {
+            let x = 1;
+            if (c) {
+                x = value();
+            };
+        }
+    }
+    ";
+
+    fn decompile(settings: OptimizerSettings) -> String {
+        let mut output = String::new();
+        utils::tmp_project(vec![("dead_stores.move", SOURCE)], |root, files| {
+            let (scripts, modules) = utils::run_compiler(root, files, false);
+            let binaries = utils::into_binary_indexed_view(&scripts, &modules);
+            output = Decompiler::new(binaries, settings)
+                .decompile()
+                .expect("Unable to decompile");
+        });
+        output
+    }
+
+    fn assert_compiles(output: &str) {
+        utils::tmp_project(vec![("dead_stores.move", output)], |root, files| {
+            utils::run_compiler(root, files, false);
+        });
+    }
+
+    #[test]
+    fn declaration_survives_removed_store() {
+        let output = decompile(OptimizerSettings::default());
+        // the calls have side effects and are kept, along with a declaration of their variable
+        assert!(output.matches("value()").count() >= 3, "{}", output);
+        assert_compiles(&output);
+    }
+
+    #[test]
+    fn dead_stores_are_kept_on_request() {
+        let output = decompile(OptimizerSettings {
+            keep_dead_stores: true,
+            ..Default::default()
+        });
+        assert_compiles(&output);
+    }
+}
@@ -7,7 +7,7 @@ mod test {
     use std::{env, fs};
 
     use super::utils;
-    use revela::decompiler::{Decompiler, OptimizerSettings};
+    use revela::decompiler::{Decompiler, MemoryCache, OptimizerSettings};
 
     pub fn decompile_compile_decompile_match_single_file(
         path: &Path,
@@ -50,6 +50,28 @@ mod test {
                     let mut decompiler = Decompiler::new(binaries, Default::default());
                     let default_output = decompiler.decompile().expect("Unable to decompile");
 
+                    // output must be reproducible byte-for-byte across runs
+                    let binaries = utils::into_binary_indexed_view(&src_scripts, &src_modules);
+                    let mut decompiler = Decompiler::new(binaries, Default::default());
+                    let rerun_output = decompiler.decompile().expect("Unable to decompile");
+                    assert_eq!(
+                        default_output, rerun_output,
+                        "decompiled output is not deterministic"
+                    );
+
+                    // the first run fills the cache, the second one reuses every function body
+                    let cache = MemoryCache::new();
+                    for _ in 0..2 {
+                        let binaries = utils::into_binary_indexed_view(&src_scripts, &src_modules);
+                        let mut decompiler =
+                            Decompiler::new(binaries, Default::default()).with_cache(cache.clone());
+                        let cached_output = decompiler.decompile().expect("Unable to decompile");
+                        assert_eq!(
+                            default_output, cached_output,
+                            "cached decompiled output differs"
+                        );
+                    }
+
                     let ref_output_path =
                         ref_output_dir.join(format!("sources-{}-decompiled.move", module_name));
                     std::fs::write(&ref_output_path, default_output).unwrap();
@@ -61,6 +83,7 @@ mod test {
                         OptimizerSettings {
                             // this settings may cause the output to be different
                             disable_optimize_variables_declaration: true,
+                            ..Default::default()
                         },
                     );
                     output = decompiler.decompile().expect("Unable to decompile");
@@ -92,6 +115,7 @@ mod test {
                     binaries,
                     OptimizerSettings {
                         disable_optimize_variables_declaration: true,
+                        ..Default::default()
                     },
                 );
 
@@ -0,0 +1,73 @@
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use revela::decompiler::{Decompiler, OptimizerSettings};
+
+    use super::utils;
+
+    const SOURCE: &str = "module 0x1234::obfuscated {
+        public fun flattened(x: u64): u64 {
+            let state = 0;
+            let result = 0;
+            while (state != 3) {
+                if (state == 0) {
+                    result = x + 1;
+                    state = 2;
+                } else if (state == 1) {
+                    result = result * 3;
+                    state = 3;
+                } else {
+                    result = result * 2;
+                    state = 1;
+                }
+            };
+            result
+        }
+
+        public fun opaque(x: u64): u64 {
+            if ((x * (x + 1)) % 2 == 0) {
+                x + 1
+            } else {
+                abort 77
+            }
+        }
+    }
+    ";
+
+    fn decompile(deobfuscate: bool) -> String {
+        let mut output = String::new();
+        utils::tmp_project(vec![("obfuscated.move", SOURCE)], |root, files| {
+            let (scripts, modules) = utils::run_compiler(root, files, false);
+            let binaries = utils::into_binary_indexed_view(&scripts, &modules);
+            output = Decompiler::new(
+                binaries,
+                OptimizerSettings {
+                    deobfuscate,
+                    ..Default::default()
+                },
+            )
+            .decompile()
+            .expect("Unable to decompile");
+        });
+        output
+    }
+
+    fn has_loop(output: &str) -> bool {
+        output.contains("while") || output.contains("loop")
+    }
+
+    #[test]
+    fn dispatcher_loop_is_unflattened() {
+        assert!(has_loop(&decompile(false)));
+        let output = decompile(true);
+        assert!(!has_loop(&output), "{}", output);
+    }
+
+    #[test]
+    fn opaque_predicate_is_folded() {
+        assert!(decompile(false).contains("77"));
+        let output = decompile(true);
+        assert!(!output.contains("77"), "{}", output);
+    }
+}
@@ -0,0 +1,265 @@
+//! Differential testing: every `-test.move` source is compiled, decompiled and recompiled, then
+//! the functions of both builds are called with the same random arguments in a FakeExecutor.
+//! Any difference in abort status, writes or events is shrunk to a small repro case which is
+//! written to `tests/refs/differential-<module>.txt`.
+//!
+//! `DIFFERENTIAL_RUNS` sets the number of random calls per function (default 8) and
+//! `DIFFERENTIAL_SEED` the seed of the generator.
+
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use std::{env, fmt::Write, fs, path::Path};
+
+    use aptos_language_e2e_tests::executor::FakeExecutor;
+    use move_binary_format::{access::ModuleAccess, file_format::SignatureToken, CompiledModule};
+    use move_core_types::{
+        account_address::AccountAddress, language_storage::ModuleId, u256::U256, value::MoveValue,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use revela::decompiler::{Decompiler, OptimizerSettings};
+
+    use super::utils;
+
+    /// A function of the module that can be called with generated arguments
+    struct Target {
+        name: String,
+        params: Vec<SignatureToken>,
+    }
+
+    fn targets(module: &CompiledModule) -> Vec<Target> {
+        module
+            .function_defs()
+            .iter()
+            .filter(|def| def.code.is_some())
+            .map(|def| module.function_handle_at(def.function))
+            .filter(|handle| handle.type_parameters.is_empty())
+            .map(|handle| Target {
+                name: module.identifier_at(handle.name).to_string(),
+                params: module.signature_at(handle.parameters).0.clone(),
+            })
+            .filter(|target| target.params.iter().all(is_generated))
+            .collect()
+    }
+
+    fn is_generated(token: &SignatureToken) -> bool {
+        match token {
+            SignatureToken::Bool
+            | SignatureToken::U8
+            | SignatureToken::U16
+            | SignatureToken::U32
+            | SignatureToken::U64
+            | SignatureToken::U128
+            | SignatureToken::U256
+            | SignatureToken::Address
+            | SignatureToken::Signer => true,
+            SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+            SignatureToken::Vector(inner) => **inner == SignatureToken::U8,
+            _ => false,
+        }
+    }
+
+    /// Boundary values first, random ones afterwards
+    fn generate(rng: &mut StdRng, token: &SignatureToken, run: usize) -> MoveValue {
+        let pick = |rng: &mut StdRng, max: u128| match run {
+            0 => 0,
+            1 => 1,
+            2 => max,
+            _ => rng.gen_range(0, max) | (rng.gen::<bool>() as u128),
+        };
+        match token {
+            SignatureToken::Bool => MoveValue::Bool(run == 1 || (run > 2 && rng.gen())),
+            SignatureToken::U8 => MoveValue::U8(pick(rng, u8::MAX as u128) as u8),
+            SignatureToken::U16 => MoveValue::U16(pick(rng, u16::MAX as u128) as u16),
+            SignatureToken::U32 => MoveValue::U32(pick(rng, u32::MAX as u128) as u32),
+            SignatureToken::U64 => MoveValue::U64(pick(rng, u64::MAX as u128) as u64),
+            SignatureToken::U128 => MoveValue::U128(pick(rng, u128::MAX)),
+            SignatureToken::U256 => MoveValue::U256(U256::from(pick(rng, u128::MAX))),
+            SignatureToken::Address => MoveValue::Address(AccountAddress::new(rng.gen())),
+            SignatureToken::Signer | SignatureToken::Reference(_) => {
+                MoveValue::Signer(AccountAddress::new(rng.gen()))
+            }
+            SignatureToken::Vector(_) => {
+                let len = if run == 0 { 0 } else { rng.gen_range(0, 32) };
+                MoveValue::Vector((0..len).map(|_| MoveValue::U8(rng.gen())).collect())
+            }
+            _ => unreachable!("arguments of this type are not generated"),
+        }
+    }
+
+    /// Smaller variants of `value`, tried while shrinking a mismatch
+    fn shrink(value: &MoveValue) -> Vec<MoveValue> {
+        match value {
+            MoveValue::Bool(true) => vec![MoveValue::Bool(false)],
+            MoveValue::U8(x) if *x > 0 => vec![MoveValue::U8(0), MoveValue::U8(x / 2)],
+            MoveValue::U16(x) if *x > 0 => vec![MoveValue::U16(0), MoveValue::U16(x / 2)],
+            MoveValue::U32(x) if *x > 0 => vec![MoveValue::U32(0), MoveValue::U32(x / 2)],
+            MoveValue::U64(x) if *x > 0 => vec![MoveValue::U64(0), MoveValue::U64(x / 2)],
+            MoveValue::U128(x) if *x > 0 => vec![MoveValue::U128(0), MoveValue::U128(x / 2)],
+            MoveValue::U256(x) if *x != U256::zero() => vec![MoveValue::U256(U256::zero())],
+            MoveValue::Address(x) if *x != AccountAddress::ZERO => {
+                vec![MoveValue::Address(AccountAddress::ZERO)]
+            }
+            MoveValue::Signer(x) if *x != AccountAddress::ONE => {
+                vec![MoveValue::Signer(AccountAddress::ONE)]
+            }
+            MoveValue::Vector(items) if !items.is_empty() => vec![
+                MoveValue::Vector(Vec::new()),
+                MoveValue::Vector(items[..items.len() / 2].to_vec()),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    fn executor_with(modules: &[CompiledModule]) -> FakeExecutor {
+        let mut executor = FakeExecutor::from_head_genesis();
+        for module in modules {
+            let mut blob = Vec::new();
+            module.serialize(&mut blob).unwrap();
+            executor.add_module(&module.self_id(), blob);
+        }
+        executor
+    }
+
+    /// Abort status, writes and events of a call, compared between both builds
+    fn call(
+        executor: &mut FakeExecutor,
+        module_id: &ModuleId,
+        function: &str,
+        args: &[MoveValue],
+    ) -> String {
+        let args = args
+            .iter()
+            .map(|arg| arg.simple_serialize().unwrap())
+            .collect();
+        format!(
+            "{:?}",
+            executor.try_exec_module(module_id, function, vec![], args)
+        )
+    }
+
+    fn runs() -> usize {
+        env::var("DIFFERENTIAL_RUNS")
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(8)
+    }
+
+    fn seed() -> u64 {
+        env::var("DIFFERENTIAL_SEED")
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(0)
+    }
+
+    pub fn decompile_recompile_same_behavior(path: &Path) -> datatest_stable::Result<()> {
+        let source = fs::read_to_string(path)?;
+        let mut original = vec![];
+        utils::tmp_project(vec![("tmp.move", source.as_str())], |root, files| {
+            (_, original) = utils::run_compiler(root, files, false);
+        });
+
+        let binaries = utils::into_binary_indexed_view(&vec![], &original);
+        let decompiled = Decompiler::new(
+            binaries,
+            OptimizerSettings {
+                disable_optimize_variables_declaration: true,
+                ..Default::default()
+            },
+        )
+        .decompile()?;
+        let mut recompiled = vec![];
+        utils::tmp_project(vec![("tmp.move", decompiled.as_str())], |root, files| {
+            (_, recompiled) = utils::run_compiler(root, files, false);
+        });
+
+        let mut original_executor = executor_with(&original);
+        let mut recompiled_executor = executor_with(&recompiled);
+        let mut rng = StdRng::seed_from_u64(seed());
+        let mut repros = String::new();
+
+        for module in &original {
+            let module_id = module.self_id();
+            for target in targets(module) {
+                let mismatches =
+                    |args: &[MoveValue],
+                     original_executor: &mut FakeExecutor,
+                     recompiled_executor: &mut FakeExecutor| {
+                        let expected = call(original_executor, &module_id, &target.name, args);
+                        let actual = call(recompiled_executor, &module_id, &target.name, args);
+                        (expected != actual).then_some((expected, actual))
+                    };
+
+                for run in 0..runs() {
+                    let mut args: Vec<_> = target
+                        .params
+                        .iter()
+                        .map(|token| generate(&mut rng, token, run))
+                        .collect();
+                    let Some(mut mismatch) =
+                        mismatches(&args, &mut original_executor, &mut recompiled_executor)
+                    else {
+                        continue;
+                    };
+
+                    // shrink one argument at a time until no smaller input still mismatches
+                    'shrink: loop {
+                        for idx in 0..args.len() {
+                            for candidate in shrink(&args[idx]) {
+                                let mut smaller = args.clone();
+                                smaller[idx] = candidate;
+                                if let Some(found) = mismatches(
+                                    &smaller,
+                                    &mut original_executor,
+                                    &mut recompiled_executor,
+                                ) {
+                                    args = smaller;
+                                    mismatch = found;
+                                    continue 'shrink;
+                                }
+                            }
+                        }
+                        break;
+                    }
+
+                    let args = args
+                        .iter()
+                        .map(|arg| arg.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    writeln!(
+                        repros,
+                        "{}::{}({})\n  original:   {}\n  recompiled: {}",
+                        module_id, target.name, args, mismatch.0, mismatch.1
+                    )?;
+                    // one repro per function is enough
+                    break;
+                }
+            }
+        }
+
+        if !repros.is_empty() {
+            let module_name = path.file_name().unwrap().to_str().unwrap();
+            let repro_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("refs")
+                .join(format!("differential-{}.txt", module_name));
+            fs::write(&repro_path, format!("{}\n{}", decompiled, repros))?;
+            panic!(
+                "decompiled {} behaves differently, see {}\n{}",
+                module_name,
+                repro_path.display(),
+                repros
+            );
+        }
+
+        Ok(())
+    }
+}
+
+datatest_stable::harness!(
+    test::decompile_recompile_same_behavior,
+    "tests/sources",
+    r"-test\.move$"
+);
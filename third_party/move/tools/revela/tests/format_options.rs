@@ -0,0 +1,46 @@
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use revela::decompiler::{Decompiler, FormatOptions, OptimizerSettings};
+
+    use super::utils;
+
+    const SOURCE: &str = "module 0x1234::formatted {
+        struct Marker has drop {}
+
+        struct Point has copy, drop {
+            x: u64,
+            y: u64,
+        }
+    }
+    ";
+
+    fn decompile(format_options: FormatOptions) -> String {
+        let mut output = String::new();
+        utils::tmp_project(vec![("formatted.move", SOURCE)], |root, files| {
+            let (scripts, modules) = utils::run_compiler(root, files, false);
+            let binaries = utils::into_binary_indexed_view(&scripts, &modules);
+            output = Decompiler::new(binaries, OptimizerSettings::default())
+                .with_format_options(format_options)
+                .decompile()
+                .expect("Unable to decompile");
+        });
+        output
+    }
+
+    #[test]
+    fn short_structs_fit_in_one_line() {
+        let output = decompile(FormatOptions {
+            max_line_width: Some(100),
+            ..Default::default()
+        });
+        let lines = output.lines().map(str::trim).collect::<Vec<_>>();
+        assert!(lines.contains(&"struct Marker has drop {}"), "{}", output);
+        assert!(
+            lines.contains(&"struct Point has copy, drop { x: u64, y: u64 }"),
+            "{}",
+            output
+        );
+    }
+}
@@ -0,0 +1,71 @@
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use revela::decompiler::{Decompiler, OptimizerSettings};
+
+    use super::utils;
+
+    const SOURCE: &str = "module 0x1234::interleaved {
+        public fun sum(x: u64, y: u64): u64 {
+            let z = x + y;
+            if (z > 10) {
+                z - 10
+            } else {
+                z
+            }
+        }
+    }
+    ";
+
+    fn decompile(interleaved: bool) -> String {
+        let mut output = String::new();
+        utils::tmp_project(vec![("interleaved.move", SOURCE)], |root, files| {
+            let (scripts, modules) = utils::run_compiler(root, files, false);
+            let binaries = utils::into_binary_indexed_view(&scripts, &modules);
+            output = Decompiler::new(binaries, OptimizerSettings::default())
+                .with_interleaved_bytecode(interleaved)
+                .decompile()
+                .expect("Unable to decompile");
+        });
+        output
+    }
+
+    fn is_bytecode_comment(line: &str) -> bool {
+        line.trim_start().starts_with("//   ")
+    }
+
+    #[test]
+    fn statements_are_annotated_with_their_bytecode() {
+        let output = decompile(true);
+        let lines = output.lines().collect::<Vec<_>>();
+
+        let function_line = lines
+            .iter()
+            .position(|line| line.contains("fun sum("))
+            .expect("function not found");
+        let comments = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| is_bytecode_comment(line))
+            .collect::<Vec<_>>();
+        assert!(!comments.is_empty(), "{}", output);
+        assert!(
+            comments.iter().all(|(idx, _)| *idx > function_line),
+            "{}",
+            output
+        );
+        // instructions of the original bytecode carry their offset
+        assert!(
+            comments.iter().any(|(_, line)| line.contains("//   @")),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn bytecode_is_not_interleaved_by_default() {
+        let output = decompile(false);
+        assert!(!output.lines().any(is_bytecode_comment), "{}", output);
+    }
+}
@@ -622,7 +622,7 @@ module 0x1::coin {
         assert!(0x1::string::length(&arg1) <= 32, 0x1::error::invalid_argument(12));
         assert!(0x1::string::length(&arg2) <= 10, 0x1::error::invalid_argument(13));
         let v1 = if (arg4) {
-            0x1::option::some<0x1::optional_aggregator::OptionalAggregator>(0x1::optional_aggregator::new(340282366920938463463374607431768211455, arg5))
+            0x1::option::some<0x1::optional_aggregator::OptionalAggregator>(0x1::optional_aggregator::new(0xffffffffffffffffffffffffffffffffu128, arg5))
         } else {
             0x1::option::none<0x1::optional_aggregator::OptionalAggregator>()
         };
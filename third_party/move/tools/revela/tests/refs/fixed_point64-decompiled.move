@@ -5,7 +5,7 @@ module 0x1::fixed_point64 {
     
     public fun add(arg0: FixedPoint64, arg1: FixedPoint64) : FixedPoint64 {
         let v0 = (get_raw_value(arg0) as u256) + (get_raw_value(arg1) as u256);
-        assert!(v0 <= 340282366920938463463374607431768211455, 131077);
+        assert!(v0 <= 0xffffffffffffffffffffffffffffffffu256, 131077);
         create_from_raw_value((v0 as u128))
     }
     
@@ -19,14 +19,14 @@ module 0x1::fixed_point64 {
         if (arg0.value == v0) {
             return v0 >> 64
         };
-        (((v0 as u256) + 18446744073709551616 >> 64) as u128)
+        (((v0 as u256) + 0x10000000000000000u256 >> 64) as u128)
     }
     
     public fun create_from_rational(arg0: u128, arg1: u128) : FixedPoint64 {
         assert!(arg1 != 0, 65537);
         let v0 = ((arg0 as u256) << 64) / (arg1 as u256);
         assert!(v0 != 0 || arg0 == 0, 131077);
-        assert!(v0 <= 340282366920938463463374607431768211455, 131077);
+        assert!(v0 <= 0xffffffffffffffffffffffffffffffffu256, 131077);
         FixedPoint64{value: (v0 as u128)}
     }
     
@@ -36,14 +36,14 @@ module 0x1::fixed_point64 {
     
     public fun create_from_u128(arg0: u128) : FixedPoint64 {
         let v0 = (arg0 as u256) << 64;
-        assert!(v0 <= 340282366920938463463374607431768211455, 131077);
+        assert!(v0 <= 0xffffffffffffffffffffffffffffffffu256, 131077);
         FixedPoint64{value: (v0 as u128)}
     }
     
     public fun divide_u128(arg0: u128, arg1: FixedPoint64) : u128 {
         assert!(arg1.value != 0, 65540);
         let v0 = ((arg0 as u256) << 64) / (arg1.value as u256);
-        assert!(v0 <= 340282366920938463463374607431768211455, 131074);
+        assert!(v0 <= 0xffffffffffffffffffffffffffffffffu256, 131074);
         (v0 as u128)
     }
     
@@ -97,7 +97,7 @@ module 0x1::fixed_point64 {
     
     public fun multiply_u128(arg0: u128, arg1: FixedPoint64) : u128 {
         let v0 = (arg0 as u256) * (arg1.value as u256) >> 64;
-        assert!(v0 <= 340282366920938463463374607431768211455, 131075);
+        assert!(v0 <= 0xffffffffffffffffffffffffffffffffu256, 131075);
         (v0 as u128)
     }
     
@@ -441,7 +441,7 @@ module 0x1::fungible_asset {
         let v0 = 0x1::object::object_address<T0>(&arg0);
         if (exists<ConcurrentSupply>(v0)) {
             let v2 = 0x1::aggregator_v2::max_value<u128>(&borrow_global<ConcurrentSupply>(v0).current);
-            if (v2 == 340282366920938463463374607431768211455) {
+            if (v2 == 0xffffffffffffffffffffffffffffffffu128) {
                 0x1::option::none<u128>()
             } else {
                 0x1::option::some<u128>(v2)
@@ -58,7 +58,7 @@ module 0x1::math128 {
         while (v1 != 0) {
             let v3 = arg0 * arg0 >> 63;
             arg0 = v3;
-            if (v3 >= 18446744073709551616) {
+            if (v3 >= 0x10000000000000000u128) {
                 v2 = v2 + v1;
                 arg0 = v3 >> 1;
             };
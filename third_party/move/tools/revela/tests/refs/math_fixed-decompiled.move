@@ -48,7 +48,7 @@ module 0x1::math_fixed {
     
     fun pow_raw(arg0: u128, arg1: u128) : u128 {
         arg0 = arg0 << 32;
-        let v0 = 18446744073709551616;
+        let v0 = 0x10000000000000000u256;
         while (arg1 != 0) {
             if (arg1 & 1 != 0) {
                 let v1 = v0 * (arg0 as u256);
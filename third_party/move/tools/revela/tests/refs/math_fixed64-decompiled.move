@@ -17,7 +17,7 @@ module 0x1::math_fixed64 {
         let v2 = arg0 % 12786308645202655660;
         let v3 = v2 / 22045359733108027;
         let v4 = v2 % 22045359733108027;
-        let v5 = pow_raw(18468802611690918839, (v3 as u128));
+        let v5 = pow_raw(18468802611690918839u256, (v3 as u128));
         let v6 = v5 - (v5 * 219071715585908898 * v3 >> 128);
         let v7 = v6 * v4 >> 64 - v1;
         let v8 = v7 * v4 >> 64;
@@ -50,7 +50,7 @@ module 0x1::math_fixed64 {
     }
     
     fun pow_raw(arg0: u256, arg1: u128) : u256 {
-        let v0 = 18446744073709551616;
+        let v0 = 0x10000000000000000u256;
         while (arg1 != 0) {
             if (arg1 & 1 != 0) {
                 let v1 = v0 * arg0;
@@ -29,7 +29,7 @@ module 0x1::pool_u64_unbound {
         if (contains(arg0, arg1)) {
             let v1 = 0x1::table_with_length::borrow_mut<address, u128>(&mut arg0.shares, arg1);
             let v2 = *v1;
-            assert!(340282366920938463463374607431768211455 - v2 >= arg2, 0x1::error::invalid_argument(5));
+            assert!(0xffffffffffffffffffffffffffffffffu128 - v2 >= arg2, 0x1::error::invalid_argument(5));
             *v1 = v2 + arg2;
             *v1
         } else {
@@ -64,7 +64,7 @@ module 0x1::pool_u64_unbound {
         };
         let v0 = amount_to_shares(arg0, arg2);
         assert!(18446744073709551615 - arg0.total_coins >= arg2, 0x1::error::invalid_argument(6));
-        let v1 = 340282366920938463463374607431768211455 - arg0.total_shares >= v0;
+        let v1 = 0xffffffffffffffffffffffffffffffffu128 - arg0.total_shares >= v0;
         assert!(v1, 0x1::error::invalid_argument(7));
         arg0.total_coins = arg0.total_coins + arg2;
         arg0.total_shares = arg0.total_shares + v0;
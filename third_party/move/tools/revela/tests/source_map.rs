@@ -0,0 +1,89 @@
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use revela::decompiler::{Decompiler, OptimizerSettings};
+
+    use super::utils;
+
+    const SOURCE: &str = "module 0x1234::mapped {
+        struct Counter has key {
+            value: u64,
+        }
+
+        public fun first(x: u64): u64 {
+            x + 1
+        }
+
+        public fun second(x: u64): u64 {
+            if (x > 10) {
+                x - 10
+            } else {
+                first(x)
+            }
+        }
+    }
+    ";
+
+    #[test]
+    fn functions_are_mapped_to_their_lines() {
+        let mut decompiled = None;
+        utils::tmp_project(vec![("mapped.move", SOURCE)], |root, files| {
+            let (scripts, modules) = utils::run_compiler(root, files, false);
+            let binaries = utils::into_binary_indexed_view(&scripts, &modules);
+            decompiled = Some(
+                Decompiler::new(binaries, OptimizerSettings::default())
+                    .decompile_with_source_map()
+                    .expect("Unable to decompile"),
+            );
+        });
+        let (source, source_map) = decompiled.unwrap();
+        let lines = source.lines().collect::<Vec<_>>();
+
+        let module = &source_map.modules[0];
+        assert_eq!(module.name, "0x1234::mapped");
+        assert!(lines[module.start_line - 1].starts_with("module 0x1234::mapped"));
+        assert_eq!(lines[module.end_line - 1], "}");
+
+        let names = module
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["first", "second"]);
+        for function in &module.functions {
+            assert!(
+                lines[function.start_line - 1].contains(&format!("fun {}(", function.name)),
+                "{}",
+                source
+            );
+            assert_eq!(lines[function.end_line - 1].trim(), "}", "{}", source);
+        }
+    }
+
+    #[test]
+    fn only_selected_functions_are_decompiled() {
+        let mut decompiled = None;
+        utils::tmp_project(vec![("mapped.move", SOURCE)], |root, files| {
+            let (scripts, modules) = utils::run_compiler(root, files, false);
+            let binaries = utils::into_binary_indexed_view(&scripts, &modules);
+            decompiled = Some(
+                Decompiler::new(binaries, OptimizerSettings::default())
+                    .with_function_selector(vec!["second".to_string()])
+                    .decompile_with_source_map()
+                    .expect("Unable to decompile"),
+            );
+        });
+        let (source, source_map) = decompiled.unwrap();
+
+        let names = source_map.modules[0]
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["second"]);
+        assert!(!source.contains("fun first("), "{}", source);
+        // the structs are kept
+        assert!(source.contains("struct Counter"), "{}", source);
+    }
+}
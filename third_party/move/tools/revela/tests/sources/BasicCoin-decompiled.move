@@ -33,17 +33,15 @@ module 0xbadbadbad::BasicCoin {
     
     fun pop_smallest_while_not_equal(arg0: vector<u64>, arg1: vector<u64>) : vector<u64> {
         let v0 = 0x1::vector::empty<u64>();
-        while (0x1::vector::is_empty<u64>(&arg0) && false || !0x1::vector::is_empty<u64>(&arg1)) {
+        while (!0x1::vector::is_empty<u64>(&arg0) && !0x1::vector::is_empty<u64>(&arg1)) {
             let v1 = *0x1::vector::borrow<u64>(&arg0, 0x1::vector::length<u64>(&arg0) - 1);
             let v2 = *0x1::vector::borrow<u64>(&arg1, 0x1::vector::length<u64>(&arg1) - 1);
             let v3 = if (v1 < v2) {
                 0x1::vector::pop_back<u64>(&mut arg0)
+            } else if (v2 < v1) {
+                0x1::vector::pop_back<u64>(&mut arg1)
             } else {
-                if (v2 < v1) {
-                    0x1::vector::pop_back<u64>(&mut arg1)
-                } else {
-                    break
-                }
+                break
             };
             0x1::vector::push_back<u64>(&mut v0, v3);
         };
@@ -126,10 +124,7 @@ module 0xbadbadbad::BasicCoin {
     public fun test_vector(arg0: u64) : u64 {
         let v0 = vector[1, 2, 3, 4, 5, 6, 7, 8, 9];
         let v1 = 0;
-        loop {
-            if (0x1::vector::is_empty<u64>(&v0)) {
-                break
-            };
+        while (!0x1::vector::is_empty<u64>(&v0)) {
             v1 = v1 + 0x1::vector::pop_back<u64>(&mut v0) * arg0;
         };
         v1
@@ -0,0 +1,19 @@
+module 0x12::stdlib_idioms {
+    public fun empty(arg0: &vector<u64>) : bool {
+        0x1::vector::is_empty<u64>(arg0)
+    }
+    
+    public fun has_value(arg0: &0x1::option::Option<u64>) : bool {
+        0x1::option::is_some<u64>(arg0)
+    }
+    
+    public fun no_value(arg0: &0x1::option::Option<u64>) : bool {
+        0x1::option::is_none<u64>(arg0)
+    }
+    
+    public fun non_empty(arg0: &vector<u64>) : bool {
+        !0x1::vector::is_empty<u64>(arg0)
+    }
+    
+    // decompiled from Move bytecode v7
+}
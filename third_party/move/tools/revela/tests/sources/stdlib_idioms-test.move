@@ -0,0 +1,21 @@
+// Testcase: standard library call idioms
+module 0x12::stdlib_idioms {
+    use std::option::{Self, Option};
+    use std::vector;
+
+    public fun empty(v: &vector<u64>): bool {
+        vector::length(v) == 0
+    }
+
+    public fun has_value(o: &Option<u64>): bool {
+        !option::is_none(o)
+    }
+
+    public fun no_value(o: &Option<u64>): bool {
+        !option::is_some(o)
+    }
+
+    public fun non_empty(v: &vector<u64>): bool {
+        0 != vector::length(v)
+    }
+}
@@ -48,6 +48,7 @@ mod test {
                 binaries,
                 OptimizerSettings {
                     disable_optimize_variables_declaration: true,
+                    ..Default::default()
                 },
             );
             let output2 = decompiler.decompile().expect("Unable to decompile");
@@ -245,7 +245,10 @@ pub(crate) fn assert_same_source_ignore_assign_order(output: &String, output2: &
     let normalized_output = normalize_source(output);
     let normalized_output2 = normalize_source(output2);
 
-    println!("Output=====\n{}\n\nOutput2=====\n{}", normalized_output, normalized_output2);
+    println!(
+        "Output=====\n{}\n\nOutput2=====\n{}",
+        normalized_output, normalized_output2
+    );
 
     assert_eq!(normalized_output.len(), normalized_output2.len());
 }
@@ -0,0 +1,50 @@
+mod utils;
+
+#[cfg(test)]
+mod test {
+    use move_binary_format::CompiledModule;
+    use revela::decompiler::{verify_modules, MatchStatus};
+
+    use super::utils;
+
+    const SOURCE: &str = "module 0x1234::verified {
+        const E_LIMIT: u64 = 1;
+
+        public fun limit(): u64 {
+            100
+        }
+
+        public fun check(x: u64) {
+            assert!(x < limit(), E_LIMIT);
+        }
+    }
+    ";
+
+    fn build(source: &str) -> Vec<CompiledModule> {
+        let mut modules = vec![];
+        utils::tmp_project(vec![("verified.move", source)], |root, files| {
+            (_, modules) = utils::run_compiler(root, files, false);
+        });
+        modules
+    }
+
+    #[test]
+    fn same_source_is_verified() {
+        let mut on_chain = build(SOURCE);
+        // metadata such as the compiler version is not part of the verification
+        on_chain[0].metadata.clear();
+        let report = verify_modules(&build(SOURCE), &on_chain).unwrap();
+        assert!(report.is_verified(), "{}", report);
+        assert!(report.modules[0].bytecode_identical);
+    }
+
+    #[test]
+    fn changed_function_is_reported() {
+        let on_chain = build(SOURCE);
+        let report = verify_modules(&build(&SOURCE.replace("100", "101")), &on_chain).unwrap();
+        assert!(!report.is_verified());
+        let functions = &report.modules[0].functions;
+        assert_eq!(functions["limit"], MatchStatus::CodeMismatch);
+        assert_eq!(functions["check"], MatchStatus::Match);
+    }
+}
@@ -4,6 +4,24 @@
 use crate::on_chain_config::BlockGasLimitType;
 use serde::{Deserialize, Serialize};
 
+/// How a transaction in parallel execution waits for the resolution of a dependency, i.e. for the
+/// re-execution of a transaction whose estimated write it read. Busy-waiting can reduce the latency
+/// of short waits, at the cost of burning CPU.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyWaitStrategy {
+    /// Block on the condition variable of the dependency right away.
+    #[default]
+    Condvar,
+    /// Busy-spin, checking the dependency up to the given number of times before blocking.
+    Spin(u32),
+    /// Yield the thread, checking the dependency up to the given number of times before blocking.
+    Yield(u32),
+    /// Block in slices of the given number of microseconds, counting the slices that time out.
+    /// The wait itself is never abandoned, as the dependency is guaranteed to get resolved.
+    Timeout(u64),
+}
+
 /// Local, per-node configuration.
 #[derive(Clone, Debug)]
 pub struct BlockExecutorLocalConfig {
@@ -14,6 +32,17 @@ pub struct BlockExecutorLocalConfig {
     // If true, we will discard the failed blocks and continue with the next block.
     // (allow_fallback needs to be set)
     pub discard_failed_blocks: bool,
+    // If specified, once the values written during parallel execution of a block exceed this
+    // many bytes, the versions that can no longer be observed are evicted as txns get committed.
+    pub versioned_data_memory_budget: Option<u64>,
+    // How transactions wait for the resolution of a dependency in parallel execution.
+    pub dependency_wait_strategy: DependencyWaitStrategy,
+    // If true, tiny or highly-conflicted blocks are executed sequentially even if the
+    // concurrency level allows parallel execution.
+    pub auto_select_execution_mode: bool,
+    // If true, the base values of the keys the transactions are predicted to read (the accounts
+    // of the senders) are prefetched from storage before parallel execution.
+    pub prefetch_predicted_reads: bool,
 }
 
 /// Configuration from on-chain configuration, that is
@@ -73,6 +102,10 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                versioned_data_memory_budget: None,
+                dependency_wait_strategy: DependencyWaitStrategy::default(),
+                auto_select_execution_mode: false,
+                prefetch_predicted_reads: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         }
@@ -87,6 +120,10 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                versioned_data_memory_budget: None,
+                dependency_wait_strategy: DependencyWaitStrategy::default(),
+                auto_select_execution_mode: false,
+                prefetch_predicted_reads: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_maybe_block_limit(maybe_block_gas_limit),
         }
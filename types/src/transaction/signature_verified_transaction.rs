@@ -92,6 +92,17 @@ pub fn into_signature_verified_block(txns: Vec<Transaction>) -> Vec<SignatureVer
     txns.into_iter().map(|t| t.into()).collect()
 }
 
+/// Like into_signature_verified_block, but for transactions proven to be committed (e.g. a chunk
+/// verified against a ledger info), which skips verifying the signatures again: only transactions
+/// with valid signatures get committed.
+pub fn into_committed_signature_verified_block(
+    txns: Vec<Transaction>,
+) -> Vec<SignatureVerifiedTransaction> {
+    txns.into_iter()
+        .map(SignatureVerifiedTransaction::Valid)
+        .collect()
+}
+
 pub trait TransactionProvider: Debug {
     fn get_transaction(&self) -> Option<&Transaction>;
 }